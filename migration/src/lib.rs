@@ -8,6 +8,37 @@ mod m20240105_000001_create_price_alerts_table;
 mod m20240105_000002_create_security_settings_table;
 mod m20240106_000001_create_swaps_table;
 mod m20240107_000001_create_token_metadata_table;
+mod m20240108_000001_add_replaces_tx_id_to_transactions;
+mod m20240109_000001_add_stop_loss_take_profit_to_price_alerts;
+mod m20240110_000001_add_max_gas_price_to_scheduled_transactions;
+mod m20240111_000001_add_ens_expires_at_to_address_book;
+mod m20240112_000001_create_portfolio_snapshots_table;
+mod m20240113_000001_add_testnet_mode;
+mod m20240114_000001_create_rebalancing_alerts_table;
+mod m20240115_000001_create_tax_lots_table;
+mod m20240116_000001_add_max_single_transfer_to_security_settings;
+mod m20240117_000001_create_dca_plans_table;
+mod m20240118_000001_add_l1_fees_to_transactions;
+mod m20240119_000001_add_batch_id_to_swaps;
+mod m20240120_000001_create_bot_dialogue_states_table;
+mod m20240121_000001_create_mempool_alerts_table;
+mod m20240122_000001_add_rpc_override_url_to_wallets;
+mod m20240123_000001_add_trailing_stop_to_price_alerts;
+mod m20240124_000001_create_gas_alerts_table;
+mod m20240125_000001_add_pin_required_above_to_security_settings;
+mod m20240126_000001_create_user_preferences_table;
+mod m20240127_000001_add_key_tracking_to_wallets;
+mod m20240128_000001_add_transfer_retries_to_transactions;
+mod m20240129_000001_create_token_discovery_cache_table;
+mod m20240130_000001_add_volume_surge_to_price_alerts;
+mod m20240131_000001_add_pin_lockout_to_security_settings;
+mod m20240201_000001_create_user_sessions_table;
+mod m20240202_000001_create_auto_compound_plans_table;
+mod m20240203_000001_add_gas_fees_to_transactions;
+mod m20240204_000001_add_swap_slippage_to_user_preferences;
+mod m20240205_000001_add_paused_to_price_alerts;
+mod m20240206_000001_create_broadcast_messages_table;
+mod m20240207_000001_create_group_permissions_table;
 
 pub struct Migrator;
 
@@ -23,6 +54,37 @@ impl MigratorTrait for Migrator {
             Box::new(m20240105_000002_create_security_settings_table::Migration),
             Box::new(m20240106_000001_create_swaps_table::Migration),
             Box::new(m20240107_000001_create_token_metadata_table::Migration),
+            Box::new(m20240108_000001_add_replaces_tx_id_to_transactions::Migration),
+            Box::new(m20240109_000001_add_stop_loss_take_profit_to_price_alerts::Migration),
+            Box::new(m20240110_000001_add_max_gas_price_to_scheduled_transactions::Migration),
+            Box::new(m20240111_000001_add_ens_expires_at_to_address_book::Migration),
+            Box::new(m20240112_000001_create_portfolio_snapshots_table::Migration),
+            Box::new(m20240113_000001_add_testnet_mode::Migration),
+            Box::new(m20240114_000001_create_rebalancing_alerts_table::Migration),
+            Box::new(m20240115_000001_create_tax_lots_table::Migration),
+            Box::new(m20240116_000001_add_max_single_transfer_to_security_settings::Migration),
+            Box::new(m20240117_000001_create_dca_plans_table::Migration),
+            Box::new(m20240118_000001_add_l1_fees_to_transactions::Migration),
+            Box::new(m20240119_000001_add_batch_id_to_swaps::Migration),
+            Box::new(m20240120_000001_create_bot_dialogue_states_table::Migration),
+            Box::new(m20240121_000001_create_mempool_alerts_table::Migration),
+            Box::new(m20240122_000001_add_rpc_override_url_to_wallets::Migration),
+            Box::new(m20240123_000001_add_trailing_stop_to_price_alerts::Migration),
+            Box::new(m20240124_000001_create_gas_alerts_table::Migration),
+            Box::new(m20240125_000001_add_pin_required_above_to_security_settings::Migration),
+            Box::new(m20240126_000001_create_user_preferences_table::Migration),
+            Box::new(m20240127_000001_add_key_tracking_to_wallets::Migration),
+            Box::new(m20240128_000001_add_transfer_retries_to_transactions::Migration),
+            Box::new(m20240129_000001_create_token_discovery_cache_table::Migration),
+            Box::new(m20240130_000001_add_volume_surge_to_price_alerts::Migration),
+            Box::new(m20240131_000001_add_pin_lockout_to_security_settings::Migration),
+            Box::new(m20240201_000001_create_user_sessions_table::Migration),
+            Box::new(m20240202_000001_create_auto_compound_plans_table::Migration),
+            Box::new(m20240203_000001_add_gas_fees_to_transactions::Migration),
+            Box::new(m20240204_000001_add_swap_slippage_to_user_preferences::Migration),
+            Box::new(m20240205_000001_add_paused_to_price_alerts::Migration),
+            Box::new(m20240206_000001_create_broadcast_messages_table::Migration),
+            Box::new(m20240207_000001_create_group_permissions_table::Migration),
         ]
     }
 }
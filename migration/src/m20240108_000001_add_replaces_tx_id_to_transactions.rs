@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Speed-up / cancel replacements point back at the transaction they replace
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .add_column(ColumnDef::new(Transaction::ReplacesTxId).uuid().null())
+                .add_foreign_key(
+                    TableForeignKey::new()
+                        .name("fk_transaction_replaces_tx")
+                        .from_tbl(Transaction::Table)
+                        .from_col(Transaction::ReplacesTxId)
+                        .to_tbl(Transaction::Table)
+                        .to_col(Transaction::Id)
+                        .on_delete(ForeignKeyAction::SetNull)
+                )
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .drop_foreign_key(Alias::new("fk_transaction_replaces_tx"))
+                .drop_column(Transaction::ReplacesTxId)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    Id,
+    ReplacesTxId,
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Stop-loss alerts can optionally sell from a wallet when they trigger
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .add_column(ColumnDef::new(PriceAlerts::StopLossPrice).decimal().null())
+                .add_column(ColumnDef::new(PriceAlerts::TakeProfitPrice).decimal().null())
+                .add_column(
+                    ColumnDef::new(PriceAlerts::AutoExecute).boolean().not_null().default(false)
+                )
+                .add_column(ColumnDef::new(PriceAlerts::WalletId).uuid().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .drop_column(PriceAlerts::StopLossPrice)
+                .drop_column(PriceAlerts::TakeProfitPrice)
+                .drop_column(PriceAlerts::AutoExecute)
+                .drop_column(PriceAlerts::WalletId)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PriceAlerts {
+    Table,
+    StopLossPrice,
+    TakeProfitPrice,
+    AutoExecute,
+    WalletId,
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Gas-conditional schedules only execute while the gas price is at or below this value
+        manager.alter_table(
+            Table::alter()
+                .table(ScheduledTransactions::Table)
+                .add_column(ColumnDef::new(ScheduledTransactions::MaxGasPriceGwei).double().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(ScheduledTransactions::Table)
+                .drop_column(ScheduledTransactions::MaxGasPriceGwei)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ScheduledTransactions {
+    Table,
+    MaxGasPriceGwei,
+}
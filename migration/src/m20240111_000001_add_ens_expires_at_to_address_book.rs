@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Entries cached from ENS resolution expire and are re-resolved after this time
+        manager.alter_table(
+            Table::alter()
+                .table(AddressBook::Table)
+                .add_column(ColumnDef::new(AddressBook::EnsExpiresAt).timestamp_with_time_zone().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(AddressBook::Table)
+                .drop_column(AddressBook::EnsExpiresAt)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AddressBook {
+    Table,
+    EnsExpiresAt,
+}
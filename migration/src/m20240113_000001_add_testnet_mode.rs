@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-user preference: new wallets are created on testnet networks
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .add_column(
+                    ColumnDef::new(SecuritySettings::UseTestnet).boolean().not_null().default(false)
+                )
+                .to_owned()
+        ).await?;
+
+        // The network a wallet was created on decides which RPCs serve it
+        manager.alter_table(
+            Table::alter()
+                .table(Wallet::Table)
+                .add_column(ColumnDef::new(Wallet::IsTestnet).boolean().not_null().default(false))
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter().table(Wallet::Table).drop_column(Wallet::IsTestnet).to_owned()
+        ).await?;
+
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .drop_column(SecuritySettings::UseTestnet)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SecuritySettings {
+    Table,
+    UseTestnet,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    IsTestnet,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RebalancingAlerts::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RebalancingAlerts::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(RebalancingAlerts::UserId).string().not_null())
+                    .col(ColumnDef::new(RebalancingAlerts::Symbol).string().not_null())
+                    .col(ColumnDef::new(RebalancingAlerts::TargetPct).double().not_null())
+                    .col(ColumnDef::new(RebalancingAlerts::DeviationThresholdPct).double().not_null())
+                    .col(ColumnDef::new(RebalancingAlerts::LastNotifiedAt).timestamp_with_time_zone().null())
+                    .col(
+                        ColumnDef::new(RebalancingAlerts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(RebalancingAlerts::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One target per symbol for each user
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_rebalancing_alerts_user_symbol")
+                    .table(RebalancingAlerts::Table)
+                    .col(RebalancingAlerts::UserId)
+                    .col(RebalancingAlerts::Symbol)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RebalancingAlerts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RebalancingAlerts {
+    Table,
+    Id,
+    UserId,
+    Symbol,
+    TargetPct,
+    DeviationThresholdPct,
+    LastNotifiedAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaxLots::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TaxLots::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(TaxLots::UserId).string().not_null())
+                    .col(ColumnDef::new(TaxLots::WalletId).uuid().not_null())
+                    .col(ColumnDef::new(TaxLots::Symbol).string().not_null())
+                    .col(ColumnDef::new(TaxLots::AcquiredDate).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(TaxLots::Quantity).double().not_null())
+                    .col(ColumnDef::new(TaxLots::CostBasisUsd).double().not_null())
+                    .col(ColumnDef::new(TaxLots::Disposed).boolean().not_null().default(false))
+                    .col(ColumnDef::new(TaxLots::DisposedDate).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(TaxLots::ProceedsUsd).double().null())
+                    .col(
+                        ColumnDef::new(TaxLots::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_tax_lots_wallet")
+                            .from(TaxLots::Table, TaxLots::WalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Open lots are consumed oldest-first per wallet and symbol
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_tax_lots_wallet_symbol_open")
+                    .table(TaxLots::Table)
+                    .col(TaxLots::WalletId)
+                    .col(TaxLots::Symbol)
+                    .col(TaxLots::Disposed)
+                    .col(TaxLots::AcquiredDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_tax_lots_user_disposed_date")
+                    .table(TaxLots::Table)
+                    .col(TaxLots::UserId)
+                    .col(TaxLots::DisposedDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaxLots::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TaxLots {
+    Table,
+    Id,
+    UserId,
+    WalletId,
+    Symbol,
+    AcquiredDate,
+    Quantity,
+    CostBasisUsd,
+    Disposed,
+    DisposedDate,
+    ProceedsUsd,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    Id,
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-send cap in USD, checked before every transfer
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .add_column(ColumnDef::new(SecuritySettings::MaxSingleTransferUsd).decimal())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .drop_column(SecuritySettings::MaxSingleTransferUsd)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SecuritySettings {
+    Table,
+    MaxSingleTransferUsd,
+}
@@ -0,0 +1,112 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DcaPlans::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(DcaPlans::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(DcaPlans::UserId).string().not_null())
+                    .col(ColumnDef::new(DcaPlans::WalletId).uuid().not_null())
+                    .col(ColumnDef::new(DcaPlans::ScheduleId).uuid().null())
+                    .col(ColumnDef::new(DcaPlans::FromToken).string().not_null())
+                    .col(ColumnDef::new(DcaPlans::ToToken).string().not_null())
+                    .col(ColumnDef::new(DcaPlans::AmountPerPeriod).double().not_null())
+                    .col(ColumnDef::new(DcaPlans::Period).string().not_null())
+                    .col(ColumnDef::new(DcaPlans::Status).string().not_null().default("active"))
+                    .col(
+                        ColumnDef::new(DcaPlans::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(DcaPlans::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_dca_plans_wallet")
+                            .from(DcaPlans::Table, DcaPlans::WalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_dca_plans_schedule")
+                            .from(DcaPlans::Table, DcaPlans::ScheduleId)
+                            .to(ScheduledTransactions::Table, ScheduledTransactions::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_dca_plans_user_id")
+                    .table(DcaPlans::Table)
+                    .col(DcaPlans::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The scheduler looks plans up by the schedule that just came due
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_dca_plans_schedule_id")
+                    .table(DcaPlans::Table)
+                    .col(DcaPlans::ScheduleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DcaPlans::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DcaPlans {
+    Table,
+    Id,
+    UserId,
+    WalletId,
+    ScheduleId,
+    FromToken,
+    ToToken,
+    AmountPerPeriod,
+    Period,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ScheduledTransactions {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    Id,
+}
@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // L1 submission cost of rollup transactions (Arbitrum), in the native token
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .add_column(ColumnDef::new(Transaction::L1FeePaid).decimal().null())
+                .add_column(ColumnDef::new(Transaction::L1Refund).decimal().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .drop_column(Transaction::L1Refund)
+                .drop_column(Transaction::L1FeePaid)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    L1FeePaid,
+    L1Refund,
+}
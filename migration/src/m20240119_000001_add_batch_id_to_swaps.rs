@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Swaps submitted together by /batchswap share a batch id
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Swaps::Table)
+                    .add_column(ColumnDef::new(Swaps::BatchId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_swaps_batch_id")
+                    .table(Swaps::Table)
+                    .col(Swaps::BatchId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_swaps_batch_id").table(Swaps::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Swaps::Table)
+                    .drop_column(Swaps::BatchId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Swaps {
+    Table,
+    BatchId,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BotDialogueStates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BotDialogueStates::UserId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BotDialogueStates::StateJson).json().not_null())
+                    .col(
+                        ColumnDef::new(BotDialogueStates::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BotDialogueStates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Stale dialogues are purged by expiry
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_bot_dialogue_states_expires_at")
+                    .table(BotDialogueStates::Table)
+                    .col(BotDialogueStates::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BotDialogueStates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BotDialogueStates {
+    Table,
+    UserId,
+    StateJson,
+    ExpiresAt,
+    UpdatedAt,
+}
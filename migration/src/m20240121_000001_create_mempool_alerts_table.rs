@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MempoolAlerts::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(MempoolAlerts::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(MempoolAlerts::UserId).string().not_null())
+                    .col(ColumnDef::new(MempoolAlerts::WalletId).uuid().not_null())
+                    .col(ColumnDef::new(MempoolAlerts::Chain).string().not_null())
+                    .col(ColumnDef::new(MempoolAlerts::Address).string().not_null())
+                    .col(ColumnDef::new(MempoolAlerts::IsTestnet).boolean().not_null().default(false))
+                    .col(
+                        ColumnDef::new(MempoolAlerts::AlertType)
+                            .string()
+                            .not_null()
+                            .default("large_incoming"),
+                    )
+                    .col(ColumnDef::new(MempoolAlerts::ThresholdUsd).double().not_null())
+                    .col(ColumnDef::new(MempoolAlerts::Active).boolean().not_null().default(true))
+                    .col(
+                        ColumnDef::new(MempoolAlerts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(MempoolAlerts::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_mempool_alerts_wallet")
+                            .from(MempoolAlerts::Table, MempoolAlerts::WalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One mempool alert per wallet; /setmempool replaces the threshold
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_mempool_alerts_wallet_id")
+                    .table(MempoolAlerts::Table)
+                    .col(MempoolAlerts::WalletId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MempoolAlerts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MempoolAlerts {
+    Table,
+    Id,
+    UserId,
+    WalletId,
+    Chain,
+    Address,
+    IsTestnet,
+    AlertType,
+    ThresholdUsd,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    Id,
+}
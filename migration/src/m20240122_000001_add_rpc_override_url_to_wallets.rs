@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Encrypted RPC URL of a private node used instead of the shared pool for this wallet
+        manager.alter_table(
+            Table::alter()
+                .table(Wallet::Table)
+                .add_column(ColumnDef::new(Wallet::RpcOverrideUrl).text().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Wallet::Table)
+                .drop_column(Wallet::RpcOverrideUrl)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    RpcOverrideUrl,
+}
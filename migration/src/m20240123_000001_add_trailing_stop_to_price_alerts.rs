@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Trailing stops remember the highest price seen and how far below it they fire
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .add_column(ColumnDef::new(PriceAlerts::PeakPrice).decimal().null())
+                .add_column(ColumnDef::new(PriceAlerts::TrailPct).decimal().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .drop_column(PriceAlerts::PeakPrice)
+                .drop_column(PriceAlerts::TrailPct)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PriceAlerts {
+    Table,
+    PeakPrice,
+    TrailPct,
+}
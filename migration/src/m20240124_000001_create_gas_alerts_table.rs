@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GasAlerts::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(GasAlerts::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(GasAlerts::UserId).string().not_null())
+                    .col(ColumnDef::new(GasAlerts::Chain).string().not_null())
+                    .col(ColumnDef::new(GasAlerts::MaxGweiThreshold).double().not_null())
+                    .col(ColumnDef::new(GasAlerts::Triggered).boolean().not_null().default(false))
+                    .col(ColumnDef::new(GasAlerts::Active).boolean().not_null().default(true))
+                    .col(ColumnDef::new(GasAlerts::LastTriggeredAt).timestamp_with_time_zone().null())
+                    .col(
+                        ColumnDef::new(GasAlerts::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(GasAlerts::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_gas_alerts_user_id")
+                    .table(GasAlerts::Table)
+                    .col(GasAlerts::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GasAlerts::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GasAlerts {
+    Table,
+    Id,
+    UserId,
+    Chain,
+    MaxGweiThreshold,
+    Triggered,
+    Active,
+    LastTriggeredAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sends above this USD value ask for the PIN; 0 asks on every send
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .add_column(
+                    ColumnDef::new(SecuritySettings::PinRequiredAboveUsd)
+                        .decimal()
+                        .not_null()
+                        .default(0)
+                )
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .drop_column(SecuritySettings::PinRequiredAboveUsd)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SecuritySettings {
+    Table,
+    PinRequiredAboveUsd,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserPreferences::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserPreferences::UserId).string().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(UserPreferences::FiatCurrency)
+                            .string()
+                            .not_null()
+                            .default("USD"),
+                    )
+                    .col(ColumnDef::new(UserPreferences::Locale).string().not_null().default("en"))
+                    .col(
+                        ColumnDef::new(UserPreferences::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(UserPreferences::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserPreferences::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserPreferences {
+    Table,
+    UserId,
+    FiatCurrency,
+    Locale,
+    CreatedAt,
+    UpdatedAt,
+}
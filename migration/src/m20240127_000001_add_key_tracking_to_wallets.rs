@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // BIP-44 index the key was derived at (null for raw private keys) and when it was last
+        // re-encrypted by a key rotation, both read by the security audit
+        manager.alter_table(
+            Table::alter()
+                .table(Wallet::Table)
+                .add_column(ColumnDef::new(Wallet::DerivationIndex).integer().null())
+                .add_column(ColumnDef::new(Wallet::KeyRotatedAt).timestamp_with_time_zone().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Wallet::Table)
+                .drop_column(Wallet::DerivationIndex)
+                .drop_column(Wallet::KeyRotatedAt)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    DerivationIndex,
+    KeyRotatedAt,
+}
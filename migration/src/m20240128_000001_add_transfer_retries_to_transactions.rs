@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // How many times the broadcast was retried and the error that triggered the last retry
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .add_column(
+                    ColumnDef::new(Transaction::TransferRetryCount).integer().not_null().default(0)
+                )
+                .add_column(ColumnDef::new(Transaction::LastRetryError).text().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .drop_column(Transaction::LastRetryError)
+                .drop_column(Transaction::TransferRetryCount)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    TransferRetryCount,
+    LastRetryError,
+}
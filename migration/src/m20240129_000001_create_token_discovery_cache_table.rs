@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TokenDiscoveryCache::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TokenDiscoveryCache::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(TokenDiscoveryCache::WalletId).uuid().not_null())
+                    .col(ColumnDef::new(TokenDiscoveryCache::Chain).string().not_null())
+                    .col(ColumnDef::new(TokenDiscoveryCache::Tokens).json().not_null())
+                    .col(ColumnDef::new(TokenDiscoveryCache::LastSyncedBlock).big_integer().null())
+                    .col(
+                        ColumnDef::new(TokenDiscoveryCache::LastDiscoveryAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_token_discovery_cache_wallet")
+                            .from(TokenDiscoveryCache::Table, TokenDiscoveryCache::WalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_token_discovery_cache_wallet_chain")
+                    .table(TokenDiscoveryCache::Table)
+                    .col(TokenDiscoveryCache::WalletId)
+                    .col(TokenDiscoveryCache::Chain)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TokenDiscoveryCache::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TokenDiscoveryCache {
+    Table,
+    Id,
+    WalletId,
+    Chain,
+    Tokens,
+    LastSyncedBlock,
+    LastDiscoveryAt,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    Id,
+}
@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Volume surge alerts compare 24h volume against the volume when they were created
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .add_column(ColumnDef::new(PriceAlerts::BaselineVolume).decimal().null())
+                .add_column(ColumnDef::new(PriceAlerts::VolumeMultiplier).decimal().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .drop_column(PriceAlerts::BaselineVolume)
+                .drop_column(PriceAlerts::VolumeMultiplier)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PriceAlerts {
+    Table,
+    BaselineVolume,
+    VolumeMultiplier,
+}
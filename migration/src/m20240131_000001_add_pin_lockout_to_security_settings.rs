@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Consecutive wrong PINs, and how long the PIN is locked once they run out
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .add_column(
+                    ColumnDef::new(SecuritySettings::PinAttempts).integer().not_null().default(0)
+                )
+                .add_column(
+                    ColumnDef::new(SecuritySettings::PinLockedUntil)
+                        .timestamp_with_time_zone()
+                        .null()
+                )
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(SecuritySettings::Table)
+                .drop_column(SecuritySettings::PinAttempts)
+                .drop_column(SecuritySettings::PinLockedUntil)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SecuritySettings {
+    Table,
+    PinAttempts,
+    PinLockedUntil,
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserSessions::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserSessions::UserId).big_integer().not_null().primary_key())
+                    .col(ColumnDef::new(UserSessions::LastWalletId).uuid().null())
+                    .col(ColumnDef::new(UserSessions::LastChain).string().null())
+                    .col(ColumnDef::new(UserSessions::LastAction).string().null())
+                    .col(
+                        ColumnDef::new(UserSessions::LastActiveAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    // Deleting the wallet only forgets it, not the rest of the session
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_sessions_wallet")
+                            .from(UserSessions::Table, UserSessions::LastWalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserSessions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserSessions {
+    Table,
+    UserId,
+    LastWalletId,
+    LastChain,
+    LastAction,
+    LastActiveAt,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    Id,
+}
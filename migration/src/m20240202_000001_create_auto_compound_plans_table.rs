@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AutoCompoundPlans::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(AutoCompoundPlans::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(AutoCompoundPlans::UserId).string().not_null())
+                    .col(ColumnDef::new(AutoCompoundPlans::WalletId).uuid().not_null())
+                    .col(ColumnDef::new(AutoCompoundPlans::ScheduleId).uuid().null())
+                    .col(ColumnDef::new(AutoCompoundPlans::Protocol).string().not_null())
+                    .col(ColumnDef::new(AutoCompoundPlans::MinProfitUsd).double().not_null())
+                    .col(ColumnDef::new(AutoCompoundPlans::Period).string().not_null())
+                    .col(ColumnDef::new(AutoCompoundPlans::Status).string().not_null().default("active"))
+                    .col(
+                        ColumnDef::new(AutoCompoundPlans::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(AutoCompoundPlans::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_auto_compound_plans_wallet")
+                            .from(AutoCompoundPlans::Table, AutoCompoundPlans::WalletId)
+                            .to(Wallet::Table, Wallet::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_auto_compound_plans_schedule")
+                            .from(AutoCompoundPlans::Table, AutoCompoundPlans::ScheduleId)
+                            .to(ScheduledTransactions::Table, ScheduledTransactions::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The scheduler looks plans up by the schedule that just came due
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_auto_compound_plans_schedule_id")
+                    .table(AutoCompoundPlans::Table)
+                    .col(AutoCompoundPlans::ScheduleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AutoCompoundPlans::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AutoCompoundPlans {
+    Table,
+    Id,
+    UserId,
+    WalletId,
+    ScheduleId,
+    Protocol,
+    MinProfitUsd,
+    Period,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ScheduledTransactions {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Wallet {
+    Table,
+    Id,
+}
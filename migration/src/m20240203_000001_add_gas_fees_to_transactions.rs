@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Network fee of a confirmed transaction, in the native token and in USD at confirmation
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .add_column(ColumnDef::new(Transaction::GasFeeNative).string().null())
+                .add_column(ColumnDef::new(Transaction::GasFeeUsd).double().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Transaction::Table)
+                .drop_column(Transaction::GasFeeUsd)
+                .drop_column(Transaction::GasFeeNative)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transaction {
+    Table,
+    GasFeeNative,
+    GasFeeUsd,
+}
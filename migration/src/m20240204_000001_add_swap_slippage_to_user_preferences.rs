@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Slippage tolerance the user last swapped with, in percent
+        manager.alter_table(
+            Table::alter()
+                .table(UserPreferences::Table)
+                .add_column(ColumnDef::new(UserPreferences::SwapSlippagePct).double().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(UserPreferences::Table)
+                .drop_column(UserPreferences::SwapSlippagePct)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserPreferences {
+    Table,
+    SwapSlippagePct,
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Paused alerts stay in place but are skipped by the alert checker
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .add_column(ColumnDef::new(PriceAlerts::Paused).boolean().not_null().default(false))
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(PriceAlerts::Table)
+                .drop_column(PriceAlerts::Paused)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PriceAlerts {
+    Table,
+    Paused,
+}
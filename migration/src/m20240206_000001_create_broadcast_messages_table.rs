@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BroadcastMessages::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BroadcastMessages::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(BroadcastMessages::MessageText).text().not_null())
+                    .col(ColumnDef::new(BroadcastMessages::SenderAdminId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(BroadcastMessages::SentAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(BroadcastMessages::RecipientCount).integer().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BroadcastMessages::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum BroadcastMessages {
+    Table,
+    Id,
+    MessageText,
+    SenderAdminId,
+    SentAt,
+    RecipientCount,
+}
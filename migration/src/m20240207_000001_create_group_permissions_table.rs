@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GroupPermissions::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(GroupPermissions::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(GroupPermissions::ChatId).big_integer().not_null())
+                    .col(ColumnDef::new(GroupPermissions::UserId).big_integer().not_null())
+                    .col(ColumnDef::new(GroupPermissions::Username).string().null())
+                    .col(ColumnDef::new(GroupPermissions::Role).string_len(20).not_null())
+                    .col(
+                        ColumnDef::new(GroupPermissions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(GroupPermissions::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_group_permissions_chat_user")
+                    .table(GroupPermissions::Table)
+                    .col(GroupPermissions::ChatId)
+                    .col(GroupPermissions::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(GroupPermissions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum GroupPermissions {
+    Table,
+    Id,
+    ChatId,
+    UserId,
+    Username,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+}
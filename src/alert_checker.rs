@@ -1,8 +1,20 @@
-use crate::enums::AlertKind;
-use crate::services::price_alert_service::PriceAlertService;
-use crate::services::price_service::PriceService;
+use crate::db::entity::price_alert;
+use crate::enums::{ AlertKind, Chain };
+use crate::price_monitor::PriceMonitor;
+use crate::services::{ BalanceService, GasEstimationService, MempoolWatcher, PortfolioService };
+use crate::services::price_alert_service::{
+    AlertCheckReply,
+    AlertCheckRequests,
+    is_native_symbol,
+    PriceAlertService,
+    TriggeredAlert,
+};
+use crate::services::rebalancing_service::RebalancingService;
+use crate::services::price_service::{ PriceService, PriceSubscription };
+use crate::services::swap_service::{ SwapRequest, SwapService };
 use sea_orm::DatabaseConnection;
 use sea_orm::prelude::Decimal;
+use std::collections::{ HashMap, HashSet };
 use std::sync::Arc;
 use teloxide::prelude::*;
 use tokio::time::{ interval, Duration };
@@ -12,58 +24,321 @@ fn decimal_to_f64(d: Decimal) -> Option<f64> {
     d.to_string().parse::<f64>().ok()
 }
 
+/// Slippage used for stop-loss auto-sells (percentage)
+const STOP_LOSS_SLIPPAGE: f64 = 1.0;
+
+/// Share of a native balance sold by a stop-loss, leaving the rest for gas
+const NATIVE_SELL_RATIO: f64 = 0.98;
+
+/// A triggered gas alert re-arms once gas rises above its threshold times this
+const GAS_ALERT_RESET_FACTOR: f64 = 1.2;
+
+/// Seconds between periodic alert check passes
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Portfolio allocations are checked every this many alert ticks (minutes)
+const REBALANCE_CHECK_EVERY_TICKS: u64 = 15;
+
+/// Compact USD amount for volumes, e.g. "$45B" or "$820.5M"
+fn format_volume_usd(value: f64) -> String {
+    let (scaled, suffix) = if value >= 1e9 {
+        (value / 1e9, "B")
+    } else if value >= 1e6 {
+        (value / 1e6, "M")
+    } else if value >= 1e3 {
+        (value / 1e3, "K")
+    } else {
+        (value, "")
+    };
+    let formatted = format!("{:.1}", scaled);
+    format!("${}{}", formatted.trim_end_matches(".0"), suffix)
+}
+
+fn format_volume_alert(alert: &price_alert::Model, current_volume: f64) -> String {
+    let baseline = alert.baseline_volume.and_then(decimal_to_f64).unwrap_or_default();
+    let ratio = if baseline > 0.0 { current_volume / baseline } else { 0.0 };
+    format!(
+        "📊 Volume alert: {} 24h volume is {:.1}x above baseline ({} vs {} baseline)\n\n\
+        This alert has been deactivated. Use /setvolumealert to create a new one.",
+        alert.token_symbol,
+        ratio,
+        format_volume_usd(current_volume),
+        format_volume_usd(baseline)
+    )
+}
+
+/// Next manual check request; never resolves when manual checks aren't wired up
+async fn next_manual_check(
+    requests: &mut Option<AlertCheckRequests>
+) -> Option<AlertCheckReply> {
+    match requests {
+        Some(requests) => requests.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Stablecoin a stop-loss sells into on each chain
+fn stablecoin_for_chain(chain: &str) -> Option<&'static str> {
+    match chain.parse::<Chain>().ok()? {
+        Chain::Eth => Some("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+        Chain::Bsc => Some("0x55d398326f99059fF775485246999027B3197955"),
+        Chain::Polygon => Some("0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"),
+        Chain::Solana => Some("USDC"),
+        _ => None,
+    }
+}
+
 pub struct AlertChecker {
     db: DatabaseConnection,
     price_service: Arc<PriceService>,
+    balance_service: Arc<BalanceService>,
+    swap_service: Arc<SwapService>,
+    portfolio_service: Arc<PortfolioService>,
+    price_monitor: Arc<PriceMonitor>,
+    mempool_watcher: Option<MempoolWatcher>,
+    gas_estimation_service: Option<Arc<GasEstimationService>>,
+    /// Pushed prices for symbol alerts, so each pass reads the latest value instead of fetching
+    price_subscriptions: HashMap<String, PriceSubscription>,
+    /// Check passes requested through `PriceAlertService::check_all_now`
+    manual_checks: Option<AlertCheckRequests>,
     bot: Bot,
 }
 
 impl AlertChecker {
-    pub fn new(db: DatabaseConnection, price_service: Arc<PriceService>, bot: Bot) -> Self {
+    pub fn new(
+        db: DatabaseConnection,
+        price_service: Arc<PriceService>,
+        balance_service: Arc<BalanceService>,
+        swap_service: Arc<SwapService>,
+        portfolio_service: Arc<PortfolioService>,
+        price_monitor: Arc<PriceMonitor>,
+        bot: Bot
+    ) -> Self {
         Self {
             db,
             price_service,
+            balance_service,
+            swap_service,
+            portfolio_service,
+            price_monitor,
+            mempool_watcher: None,
+            gas_estimation_service: None,
+            price_subscriptions: HashMap::new(),
+            manual_checks: None,
             bot,
         }
     }
 
+    /// Also watch mempools for large incoming transfers (`/setmempool` alerts)
+    pub fn with_mempool_watcher(mut self, mempool_watcher: MempoolWatcher) -> Self {
+        self.mempool_watcher = Some(mempool_watcher);
+        self
+    }
+
+    /// Also check `/gasalert` thresholds against current network gas prices
+    pub fn with_gas_estimation_service(mut self, gas_estimation_service: Arc<GasEstimationService>) -> Self {
+        self.gas_estimation_service = Some(gas_estimation_service);
+        self
+    }
+
+    /// Also run a check pass whenever one is requested through the paired `AlertCheckHandle`
+    pub fn with_manual_checks(mut self, requests: AlertCheckRequests) -> Self {
+        self.manual_checks = Some(requests);
+        self
+    }
+
     /// Start the background alert checker that runs every 60 seconds
-    pub async fn start(self) {
-        let mut interval = interval(Duration::from_secs(60));
+    pub async fn start(mut self) {
+        self.register_watched_symbols().await;
+
+        // Pending transactions need a much tighter loop than price checks
+        if let Some(watcher) = self.mempool_watcher.take() {
+            tokio::spawn(watcher.run(self.bot.clone()));
+        }
+
+        let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+        let mut ticks: u64 = 0;
+        let mut manual_checks = self.manual_checks.take();
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                Some(reply) = next_manual_check(&mut manual_checks) => {
+                    let result = self.check_alerts().await;
+                    if let Some(ref requests) = manual_checks {
+                        requests.record_check(None);
+                    }
+                    let _ = reply.send(result);
+                    continue;
+                }
+            }
 
             if let Err(e) = self.check_alerts().await {
                 eprintln!("Alert checker error: {}", e);
             }
+            if let Some(ref requests) = manual_checks {
+                let next = chrono::Utc::now() + chrono::Duration::seconds(CHECK_INTERVAL_SECS as i64);
+                requests.record_check(Some(next));
+            }
+
+            if let Err(e) = self.check_gas_alerts().await {
+                eprintln!("Gas alert check error: {}", e);
+            }
+
+            if ticks.is_multiple_of(REBALANCE_CHECK_EVERY_TICKS) {
+                if let Err(e) = self.check_rebalancing().await {
+                    eprintln!("Rebalancing check error: {}", e);
+                }
+            }
+            ticks += 1;
+        }
+    }
+
+    /// Notify users whose portfolio allocation drifted past one of their targets
+    async fn check_rebalancing(&self) -> crate::error::Result<()> {
+        let rebalancing_service = RebalancingService::new(self.db.clone());
+        let targets = rebalancing_service.get_all_targets().await?;
+
+        let mut by_user: std::collections::HashMap<String, Vec<_>> = std::collections::HashMap::new();
+        for target in targets.into_iter().filter(RebalancingService::can_notify) {
+            by_user.entry(target.user_id.clone()).or_default().push(target);
+        }
+
+        for (user_id, targets) in by_user {
+            let portfolio = match self.portfolio_service.get_portfolio(&user_id).await {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!("Rebalancing check skipped for user {}: {}", user_id, e);
+                    continue;
+                }
+            };
+
+            let allocations = RebalancingService::compute_allocations(&portfolio, &targets);
+            for (target, allocation) in targets.iter().zip(allocations) {
+                if !allocation.needs_rebalance() {
+                    continue;
+                }
+
+                let action = if allocation.rebalance_amount > 0.0 { "selling" } else { "buying" };
+                let message = format!(
+                    "⚖️ Rebalancing needed: {} is {:.0}% (target {:.0}%). Consider {} {:.6} {}.",
+                    allocation.symbol,
+                    allocation.actual_pct,
+                    allocation.target_pct,
+                    action,
+                    allocation.rebalance_amount.abs(),
+                    allocation.symbol
+                );
+
+                if let Ok(chat_id) = user_id.parse::<i64>() {
+                    let _ = self.bot.send_message(ChatId(chat_id), message).await;
+                }
+                let _ = rebalancing_service.mark_notified(target.id).await;
+            }
         }
+
+        Ok(())
     }
 
-    /// Check all active alerts
-    async fn check_alerts(&self) -> crate::error::Result<()> {
+    /// Notify users whose gas threshold has been reached, once per dip below it
+    async fn check_gas_alerts(&self) -> crate::error::Result<()> {
+        let Some(gas_estimation_service) = &self.gas_estimation_service else {
+            return Ok(());
+        };
+
+        let alert_service = PriceAlertService::new(self.db.clone());
+        let alerts = alert_service.get_active_gas_alerts().await?;
+
+        // One gas price lookup per chain, however many alerts watch it
+        let mut gas_prices: std::collections::HashMap<String, Option<f64>> = std::collections::HashMap::new();
+        for alert in alerts {
+            if !gas_prices.contains_key(&alert.chain) {
+                let price = match gas_estimation_service.get_current_gas_price_gwei(&alert.chain).await {
+                    Ok(gwei) => Some(gwei),
+                    Err(e) => {
+                        tracing::warn!("Gas price unavailable for {}: {}", alert.chain, e);
+                        None
+                    }
+                };
+                gas_prices.insert(alert.chain.clone(), price);
+            }
+            let Some(current_gwei) = gas_prices[&alert.chain] else {
+                continue;
+            };
+
+            if !alert.triggered && current_gwei <= alert.max_gwei_threshold {
+                let message = format!(
+                    "⛽ {} gas is now {:.0} Gwei (your threshold: {} Gwei). Good time to transact!",
+                    alert.chain,
+                    current_gwei,
+                    alert.max_gwei_threshold
+                );
+                if let Ok(chat_id) = alert.user_id.parse::<i64>() {
+                    let _ = self.bot.send_message(ChatId(chat_id), message).await;
+                }
+                alert_service.set_gas_alert_triggered(alert.id, true).await?;
+            } else if alert.triggered && current_gwei > alert.max_gwei_threshold * GAS_ALERT_RESET_FACTOR {
+                alert_service.set_gas_alert_triggered(alert.id, false).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Have the price monitor keep prices warm for every symbol with an active alert
+    async fn register_watched_symbols(&self) {
+        let alert_service = PriceAlertService::new(self.db.clone());
+        match alert_service.get_active_alerts().await {
+            Ok(alerts) => {
+                for alert in alerts.iter().filter(|a| a.token_address.is_none()) {
+                    self.price_monitor.register(&alert.token_symbol);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to register alert symbols with price monitor: {}", e),
+        }
+    }
+
+    /// Check all active alerts, returning the ones that fired
+    async fn check_alerts(&mut self) -> crate::error::Result<Vec<TriggeredAlert>> {
         let alert_service = PriceAlertService::new(self.db.clone());
         let alerts = alert_service.get_active_alerts().await?;
+        let mut triggered = Vec::new();
+        // New trailing-stop highs, written in one batch after the pass
+        let mut peak_updates: Vec<(uuid::Uuid, Decimal)> = Vec::new();
+        let alerting_symbols: HashSet<String> = alerts
+            .iter()
+            .filter(|a| a.token_address.is_none())
+            .map(|a| a.token_symbol.to_uppercase())
+            .collect();
 
         for alert in alerts {
             // Get current price
-            let current_price = if let Some(ref token_addr) = alert.token_address {
+            let price_info = if let Some(ref token_addr) = alert.token_address {
                 // Get price by token address
                 match self.price_service.get_token_price_by_address(&alert.chain, token_addr).await {
-                    Ok(price_info) => price_info.usd_price,
+                    Ok(price_info) => price_info,
                     Err(_) => {
                         continue;
                     }
                 }
             } else {
-                // Get price by symbol
-                match self.price_service.get_price(&alert.token_symbol).await {
-                    Ok(price_info) => price_info.usd_price,
-                    Err(_) => {
-                        continue;
-                    }
+                // Latest pushed price, fetching only when none has arrived or it went stale
+                let symbol = alert.token_symbol.to_uppercase();
+                let subscription = self.price_subscriptions
+                    .entry(symbol.clone())
+                    .or_insert_with(|| self.price_service.subscribe(&symbol));
+                match subscription.latest() {
+                    Some(price_info) => price_info,
+                    None =>
+                        match self.price_service.get_price(&symbol).await {
+                            Ok(price_info) => price_info,
+                            Err(_) => {
+                                continue;
+                            }
+                        }
                 }
             };
+            let current_price = price_info.usd_price;
 
             // Parse the alert kind from the DB string
             let alert_kind = match alert.alert_type.parse::<AlertKind>() {
@@ -101,6 +376,45 @@ impl AlertChecker {
                         _ => false,
                     }
                 }
+                AlertKind::StopLoss => {
+                    alert.stop_loss_price
+                        .and_then(decimal_to_f64)
+                        .map(|stop| current_price <= stop)
+                        .unwrap_or(false)
+                }
+                AlertKind::TakeProfit => {
+                    alert.take_profit_price
+                        .and_then(decimal_to_f64)
+                        .map(|target| current_price >= target)
+                        .unwrap_or(false)
+                }
+                AlertKind::TrailingStop => {
+                    match alert.trail_pct.and_then(decimal_to_f64) {
+                        Some(trail_pct) => {
+                            let stored_peak = alert.peak_price.and_then(decimal_to_f64).unwrap_or(0.0);
+                            if current_price > stored_peak {
+                                if let Some(peak) = Decimal::from_f64_retain(current_price) {
+                                    peak_updates.push((alert.id, peak));
+                                }
+                            }
+                            let peak = stored_peak.max(current_price);
+                            current_price <= peak * (1.0 - trail_pct / 100.0)
+                        }
+                        None => false,
+                    }
+                }
+                // Watched by the mempool watcher, never stored as a price alert
+                AlertKind::LargeIncoming => false,
+                AlertKind::VolumeSurge => {
+                    match (
+                        price_info.volume_24h,
+                        alert.baseline_volume.and_then(decimal_to_f64),
+                        alert.volume_multiplier.and_then(decimal_to_f64),
+                    ) {
+                        (Some(volume), Some(baseline), Some(multiplier)) => volume > baseline * multiplier,
+                        _ => false,
+                    }
+                }
             };
 
             // Update last checked time
@@ -108,7 +422,16 @@ impl AlertChecker {
 
             if should_trigger {
                 // Send notification
-                let message = self.format_alert_message(&alert, current_price, alert_kind);
+                let mut message = if alert_kind == AlertKind::VolumeSurge {
+                    format_volume_alert(&alert, price_info.volume_24h.unwrap_or_default())
+                } else {
+                    self.format_alert_message(&alert, current_price, alert_kind)
+                };
+
+                if alert_kind == AlertKind::StopLoss && alert.auto_execute {
+                    message.push_str("\n\n");
+                    message.push_str(&self.execute_stop_loss(&alert).await);
+                }
 
                 if let Ok(user_id) = alert.user_id.parse::<i64>() {
                     let chat_id = ChatId(user_id);
@@ -117,6 +440,14 @@ impl AlertChecker {
 
                 // Mark alert as triggered
                 let _ = alert_service.trigger_alert(alert.id).await;
+                triggered.push(TriggeredAlert {
+                    alert_id: alert.id,
+                    user_id: alert.user_id.clone(),
+                    token_symbol: alert.token_symbol.clone(),
+                    chain: alert.chain.clone(),
+                    alert_type: alert_kind.to_string(),
+                    current_price,
+                });
 
                 println!(
                     "Alert triggered for user {} - {} {} at ${:.4}",
@@ -128,7 +459,21 @@ impl AlertChecker {
             }
         }
 
-        Ok(())
+        if let Err(e) = alert_service.raise_peak_prices(&peak_updates).await {
+            tracing::warn!("Failed to update trailing stop peaks: {}", e);
+        }
+
+        // Stop following symbols whose alerts have all triggered or been removed
+        let price_service = &self.price_service;
+        self.price_subscriptions.retain(|symbol, subscription| {
+            let keep = alerting_symbols.contains(symbol);
+            if !keep {
+                price_service.unsubscribe(symbol, subscription.id);
+            }
+            keep
+        });
+
+        Ok(triggered)
     }
 
     fn format_alert_message(
@@ -141,6 +486,11 @@ impl AlertChecker {
             AlertKind::Above => "📈",
             AlertKind::Below => "📉",
             AlertKind::PercentChange => "⚡",
+            AlertKind::StopLoss => "🛑",
+            AlertKind::TakeProfit => "🎯",
+            AlertKind::TrailingStop => "🔻",
+            AlertKind::LargeIncoming => "📨",
+            AlertKind::VolumeSurge => "📊",
         };
 
         let condition = match kind {
@@ -168,6 +518,26 @@ impl AlertChecker {
                     _ => "triggered".to_string(),
                 }
             }
+            AlertKind::StopLoss => {
+                alert.stop_loss_price
+                    .and_then(decimal_to_f64)
+                    .map(|t| format!("stop-loss at ${:.4}", t))
+                    .unwrap_or_else(|| "triggered".to_string())
+            }
+            AlertKind::TakeProfit => {
+                alert.take_profit_price
+                    .and_then(decimal_to_f64)
+                    .map(|t| format!("take-profit at ${:.4}", t))
+                    .unwrap_or_else(|| "triggered".to_string())
+            }
+            AlertKind::TrailingStop => {
+                match (alert.trail_pct.and_then(decimal_to_f64), alert.peak_price.and_then(decimal_to_f64)) {
+                    (Some(trail), Some(peak)) => format!("trailing stop {}% below peak ${:.4}", trail, peak),
+                    _ => "trailing stop".to_string(),
+                }
+            }
+            AlertKind::LargeIncoming => "large incoming transfer".to_string(),
+            AlertKind::VolumeSurge => "24h volume surge".to_string(),
         };
 
         format!(
@@ -184,4 +554,65 @@ impl AlertChecker {
             condition = condition,
         )
     }
+
+    /// Sell the alert's position into a stablecoin, returning a status line for the user
+    async fn execute_stop_loss(&self, alert: &price_alert::Model) -> String {
+        let Some(wallet_id) = alert.wallet_id else {
+            return "⚠️ Auto-sell skipped: no wallet linked to this stop-loss.".to_string();
+        };
+
+        let Some(to_token) = stablecoin_for_chain(&alert.chain) else {
+            return format!("⚠️ Auto-sell is not supported on {}.", alert.chain);
+        };
+
+        // Without a contract address the balance below would be the native token's
+        if alert.token_address.is_none() && !is_native_symbol(&alert.chain, &alert.token_symbol) {
+            return format!(
+                "⚠️ Auto-sell skipped: {} has no contract address on this stop-loss, so it can't be sold safely.",
+                alert.token_symbol
+            );
+        }
+
+        let balance = match
+            self.balance_service.get_balance(wallet_id, alert.token_address.clone()).await
+        {
+            Ok(b) => b,
+            Err(e) => {
+                return format!("❌ Auto-sell failed: could not fetch balance: {}", e);
+            }
+        };
+
+        let available: f64 = balance.balance.parse().unwrap_or(0.0);
+        let amount = if alert.token_address.is_some() {
+            available
+        } else {
+            available * NATIVE_SELL_RATIO
+        };
+
+        if amount <= 0.0 {
+            return "⚠️ Auto-sell skipped: nothing to sell.".to_string();
+        }
+
+        let from_token = alert.token_address.clone().unwrap_or_else(|| balance.symbol.clone());
+        let request = SwapRequest {
+            user_id: alert.user_id.clone(),
+            wallet_id,
+            from_token,
+            to_token: to_token.to_string(),
+            amount,
+            slippage: STOP_LOSS_SLIPPAGE,
+            dex: None,
+        };
+
+        match self.swap_service.execute_swap(request).await {
+            Ok(swap) =>
+                format!(
+                    "✅ Auto-sold {:.6} {} (tx: {})",
+                    amount,
+                    balance.symbol,
+                    swap.tx_hash.unwrap_or_else(|| "pending".to_string())
+                ),
+            Err(e) => format!("❌ Auto-sell failed: {}", e),
+        }
+    }
 }
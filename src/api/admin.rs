@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ ConnectInfo, State },
+    http::{ header, HeaderMap },
+    response::IntoResponse,
+    Json,
+};
+use serde::{ Deserialize, Serialize };
+
+use crate::error::{ AppError, Result };
+use crate::services::price_alert_service::{ AlertStatus, TriggeredAlert };
+
+use super::AppState;
+
+#[derive(Deserialize)]
+pub struct RotateKeyRequest {
+    pub old_key: String,
+    pub new_key: String,
+}
+
+#[derive(Serialize)]
+pub struct RotateKeyResponse {
+    pub rotated_wallets: usize,
+}
+
+/// Re-encrypt all wallet keys with a new encryption key; needs the admin API key and an
+/// allowlisted IP
+pub async fn rotate_key(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<RotateKeyRequest>
+) -> Result<Json<RotateKeyResponse>> {
+    require_allowed_ip(&state, &addr, "key rotation")?;
+    require_admin_key(&state, &headers)?;
+
+    tracing::info!("Key rotation requested from {}", addr.ip());
+    let rotated_wallets = state.wallet_service.rotate_encryption_key(&req.old_key, &req.new_key).await?;
+
+    Ok(Json(RotateKeyResponse { rotated_wallets }))
+}
+
+/// Shortest password accepted for a full-server wallet export
+const MIN_EXPORT_PASSWORD_LEN: usize = 12;
+
+#[derive(Deserialize)]
+pub struct ExportWalletsRequest {
+    pub password: String,
+}
+
+/// Download every stored wallet as a password-encrypted zip archive. Keys stay encrypted with
+/// the server key; needs the admin API key and an allowlisted IP
+pub async fn export_wallets(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<ExportWalletsRequest>
+) -> Result<impl IntoResponse> {
+    require_allowed_ip(&state, &addr, "wallet export")?;
+    require_admin_key(&state, &headers)?;
+    if req.password.len() < MIN_EXPORT_PASSWORD_LEN {
+        return Err(
+            AppError::InvalidInput(
+                format!("The export password must be at least {} characters", MIN_EXPORT_PASSWORD_LEN)
+            )
+        );
+    }
+
+    tracing::info!("Wallet export requested from {}", addr.ip());
+    let archive = state.wallet_service.admin_export_all_wallets(&req.password).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"wallets-backup.zip\""),
+        ],
+        archive,
+    ))
+}
+
+#[derive(Serialize)]
+pub struct CheckAlertsResponse {
+    pub triggered: Vec<TriggeredAlert>,
+}
+
+/// Reject requests from client addresses outside `admin_allowed_ips`
+fn require_allowed_ip(state: &AppState, addr: &SocketAddr, action: &str) -> Result<()> {
+    if !state.admin_allowed_ips.contains(&addr.ip()) {
+        tracing::warn!("Rejected {} request from {}", action, addr.ip());
+        return Err(AppError::Forbidden("Admin access is not allowed from this address".to_string()));
+    }
+    Ok(())
+}
+
+/// Reject requests without the configured admin API key in `X-Admin-Key`
+fn require_admin_key(state: &AppState, headers: &HeaderMap) -> Result<()> {
+    let expected = state.admin_api_key
+        .as_deref()
+        .ok_or_else(|| AppError::Forbidden("Admin API key is not configured".to_string()))?;
+    let given = headers
+        .get("x-admin-key")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    // Compare every byte so the time taken doesn't reveal how much of the key matched
+    let matches =
+        given.len() == expected.len() &&
+        given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0;
+    if !matches {
+        return Err(AppError::Forbidden("Invalid admin API key".to_string()));
+    }
+    Ok(())
+}
+
+/// Run every active alert now rather than on the next poll; matching users are notified as usual
+pub async fn check_alerts(
+    State(state): State<AppState>,
+    headers: HeaderMap
+) -> Result<Json<CheckAlertsResponse>> {
+    require_admin_key(&state, &headers)?;
+
+    let triggered = state.price_alert_service.check_all_now().await?;
+    tracing::info!("Manual alert check triggered {} alert(s)", triggered.len());
+
+    Ok(Json(CheckAlertsResponse { triggered }))
+}
+
+/// Active alert counts and the alert checker's last and next run
+pub async fn alert_status(
+    State(state): State<AppState>,
+    headers: HeaderMap
+) -> Result<Json<AlertStatus>> {
+    require_admin_key(&state, &headers)?;
+    Ok(Json(state.price_alert_service.alert_status().await?))
+}
+
+#[derive(Serialize)]
+pub struct WalletCountResponse {
+    pub total: u64,
+    pub by_chain: HashMap<String, u64>,
+}
+
+/// Number of wallets stored on the server, in total and per chain
+pub async fn wallet_count(
+    State(state): State<AppState>,
+    headers: HeaderMap
+) -> Result<Json<WalletCountResponse>> {
+    require_admin_key(&state, &headers)?;
+
+    let (total, by_chain) = state.wallet_service.count_all_wallets().await?;
+    Ok(Json(WalletCountResponse { total, by_chain }))
+}
@@ -0,0 +1,35 @@
+use axum::{ extract::{ Query, State }, Json };
+use chrono::{ Duration, NaiveDate, Utc };
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::services::FeeSummary;
+
+use super::AppState;
+
+const DEFAULT_FEE_DAYS: i64 = 30;
+
+#[derive(Deserialize)]
+pub struct FeeQueryParams {
+    pub user_id: String,
+    /// First day to include (UTC); defaults to 30 days before `to`
+    pub from: Option<NaiveDate>,
+    /// Last day to include (UTC); defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+pub async fn get_fees(
+    State(state): State<AppState>,
+    Query(params): Query<FeeQueryParams>
+) -> Result<Json<FeeSummary>> {
+    let to = params.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = params.from.unwrap_or(to - Duration::days(DEFAULT_FEE_DAYS));
+
+    // Both ends are whole days, so `to` runs through its final second
+    let from = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let to = to.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc();
+
+    let summary = state.transaction_service.get_fee_summary(&params.user_id, from, to).await?;
+
+    Ok(Json(summary))
+}
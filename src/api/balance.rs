@@ -1,5 +1,10 @@
-use axum::{ extract::{ Path, Query, State }, Json };
+use axum::{
+    extract::{ ws::{ Message, WebSocket, WebSocketUpgrade }, Path, Query, State },
+    response::Response,
+    Json,
+};
 use serde::Deserialize;
+use tokio_stream::{ Stream, StreamExt };
 use uuid::Uuid;
 
 use crate::error::Result;
@@ -22,3 +27,64 @@ pub async fn get_balance(
 
     Ok(Json(balance))
 }
+
+#[derive(Deserialize)]
+pub struct HistoricalBalanceQuery {
+    pub block: u64,
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+pub async fn get_historical_balance(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<Uuid>,
+    Query(query): Query<HistoricalBalanceQuery>
+) -> Result<Json<Balance>> {
+    let balance = state.balance_service.get_historical_balance(
+        wallet_id,
+        query.token,
+        query.block
+    ).await?;
+
+    Ok(Json(balance))
+}
+
+/// Stream balance updates over a WebSocket: one JSON `Balance` per change, starting with the
+/// current one, or `{"error": ...}` when a read fails. Closes when the client disconnects.
+pub async fn watch_balance(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<Uuid>,
+    Query(query): Query<BalanceQuery>,
+    ws: WebSocketUpgrade
+) -> Result<Response> {
+    // Resolved before upgrading so an unknown wallet is a plain 404
+    let balances = state.balance_service.watch_balance(wallet_id, query.token).await?;
+
+    Ok(ws.on_upgrade(move |socket| stream_balances(socket, balances)))
+}
+
+async fn stream_balances(mut socket: WebSocket, balances: impl Stream<Item = Result<Balance>>) {
+    let mut balances = Box::pin(balances);
+    loop {
+        tokio::select! {
+            update = balances.next() => {
+                let Some(update) = update else {
+                    break;
+                };
+                let payload = match update {
+                    Ok(balance) => serde_json::to_string(&balance).unwrap_or_default(),
+                    Err(e) => serde_json::json!({ "error": e.user_facing_message() }).to_string(),
+                };
+                if socket.send(Message::Text(payload.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                // Clients only listen; anything but a close or error is ignored
+                if matches!(incoming, None | Some(Err(_)) | Some(Ok(Message::Close(_)))) {
+                    break;
+                }
+            }
+        }
+    }
+}
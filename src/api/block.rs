@@ -0,0 +1,18 @@
+use axum::{ extract::{ Path, State }, Json };
+
+use crate::error::Result;
+use crate::providers::BlockInfo;
+use crate::services::block_service::parse_block_number;
+
+use super::AppState;
+
+/// `number` is a block height or `latest`
+pub async fn get_block(
+    State(state): State<AppState>,
+    Path((chain, number)): Path<(String, String)>
+) -> Result<Json<BlockInfo>> {
+    let number = parse_block_number(&number)?;
+    let block = state.block_service.get_block(&chain, number).await?;
+
+    Ok(Json(block))
+}
@@ -0,0 +1,16 @@
+use axum::{ extract::{ Path, State }, Json };
+
+use crate::error::Result;
+use crate::services::network_congestion::CongestionReport;
+
+use super::AppState;
+
+/// Congestion level with the current, 7-day average and recent base fees of an EVM chain
+pub async fn get_congestion(
+    State(state): State<AppState>,
+    Path(chain): Path<String>
+) -> Result<Json<CongestionReport>> {
+    let report = state.network_congestion.report_for(&chain)?;
+
+    Ok(Json(report))
+}
@@ -1,11 +1,38 @@
+use std::net::IpAddr;
 use std::sync::Arc;
 
+pub mod admin;
+pub mod analytics;
 pub mod wallet;
 pub mod balance;
+pub mod block;
+pub mod congestion;
 pub mod transfer;
 pub mod transaction;
+pub mod swap;
+pub mod portfolio;
+pub mod tax;
+pub mod price;
+pub mod schedule;
+pub mod security;
+pub mod rate_limit;
 
-use crate::services::{ BalanceService, TransferService, WalletService, TransactionService };
+use crate::db::SwapRepository;
+use crate::services::{
+    BalanceService,
+    BlockService,
+    CrossChainBalanceService,
+    NetworkCongestionService,
+    PortfolioService,
+    PriceService,
+    TaxReportService,
+    TransferService,
+    WalletService,
+    TransactionService,
+};
+use crate::services::price_alert_service::PriceAlertService;
+use crate::services::scheduling_service::SchedulingService;
+use crate::services::security_service::SecurityService;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -13,20 +40,18 @@ pub struct AppState {
     pub balance_service: Arc<BalanceService>,
     pub transfer_service: Arc<TransferService>,
     pub transaction_service: Arc<TransactionService>,
-}
-
-impl AppState {
-    pub fn new(
-        wallet_service: Arc<WalletService>,
-        balance_service: Arc<BalanceService>,
-        transfer_service: Arc<TransferService>,
-        transaction_service: Arc<TransactionService>
-    ) -> Self {
-        Self {
-            wallet_service,
-            balance_service,
-            transfer_service,
-            transaction_service,
-        }
-    }
+    pub swap_repository: Arc<SwapRepository>,
+    pub portfolio_service: Arc<PortfolioService>,
+    pub cross_chain_balance_service: Arc<CrossChainBalanceService>,
+    pub tax_report_service: Arc<TaxReportService>,
+    pub price_service: Arc<PriceService>,
+    pub scheduling_service: Arc<SchedulingService>,
+    pub price_alert_service: Arc<PriceAlertService>,
+    pub security_service: Arc<SecurityService>,
+    pub block_service: Arc<BlockService>,
+    pub network_congestion: Arc<NetworkCongestionService>,
+    /// Client IPs allowed to call `/admin` endpoints
+    pub admin_allowed_ips: Arc<Vec<IpAddr>>,
+    /// Key required in `X-Admin-Key` by every `/admin` endpoint
+    pub admin_api_key: Option<Arc<str>>,
 }
@@ -0,0 +1,95 @@
+use axum::{ extract::{ Query, State }, http::header, response::IntoResponse, Json };
+use serde::{ Deserialize, Serialize };
+
+use crate::error::{ AppError, Result };
+use crate::services::cross_chain_balance_service::AggregatedTokenBalance;
+use crate::services::portfolio_service::{
+    BenchmarkComparison,
+    ChainAllocation,
+    DustWallet,
+    Portfolio,
+    PortfolioService,
+    DEFAULT_DUST_THRESHOLD_USD,
+};
+
+use super::AppState;
+
+#[derive(Deserialize)]
+pub struct PortfolioQueryParams {
+    pub user_id: String,
+}
+
+const DEFAULT_BENCHMARK_DAYS: u32 = 30;
+
+#[derive(Deserialize)]
+pub struct BenchmarkQueryParams {
+    pub user_id: String,
+    pub days: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct DustQueryParams {
+    pub user_id: String,
+    pub threshold_usd: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct PortfolioResponse {
+    #[serde(flatten)]
+    pub portfolio: Portfolio,
+    pub chain_breakdown: Vec<ChainAllocation>,
+}
+
+pub async fn get_portfolio(
+    State(state): State<AppState>,
+    Query(params): Query<PortfolioQueryParams>
+) -> Result<Json<PortfolioResponse>> {
+    let portfolio = state.portfolio_service.get_portfolio(&params.user_id).await?;
+    let chain_breakdown = PortfolioService::chain_breakdown(&portfolio);
+
+    Ok(Json(PortfolioResponse { portfolio, chain_breakdown }))
+}
+
+pub async fn get_benchmark(
+    State(state): State<AppState>,
+    Query(params): Query<BenchmarkQueryParams>
+) -> Result<Json<BenchmarkComparison>> {
+    let days = params.days.unwrap_or(DEFAULT_BENCHMARK_DAYS).clamp(1, 365);
+    let comparison = state.portfolio_service.compare_to_benchmark(&params.user_id, days).await?;
+
+    Ok(Json(comparison))
+}
+
+/// Wallets worth less than `threshold_usd`, with where each could be merged
+pub async fn get_dust_wallets(
+    State(state): State<AppState>,
+    Query(params): Query<DustQueryParams>
+) -> Result<Json<Vec<DustWallet>>> {
+    let threshold_usd = params.threshold_usd.unwrap_or(DEFAULT_DUST_THRESHOLD_USD);
+    if !threshold_usd.is_finite() || threshold_usd <= 0.0 {
+        return Err(AppError::InvalidInput("threshold_usd must be a positive USD amount".to_string()));
+    }
+    let dust = state.portfolio_service.find_dust_wallets(&params.user_id, threshold_usd).await?;
+
+    Ok(Json(dust))
+}
+
+/// Holdings grouped by token across every chain they're held on
+pub async fn get_by_token(
+    State(state): State<AppState>,
+    Query(params): Query<PortfolioQueryParams>
+) -> Result<Json<Vec<AggregatedTokenBalance>>> {
+    let tokens = state.cross_chain_balance_service.aggregate_by_token(&params.user_id).await?;
+
+    Ok(Json(tokens))
+}
+
+/// Portfolio card PNG for embedding in third-party pages
+pub async fn get_portfolio_card(
+    State(state): State<AppState>,
+    Query(params): Query<PortfolioQueryParams>
+) -> Result<impl IntoResponse> {
+    let bytes = state.portfolio_service.get_portfolio_card(&params.user_id).await?;
+
+    Ok(([(header::CONTENT_TYPE, "image/png")], bytes))
+}
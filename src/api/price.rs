@@ -0,0 +1,86 @@
+use axum::{ extract::{ Path, Query, State }, Json };
+use serde::{ Deserialize, Serialize };
+
+use crate::error::{ AppError, Result };
+use crate::services::price_service::{ Candle, OhlcvInterval };
+
+use super::AppState;
+
+/// Longest history the chart endpoint serves
+const MAX_CHART_DAYS: u32 = 365;
+
+#[derive(Deserialize)]
+pub struct PriceChartQueryParams {
+    pub days: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct OhlcvQueryParams {
+    pub interval: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct OhlcvResponse {
+    pub symbol: String,
+    pub interval: &'static str,
+    pub candles: Vec<Candle>,
+}
+
+#[derive(Serialize)]
+pub struct PricePoint {
+    pub timestamp: u64,
+    pub price: f64,
+}
+
+#[derive(Serialize)]
+pub struct PriceChartResponse {
+    pub symbol: String,
+    pub days: u32,
+    pub prices: Vec<PricePoint>,
+}
+
+pub async fn get_price_chart(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(params): Query<PriceChartQueryParams>
+) -> Result<Json<PriceChartResponse>> {
+    let days = params.days.unwrap_or(7);
+    if days == 0 || days > MAX_CHART_DAYS {
+        return Err(
+            AppError::InvalidInput(format!("days must be between 1 and {}", MAX_CHART_DAYS))
+        );
+    }
+
+    let history = state.price_service.get_price_history(&symbol, days).await?;
+
+    Ok(
+        Json(PriceChartResponse {
+            symbol: symbol.to_uppercase(),
+            days,
+            prices: history
+                .into_iter()
+                .map(|(timestamp, price)| PricePoint { timestamp, price })
+                .collect(),
+        })
+    )
+}
+
+pub async fn get_ohlcv(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(params): Query<OhlcvQueryParams>
+) -> Result<Json<OhlcvResponse>> {
+    let interval: OhlcvInterval = params.interval.as_deref().unwrap_or("1h").parse()?;
+    let limit = params.limit.unwrap_or(100);
+
+    let candles = state.price_service.get_ohlcv(&symbol, interval, limit).await?;
+
+    Ok(
+        Json(OhlcvResponse {
+            symbol: symbol.to_uppercase(),
+            interval: interval.as_str(),
+            candles,
+        })
+    )
+}
@@ -0,0 +1,168 @@
+use std::net::{ IpAddr, Ipv4Addr, SocketAddr };
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+
+use axum::{
+    extract::{ ConnectInfo, Request, State },
+    http::{ HeaderMap, HeaderValue },
+    middleware::Next,
+    response::{ IntoResponse, Response },
+};
+use dashmap::DashMap;
+
+use crate::error::AppError;
+
+/// Requests per client IP per minute for `POST /api/wallets/generate` and `/restore`
+pub const WALLET_CREATION_PER_MINUTE: u32 = 5;
+/// Requests per client IP per minute for `GET /api/wallets/{id}/balance`
+pub const BALANCE_PER_MINUTE: u32 = 30;
+/// Requests per client IP per minute for `POST /api/wallets/{id}/transfer`
+pub const SEND_TRANSACTION_PER_MINUTE: u32 = 10;
+
+/// Past this many tracked clients, expired windows are dropped on the next request
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+/// Where a client stands in its current window, reported in the `X-RateLimit-*` headers
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitStatus {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the window resets, rounded up
+    pub reset_secs: u64,
+}
+
+impl RateLimitStatus {
+    fn apply(&self, headers: &mut HeaderMap) {
+        headers.insert("x-ratelimit-limit", HeaderValue::from(self.limit));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert("x-ratelimit-reset", HeaderValue::from(self.reset_secs));
+    }
+}
+
+/// Fixed-window request counter per client IP, shared by the routes it is layered on
+pub struct ApiRateLimiter {
+    limit: u32,
+    window: Duration,
+    windows: DashMap<IpAddr, Window>,
+}
+
+impl ApiRateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            windows: DashMap::new(),
+        }
+    }
+
+    pub fn per_minute(limit: u32) -> Self {
+        Self::new(limit, Duration::from_secs(60))
+    }
+
+    /// Count a request from `ip`. `Err` once the client is over the limit for this window.
+    pub fn check(&self, ip: IpAddr) -> std::result::Result<RateLimitStatus, RateLimitStatus> {
+        let now = Instant::now();
+        if self.windows.len() > MAX_TRACKED_CLIENTS {
+            self.windows.retain(|_, w| now.duration_since(w.started) < self.window);
+        }
+
+        let mut entry = self.windows.entry(ip).or_insert_with(|| Window { started: now, count: 0 });
+        if now.duration_since(entry.started) >= self.window {
+            entry.started = now;
+            entry.count = 0;
+        }
+
+        // Rounded up so a client honouring Retry-After never arrives before the reset
+        let reset = self.window.saturating_sub(now.duration_since(entry.started));
+        let reset_secs = reset.as_millis().div_ceil(1000) as u64;
+
+        if entry.count >= self.limit {
+            return Err(RateLimitStatus { limit: self.limit, remaining: 0, reset_secs });
+        }
+        entry.count += 1;
+
+        Ok(RateLimitStatus {
+            limit: self.limit,
+            remaining: self.limit - entry.count,
+            reset_secs,
+        })
+    }
+}
+
+/// Middleware for `axum::middleware::from_fn_with_state`, answering 429 with `Retry-After`
+/// once the client IP has used up its requests for the window
+pub async fn rate_limit(
+    State(limiter): State<Arc<ApiRateLimiter>>,
+    request: Request,
+    next: Next
+) -> Response {
+    // Requests without connection info (in-process callers) share a single bucket
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    let (status, mut response) = match limiter.check(ip) {
+        Ok(status) => (status, next.run(request).await),
+        Err(status) => {
+            tracing::warn!("Rate limit exceeded for {} on {}", ip, request.uri().path());
+            let error = AppError::RateLimitExceeded { retry_after_secs: status.reset_secs };
+            (status, error.into_response())
+        }
+    };
+
+    status.apply(response.headers_mut());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{ body::Body, http::StatusCode, middleware::from_fn_with_state, routing::get, Router };
+    use tower::ServiceExt;
+
+    fn app(limiter: Arc<ApiRateLimiter>) -> Router {
+        Router::new().route("/", get(|| async { "ok" }).layer(from_fn_with_state(limiter, rate_limit)))
+    }
+
+    async fn call(app: &Router) -> Response {
+        app.clone().oneshot(Request::builder().uri("/").body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_returns_429_until_window_resets() {
+        let app = app(Arc::new(ApiRateLimiter::new(2, Duration::from_secs(1))));
+
+        let first = call(&app).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers()["x-ratelimit-limit"], "2");
+        assert_eq!(first.headers()["x-ratelimit-remaining"], "1");
+        assert_eq!(call(&app).await.status(), StatusCode::OK);
+
+        let limited = call(&app).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()["x-ratelimit-remaining"], "0");
+        let retry_after: u64 = limited.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+        assert_eq!(retry_after, 1);
+
+        tokio::time::sleep(Duration::from_secs(retry_after)).await;
+        assert_eq!(call(&app).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_clients_are_counted_separately() {
+        let limiter = ApiRateLimiter::per_minute(1);
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(limiter.check(a).is_ok());
+        assert!(limiter.check(a).is_err());
+        assert!(limiter.check(b).is_ok());
+    }
+}
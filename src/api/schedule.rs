@@ -0,0 +1,45 @@
+use axum::{ extract::{ Query, State }, Json };
+use chrono::{ Duration, NaiveDate, Utc };
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::services::scheduling_service::{ ScheduleRequest, ScheduleSimulation, ScheduledExecution };
+
+use super::AppState;
+
+const DEFAULT_CALENDAR_DAYS: i64 = 7;
+
+#[derive(Deserialize)]
+pub struct CalendarQueryParams {
+    pub user_id: String,
+    /// First day to include (UTC); defaults to today
+    pub from: Option<NaiveDate>,
+    /// Last day to include (UTC); defaults to a week after `from`
+    pub to: Option<NaiveDate>,
+}
+
+pub async fn get_schedule_calendar(
+    State(state): State<AppState>,
+    Query(params): Query<CalendarQueryParams>
+) -> Result<Json<Vec<ScheduledExecution>>> {
+    let from = params.from.unwrap_or_else(|| Utc::now().date_naive());
+    let to = params.to.unwrap_or(from + Duration::days(DEFAULT_CALENDAR_DAYS));
+
+    // Both ends are whole days, so `to` runs through its final second
+    let from = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let to = to.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc();
+
+    let executions = state.scheduling_service.get_execution_calendar(&params.user_id, from, to).await?;
+
+    Ok(Json(executions))
+}
+
+/// Dry run of a schedule against the wallet's current balance and fees; nothing is stored
+pub async fn simulate_schedule(
+    State(state): State<AppState>,
+    Json(request): Json<ScheduleRequest>
+) -> Result<Json<ScheduleSimulation>> {
+    let simulation = state.scheduling_service.simulate_schedule(&request).await?;
+
+    Ok(Json(simulation))
+}
@@ -0,0 +1,21 @@
+use axum::{ extract::{ Query, State }, Json };
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::services::security_service::PinStatus;
+
+use super::AppState;
+
+#[derive(Deserialize)]
+pub struct PinStatusQueryParams {
+    pub user_id: String,
+}
+
+pub async fn get_pin_status(
+    State(state): State<AppState>,
+    Query(params): Query<PinStatusQueryParams>
+) -> Result<Json<PinStatus>> {
+    let status = state.security_service.pin_status(&params.user_id).await?;
+
+    Ok(Json(status))
+}
@@ -0,0 +1,72 @@
+use axum::{ extract::{ Query, State }, Json };
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::db::entity::swap;
+
+use super::AppState;
+
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+
+#[derive(Deserialize)]
+pub struct SwapQueryParams {
+    pub wallet_id: Uuid,
+    pub limit: Option<u64>,
+    /// Return swaps older than this swap ID
+    pub before: Option<Uuid>,
+}
+
+pub async fn get_swaps(
+    State(state): State<AppState>,
+    Query(params): Query<SwapQueryParams>
+) -> Result<Json<Vec<SwapResponse>>> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    let swaps = match params.before {
+        Some(before) => {
+            state.swap_repository.find_by_wallet_before(params.wallet_id, before, limit).await?
+        }
+        None => state.swap_repository.find_by_wallet(params.wallet_id, limit, 0).await?,
+    };
+
+    Ok(Json(swaps.into_iter().map(|s| s.into()).collect()))
+}
+
+#[derive(Serialize)]
+pub struct SwapResponse {
+    pub id: Uuid,
+    pub wallet_id: Uuid,
+    pub chain: String,
+    pub dex: String,
+    pub from_token: String,
+    pub to_token: String,
+    pub from_amount: String,
+    pub to_amount: String,
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub error_message: Option<String>,
+    pub batch_id: Option<Uuid>,
+    pub created_at: String,
+}
+
+impl From<swap::Model> for SwapResponse {
+    fn from(swap: swap::Model) -> Self {
+        Self {
+            id: swap.id,
+            wallet_id: swap.wallet_id,
+            chain: swap.chain,
+            dex: swap.dex,
+            from_token: swap.from_token,
+            to_token: swap.to_token,
+            from_amount: swap.from_amount.to_string(),
+            to_amount: swap.to_amount.to_string(),
+            status: swap.status,
+            tx_hash: swap.tx_hash,
+            error_message: swap.error_message,
+            batch_id: swap.batch_id,
+            created_at: swap.created_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use axum::{ extract::{ Query, State }, Json };
+use serde::Deserialize;
+
+use crate::error::Result;
+use crate::services::tax_report_service::TaxReport;
+
+use super::AppState;
+
+#[derive(Deserialize)]
+pub struct TaxReportQueryParams {
+    pub user_id: String,
+    pub year: i32,
+}
+
+pub async fn get_tax_report(
+    State(state): State<AppState>,
+    Query(params): Query<TaxReportQueryParams>
+) -> Result<Json<TaxReport>> {
+    let report = state.tax_report_service.generate_report(&params.user_id, params.year).await?;
+
+    Ok(Json(report))
+}
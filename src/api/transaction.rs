@@ -2,8 +2,10 @@ use axum::{ extract::{ Path, Query, State }, Json };
 use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 
-use crate::error::Result;
+use crate::error::{ AppError, Result };
 use crate::db::entity::transaction;
+use crate::providers::TransactionDetail;
+use crate::services::polygon_bridge_service::BridgeTx;
 
 use super::AppState;
 
@@ -13,6 +15,12 @@ pub struct TransactionQueryParams {
     pub offset: Option<u64>,
 }
 
+#[derive(Deserialize)]
+pub struct ChainTransactionQueryParams {
+    #[serde(default)]
+    pub testnet: bool,
+}
+
 #[derive(Deserialize)]
 pub struct UserTransactionQueryParams {
     pub user_id: String,
@@ -40,6 +48,33 @@ pub async fn get_wallet_transactions(
     Ok(Json(response))
 }
 
+pub async fn get_received_transactions(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<Uuid>,
+    Query(params): Query<TransactionQueryParams>
+) -> Result<Json<Vec<TransactionResponse>>> {
+    let transactions = state.transaction_service.get_inbound_transactions(
+        wallet_id,
+        params.limit,
+        params.offset
+    ).await?;
+
+    let response: Vec<TransactionResponse> = transactions
+        .into_iter()
+        .map(|tx| tx.into())
+        .collect();
+
+    Ok(Json(response))
+}
+
+pub async fn get_pending_bridge_transactions(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<Uuid>
+) -> Result<Json<Vec<BridgeTx>>> {
+    let pending = state.transaction_service.get_pending_bridge_transactions(wallet_id).await?;
+    Ok(Json(pending))
+}
+
 pub async fn get_user_transactions(
     State(state): State<AppState>,
     Query(params): Query<UserTransactionQueryParams>
@@ -68,6 +103,18 @@ pub async fn get_transaction(
     Ok(Json(transaction.into()))
 }
 
+pub async fn get_chain_transaction(
+    State(state): State<AppState>,
+    Path((chain, tx_hash)): Path<(String, String)>,
+    Query(params): Query<ChainTransactionQueryParams>
+) -> Result<Json<TransactionDetail>> {
+    let detail = state.transaction_service
+        .get_chain_transaction(&chain, params.testnet, &tx_hash).await?
+        .ok_or_else(|| AppError::NotFound(format!("Transaction {} not found on {}", tx_hash, chain)))?;
+
+    Ok(Json(detail))
+}
+
 #[derive(Serialize)]
 pub struct TransactionResponse {
     pub id: Uuid,
@@ -82,6 +129,11 @@ pub struct TransactionResponse {
     pub status: String,
     pub block_number: Option<i64>,
     pub gas_used: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l1_fee_paid: Option<String>,
+    /// Arbitrum L1 gas reserved at submission but not charged, in the native token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l1_refund: Option<String>,
     pub created_at: String,
 }
 
@@ -100,6 +152,8 @@ impl From<transaction::Model> for TransactionResponse {
             status: tx.status,
             block_number: tx.block_number,
             gas_used: tx.gas_used,
+            l1_fee_paid: tx.l1_fee_paid.map(|fee| fee.normalize().to_string()),
+            l1_refund: tx.l1_refund.map(|refund| refund.normalize().to_string()),
             created_at: tx.created_at.to_string(),
         }
     }
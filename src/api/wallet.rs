@@ -1,9 +1,13 @@
-use axum::{ extract::{ Path, State }, http::StatusCode, Json };
+use axum::{ extract::{ Path, Query, State }, http::StatusCode, Json };
 use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 
 use crate::error::Result;
-use crate::services::wallet_service::{ GeneratedWalletResponse, RestoredWalletResponse };
+use crate::services::wallet_service::{
+    GeneratedWalletResponse,
+    RestoredWalletResponse,
+    WalletStatistics,
+};
 
 use super::AppState;
 
@@ -13,6 +17,9 @@ pub struct GenerateWalletRequest {
     pub chain: String,
     #[serde(default)]
     pub derivation_index: Option<u32>,
+    /// Create the wallet on the chain's testnet
+    #[serde(default)]
+    pub testnet: bool,
 }
 
 #[derive(Deserialize)]
@@ -22,6 +29,9 @@ pub struct RestoreWalletRequest {
     pub secret: String,
     #[serde(default)]
     pub derivation_index: Option<u32>,
+    /// Create the wallet on the chain's testnet
+    #[serde(default)]
+    pub testnet: bool,
 }
 
 pub async fn generate_wallet(
@@ -31,7 +41,8 @@ pub async fn generate_wallet(
     let response = state.wallet_service.generate_wallet(
         request.user_id,
         request.chain,
-        request.derivation_index
+        request.derivation_index,
+        request.testnet
     ).await?;
 
     Ok(Json(response))
@@ -45,7 +56,8 @@ pub async fn restore_wallet(
         request.user_id,
         request.chain,
         request.secret,
-        request.derivation_index
+        request.derivation_index,
+        request.testnet
     ).await?;
 
     Ok(Json(response))
@@ -68,6 +80,19 @@ pub async fn get_wallet(
     )
 }
 
+#[derive(Deserialize)]
+pub struct WalletStatisticsQuery {
+    pub user_id: String,
+}
+
+pub async fn get_wallet_statistics(
+    State(state): State<AppState>,
+    Query(query): Query<WalletStatisticsQuery>
+) -> Result<Json<WalletStatistics>> {
+    let statistics = state.wallet_service.get_wallet_statistics(&query.user_id).await?;
+    Ok(Json(statistics))
+}
+
 #[derive(Serialize)]
 pub struct WalletResponse {
     pub id: Uuid,
@@ -76,3 +101,39 @@ pub struct WalletResponse {
     pub address: String,
     pub created_at: String,
 }
+
+#[derive(Deserialize)]
+pub struct LightningInvoiceQuery {
+    /// 0 (or omitted) creates an invoice where the payer picks the amount
+    #[serde(default)]
+    pub amount_sats: u64,
+    #[serde(default)]
+    pub memo: String,
+}
+
+#[derive(Serialize)]
+pub struct LightningInvoiceResponse {
+    pub wallet_id: Uuid,
+    pub amount_sats: u64,
+    pub payment_request: String,
+}
+
+pub async fn get_lightning_invoice(
+    State(state): State<AppState>,
+    Path(wallet_id): Path<Uuid>,
+    Query(query): Query<LightningInvoiceQuery>
+) -> Result<Json<LightningInvoiceResponse>> {
+    let payment_request = state.wallet_service.generate_lightning_invoice(
+        wallet_id,
+        query.amount_sats,
+        &query.memo
+    ).await?;
+
+    Ok(
+        Json(LightningInvoiceResponse {
+            wallet_id,
+            amount_sats: query.amount_sats,
+            payment_request,
+        })
+    )
+}
@@ -2,9 +2,10 @@ use std::sync::Arc;
 use teloxide::prelude::*;
 use teloxide::types::MessageId;
 
-use crate::enums::{ Chain, AlertKind };
-use super::{BotState, DialogueState};
+use crate::enums::{ Chain, AlertKind, DcaStatus, TxStatus };
+use super::{BotState, DialogueState, PendingSendConfirmation, PendingSwapState, PendingSwapTokens, PinProtectedSend};
 use super::keyboards;
+use crate::services::token_security_service::{ RiskLevel, TokenSecurity };
 
 type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
 
@@ -14,25 +15,28 @@ pub async fn handle_text_message(
     msg: Message,
     state: Arc<BotState>,
 ) -> HandlerResult {
-    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
     let chat_id = msg.chat.id;
+    if !msg.chat.is_private() && !state.config.group_wallet_mode {
+        return Ok(());
+    }
+    let user_id = super::dialogue_key(chat_id, msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0));
+    // The dialogue key is only for dialogue storage: group wallets and contacts belong to the
+    // chat, while the PIN belongs to the member typing
+    let owner_id = if msg.chat.is_private() { user_id.to_string() } else { chat_id.0.to_string() };
+    let pin_owner = msg.from.as_ref().map(|u| u.id.0.to_string()).unwrap_or_else(|| chat_id.0.to_string());
     let text = msg.text().unwrap_or("");
 
     tracing::info!("handle_text_message called: user_id={}, text={}", user_id, text);
 
     // Get current dialogue state
-    let dialogue_state = {
-        let storage = state.dialogue_storage.read().await;
-        tracing::info!("Current dialogue storage keys: {:?}", storage.keys().collect::<Vec<_>>());
-        storage.get(&user_id).cloned().unwrap_or(DialogueState::None)
-    };
+    let dialogue_state = state.dialogue_storage.get(user_id).await?;
 
     tracing::info!("Dialogue state for user {}: {:?}", user_id, dialogue_state);
 
     match dialogue_state {
-        DialogueState::WaitingForSendAddress { wallet_id, amount, symbol } => {
+        DialogueState::WaitingForSendAddress { wallet_id, amount, symbol, send_max, amount_usd_estimate } => {
             // User entered recipient address
-            let recipient = text.trim().to_string();
+            let mut recipient = text.trim().to_string();
 
             // Validate address format (basic check)
             if recipient.is_empty() {
@@ -41,14 +45,60 @@ pub async fn handle_text_message(
                 return Ok(());
             }
 
-            // Clear dialogue state
-            {
-                let mut storage = state.dialogue_storage.write().await;
-                storage.remove(&user_id);
+            // Resolve ENS names (Ethereum wallets only)
+            if crate::chains::evm::ens::is_ens_name(&recipient) {
+                let is_eth_wallet = match uuid::Uuid::parse_str(&wallet_id) {
+                    Ok(uuid) => state.wallet_service.get_wallet(uuid).await
+                        .map(|w| w.chain == Chain::Eth.to_string())
+                        .unwrap_or(false),
+                    Err(_) => false,
+                };
+                if !is_eth_wallet {
+                    bot.send_message(chat_id, "❌ ENS names are only supported for Ethereum wallets. Please enter an address.")
+                        .await?;
+                    return Ok(());
+                }
+
+                match state.address_book_service.resolve_ens(&owner_id, &recipient).await {
+                    Ok(address) => {
+                        bot.send_message(chat_id, format!("✅ Resolved {} → {}", recipient.to_lowercase(), address))
+                            .await?;
+                        recipient = address;
+                    }
+                    Err(e) => {
+                        bot.send_message(chat_id, format!("❌ {}", e.user_facing_message()))
+                            .await?;
+                        return Ok(());
+                    }
+                }
+            } else if !super::handlers::looks_like_address(&recipient) {
+                // Auto-complete a contact name or address fragment from the address book
+                let matches = find_send_contacts(&wallet_id, &owner_id, &recipient, &state).await;
+                let exact = matches.iter().find(|c| c.name.eq_ignore_ascii_case(&recipient));
+                match (exact, matches.as_slice()) {
+                    (Some(contact), _) | (None, [contact]) => {
+                        bot.send_message(chat_id, format!("📖 Found saved address: {} → {}", contact.name, contact.address))
+                            .await?;
+                        recipient = contact.address.clone();
+                    }
+                    (None, []) => {}
+                    (None, _) => {
+                        bot.send_message(chat_id, format!(
+                            "📖 {} saved addresses match \"{}\". Pick one or type more:",
+                            matches.len(), recipient
+                        ))
+                        .reply_markup(keyboards::address_book_matches(&matches, user_id, &wallet_id))
+                        .await?;
+                        return Ok(());
+                    }
+                }
             }
 
+            // Clear dialogue state
+            state.dialogue_storage.remove(user_id).await?;
+
             // Show confirmation
-            show_send_confirmation(&bot, chat_id, &wallet_id, &recipient, &amount, &symbol, &state, user_id).await?;
+            show_send_confirmation(&bot, chat_id, &wallet_id, &recipient, &amount, &symbol, send_max, amount_usd_estimate, &state, user_id).await?;
         }
         DialogueState::WaitingForSendAmount { wallet_id, recipient, symbol } => {
             // User entered amount
@@ -64,21 +114,16 @@ pub async fn handle_text_message(
             // If recipient is empty, we need to ask for the address next
             if recipient.is_empty() {
                 // Update state to wait for address
-                {
-                    let mut storage = state.dialogue_storage.write().await;
-                    storage.insert(user_id, DialogueState::WaitingForSendAddress {
-                        wallet_id: wallet_id.clone(),
-                        amount: amount.clone(),
-                        symbol: symbol.clone(),
-                    });
-                }
+                state.dialogue_storage.set(user_id, DialogueState::WaitingForSendAddress {
+                    wallet_id: wallet_id.clone(),
+                    amount: amount.clone(),
+                    symbol: symbol.clone(),
+                    send_max: false,
+                    amount_usd_estimate: None,
+                }).await?;
 
-                // Ask for recipient address with cancel button
-                let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-                    vec![
-                        teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
-                    ],
-                ]);
+                // Ask for recipient address with saved-address shortcuts and cancel button
+                let keyboard = send_address_keyboard(&wallet_id, user_id, &owner_id, &state).await;
 
                 bot.send_message(chat_id, format!(
                     "📤 Send {} {}\n\n\
@@ -90,15 +135,73 @@ pub async fn handle_text_message(
                 .await?;
             } else {
                 // Clear dialogue state
-                {
-                    let mut storage = state.dialogue_storage.write().await;
-                    storage.remove(&user_id);
-                }
+                state.dialogue_storage.remove(user_id).await?;
 
                 // Show confirmation
-                show_send_confirmation(&bot, chat_id, &wallet_id, &recipient, &amount, &symbol, &state, user_id).await?;
+                show_send_confirmation(&bot, chat_id, &wallet_id, &recipient, &amount, &symbol, false, None, &state, user_id).await?;
             }
         }
+        DialogueState::WaitingForUsdSendAmount { wallet_id, symbol } => {
+            let usd_amount: f64 = match text.trim().trim_start_matches('$').parse() {
+                Ok(v) if v > 0.0 => v,
+                _ => {
+                    bot.send_message(chat_id, "❌ Please enter a valid positive USD amount.")
+                        .await?;
+                    return Ok(());
+                }
+            };
+
+            let cancel_keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+                vec![
+                    teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
+                ],
+            ]);
+
+            let usd_price = match state.price_service.get_price(&symbol).await {
+                Ok(price) if price.usd_price > 0.0 => price.usd_price,
+                result => {
+                    let reason = match result {
+                        Err(e) => e.user_facing_message().into_owned(),
+                        Ok(_) => "price unavailable".to_string(),
+                    };
+
+                    // Fall back to entering the amount in the native token
+                    state.dialogue_storage.set(user_id, DialogueState::WaitingForSendAmount {
+                        wallet_id: wallet_id.clone(),
+                        recipient: String::new(),
+                        symbol: symbol.clone(),
+                    }).await?;
+
+                    bot.send_message(chat_id, format!(
+                        "❌ Could not fetch {} price: {}\n\n\
+Type the amount in {} instead:",
+                        symbol, reason, symbol
+                    ))
+                    .reply_markup(cancel_keyboard)
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+            let amount = format!("{:.6}", usd_amount / usd_price);
+
+            state.dialogue_storage.set(user_id, DialogueState::WaitingForSendAddress {
+                wallet_id: wallet_id.clone(),
+                amount: amount.clone(),
+                symbol: symbol.clone(),
+                send_max: false,
+                amount_usd_estimate: Some(usd_amount),
+            }).await?;
+
+            bot.send_message(chat_id, format!(
+                "📤 Send {}\n\n\
+💰 Sending {} {} (~${:.2} USD)\n\n\
+📬 Now paste or type the recipient address:",
+                symbol, amount, symbol, usd_amount
+            ))
+            .reply_markup(send_address_keyboard(&wallet_id, user_id, &owner_id, &state).await)
+            .await?;
+        }
         DialogueState::WaitingForSwapAmount { wallet_id, from_token, to_token } => {
             // User entered swap amount
             let amount = text.trim().to_string();
@@ -109,14 +212,8 @@ pub async fn handle_text_message(
                 return Ok(());
             }
 
-            // Clear dialogue state
-            {
-                let mut storage = state.dialogue_storage.write().await;
-                storage.remove(&user_id);
-            }
-
-            // Show swap confirmation
-            show_swap_confirmation(&bot, chat_id, &wallet_id, &from_token, &to_token, &amount, &state).await?;
+            // Show swap confirmation; it replaces the dialogue state with the pending swap
+            show_swap_confirmation(&bot, chat_id, &wallet_id, &from_token, &to_token, &amount, None, user_id, &state).await?;
         }
         DialogueState::WaitingForAlertValue { token_symbol, chain, alert_kind } => {
             let value_str = text.trim();
@@ -130,10 +227,7 @@ pub async fn handle_text_message(
             };
 
             // Clear dialogue state
-            {
-                let mut storage = state.dialogue_storage.write().await;
-                storage.remove(&user_id);
-            }
+            state.dialogue_storage.remove(user_id).await?;
 
             // Show confirmation
             show_alert_confirmation(&bot, chat_id, &token_symbol, &chain, &alert_kind, value, user_id, &state).await?;
@@ -141,9 +235,78 @@ pub async fn handle_text_message(
         DialogueState::PendingAlertConfirmation { .. } => {
             // Waiting for button confirmation - ignore text
         }
-        DialogueState::PendingSendConfirmation { .. } => {
+        DialogueState::PendingSendConfirmation(_) => {
             // User already entered address, waiting for button confirmation - ignore text
         }
+        DialogueState::WaitingForPin { pending_send } => {
+            // Don't leave the PIN in the chat history
+            let _ = bot.delete_message(chat_id, msg.id).await;
+
+            match state.security_service.verify_pin(&pin_owner, text.trim()).await {
+                Ok(true) => {
+                    state.dialogue_storage.remove(user_id).await?;
+                    match *pending_send {
+                        PinProtectedSend::Single(pending) => {
+                            let status = bot.send_message(chat_id, "🔓 PIN accepted").await?;
+                            execute_send_with_params(&bot, chat_id, status.id, &pending, &state).await?;
+                        }
+                        PinProtectedSend::Batch { wallet_id, recipients } => {
+                            let uuid = uuid::Uuid::parse_str(&wallet_id)?;
+                            super::handlers::execute_batch_send(&bot, chat_id, uuid, recipients, &state).await?;
+                        }
+                    }
+                }
+                Ok(false) => {
+                    bot.send_message(chat_id, "❌ Incorrect PIN. Try again:")
+                        .reply_markup(pin_cancel_keyboard(pending_send.wallet_id()))
+                        .await?;
+                }
+                Err(crate::error::AppError::PinLocked { unlocks_at }) => {
+                    // No point waiting for a PIN that can't be checked
+                    state.dialogue_storage.remove(user_id).await?;
+                    bot.send_message(chat_id, crate::bot::utils::format_pin_locked(&unlocks_at)).await?;
+                }
+                Err(e) => {
+                    bot.send_message(chat_id, format!("❌ Could not verify PIN: {}", e.user_facing_message()))
+                        .reply_markup(pin_cancel_keyboard(pending_send.wallet_id()))
+                        .await?;
+                }
+            }
+        }
+        DialogueState::WaitingForSlippage { pending_swap } => {
+            let Some(slippage) = crate::services::swap_service::parse_slippage_pct(text) else {
+                bot.send_message(
+                    chat_id,
+                    format!(
+                        "❌ Enter a slippage between 0 and {}%, e.g. 0.8",
+                        crate::services::swap_service::MAX_SLIPPAGE_PCT
+                    )
+                ).await?;
+                return Ok(());
+            };
+
+            let pending = apply_swap_slippage(*pending_swap, slippage, user_id, &state).await?;
+            let (text, keyboard) = swap_confirmation_view(&pending, &state).await;
+
+            bot.send_message(chat_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        DialogueState::PendingSwapConfirmation(_) => {
+            // Swap quote shown, waiting for button confirmation - ignore text
+        }
+        DialogueState::PendingSwapTokenPick(_) => {
+            // Token picker shown, waiting for a button - ignore text
+        }
+        DialogueState::PendingRpcOverride { .. } => {
+            // Waiting for the privacy warning to be accepted - ignore text
+        }
+        DialogueState::PendingBatchSend { .. } => {
+            // Batch preview shown, waiting for proceed/cancel - ignore text
+        }
+        DialogueState::PendingSchedule { .. } => {
+            // Schedule check or simulation shown, waiting for a button - ignore text
+        }
         DialogueState::None => {
             // No active dialogue - ignore the message
         }
@@ -175,20 +338,47 @@ pub async fn handle_callback(
         None => return Ok(()),
     };
 
-    let user_id = q.from.id.0 as i64;
-    let user_id_str = user_id.to_string();
+    let is_group_chat = q.message.as_ref().map(|m| !m.chat().is_private()).unwrap_or(false);
+    let user_id = super::dialogue_key(chat_id, q.from.id.0 as i64);
+    // Group wallets belong to the chat rather than the member pressing the button
+    let user_id_str = if is_group_chat { chat_id.0.to_string() } else { user_id.to_string() };
+    // The PIN belongs to the member pressing the button, whichever chat the wallet is in
+    let pin_owner = q.from.id.0.to_string();
 
     // Parse callback data
     let parts: Vec<&str> = data.split(':').collect();
 
+    if is_group_chat {
+        if !state.config.group_wallet_mode {
+            return Ok(());
+        }
+        let role = super::group::member_role(&bot, &state, chat_id, &q.from).await?;
+        if !super::group::satisfies(role, super::group::required_callback_role(&parts)) {
+            bot.send_message(chat_id, format!("🔒 Only group admins can do that. Your role: {}.", role)).await?;
+            return Ok(());
+        }
+    }
+
+    // Remember the wallet so /start can offer to resume with it
+    if let ["wallet", action, wallet_id, ..] = parts.as_slice() {
+        if let Ok(wallet_id) = uuid::Uuid::parse_str(wallet_id) {
+            if let Err(e) = state.user_session_service.update_session(user_id, wallet_id, action).await {
+                tracing::warn!("Failed to update session for user {}: {}", user_id, e);
+            }
+        }
+    }
+
     match parts.as_slice() {
         // Main menu navigation
         ["menu", "main"] => {
-            show_main_menu(&bot, chat_id, message_id).await?;
+            show_main_menu(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
         ["menu", "wallets"] => {
             show_wallets(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
+        ["menu", "wallet_stats"] => {
+            show_wallet_statistics(&bot, chat_id, message_id, &user_id_str, &state).await?;
+        }
         ["menu", "create_wallet"] => {
             show_chain_selection(&bot, chat_id, message_id).await?;
         }
@@ -199,7 +389,7 @@ pub async fn handle_callback(
             show_portfolio(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
         ["menu", "prices"] => {
-            show_prices(&bot, chat_id, message_id, &state).await?;
+            show_prices(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
         ["menu", "addresses"] => {
             show_address_book_menu(&bot, chat_id, message_id).await?;
@@ -216,7 +406,16 @@ pub async fn handle_callback(
 
         // Chain selection for wallet creation
         ["chain", chain] => {
-            create_wallet(&bot, chat_id, message_id, chain, &user_id_str, &state).await?;
+            if is_group_chat {
+                // The button flow would show the mnemonic in the group; /createwallet sends it privately
+                bot.edit_message_text(
+                    chat_id,
+                    message_id,
+                    format!("🔒 In groups, create wallets with /createwallet {} so the mnemonic is sent to you privately.", chain)
+                ).await?;
+            } else {
+                create_wallet(&bot, chat_id, message_id, chain, &user_id_str, &state).await?;
+            }
         }
 
         // Wallet actions
@@ -229,6 +428,18 @@ pub async fn handle_callback(
         ["wallet", "history", wallet_id] => {
             show_wallet_history(&bot, chat_id, message_id, wallet_id, &user_id_str, &state).await?;
         }
+        ["wallet", "received", wallet_id] => {
+            show_wallet_received(&bot, chat_id, message_id, wallet_id, &state).await?;
+        }
+        ["tx", "view", tx_id] => {
+            show_transaction_detail(&bot, chat_id, message_id, tx_id, &user_id_str, &state).await?;
+        }
+        ["tx", "speedup", tx_id] => {
+            replace_transaction(&bot, chat_id, message_id, tx_id, false, &user_id_str, &state).await?;
+        }
+        ["tx", "cancel", tx_id] => {
+            replace_transaction(&bot, chat_id, message_id, tx_id, true, &user_id_str, &state).await?;
+        }
         ["wallet", "qr", wallet_id] => {
             show_wallet_qr(&bot, chat_id, wallet_id, &state).await?;
         }
@@ -241,6 +452,9 @@ pub async fn handle_callback(
         ["wallet", "receive", wallet_id] => {
             show_receive_address(&bot, chat_id, message_id, wallet_id, &state).await?;
         }
+        ["wallet", "lninvoice", wallet_id] => {
+            show_lightning_invoice(&bot, chat_id, wallet_id, &state).await?;
+        }
         ["wallet", "tokens", wallet_id] => {
             show_wallet_tokens(&bot, chat_id, message_id, wallet_id, 0, &state).await?;
         }
@@ -248,9 +462,40 @@ pub async fn handle_callback(
             let page: usize = page.parse().unwrap_or(0);
             show_wallet_tokens(&bot, chat_id, message_id, wallet_id, page, &state).await?;
         }
+        ["wallet", "nfts", wallet_id] => {
+            show_wallet_nfts(&bot, chat_id, message_id, wallet_id, 0, &user_id_str, &state).await?;
+        }
+        ["wallet", "nfts", wallet_id, page] => {
+            let page: usize = page.parse().unwrap_or(0);
+            show_wallet_nfts(&bot, chat_id, message_id, wallet_id, page, &user_id_str, &state).await?;
+        }
+        ["wallet", "approvals", wallet_id] => {
+            show_wallet_approvals(&bot, chat_id, message_id, wallet_id, 0, &user_id_str, &state).await?;
+        }
+        ["wallet", "approvals", wallet_id, page] => {
+            let page: usize = page.parse().unwrap_or(0);
+            show_wallet_approvals(&bot, chat_id, message_id, wallet_id, page, &user_id_str, &state).await?;
+        }
+        ["approval", "revoke", wallet_id, key] => {
+            revoke_wallet_approval(&bot, chat_id, message_id, wallet_id, key, &user_id_str, &state).await?;
+        }
         ["wallet", "explorer", wallet_id] => {
             show_wallet_explorer_link(&bot, chat_id, message_id, wallet_id, &state).await?;
         }
+        ["wallet", "addchain", wallet_id] => {
+            show_add_chain_options(&bot, chat_id, message_id, wallet_id, &user_id_str, &state).await?;
+        }
+        ["wallet", "addchain", wallet_id, chain] => {
+            add_wallet_chain(&bot, chat_id, message_id, wallet_id, chain, &user_id_str, &state).await?;
+        }
+
+        // DeFi auto-compounding
+        ["defi", "auto", wallet_id, protocol] => {
+            start_auto_compound(&bot, chat_id, message_id, wallet_id, protocol, &user_id_str, &state).await?;
+        }
+        ["defi", "stop", plan_id] => {
+            stop_auto_compound(&bot, chat_id, message_id, plan_id, &user_id_str, &state).await?;
+        }
 
         // Send flow
         ["send", "native", wallet_id] => {
@@ -262,36 +507,45 @@ pub async fn handle_callback(
         ["send", "custom", wallet_id] => {
             show_send_custom_prompt(&bot, chat_id, message_id, wallet_id, user_id, &state).await?;
         }
+        ["send", "usd", wallet_id] => {
+            show_send_usd_prompt(&bot, chat_id, message_id, wallet_id, user_id, &state).await?;
+        }
         ["send", "amount", wallet_id, percent] => {
             // User selected a percentage - ask for recipient address
-            show_send_ask_recipient(&bot, chat_id, message_id, wallet_id, percent, user_id, &state).await?;
+            show_send_ask_recipient(&bot, chat_id, message_id, wallet_id, percent, user_id, &user_id_str, &state).await?;
         }
         ["send", "confirm"] => {
             // Read transaction details from dialogue state
-            let dialogue_state = {
-                let storage = state.dialogue_storage.read().await;
-                storage.get(&user_id).cloned()
-            };
+            let dialogue_state = state.dialogue_storage.get(user_id).await?;
 
-            if let Some(DialogueState::PendingSendConfirmation { wallet_id, recipient, amount, symbol: _ }) = dialogue_state {
-                // Clear the state
-                {
-                    let mut storage = state.dialogue_storage.write().await;
-                    storage.remove(&user_id);
-                }
-                execute_send_with_params(&bot, chat_id, message_id, &wallet_id, &recipient, &amount, &state).await?;
+            if let DialogueState::PendingSendConfirmation(pending) = dialogue_state {
+                confirm_pending_send(&bot, chat_id, message_id, PinProtectedSend::Single(pending), user_id, &pin_owner, &state).await?;
             } else {
                 bot.edit_message_text(chat_id, message_id, "❌ Transaction expired. Please start again.")
                     .reply_markup(keyboards::back_to_menu())
                     .await?;
             }
         }
+        ["dust", "merge", wallet_id] => {
+            merge_dust_wallet(&bot, chat_id, message_id, wallet_id, user_id, &user_id_str, &pin_owner, &state).await?;
+        }
+        ["dust", "keep", _wallet_id] => {
+            bot.edit_message_text(chat_id, message_id, "👍 Keeping the dust in this wallet.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+        ["send", "simulate"] => {
+            simulate_pending_send(&bot, chat_id, message_id, user_id, &state).await?;
+        }
         ["send", "confirm", wallet_id] => {
             execute_send(&bot, chat_id, message_id, wallet_id, user_id, &state).await?;
         }
         ["send", "cancel", wallet_id] => {
             cancel_send(&bot, chat_id, message_id, wallet_id, user_id, &state).await?;
         }
+        ["addr_book_pick", owner_id, entry_id] => {
+            pick_send_address(&bot, chat_id, owner_id, entry_id, user_id, &user_id_str, &state).await?;
+        }
 
         // Swap flow
         ["swap", "preset1", wallet_id] => {
@@ -307,10 +561,82 @@ pub async fn handle_callback(
             show_swap_amount_custom_prompt(&bot, chat_id, message_id, wallet_id, from_token, to_token, user_id, &state).await?;
         }
         ["swap", "amount", wallet_id, from_token, to_token, percent] => {
-            show_swap_confirm(&bot, chat_id, message_id, wallet_id, from_token, to_token, percent, &state).await?;
+            show_swap_confirm(&bot, chat_id, message_id, wallet_id, from_token, to_token, percent, user_id, &state).await?;
+        }
+        ["swap", "confirm"] => {
+            if let Some(pending) = pending_swap(user_id, &state).await? {
+                state.dialogue_storage.remove(user_id).await?;
+                execute_swap(&bot, chat_id, message_id, pending, user_id, &state).await?;
+            } else {
+                show_swap_expired(&bot, chat_id, message_id).await?;
+            }
+        }
+        ["swap", "via", dex] => {
+            if let Some(mut pending) = pending_swap(user_id, &state).await? {
+                // Remember the chosen DEX until the user confirms
+                pending.dex = Some(dex.to_string());
+                state.dialogue_storage.set(user_id, DialogueState::PendingSwapConfirmation(pending.clone())).await?;
+                edit_swap_confirmation(&bot, chat_id, message_id, &pending, &state).await?;
+            } else {
+                show_swap_expired(&bot, chat_id, message_id).await?;
+            }
+        }
+        ["swap", "review"] => {
+            if let Some(pending) = pending_swap(user_id, &state).await? {
+                state.dialogue_storage.set(user_id, DialogueState::PendingSwapConfirmation(pending.clone())).await?;
+                edit_swap_confirmation(&bot, chat_id, message_id, &pending, &state).await?;
+            } else {
+                show_swap_expired(&bot, chat_id, message_id).await?;
+            }
+        }
+        ["swap", "pick", index] => {
+            pick_swap_token(&bot, chat_id, message_id, index, user_id, &state).await?;
+        }
+        ["swap", "pickcancel"] => {
+            state.dialogue_storage.remove(user_id).await?;
+            bot.edit_message_text(chat_id, message_id, "❌ Swap cancelled.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+        ["swap", "slippage"] => {
+            show_slippage_presets(&bot, chat_id, message_id, user_id, &state).await?;
+        }
+        ["swap", "slip", "custom"] => {
+            show_slippage_custom_prompt(&bot, chat_id, message_id, user_id, &state).await?;
+        }
+        ["swap", "slip", value] => {
+            let slippage = crate::services::swap_service::parse_slippage_pct(value);
+            match (slippage, pending_swap(user_id, &state).await?) {
+                (Some(slippage), Some(pending)) => {
+                    let pending = apply_swap_slippage(pending, slippage, user_id, &state).await?;
+                    edit_swap_confirmation(&bot, chat_id, message_id, &pending, &state).await?;
+                }
+                _ => show_swap_expired(&bot, chat_id, message_id).await?,
+            }
+        }
+        ["confirm", "wrpc", wallet_id] => {
+            confirm_rpc_override(&bot, chat_id, message_id, wallet_id, user_id, &state).await?;
         }
-        ["swap", "confirm", wallet_id, from_token, to_token, amount] => {
-            execute_swap(&bot, chat_id, message_id, wallet_id, from_token, to_token, amount, &state).await?;
+        ["confirm", "batch", wallet_id] => {
+            confirm_batch_send(&bot, chat_id, message_id, wallet_id, user_id, &pin_owner, &state).await?;
+        }
+        ["batch", "cancel"] => {
+            state.dialogue_storage.remove(user_id).await?;
+            bot.edit_message_text(chat_id, message_id, "❌ Batch transfer cancelled.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+        ["confirm", "schedule"] => {
+            confirm_schedule(&bot, chat_id, message_id, user_id, &state).await?;
+        }
+        ["schedule", "simulate"] => {
+            simulate_schedule(&bot, chat_id, message_id, user_id, &state).await?;
+        }
+        ["schedule", "cancel"] => {
+            state.dialogue_storage.remove(user_id).await?;
+            bot.edit_message_text(chat_id, message_id, "❌ Schedule cancelled.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
         }
         ["swap", "cancel", wallet_id] => {
             cancel_swap(&bot, chat_id, message_id, wallet_id, &state).await?;
@@ -348,6 +674,12 @@ pub async fn handle_callback(
         ["alert", "list"] => {
             show_alerts(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
+        ["alert", "pause", alert_id] => {
+            toggle_alert_pause(&bot, chat_id, message_id, alert_id, true, &user_id_str, &state).await?;
+        }
+        ["alert", "resume", alert_id] => {
+            toggle_alert_pause(&bot, chat_id, message_id, alert_id, false, &user_id_str, &state).await?;
+        }
         ["alert", "new"] => {
             show_alert_token_selection(&bot, chat_id, message_id).await?;
         }
@@ -365,10 +697,7 @@ pub async fn handle_callback(
         }
         ["alert", "cancel"] => {
             // Clear dialogue state and go back to alerts menu
-            {
-                let mut storage = state.dialogue_storage.write().await;
-                storage.remove(&user_id);
-            }
+            state.dialogue_storage.remove(user_id).await?;
             show_alerts_menu(&bot, chat_id, message_id).await?;
         }
 
@@ -376,8 +705,33 @@ pub async fn handle_callback(
         ["refresh", "portfolio"] => {
             show_portfolio(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
+        ["portfolio", "bychain"] => {
+            show_chain_breakdown(&bot, chat_id, message_id, &user_id_str, &state).await?;
+        }
+        ["portfolio", "bytoken"] => {
+            show_token_aggregation(&bot, chat_id, message_id, &user_id_str, &state).await?;
+        }
+        ["portfolio", "share"] => {
+            share_portfolio_card(&bot, chat_id, &user_id_str, &state).await?;
+        }
         ["refresh", "prices"] => {
-            show_prices(&bot, chat_id, message_id, &state).await?;
+            show_prices(&bot, chat_id, message_id, &user_id_str, &state).await?;
+        }
+        ["prices", "chart", symbol, period] => {
+            let days = match *period {
+                "30d" => 30,
+                _ => 7,
+            };
+            show_price_chart(&bot, chat_id, message_id, symbol, days, &user_id_str, &state).await?;
+        }
+
+        ["swaphist", page] => {
+            let page: usize = page.parse().unwrap_or(0);
+            show_swap_history_page(&bot, chat_id, message_id, &user_id_str, None, page, &state).await?;
+        }
+        ["swaphist", page, wallet_id] => {
+            let page: usize = page.parse().unwrap_or(0);
+            show_swap_history_page(&bot, chat_id, message_id, &user_id_str, Some(wallet_id), page, &state).await?;
         }
 
         // No-op for non-interactive buttons (e.g. page indicators)
@@ -385,7 +739,7 @@ pub async fn handle_callback(
 
         // Cancel action
         ["cancel"] => {
-            show_main_menu(&bot, chat_id, message_id).await?;
+            show_main_menu(&bot, chat_id, message_id, &user_id_str, &state).await?;
         }
 
         _ => {
@@ -396,8 +750,18 @@ pub async fn handle_callback(
     Ok(())
 }
 
-async fn show_main_menu(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> HandlerResult {
-    let text = "🏠 Main Menu\n\nSelect an option:";
+async fn show_main_menu(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let text = if state.security_service.is_testnet_mode(user_id).await.unwrap_or(false) {
+        "⚠️ TESTNET MODE - new wallets use test networks\n\n🏠 Main Menu\n\nSelect an option:"
+    } else {
+        "🏠 Main Menu\n\nSelect an option:"
+    };
 
     bot.edit_message_text(chat_id, message_id, text)
         .reply_markup(keyboards::main_menu())
@@ -454,6 +818,9 @@ async fn show_wallets(
                 teloxide::types::InlineKeyboardButton::callback("➕ Create New", "menu:create_wallet"),
                 teloxide::types::InlineKeyboardButton::callback("📥 Import", "menu:import_wallet"),
             ]);
+            buttons.push(vec![
+                teloxide::types::InlineKeyboardButton::callback("📊 Statistics", "menu:wallet_stats"),
+            ]);
             buttons.push(vec![
                 teloxide::types::InlineKeyboardButton::callback("« Back to Menu", "menu:main"),
             ]);
@@ -468,7 +835,7 @@ async fn show_wallets(
         }
         Err(e) => {
             tracing::error!("Failed to get wallets: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Error loading wallets: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Error loading wallets: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -477,6 +844,53 @@ async fn show_wallets(
     Ok(())
 }
 
+async fn show_wallet_statistics(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let back = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("« Back to Wallets", "menu:wallets"),
+        ],
+    ]);
+
+    match state.wallet_service.get_wallet_statistics(user_id).await {
+        Ok(stats) => {
+            let mut by_chain: Vec<(&String, &usize)> = stats.by_chain.iter().collect();
+            by_chain.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+            let mut text = format!("📊 Wallet Statistics\n\nTotal wallets: {}\n\n", stats.total_wallets);
+            for (chain, count) in by_chain {
+                let chain_display = chain.parse::<Chain>()
+                    .map(|c| c.display_name().to_string())
+                    .unwrap_or_else(|_| chain.clone());
+                text.push_str(&format!("{} {}: {}\n", chain_emoji(chain), chain_display, count));
+            }
+            if let (Some(oldest), Some(newest)) = (stats.oldest_wallet_date, stats.newest_wallet_date) {
+                text.push_str(&format!(
+                    "\n📅 Oldest: {}\n🆕 Newest: {}",
+                    oldest.format("%Y-%m-%d"),
+                    newest.format("%Y-%m-%d")
+                ));
+            }
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(back)
+                .await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Error loading statistics: {}", e.user_facing_message()))
+                .reply_markup(back)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
 async fn show_wallet_actions(
     bot: &Bot,
     chat_id: ChatId,
@@ -499,19 +913,57 @@ async fn show_wallet_actions(
         Ok(wallet) => {
             let chain_emoji = chain_emoji(&wallet.chain);
 
+            // Show the primary ENS name under the title when one is set
+            let ens_subtitle = if wallet.chain == Chain::Eth.to_string() {
+                state.address_book_service.reverse_resolve_ens(&wallet.address).await
+                    .map(|name| format!("🏷 {}\n", super::handlers::escape_markdown(&name)))
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            };
+
+            // Polygon wallets list transfers still in flight on the PoS bridge
+            let bridge_pending = if wallet.chain == Chain::Polygon.to_string() {
+                match state.transaction_service.get_pending_bridge_transactions(uuid).await {
+                    Ok(pending) => pending
+                        .iter()
+                        .map(|tx| {
+                            let eta = tx.eta_secs
+                                .map(|secs| crate::services::gas_estimation_service::format_confirmation_time(secs as f64))
+                                .unwrap_or_default();
+                            format!(
+                                "🌉 Bridge Pending: {}\n",
+                                super::handlers::escape_markdown(
+                                    &format!("{} {} → {} ({})", tx.amount, tx.token, tx.direction.destination(), eta)
+                                )
+                            )
+                        })
+                        .collect::<String>(),
+                    Err(e) => {
+                        tracing::warn!("Bridge status unavailable for wallet {}: {}", uuid, e);
+                        String::new()
+                    }
+                }
+            } else {
+                String::new()
+            };
+
             let text = format!(
-                "{} {} Wallet\n\n\
+                "{} {} Wallet\n{}\n\
 📬 Address:\n`{}`\n\n\
+{}\
 Tap address to copy\\. What would you like to do?",
                 chain_emoji,
                 wallet.chain,
-                wallet.address
+                ens_subtitle,
+                wallet.address,
+                if bridge_pending.is_empty() { String::new() } else { format!("{}\n", bridge_pending) }
             );
 
             // Try to edit the message, if it fails (e.g., it's a photo), delete and send new
             let edit_result = bot.edit_message_text(chat_id, message_id, &text)
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                .reply_markup(keyboards::wallet_actions(wallet_id))
+                .reply_markup(keyboards::wallet_actions(wallet_id, &wallet.chain))
                 .await;
 
             if edit_result.is_err() {
@@ -519,13 +971,13 @@ Tap address to copy\\. What would you like to do?",
                 let _ = bot.delete_message(chat_id, message_id).await;
                 bot.send_message(chat_id, text)
                     .parse_mode(teloxide::types::ParseMode::MarkdownV2)
-                    .reply_markup(keyboards::wallet_actions(wallet_id))
+                    .reply_markup(keyboards::wallet_actions(wallet_id, &wallet.chain))
                     .await?;
             }
         }
         Err(e) => {
             let _ = bot.delete_message(chat_id, message_id).await;
-            bot.send_message(chat_id, format!("❌ Failed to load wallet: {}", e))
+            bot.send_message(chat_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -546,7 +998,9 @@ async fn create_wallet(
     bot.edit_message_text(chat_id, message_id, "⏳ Creating wallet...")
         .await?;
 
-    match state.wallet_service.generate_wallet(user_id.to_string(), chain.to_string(), Some(0)).await {
+    let use_testnet = state.security_service.is_testnet_mode(user_id).await.unwrap_or(false);
+
+    match state.wallet_service.generate_wallet(user_id.to_string(), chain.to_string(), Some(0), use_testnet).await {
         Ok(response) => {
             let text = format!(
                 "✅ Wallet Created Successfully!\n\n\
@@ -566,9 +1020,15 @@ async fn create_wallet(
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
+        Err(crate::error::AppError::WalletAlreadyExists { existing_id }) => {
+            let text = crate::bot::utils::format_existing_wallet(chain, &existing_id);
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::existing_wallet(&existing_id.to_string()))
+                .await?;
+        }
         Err(e) => {
             tracing::error!("Failed to create wallet: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to create wallet: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to create wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -629,7 +1089,8 @@ async fn show_wallet_balance(
             } else {
                 balances.address.clone()
             };
-            let explorer_url = state.config.get_address_explorer_url(&balances.chain, &balances.address);
+            let testnet = wallet_is_testnet(state, uuid).await;
+            let explorer_url = state.config.get_address_explorer_url(&balances.chain, testnet, &balances.address);
             text.push_str(&format!("\n📬 {}\n🔍 {}", addr_short, explorer_url));
 
             let mut buttons = vec![
@@ -648,7 +1109,7 @@ async fn show_wallet_balance(
         }
         Err(e) => {
             tracing::error!("Failed to get balance: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -678,7 +1139,14 @@ async fn show_wallet_history(
     bot.edit_message_text(chat_id, message_id, "⏳ Fetching transaction history...")
         .await?;
 
-    match state.transaction_service.get_wallet_transactions(uuid, Some(10), None).await {
+    // Served from `state.recent_transactions` when the wallet was viewed in the last 30 seconds
+    let history = state.transaction_service.get_wallet_transactions(uuid, Some(10), None).await;
+    tracing::debug!(
+        "Recent transaction cache hit rate: {:.1}%",
+        state.recent_transactions.cache_hit_rate() * 100.0
+    );
+
+    match history {
         Ok(transactions) if transactions.is_empty() => {
             let text = "📭 No Transaction History\n\n\
 This wallet has no transactions yet.\n\n\
@@ -686,33 +1154,64 @@ Transactions will appear here after you:\n\
 • Send or receive crypto\n\
 • Perform swaps";
 
+            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+                keyboards::history_tabs(wallet_id),
+                vec![
+                    teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+                ],
+            ]);
+
             bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboards::wallet_actions(wallet_id))
+                .reply_markup(keyboard)
                 .await?;
         }
         Ok(transactions) => {
             let mut text = String::from("📋 Transaction History\n\n");
+            let testnet = wallet_is_testnet(state, uuid).await;
 
             for tx in transactions.iter().take(5) {
                 let symbol = tx.token_symbol.as_deref().unwrap_or(&tx.chain);
                 let tx_hash_short = if tx.tx_hash.len() > 16 { &tx.tx_hash[..16] } else { &tx.tx_hash };
                 let to_addr_short = if tx.to_address.len() > 10 { &tx.to_address[..10] } else { &tx.to_address };
-                let explorer_url = state.config.get_tx_explorer_url(&tx.chain, &tx.tx_hash);
+                let explorer_url = state.config.get_tx_explorer_url(&tx.chain, testnet, &tx.tx_hash);
+                let l1_refund = tx.l1_refund
+                    .filter(|refund| !refund.is_zero())
+                    .map(|refund| {
+                        let native = tx.chain.parse::<Chain>().map(|c| c.native_symbol()).unwrap_or("ETH");
+                        format!("   ⚡ ARB L1 refund: {} {}\n", refund.normalize(), native)
+                    })
+                    .unwrap_or_default();
                 text.push_str(&format!(
-                    "🔸 {}...\n   {} {} → {}...\n   🔍 {}\n\n",
+                    "🔸 {}...\n   {} {} → {}...\n{}   🔍 {}\n\n",
                     tx_hash_short,
                     tx.amount,
                     symbol,
                     to_addr_short,
+                    l1_refund,
                     explorer_url
                 ));
             }
 
-            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-                vec![
-                    teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
-                ],
+            // Pending transactions get a detail button so they can be sped up or cancelled
+            let mut rows: Vec<Vec<teloxide::types::InlineKeyboardButton>> = transactions
+                .iter()
+                .take(5)
+                .filter(|tx| tx.status == TxStatus::Pending.as_str())
+                .map(|tx| {
+                    let hash_short = if tx.tx_hash.len() > 12 { &tx.tx_hash[..12] } else { &tx.tx_hash };
+                    vec![
+                        teloxide::types::InlineKeyboardButton::callback(
+                            format!("⏳ {}...", hash_short),
+                            format!("tx:view:{}", tx.id)
+                        ),
+                    ]
+                })
+                .collect();
+            rows.push(keyboards::history_tabs(wallet_id));
+            rows.push(vec![
+                teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
             ]);
+            let keyboard = teloxide::types::InlineKeyboardMarkup::new(rows);
 
             bot.edit_message_text(chat_id, message_id, text)
                 .reply_markup(keyboard)
@@ -720,7 +1219,7 @@ Transactions will appear here after you:\n\
         }
         Err(e) => {
             tracing::error!("Failed to get history: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get history: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get history: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -729,37 +1228,248 @@ Transactions will appear here after you:\n\
     Ok(())
 }
 
-async fn show_wallet_qr(
+async fn show_wallet_received(
     bot: &Bot,
     chat_id: ChatId,
+    message_id: MessageId,
     wallet_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
     let uuid = match uuid::Uuid::parse_str(wallet_id) {
         Ok(id) => id,
         Err(_) => {
-            bot.send_message(chat_id, "❌ Invalid wallet ID").await?;
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
             return Ok(());
         }
     };
 
-    match state.wallet_service.get_wallet(uuid).await {
-        Ok(wallet) => {
-            let chain_emoji = chain_emoji(&wallet.chain);
-
-            // Generate QR code
-            let qr = qrcode::QrCode::new(&wallet.address)?;
-            let image = qr.render::<image::Luma<u8>>().build();
+    bot.edit_message_text(chat_id, message_id, "⏳ Fetching received transactions...")
+        .await?;
 
-            let mut bytes: Vec<u8> = Vec::new();
-            image::DynamicImage::ImageLuma8(image)
-                .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        keyboards::history_tabs(wallet_id),
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+        ],
+    ]);
 
-            let input_file = teloxide::types::InputFile::memory(bytes).file_name("qr.png");
+    match state.transaction_service.get_inbound_transactions(uuid, Some(5), None).await {
+        Ok(transactions) if transactions.is_empty() => {
+            bot.edit_message_text(chat_id, message_id, "📭 No received transactions recorded for this wallet yet.")
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Ok(transactions) => {
+            let mut text = String::from("📥 Received Transactions\n\n");
+            let testnet = wallet_is_testnet(state, uuid).await;
 
-            let caption = format!(
-                "📥 Receive {}\n\n\
-{} Address:\n`{}`\n\n\
+            for tx in &transactions {
+                let symbol = tx.token_symbol.as_deref().unwrap_or(&tx.chain);
+                let from_short = if tx.from_address.len() > 10 {
+                    format!("{}...{}", &tx.from_address[..6], &tx.from_address[tx.from_address.len() - 4..])
+                } else {
+                    tx.from_address.clone()
+                };
+                let explorer_url = state.config.get_tx_explorer_url(&tx.chain, testnet, &tx.tx_hash);
+                text.push_str(&format!(
+                    "📨 Received {} {} from {}\n   🔍 {}\n\n",
+                    tx.amount,
+                    symbol,
+                    from_short,
+                    explorer_url
+                ));
+            }
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get received transactions: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get history: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_transaction_detail(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    tx_id: &str,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let tx = match find_user_transaction(tx_id, user_id, state).await {
+        Some(tx) => tx,
+        None => {
+            bot.edit_message_text(chat_id, message_id, "❌ Transaction not found")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let symbol = tx.token_symbol.as_deref().unwrap_or(&tx.chain);
+    let testnet = wallet_is_testnet(state, tx.wallet_id).await;
+    let explorer_url = state.config.get_tx_explorer_url(&tx.chain, testnet, &tx.tx_hash);
+    let replaces = tx.replaces_tx_id
+        .map(|id| format!("\n♻️ Replaces: {}", id))
+        .unwrap_or_default();
+
+    let text = format!(
+        "🧾 Transaction Details\n\n\
+🔗 Hash: {}\n\
+📊 Status: {}\n\
+💎 Amount: {} {}\n\
+📬 To: {}\n\
+📅 {}{}\n\n\
+🔍 {}",
+        tx.tx_hash,
+        tx.status,
+        tx.amount,
+        symbol,
+        tx.to_address,
+        tx.created_at.format("%Y-%m-%d %H:%M"),
+        replaces,
+        explorer_url
+    );
+
+    let mut rows = Vec::new();
+    if tx.status == TxStatus::Pending.as_str() {
+        rows.push(vec![
+            teloxide::types::InlineKeyboardButton::callback("⚡ Speed Up", format!("tx:speedup:{}", tx.id)),
+            teloxide::types::InlineKeyboardButton::callback("🚫 Cancel TX", format!("tx:cancel:{}", tx.id)),
+        ]);
+    }
+    rows.push(vec![
+        teloxide::types::InlineKeyboardButton::callback("« Back to History", format!("wallet:history:{}", tx.wallet_id)),
+    ]);
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(teloxide::types::InlineKeyboardMarkup::new(rows))
+        .await?;
+
+    Ok(())
+}
+
+async fn replace_transaction(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    tx_id: &str,
+    cancel: bool,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let tx = match find_user_transaction(tx_id, user_id, state).await {
+        Some(tx) => tx,
+        None => {
+            bot.edit_message_text(chat_id, message_id, "❌ Transaction not found")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let progress = if cancel { "⏳ Sending cancellation..." } else { "⏳ Rebroadcasting with a higher fee..." };
+    bot.edit_message_text(chat_id, message_id, progress)
+        .await?;
+
+    let result = if cancel {
+        state.transaction_service.cancel_transaction(tx.id).await
+    } else {
+        state.transaction_service.speed_up_transaction(tx.id, None).await
+    };
+
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("« Back to History", format!("wallet:history:{}", tx.wallet_id)),
+        ],
+    ]);
+
+    match result {
+        Ok(response) => {
+            let title = if cancel { "🚫 Cancellation Sent!" } else { "⚡ Transaction Sped Up!" };
+            let testnet = wallet_is_testnet(state, tx.wallet_id).await;
+            let explorer_url = state.config.get_tx_explorer_url(&tx.chain, testnet, &response.tx_hash);
+            let text = format!(
+                "{}\n\n\
+🔗 New TX Hash:\n{}\n\n\
+🔍 View on Explorer:\n{}",
+                title,
+                response.tx_hash,
+                explorer_url
+            );
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Transaction replacement failed: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Replacement Failed\n\n{}", e.user_facing_message()))
+                .reply_markup(keyboard)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Look up a transaction by ID, only returning it if one of the user's wallets sent it
+async fn find_user_transaction(
+    tx_id: &str,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> Option<crate::db::entity::transaction::Model> {
+    let uuid = uuid::Uuid::parse_str(tx_id).ok()?;
+    let tx = state.transaction_service.get_transaction(uuid).await.ok()?;
+    let wallet = state.wallet_service.get_wallet(tx.wallet_id).await.ok()?;
+
+    if wallet.user_id == user_id {
+        Some(tx)
+    } else {
+        None
+    }
+}
+
+async fn show_wallet_qr(
+    bot: &Bot,
+    chat_id: ChatId,
+    wallet_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(chat_id, "❌ Invalid wallet ID").await?;
+            return Ok(());
+        }
+    };
+
+    match state.wallet_service.get_wallet(uuid).await {
+        Ok(wallet) => {
+            let chain_emoji = chain_emoji(&wallet.chain);
+
+            // Generate QR code
+            let qr = qrcode::QrCode::new(&wallet.address)?;
+            let image = qr.render::<image::Luma<u8>>().build();
+
+            let mut bytes: Vec<u8> = Vec::new();
+            image::DynamicImage::ImageLuma8(image)
+                .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+
+            let input_file = teloxide::types::InputFile::memory(bytes).file_name("qr.png");
+
+            let caption = format!(
+                "📥 Receive {}\n\n\
+{} Address:\n`{}`\n\n\
 Scan QR or tap address to copy\\.",
                 wallet.chain,
                 chain_emoji,
@@ -780,7 +1490,7 @@ Scan QR or tap address to copy\\.",
         }
         Err(e) => {
             tracing::error!("Failed to get wallet: {:?}", e);
-            bot.send_message(chat_id, format!("❌ Failed to get wallet: {}", e)).await?;
+            bot.send_message(chat_id, format!("❌ Failed to get wallet: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -847,10 +1557,12 @@ async fn show_wallet_tokens(
             );
 
             for token in page_tokens {
-                text.push_str(&format!("🪙 {} — {}\n", token.symbol, token.balance));
-                if let Some(ref name) = token.logo_url {
-                    // Just show name, not the URL
-                    let _ = name;
+                // Telegram can't inline images here; listed tokens (which carry a logo) get a picture marker
+                let icon = if token.logo_url.is_some() { "🖼️" } else { "🪙" };
+                if token.name.is_empty() || token.name.eq_ignore_ascii_case(&token.symbol) {
+                    text.push_str(&format!("{} {} — {}\n", icon, token.symbol, token.balance));
+                } else {
+                    text.push_str(&format!("{} {} ({}) — {}\n", icon, token.symbol, token.name, token.balance));
                 }
             }
 
@@ -862,7 +1574,7 @@ async fn show_wallet_tokens(
         }
         Err(e) => {
             tracing::error!("Failed to get tokens: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to discover tokens: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to discover tokens: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -871,64 +1583,77 @@ async fn show_wallet_tokens(
     Ok(())
 }
 
-async fn show_wallet_explorer_link(
+async fn show_wallet_nfts(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
     wallet_id: &str,
+    page: usize,
+    user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-            return Ok(());
-        }
+    let back = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("« Back", format!("wallet:select:{}", wallet_id)),
+        ],
+    ]);
+
+    let Some(wallet) = find_user_wallet(wallet_id, user_id, state).await else {
+        bot.edit_message_text(chat_id, message_id, "❌ Wallet not found")
+            .reply_markup(keyboards::back_to_menu())
+            .await?;
+        return Ok(());
     };
 
-    match state.wallet_service.get_wallet(uuid).await {
-        Ok(wallet) => {
-            let emoji = chain_emoji(&wallet.chain);
-            let explorer_url = state.config.get_address_explorer_url(&wallet.chain, &wallet.address);
-            let chain_name = wallet.chain.parse::<Chain>()
-                .map(|c| c.display_name())
-                .unwrap_or("Unknown");
+    if !state.balance_service.nfts_enabled() {
+        bot.edit_message_text(chat_id, message_id, "🖼️ Configure Alchemy API key to view NFTs")
+            .reply_markup(back)
+            .await?;
+        return Ok(());
+    }
 
-            let text = format!(
-                "🔍 {} {} Explorer\n\n\
-                📬 Address:\n{}\n\n\
-                🔗 View on Explorer:\n{}",
-                emoji, chain_name,
-                wallet.address,
-                explorer_url
-            );
+    let chain = wallet.chain.parse::<Chain>().ok();
+    if !chain.as_ref().is_some_and(crate::services::NftService::is_supported) {
+        bot.edit_message_text(chat_id, message_id, format!("🖼️ NFTs are not available for {} wallets", wallet.chain))
+            .reply_markup(back)
+            .await?;
+        return Ok(());
+    }
 
-            let keyboard = if let Ok(parsed_url) = reqwest::Url::parse(&explorer_url) {
-                teloxide::types::InlineKeyboardMarkup::new(vec![
-                    vec![
-                        teloxide::types::InlineKeyboardButton::url("🌐 Open Explorer", parsed_url),
-                    ],
-                    vec![
-                        teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
-                    ],
-                ])
-            } else {
-                teloxide::types::InlineKeyboardMarkup::new(vec![
-                    vec![
-                        teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
-                    ],
-                ])
-            };
+    bot.edit_message_text(chat_id, message_id, "⏳ Loading NFTs...")
+        .await?;
+
+    match state.balance_service.get_wallet_nfts(wallet.id).await {
+        Ok(nfts) if nfts.is_empty() => {
+            bot.edit_message_text(chat_id, message_id, "🖼️ No NFTs found in this wallet.")
+                .reply_markup(keyboards::nft_list(wallet_id, 0, 1))
+                .await?;
+        }
+        Ok(nfts) => {
+            let nfts_per_page = 8;
+            let total_pages = nfts.len().div_ceil(nfts_per_page);
+            let page = page.min(total_pages.saturating_sub(1));
+            let start = page * nfts_per_page;
+            let page_nfts = &nfts[start..(start + nfts_per_page).min(nfts.len())];
+
+            let mut text = format!("🖼️ NFTs ({})\n\n", nfts.len());
+            for nft in page_nfts {
+                let collection = nft.collection_name.as_deref().unwrap_or("Unknown collection");
+                let name = nft.name.clone().unwrap_or_else(|| format!("#{}", nft.token_id));
+                let floor = nft.floor_price_eth
+                    .map(|p| format!("Floor: {} ETH", p))
+                    .unwrap_or_else(|| "Floor: n/a".to_string());
+                text.push_str(&format!("🖼️ {} — {}\n   {}\n", collection, name, floor));
+            }
 
             bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboard)
+                .reply_markup(keyboards::nft_list(wallet_id, page, total_pages))
                 .await?;
         }
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e))
-                .reply_markup(keyboards::back_to_menu())
+            tracing::error!("Failed to get NFTs: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load NFTs: {}", e.user_facing_message()))
+                .reply_markup(back)
                 .await?;
         }
     }
@@ -936,121 +1661,154 @@ async fn show_wallet_explorer_link(
     Ok(())
 }
 
-async fn show_portfolio(
+/// Look up a wallet by its callback ID string, only if it belongs to the user
+async fn find_user_wallet(
+    wallet_id: &str,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> Option<crate::db::entity::wallet::Model> {
+    let uuid = uuid::Uuid::parse_str(wallet_id).ok()?;
+    state.wallet_service
+        .get_wallet(uuid)
+        .await
+        .ok()
+        .filter(|w| w.user_id == user_id)
+}
+
+async fn show_wallet_approvals(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
+    wallet_id: &str,
+    page: usize,
     user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    bot.edit_message_text(chat_id, message_id, "⏳ Fetching portfolio data...")
-        .await?;
-
-    match state.portfolio_service.get_portfolio(user_id).await {
-        Ok(portfolio) => {
-            let mut text = format!(
-                "💼 Your Portfolio\n📊 {} wallets across {} chains\n\n",
-                portfolio.wallet_count,
-                portfolio.chains.len()
-            );
-
-            for holding in &portfolio.holdings {
-                let change_str = holding.price_change_24h
-                    .map(|c| {
-                        let arrow = if c >= 0.0 { "📈" } else { "📉" };
-                        format!(" {} {:.1}%", arrow, c.abs())
-                    })
-                    .unwrap_or_default();
-
-                text.push_str(&format!(
-                    "{} {} {:.6} (${:.2}){}\n",
-                    chain_emoji(&holding.symbol),
-                    holding.symbol,
-                    holding.total_balance,
-                    holding.usd_value,
-                    change_str,
-                ));
-
-                // Show per-wallet breakdown if multiple wallets hold this token
-                if holding.wallets.len() > 1 {
-                    for wh in &holding.wallets {
-                        let short_addr = if wh.address.len() >= 10 {
-                            format!("{}...{}", &wh.address[..6], &wh.address[wh.address.len()-4..])
-                        } else {
-                            wh.address.clone()
-                        };
-                        text.push_str(&format!("   └ {} {}\n", short_addr, wh.balance));
-                    }
-                }
-            }
-
-            text.push_str(&format!("\n💰 Total Value: ${:.2}", portfolio.total_usd_value));
-
-            bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboards::refresh_button("portfolio"))
+    let wallet = match find_user_wallet(wallet_id, user_id, state).await {
+        Some(w) => w,
+        None => {
+            bot.edit_message_text(chat_id, message_id, "❌ Wallet not found")
+                .reply_markup(keyboards::back_to_menu())
                 .await?;
+            return Ok(());
         }
+    };
+
+    bot.edit_message_text(chat_id, message_id, "⏳ Scanning token approvals...")
+        .await?;
+
+    let approvals = match state.token_approval_service.list_approvals(&wallet.address, &wallet.chain, wallet.is_testnet).await {
+        Ok(a) => a,
         Err(e) => {
-            tracing::error!("Failed to get portfolio: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load portfolio: {}", e))
-                .reply_markup(keyboards::back_to_menu())
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load approvals: {}", e.user_facing_message()))
+                .reply_markup(keyboards::wallet_actions(wallet_id, &wallet.chain))
                 .await?;
+            return Ok(());
         }
+    };
+
+    let emoji = chain_emoji(&wallet.chain);
+    if approvals.is_empty() {
+        bot.edit_message_text(
+            chat_id,
+            message_id,
+            format!("{} 🔓 Token Approvals\n\n✅ No active approvals. No contract can spend your tokens.", emoji),
+        )
+            .reply_markup(keyboards::approval_list(wallet_id, &[], 0, 1))
+            .await?;
+        return Ok(());
+    }
+
+    let per_page = 5;
+    let total_pages = approvals.len().div_ceil(per_page);
+    let page = page.min(total_pages.saturating_sub(1));
+    let start = page * per_page;
+    let end = (start + per_page).min(approvals.len());
+
+    let mut text = format!("{} 🔓 Token Approvals ({})\n\n", emoji, approvals.len());
+    let mut buttons = Vec::new();
+
+    for approval in approvals.iter().take(end).skip(start) {
+        let spender = approval.spender_name
+            .clone()
+            .unwrap_or_else(|| format!("{}...{}", &approval.spender[..8], &approval.spender[approval.spender.len() - 6..]));
+        text.push_str(&format!(
+            "🪙 {} → {}\n   Allowance: {}\n   Spender: {}\n\n",
+            approval.token_symbol,
+            spender,
+            approval.allowance,
+            approval.spender
+        ));
+        buttons.push((approval.key(), format!("{} → {}", approval.token_symbol, spender)));
     }
 
+    text.push_str("⚠️ Revoking sends an on-chain transaction and costs gas.");
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboards::approval_list(wallet_id, &buttons, page, total_pages))
+        .await?;
+
     Ok(())
 }
 
-async fn show_prices(
+async fn revoke_wallet_approval(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
+    wallet_id: &str,
+    key: &str,
+    user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    bot.edit_message_text(chat_id, message_id, "⏳ Fetching current prices...")
-        .await?;
-
-    // Fetch prices for all configured chain native tokens
-    let symbols: Vec<String> = state.config.configured_chains()
-        .iter()
-        .map(|c| c.native_symbol().to_string())
-        .collect::<std::collections::HashSet<_>>()
-        .into_iter()
-        .collect();
+    let wallet = match find_user_wallet(wallet_id, user_id, state).await {
+        Some(w) => w,
+        None => {
+            bot.edit_message_text(chat_id, message_id, "❌ Wallet not found")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
 
-    match state.price_service.get_prices(&symbols).await {
-        Ok(prices) => {
-            let mut text = String::from("📊 Cryptocurrency Prices\n\n");
+    bot.edit_message_text(chat_id, message_id, "⏳ Revoking approval...")
+        .await?;
 
-            // Display in a deterministic order
-            let mut sorted_prices: Vec<_> = prices.iter().collect();
-            sorted_prices.sort_by(|a, b| {
-                b.1.usd_price.partial_cmp(&a.1.usd_price).unwrap_or(std::cmp::Ordering::Equal)
-            });
+    // Buttons carry a key of the token/spender pair; re-fetch to check it's still approved
+    let approval = match state.token_approval_service.list_approvals(&wallet.address, &wallet.chain, wallet.is_testnet).await {
+        Ok(approvals) => approvals.into_iter().find(|a| a.key() == key),
+        Err(_) => None,
+    };
 
-            for (symbol, price) in &sorted_prices {
-                let change = price.price_change_24h.unwrap_or(0.0);
-                let change_emoji = if change >= 0.0 { "📈" } else { "📉" };
-                text.push_str(&format!(
-                    "{} {}: ${:.2} {} {:.2}%\n",
-                    chain_emoji(symbol),
-                    symbol,
-                    price.usd_price,
-                    change_emoji,
-                    change.abs()
-                ));
-            }
+    let Some(approval) = approval else {
+        bot.edit_message_text(chat_id, message_id, "❌ Approval not found. It may already be revoked.")
+            .reply_markup(keyboards::approval_list(wallet_id, &[], 0, 1))
+            .await?;
+        return Ok(());
+    };
 
-            text.push_str("\n_Updated just now_");
+    let spender = approval.spender_name.clone().unwrap_or_else(|| approval.spender.clone());
 
+    match state.token_approval_service.revoke_approval(wallet.id, &approval.token_address, &approval.spender).await {
+        Ok(response) => {
+            let explorer_url = state.config.get_tx_explorer_url(&wallet.chain, wallet.is_testnet, &response.tx_hash);
+            let text = format!(
+                "✅ Revoke Submitted\n\n\
+🪙 Token: {}\n\
+🚫 Spender: {}\n\
+🔗 Hash: {}\n\n\
+{}",
+                approval.token_symbol,
+                spender,
+                response.tx_hash,
+                explorer_url
+            );
             bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboards::refresh_button("prices"))
+                .reply_markup(keyboards::approval_list(wallet_id, &[], 0, 1))
                 .await?;
         }
         Err(e) => {
-            tracing::error!("Failed to get prices: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load prices: {}", e))
-                .reply_markup(keyboards::back_to_menu())
+            bot.edit_message_text(chat_id, message_id, format!("❌ Revoke failed: {}", e.user_facing_message()))
+                .reply_markup(keyboards::approval_list(wallet_id, &[], 0, 1))
                 .await?;
         }
     }
@@ -1058,119 +1816,99 @@ async fn show_prices(
     Ok(())
 }
 
-async fn show_import_instructions(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> HandlerResult {
-    let chain_list: String = Chain::all()
-        .iter()
-        .map(|c| c.as_str())
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    let text = format!(
-        "📥 Import Wallet\n\n\
-To import an existing wallet, use the command:\n\n\
-/importwallet <chain> <mnemonic or private key>\n\n\
-Examples:\n\
-/importwallet ETH word1 word2 word3...\n\
-/importwallet SOLANA 5J7K...\n\
-/importwallet POLYGON word1 word2 word3...\n\n\
-Supported chains: {}",
-        chain_list
-    );
-
-    bot.edit_message_text(chat_id, message_id, &text)
-        .reply_markup(keyboards::back_to_menu())
-        .await?;
-
-    Ok(())
-}
-
-async fn show_send_menu(
+async fn show_add_chain_options(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
     wallet_id: &str,
+    user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+    let wallet = match find_user_wallet(wallet_id, user_id, state).await {
+        Some(w) => w,
+        None => {
+            bot.edit_message_text(chat_id, message_id, "❌ Wallet not found")
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
             return Ok(());
         }
     };
 
-    // Get wallet to know the chain
-    match state.wallet_service.get_wallet(uuid).await {
-        Ok(wallet) => {
-            // Get balance
-            let balance_str = match state.balance_service.get_balance(uuid, None).await {
-                Ok(b) => format!("{} {}", b.balance, b.symbol),
-                Err(_) => "Unknown".to_string(),
-            };
+    let chains: Vec<Chain> = Chain::all()
+        .iter()
+        .copied()
+        .filter(|c| c.is_evm() && c.as_str() != wallet.chain && state.config.configured_chains().contains(c))
+        .collect();
 
-            let native_token = wallet.chain.parse::<Chain>()
-                .map(|c| c.native_symbol())
-                .unwrap_or("tokens");
+    if chains.is_empty() {
+        bot.edit_message_text(chat_id, message_id, "ℹ️ No other EVM chains are configured")
+            .reply_markup(keyboards::wallet_actions(wallet_id, &wallet.chain))
+            .await?;
+        return Ok(());
+    }
 
-            let text = format!(
-                "📤 Send Crypto\n\n\
-💰 Available: {}\n\n\
-What would you like to send?",
-                balance_str
-            );
+    let text = format!(
+        "➕ Add Chain\n\n\
+        Use this {} wallet's key on another EVM chain\\. The address stays the same:\n\
+        `{}`\n\n\
+        ⚠️ Solana and other non\\-EVM chains use a different key format; restore your recovery phrase there instead\\.",
+        super::handlers::escape_markdown(&wallet.chain),
+        wallet.address
+    );
 
-            bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboards::send_menu(wallet_id, &wallet.chain))
-                .await?;
-        }
-        Err(e) => {
-            tracing::error!("Failed to get wallet: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e))
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-        }
-    }
+    bot.edit_message_text(chat_id, message_id, text)
+        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+        .reply_markup(keyboards::add_chain_selection(wallet_id, &chains))
+        .await?;
 
     Ok(())
 }
 
-async fn show_send_native(
+async fn add_wallet_chain(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
     wallet_id: &str,
+    chain: &str,
+    user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+    let wallet = match find_user_wallet(wallet_id, user_id, state).await {
+        Some(w) => w,
+        None => {
+            bot.edit_message_text(chat_id, message_id, "❌ Wallet not found")
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
             return Ok(());
         }
     };
 
-    match state.balance_service.get_balance(uuid, None).await {
-        Ok(balance) => {
+    match state.wallet_service.add_chain_to_existing_wallet(wallet.id, chain).await {
+        Ok(added) => {
             let text = format!(
-                "📤 Send {}\n\n\
-💰 Available: {} {}\n\n\
-Select the amount you want to send:",
-                balance.symbol,
-                balance.balance,
-                balance.symbol
+                "✅ {} {} Wallet Added\n\n\
+                📬 Address:\n`{}`\n\n\
+                Same key as your {} wallet\\. Tap address to copy\\.",
+                chain_emoji(&added.chain),
+                super::handlers::escape_markdown(&added.chain),
+                added.address,
+                super::handlers::escape_markdown(&wallet.chain)
             );
-
             bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboards::send_amount_presets(wallet_id, &balance.balance))
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+                .reply_markup(keyboards::existing_wallet(&added.id.to_string()))
+                .await?;
+        }
+        Err(crate::error::AppError::WalletAlreadyExists { existing_id }) => {
+            let text = crate::bot::utils::format_existing_wallet(chain, &existing_id);
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::existing_wallet(&existing_id.to_string()))
                 .await?;
         }
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e))
-                .reply_markup(keyboards::back_to_menu())
+            tracing::error!("Failed to add chain {} to wallet {}: {:?}", chain, wallet.id, e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to add chain: {}", e.user_facing_message()))
+                .reply_markup(keyboards::wallet_actions(wallet_id, &wallet.chain))
                 .await?;
         }
     }
@@ -1178,79 +1916,66 @@ Select the amount you want to send:",
     Ok(())
 }
 
-async fn show_send_ask_recipient(
+async fn start_auto_compound(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
     wallet_id: &str,
-    percent: &str,
-    user_id: i64,
+    protocol: &str,
+    user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-            return Ok(());
-        }
+    let (Ok(wallet_id), Ok(protocol)) = (uuid::Uuid::parse_str(wallet_id), protocol.parse::<crate::enums::DefiProtocol>()) else {
+        bot.edit_message_text(chat_id, message_id, "❌ Invalid auto-compound request").await?;
+        return Ok(());
     };
 
-    let percent_val: f64 = percent.parse().unwrap_or(0.0);
-
-    match state.balance_service.get_balance(uuid, None).await {
-        Ok(balance) => {
-            let balance_num: f64 = balance.balance.parse().unwrap_or(0.0);
-            let amount = balance_num * (percent_val / 100.0);
-            let amount_str = format!("{:.6}", amount);
-
-            // Set dialogue state to wait for recipient address
-            {
-                tracing::info!("Setting dialogue state for user_id: {}", user_id);
-                let mut storage = state.dialogue_storage.write().await;
-                storage.insert(user_id, DialogueState::WaitingForSendAddress {
-                    wallet_id: wallet_id.to_string(),
-                    amount: amount_str.clone(),
-                    symbol: balance.symbol.clone(),
-                });
-                tracing::info!("Dialogue state set successfully. Storage now has {} entries", storage.len());
-            }
+    let period = state.config.auto_compound_interval;
+    let min_profit_usd = crate::services::defi_protocol_service::DEFAULT_MIN_COMPOUND_PROFIT_USD;
+    let text = match state.dca_service.create_auto_compound_plan(user_id, wallet_id, protocol, min_profit_usd, period).await {
+        Ok(plan) => format!(
+            "✅ {} Auto-compound On\n\n\
+            Runs {}: rewards worth at least ${:.2} are claimed and supplied back.\n\
+            🆔 Plan: {}",
+            protocol.display_name(),
+            period,
+            min_profit_usd,
+            plan.id
+        ),
+        Err(e) => format!("❌ Failed to start auto-compound: {}", e.user_facing_message()),
+    };
+    bot.edit_message_text(chat_id, message_id, text).await?;
 
-            let text = format!(
-                "📤 Send {} {}\n\n\
-💰 Amount: {} {} ({}%)\n\n\
-📬 Now paste or type the recipient address:",
-                percent, balance.symbol,
-                amount_str, balance.symbol, percent
-            );
+    Ok(())
+}
 
-            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-                vec![
-                    teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
-                ],
-            ]);
+async fn stop_auto_compound(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    plan_id: &str,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let Ok(plan_id) = uuid::Uuid::parse_str(plan_id) else {
+        bot.edit_message_text(chat_id, message_id, "❌ Invalid plan ID").await?;
+        return Ok(());
+    };
 
-            bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboard)
-                .await?;
-        }
-        Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e))
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-        }
-    }
+    let text = match state.dca_service.cancel_auto_compound_plan(plan_id, user_id).await {
+        Ok(_) => "⏹ Auto-compound stopped. Rewards will keep accruing until you claim them.".to_string(),
+        Err(e) => format!("❌ Failed to stop auto-compound: {}", e.user_facing_message()),
+    };
+    bot.edit_message_text(chat_id, message_id, text).await?;
 
     Ok(())
 }
 
-async fn show_send_custom_prompt(
+async fn show_wallet_explorer_link(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
     wallet_id: &str,
-    user_id: i64,
     state: &Arc<BotState>,
 ) -> HandlerResult {
     let uuid = match uuid::Uuid::parse_str(wallet_id) {
@@ -1263,39 +1988,46 @@ async fn show_send_custom_prompt(
         }
     };
 
-    match state.balance_service.get_balance(uuid, None).await {
-        Ok(balance) => {
-            // Set dialogue state to wait for amount first, then recipient
-            {
-                let mut storage = state.dialogue_storage.write().await;
-                storage.insert(user_id, DialogueState::WaitingForSendAmount {
-                    wallet_id: wallet_id.to_string(),
-                    recipient: String::new(), // Will ask for this after amount
-                    symbol: balance.symbol.clone(),
-                });
-            }
+    match state.wallet_service.get_wallet(uuid).await {
+        Ok(wallet) => {
+            let emoji = chain_emoji(&wallet.chain);
+            let explorer_url = state.config.get_address_explorer_url(&wallet.chain, wallet.is_testnet, &wallet.address);
+            let chain_name = wallet.chain.parse::<Chain>()
+                .map(|c| c.display_name())
+                .unwrap_or("Unknown");
 
             let text = format!(
-                "📤 Send {}\n\n\
-💰 Available: {} {}\n\n\
-Type the amount you want to send:",
-                balance.symbol,
-                balance.balance,
-                balance.symbol
+                "🔍 {} {} Explorer\n\n\
+                📬 Address:\n{}\n\n\
+                🔗 View on Explorer:\n{}",
+                emoji, chain_name,
+                wallet.address,
+                explorer_url
             );
 
-            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-                vec![
-                    teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
-                ],
-            ]);
+            let keyboard = if let Ok(parsed_url) = reqwest::Url::parse(&explorer_url) {
+                teloxide::types::InlineKeyboardMarkup::new(vec![
+                    vec![
+                        teloxide::types::InlineKeyboardButton::url("🌐 Open Explorer", parsed_url),
+                    ],
+                    vec![
+                        teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+                    ],
+                ])
+            } else {
+                teloxide::types::InlineKeyboardMarkup::new(vec![
+                    vec![
+                        teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+                    ],
+                ])
+            };
 
             bot.edit_message_text(chat_id, message_id, text)
                 .reply_markup(keyboard)
                 .await?;
         }
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -1304,98 +2036,1183 @@ Type the amount you want to send:",
     Ok(())
 }
 
-async fn show_send_token_prompt(
+async fn show_portfolio(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
-    wallet_id: &str,
+    user_id: &str,
+    state: &Arc<BotState>,
 ) -> HandlerResult {
-    let text = "🪙 Send Token\n\n\
-Token sending coming soon!\n\n\
-For now, please send native tokens only.";
-
-    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-        vec![
-            teloxide::types::InlineKeyboardButton::callback("« Back", format!("wallet:send:{}", wallet_id)),
-        ],
-    ]);
-
-    bot.edit_message_text(chat_id, message_id, text)
-        .reply_markup(keyboard)
+    bot.edit_message_text(chat_id, message_id, "⏳ Fetching portfolio data...")
+        .await?;
+
+    match state.portfolio_service.get_portfolio(user_id).await {
+        Ok(portfolio) => {
+            let (currency, rate) = super::handlers::user_fiat(state, user_id).await;
+            let mut text = format!(
+                "💼 Your Portfolio\n📊 {} wallets across {} chains\n\n",
+                portfolio.wallet_count,
+                portfolio.chains.len()
+            );
+
+            for holding in &portfolio.holdings {
+                let change_str = holding.price_change_24h
+                    .map(|c| {
+                        let arrow = if c >= 0.0 { "📈" } else { "📉" };
+                        format!(" {} {:.1}%", arrow, c.abs())
+                    })
+                    .unwrap_or_default();
+
+                text.push_str(&format!(
+                    "{} {} {:.6} ({}){}\n",
+                    chain_emoji(&holding.symbol),
+                    holding.symbol,
+                    holding.total_balance,
+                    super::handlers::format_fiat(holding.usd_value * rate, currency),
+                    change_str,
+                ));
+
+                // Show per-wallet breakdown if multiple wallets hold this token
+                if holding.wallets.len() > 1 {
+                    for wh in &holding.wallets {
+                        let short_addr = if wh.address.len() >= 10 {
+                            format!("{}...{}", &wh.address[..6], &wh.address[wh.address.len()-4..])
+                        } else {
+                            wh.address.clone()
+                        };
+                        text.push_str(&format!("   └ {} {}\n", short_addr, wh.balance));
+                    }
+                }
+            }
+
+            text.push_str(&format!(
+                "\n💰 Total Value: {}",
+                super::handlers::format_fiat(portfolio.total_usd_value * rate, currency)
+            ));
+
+            let targets = state.rebalancing_service.list_targets(user_id).await.unwrap_or_default();
+            let allocations = crate::services::rebalancing_service::RebalancingService::compute_allocations(&portfolio, &targets);
+            if !allocations.is_empty() {
+                text.push_str("\n\n⚖️ Allocations\n");
+                for allocation in &allocations {
+                    text.push_str(&format!(
+                        "{} {}: {:.1}% (target {}%)\n",
+                        if allocation.needs_rebalance() { "⚠️" } else { "✅" },
+                        allocation.symbol,
+                        allocation.actual_pct,
+                        allocation.target_pct,
+                    ));
+                }
+            }
+
+            let dca_plans = state.dca_service.list_dca_plans(user_id).await.unwrap_or_default();
+            let active_plans: Vec<_> = dca_plans
+                .iter()
+                .filter(|dca| dca.plan.status == DcaStatus::Active.as_str())
+                .collect();
+            if !active_plans.is_empty() {
+                text.truncate(text.trim_end().len());
+                text.push_str("\n\n🔁 DCA Plans\n");
+                for dca in active_plans {
+                    text.push_str(&format!(
+                        "{} {} → {} ({}), next {}\n",
+                        dca.plan.amount_per_period,
+                        dca.plan.from_token,
+                        dca.plan.to_token,
+                        dca.plan.period,
+                        super::handlers::format_next_execution(dca.next_execution),
+                    ));
+                }
+            }
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::portfolio_menu())
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get portfolio: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load portfolio: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn share_portfolio_card(
+    bot: &Bot,
+    chat_id: ChatId,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    match state.portfolio_service.get_portfolio_card(user_id).await {
+        Ok(bytes) => {
+            let input_file = teloxide::types::InputFile::memory(bytes).file_name("portfolio.png");
+
+            bot.send_photo(chat_id, input_file)
+                .caption("📤 Your portfolio card. Forward it anywhere to share.")
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to render portfolio card: {:?}", e);
+            bot.send_message(chat_id, format!("❌ Failed to create portfolio card: {}", e.user_facing_message()))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn confirm_rpc_override(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let sealed_url = match state.dialogue_storage.get(user_id).await? {
+        DialogueState::PendingRpcOverride { wallet_id: pending_id, sealed_url } if pending_id == wallet_id => sealed_url,
+        _ => {
+            bot.edit_message_text(chat_id, message_id, "❌ Request expired. Please run /setwrpc again.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+    state.dialogue_storage.remove(user_id).await?;
+
+    let uuid = uuid::Uuid::parse_str(wallet_id)?;
+    let text = match state.wallet_service.set_rpc_override(uuid, &sealed_url).await {
+        Ok(()) => format!(
+            "✅ Custom RPC Set\n\n\
+Balance, send and fee estimation calls for this wallet now go to your node.\n\
+Use /setwrpc {} off to go back to the default RPCs.",
+            wallet_id
+        ),
+        Err(e) => {
+            tracing::error!("Failed to set RPC override for {}: {:?}", wallet_id, e);
+            format!("❌ Failed to set custom RPC: {}", e.user_facing_message())
+        }
+    };
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboards::back_to_menu())
+        .await?;
+
+    Ok(())
+}
+
+async fn confirm_batch_send(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    user_id: i64,
+    pin_owner: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let recipients = match state.dialogue_storage.get(user_id).await? {
+        DialogueState::PendingBatchSend { wallet_id: pending_id, recipients } if pending_id == wallet_id => recipients,
+        _ => {
+            bot.edit_message_text(chat_id, message_id, "❌ Batch expired. Please run /batchsend again.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+    let pending = PinProtectedSend::Batch { wallet_id: wallet_id.to_string(), recipients };
+    confirm_pending_send(bot, chat_id, message_id, pending, user_id, pin_owner, state).await
+}
+
+async fn confirm_schedule(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let request = match state.dialogue_storage.get(user_id).await? {
+        DialogueState::PendingSchedule { request } => request,
+        _ => {
+            bot.edit_message_text(chat_id, message_id, "❌ Schedule expired. Please run /schedule again.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+    state.dialogue_storage.remove(user_id).await?;
+
+    // Drop the buttons so the schedule can't be created twice
+    bot.edit_message_reply_markup(chat_id, message_id).await?;
+
+    super::handlers::create_schedule(bot, chat_id, request, state).await?;
+
+    Ok(())
+}
+
+/// Dry-run the pending schedule and offer to create it
+async fn simulate_schedule(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let request = match state.dialogue_storage.get(user_id).await? {
+        DialogueState::PendingSchedule { request } => request,
+        _ => {
+            bot.edit_message_text(chat_id, message_id, "❌ Schedule expired. Please run /schedule again.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.edit_message_text(chat_id, message_id, "⏳ Simulating schedule...").await?;
+
+    let simulation = match state.scheduling_service.simulate_schedule(&request).await {
+        Ok(simulation) => simulation,
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Simulation failed: {}", e.user_facing_message()))
+                .reply_markup(keyboards::schedule_confirm())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let outcome = if simulation.would_succeed {
+        "✅ Would succeed with the current balance"
+    } else {
+        "❌ Would fail with the current balance"
+    };
+    let mut text = format!(
+        "🔍 Schedule Simulation\n\n\
+{}\n\n\
+Balance now: {}\n\
+Estimated fee: ~{} (~${:.2})\n",
+        outcome,
+        simulation.balance_at_schedule_time,
+        simulation.estimated_gas_native,
+        simulation.estimated_fee_usd
+    );
+    if !simulation.warning_messages.is_empty() {
+        text.push('\n');
+        for warning in &simulation.warning_messages {
+            text.push_str(&format!("⚠️ {}\n", warning));
+        }
+    }
+    text.push_str(&format!(
+        "\nBalance and fees may change before {}.",
+        request.scheduled_for.format("%Y-%m-%d %H:%M UTC")
+    ));
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboards::schedule_confirm())
+        .await?;
+
+    Ok(())
+}
+
+async fn show_chain_breakdown(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    bot.edit_message_text(chat_id, message_id, "⏳ Fetching portfolio data...")
+        .await?;
+
+    match state.portfolio_service.get_chain_breakdown(user_id).await {
+        Ok(breakdown) if breakdown.is_empty() => {
+            bot.edit_message_text(chat_id, message_id, "📊 No wallets yet. Create one to see your allocation.")
+                .reply_markup(keyboards::portfolio_menu())
+                .await?;
+        }
+        Ok(breakdown) => {
+            let total: f64 = breakdown.iter().map(|a| a.usd_value).sum();
+            let text = format!(
+                "📊 Portfolio by Chain\n\n{}\n💰 Total Value: ${:.2}",
+                crate::bot::utils::format_chain_breakdown(&breakdown),
+                total
+            );
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::portfolio_menu())
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get chain breakdown: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load portfolio: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_token_aggregation(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    bot.edit_message_text(chat_id, message_id, "⏳ Fetching portfolio data...")
+        .await?;
+
+    match state.cross_chain_balance_service.aggregate_by_token(user_id).await {
+        Ok(tokens) if tokens.is_empty() => {
+            bot.edit_message_text(chat_id, message_id, "🌐 No token balances yet.")
+                .reply_markup(keyboards::portfolio_menu())
+                .await?;
+        }
+        Ok(tokens) => {
+            let total: f64 = tokens.iter().map(|t| t.total_usd_value).sum();
+            let text = format!(
+                "🌐 All Chains\n\n{}\n💰 Total Value: ${}",
+                crate::bot::utils::format_token_aggregation(&tokens),
+                super::handlers::format_currency(total)
+            );
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::portfolio_menu())
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to aggregate portfolio by token: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load portfolio: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_prices(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    bot.edit_message_text(chat_id, message_id, "⏳ Fetching current prices...")
+        .await?;
+
+    // Fetch prices for all configured chain native tokens
+    let symbols: Vec<String> = state.config.configured_chains()
+        .iter()
+        .map(|c| c.native_symbol().to_string())
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    match state.price_service.get_prices(&symbols).await {
+        Ok(prices) => {
+            let (currency, rate) = super::handlers::user_fiat(state, user_id).await;
+            let mut text = String::from("📊 Cryptocurrency Prices\n\n");
+
+            // Display in a deterministic order
+            let mut sorted_prices: Vec<_> = prices.iter().collect();
+            sorted_prices.sort_by(|a, b| {
+                b.1.usd_price.partial_cmp(&a.1.usd_price).unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            for (symbol, price) in &sorted_prices {
+                let change = price.price_change_24h.unwrap_or(0.0);
+                let change_emoji = if change >= 0.0 { "📈" } else { "📉" };
+                text.push_str(&format!(
+                    "{} {}: {} {} {:.2}%\n",
+                    chain_emoji(symbol),
+                    symbol,
+                    super::handlers::format_fiat(price.usd_price * rate, currency),
+                    change_emoji,
+                    change.abs()
+                ));
+            }
+
+            text.push_str("\n_Updated just now_");
+
+            let symbols: Vec<&str> = sorted_prices.iter().map(|(symbol, _)| symbol.as_str()).collect();
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::prices_keyboard(&symbols))
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get prices: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load prices: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Width of the chart bars, in characters
+const PRICE_CHART_BAR_WIDTH: usize = 5;
+
+async fn show_price_chart(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    symbol: &str,
+    days: u32,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    bot.edit_message_text(chat_id, message_id, format!("⏳ Loading {} {}d chart...", symbol, days))
+        .await?;
+
+    // CoinGecko quotes history directly in the user's currency
+    let currency = state.user_preference_service
+        .get_fiat_currency(user_id).await
+        .unwrap_or(crate::services::user_preference_service::DEFAULT_FIAT_CURRENCY);
+    match state.price_service.get_price_history_in(symbol, days, currency).await {
+        Ok(points) if points.len() >= 2 => {
+            let values: Vec<f64> = points.iter().map(|(_, price)| *price).collect();
+            let bars = crate::bot::utils::chart::render_bar_rows(&values, PRICE_CHART_BAR_WIDTH);
+
+            let mut text = format!("{} {} — last {} days\n\n", chain_emoji(symbol), symbol, days);
+            for ((timestamp, price), bar) in points.iter().zip(bars) {
+                let date = chrono::DateTime::from_timestamp(*timestamp as i64, 0)
+                    .map(|d| d.format("%m-%d").to_string())
+                    .unwrap_or_default();
+                text.push_str(&format!("{} {} {}\n", date, bar, super::handlers::format_fiat(*price, currency)));
+            }
+
+            let first = values[0];
+            let last = values[values.len() - 1];
+            let change = if first > 0.0 { (last - first) / first * 100.0 } else { 0.0 };
+            text.push_str(&format!(
+                "\n{} {:+.2}% over {} days",
+                if change >= 0.0 { "📈" } else { "📉" },
+                change,
+                days
+            ));
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::price_chart_keyboard(symbol, days))
+                .await?;
+        }
+        Ok(_) => {
+            bot.edit_message_text(chat_id, message_id, format!("📭 No price history for {}", symbol))
+                .reply_markup(keyboards::price_chart_keyboard(symbol, days))
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get {} price history: {:?}", symbol, e);
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                format!("❌ Failed to load chart: {}", e.user_facing_message())
+            )
+                .reply_markup(keyboards::price_chart_keyboard(symbol, days))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_import_instructions(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> HandlerResult {
+    let chain_list: String = Chain::all()
+        .iter()
+        .map(|c| c.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let text = format!(
+        "📥 Import Wallet\n\n\
+To import an existing wallet, use the command:\n\n\
+/importwallet <chain> <mnemonic or private key>\n\n\
+Examples:\n\
+/importwallet ETH word1 word2 word3...\n\
+/importwallet SOLANA 5J7K...\n\
+/importwallet POLYGON word1 word2 word3...\n\n\
+Supported chains: {}",
+        chain_list
+    );
+
+    bot.edit_message_text(chat_id, message_id, &text)
+        .reply_markup(keyboards::back_to_menu())
+        .await?;
+
+    Ok(())
+}
+
+async fn show_send_menu(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Get wallet to know the chain
+    match state.wallet_service.get_wallet(uuid).await {
+        Ok(wallet) => {
+            // Get balance
+            let balance_str = match state.balance_service.get_balance(uuid, None).await {
+                Ok(b) => format!("{} {}", b.balance, b.symbol),
+                Err(_) => "Unknown".to_string(),
+            };
+
+            let native_token = wallet.chain.parse::<Chain>()
+                .map(|c| c.native_symbol())
+                .unwrap_or("tokens");
+
+            let text = format!(
+                "📤 Send Crypto\n\n\
+💰 Available: {}\n\n\
+What would you like to send?",
+                balance_str
+            );
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::send_menu(wallet_id, &wallet.chain))
+                .await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get wallet: {:?}", e);
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_send_native(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    match state.balance_service.get_balance(uuid, None).await {
+        Ok(balance) => {
+            let text = format!(
+                "📤 Send {}\n\n\
+💰 Available: {} {}\n\n\
+Select the amount you want to send:",
+                balance.symbol,
+                balance.balance,
+                balance.symbol
+            );
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::send_amount_presets(wallet_id, &balance.balance))
+                .await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_send_ask_recipient(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    percent: &str,
+    user_id: i64,
+    owner_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let percent_val: f64 = percent.parse().unwrap_or(0.0);
+
+    match state.balance_service.get_balance(uuid, None).await {
+        Ok(balance) => {
+            let balance_num: f64 = balance.balance.parse().unwrap_or(0.0);
+            let amount = balance_num * (percent_val / 100.0);
+            let amount_str = format!("{:.6}", amount);
+
+            // 100% sends the balance minus gas, computed once the recipient is known
+            let send_max = percent == "100";
+
+            // Set dialogue state to wait for recipient address
+            tracing::info!("Setting dialogue state for user_id: {}", user_id);
+            state.dialogue_storage.set(user_id, DialogueState::WaitingForSendAddress {
+                wallet_id: wallet_id.to_string(),
+                amount: amount_str.clone(),
+                symbol: balance.symbol.clone(),
+                send_max,
+                amount_usd_estimate: None,
+            }).await?;
+
+            let amount_line = if send_max {
+                format!("💰 Amount: Max {} (balance minus gas)", balance.symbol)
+            } else {
+                format!("💰 Amount: {} {} ({}%)", amount_str, balance.symbol, percent)
+            };
+
+            let text = format!(
+                "📤 Send {} {}\n\n\
+{}\n\n\
+📬 Now paste or type the recipient address:",
+                percent, balance.symbol,
+                amount_line
+            );
+
+            let keyboard = send_address_keyboard(wallet_id, user_id, owner_id, state).await;
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_send_custom_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    match state.balance_service.get_balance(uuid, None).await {
+        Ok(balance) => {
+            // Set dialogue state to wait for amount first, then recipient
+            state.dialogue_storage.set(user_id, DialogueState::WaitingForSendAmount {
+                wallet_id: wallet_id.to_string(),
+                recipient: String::new(), // Will ask for this after amount
+                symbol: balance.symbol.clone(),
+            }).await?;
+
+            let text = format!(
+                "📤 Send {}\n\n\
+💰 Available: {} {}\n\n\
+Type the amount you want to send:",
+                balance.symbol,
+                balance.balance,
+                balance.symbol
+            );
+
+            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+                vec![
+                    teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
+                ],
+            ]);
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_send_usd_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    match state.balance_service.get_balance(uuid, None).await {
+        Ok(balance) => {
+            state.dialogue_storage.set(user_id, DialogueState::WaitingForUsdSendAmount {
+                wallet_id: wallet_id.to_string(),
+                symbol: balance.symbol.clone(),
+            }).await?;
+
+            let text = format!(
+                "📤 Send {}\n\n\
+💰 Available: {} {}\n\n\
+💵 Type the amount in USD (e.g. 50):",
+                balance.symbol,
+                balance.balance,
+                balance.symbol
+            );
+
+            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+                vec![
+                    teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
+                ],
+            ]);
+
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_send_token_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+) -> HandlerResult {
+    let text = "🪙 Send Token\n\n\
+Token sending coming soon!\n\n\
+For now, please send native tokens only.";
+
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("« Back", format!("wallet:send:{}", wallet_id)),
+        ],
+    ]);
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+async fn show_send_confirmation(
+    bot: &Bot,
+    chat_id: ChatId,
+    wallet_id: &str,
+    recipient: &str,
+    amount: &str,
+    symbol: &str,
+    send_max: bool,
+    amount_usd_estimate: Option<f64>,
+    state: &Arc<BotState>,
+    user_id: i64,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(chat_id, "❌ Invalid wallet ID").await?;
+            return Ok(());
+        }
+    };
+
+    let wallet = match state.wallet_service.get_wallet(uuid).await {
+        Ok(w) => w,
+        Err(e) => {
+            bot.send_message(chat_id, format!("❌ Failed to load wallet: {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    };
+
+    let short_recipient = short_address(recipient);
+
+    let amount_line = if send_max {
+        match state.transfer_service.get_max_sendable_amount(uuid, recipient).await {
+            Ok(max) => format!(
+                "Sending max: {} {} (after gas)\nEstimated fee: {} {}",
+                max.amount, max.symbol, max.estimated_fee, max.symbol
+            ),
+            Err(e) => {
+                bot.send_message(chat_id, format!("❌ Cannot send max: {}", e.user_facing_message())).await?;
+                return Ok(());
+            }
+        }
+    } else if let Some(usd) = amount_usd_estimate {
+        format!("Sending {} {} (~${:.2} USD)", amount, symbol, usd)
+    } else {
+        format!("Amount: {} {}", amount, symbol)
+    };
+
+    let text = format!(
+        "📤 Confirm Transaction\n\n\
+From: {} Wallet\n\
+{}\n\n\
+To: {}\n\n\
+{}\n\n\
+{}\
+⚠️ Please verify all details before confirming.",
+        wallet.chain,
+        wallet.address,
+        short_recipient,
+        amount_line,
+        congestion_notice(state, &wallet.chain)
+    );
+
+    // Store transaction details in dialogue state for the confirm button
+    // (Telegram callback data has 64-byte limit, can't fit wallet_id + address + amount)
+    state.dialogue_storage.set(user_id, DialogueState::PendingSendConfirmation(PendingSendConfirmation {
+        wallet_id: wallet_id.to_string(),
+        recipient: recipient.to_string(),
+        amount: amount.to_string(),
+        symbol: symbol.to_string(),
+        send_max,
+        amount_usd_estimate,
+        token_address: None,
+    })).await?;
+
+    let can_simulate = wallet.chain.parse::<Chain>().map(|c| c.is_evm()).unwrap_or(false);
+
+    bot.send_message(chat_id, text)
+        .reply_markup(send_confirmation_keyboard(wallet_id, can_simulate))
         .await?;
 
     Ok(())
 }
 
-async fn show_send_confirmation(
-    bot: &Bot,
-    chat_id: ChatId,
+/// Number of saved addresses offered as one-tap recipients
+const ADDRESS_QUICK_PICK_LIMIT: usize = 5;
+
+/// Keyboard for the recipient step: saved addresses on the wallet's chain plus a cancel button
+async fn send_address_keyboard(
+    wallet_id: &str,
+    user_id: i64,
+    owner_id: &str,
+    state: &Arc<BotState>,
+) -> teloxide::types::InlineKeyboardMarkup {
+    let mut rows = Vec::new();
+
+    let chain = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(uuid) => state.wallet_service.get_wallet(uuid).await.ok().map(|w| w.chain),
+        Err(_) => None,
+    };
+    if let Some(chain) = chain {
+        let entries = state.address_book_service
+            .list_addresses(owner_id, Some(&chain)).await
+            .unwrap_or_default();
+        let picks: Vec<_> = entries
+            .into_iter()
+            // ENS cache rows aren't addresses the user saved
+            .filter(|e| e.ens_expires_at.is_none())
+            .take(ADDRESS_QUICK_PICK_LIMIT)
+            .map(|e| teloxide::types::InlineKeyboardButton::callback(
+                format!("📇 {}", e.name),
+                format!("addr_book_pick:{}:{}", user_id, e.id),
+            ))
+            .collect();
+        if !picks.is_empty() {
+            rows.push(picks);
+        }
+    }
+
+    rows.push(vec![
+        teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
+    ]);
+    teloxide::types::InlineKeyboardMarkup::new(rows)
+}
+
+/// Saved contacts on the wallet's chain whose name or address contains `query`
+pub(super) async fn find_send_contacts(
     wallet_id: &str,
-    recipient: &str,
-    amount: &str,
-    symbol: &str,
+    user_id: &str,
+    query: &str,
     state: &Arc<BotState>,
+) -> Vec<crate::db::entity::address_book::Model> {
+    let chain = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(uuid) => state.wallet_service.get_wallet(uuid).await.ok().map(|w| w.chain),
+        Err(_) => None,
+    };
+    let Some(chain) = chain else {
+        return Vec::new();
+    };
+
+    state.address_book_service
+        .search(user_id, query, Some(&chain)).await
+        .unwrap_or_default()
+        .into_iter()
+        // ENS cache rows aren't addresses the user saved
+        .filter(|e| e.ens_expires_at.is_none())
+        .collect()
+}
+
+/// Use a saved address as the recipient of the pending send
+async fn pick_send_address(
+    bot: &Bot,
+    chat_id: ChatId,
+    owner_id: &str,
+    entry_id: &str,
     user_id: i64,
+    address_owner: &str,
+    state: &Arc<BotState>,
 ) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.send_message(chat_id, "❌ Invalid wallet ID").await?;
-            return Ok(());
-        }
+    // `owner_id` is the dialogue key of whoever opened the picker
+    if owner_id != user_id.to_string() {
+        return Ok(());
+    }
+
+    let dialogue_state = state.dialogue_storage.get(user_id).await?;
+    let DialogueState::WaitingForSendAddress { wallet_id, amount, symbol, send_max, amount_usd_estimate } = dialogue_state else {
+        bot.send_message(chat_id, "❌ This send has expired. Please start again.").await?;
+        return Ok(());
     };
 
-    let wallet = match state.wallet_service.get_wallet(uuid).await {
-        Ok(w) => w,
+    let entry = match uuid::Uuid::parse_str(entry_id) {
+        Ok(id) => state.address_book_service.get_address_by_id(address_owner, id).await,
+        Err(_) => Err(crate::error::AppError::NotFound("Address not found".to_string())),
+    };
+    let entry = match entry {
+        Ok(e) => e,
         Err(e) => {
-            bot.send_message(chat_id, format!("❌ Failed to load wallet: {}", e)).await?;
+            bot.send_message(chat_id, format!("❌ {}", e.user_facing_message())).await?;
             return Ok(());
         }
     };
 
-    let short_recipient = if recipient.len() > 16 {
-        format!("{}...{}", &recipient[..8], &recipient[recipient.len()-6..])
-    } else {
-        recipient.to_string()
+    state.dialogue_storage.remove(user_id).await?;
+
+    show_send_confirmation(bot, chat_id, &wallet_id, &entry.address, &amount, &symbol, send_max, amount_usd_estimate, state, user_id).await?;
+
+    Ok(())
+}
+
+const SWAPS_PER_PAGE: u64 = 5;
+
+/// Text and navigation for one page of a user's swap history, optionally for a single wallet
+pub(super) async fn swap_history_page(
+    user_id: &str,
+    wallet_id: Option<&str>,
+    page: usize,
+    state: &Arc<BotState>,
+) -> crate::error::Result<(String, teloxide::types::InlineKeyboardMarkup)> {
+    // Fetch one extra row to know whether there's a next page
+    let offset = page as u64 * SWAPS_PER_PAGE;
+    let mut swaps = match wallet_id {
+        Some(id) => {
+            let uuid = uuid::Uuid::parse_str(id)
+                .map_err(|_| crate::error::AppError::InvalidInput("Invalid wallet ID".to_string()))?;
+            let wallet = state.wallet_service.get_wallet(uuid).await?;
+            if wallet.user_id != user_id {
+                return Err(crate::error::AppError::WalletNotFound);
+            }
+            state.swap_repository.find_by_wallet(uuid, SWAPS_PER_PAGE + 1, offset).await?
+        }
+        None => state.swap_repository.find_by_user(user_id, SWAPS_PER_PAGE + 1, offset).await?,
     };
 
-    let text = format!(
-        "📤 Confirm Transaction\n\n\
-From: {} Wallet\n\
-{}\n\n\
-To: {}\n\n\
-Amount: {} {}\n\n\
-⚠️ Please verify all details before confirming.",
-        wallet.chain,
-        wallet.address,
-        short_recipient,
-        amount,
-        symbol
-    );
+    let has_next = swaps.len() as u64 > SWAPS_PER_PAGE;
+    swaps.truncate(SWAPS_PER_PAGE as usize);
 
-    // Store transaction details in dialogue state for the confirm button
-    // (Telegram callback data has 64-byte limit, can't fit wallet_id + address + amount)
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.insert(user_id, DialogueState::PendingSendConfirmation {
-            wallet_id: wallet_id.to_string(),
-            recipient: recipient.to_string(),
-            amount: amount.to_string(),
-            symbol: symbol.to_string(),
-        });
+    let mut text = String::from("📊 Swap History\n\n");
+    if swaps.is_empty() {
+        text.push_str("No swaps yet. Use /swap to make one.");
+    }
+    for swap in &swaps {
+        text.push_str(&crate::bot::utils::format_swap_line(swap));
+        text.push_str(&format!("\n   {}\n\n", swap.created_at.format("%Y-%m-%d %H:%M UTC")));
     }
 
-    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+    Ok((text, keyboards::swap_history(page, has_next, wallet_id)))
+}
+
+async fn show_swap_history_page(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: &str,
+    wallet_id: Option<&str>,
+    page: usize,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    match swap_history_page(user_id, wallet_id, page, state).await {
+        Ok((text, keyboard)) => {
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load swap history: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a wallet lives on a testnet network (defaults to mainnet if it can't be loaded)
+async fn wallet_is_testnet(state: &Arc<BotState>, wallet_id: uuid::Uuid) -> bool {
+    state.wallet_service.get_wallet(wallet_id).await
+        .map(|w| w.is_testnet)
+        .unwrap_or(false)
+}
+
+fn short_address(address: &str) -> String {
+    if address.len() > 16 {
+        format!("{}...{}", &address[..8], &address[address.len()-6..])
+    } else {
+        address.to_string()
+    }
+}
+
+fn send_confirmation_keyboard(wallet_id: &str, can_simulate: bool) -> teloxide::types::InlineKeyboardMarkup {
+    let mut rows = vec![
         vec![
             teloxide::types::InlineKeyboardButton::callback("✅ Confirm & Send", "send:confirm"),
         ],
-        vec![
-            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("wallet:select:{}", wallet_id)),
-        ],
+    ];
+    if can_simulate {
+        rows.push(vec![
+            teloxide::types::InlineKeyboardButton::callback("🔍 Simulate", "send:simulate"),
+        ]);
+    }
+    rows.push(vec![
+        teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("wallet:select:{}", wallet_id)),
     ]);
+    teloxide::types::InlineKeyboardMarkup::new(rows)
+}
 
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
+/// Dry-run the pending send and show the outcome, keeping the confirmation open
+async fn simulate_pending_send(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    use crate::services::transfer_service::TransferRequest;
+
+    let dialogue_state = state.dialogue_storage.get(user_id).await?;
+
+    let DialogueState::PendingSendConfirmation(PendingSendConfirmation { wallet_id, recipient, amount, symbol, send_max, .. }) = dialogue_state else {
+        bot.edit_message_text(chat_id, message_id, "❌ Transaction expired. Please start again.")
+            .reply_markup(keyboards::back_to_menu())
+            .await?;
+        return Ok(());
+    };
+
+    let uuid = match uuid::Uuid::parse_str(&wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.edit_message_text(chat_id, message_id, "🔍 Simulating transaction...")
+        .await?;
+
+    // Simulate the amount that would actually be sent
+    let amount = if send_max {
+        match state.transfer_service.get_max_sendable_amount(uuid, &recipient).await {
+            Ok(max) => max.amount,
+            Err(e) => {
+                bot.edit_message_text(chat_id, message_id, format!("❌ Cannot send max: {}", e.user_facing_message()))
+                    .reply_markup(send_confirmation_keyboard(&wallet_id, false))
+                    .await?;
+                return Ok(());
+            }
+        }
+    } else {
+        amount
+    };
+
+    let request = TransferRequest {
+        to: recipient.clone(),
+        amount: amount.clone(),
+        token_address: None,
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        gas_limit: None,
+        compute_units: None,
+        send_max: false,
+    };
+
+    let text = match state.transaction_simulator.simulate(uuid, &request).await {
+        Ok(result) => {
+            let changes = result.state_changes
+                .iter()
+                .map(|c| format!("• {}: {} {}", short_address(&c.address), c.change, c.asset))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                "🔍 Simulation Succeeded\n\n\
+Sending {} {} to {}\n\n\
+⛽ Gas used: {}\n\n\
+Balance changes:\n{}\n\n\
+Confirm to broadcast the transaction.",
+                amount,
+                symbol,
+                short_address(&recipient),
+                result.gas_used,
+                changes
+            )
+        }
+        Err(e) => format!(
+            "⚠️ {}\n\nThis transaction would most likely fail on-chain. You can still send it, but the network fee would be lost.",
+            e.user_facing_message()
+        ),
+    };
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(send_confirmation_keyboard(&wallet_id, false))
         .await?;
 
     Ok(())
@@ -1425,14 +3242,14 @@ async fn execute_send_with_params(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
-    wallet_id: &str,
-    recipient: &str,
-    amount: &str,
+    pending: &PendingSendConfirmation,
     state: &Arc<BotState>,
 ) -> HandlerResult {
     use crate::services::transfer_service::TransferRequest;
 
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+    let recipient = pending.recipient.as_str();
+    let amount = pending.amount.as_str();
+    let uuid = match uuid::Uuid::parse_str(&pending.wallet_id) {
         Ok(id) => id,
         Err(_) => {
             bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
@@ -1449,32 +3266,36 @@ async fn execute_send_with_params(
     let wallet = match state.wallet_service.get_wallet(uuid).await {
         Ok(w) => w,
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
             return Ok(());
         }
     };
 
-    let symbol = wallet.chain.parse::<Chain>()
-        .map(|c| c.native_symbol())
-        .unwrap_or("tokens");
+    let symbol = match &pending.token_address {
+        Some(_) => pending.symbol.as_str(),
+        None => wallet.chain.parse::<Chain>()
+            .map(|c| c.native_symbol())
+            .unwrap_or("tokens"),
+    };
 
     // Create transfer request
     let transfer_request = TransferRequest {
         to: recipient.to_string(),
         amount: amount.to_string(),
-        token_address: None,
+        token_address: pending.token_address.clone(),
         max_fee_per_gas: None,
         max_priority_fee_per_gas: None,
         gas_limit: None,
         compute_units: None,
+        send_max: pending.send_max,
     };
 
     // Execute the transfer
     match state.transfer_service.send_transaction(uuid, transfer_request).await {
         Ok(result) => {
-            let explorer_url = state.config.get_tx_explorer_url(&wallet.chain, &result.tx_hash);
+            let explorer_url = state.config.get_tx_explorer_url(&wallet.chain, wallet.is_testnet, &result.tx_hash);
             let text = format!(
                 "✅ Transaction Submitted!\n\n\
 📤 Sent: {} {}\n\
@@ -1499,7 +3320,11 @@ Your transaction is being processed.",
         }
         Err(e) => {
             tracing::error!("Transfer failed: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Transaction Failed\n\n{}", e))
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                format!("❌ Transaction Failed\n\n{}", crate::bot::utils::format_transfer_error(&e))
+            )
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -1508,6 +3333,224 @@ Your transaction is being processed.",
     Ok(())
 }
 
+/// Send a confirmed transfer or batch, first asking for the PIN if the amount requires it.
+/// Every bot send goes through here so none of them can skip the PIN.
+pub(super) async fn confirm_pending_send(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    pending: PinProtectedSend,
+    user_id: i64,
+    pin_owner: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let amount_usd = match &pending {
+        PinProtectedSend::Single(pending) => pending_send_usd_value(pending, state).await,
+        PinProtectedSend::Batch { wallet_id, recipients } => batch_usd_value(wallet_id, recipients, state).await,
+    };
+    let pin_required = state.security_service
+        .is_pin_required_for_amount(pin_owner, amount_usd).await
+        .unwrap_or(true);
+
+    if pin_required {
+        let wallet_id = pending.wallet_id().to_string();
+        state.dialogue_storage.set(user_id, DialogueState::WaitingForPin {
+            pending_send: Box::new(pending),
+        }).await?;
+        bot.edit_message_text(chat_id, message_id, "🔐 Enter your PIN to confirm this transaction:")
+            .reply_markup(pin_cancel_keyboard(&wallet_id))
+            .await?;
+        return Ok(());
+    }
+
+    // Clear the state
+    state.dialogue_storage.remove(user_id).await?;
+    match pending {
+        PinProtectedSend::Single(pending) => {
+            execute_send_with_params(bot, chat_id, message_id, &pending, state).await?;
+        }
+        PinProtectedSend::Batch { wallet_id, recipients } => {
+            // Drop the buttons so the batch can't be sent twice
+            bot.edit_message_reply_markup(chat_id, message_id).await?;
+            let uuid = uuid::Uuid::parse_str(&wallet_id)?;
+            super::handlers::execute_batch_send(bot, chat_id, uuid, recipients, state).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A dust wallet that was checked and can be merged into the user's main wallet
+pub(super) struct DustMerge {
+    pub dust: crate::services::portfolio_service::DustWallet,
+    pub main_wallet_address: String,
+    pub native_symbol: String,
+    pub native_balance: String,
+    pub native_usd_value: f64,
+}
+
+impl DustMerge {
+    pub fn confirmation_text(&self) -> String {
+        let mut text = format!(
+            "Dust detected: {}. Transfer to main wallet?",
+            crate::bot::utils::format_dust_balances(&self.dust.balances)
+        );
+        if self.dust.balances.len() > 1 {
+            text.push_str(&format!("\n\nOnly the {} is moved; tokens stay in this wallet.", self.native_symbol));
+        }
+        text
+    }
+}
+
+/// Check that `wallet_id` is one of the user's dust wallets and that merging its native
+/// balance is worth the fee. The error is the message to show the user instead.
+pub(super) async fn prepare_dust_merge(
+    wallet_id: &str,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> std::result::Result<DustMerge, String> {
+    use crate::services::portfolio_service::DEFAULT_DUST_THRESHOLD_USD;
+
+    let uuid = uuid::Uuid::parse_str(wallet_id).map_err(|_| "❌ Invalid wallet ID".to_string())?;
+    match state.wallet_service.get_wallet(uuid).await {
+        Ok(wallet) if wallet.user_id == user_id => {}
+        Ok(_) => return Err("❌ Wallet not found".to_string()),
+        Err(e) => return Err(format!("❌ Failed to load wallet: {}", e.user_facing_message())),
+    }
+
+    let dust = state.portfolio_service
+        .find_dust_wallets(user_id, DEFAULT_DUST_THRESHOLD_USD).await
+        .map_err(|e| format!("❌ Error: {}", e.user_facing_message()))?
+        .into_iter()
+        .find(|d| d.wallet_id == wallet_id)
+        .ok_or_else(|| format!(
+            "✅ No dust here. Only wallets worth less than ${} can be cleaned.",
+            super::handlers::format_currency(DEFAULT_DUST_THRESHOLD_USD)
+        ))?;
+    let summary = crate::bot::utils::format_dust_balances(&dust.balances);
+
+    let Some(main_wallet_address) = dust.main_wallet_address.clone() else {
+        return Err(format!(
+            "Dust detected: {}.\n\nYou have no other {} wallet worth merging it into.",
+            summary,
+            dust.chain
+        ));
+    };
+
+    let native_symbol = dust.chain
+        .parse::<Chain>()
+        .map(|c| c.native_symbol())
+        .unwrap_or(&dust.chain)
+        .to_string();
+    let Some(native) = dust.balances.iter().find(|b| b.symbol == native_symbol) else {
+        return Err(format!(
+            "Dust detected: {}.\n\nThis wallet has no {} to pay the network fee, so it can't be merged.",
+            summary,
+            native_symbol
+        ));
+    };
+    let native_balance = native.balance.clone();
+    let native_usd_value = native.usd_value;
+
+    let estimate = state.gas_estimation_service
+        .estimate_transaction_fee(uuid, &main_wallet_address, &native_balance, None).await
+        .map_err(|e| format!("❌ Could not estimate the network fee: {}", e.user_facing_message()))?;
+    let fee_native: f64 = estimate.gas_estimate.total_cost_native.parse().unwrap_or(0.0);
+    if fee_native >= native_balance.parse::<f64>().unwrap_or(0.0) {
+        let fee = match estimate.gas_estimate.total_cost_usd {
+            Some(usd) => format!("${}", crate::bot::utils::format_dust_usd(usd)),
+            None => format!("{} {}", estimate.gas_estimate.total_cost_native, native_symbol),
+        };
+        return Err(format!(
+            "⚠️ Dust detected: {}.\n\nThe network fee ({}) is more than the dust is worth, so it won't be merged.",
+            summary,
+            fee
+        ));
+    }
+
+    Ok(DustMerge { dust, main_wallet_address, native_symbol, native_balance, native_usd_value })
+}
+
+/// Send a dust wallet's whole native balance, minus gas, to the user's main wallet
+async fn merge_dust_wallet(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    user_id: i64,
+    owner_id: &str,
+    pin_owner: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    // Balances and fees may have moved since the summary was shown
+    let merge = match prepare_dust_merge(wallet_id, owner_id, state).await {
+        Ok(merge) => merge,
+        Err(text) => {
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let pending = PendingSendConfirmation {
+        wallet_id: wallet_id.to_string(),
+        recipient: merge.main_wallet_address,
+        amount: merge.native_balance,
+        symbol: merge.native_symbol,
+        send_max: true,
+        amount_usd_estimate: Some(merge.native_usd_value),
+        token_address: None,
+    };
+    confirm_pending_send(bot, chat_id, message_id, PinProtectedSend::Single(pending), user_id, pin_owner, state).await
+}
+
+/// USD value of a pending send for the PIN threshold; unknown values count as unbounded
+async fn pending_send_usd_value(pending: &PendingSendConfirmation, state: &Arc<BotState>) -> f64 {
+    if let Some(usd) = pending.amount_usd_estimate {
+        return usd;
+    }
+    let Ok(amount) = pending.amount.parse::<f64>() else {
+        return f64::INFINITY;
+    };
+    match state.price_service.get_price(&pending.symbol).await {
+        Ok(price) => amount * price.usd_price,
+        Err(_) => f64::INFINITY,
+    }
+}
+
+/// USD value of a batch for the PIN threshold; infinite when any transfer can't be priced
+async fn batch_usd_value(
+    wallet_id: &str,
+    recipients: &[crate::services::transfer_service::BatchRecipient],
+    state: &Arc<BotState>,
+) -> f64 {
+    if recipients.iter().any(|r| r.token_address.is_some()) {
+        return f64::INFINITY;
+    }
+    let Ok(uuid) = uuid::Uuid::parse_str(wallet_id) else {
+        return f64::INFINITY;
+    };
+    let Ok(chain) = state.wallet_service.get_wallet(uuid).await.and_then(|w| w.chain.parse::<Chain>()) else {
+        return f64::INFINITY;
+    };
+    let Ok(amounts) = recipients.iter().map(|r| r.amount.parse::<f64>()).collect::<Result<Vec<f64>, _>>() else {
+        return f64::INFINITY;
+    };
+    match state.price_service.get_price(chain.native_symbol()).await {
+        Ok(price) => amounts.iter().sum::<f64>() * price.usd_price,
+        Err(_) => f64::INFINITY,
+    }
+}
+
+fn pin_cancel_keyboard(wallet_id: &str) -> teloxide::types::InlineKeyboardMarkup {
+    teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id)),
+        ],
+    ])
+}
+
 async fn cancel_send(
     bot: &Bot,
     chat_id: ChatId,
@@ -1517,10 +3560,7 @@ async fn cancel_send(
     state: &Arc<BotState>,
 ) -> HandlerResult {
     // Clear dialogue state
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.remove(&user_id);
-    }
+    state.dialogue_storage.remove(user_id).await?;
 
     // Return to wallet
     show_wallet_actions(bot, chat_id, message_id, wallet_id, state).await
@@ -1566,11 +3606,16 @@ Scan QR or tap address to copy\\.",
                 wallet.address
             );
 
-            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-                vec![
-                    teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
-                ],
+            let mut rows = Vec::new();
+            if wallet.chain == Chain::Btc.as_str() {
+                rows.push(vec![
+                    teloxide::types::InlineKeyboardButton::callback("⚡ Lightning Invoice", format!("wallet:lninvoice:{}", wallet_id)),
+                ]);
+            }
+            rows.push(vec![
+                teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
             ]);
+            let keyboard = teloxide::types::InlineKeyboardMarkup::new(rows);
 
             // Delete the old message and send photo
             let _ = bot.delete_message(chat_id, message_id).await;
@@ -1582,7 +3627,7 @@ Scan QR or tap address to copy\\.",
                 .await?;
         }
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -1591,6 +3636,59 @@ Scan QR or tap address to copy\\.",
     Ok(())
 }
 
+/// Send an any-amount Lightning invoice for a BTC wallet as a QR code plus copyable text
+async fn show_lightning_invoice(
+    bot: &Bot,
+    chat_id: ChatId,
+    wallet_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(chat_id, "❌ Invalid wallet ID").await?;
+            return Ok(());
+        }
+    };
+
+    let invoice = match state.wallet_service.generate_lightning_invoice(uuid, 0, "Wallet top-up").await {
+        Ok(invoice) => invoice,
+        Err(e) => {
+            bot.send_message(chat_id, format!("❌ Failed to create Lightning invoice: {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    };
+
+    // Box<dyn Error> isn't Send, so stringify before awaiting
+    let png = match crate::bot::utils::generate_qr_code(&invoice.to_uppercase()).map_err(|e| e.to_string()) {
+        Ok(png) => png,
+        Err(e) => {
+            bot.send_message(chat_id, format!("❌ Failed to render QR code: {}", e)).await?;
+            return Ok(());
+        }
+    };
+    let input_file = teloxide::types::InputFile::memory(png).file_name("invoice.png");
+
+    let caption = format!(
+        "⚡ Lightning Invoice\n\n`{}`\n\nScan the QR or tap the invoice to copy\\. The payer chooses the amount\\.",
+        invoice
+    );
+
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+        ],
+    ]);
+
+    bot.send_photo(chat_id, input_file)
+        .caption(caption)
+        .parse_mode(teloxide::types::ParseMode::MarkdownV2)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
 async fn show_swap_menu(
     bot: &Bot,
     chat_id: ChatId,
@@ -1628,7 +3726,7 @@ Select a swap pair or create custom swap:",
         }
         Err(e) => {
             tracing::error!("Failed to get wallet: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -1659,7 +3757,7 @@ async fn show_swap_preset(
     let wallet = match state.wallet_service.get_wallet(uuid).await {
         Ok(w) => w,
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load wallet: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
             return Ok(());
@@ -1689,56 +3787,499 @@ Select amount to swap:",
         from_token, to_token, balance_str
     );
 
-    bot.edit_message_text(chat_id, message_id, text)
-        .reply_markup(keyboards::swap_amount_presets(wallet_id, from_token, to_token))
-        .await?;
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboards::swap_amount_presets(wallet_id, from_token, to_token))
+        .await?;
+
+    Ok(())
+}
+
+async fn show_swap_custom_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let balance_str = match state.balance_service.get_balance(uuid, None).await {
+        Ok(b) => b.symbol,
+        Err(_) => "tokens".to_string(),
+    };
+
+    // Set dialogue to wait for swap amount
+    state.dialogue_storage.set(user_id, DialogueState::WaitingForSwapAmount {
+        wallet_id: wallet_id.to_string(),
+        from_token: balance_str.clone(),
+        to_token: "USDC".to_string(),
+    }).await?;
+
+    let text = format!(
+        "💱 Custom Swap\n\n\
+Type the amount of {} you want to swap:\n\n\
+(You can swap to stablecoins)",
+        balance_str
+    );
+
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("swap:cancel:{}", wallet_id)),
+        ],
+    ]);
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+async fn show_swap_amount_custom_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    from_token: &str,
+    to_token: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    // Set dialogue to wait for swap amount
+    state.dialogue_storage.set(user_id, DialogueState::WaitingForSwapAmount {
+        wallet_id: wallet_id.to_string(),
+        from_token: from_token.to_string(),
+        to_token: to_token.to_string(),
+    }).await?;
+
+    let text = format!(
+        "💱 Swap {} → {}\n\n\
+Type the amount of {} you want to swap:",
+        from_token, to_token, from_token
+    );
+
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("swap:cancel:{}", wallet_id)),
+        ],
+    ]);
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+async fn show_swap_confirm(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    wallet_id: &str,
+    from_token: &str,
+    to_token: &str,
+    percent: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let uuid = match uuid::Uuid::parse_str(wallet_id) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let percent_val: f64 = percent.parse().unwrap_or(0.0);
+
+    match state.balance_service.get_balance(uuid, None).await {
+        Ok(balance) => {
+            let balance_num: f64 = balance.balance.parse().unwrap_or(0.0);
+            let amount = balance_num * (percent_val / 100.0);
+
+            let pending = PendingSwapState {
+                wallet_id: wallet_id.to_string(),
+                from_token: from_token.to_string(),
+                to_token: to_token.to_string(),
+                amount: format!("{:.6}", amount),
+                dex: None,
+                slippage: preferred_swap_slippage(user_id, state).await,
+            };
+            state.dialogue_storage.set(user_id, DialogueState::PendingSwapConfirmation(pending.clone())).await?;
+
+            edit_swap_confirmation(bot, chat_id, message_id, &pending, state).await?;
+        }
+        Err(e) => {
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e.user_facing_message()))
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn show_swap_confirmation(
+    bot: &Bot,
+    chat_id: ChatId,
+    wallet_id: &str,
+    from_token: &str,
+    to_token: &str,
+    amount: &str,
+    slippage: Option<f64>,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let slippage = match slippage {
+        Some(slippage) => slippage,
+        None => preferred_swap_slippage(user_id, state).await,
+    };
+    let pending = PendingSwapState {
+        wallet_id: wallet_id.to_string(),
+        from_token: from_token.to_string(),
+        to_token: to_token.to_string(),
+        amount: amount.to_string(),
+        dex: None,
+        slippage,
+    };
+    state.dialogue_storage.set(user_id, DialogueState::PendingSwapConfirmation(pending.clone())).await?;
+
+    let (text, keyboard) = swap_confirmation_view(&pending, state).await;
+
+    bot.send_message(chat_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Resolve the tokens of a /swap command, asking the user to choose when a symbol matches
+/// several tokens, then show the swap confirmation
+pub(super) async fn resolve_swap_tokens(
+    bot: &Bot,
+    chat_id: ChatId,
+    mut pending: PendingSwapTokens,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    use crate::services::swap_service::ResolvedToken;
+
+    for picking_from in [true, false] {
+        let query = if picking_from { &pending.from_token } else { &pending.to_token };
+
+        let resolved = match state.swap_service.resolve_token_for_swap(&pending.chain, query).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                state.dialogue_storage.remove(user_id).await?;
+                bot.send_message(chat_id, format!("❌ {}", e.user_facing_message())).await?;
+                return Ok(());
+            }
+        };
+
+        if let ResolvedToken::Ambiguous(candidates) = resolved {
+            let text = format!("🔎 Several tokens match \"{}\". Which one do you mean?", query);
+            let mut rows: Vec<Vec<teloxide::types::InlineKeyboardButton>> = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, token)| {
+                    vec![
+                        teloxide::types::InlineKeyboardButton::callback(
+                            format!("{} - {} ({})", token.symbol, token.name, short_address(&token.address)),
+                            format!("swap:pick:{}", i)
+                        ),
+                    ]
+                })
+                .collect();
+            rows.push(vec![teloxide::types::InlineKeyboardButton::callback("❌ Cancel", "swap:pickcancel")]);
+
+            pending.candidates = candidates;
+            pending.picking_from = picking_from;
+            state.dialogue_storage.set(user_id, DialogueState::PendingSwapTokenPick(pending)).await?;
+
+            bot.send_message(chat_id, text)
+                .reply_markup(teloxide::types::InlineKeyboardMarkup::new(rows))
+                .await?;
+            return Ok(());
+        }
+
+        if let Some(token) = resolved.swap_token() {
+            let token = token.to_string();
+            if picking_from {
+                pending.from_token = token;
+            } else {
+                pending.to_token = token;
+            }
+        }
+    }
+
+    show_swap_confirmation(
+        bot,
+        chat_id,
+        &pending.wallet_id,
+        &pending.from_token,
+        &pending.to_token,
+        &pending.amount,
+        pending.slippage,
+        user_id,
+        state,
+    ).await
+}
+
+async fn pick_swap_token(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    index: &str,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let DialogueState::PendingSwapTokenPick(mut pending) = state.dialogue_storage.get(user_id).await? else {
+        return show_swap_expired(bot, chat_id, message_id).await;
+    };
+    let Some(token) = index.parse::<usize>().ok().and_then(|i| pending.candidates.get(i)).cloned() else {
+        return show_swap_expired(bot, chat_id, message_id).await;
+    };
+
+    if pending.picking_from {
+        pending.from_token = token.address;
+    } else {
+        pending.to_token = token.address;
+    }
+    pending.candidates.clear();
+
+    bot.edit_message_text(chat_id, message_id, format!("✅ Using {} ({})", token.symbol, token.name))
+        .await?;
+
+    resolve_swap_tokens(bot, chat_id, pending, user_id, state).await
+}
+
+async fn edit_swap_confirmation(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    pending: &PendingSwapState,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let (text, keyboard) = swap_confirmation_view(pending, state).await;
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Confirmation text and buttons for a pending swap; quotes are compared until a DEX is picked
+async fn swap_confirmation_view(
+    pending: &PendingSwapState,
+    state: &Arc<BotState>,
+) -> (String, teloxide::types::InlineKeyboardMarkup) {
+    let slippage = format_slippage(pending.slippage);
+    let chain = match uuid::Uuid::parse_str(&pending.wallet_id) {
+        Ok(uuid) => state.wallet_service.get_wallet(uuid).await.ok().map(|w| w.chain),
+        Err(_) => None,
+    };
+    let congestion = chain.as_deref().map(|c| congestion_notice(state, c)).unwrap_or_default();
+    let from_label = swap_token_label(state, chain.as_deref(), &pending.from_token).await;
+    let to_label = swap_token_label(state, chain.as_deref(), &pending.to_token).await;
+
+    if let Some(dex) = &pending.dex {
+        let text = format!(
+            "💱 Confirm Swap\n\n\
+Swap: {} {}\n\
+To: {} (estimated)\n\
+DEX: {}\n\n\
+{}\
+{}\
+Final amount may vary.",
+            pending.amount, from_label, to_label, dex, slippage, congestion
+        );
+
+        let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+            vec![
+                teloxide::types::InlineKeyboardButton::callback(format!("✅ Confirm via {}", dex), "swap:confirm"),
+            ],
+            vec![
+                teloxide::types::InlineKeyboardButton::callback("⚙️ Adjust Slippage", "swap:slippage"),
+            ],
+            vec![
+                teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("wallet:select:{}", pending.wallet_id)),
+            ],
+        ]);
+
+        return (text, keyboard);
+    }
+
+    let (warnings, quote_details, dexes) = match (uuid::Uuid::parse_str(&pending.wallet_id), pending.amount.parse::<f64>()) {
+        (Ok(uuid), Ok(amount_num)) => {
+            swap_quote_details(state, uuid, &pending.from_token, &pending.to_token, amount_num).await
+        }
+        _ => (String::new(), String::new(), Vec::new()),
+    };
+
+    let text = format!(
+        "💱 Confirm Swap\n\n\
+{}\
+Swap: {} {}\n\
+To: {} (estimated)\n\n\
+{}\
+{}\
+{}\
+Final amount may vary.",
+        warnings, pending.amount, from_label, to_label, quote_details, slippage, congestion
+    );
+
+    (text, swap_confirm_keyboard(&pending.wallet_id, &dexes))
+}
+
+/// Symbol and contract address of a token on a swap confirmation, so the user can check
+/// which of several same-named tokens they are trading
+async fn swap_token_label(state: &Arc<BotState>, chain: Option<&str>, token: &str) -> String {
+    use crate::services::swap_service::ResolvedToken;
+
+    let Some(chain) = chain else {
+        return token.to_string();
+    };
+    match state.swap_service.resolve_token_for_swap(chain, token).await {
+        Ok(ResolvedToken::Token(t)) if t.symbol != t.address => format!("{} ({})", t.symbol, t.address),
+        Ok(ResolvedToken::Token(t)) => format!("unknown token ({})", t.address),
+        _ => token.to_string(),
+    }
+}
+
+/// Congestion warning paragraph for a confirmation screen, empty when the chain is calm
+fn congestion_notice(state: &Arc<BotState>, chain: &str) -> String {
+    chain
+        .parse::<Chain>()
+        .ok()
+        .and_then(|chain| state.network_congestion.congestion_warning(chain))
+        .map(|warning| format!("{}\n\n", warning))
+        .unwrap_or_default()
+}
+
+/// Confirm at the best price, or pick one of the quoted DEXes explicitly
+fn swap_confirm_keyboard(wallet_id: &str, dexes: &[String]) -> teloxide::types::InlineKeyboardMarkup {
+    let mut rows = vec![
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("✅ Confirm Swap (best price)", "swap:confirm"),
+        ],
+    ];
+
+    // Choosing only makes sense when there is more than one DEX
+    if dexes.len() > 1 {
+        for dex in dexes {
+            rows.push(vec![
+                teloxide::types::InlineKeyboardButton::callback(
+                    format!("🔀 Via {}", dex),
+                    format!("swap:via:{}", dex)
+                ),
+            ]);
+        }
+    }
+
+    rows.push(vec![
+        teloxide::types::InlineKeyboardButton::callback("⚙️ Adjust Slippage", "swap:slippage"),
+    ]);
+    rows.push(vec![
+        teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("wallet:select:{}", wallet_id)),
+    ]);
+
+    teloxide::types::InlineKeyboardMarkup::new(rows)
+}
+
+fn format_slippage(slippage: f64) -> String {
+    let mut text = format!("⚠️ Slippage: {}%\n", slippage);
+    if slippage > crate::services::swap_service::HIGH_SLIPPAGE_PCT {
+        text.push_str("⚠️ High slippage: your transaction may be frontrun\n");
+    }
+    text
+}
+
+/// Swap being confirmed, also while the user is typing a custom slippage for it
+async fn pending_swap(
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> Result<Option<PendingSwapState>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match state.dialogue_storage.get(user_id).await? {
+        DialogueState::PendingSwapConfirmation(pending) => Some(pending),
+        DialogueState::WaitingForSlippage { pending_swap } => Some(*pending_swap),
+        _ => None,
+    })
+}
+
+/// Slippage of the user's last swap, or the bot default
+async fn preferred_swap_slippage(user_id: i64, state: &Arc<BotState>) -> f64 {
+    match state.user_preference_service.get_swap_slippage(&user_id.to_string()).await {
+        Ok(slippage) => slippage.unwrap_or(crate::services::swap_service::DEFAULT_SLIPPAGE_PCT),
+        Err(e) => {
+            tracing::warn!("Failed to load slippage preference for user {}: {:?}", user_id, e);
+            crate::services::swap_service::DEFAULT_SLIPPAGE_PCT
+        }
+    }
+}
+
+/// Set the pending swap's slippage and remember it for the user's next swaps
+async fn apply_swap_slippage(
+    mut pending: PendingSwapState,
+    slippage: f64,
+    user_id: i64,
+    state: &Arc<BotState>,
+) -> Result<PendingSwapState, Box<dyn std::error::Error + Send + Sync>> {
+    pending.slippage = slippage;
+    state.dialogue_storage.set(user_id, DialogueState::PendingSwapConfirmation(pending.clone())).await?;
 
-    Ok(())
+    if let Err(e) = state.user_preference_service.set_swap_slippage(&user_id.to_string(), slippage).await {
+        tracing::warn!("Failed to save slippage preference for user {}: {:?}", user_id, e);
+    }
+
+    Ok(pending)
 }
 
-async fn show_swap_custom_prompt(
+const SLIPPAGE_PRESETS: [f64; 4] = [0.1, 0.5, 1.0, 3.0];
+
+async fn show_slippage_presets(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
-    wallet_id: &str,
     user_id: i64,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-            return Ok(());
-        }
-    };
-
-    let balance_str = match state.balance_service.get_balance(uuid, None).await {
-        Ok(b) => b.symbol,
-        Err(_) => "tokens".to_string(),
+    let Some(pending) = pending_swap(user_id, state).await? else {
+        return show_swap_expired(bot, chat_id, message_id).await;
     };
 
-    // Set dialogue to wait for swap amount
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.insert(user_id, DialogueState::WaitingForSwapAmount {
-            wallet_id: wallet_id.to_string(),
-            from_token: balance_str.clone(),
-            to_token: "USDC".to_string(),
-        });
-    }
-
     let text = format!(
-        "💱 Custom Swap\n\n\
-Type the amount of {} you want to swap:\n\n\
-(You can swap to stablecoins)",
-        balance_str
+        "⚙️ Slippage Tolerance\n\n\
+Current: {}%\n\n\
+The swap reverts if the price moves against you by more than this before it is mined.",
+        pending.slippage
     );
 
+    let presets = SLIPPAGE_PRESETS
+        .iter()
+        .map(|pct| {
+            teloxide::types::InlineKeyboardButton::callback(format!("{:.1}%", pct), format!("swap:slip:{}", pct))
+        })
+        .collect();
+
     let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        presets,
         vec![
-            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("swap:cancel:{}", wallet_id)),
+            teloxide::types::InlineKeyboardButton::callback("✏️ Custom", "swap:slip:custom"),
+        ],
+        vec![
+            teloxide::types::InlineKeyboardButton::callback("⬅️ Back", "swap:review"),
         ],
     ]);
 
@@ -1749,35 +4290,30 @@ Type the amount of {} you want to swap:\n\n\
     Ok(())
 }
 
-async fn show_swap_amount_custom_prompt(
+async fn show_slippage_custom_prompt(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
-    wallet_id: &str,
-    from_token: &str,
-    to_token: &str,
     user_id: i64,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    // Set dialogue to wait for swap amount
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.insert(user_id, DialogueState::WaitingForSwapAmount {
-            wallet_id: wallet_id.to_string(),
-            from_token: from_token.to_string(),
-            to_token: to_token.to_string(),
-        });
-    }
+    let Some(pending) = pending_swap(user_id, state).await? else {
+        return show_swap_expired(bot, chat_id, message_id).await;
+    };
+
+    state.dialogue_storage.set(user_id, DialogueState::WaitingForSlippage {
+        pending_swap: Box::new(pending),
+    }).await?;
 
     let text = format!(
-        "💱 Swap {} → {}\n\n\
-Type the amount of {} you want to swap:",
-        from_token, to_token, from_token
+        "✏️ Custom Slippage\n\n\
+Type the slippage tolerance in percent (up to {}%), e.g. 0.8",
+        crate::services::swap_service::MAX_SLIPPAGE_PCT
     );
 
     let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
         vec![
-            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("swap:cancel:{}", wallet_id)),
+            teloxide::types::InlineKeyboardButton::callback("⬅️ Back", "swap:review"),
         ],
     ]);
 
@@ -1788,124 +4324,169 @@ Type the amount of {} you want to swap:",
     Ok(())
 }
 
-async fn show_swap_confirm(
-    bot: &Bot,
-    chat_id: ChatId,
-    message_id: MessageId,
-    wallet_id: &str,
+async fn show_swap_expired(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> HandlerResult {
+    bot.edit_message_text(chat_id, message_id, "❌ Swap expired. Please start again.")
+        .reply_markup(keyboards::back_to_menu())
+        .await?;
+
+    Ok(())
+}
+
+/// Best-effort quote lines for the swap confirmation: every DEX's output and price impact,
+/// plus the DEX names in best-first order for the per-DEX buttons
+async fn swap_quote_details(
+    state: &Arc<BotState>,
+    wallet_id: uuid::Uuid,
     from_token: &str,
     to_token: &str,
-    percent: &str,
-    state: &Arc<BotState>,
-) -> HandlerResult {
-    let uuid = match uuid::Uuid::parse_str(wallet_id) {
-        Ok(id) => id,
-        Err(_) => {
-            bot.edit_message_text(chat_id, message_id, "❌ Invalid wallet ID")
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-            return Ok(());
+    amount: f64,
+) -> (String, String, Vec<String>) {
+    let bot_quote = match state.swap_service
+        .get_bot_quote(wallet_id, from_token, to_token, &amount.to_string()).await
+    {
+        Ok(q) => q,
+        Err(e) => {
+            tracing::warn!("Swap quote failed: {:?}", e);
+            return (String::new(), String::new(), Vec::new());
         }
     };
 
-    let percent_val: f64 = percent.parse().unwrap_or(0.0);
-
-    match state.balance_service.get_balance(uuid, None).await {
-        Ok(balance) => {
-            let balance_num: f64 = balance.balance.parse().unwrap_or(0.0);
-            let amount = balance_num * (percent_val / 100.0);
-            let amount_str = format!("{:.6}", amount);
-
-            let text = format!(
-                "💱 Confirm Swap\n\n\
-From: {} {}\n\
-To: {} (estimated)\n\n\
-Amount: {}%\n\n\
-⚠️ Slippage: 0.5%\n\
-Final amount may vary.",
-                amount_str, from_token,
-                to_token,
-                percent
-            );
+    let warnings = if bot_quote.warning_messages.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n\n", bot_quote.warning_messages.join("\n"))
+    };
 
-            let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-                vec![
-                    teloxide::types::InlineKeyboardButton::callback(
-                        "✅ Confirm Swap",
-                        format!("swap:confirm:{}:{}:{}:{}", wallet_id, from_token, to_token, amount_str)
-                    ),
-                ],
-                vec![
-                    teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("wallet:select:{}", wallet_id)),
-                ],
-            ]);
+    let mut quote = bot_quote.quote;
+    let mut quotes = std::mem::take(&mut quote.alternatives);
+    quotes.insert(0, quote);
+
+    let mut details = String::new();
+    if quotes.len() > 1 {
+        let table = quotes
+            .iter()
+            .map(|q| format!("{}: {} {}", q.dex, format_quote_amount(q.expected_to_amount), to_token))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        details.push_str(&format!("📊 {}\n", table));
+        details.push_str(&format!("🏆 Best: {}\n", quotes[0].dex));
+        details.push_str("Price Impact:\n");
+        for quote in &quotes {
+            details.push_str(&format!(
+                "{} {}: {:.2}%\n",
+                price_impact_indicator(quote.price_impact),
+                quote.dex,
+                quote.price_impact
+            ));
+        }
+        details.push('\n');
+    } else {
+        details.push_str(&format!(
+            "{} Price Impact: {:.2}%\n\n",
+            price_impact_indicator(quotes[0].price_impact),
+            quotes[0].price_impact
+        ));
+    }
 
-            bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboard)
-                .await?;
-        }
-        Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to get balance: {}", e))
-                .reply_markup(keyboards::back_to_menu())
-                .await?;
-        }
+    if let Some(security) = &bot_quote.token_security {
+        details.push_str(&format_token_security(security));
     }
 
-    Ok(())
+    let dexes = quotes.into_iter().map(|q| q.dex).collect();
+    (warnings, details, dexes)
 }
 
-async fn show_swap_confirmation(
-    bot: &Bot,
-    chat_id: ChatId,
-    wallet_id: &str,
-    from_token: &str,
-    to_token: &str,
-    amount: &str,
-    state: &Arc<BotState>,
-) -> HandlerResult {
-    let text = format!(
-        "💱 Confirm Swap\n\n\
-Swap: {} {}\n\
-To: {} (estimated)\n\n\
-⚠️ Slippage: 0.5%\n\
-Final amount may vary.",
-        amount, from_token, to_token
-    );
+/// Thousands separators for larger outputs, more precision for small ones
+fn format_quote_amount(amount: f64) -> String {
+    if amount >= 1.0 {
+        super::handlers::format_currency(amount)
+    } else {
+        format!("{:.6}", amount)
+    }
+}
 
-    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
-        vec![
-            teloxide::types::InlineKeyboardButton::callback(
-                "✅ Confirm Swap",
-                format!("swap:confirm:{}:{}:{}:{}", wallet_id, from_token, to_token, amount)
-            ),
-        ],
-        vec![
-            teloxide::types::InlineKeyboardButton::callback("❌ Cancel", format!("wallet:select:{}", wallet_id)),
-        ],
-    ]);
+fn format_token_security(security: &TokenSecurity) -> String {
+    let (icon, label) = match security.risk_level() {
+        RiskLevel::Low => ("🟢", "Low"),
+        RiskLevel::Medium => ("🟡", "Medium"),
+        RiskLevel::High => ("🔴", "High"),
+    };
 
-    bot.send_message(chat_id, text)
-        .reply_markup(keyboard)
-        .await?;
+    let mut text = format!("🛡 Token Risk: {} {}\n", icon, label);
+    if security.is_honeypot {
+        text.push_str("⛔ Honeypot: this token can't be sold. Swap will be blocked.\n");
+    }
+    text.push_str(&format!(
+        "Buy tax: {:.1}% | Sell tax: {:.1}%\n",
+        security.buy_tax_pct,
+        security.sell_tax_pct
+    ));
+    if security.is_proxy {
+        text.push_str("⚠️ Upgradeable proxy contract\n");
+    }
+    for warning in &security.warnings {
+        text.push_str(&format!("⚠️ {}\n", warning));
+    }
+    text.push('\n');
+    text
+}
 
-    Ok(())
+fn price_impact_indicator(pct: f64) -> &'static str {
+    if pct < 1.0 {
+        "🟢"
+    } else if pct <= 3.0 {
+        "🟡"
+    } else {
+        "🔴"
+    }
 }
 
 async fn execute_swap(
     bot: &Bot,
     chat_id: ChatId,
     message_id: MessageId,
-    wallet_id: &str,
-    from_token: &str,
-    to_token: &str,
-    amount: &str,
+    pending: PendingSwapState,
+    user_id: i64,
     state: &Arc<BotState>,
 ) -> HandlerResult {
+    use crate::services::swap_service::SwapRequest;
+
+    let (uuid, amount_num) = match (uuid::Uuid::parse_str(&pending.wallet_id), pending.amount.parse::<f64>()) {
+        (Ok(id), Ok(a)) if a > 0.0 => (id, a),
+        _ => {
+            bot.edit_message_text(chat_id, message_id, "❌ Invalid swap details. Please start again.")
+                .reply_markup(keyboards::back_to_menu())
+                .await?;
+            return Ok(());
+        }
+    };
+
     bot.edit_message_text(chat_id, message_id, "⏳ Processing swap...")
         .await?;
 
-    // TODO: Implement actual swap
-    bot.edit_message_text(chat_id, message_id, "✅ Swap submitted!\n\nYou will receive a notification when it's confirmed.")
+    let request = SwapRequest {
+        user_id: user_id.to_string(),
+        wallet_id: uuid,
+        from_token: pending.from_token,
+        to_token: pending.to_token,
+        amount: amount_num,
+        slippage: pending.slippage,
+        dex: pending.dex,
+    };
+
+    let text = match state.swap_service.execute_swap(request).await {
+        Ok(swap) => format!(
+            "✅ Swap submitted!\n\n\
+DEX: {}\n\
+Tx: {}\n\n\
+You will receive a notification when it's confirmed.",
+            swap.dex,
+            swap.tx_hash.unwrap_or_else(|| "pending".to_string())
+        ),
+        Err(e) => format!("❌ Swap failed: {}", e.user_facing_message()),
+    };
+
+    bot.edit_message_text(chat_id, message_id, text)
         .reply_markup(keyboards::back_to_menu())
         .await?;
 
@@ -1943,9 +4524,13 @@ async fn show_help_wallets(bot: &Bot, chat_id: ChatId, message_id: MessageId) ->
         "💼 Wallet Commands\n\n\
 /createwallet <chain> - Create new wallet\n\
 /importwallet <chain> <key> - Import wallet\n\
+/vanity <chain> --prefix <hex> - Wallet with a custom EVM address\n\
 /wallets - List all wallets\n\
 /balance <wallet_id> - Check balance\n\
-/address <wallet_id> - Get address with QR\n\n\
+/historicalbalance <wallet_id> <block|date> - Balance in the past\n\
+/address <wallet_id> - Get address with QR\n\
+/findwallet <partial_address> - Find a wallet by address\n\
+/cleanwallet <wallet_id> - Merge a near-empty wallet into your main one\n\n\
 Supported chains:\n{}",
         chain_list
     );
@@ -1962,7 +4547,13 @@ async fn show_help_transactions(bot: &Bot, chat_id: ChatId, message_id: MessageI
 /send <wallet_id> <to> <amount> - Send tokens\n\
 /estimatefee <wallet_id> <to> <amount> - Estimate fees\n\
 /batchsend <wallet_id> - Send to multiple addresses\n\
-/history <wallet_id> - View transaction history";
+/history <wallet_id> - View transaction history\n\
+/speedup <tx_id> [max_fee_gwei] - Speed up pending tx\n\
+/canceltx <tx_id> - Cancel pending tx\n\
+/txstatus <tx_hash> <chain> - Look up any transaction\n\
+/block <chain> <number|latest> - Block details\n\
+/networkstatus - Latest block and gas on every chain\n\
+/fees [days] - Network fees you paid";
 
     bot.edit_message_text(chat_id, message_id, text)
         .reply_markup(keyboards::help_menu())
@@ -1976,6 +4567,7 @@ async fn show_help_addressbook(bot: &Bot, chat_id: ChatId, message_id: MessageId
 /saveaddress <name> <addr> <chain> - Save address\n\
 /addresses - List saved addresses\n\
 /deleteaddress <name> - Delete saved address\n\n\
+Send a .csv (name,address,chain[,notes]) or .json file to import contacts in bulk.\n\n\
 Use saved names instead of addresses when sending!";
 
     bot.edit_message_text(chat_id, message_id, text)
@@ -1987,12 +4579,28 @@ Use saved names instead of addresses when sending!";
 
 async fn show_help_alerts(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> HandlerResult {
     let text = "🔔 Alerts & Scheduling\n\n\
+/chart <symbol> [interval] - Candle chart with support/resistance\n\
 /setalert <symbol> <above|below> <price> - Set price alert\n\
-/alerts - List your alerts\n\
-/deletealert <id> - Delete alert\n\n\
+/setstoploss <symbol> <price> [chain] [wallet_id] - Stop-loss (auto-sells with wallet)\n\
+/settakeprofit <symbol> <price> [chain] - Take-profit alert\n\
+/settrailstop <symbol> <trail%> [chain] - Trailing stop alert\n\
+/setvolumealert <symbol> <multiplier> [chain] - 24h volume spike alert\n\
+/gasalert <chain> <max_gwei> - Alert when gas drops\n\
+/alerts [gas] - List your alerts\n\
+/deletealert <id> - Delete alert\n\
+/pausealert <id> - Pause alert\n\
+/resumealert <id> - Resume paused alert\n\
+/deletegasalert <id> - Delete gas alert\n\
+/setallocation <symbol> <target%> [threshold%] - Rebalancing alert\n\
+/watchbalance <wallet_id> [off] - Notify on large balance changes\n\n\
 /schedule <wallet_id> <to> <amount> <datetime> - Schedule tx\n\
 /scheduled - List scheduled transactions\n\
-/cancelschedule <id> - Cancel scheduled tx";
+/calendar [days] - Upcoming scheduled txs by day\n\
+/cancelschedule <id> - Cancel scheduled tx\n\
+/dca create <wallet_id> <from> <to> <amount> <daily|weekly|monthly> - Recurring buy\n\
+/dca list - List DCA plans\n\
+/dca pause|resume|cancel <plan_id> - Manage a DCA plan\n\
+/defi <wallet_id> - Aave/Compound positions and auto-compounding";
 
     bot.edit_message_text(chat_id, message_id, text)
         .reply_markup(keyboards::help_menu())
@@ -2007,9 +4615,16 @@ async fn show_help_security(bot: &Bot, chat_id: ChatId, message_id: MessageId) -
 /changepin <old> <new> - Change PIN\n\
 /disablepin - Disable PIN protection\n\
 /setlimit daily|weekly <amount> - Set limits\n\
+/setmaxsend <amount_usd|off> - Cap a single send\n\
+/getlimits - View your limits\n\
 /lockwallet - Lock your wallet\n\
 /unlock <pin> - Unlock wallet\n\
-/security - View security settings";
+/security - View security settings\n\
+/audit - Run a security audit of your account\n\
+/testnet on|off - Create new wallets on testnets\n\
+/backup <password> - Export encrypted wallet backup\n\
+/restore <password> - Restore wallets (reply to a backup file)\n\
+/setgroupadmin @username - Let a group member send from the group wallets (group owner only)";
 
     bot.edit_message_text(chat_id, message_id, text)
         .reply_markup(keyboards::help_menu())
@@ -2022,7 +4637,9 @@ async fn show_help_swap(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> Ha
     let text = "💱 Swap Commands\n\n\
 /swapquote <chain> <from> <to> <amount> - Get quote\n\
 /swap <wallet_id> <from> <to> <amount> - Execute swap\n\
-/swaphistory - View swap history";
+/batchswap <wallet_id> - Several swaps, one FROM TO AMOUNT per line\n\
+/swaphistory - View swap history\n\n\
+Use ⚙️ Adjust Slippage on a swap confirmation to change the tolerance; your choice is kept for later swaps.";
 
     bot.edit_message_text(chat_id, message_id, text)
         .reply_markup(keyboards::help_menu())
@@ -2092,7 +4709,7 @@ async fn show_addresses(
         }
         Err(e) => {
             tracing::error!("Failed to get addresses: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load addresses: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load addresses: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -2108,42 +4725,74 @@ async fn show_alerts(
     user_id: &str,
     state: &Arc<BotState>,
 ) -> HandlerResult {
-    match state.price_alert_service.list_user_alerts(user_id, true).await {
+    // Paused alerts are listed with a resume button; triggered ones are left out
+    let alerts = state.price_alert_service
+        .list_user_alerts(user_id, false).await
+        .map(|alerts| alerts.into_iter().filter(|a| a.active).collect::<Vec<_>>());
+
+    match alerts {
         Ok(alerts) if alerts.is_empty() => {
             bot.edit_message_text(chat_id, message_id, "📭 No price alerts set.\n\nCreate one with /setalert")
                 .reply_markup(keyboards::alerts_menu())
                 .await?;
         }
         Ok(alerts) => {
-            let mut text = String::from("🔔 Your Price Alerts\n\n");
+            let paused = alerts.iter().filter(|a| a.paused).count();
+            let mut text = format!(
+                "🔔 Your Price Alerts\n\n{} active | ⏸ {} paused\n\n",
+                alerts.len() - paused,
+                paused
+            );
+            let mut rows = Vec::with_capacity(alerts.len() + 2);
 
             for alert in &alerts {
                 let condition = match alert.alert_type.parse::<AlertKind>() {
                     Ok(AlertKind::Above) => "📈 Above",
                     Ok(AlertKind::Below) => "📉 Below",
                     Ok(AlertKind::PercentChange) => "⚡ Change",
+                    Ok(AlertKind::StopLoss) => "🛑 Stop-loss",
+                    Ok(AlertKind::TakeProfit) => "🎯 Take-profit",
+                    Ok(AlertKind::TrailingStop) => "🔻 Trailing stop",
+                    Ok(AlertKind::LargeIncoming) => "📨 Large incoming",
+                    Ok(AlertKind::VolumeSurge) => "📊 Volume",
                     Err(_) => "🔔 Alert",
                 };
-                let price_str = alert.target_price
-                    .map(|p| format!("${}", p))
-                    .unwrap_or_else(|| "N/A".to_string());
+                let price_str = match (alert.trail_pct, alert.volume_multiplier) {
+                    (Some(pct), _) => format!("{}% from peak", pct.normalize()),
+                    (_, Some(multiplier)) => format!("{}x baseline", multiplier.normalize()),
+                    _ => alert.target_price
+                        .or(alert.stop_loss_price)
+                        .or(alert.take_profit_price)
+                        .map(|p| format!("${}", p))
+                        .unwrap_or_else(|| "N/A".to_string()),
+                };
                 let id_short = &alert.id.to_string()[..8];
                 text.push_str(&format!(
-                    "🔸 {} {} {}\n   ID: {}\n\n",
+                    "{} {} {} {}\n   ID: {}\n\n",
+                    if alert.paused { "⏸" } else { "🔸" },
                     alert.token_symbol,
                     condition,
                     price_str,
                     id_short
                 ));
+
+                let (label, action) = if alert.paused { ("▶ Resume", "resume") } else { ("⏸ Pause", "pause") };
+                rows.push(vec![
+                    teloxide::types::InlineKeyboardButton::callback(
+                        format!("{} {} {}", label, alert.token_symbol, id_short),
+                        format!("alert:{}:{}", action, alert.id)
+                    ),
+                ]);
             }
+            rows.extend(keyboards::alerts_menu().inline_keyboard);
 
             bot.edit_message_text(chat_id, message_id, text)
-                .reply_markup(keyboards::alerts_menu())
+                .reply_markup(teloxide::types::InlineKeyboardMarkup::new(rows))
                 .await?;
         }
         Err(e) => {
             tracing::error!("Failed to get alerts: {:?}", e);
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load alerts: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to load alerts: {}", e.user_facing_message()))
                 .reply_markup(keyboards::back_to_menu())
                 .await?;
         }
@@ -2152,6 +4801,38 @@ async fn show_alerts(
     Ok(())
 }
 
+async fn toggle_alert_pause(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    alert_id: &str,
+    pause: bool,
+    user_id: &str,
+    state: &Arc<BotState>,
+) -> HandlerResult {
+    let Ok(alert_id) = uuid::Uuid::parse_str(alert_id) else {
+        bot.edit_message_text(chat_id, message_id, "❌ Invalid alert ID")
+            .reply_markup(keyboards::alerts_menu())
+            .await?;
+        return Ok(());
+    };
+
+    let result = if pause {
+        state.price_alert_service.pause_alert(alert_id, user_id).await
+    } else {
+        state.price_alert_service.resume_alert(alert_id, user_id).await
+    };
+
+    if let Err(e) = result {
+        bot.edit_message_text(chat_id, message_id, format!("❌ {}", e.user_facing_message()))
+            .reply_markup(keyboards::alerts_menu())
+            .await?;
+        return Ok(());
+    }
+
+    show_alerts(bot, chat_id, message_id, user_id, state).await
+}
+
 async fn show_save_address_instructions(bot: &Bot, chat_id: ChatId, message_id: MessageId) -> HandlerResult {
     let text = "📝 Save Address\n\n\
 Use the command:\n\
@@ -2288,14 +4969,11 @@ async fn show_alert_value_prompt(
     };
 
     // Set dialogue state
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.insert(user_id, DialogueState::WaitingForAlertValue {
-            token_symbol: symbol.to_string(),
-            chain: chain.to_string(),
-            alert_kind: alert_kind.to_string(),
-        });
-    }
+    state.dialogue_storage.set(user_id, DialogueState::WaitingForAlertValue {
+        token_symbol: symbol.to_string(),
+        chain: chain.to_string(),
+        alert_kind: alert_kind.to_string(),
+    }).await?;
 
     let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
         vec![
@@ -2347,15 +5025,12 @@ Create this alert?",
     );
 
     // Store confirmation state
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.insert(user_id, DialogueState::PendingAlertConfirmation {
-            token_symbol: token_symbol.to_string(),
-            chain: chain.to_string(),
-            alert_kind: alert_kind.to_string(),
-            value,
-        });
-    }
+    state.dialogue_storage.set(user_id, DialogueState::PendingAlertConfirmation {
+        token_symbol: token_symbol.to_string(),
+        chain: chain.to_string(),
+        alert_kind: alert_kind.to_string(),
+        value,
+    }).await?;
 
     let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
         vec![
@@ -2383,13 +5058,10 @@ async fn confirm_create_alert(
     use crate::enums::AlertType;
     use crate::services::price_alert_service::CreateAlertRequest;
 
-    let dialogue_state = {
-        let storage = state.dialogue_storage.read().await;
-        storage.get(&user_id).cloned()
-    };
+    let dialogue_state = state.dialogue_storage.get(user_id).await?;
 
     let (token_symbol, chain, alert_kind, value) = match dialogue_state {
-        Some(DialogueState::PendingAlertConfirmation { token_symbol, chain, alert_kind, value }) => {
+        DialogueState::PendingAlertConfirmation { token_symbol, chain, alert_kind, value } => {
             (token_symbol, chain, alert_kind, value)
         }
         _ => {
@@ -2401,10 +5073,7 @@ async fn confirm_create_alert(
     };
 
     // Clear dialogue state
-    {
-        let mut storage = state.dialogue_storage.write().await;
-        storage.remove(&user_id);
-    }
+    state.dialogue_storage.remove(user_id).await?;
 
     bot.edit_message_text(chat_id, message_id, "⏳ Creating alert...")
         .await?;
@@ -2420,7 +5089,7 @@ async fn confirm_create_alert(
                     base_price: current_price.usd_price,
                 },
                 Err(e) => {
-                    bot.edit_message_text(chat_id, message_id, format!("❌ Could not get current price: {}", e))
+                    bot.edit_message_text(chat_id, message_id, format!("❌ Could not get current price: {}", e.user_facing_message()))
                         .reply_markup(keyboards::alerts_menu())
                         .await?;
                     return Ok(());
@@ -2441,6 +5110,8 @@ async fn confirm_create_alert(
         chain: chain.clone(),
         token_address: None,
         alert_type,
+        auto_execute: false,
+        wallet_id: None,
     };
 
     match state.price_alert_service.create_alert(request).await {
@@ -2470,7 +5141,7 @@ You'll be notified when triggered.",
                 .await?;
         }
         Err(e) => {
-            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to create alert: {}", e))
+            bot.edit_message_text(chat_id, message_id, format!("❌ Failed to create alert: {}", e.user_facing_message()))
                 .reply_markup(keyboards::alerts_menu())
                 .await?;
         }
@@ -2479,6 +5150,151 @@ You'll be notified when triggered.",
     Ok(())
 }
 
+/// Largest address book file accepted for import
+const MAX_IMPORT_FILE_SIZE: u32 = 512 * 1024;
+
+/// Import address book contacts from an uploaded CSV or JSON file
+pub async fn handle_document(
+    bot: Bot,
+    msg: Message,
+    state: Arc<BotState>,
+) -> HandlerResult {
+    let Some(document) = msg.document() else {
+        return Ok(());
+    };
+    let user_id = msg.from.as_ref().map(|u| u.id.0.to_string()).unwrap_or_default();
+    let chat_id = msg.chat.id;
+
+    let file_name = document.file_name.clone().unwrap_or_default().to_lowercase();
+    let mime = document.mime_type.as_ref().map(|m| m.essence_str().to_string()).unwrap_or_default();
+    let is_csv = mime == "text/csv" || file_name.ends_with(".csv");
+    let is_json = mime == "application/json" || file_name.ends_with(".json");
+    // Other uploads (e.g. wallet backups waiting for /restore) aren't ours to handle
+    if !is_csv && !is_json {
+        return Ok(());
+    }
+
+    if document.file.size > MAX_IMPORT_FILE_SIZE {
+        bot.send_message(chat_id, "❌ That file is too large to import.").await?;
+        return Ok(());
+    }
+
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut data = Vec::new();
+    if let Err(e) = teloxide::net::Download::download_file(&bot, &file.path, &mut data).await {
+        bot.send_message(chat_id, format!("❌ Could not download file: {}", e)).await?;
+        return Ok(());
+    }
+
+    let result = if is_csv {
+        state.address_book_service.import_from_csv(&user_id, &data).await
+    } else {
+        state.address_book_service.import_from_json(&user_id, &data).await
+    };
+
+    match result {
+        Ok(result) => {
+            let mut text = format!(
+                "📥 Address Book Import\n\n✅ Imported: {}\n⏭️ Skipped: {}",
+                result.imported,
+                result.skipped
+            );
+            if !result.errors.is_empty() {
+                text.push_str("\n\nProblems:\n");
+                for error in result.errors.iter().take(10) {
+                    text.push_str(&format!("• {}\n", error));
+                }
+                if result.errors.len() > 10 {
+                    text.push_str(&format!("…and {} more\n", result.errors.len() - 10));
+                }
+            }
+            bot.send_message(chat_id, text).await?;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, format!("❌ Import failed: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Symbols looked up per inline query
+const INLINE_MAX_SYMBOLS: usize = 5;
+
+/// Answer `@bot BTC ETH` inline queries with a price card per symbol
+pub async fn handle_inline_query(
+    bot: Bot,
+    q: InlineQuery,
+    state: Arc<BotState>,
+) -> HandlerResult {
+    use teloxide::types::{
+        InlineKeyboardButton,
+        InlineKeyboardMarkup,
+        InlineQueryResult,
+        InlineQueryResultArticle,
+        InputMessageContent,
+        InputMessageContentText,
+    };
+
+    let user_id = q.from.id.0 as i64;
+    // Telegram sends a query per keystroke; drop the excess instead of answering with an error
+    if !state.rate_limiter.check(user_id) {
+        return Ok(());
+    }
+
+    let query = q.query.trim();
+    let symbols: Vec<String> = if query.is_empty() {
+        vec!["BTC".to_string(), "ETH".to_string()]
+    } else {
+        query
+            .split_whitespace()
+            .take(INLINE_MAX_SYMBOLS)
+            .map(|s| s.to_uppercase())
+            .collect()
+    };
+
+    let mut results = Vec::new();
+    for symbol in &symbols {
+        let price = match state.price_service.get_price(symbol).await {
+            Ok(price) => price,
+            Err(e) => {
+                tracing::debug!("Inline price lookup for {} failed: {}", symbol, e);
+                continue;
+            }
+        };
+
+        let change_text = match price.price_change_24h {
+            Some(change) => format!("{} {:+.2}%", if change >= 0.0 { "📈" } else { "📉" }, change),
+            None => "—".to_string(),
+        };
+        let text = format!(
+            "{} {}\n\n💵 Price: ${:.2}\n24h: {}",
+            chain_emoji(symbol),
+            symbol,
+            price.usd_price,
+            change_text
+        );
+
+        let mut article = InlineQueryResultArticle::new(
+            format!("price:{}", symbol),
+            format!("{} ${:.2}", symbol, price.usd_price),
+            InputMessageContent::Text(InputMessageContentText::new(text))
+        ).description(format!("24h: {}", change_text));
+
+        let chart_url = format!("https://www.tradingview.com/symbols/{}USD/", symbol);
+        if let Ok(url) = reqwest::Url::parse(&chart_url) {
+            article = article.reply_markup(
+                InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::url("📊 View Chart", url)]])
+            );
+        }
+
+        results.push(InlineQueryResult::Article(article));
+    }
+
+    bot.answer_inline_query(q.id, results).cache_time(30).await?;
+    Ok(())
+}
+
 fn chain_emoji(chain: &str) -> &'static str {
     chain.parse::<Chain>().map(|c| c.emoji()).unwrap_or("📍")
 }
@@ -14,6 +14,10 @@ pub enum Command {
         description = "Import existing wallet - Usage: /importwallet <chain> <mnemonic or private key>"
     )] ImportWallet(String),
 
+    #[command(
+        description = "Create an EVM wallet with a custom address - Usage: /vanity <chain> [--prefix <hex>] [--suffix <hex>] [--case]"
+    )] Vanity(String),
+
     #[command(description = "List all your wallets")]
     Wallets,
 
@@ -21,6 +25,10 @@ pub enum Command {
         description = "Check wallet balance - Usage: /balance <wallet_id> [token_address]"
     )] Balance(String),
 
+    #[command(
+        description = "Balance at a past block or date - Usage: /historicalbalance <wallet_id> <block|YYYY-MM-DD> [token_address]"
+    )] HistoricalBalance(String),
+
     #[command(
         description = "Send transaction - Usage: /send <wallet_id> <to_address> <amount> [token_address]"
     )] Send(String),
@@ -37,16 +45,67 @@ pub enum Command {
         description = "View transaction history - Usage: /history <wallet_id> [limit]"
     )] History(String),
 
+    #[command(
+        description = "Speed up a pending transaction - Usage: /speedup <tx_id> [max_fee_gwei]"
+    )] SpeedUp(String),
+
+    #[command(description = "Cancel a pending transaction - Usage: /canceltx <tx_id>")] CancelTx(
+        String,
+    ),
+
+    #[command(
+        description = "Look up any transaction on-chain - Usage: /txstatus <tx_hash> <chain>"
+    )] TxStatus(String),
+
+    #[command(description = "Show block details - Usage: /block <chain> <block_number|latest>")] Block(
+        String,
+    ),
+
+    #[command(description = "Latest block, gas and activity on every configured chain")]
+    NetworkStatus,
+
     #[command(
         description = "Get wallet address with QR code - Usage: /address <wallet_id>"
     )] Address(String),
 
+    #[command(
+        description = "Find your wallets by partial address - Usage: /findwallet <partial_address>"
+    )] FindWallet(String),
+
+    #[command(
+        description = "Merge a near-empty wallet into your main one - Usage: /cleanwallet <wallet_id>"
+    )] CleanWallet(String),
+
     #[command(description = "Show your complete portfolio with USD values")]
     Portfolio,
 
+    #[command(
+        description = "Chart portfolio value over time - Usage: /portfoliohistory [days]"
+    )] PortfolioHistory(String),
+
+    #[command(
+        description = "Compare portfolio returns with ETH and BTC - Usage: /benchmark [days]"
+    )] Benchmark(String),
+
+    #[command(
+        description = "Download realized gains/losses as CSV - Usage: /taxreport <year>"
+    )] TaxReport(String),
+
+    #[command(description = "Network fees paid across your wallets - Usage: /fees [days]")] Fees(
+        String,
+    ),
+
+    #[command(
+        description = "Notify on large balance changes - Usage: /watchbalance <wallet_id> [off]"
+    )] WatchBalance(String),
+
     #[command(description = "Get current cryptocurrency prices")]
     Prices,
 
+    #[command(
+        description = "Candle chart with support and resistance - Usage: /chart <symbol> [1m|5m|15m|1h|4h|1d]"
+    )] Chart(String),
+
     #[command(
         description = "Save address to address book - Usage: /saveaddress <name> <address> <ETH|BSC|SOLANA> [notes]"
     )] SaveAddress(String),
@@ -59,7 +118,7 @@ pub enum Command {
     ),
 
     #[command(
-        description = "Schedule a transaction - Usage: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring]"
+        description = "Schedule a transaction - Usage: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring] [--maxgas <gwei>]"
     )] Schedule(String),
 
     #[command(description = "List scheduled transactions")]
@@ -69,17 +128,74 @@ pub enum Command {
         description = "Cancel scheduled transaction - Usage: /cancelschedule <schedule_id>"
     )] CancelSchedule(String),
 
+    #[command(description = "Upcoming scheduled transactions by day - Usage: /calendar [days]")] Calendar(
+        String,
+    ),
+
+    #[command(
+        description = "Dollar-cost average - Usage: /dca create <wallet_id> <from> <to> <amount> <daily|weekly|monthly>, /dca list, /dca pause|resume|cancel <plan_id>"
+    )] Dca(String),
+
+    #[command(
+        description = "DeFi lending positions with APY and earned yield - Usage: /defi <wallet_id>"
+    )] Defi(String),
+
     #[command(
         description = "Set price alert - Usage: /setalert <symbol> <above|below> <price> [chain]"
     )] SetAlert(String),
 
-    #[command(description = "List your price alerts")]
-    Alerts,
+    #[command(
+        description = "Set stop-loss - Usage: /setstoploss <symbol> <price> [chain] [wallet_id]"
+    )] SetStopLoss(String),
+
+    #[command(
+        description = "Set take-profit - Usage: /settakeprofit <symbol> <price> [chain]"
+    )] SetTakeProfit(String),
+
+    #[command(
+        description = "Set trailing stop - Usage: /settrailstop <symbol> <trail_pct> [chain]"
+    )] SetTrailStop(String),
+
+    #[command(
+        description = "Alert on a 24h volume spike - Usage: /setvolumealert <symbol> <multiplier> [chain]"
+    )] SetVolumeAlert(String),
+
+    #[command(
+        description = "Alert on large pending incoming transfers - Usage: /setmempool <wallet_id> <threshold_usd|off>"
+    )] SetMempool(String),
+
+    #[command(
+        description = "Use a private RPC node for a wallet - Usage: /setwrpc <wallet_id> <url|off>"
+    )] SetWrpc(String),
+
+    #[command(
+        description = "Set gas alert - Usage: /gasalert <chain> <max_gwei>"
+    )] GasAlert(String),
+
+    #[command(description = "List your alerts - Usage: /alerts [gas]", aliases = ["listalerts"])] Alerts(
+        String,
+    ),
 
     #[command(description = "Delete price alert - Usage: /deletealert <alert_id>")] DeleteAlert(
         String,
     ),
 
+    #[command(description = "Pause a price alert - Usage: /pausealert <alert_id>")] PauseAlert(
+        String,
+    ),
+
+    #[command(description = "Resume a paused price alert - Usage: /resumealert <alert_id>")] ResumeAlert(
+        String,
+    ),
+
+    #[command(
+        description = "Delete gas alert - Usage: /deletegasalert <alert_id>"
+    )] DeleteGasAlert(String),
+
+    #[command(
+        description = "Set target allocation - Usage: /setallocation <symbol> <target_pct> [threshold_pct]"
+    )] SetAllocation(String),
+
     #[command(description = "Set transaction PIN - Usage: /setpin <6-digit-pin>")] SetPin(String),
 
     #[command(description = "Change your PIN - Usage: /changepin <old-pin> <new-pin>")] ChangePin(
@@ -93,6 +209,13 @@ pub enum Command {
         description = "Set withdrawal limits - Usage: /setlimit daily <amount> or weekly <amount>"
     )] SetLimit(String),
 
+    #[command(
+        description = "Cap the USD value of a single send - Usage: /setmaxsend <amount_usd|off>"
+    )] SetMaxSend(String),
+
+    #[command(description = "View your withdrawal and per-send limits")]
+    GetLimits,
+
     #[command(description = "Lock wallet (requires PIN to unlock)")]
     LockWallet,
 
@@ -101,10 +224,33 @@ pub enum Command {
     #[command(description = "View security settings")]
     Security,
 
+    #[command(description = "Run a security audit of your account")]
+    Audit,
+
+    #[command(description = "Create new wallets on testnets - Usage: /testnet <on|off>")] Testnet(
+        String,
+    ),
+
+    #[command(
+        description = "Show prices in another fiat currency - Usage: /setcurrency <EUR|GBP|JPY|...>"
+    )] SetCurrency(String),
+
+    #[command(description = "Export encrypted wallet backup - Usage: /backup <password>")] Backup(
+        String,
+    ),
+
+    #[command(
+        description = "Restore wallets from backup - Usage: reply to a backup file with /restore <password>"
+    )] Restore(String),
+
     #[command(
         description = "Swap tokens - Usage: /swap <wallet_id> <from_token> <to_token> <amount> [slippage]"
     )] Swap(String),
 
+    #[command(
+        description = "Batch swap - Usage: /batchswap <wallet_id> then one FROM TO AMOUNT per line"
+    )] BatchSwap(String),
+
     #[command(
         description = "Get swap quote - Usage: /swapquote <chain> <from_token> <to_token> <amount> [slippage]"
     )] SwapQuote(String),
@@ -113,6 +259,15 @@ pub enum Command {
         String,
     ),
 
+    #[command(description = "Make a group member an admin of the group wallets (group owner only) - Usage: /setgroupadmin @username")]
+    SetGroupAdmin(String),
+
     #[command(description = "Show help message")]
     Help,
+
+    #[command(hide, description = "Run all price alerts now (admin only)")]
+    CheckAlerts,
+
+    #[command(hide, description = "Send an announcement to all users (admin only) - Usage: /broadcast <message>")]
+    Broadcast(String),
 }
@@ -4,8 +4,12 @@ pub mod command_descriptions {
     pub const CREATE_WALLET: &str = "Create a new wallet - Usage: /createwallet <chain>";
     pub const IMPORT_WALLET: &str =
         "Import existing wallet - Usage: /importwallet <chain> <mnemonic or private key>";
+    pub const VANITY: &str =
+        "Create an EVM wallet with a custom address - Usage: /vanity <chain> [--prefix <hex>] [--suffix <hex>] [--case]";
     pub const WALLETS: &str = "List all your wallets";
     pub const BALANCE: &str = "Check wallet balance - Usage: /balance <wallet_id> [token_address]";
+    pub const HISTORICAL_BALANCE: &str =
+        "Balance at a past block or date - Usage: /historicalbalance <wallet_id> <block|YYYY-MM-DD> [token_address]";
     pub const SEND: &str =
         "Send transaction - Usage: /send <wallet_id> <to_address> <amount> [token_address]";
     pub const ESTIMATE_FEE: &str =
@@ -13,36 +17,89 @@ pub mod command_descriptions {
     pub const BATCH_SEND: &str =
         "Batch send - Usage: /batchsend <wallet_id> then paste CSV (to,amount)";
     pub const HISTORY: &str = "View transaction history - Usage: /history <wallet_id> [limit]";
+    pub const TX_STATUS: &str =
+        "Look up any transaction on-chain - Usage: /txstatus <tx_hash> <chain>";
+    pub const BLOCK: &str = "Show block details - Usage: /block <chain> <block_number|latest>";
+    pub const NETWORK_STATUS: &str = "Latest block, gas and activity on every configured chain";
     pub const ADDRESS: &str = "Get wallet address with QR code - Usage: /address <wallet_id>";
+    pub const FIND_WALLET: &str =
+        "Find your wallets by partial address - Usage: /findwallet <partial_address>";
+    pub const CLEAN_WALLET: &str =
+        "Merge a near-empty wallet into your main one - Usage: /cleanwallet <wallet_id>";
     pub const PORTFOLIO: &str = "Show your complete portfolio with USD values";
+    pub const PORTFOLIO_HISTORY: &str =
+        "Chart portfolio value over time - Usage: /portfoliohistory [days]";
+    pub const BENCHMARK: &str =
+        "Compare portfolio returns with ETH and BTC - Usage: /benchmark [days]";
+    pub const TAX_REPORT: &str =
+        "Download realized gains/losses as CSV - Usage: /taxreport <year>";
+    pub const FEES: &str = "Network fees paid across your wallets - Usage: /fees [days]";
+    pub const WATCH_BALANCE: &str =
+        "Notify on large balance changes - Usage: /watchbalance <wallet_id> [off]";
     pub const PRICES: &str = "Get current cryptocurrency prices";
+    pub const CHART: &str =
+        "Candle chart with support and resistance - Usage: /chart <symbol> [1m|5m|15m|1h|4h|1d]";
     pub const SAVE_ADDRESS: &str =
         "Save address to address book - Usage: /saveaddress <name> <address> <chain> [notes]";
     pub const ADDRESSES: &str = "List all saved addresses";
     pub const DELETE_ADDRESS: &str = "Delete saved address - Usage: /deleteaddress <name>";
     pub const SCHEDULE: &str =
-        "Schedule a transaction - Usage: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring]";
+        "Schedule a transaction - Usage: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring] [--maxgas <gwei>]";
     pub const SCHEDULED: &str = "List scheduled transactions";
     pub const CANCEL_SCHEDULE: &str =
         "Cancel scheduled transaction - Usage: /cancelschedule <schedule_id>";
+    pub const CALENDAR: &str = "Upcoming scheduled transactions by day - Usage: /calendar [days]";
+    pub const DCA: &str =
+        "Dollar-cost average - Usage: /dca create <wallet_id> <from> <to> <amount> <daily|weekly|monthly>, /dca list, /dca pause|resume|cancel <plan_id>";
+    pub const DEFI: &str =
+        "DeFi lending positions with APY and earned yield - Usage: /defi <wallet_id>";
     pub const SET_ALERT: &str =
         "Set price alert - Usage: /setalert <symbol> <above|below> <price> [chain]";
-    pub const ALERTS: &str = "List your price alerts";
+    pub const SET_TRAIL_STOP: &str = "Set trailing stop - Usage: /settrailstop <symbol> <trail_pct> [chain]";
+    pub const SET_VOLUME_ALERT: &str =
+        "Alert on a 24h volume spike - Usage: /setvolumealert <symbol> <multiplier> [chain]";
+    pub const SET_MEMPOOL: &str =
+        "Alert on large pending incoming transfers - Usage: /setmempool <wallet_id> <threshold_usd|off>";
+    pub const SET_WRPC: &str =
+        "Use a private RPC node for a wallet - Usage: /setwrpc <wallet_id> <url|off>";
+    pub const GAS_ALERT: &str = "Set gas alert - Usage: /gasalert <chain> <max_gwei>";
+    pub const ALERTS: &str = "List your alerts - Usage: /alerts [gas]";
     pub const DELETE_ALERT: &str = "Delete price alert - Usage: /deletealert <alert_id>";
+    pub const PAUSE_ALERT: &str = "Pause a price alert - Usage: /pausealert <alert_id>";
+    pub const RESUME_ALERT: &str = "Resume a paused price alert - Usage: /resumealert <alert_id>";
+    pub const DELETE_GAS_ALERT: &str = "Delete gas alert - Usage: /deletegasalert <alert_id>";
+    pub const SET_ALLOCATION: &str =
+        "Set target allocation - Usage: /setallocation <symbol> <target_pct> [threshold_pct]";
     pub const SET_PIN: &str = "Set transaction PIN - Usage: /setpin <6-digit-pin>";
     pub const CHANGE_PIN: &str = "Change your PIN - Usage: /changepin <old-pin> <new-pin>";
     pub const DISABLE_PIN: &str = "Disable PIN protection";
     pub const SET_LIMIT: &str =
         "Set withdrawal limits - Usage: /setlimit daily <amount> or weekly <amount>";
+    pub const SET_MAX_SEND: &str =
+        "Cap the USD value of a single send - Usage: /setmaxsend <amount_usd|off>";
+    pub const GET_LIMITS: &str = "View your withdrawal and per-send limits";
     pub const LOCK_WALLET: &str = "Lock wallet (requires PIN to unlock)";
     pub const UNLOCK_WALLET: &str = "Unlock wallet - Usage: /unlock <pin>";
     pub const SECURITY: &str = "View security settings";
+    pub const AUDIT: &str = "Run a security audit of your account";
+    pub const TESTNET: &str = "Create new wallets on testnets - Usage: /testnet <on|off>";
+    pub const SET_CURRENCY: &str = "Show prices in another fiat currency - Usage: /setcurrency <EUR|GBP|JPY|...>";
+    pub const BACKUP: &str = "Export encrypted wallet backup - Usage: /backup <password>";
+    pub const RESTORE: &str =
+        "Restore wallets from backup - Usage: reply to a backup file with /restore <password>";
     pub const SWAP: &str =
         "Swap tokens - Usage: /swap <wallet_id> <from_token> <to_token> <amount> [slippage]";
+    pub const BATCH_SWAP: &str =
+        "Batch swap - Usage: /batchswap <wallet_id> then one FROM TO AMOUNT per line";
     pub const SWAP_QUOTE: &str =
         "Get swap quote - Usage: /swapquote <chain> <from_token> <to_token> <amount> [slippage]";
     pub const SWAP_HISTORY: &str = "View swap history - Usage: /swaphistory [wallet_id]";
+    pub const SET_GROUP_ADMIN: &str =
+        "Make a group member an admin of the group wallets (group owner only) - Usage: /setgroupadmin @username";
     pub const HELP: &str = "Show help message";
+    pub const CHECK_ALERTS: &str = "Run all price alerts now (admin only)";
+    pub const BROADCAST: &str =
+        "Send an announcement to all users (admin only) - Usage: /broadcast <message>";
 }
 
 // Bot messages
@@ -85,6 +142,12 @@ Use /help to see all available commands\.
         `/address <wallet_id>` \\- Get wallet address\n\
           Example: `/address abc123`\n\n\
         `/portfolio` \\- View your complete portfolio\n\n\
+        `/portfoliohistory [days]` \\- Chart portfolio value over time\n\
+          Example: `/portfoliohistory 30`\n\n\
+        `/benchmark [days]` \\- Compare returns with ETH and BTC\n\
+          Example: `/benchmark 30`\n\n\
+        `/taxreport <year>` \\- Realized gains/losses CSV \\(FIFO\\)\n\
+          Example: `/taxreport 2024`\n\n\
         `/prices` \\- Get current crypto prices\n\n\
         `/saveaddress <name> <addr> <chain>` \\- Save address\n\
           Example: `/saveaddress alice 0x\\.\\.\\. ETH`\n\n\
@@ -100,7 +163,23 @@ Use /help to see all available commands\.
     pub const ERR_INVALID_WALLET_ID: &str = "❌ Invalid wallet ID format";
     pub const ERR_IMPORT_USAGE: &str =
         "❌ Usage: /importwallet <chain> <mnemonic or private key>\nExample: /importwallet ETH word1 word2 word3...";
+    pub const ERR_VANITY_USAGE: &str =
+        "❌ Usage: /vanity <chain> [--prefix <hex>] [--suffix <hex>] [--case]\nExample: /vanity ETH --prefix 0xDEAD --suffix BEEF\nEach extra character makes the search 16x longer; 4 or fewer is practical.";
+    pub const STATUS_VANITY_SEARCH: &str = "🎰 Searching for a matching address...";
+    pub const ERR_CHART_USAGE: &str =
+        "❌ Usage: /chart <symbol> [interval]\nIntervals: 1m, 5m, 15m, 1h, 4h, 1d (default 1h)\nExample: /chart ETH 4h";
+    pub const ERR_FEES_USAGE: &str = "❌ Days must be a number between 1 and 365\n\nUsage: /fees [days]";
+    pub const ERR_WATCH_BALANCE_USAGE: &str =
+        "❌ Usage: /watchbalance <wallet_id> [off]\nMessages you when the wallet's balance moves by more than the configured percentage.";
+    pub const ERR_BLOCK_USAGE: &str =
+        "❌ Usage: /block <chain> <block_number|latest>\n\nExample: /block ETH latest";
+    pub const ERR_DEFI_USAGE: &str =
+        "❌ Usage: /defi <wallet_id>\nShows Aave and Compound supply positions of an EVM wallet.";
     pub const ERR_BALANCE_USAGE: &str = "❌ Usage: /balance <wallet_id> [token_address]";
+    pub const ERR_CLEAN_WALLET_USAGE: &str =
+        "❌ Usage: /cleanwallet <wallet_id>\nMerges a wallet worth less than $1 into your main wallet on the same chain.";
+    pub const ERR_HISTORICAL_BALANCE_USAGE: &str =
+        "❌ Usage: /historicalbalance <wallet_id> <block|YYYY-MM-DD> [token_address]\nExample: /historicalbalance abc123 2024-12-31";
     pub const ERR_SEND_USAGE: &str =
         "❌ Usage: /send <wallet_id> <to_address|name> <amount> [token_address]\n\
             You can use saved address names instead of full addresses!";
@@ -118,20 +197,42 @@ Use /help to see all available commands\.
     pub const ERR_SCHEDULE_USAGE: &str =
         "❌ Usage: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring]\nExample: /schedule abc123 0x742d... 0.1 2024-12-31T23:59:00 - daily";
     pub const ERR_CANCEL_SCHEDULE_USAGE: &str = "❌ Usage: /cancelschedule <schedule_id>";
+    pub const ERR_CALENDAR_USAGE: &str = "❌ Usage: /calendar [days]\nDays must be between 1 and 90. Example: /calendar 30";
     pub const ERR_SET_ALERT_USAGE: &str =
         "❌ Usage: /setalert <symbol> <above|below> <price> [chain]\nExample: /setalert BTC above 100000 ETH";
     pub const ERR_DELETE_ALERT_USAGE: &str = "❌ Usage: /deletealert <alert_id>";
+    pub const ERR_BROADCAST_USAGE: &str = "❌ Usage: /broadcast <message>";
+    pub const ERR_SET_GROUP_ADMIN_USAGE: &str = "❌ Usage: /setgroupadmin @username";
+    pub const ERR_PRIVATE_ONLY: &str =
+        "🔒 This command handles keys or passwords, so it only works in a private chat with me.";
+    pub const ERR_START_PRIVATE_CHAT: &str =
+        "🔒 I send mnemonics privately. Start a private chat with me first, then run the command again.";
+    pub const ERR_PAUSE_ALERT_USAGE: &str = "❌ Usage: /pausealert <alert_id>";
+    pub const ERR_RESUME_ALERT_USAGE: &str = "❌ Usage: /resumealert <alert_id>";
+    pub const ERR_SET_TRAIL_STOP_USAGE: &str =
+        "❌ Usage: /settrailstop <symbol> <trail_pct> [chain]\nExample: /settrailstop ETH 10";
+    pub const ERR_SET_VOLUME_ALERT_USAGE: &str =
+        "❌ Usage: /setvolumealert <symbol> <multiplier> [chain]\nExample: /setvolumealert BTC 3";
+    pub const ERR_GAS_ALERT_USAGE: &str =
+        "❌ Usage: /gasalert <chain> <max_gwei>\nExample: /gasalert ETH 15";
+    pub const ERR_DELETE_GAS_ALERT_USAGE: &str = "❌ Usage: /deletegasalert <alert_id>";
+    pub const ERR_SET_MEMPOOL_USAGE: &str =
+        "❌ Usage: /setmempool <wallet_id> <threshold_usd|off>\nExample: /setmempool abc123 5000";
+    pub const ERR_SET_WRPC_USAGE: &str =
+        "❌ Usage: /setwrpc <wallet_id> <url|off>\nExample: /setwrpc abc123 https://my-node.example.com";
     pub const ERR_SET_PIN_USAGE: &str = "❌ Usage: /setpin <6-digit-pin>\nExample: /setpin 123456";
     pub const ERR_CHANGE_PIN_USAGE: &str =
         "❌ Usage: /changepin <old-pin> <new-pin>\nExample: /changepin 123456 654321";
     pub const ERR_SET_LIMIT_USAGE: &str =
         "❌ Usage: /setlimit daily <amount> OR /setlimit weekly <amount>\nExample: /setlimit daily 1000";
     pub const ERR_UNLOCK_USAGE: &str = "❌ Usage: /unlock <pin>";
+    pub const ERR_SET_CURRENCY_USAGE: &str =
+        "❌ Usage: /setcurrency <currency>\nExample: /setcurrency EUR";
     pub const ERR_SWAP_USAGE: &str =
         "❌ Usage: /swap <wallet_id> <from_token> <to_token> <amount> [slippage]\n\
             Example: /swap abc123 USDC SOL 100 1.0\n\
-            Tokens: Use contract address or native (ETH/BNB/SOL)\n\
-            Slippage: Optional, default 1.0%";
+            Tokens: Symbol, contract address or native (ETH/BNB/SOL)\n\
+            Slippage: Optional, defaults to your last used slippage (0.5% at first)";
     pub const ERR_SWAP_QUOTE_USAGE: &str =
         "❌ Usage: /swapquote <chain> <from_token> <to_token> <amount> [slippage]\n\
             Example: /swapquote ETH USDC ETH 1000 1.0";
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveValue,
+    ColumnTrait,
+    DatabaseConnection,
+    EntityTrait,
+    QueryFilter,
+    sea_query::OnConflict,
+};
+use tokio::sync::RwLock;
+
+use super::DialogueState;
+use crate::db::entity::bot_dialogue_state;
+use crate::error::{ AppError, Result };
+
+/// Dialogues left untouched this long are dropped
+const DIALOGUE_TTL_SECS: i64 = 60 * 60;
+
+/// Where each user's in-progress bot dialogue is kept between messages
+#[async_trait]
+pub trait PersistentDialogueStorage: Send + Sync {
+    /// The user's current dialogue, `DialogueState::None` when there is none
+    async fn get(&self, user_id: i64) -> Result<DialogueState>;
+
+    async fn set(&self, user_id: i64, state: DialogueState) -> Result<()>;
+
+    async fn remove(&self, user_id: i64) -> Result<()>;
+}
+
+/// Process-local storage; dialogues are lost on restart
+#[derive(Default)]
+pub struct InMemoryDialogueStorage {
+    states: RwLock<HashMap<i64, DialogueState>>,
+}
+
+impl InMemoryDialogueStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PersistentDialogueStorage for InMemoryDialogueStorage {
+    async fn get(&self, user_id: i64) -> Result<DialogueState> {
+        Ok(self.states.read().await.get(&user_id).cloned().unwrap_or_default())
+    }
+
+    async fn set(&self, user_id: i64, state: DialogueState) -> Result<()> {
+        self.states.write().await.insert(user_id, state);
+        Ok(())
+    }
+
+    async fn remove(&self, user_id: i64) -> Result<()> {
+        self.states.write().await.remove(&user_id);
+        Ok(())
+    }
+}
+
+/// Dialogues stored in `bot_dialogue_states` so they survive restarts
+pub struct DatabaseDialogueStorage {
+    db: DatabaseConnection,
+}
+
+impl DatabaseDialogueStorage {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl PersistentDialogueStorage for DatabaseDialogueStorage {
+    async fn get(&self, user_id: i64) -> Result<DialogueState> {
+        let Some(row) = bot_dialogue_state::Entity::find_by_id(user_id).one(&self.db).await? else {
+            return Ok(DialogueState::None);
+        };
+
+        if row.expires_at <= chrono::Utc::now() {
+            self.remove(user_id).await?;
+            return Ok(DialogueState::None);
+        }
+
+        match serde_json::from_value(row.state_json) {
+            Ok(state) => Ok(state),
+            Err(e) => {
+                // A state written by an older build whose shape no longer matches
+                tracing::warn!("Discarding unreadable dialogue state for user {}: {}", user_id, e);
+                self.remove(user_id).await?;
+                Ok(DialogueState::None)
+            }
+        }
+    }
+
+    async fn set(&self, user_id: i64, state: DialogueState) -> Result<()> {
+        if matches!(state, DialogueState::None) {
+            return self.remove(user_id).await;
+        }
+
+        let state_json = serde_json
+            ::to_value(&state)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize dialogue state: {}", e)))?;
+        let now = chrono::Utc::now();
+
+        let row = bot_dialogue_state::ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            state_json: ActiveValue::Set(state_json),
+            expires_at: ActiveValue::Set(now + chrono::Duration::seconds(DIALOGUE_TTL_SECS)),
+            updated_at: ActiveValue::Set(now),
+        };
+
+        bot_dialogue_state::Entity
+            ::insert(row)
+            .on_conflict(
+                OnConflict::column(bot_dialogue_state::Column::UserId)
+                    .update_columns([
+                        bot_dialogue_state::Column::StateJson,
+                        bot_dialogue_state::Column::ExpiresAt,
+                        bot_dialogue_state::Column::UpdatedAt,
+                    ])
+                    .to_owned()
+            )
+            .exec(&self.db).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, user_id: i64) -> Result<()> {
+        bot_dialogue_state::Entity
+            ::delete_many()
+            .filter(bot_dialogue_state::Column::UserId.eq(user_id))
+            .exec(&self.db).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialogue_state_json_roundtrip() {
+        let state = DialogueState::WaitingForSendAddress {
+            wallet_id: "abc".to_string(),
+            amount: "0.5".to_string(),
+            symbol: "ETH".to_string(),
+            send_max: false,
+            amount_usd_estimate: Some(1500.0),
+        };
+
+        let json = serde_json::to_value(&state).unwrap();
+        let restored: DialogueState = serde_json::from_value(json).unwrap();
+        assert!(
+            matches!(
+                restored,
+                DialogueState::WaitingForSendAddress { ref amount, amount_usd_estimate: Some(usd), .. }
+                    if amount == "0.5" && usd == 1500.0
+            )
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use teloxide::prelude::*;
+use teloxide::types::User;
+
+use crate::enums::GroupRole;
+use crate::error::{ AppError, Result };
+
+use super::{ commands::Command, BotState };
+
+/// Role of `user` in the group chat. The first time a member uses the bot there they are
+/// recorded: the group's Telegram owner as `Owner`, everyone else as `Viewer`.
+pub(super) async fn member_role(bot: &Bot, state: &BotState, chat_id: ChatId, user: &User) -> Result<GroupRole> {
+    let user_id = user.id.0 as i64;
+    let username = user.username.as_deref();
+    if let Some(role) = state.group_permission_service.role_of(chat_id.0, user_id, username).await? {
+        return Ok(role);
+    }
+
+    let member = bot
+        .get_chat_member(chat_id, user.id).await
+        .map_err(|e| AppError::External(format!("Failed to look up group member: {}", e)))?;
+    let role = if member.is_owner() { GroupRole::Owner } else { GroupRole::Viewer };
+
+    state.group_permission_service.add_member(chat_id.0, user_id, username, role).await
+}
+
+/// Whether `role` is at least `required`
+pub(super) fn satisfies(role: GroupRole, required: GroupRole) -> bool {
+    match required {
+        GroupRole::Viewer => true,
+        GroupRole::Admin => role.can_transact(),
+        GroupRole::Owner => role == GroupRole::Owner,
+    }
+}
+
+/// Lowest role allowed to run `cmd` in a group. Anything not known to be read-only needs an
+/// admin, so new commands are locked down until listed here.
+pub(super) fn required_role(cmd: &Command) -> GroupRole {
+    match cmd {
+        Command::SetGroupAdmin(_) => GroupRole::Owner,
+        | Command::Start
+        | Command::Help
+        | Command::Wallets
+        | Command::Balance(_)
+        | Command::HistoricalBalance(_)
+        | Command::Address(_)
+        | Command::FindWallet(_)
+        | Command::History(_)
+        | Command::TxStatus(_)
+        | Command::Block(_)
+        | Command::NetworkStatus
+        | Command::EstimateFee(_)
+        | Command::Portfolio
+        | Command::PortfolioHistory(_)
+        | Command::Benchmark(_)
+        | Command::Fees(_)
+        | Command::Prices
+        | Command::Chart(_)
+        | Command::Addresses
+        | Command::Scheduled
+        | Command::Calendar(_)
+        | Command::Alerts(_)
+        | Command::SwapQuote(_)
+        | Command::SwapHistory(_) => GroupRole::Viewer,
+        _ => GroupRole::Admin,
+    }
+}
+
+/// Commands that carry or return keys and passwords, which must never appear in a group
+pub(super) fn private_only(cmd: &Command) -> bool {
+    matches!(
+        cmd,
+        | Command::ImportWallet(_)
+        | Command::Backup(_)
+        | Command::Restore(_)
+        | Command::SetPin(_)
+        | Command::ChangePin(_)
+        | Command::UnlockWallet(_)
+    )
+}
+
+/// Lowest role allowed to press a button, by its callback data split on `:`
+pub(super) fn required_callback_role(parts: &[&str]) -> GroupRole {
+    match parts {
+        | ["menu", "main" | "wallets" | "wallet_stats" | "portfolio" | "prices" | "help"]
+        | ["wallet", "select" | "balance" | "history" | "received" | "qr" | "receive" | "explorer", ..]
+        | ["wallet", "tokens" | "nfts" | "approvals", ..]
+        | ["tx", "view", _]
+        | ["help", ..]
+        | ["prices", ..]
+        | ["refresh", ..]
+        | ["portfolio", ..]
+        | ["swaphist", ..]
+        | ["noop"]
+        | ["cancel"] => GroupRole::Viewer,
+        _ => GroupRole::Admin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewers_can_look_but_not_send() {
+        assert!(satisfies(GroupRole::Viewer, required_role(&Command::Balance(String::new()))));
+        assert!(!satisfies(GroupRole::Viewer, required_role(&Command::Send(String::new()))));
+        assert!(satisfies(GroupRole::Admin, required_role(&Command::Send(String::new()))));
+        assert!(!satisfies(GroupRole::Viewer, required_callback_role(&["wallet", "send", "id"])));
+        assert!(satisfies(GroupRole::Viewer, required_callback_role(&["wallet", "balance", "id"])));
+    }
+
+    #[test]
+    fn only_owner_grants_admins() {
+        let grant = required_role(&Command::SetGroupAdmin("@alice".to_string()));
+        assert!(satisfies(GroupRole::Owner, grant));
+        assert!(!satisfies(GroupRole::Admin, grant));
+    }
+
+    #[test]
+    fn secrets_stay_out_of_groups() {
+        assert!(private_only(&Command::ImportWallet("eth abandon".to_string())));
+        assert!(private_only(&Command::Backup("hunter22".to_string())));
+        assert!(!private_only(&Command::CreateWallet("eth".to_string())));
+    }
+}
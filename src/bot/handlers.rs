@@ -1,11 +1,13 @@
 use teloxide::prelude::*;
 use teloxide::types::ParseMode;
-use crate::bot::{ BotState, commands::Command, keyboards };
+use crate::bot::{ BotState, commands::Command, group, keyboards };
 use super::constants::{ messages as msg, chains };
-use crate::enums::{ Chain, AlertType, AlertKind, RecurringType, ScheduleStatus, TxStatus };
+use crate::enums::{ Chain, AlertType, AlertKind, DcaStatus, DefiProtocol, GroupRole, RecurringType, ScheduleStatus, TxStatus };
 use crate::services::*;
-use crate::services::scheduling_service::{ SchedulingService, ScheduleRequest };
+use crate::services::scheduling_service::{ SchedulingService, ScheduleRequest, ScheduleWarningSeverity };
 use crate::services::price_alert_service;
+use crate::services::gas_estimation_service::format_confirmation_time;
+use crate::services::user_preference_service::{ DEFAULT_FIAT_CURRENCY, SUPPORTED_FIAT_CURRENCIES };
 use uuid::Uuid;
 use std::sync::Arc;
 
@@ -16,12 +18,87 @@ pub async fn handle_command_dispatch(
     cmd: Command,
     state: Arc<BotState>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0);
+    if !state.rate_limiter.check_cost(user_id, command_cost(&cmd)) {
+        bot.send_message(msg.chat.id, "⏱️ Too many requests. Please wait a moment.").await?;
+        return Ok(());
+    }
+
+    // In groups the wallets belong to the chat, so members need a role for anything beyond viewing
+    if !msg.chat.is_private() {
+        if !state.config.group_wallet_mode {
+            return Ok(());
+        }
+        // Anonymous admins and channel posts have no member to check
+        let Some(from) = msg.from.as_ref() else {
+            return Ok(());
+        };
+        let role = match group::member_role(&bot, &state, msg.chat.id, from).await {
+            Ok(role) => role,
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+                return Ok(());
+            }
+        };
+        let required = group::required_role(&cmd);
+        if !group::satisfies(role, required) {
+            bot.send_message(
+                msg.chat.id,
+                format!("🔒 This command needs the {} role in this group. Your role: {}.", required, role)
+            ).await?;
+            return Ok(());
+        }
+        if group::private_only(&cmd) {
+            // The arguments may hold a key or password; take them out of the group's history
+            let _ = bot.delete_message(msg.chat.id, msg.id).await;
+            bot.send_message(msg.chat.id, msg::ERR_PRIVATE_ONLY).await?;
+            return Ok(());
+        }
+    }
+
+    if let Some((wallet_id, action)) = command_wallet_target(&cmd) {
+        if let Err(e) = state.user_session_service.update_session(user_id, wallet_id, action).await {
+            tracing::warn!("Failed to update session for user {}: {}", user_id, e);
+        }
+    }
+
     handle_command(bot, msg, cmd, state).await?;
     Ok(())
 }
 
+/// Wallet a command acts on and the session action it counts as
+fn command_wallet_target(cmd: &Command) -> Option<(Uuid, &'static str)> {
+    let (args, action) = match cmd {
+        Command::Balance(args) | Command::HistoricalBalance(args) | Command::WatchBalance(args) =>
+            (args, "balance"),
+        Command::Send(args) => (args, "send"),
+        Command::History(args) => (args, "history"),
+        Command::Address(args) => (args, "receive"),
+        _ => {
+            return None;
+        }
+    };
+    let wallet_id = Uuid::parse_str(args.split_whitespace().next()?).ok()?;
+    Some((wallet_id, action))
+}
+
+/// Rate limit tokens a command consumes; commands hitting external APIs or the chain cost more
+fn command_cost(cmd: &Command) -> f64 {
+    match cmd {
+        Command::Send(_) | Command::BatchSend(_) | Command::Swap(_) | Command::BatchSwap(_) => 3.0,
+        Command::Backup(_) | Command::Restore(_) => 3.0,
+        Command::Vanity(_) => 5.0,
+        Command::Prices | Command::Portfolio | Command::PortfolioHistory(_) => 2.0,
+        Command::Chart(_) | Command::Defi(_) => 2.0,
+        Command::NetworkStatus => 2.0,
+        Command::Benchmark(_) => 2.0,
+        Command::TaxReport(_) | Command::Fees(_) => 2.0,
+        _ => 1.0,
+    }
+}
+
 // Helper function to format numbers with thousand separators
-fn format_currency(value: f64) -> String {
+pub(super) fn format_currency(value: f64) -> String {
     let formatted = format!("{:.2}", value);
     let parts: Vec<&str> = formatted.split('.').collect();
     let int_part = parts[0];
@@ -38,6 +115,38 @@ fn format_currency(value: f64) -> String {
     format!("{}.{}", result, dec_part)
 }
 
+/// Format a fiat amount with its currency sign, e.g. "€1,234.50" or "CHF 1,234.50"
+pub(super) fn format_fiat(value: f64, currency: &str) -> String {
+    match currency {
+        "USD" => format!("${}", format_currency(value)),
+        "EUR" => format!("€{}", format_currency(value)),
+        "GBP" => format!("£{}", format_currency(value)),
+        "JPY" | "CNY" => format!("¥{}", format_currency(value)),
+        "INR" => format!("₹{}", format_currency(value)),
+        "KRW" => format!("₩{}", format_currency(value)),
+        _ => format!("{} {}", currency, format_currency(value)),
+    }
+}
+
+/// The user's display currency and its rate per USD, falling back to USD if either lookup fails
+pub(super) async fn user_fiat(state: &BotState, user_id: &str) -> (&'static str, f64) {
+    let currency = state.user_preference_service
+        .get_fiat_currency(user_id).await
+        .unwrap_or(DEFAULT_FIAT_CURRENCY);
+    match state.price_service.get_exchange_rate(currency).await {
+        Ok(rate) => (currency, rate),
+        Err(e) => {
+            tracing::warn!("No {} exchange rate, showing USD: {}", currency, e);
+            (DEFAULT_FIAT_CURRENCY, 1.0)
+        }
+    }
+}
+
+/// Commands only `ADMIN_TELEGRAM_USER_ID` may run
+fn is_admin_command(cmd: &Command) -> bool {
+    matches!(cmd, Command::CheckAlerts | Command::Broadcast(_))
+}
+
 pub async fn handle_command(
     bot: Bot,
     msg: Message,
@@ -47,52 +156,117 @@ pub async fn handle_command(
     let chat_id = msg.chat.id;
     let user_id = chat_id.0.to_string();
 
+    if is_admin_command(&cmd) {
+        let sender_id = msg.from.as_ref().map(|u| u.id.0 as i64);
+        if sender_id.is_none() || sender_id != state.config.admin_telegram_user_id {
+            bot.send_message(chat_id, "❌ This command is only available to the bot admin.").await?;
+            return Ok(());
+        }
+    }
+
     match cmd {
-        Command::Start => handle_start(bot, msg).await,
+        Command::Start => handle_start(bot, msg, user_id, state).await,
         Command::Help => handle_help(bot, msg).await,
+        Command::CheckAlerts => handle_check_alerts(bot, msg, state).await,
+        Command::Broadcast(args) => handle_broadcast(bot, msg, args, state).await,
+        Command::SetGroupAdmin(args) => handle_set_group_admin(bot, msg, args, state).await,
         Command::CreateWallet(args) => handle_create_wallet(bot, msg, args, user_id, state).await,
         Command::ImportWallet(args) => handle_import_wallet(bot, msg, args, user_id, state).await,
+        Command::Vanity(args) => handle_vanity(bot, msg, args, user_id, state).await,
         Command::Wallets => handle_list_wallets(bot, msg, user_id, state).await,
         Command::Balance(args) => handle_balance(bot, msg, args, user_id, state).await,
+        Command::HistoricalBalance(args) =>
+            handle_historical_balance(bot, msg, args, user_id, state).await,
         Command::Send(args) => handle_send(bot, msg, args, user_id, state).await,
         Command::EstimateFee(args) => handle_estimate_fee(bot, msg, args, user_id, state).await,
         Command::BatchSend(args) => handle_batch_send(bot, msg, args, user_id, state).await,
         Command::History(args) => handle_history(bot, msg, args, user_id, state).await,
+        Command::SpeedUp(args) => handle_speed_up(bot, msg, args, user_id, state).await,
+        Command::CancelTx(args) => handle_cancel_tx(bot, msg, args, user_id, state).await,
+        Command::TxStatus(args) => handle_tx_status(bot, msg, args, user_id, state).await,
+        Command::Block(args) => handle_block(bot, msg, args, state).await,
+        Command::NetworkStatus => handle_network_status(bot, msg, state).await,
+        Command::FindWallet(args) => handle_find_wallet(bot, msg, args, user_id, state).await,
+        Command::CleanWallet(args) => handle_clean_wallet(bot, msg, args, user_id, state).await,
         Command::Address(args) => handle_address(bot, msg, args, user_id, state).await,
         Command::Portfolio => handle_portfolio(bot, msg, user_id, state).await,
-        Command::Prices => handle_prices(bot, msg, state).await,
+        Command::PortfolioHistory(args) => handle_portfolio_history(bot, msg, args, user_id, state).await,
+        Command::Benchmark(args) => handle_benchmark(bot, msg, args, user_id, state).await,
+        Command::Fees(args) => handle_fees(bot, msg, args, user_id, state).await,
+        Command::WatchBalance(args) => handle_watch_balance(bot, msg, args, user_id, state).await,
+        Command::Prices => handle_prices(bot, msg, user_id, state).await,
+        Command::Chart(args) => handle_chart(bot, msg, args, state).await,
         Command::SaveAddress(args) => handle_save_address(bot, msg, args, user_id, state).await,
         Command::Addresses => handle_list_addresses(bot, msg, user_id, state).await,
         Command::DeleteAddress(args) => handle_delete_address(bot, msg, args, user_id, state).await,
         Command::Schedule(args) => handle_schedule(bot, msg, args, user_id, state).await,
         Command::Scheduled => handle_list_scheduled(bot, msg, user_id, state).await,
+        Command::Calendar(args) => handle_calendar(bot, msg, args, user_id, state).await,
+        Command::Dca(args) => handle_dca(bot, msg, args, user_id, state).await,
+        Command::Defi(args) => handle_defi(bot, msg, args, user_id, state).await,
         Command::CancelSchedule(args) =>
             handle_cancel_schedule(bot, msg, args, user_id, state).await,
         Command::SetAlert(args) => handle_set_alert(bot, msg, args, user_id, state).await,
-        Command::Alerts => handle_list_alerts(bot, msg, user_id, state).await,
+        Command::SetStopLoss(args) =>
+            handle_set_exit_alert(bot, msg, args, AlertKind::StopLoss, user_id, state).await,
+        Command::SetTakeProfit(args) =>
+            handle_set_exit_alert(bot, msg, args, AlertKind::TakeProfit, user_id, state).await,
+        Command::SetTrailStop(args) => handle_set_trail_stop(bot, msg, args, user_id, state).await,
+        Command::SetVolumeAlert(args) => handle_set_volume_alert(bot, msg, args, user_id, state).await,
+        Command::SetMempool(args) => handle_set_mempool(bot, msg, args, user_id, state).await,
+        Command::SetWrpc(args) => handle_set_wallet_rpc(bot, msg, args, user_id, state).await,
+        Command::GasAlert(args) => handle_gas_alert(bot, msg, args, user_id, state).await,
+        Command::Alerts(args) => {
+            if args.trim().eq_ignore_ascii_case("gas") {
+                handle_list_gas_alerts(bot, msg, user_id, state).await
+            } else {
+                handle_list_alerts(bot, msg, user_id, state).await
+            }
+        }
         Command::DeleteAlert(args) => handle_delete_alert(bot, msg, args, user_id, state).await,
+        Command::PauseAlert(args) => handle_pause_alert(bot, msg, args, user_id, state, true).await,
+        Command::ResumeAlert(args) => handle_pause_alert(bot, msg, args, user_id, state, false).await,
+        Command::DeleteGasAlert(args) =>
+            handle_delete_gas_alert(bot, msg, args, user_id, state).await,
+        Command::SetAllocation(args) =>
+            handle_set_allocation(bot, msg, args, user_id, state).await,
+        Command::TaxReport(args) => handle_tax_report(bot, msg, args, user_id, state).await,
         Command::SetPin(args) => handle_set_pin(bot, msg, args, user_id, state).await,
         Command::ChangePin(args) => handle_change_pin(bot, msg, args, user_id, state).await,
         Command::DisablePin => handle_disable_pin(bot, msg, user_id, state).await,
         Command::SetLimit(args) => handle_set_limit(bot, msg, args, user_id, state).await,
+        Command::SetMaxSend(args) => handle_set_max_send(bot, msg, args, user_id, state).await,
+        Command::GetLimits => handle_get_limits(bot, msg, user_id, state).await,
         Command::LockWallet => handle_lock_wallet(bot, msg, user_id, state).await,
         Command::UnlockWallet(args) => handle_unlock_wallet(bot, msg, args, user_id, state).await,
         Command::Security => handle_security_info(bot, msg, user_id, state).await,
+        Command::Audit => handle_audit(bot, msg, user_id, state).await,
+        Command::Testnet(args) => handle_testnet(bot, msg, args, user_id, state).await,
+        Command::SetCurrency(args) => handle_set_currency(bot, msg, args, user_id, state).await,
+        Command::Backup(args) => handle_backup(bot, msg, args, user_id, state).await,
+        Command::Restore(args) => handle_restore(bot, msg, args, user_id, state).await,
         Command::Swap(args) => handle_swap(bot, msg, args, user_id, state).await,
+        Command::BatchSwap(args) => handle_batch_swap(bot, msg, args, user_id, state).await,
         Command::SwapQuote(args) => handle_swap_quote(bot, msg, args, state).await,
         Command::SwapHistory(args) => handle_swap_history(bot, msg, args, user_id, state).await,
     }
 }
 
-async fn handle_start(bot: Bot, msg: Message) -> ResponseResult<()> {
+async fn handle_start(bot: Bot, msg: Message, user_id: String, state: Arc<BotState>) -> ResponseResult<()> {
     let chain_lines: String = Chain::all()
         .iter()
         .map(|c| format!("{} {} \\({}\\)", c.emoji(), escape_markdown(c.display_name()), escape_markdown(c.native_symbol())))
         .collect::<Vec<_>>()
         .join("\n");
 
+    let testnet_banner = if state.security_service.is_testnet_mode(&user_id).await.unwrap_or(false) {
+        "⚠️ *TESTNET MODE* \\- new wallets use test networks\n\n"
+    } else {
+        ""
+    };
+
     let welcome = format!(
-        "🔐 *Welcome to Crypto Wallet Bot\\!*\n\n\
+        "{}🔐 *Welcome to Crypto Wallet Bot\\!*\n\n\
 Your secure multi\\-chain wallet manager\\.\n\n\
 *Supported Blockchains:*\n\
 {}\n\n\
@@ -104,12 +278,37 @@ Your secure multi\\-chain wallet manager\\.\n\n\
 • Set price alerts\n\
 • Swap tokens\n\n\
 Select an option below to get started:",
+        testnet_banner,
         chain_lines
     );
 
+    // Returning users get a shortcut back to the wallet they last used
+    let session_user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0);
+    let resume_wallet = match state.user_session_service.get_session(session_user_id).await {
+        Ok(Some(session)) => session.last_wallet_id,
+        Ok(None) => None,
+        Err(e) => {
+            tracing::warn!("Failed to load session for user {}: {}", session_user_id, e);
+            None
+        }
+    };
+    let resume_wallet = match resume_wallet {
+        Some(wallet_id) =>
+            match state.wallet_service.get_wallet(wallet_id).await {
+                Ok(wallet) if wallet.user_id == user_id => Some(wallet),
+                _ => None,
+            }
+        None => None,
+    };
+
+    let keyboard = match &resume_wallet {
+        Some(wallet) => keyboards::main_menu_with_resume(&wallet.id.to_string(), &wallet.chain),
+        None => keyboards::main_menu(),
+    };
+
     bot.send_message(msg.chat.id, welcome)
         .parse_mode(ParseMode::MarkdownV2)
-        .reply_markup(keyboards::main_menu())
+        .reply_markup(keyboard)
         .await?;
     Ok(())
 }
@@ -147,10 +346,17 @@ async fn handle_create_wallet(
         }
     };
 
+    let Some(secret_chat) = secret_chat(&bot, &msg).await else {
+        bot.send_message(msg.chat.id, msg::ERR_START_PRIVATE_CHAT).await?;
+        return Ok(());
+    };
+
     bot.send_message(msg.chat.id, msg::STATUS_CREATING_WALLET).await?;
 
+    let use_testnet = state.security_service.is_testnet_mode(&user_id).await.unwrap_or(false);
+
     match
-        state.wallet_service.generate_wallet(user_id, chain.to_string(), Some(0)).await
+        state.wallet_service.generate_wallet(user_id, chain.to_string(), Some(0), use_testnet).await
     {
         Ok(response) => {
             let safe_msg = format!(
@@ -170,12 +376,185 @@ async fn handle_create_wallet(
                 escape_markdown(&response.address),
                 escape_markdown(&response.mnemonic.unwrap_or_default())
             );
+            let (chain, id, address) = (response.chain, response.id, response.address);
 
-            bot.send_message(msg.chat.id, safe_msg).parse_mode(ParseMode::MarkdownV2).await?;
+            bot.send_message(secret_chat, safe_msg).parse_mode(ParseMode::MarkdownV2).await?;
+            if secret_chat != msg.chat.id {
+                announce_group_wallet(&bot, &msg, &chain, &id, &address).await?;
+            }
+        }
+        Err(crate::error::AppError::WalletAlreadyExists { existing_id }) => {
+            bot
+                .send_message(
+                    msg.chat.id,
+                    crate::bot::utils::format_existing_wallet(chain.as_str(), &existing_id)
+                )
+                .reply_markup(keyboards::existing_wallet(&existing_id.to_string())).await?;
         }
         Err(e) => {
             tracing::error!("Failed to create wallet: {:?}", e);
-            bot.send_message(msg.chat.id, format!("❌ Failed to create wallet: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to create wallet: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Chat to send a new wallet's mnemonic to: the chat itself when private, otherwise the
+/// sender's private chat. `None` when the sender hasn't started a private chat with the bot.
+async fn secret_chat(bot: &Bot, msg: &Message) -> Option<ChatId> {
+    if msg.chat.is_private() {
+        return Some(msg.chat.id);
+    }
+    let chat = ChatId::from(msg.from.as_ref()?.id);
+    // Bots can't open a private chat themselves, so a failed message means there is none yet
+    bot.send_message(
+        chat,
+        format!(
+            "🔐 The mnemonic for the wallet you're creating in {} will arrive here.",
+            msg.chat.title().unwrap_or("the group")
+        )
+    ).await.ok()?;
+    Some(chat)
+}
+
+/// Tell the group a wallet was created without showing its mnemonic
+async fn announce_group_wallet(
+    bot: &Bot,
+    msg: &Message,
+    chain: &str,
+    wallet_id: &Uuid,
+    address: &str
+) -> ResponseResult<()> {
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "✅ Wallet created\n\n📍 Chain: {}\n🆔 Wallet ID: {}\n📬 Address: {}\n\n🔑 The mnemonic was sent privately to the member who created it.",
+            chain,
+            wallet_id,
+            address
+        )
+    ).await?;
+    Ok(())
+}
+
+/// How often the vanity search progress message is refreshed
+const VANITY_PROGRESS_INTERVAL_SECS: u64 = 3;
+
+async fn handle_vanity(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let mut parts = args.split_whitespace();
+    let Some(chain) = parts.next().and_then(|c| c.parse::<Chain>().ok()) else {
+        bot.send_message(msg.chat.id, msg::ERR_VANITY_USAGE).await?;
+        return Ok(());
+    };
+
+    let mut pattern = wallet_service::VanityPattern { prefix: None, suffix: None, case_sensitive: false };
+    while let Some(flag) = parts.next() {
+        match flag {
+            "--prefix" => {
+                pattern.prefix = parts.next().map(str::to_string);
+            }
+            "--suffix" => {
+                pattern.suffix = parts.next().map(str::to_string);
+            }
+            "--case" => {
+                pattern.case_sensitive = true;
+            }
+            _ => {
+                bot.send_message(msg.chat.id, msg::ERR_VANITY_USAGE).await?;
+                return Ok(());
+            }
+        }
+    }
+    if pattern.prefix.is_none() && pattern.suffix.is_none() {
+        bot.send_message(msg.chat.id, msg::ERR_VANITY_USAGE).await?;
+        return Ok(());
+    }
+
+    let Some(secret_chat) = secret_chat(&bot, &msg).await else {
+        bot.send_message(msg.chat.id, msg::ERR_START_PRIVATE_CHAT).await?;
+        return Ok(());
+    };
+
+    let max_attempts = wallet_service::MAX_VANITY_ATTEMPTS;
+    let progress_msg = bot.send_message(msg.chat.id, msg::STATUS_VANITY_SEARCH).await?;
+
+    let use_testnet = state.security_service.is_testnet_mode(&user_id).await.unwrap_or(false);
+    let attempts = Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let search = {
+        let state = state.clone();
+        let attempts = attempts.clone();
+        let chain = chain.to_string();
+        tokio::spawn(async move {
+            state.wallet_service.generate_vanity_wallet(
+                &user_id,
+                &chain,
+                pattern,
+                max_attempts,
+                attempts,
+                use_testnet
+            ).await
+        })
+    };
+
+    while !search.is_finished() {
+        tokio::time::sleep(std::time::Duration::from_secs(VANITY_PROGRESS_INTERVAL_SECS)).await;
+        let tried = attempts.load(std::sync::atomic::Ordering::Relaxed).min(max_attempts);
+        let _ = bot
+            .edit_message_text(
+                msg.chat.id,
+                progress_msg.id,
+                format!("{}\n🔄 {} / {} attempts", msg::STATUS_VANITY_SEARCH, tried, max_attempts)
+            ).await;
+    }
+
+    let result = search
+        .await
+        .unwrap_or_else(|e| Err(crate::error::AppError::Internal(format!("Vanity search panicked: {}", e))));
+
+    match result {
+        Ok(response) => {
+            let tried = attempts.load(std::sync::atomic::Ordering::Relaxed).min(max_attempts);
+            let _ = bot
+                .edit_message_text(
+                    msg.chat.id,
+                    progress_msg.id,
+                    format!("✅ Found a match after {} attempts", tried)
+                ).await;
+
+            let safe_msg = format!(
+                "{}
+
+📍 Chain: `{}`
+🆔 Wallet ID: `{}`
+📬 Address: `{}`
+
+🔑 *SAVE YOUR MNEMONIC SECURELY:*
+`{}`
+
+⚠️ *IMPORTANT:* Never share your mnemonic\\. I will send it once\\. Save it now\\!",
+                msg::SUCCESS_WALLET_CREATED,
+                escape_markdown(&response.chain),
+                escape_markdown(&response.id.to_string()),
+                escape_markdown(&response.address),
+                escape_markdown(&response.mnemonic.unwrap_or_default())
+            );
+            let (chain, id, address) = (response.chain, response.id, response.address);
+
+            bot.send_message(secret_chat, safe_msg).parse_mode(ParseMode::MarkdownV2).await?;
+            if secret_chat != msg.chat.id {
+                announce_group_wallet(&bot, &msg, &chain, &id, &address).await?;
+            }
+        }
+        Err(e) => {
+            let _ = bot
+                .edit_message_text(msg.chat.id, progress_msg.id, format!("❌ {}", e.user_facing_message())).await;
         }
     }
 
@@ -208,16 +587,19 @@ async fn handle_import_wallet(
 
     bot.send_message(msg.chat.id, msg::STATUS_IMPORTING_WALLET).await?;
 
+    let use_testnet = state.security_service.is_testnet_mode(&user_id).await.unwrap_or(false);
+
     match
         state.wallet_service.restore_wallet(
             user_id,
             chain.to_string(),
             key,
-            Some(0)
+            Some(0),
+            use_testnet
         ).await
     {
         Ok(response) => {
-            let safe_msg = format!(
+            let mut safe_msg = format!(
                 "{}
 
 📍 Chain: `{}`
@@ -228,12 +610,27 @@ async fn handle_import_wallet(
                 escape_markdown(&response.id.to_string()),
                 escape_markdown(&response.address)
             );
+            if !response.warnings.is_empty() {
+                safe_msg.push_str(&escape_markdown("\n\n⚠️ This recovery phrase looks weak:\n"));
+                for warning in &response.warnings {
+                    safe_msg.push_str(&escape_markdown(&format!("• {}\n", warning.message)));
+                }
+                safe_msg.push_str(&escape_markdown("Consider moving funds to a newly generated wallet."));
+            }
 
             bot.send_message(msg.chat.id, safe_msg).parse_mode(ParseMode::MarkdownV2).await?;
         }
+        Err(crate::error::AppError::WalletAlreadyExists { existing_id }) => {
+            bot
+                .send_message(
+                    msg.chat.id,
+                    crate::bot::utils::format_existing_wallet(chain.as_str(), &existing_id)
+                )
+                .reply_markup(keyboards::existing_wallet(&existing_id.to_string())).await?;
+        }
         Err(e) => {
             tracing::error!("Failed to import wallet: {:?}", e);
-            bot.send_message(msg.chat.id, format!("❌ Failed to import wallet: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to import wallet: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -271,7 +668,7 @@ async fn handle_list_wallets(
         }
         Err(e) => {
             tracing::error!("Failed to list wallets: {:?}", e);
-            bot.send_message(msg.chat.id, format!("❌ Failed to list wallets: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to list wallets: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -306,23 +703,116 @@ async fn handle_balance(
 
     match state.balance_service.get_balance(wallet_id, token_address).await {
         Ok(balance) => {
-            let msg_text = format!(
+            let mut msg_text = format!(
                 "💰 *Balance*\n\n💵 Symbol: *{}*\n💎 Amount: `{}`",
                 escape_markdown(&balance.symbol),
                 escape_markdown(&balance.balance)
             );
+            if let Some(usd_value) = balance.usd_value {
+                msg_text.push_str(&format!("\n💲 Value: ${}", escape_markdown(&format_currency(usd_value))));
+            }
 
             bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
             tracing::error!("Failed to get balance: {:?}", e);
-            bot.send_message(msg.chat.id, format!("❌ Failed to get balance: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to get balance: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_historical_balance(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.trim().split_whitespace().collect();
+
+    if parts.len() < 2 {
+        bot.send_message(msg.chat.id, msg::ERR_HISTORICAL_BALANCE_USAGE).await?;
+        return Ok(());
+    }
+
+    let wallet_id = match Uuid::parse_str(parts[0]) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(msg.chat.id, msg::ERR_INVALID_WALLET_ID).await?;
+            return Ok(());
+        }
+    };
+
+    match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(wallet) if wallet.user_id == user_id => {}
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+            return Ok(());
+        }
+    }
+
+    let token_address = parts.get(2).map(|s| s.to_string());
+
+    bot.send_message(msg.chat.id, msg::STATUS_FETCHING_BALANCE).await?;
+
+    // A date is converted to the block mined around the end of that day (UTC)
+    let (block_number, date) = if let Ok(block) = parts[1].parse::<u64>() {
+        (block, None)
+    } else if let Ok(date) = chrono::NaiveDate::parse_from_str(parts[1], "%Y-%m-%d") {
+        let end_of_day = date.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc();
+        let at = end_of_day.min(chrono::Utc::now());
+        match state.balance_service.estimate_block_at(wallet_id, at).await {
+            Ok(block) => (block, Some(date)),
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ Failed to find the block: {}", e.user_facing_message())).await?;
+                return Ok(());
+            }
+        }
+    } else {
+        bot.send_message(msg.chat.id, msg::ERR_HISTORICAL_BALANCE_USAGE).await?;
+        return Ok(());
+    };
+
+    match state.balance_service.get_historical_balance(wallet_id, token_address, block_number).await {
+        Ok(balance) => {
+            let mut msg_text = format!(
+                "🕰 *Historical Balance*\n\n🧱 Block: `{}`",
+                block_number
+            );
+            if let Some(date) = date {
+                msg_text.push_str(
+                    &format!(
+                        " \\(approx\\. end of {}\\)",
+                        escape_markdown(&date.format("%Y-%m-%d").to_string())
+                    )
+                );
+            }
+            msg_text.push_str(
+                &format!(
+                    "\n💵 Symbol: *{}*\n💎 Amount: `{}`",
+                    escape_markdown(&balance.symbol),
+                    escape_markdown(&balance.balance)
+                )
+            );
+
+            bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get historical balance: {:?}", e);
+            bot.send_message(msg.chat.id, format!("❌ Failed to get balance: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
+/// Whether a recipient looks like a full address rather than a name or fragment
+pub(super) fn looks_like_address(input: &str) -> bool {
+    (input.starts_with("0x") && input.len() >= 42) || input.len() > 40
+}
+
 async fn handle_send(
     bot: Bot,
     msg: Message,
@@ -349,52 +839,156 @@ async fn handle_send(
     let amount = parts[2].to_string();
     let token_address = parts.get(3).map(|s| s.to_string());
 
-    // Check if to_input is an address or a saved name
-    let to_address = if to_input.starts_with("0x") || to_input.len() > 40 {
-        // Looks like an address
+    // Check if to_input is an address, a saved name (or part of one) or the start of one of the user's own wallets
+    let to_address = if looks_like_address(&to_input) {
         to_input
+    } else if let Ok(saved_addr) = state.address_book_service.get_address(&user_id, &to_input).await {
+        bot.send_message(
+            msg.chat.id,
+            format!("📖 Using saved address: {} ({})", saved_addr.name, saved_addr.address)
+        ).await?;
+        saved_addr.address
     } else {
-        // Try to find it in address book
-        match state.address_book_service.get_address(&user_id, &to_input).await {
-            Ok(saved_addr) => {
+        let contacts = super::callbacks::find_send_contacts(
+            &wallet_id.to_string(),
+            &user_id,
+            &to_input,
+            &state
+        ).await;
+
+        match contacts.as_slice() {
+            [] => {
+                let own_wallets = state.wallet_service
+                    .find_wallets_by_partial_address(&user_id, &to_input).await
+                    .unwrap_or_default();
+
+                // Never send to a prefix match; the user has to type the full address
+                match own_wallets.as_slice() {
+                    [] => to_input,
+                    [wallet] => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "💼 {} matches your {} wallet {}. Send again with the full address.",
+                                to_input,
+                                wallet.chain,
+                                wallet.address
+                            )
+                        ).await?;
+                        return Ok(());
+                    }
+                    _ => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "❌ {} of your wallets start with {}. Type more of the address.",
+                                own_wallets.len(),
+                                to_input
+                            )
+                        )
+                            .reply_markup(keyboards::wallet_matches(&own_wallets))
+                            .await?;
+                        return Ok(());
+                    }
+                }
+            }
+            // A fuzzy match is only a suggestion: the user confirms it by tapping the contact
+            _ => {
+                let names: Vec<&str> = contacts
+                    .iter()
+                    .map(|c| c.name.as_str())
+                    .collect();
+
+                // Token sends can't resume from a button pick, so ask for the exact name instead
+                let wallet = match (&token_address, state.wallet_service.get_wallet(wallet_id).await) {
+                    (None, Ok(wallet)) => wallet,
+                    _ => {
+                        bot.send_message(
+                            msg.chat.id,
+                            format!(
+                                "❌ No saved address is named {} (closest: {}). Use the full name.",
+                                to_input,
+                                names.join(", ")
+                            )
+                        ).await?;
+                        return Ok(());
+                    }
+                };
+
+                let dialogue_user_id = crate::bot::dialogue_key(
+                    msg.chat.id,
+                    msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0)
+                );
+                let symbol = wallet.chain
+                    .parse::<Chain>()
+                    .map(|c| c.native_symbol().to_string())
+                    .unwrap_or_else(|_| wallet.chain.to_uppercase());
+                let pending = crate::bot::DialogueState::WaitingForSendAddress {
+                    wallet_id: wallet_id.to_string(),
+                    amount: amount.clone(),
+                    symbol,
+                    send_max: false,
+                    amount_usd_estimate: None,
+                };
+                if let Err(e) = state.dialogue_storage.set(dialogue_user_id, pending).await {
+                    bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+                    return Ok(());
+                }
+
                 bot.send_message(
                     msg.chat.id,
-                    format!("📖 Using saved address: {} ({})", saved_addr.name, saved_addr.address)
-                ).await?;
-                saved_addr.address
-            }
-            Err(_) => {
-                // Not in address book, treat as address
-                to_input
+                    format!("📖 No saved address is named \"{}\". Did you mean:", to_input)
+                )
+                    .reply_markup(
+                        keyboards::address_book_matches(&contacts, dialogue_user_id, &wallet_id.to_string())
+                    )
+                    .await?;
+                return Ok(());
             }
         }
     };
 
-    bot.send_message(msg.chat.id, msg::STATUS_SENDING_TX).await?;
-
-    let request = transfer_service::TransferRequest {
-        to: to_address,
+    let symbol = match &token_address {
+        Some(token) => state.balance_service
+            .get_balance(wallet_id, Some(token.clone())).await
+            .map(|b| b.symbol)
+            .unwrap_or_else(|_| token.clone()),
+        None => match state.wallet_service.get_wallet(wallet_id).await {
+            Ok(wallet) => wallet.chain
+                .parse::<Chain>()
+                .map(|c| c.native_symbol().to_string())
+                .unwrap_or_else(|_| wallet.chain.to_uppercase()),
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ Failed to load wallet: {}", e.user_facing_message())).await?;
+                return Ok(());
+            }
+        },
+    };
+    let pending = crate::bot::PinProtectedSend::Single(crate::bot::PendingSendConfirmation {
+        wallet_id: wallet_id.to_string(),
+        recipient: to_address,
         amount,
+        symbol,
+        send_max: false,
+        amount_usd_estimate: None,
         token_address,
-        max_fee_per_gas: None,
-        max_priority_fee_per_gas: None,
-        gas_limit: None,
-        compute_units: None,
-    };
-
-    match state.transfer_service.send_transaction(wallet_id, request).await {
-        Ok(response) => {
-            let msg_text = format!(
-                "✅ *Transaction Sent\\!*\n\n🔗 Hash: `{}`\n📊 Status: `{}`",
-                escape_markdown(&response.tx_hash),
-                escape_markdown(&response.status)
-            );
-
-            bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
-        }
-        Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Transaction failed: {}", e)).await?;
-        }
+    });
+
+    // Typed sends take the same PIN check as the confirm button
+    let status = bot.send_message(msg.chat.id, msg::STATUS_SENDING_TX).await?;
+    let sender_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0);
+    let dialogue_user_id = crate::bot::dialogue_key(msg.chat.id, sender_id);
+    if let Err(e) = super::callbacks::confirm_pending_send(
+        &bot,
+        msg.chat.id,
+        status.id,
+        pending,
+        dialogue_user_id,
+        &sender_id.to_string(),
+        &state
+    ).await {
+        tracing::error!("Send failed: {:?}", e);
+        bot.send_message(msg.chat.id, "❌ Could not send the transaction. Please try again.").await?;
     }
 
     Ok(())
@@ -404,7 +998,7 @@ async fn handle_estimate_fee(
     bot: Bot,
     msg: Message,
     args: String,
-    _user_id: String,
+    user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
     let parts: Vec<&str> = args.trim().split_whitespace().collect();
@@ -441,13 +1035,15 @@ async fn handle_estimate_fee(
         ).await
     {
         Ok(estimate) => {
-            let mut response = format!(
-                "⛽ *Gas Estimation*\n\n\
-                ⛓️ Chain: {}\n\
-                📊 Gas Limit: {}\n",
+            let mut response = String::from("⛽ *Gas Estimation*\n\n");
+            response.push_str(&format!(
+                "⛓️ Chain: {}\n\
+                📊 Estimated Gas: {}\n\
+                🧱 Gas Limit: {} \\(\\+20%\\)\n",
                 escape_markdown(&estimate.chain),
-                escape_markdown(&estimate.gas_estimate.estimated_gas.to_string())
-            );
+                escape_markdown(&estimate.gas_estimate.estimated_gas.to_string()),
+                escape_markdown(&estimate.gas_estimate.gas_limit.to_string())
+            ));
 
             if let Some(gas_price) = &estimate.gas_estimate.gas_price {
                 response.push_str(&format!("💵 Gas Price: {}\n", escape_markdown(gas_price)));
@@ -471,16 +1067,48 @@ async fn handle_estimate_fee(
                 )
             );
 
+            let (currency, rate) = user_fiat(&state, &user_id).await;
             if let Some(usd_cost) = estimate.gas_estimate.total_cost_usd {
-                response.push_str(&format!("💵 \\~${:.4} USD\n", usd_cost));
-            }
+                response.push_str(
+                    &format!(
+                        "💵 \\~{} {}\n",
+                        escape_markdown(&format!("{:.4}", usd_cost * rate)),
+                        currency
+                    )
+                );
+            }
+
+            if let Some(l1_fee) = &estimate.gas_estimate.l1_data_fee_native {
+                let usd = estimate.gas_estimate.l1_data_fee_usd
+                    .map(|usd| {
+                        format!(" \\(\\~{}\\)", escape_markdown(&format_fiat(usd * rate, currency)))
+                    })
+                    .unwrap_or_default();
+                response.push_str(
+                    &format!(
+                        "📦 L1 data fee: {} ETH{}\n",
+                        escape_markdown(l1_fee.trim_end_matches('0').trim_end_matches('.')),
+                        usd
+                    )
+                );
+            }
+
+            if let Some(times) = &estimate.confirmation_times {
+                let line = format!(
+                    "🐢 Slow: {} | ⚡ Standard: {} | 🚀 Fast: {}",
+                    format_confirmation_time(times.slow_secs),
+                    format_confirmation_time(times.standard_secs),
+                    format_confirmation_time(times.fast_secs)
+                );
+                response.push_str(&format!("\n⏱️ {}\n", escape_markdown(&line)));
+            }
 
             response.push_str("\n💡 _This is an estimate\\. Actual cost may vary\\._");
 
             bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Failed to estimate fee: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to estimate fee: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -569,8 +1197,90 @@ async fn handle_batch_send(
         });
     }
 
-    bot.send_message(
+    let estimate = match state.transfer_service.estimate_batch_cost(wallet_id, &recipients).await {
+        Ok(estimate) => estimate,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Could not estimate batch: {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    };
+
+    let mut text = format!(
+        "📋 Batch Preview ({} recipients)\n\n\
+        💸 Total: {} {}\n\
+        ⛽ Fees: {} {}\n\
+        💵 Value: ${}\n\n",
+        recipients.len(),
+        estimate.total_native_amount,
+        estimate.symbol,
+        estimate.total_gas_cost_native,
+        estimate.symbol,
+        format_currency(estimate.total_usd_value)
+    );
+    for status in estimate.recipients.iter().take(20) {
+        let short_to: String = status.to.chars().take(10).collect();
+        let line = match (&status.error, &status.fee_native) {
+            (Some(error), _) => format!("❌ #{} {} → {}: {}\n", status.index + 1, status.amount, short_to, error),
+            (None, Some(fee)) if status.below_dust =>
+                format!("⚠️ #{} {} → {} (fee {}, dust)\n", status.index + 1, status.amount, short_to, fee),
+            (None, fee) =>
+                format!(
+                    "✅ #{} {} → {} (fee {})\n",
+                    status.index + 1,
+                    status.amount,
+                    short_to,
+                    fee.as_deref().unwrap_or("?")
+                ),
+        };
+        text.push_str(&line);
+    }
+    if estimate.recipients.len() > 20 {
+        text.push_str(&format!("... and {} more\n", estimate.recipients.len() - 20));
+    }
+    if !estimate.warnings.is_empty() {
+        text.push_str("\n⚠️ Warnings:\n");
+        for warning in &estimate.warnings {
+            text.push_str(&format!("• {}\n", warning));
+        }
+    }
+
+    if estimate.insufficient_balance {
+        text.push_str("\n❌ Not enough balance for this batch. Adjust the amounts and try again.");
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let dialogue_user_id = crate::bot::dialogue_key(
         msg.chat.id,
+        msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0)
+    );
+    let pending = crate::bot::DialogueState::PendingBatchSend {
+        wallet_id: wallet_id.to_string(),
+        recipients,
+    };
+    if let Err(e) = state.dialogue_storage.set(dialogue_user_id, pending).await {
+        bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        return Ok(());
+    }
+
+    text.push_str("\nSend this batch?");
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboards::batch_send_confirm(&wallet_id.to_string()))
+        .await?;
+
+    Ok(())
+}
+
+/// Send a confirmed batch and report each transfer's outcome
+pub(super) async fn execute_batch_send(
+    bot: &Bot,
+    chat_id: ChatId,
+    wallet_id: Uuid,
+    recipients: Vec<transfer_service::BatchRecipient>,
+    state: &Arc<BotState>
+) -> ResponseResult<()> {
+    bot.send_message(
+        chat_id,
         format!("⏳ Processing batch transfer for {} recipients...", recipients.len())
     ).await?;
 
@@ -623,10 +1333,13 @@ async fn handle_batch_send(
                 );
             }
 
-            bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
+            bot.send_message(chat_id, response).parse_mode(ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Batch transfer failed: {}", e)).await?;
+            bot.send_message(
+                chat_id,
+                format!("❌ Batch transfer failed: {}", crate::bot::utils::format_transfer_error(&e))
+            ).await?;
         }
     }
 
@@ -678,10 +1391,12 @@ async fn handle_history(
                 response.push_str(
                     &format!(
                         "🔸 `{}`\n\
+                      🆔 `{}`\n\
                       📊 Status: {}\n\
                       💎 Amount: {} {}\n\
                       📅 {}\n\n",
                         escape_markdown(&tx.tx_hash[..16]),
+                        tx.id,
                         escape_markdown(&tx.status),
                         escape_markdown(&tx.amount),
                         escape_markdown(tx.token_symbol.as_deref().unwrap_or("UNKNOWN")),
@@ -693,241 +1408,199 @@ async fn handle_history(
             bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Failed to fetch history: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to fetch history: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_address(
+/// Look up a transaction and make sure it belongs to one of the user's wallets
+async fn find_user_transaction(
+    tx_id: Uuid,
+    user_id: &str,
+    state: &Arc<BotState>
+) -> std::result::Result<crate::db::entity::transaction::Model, String> {
+    let tx = state.transaction_service
+        .get_transaction(tx_id).await
+        .map_err(|e| format!("❌ {}", e.user_facing_message()))?;
+
+    match state.wallet_service.get_wallet(tx.wallet_id).await {
+        Ok(wallet) if wallet.user_id == user_id => Ok(tx),
+        _ => Err("❌ Transaction not found".to_string()),
+    }
+}
+
+async fn handle_speed_up(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    let wallet_id = match Uuid::parse_str(args.trim()) {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.is_empty() {
+        bot.send_message(msg.chat.id, "❌ Usage: /speedup <tx_id> [max_fee_gwei]").await?;
+        return Ok(());
+    }
+
+    let tx_id = match Uuid::parse_str(parts[0]) {
         Ok(id) => id,
         Err(_) => {
-            bot.send_message(
-                msg.chat.id,
-                "❌ Invalid wallet ID format\nUsage: /address <wallet_id>"
-            ).await?;
+            bot.send_message(msg.chat.id, "❌ Invalid transaction ID format").await?;
             return Ok(());
         }
     };
+    let new_max_fee = parts.get(1).map(|s| s.to_string());
 
-    match state.wallet_service.list_user_wallets(&user_id, None).await {
-        Ok(wallets) => {
-            if let Some(wallet) = wallets.iter().find(|w| w.id == wallet_id) {
-                let msg_text = format!(
-                    "📬 *Wallet Address*\n\n\
-                    📍 Chain: `{}`\n\
-                    🆔 ID: `{}`\n\
-                    📬 Address:\n`{}`\n\n\
-                    You can use this address to receive {} tokens\\.",
-                    escape_markdown(&wallet.chain),
-                    escape_markdown(&wallet.id.to_string()),
-                    escape_markdown(&wallet.address),
-                    escape_markdown(&wallet.chain)
-                );
+    let tx = match find_user_transaction(tx_id, &user_id, &state).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e).await?;
+            return Ok(());
+        }
+    };
 
-                bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
-            } else {
-                bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
-            }
+    bot.send_message(msg.chat.id, "⏳ Rebroadcasting with a higher fee...").await?;
+
+    match state.transaction_service.speed_up_transaction(tx.id, new_max_fee).await {
+        Ok(response) => {
+            let msg_text = format!(
+                "⚡ *Transaction Sped Up\\!*\n\n🔗 New Hash: `{}`\n📊 Status: `{}`",
+                escape_markdown(&response.tx_hash),
+                escape_markdown(&response.status)
+            );
+            bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Failed to get wallet: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Speed up failed: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_portfolio(
+async fn handle_cancel_tx(
     bot: Bot,
     msg: Message,
+    args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    bot.send_message(msg.chat.id, "⏳ Fetching your portfolio...").await?;
-
-    match state.portfolio_service.get_portfolio(&user_id).await {
-        Ok(portfolio) => {
-            if portfolio.holdings.is_empty() {
-                bot.send_message(
-                    msg.chat.id,
-                    "📭 Your portfolio is empty.\n\nCreate a wallet with: /createwallet <chain>"
-                ).await?;
-                return Ok(());
-            }
-
-            let mut response = String::from("💼 *Your Portfolio*\n\n");
-
-            for holding in &portfolio.holdings {
-                let change_emoji = match holding.price_change_24h {
-                    Some(change) if change > 0.0 => "📈",
-                    Some(change) if change < 0.0 => "📉",
-                    _ => "➖",
-                };
-
-                let change_text = if let Some(change) = holding.price_change_24h {
-                    format!(" \\({}{:.2}%\\)", if change > 0.0 { "+" } else { "" }, change)
-                } else {
-                    String::new()
-                };
+    let tx_id = match Uuid::parse_str(args.trim()) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Usage: /canceltx <tx_id>").await?;
+            return Ok(());
+        }
+    };
 
-                response.push_str(
-                    &format!(
-                        "*{}:* {} {} \\(${}\\) {}{}\n",
-                        escape_markdown(&holding.symbol),
-                        escape_markdown(
-                            &format!("{:.6}", holding.total_balance)
-                                .trim_end_matches('0')
-                                .trim_end_matches('.')
-                        ),
-                        escape_markdown(&holding.symbol),
-                        format_currency(holding.usd_value),
-                        change_emoji,
-                        change_text
-                    )
-                );
+    let tx = match find_user_transaction(tx_id, &user_id, &state).await {
+        Ok(tx) => tx,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e).await?;
+            return Ok(());
+        }
+    };
 
-                response.push_str(
-                    &format!(
-                        "  💵 ${} per {}\n",
-                        format_currency(holding.usd_price),
-                        escape_markdown(&holding.symbol)
-                    )
-                );
+    bot.send_message(msg.chat.id, "⏳ Sending cancellation...").await?;
 
-                if holding.wallets.len() > 1 {
-                    response.push_str(&format!("  📦 {} wallets\n", holding.wallets.len()));
-                }
+    match state.transaction_service.cancel_transaction(tx.id).await {
+        Ok(response) => {
+            let msg_text = format!(
+                "🚫 *Cancellation Sent\\!*\n\n🔗 Hash: `{}`\n📊 Status: `{}`\n\n\
+                 If it confirms first, the original transaction will be dropped\\.",
+                escape_markdown(&response.tx_hash),
+                escape_markdown(&response.status)
+            );
+            bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Cancel failed: {}", e.user_facing_message())).await?;
+        }
+    }
 
-                response.push_str("\n");
-            }
+    Ok(())
+}
 
-            response.push_str(
-                &format!(
-                    "━━━━━━━━━━━━━━━━\n\
-                💰 *Total Value:* ${}\n\
-                📊 {} chains \\| {} wallets",
-                    format_currency(portfolio.total_usd_value),
-                    portfolio.chains.len(),
-                    portfolio.wallet_count
-                )
-            );
+async fn handle_block(bot: Bot, msg: Message, args: String, state: Arc<BotState>) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (chain, number) = match parts.as_slice() {
+        [chain, number] => (chain.parse::<Chain>(), crate::services::block_service::parse_block_number(number)),
+        _ => {
+            bot.send_message(msg.chat.id, msg::ERR_BLOCK_USAGE).await?;
+            return Ok(());
+        }
+    };
+    let Ok(chain) = chain else {
+        bot.send_message(msg.chat.id, msg::ERR_INVALID_CHAIN).await?;
+        return Ok(());
+    };
+    let Ok(number) = number else {
+        bot.send_message(msg.chat.id, msg::ERR_BLOCK_USAGE).await?;
+        return Ok(());
+    };
 
-            bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
+    match state.block_service.get_block(chain.as_str(), number).await {
+        Ok(block) => {
+            bot.send_message(msg.chat.id, crate::bot::utils::format_block_info(chain, &block)).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Failed to fetch portfolio: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_prices(bot: Bot, msg: Message, state: Arc<BotState>) -> ResponseResult<()> {
-    bot.send_message(msg.chat.id, "⏳ Fetching prices...").await?;
-
-    // Build unique native symbols from all configured chains
-    let symbols: Vec<String> = state.config.configured_chains()
-        .iter()
-        .map(|c| c.native_symbol().to_string())
-        .collect::<std::collections::HashSet<_>>()
+async fn handle_network_status(bot: Bot, msg: Message, state: Arc<BotState>) -> ResponseResult<()> {
+    // Block headers are an EVM notion; other chains have no gas or block size to show
+    let mut chains: Vec<Chain> = state.config
+        .configured_chains()
         .into_iter()
+        .filter(|c| c.is_evm())
         .collect();
+    chains.sort_by_key(|c| c.as_str());
+    if chains.is_empty() {
+        bot.send_message(msg.chat.id, "ℹ️ No EVM chains are configured").await?;
+        return Ok(());
+    }
 
-    match state.price_service.get_prices(&symbols).await {
-        Ok(prices) => {
-            let mut response = String::from("💵 *Cryptocurrency Prices*\n\n");
-
-            // Sort by price descending for consistent display
-            let mut sorted: Vec<_> = prices.iter().collect();
-            sorted.sort_by(|a, b| b.1.usd_price.partial_cmp(&a.1.usd_price).unwrap_or(std::cmp::Ordering::Equal));
-
-            for (symbol, price) in &sorted {
-                let emoji = symbol.parse::<Chain>()
-                    .map(|c| c.emoji())
-                    .unwrap_or("🪙");
-
-                let change_emoji = match price.price_change_24h {
-                    Some(change) if change > 0.0 => "📈",
-                    Some(change) if change < 0.0 => "📉",
-                    _ => "➖",
-                };
-
-                let change_text = if let Some(change) = price.price_change_24h {
-                    format!(" \\({}{:.2}%\\)", if change > 0.0 { "\\+" } else { "" }, change)
-                } else {
-                    String::new()
-                };
-
-                response.push_str(
-                    &format!(
-                        "{} *{}:* ${} {}{}\n",
-                        emoji,
-                        escape_markdown(symbol),
-                        format_currency(price.usd_price),
-                        change_emoji,
-                        change_text
-                    )
-                );
-
-                if let Some(market_cap) = price.market_cap {
-                    if market_cap > 0.0 {
-                        response.push_str(
-                            &format!(
-                                "  📊 Cap: ${}B\n",
-                                escape_markdown(&format!("{:.1}", market_cap / 1_000_000_000.0))
-                            )
-                        );
-                    }
-                }
-
-                response.push_str("\n");
-            }
-
-            response.push_str("_Updated just now_");
+    let status = bot.send_message(msg.chat.id, "⏳ Checking networks...").await?;
+    let lines: Vec<String> = state.block_service
+        .network_status(&chains).await
+        .into_iter()
+        .map(|(chain, block)| match block {
+            Ok(block) => crate::bot::utils::format_network_status_line(chain, &block),
+            Err(e) => format!("{} {}: unavailable ({})", chain.emoji(), chain.as_str(), e.user_facing_message()),
+        })
+        .collect();
 
-            bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
-        }
-        Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Failed to fetch prices: {}", e)).await?;
-        }
-    }
+    bot.edit_message_text(msg.chat.id, status.id, format!("🌐 Network status\n\n{}", lines.join("\n"))).await?;
 
     Ok(())
 }
 
-async fn handle_save_address(
+async fn handle_tx_status(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    let parts: Vec<&str> = args.trim().split_whitespace().collect();
-
-    if parts.len() < 3 {
-        bot.send_message(
-            msg.chat.id,
-            "Usage: /saveaddress <name> <address> <chain> [notes]\n\
-            Example: /saveaddress alice 0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb ETH"
-        ).await?;
-        return Ok(());
-    }
-
-    let name = parts[0].to_string();
-    let address = parts[1].to_string();
-    let notes = if parts.len() > 3 { Some(parts[3..].join(" ")) } else { None };
-
-    // Validate chain
-    let chain = match parts[2].to_uppercase().parse::<Chain>() {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (tx_hash, chain) = match parts.as_slice() {
+        [hash, chain] => (*hash, chain.parse::<Chain>()),
+        _ => {
+            bot.send_message(
+                msg.chat.id,
+                "❌ Usage: /txstatus <tx_hash> <chain>\n\nExample: /txstatus 0xabc... ETH"
+            ).await?;
+            return Ok(());
+        }
+    };
+    let chain = match chain {
         Ok(c) => c,
         Err(_) => {
             bot.send_message(msg.chat.id, msg::ERR_INVALID_CHAIN).await?;
@@ -935,483 +1608,2172 @@ async fn handle_save_address(
         }
     };
 
-    match
-        state.address_book_service.save_address(
-            user_id,
-            name.clone(),
-            address.clone(),
-            chain.to_string(),
-            notes
-        ).await
-    {
-        Ok(_) => {
-            let response = format!(
-                "✅ Address saved successfully!\n\n\
-                Name: {}\n\
-                Address: {}\n\
-                Chain: {}\n\n\
-                Use /addresses to view all saved addresses",
-                name,
-                address,
-                chain
+    let testnet = state.security_service.is_testnet_mode(&user_id).await.unwrap_or(false);
+    match state.transaction_service.get_chain_transaction(chain.as_str(), testnet, tx_hash).await {
+        Ok(Some(tx)) => {
+            let status_emoji = match tx.status.parse::<TxStatus>() {
+                Ok(TxStatus::Confirmed) => "✅",
+                Ok(TxStatus::Failed) => "❌",
+                _ => "⏳",
+            };
+            let block = tx.block_number.map(|b| b.to_string()).unwrap_or_else(|| "pending".to_string());
+            let time = tx.timestamp
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| "—".to_string());
+            let gas_used = tx.gas_used.map(|g| g.to_string()).unwrap_or_else(|| "—".to_string());
+            let explorer_url = state.config.get_tx_explorer_url(chain.as_str(), false, &tx.hash);
+
+            let text = format!(
+                "{} Transaction {}\n\n\
+                Chain: {} {}\n\
+                Block: {}\n\
+                Time: {}\n\
+                From: {}\n\
+                To: {}\n\
+                Value: {} {}\n\
+                Gas used: {}\n\n\
+                🔗 {}",
+                status_emoji,
+                tx.status,
+                chain.emoji(),
+                chain.display_name(),
+                block,
+                time,
+                tx.from,
+                tx.to.as_deref().unwrap_or("contract creation"),
+                tx.value,
+                chain.native_symbol(),
+                gas_used,
+                explorer_url
             );
-            bot.send_message(msg.chat.id, response).await?;
+            bot.send_message(msg.chat.id, text).await?;
+        }
+        Ok(None) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("🔍 Transaction {} not found on {}", tx_hash, chain.display_name())
+            ).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_list_addresses(
+/// Candles shown by /chart
+const CHART_CANDLES: u32 = 24;
+/// Rows in the /chart range plot
+const CHART_HEIGHT: usize = 10;
+/// Highest highs and lowest lows averaged into the resistance and support levels
+const CHART_LEVEL_TOUCHES: usize = 3;
+
+/// Support and resistance as the average of the lowest lows and highest highs,
+/// the prices the market turned at more than once
+fn support_resistance(candles: &[price_service::Candle]) -> (f64, f64) {
+    let mut lows: Vec<f64> = candles.iter().map(|c| c.low).collect();
+    let mut highs: Vec<f64> = candles.iter().map(|c| c.high).collect();
+    lows.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    highs.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let touches = CHART_LEVEL_TOUCHES.min(candles.len()).max(1);
+    let support = lows.iter().take(touches).sum::<f64>() / touches as f64;
+    let resistance = highs.iter().take(touches).sum::<f64>() / touches as f64;
+    (support, resistance)
+}
+
+/// Prices under $1 keep enough decimals to be told apart
+fn format_chart_price(price: f64) -> String {
+    if price >= 1.0 { format_currency(price) } else { format!("{:.6}", price) }
+}
+
+async fn handle_chart(bot: Bot, msg: Message, args: String, state: Arc<BotState>) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let Some(symbol) = parts.first().map(|s| s.to_uppercase()) else {
+        bot.send_message(msg.chat.id, msg::ERR_CHART_USAGE).await?;
+        return Ok(());
+    };
+    let interval = match parts.get(1) {
+        Some(interval) =>
+            match interval.parse::<price_service::OhlcvInterval>() {
+                Ok(interval) => interval,
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
+                    return Ok(());
+                }
+            }
+        None => price_service::OhlcvInterval::Hour1,
+    };
+
+    let candles = match state.price_service.get_ohlcv(&symbol, interval, CHART_CANDLES).await {
+        Ok(candles) if candles.len() >= 2 => candles,
+        Ok(_) => {
+            bot.send_message(msg.chat.id, format!("📭 No candle data for {}", symbol)).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("❌ Failed to load chart: {}", e.user_facing_message())
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    let (support, resistance) = support_resistance(&candles);
+    let ranges: Vec<(f64, f64)> = candles
+        .iter()
+        .map(|c| (c.low, c.high))
+        .collect();
+    let rows = crate::bot::utils::chart::render_range_columns(
+        &ranges,
+        &[support, resistance],
+        CHART_HEIGHT
+    );
+    let high = ranges.iter().map(|(_, high)| *high).fold(f64::NEG_INFINITY, f64::max);
+    let low = ranges.iter().map(|(low, _)| *low).fold(f64::INFINITY, f64::min);
+
+    // Label the top and bottom rows with the range they span
+    let mut plot = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        plot.push_str(row);
+        if i == 0 {
+            plot.push_str(&format!(" ${}", format_chart_price(high)));
+        } else if i == rows.len() - 1 {
+            plot.push_str(&format!(" ${}", format_chart_price(low)));
+        }
+        plot.push('\n');
+    }
+
+    let last_close = candles[candles.len() - 1].close;
+    let text = format!(
+        "📈 *{} {}* \\- last {} candles\n\n```\n{}```\n\
+        ┈ Resistance: ${}\n\
+        ┈ Support: ${}\n\
+        Last close: ${}",
+        escape_markdown(&symbol),
+        escape_markdown(interval.as_str()),
+        candles.len(),
+        plot,
+        escape_markdown(&format_chart_price(resistance)),
+        escape_markdown(&format_chart_price(support)),
+        escape_markdown(&format_chart_price(last_close))
+    );
+
+    bot.send_message(msg.chat.id, text).parse_mode(ParseMode::MarkdownV2).await?;
+    Ok(())
+}
+
+async fn handle_clean_wallet(
     bot: Bot,
     msg: Message,
+    args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    match state.address_book_service.list_addresses(&user_id, None).await {
-        Ok(addresses) => {
-            if addresses.is_empty() {
-                bot.send_message(
-                    msg.chat.id,
-                    "📪 You don't have any saved addresses yet.\n\n\
-                    Use /saveaddress to add one!"
-                ).await?;
-                return Ok(());
-            }
-
-            let mut response = String::from("📖 *Your Address Book*\n\n");
-
-            for addr in addresses {
-                let notes_text = addr.notes
-                    .as_ref()
-                    .map(|n| format!("\n  📝 {}", escape_markdown(n)))
-                    .unwrap_or_default();
-
-                response.push_str(
-                    &format!(
-                        "*{}*\n\
-                    📍 {}\n\
-                    ⛓️ {}{}\n\n",
-                        escape_markdown(&addr.name),
-                        escape_markdown(&addr.address),
-                        escape_markdown(&addr.chain),
-                        notes_text
-                    )
-                );
-            }
+    let wallet_id = args.trim();
+    if wallet_id.is_empty() {
+        bot.send_message(msg.chat.id, msg::ERR_CLEAN_WALLET_USAGE).await?;
+        return Ok(());
+    }
 
-            response.push_str(
-                "💡 Use `/send <wallet_id> <name> <amount>` to send to saved addresses"
-            );
+    let status = bot.send_message(msg.chat.id, "🔍 Checking wallet for dust...").await?;
 
-            bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
+    match super::callbacks::prepare_dust_merge(wallet_id, &user_id, &state).await {
+        Ok(merge) => {
+            bot
+                .edit_message_text(msg.chat.id, status.id, merge.confirmation_text())
+                .reply_markup(keyboards::dust_merge(wallet_id)).await?;
         }
-        Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+        Err(text) => {
+            bot.edit_message_text(msg.chat.id, status.id, text).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_delete_address(
+async fn handle_find_wallet(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    let name = args.trim();
-
-    if name.is_empty() {
+    let prefix = args.trim();
+    if prefix.is_empty() {
         bot.send_message(
             msg.chat.id,
-            "Usage: /deleteaddress <name>\n\
-            Example: /deleteaddress alice"
+            "❌ Usage: /findwallet <partial_address>\n\nExample: /findwallet 0x742d"
         ).await?;
         return Ok(());
     }
 
-    match state.address_book_service.delete_address(&user_id, name).await {
-        Ok(_) => {
+    match state.wallet_service.find_wallets_by_partial_address(&user_id, prefix).await {
+        Ok(wallets) if wallets.is_empty() => {
+            bot.send_message(msg.chat.id, format!("🔍 No wallets found starting with {}", prefix)).await?;
+        }
+        Ok(wallets) => {
             bot.send_message(
                 msg.chat.id,
-                format!("✅ Address '{}' deleted successfully!", name)
-            ).await?;
+                format!("🔍 Found {} wallet(s) starting with {}:", wallets.len(), prefix)
+            )
+                .reply_markup(keyboards::wallet_matches(&wallets))
+                .await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-// Helper function to escape markdown special characters
-fn escape_markdown(text: &str) -> String {
-    text.replace('_', "\\_")
-        .replace('*', "\\*")
-        .replace('[', "\\[")
-        .replace(']', "\\]")
-        .replace('(', "\\(")
-        .replace(')', "\\)")
-        .replace('~', "\\~")
-        .replace('`', "\\`")
-        .replace('>', "\\>")
-        .replace('#', "\\#")
-        .replace('+', "\\+")
-        .replace('-', "\\-")
-        .replace('=', "\\=")
-        .replace('|', "\\|")
-        .replace('{', "\\{")
-        .replace('}', "\\}")
-        .replace('.', "\\.")
-        .replace('!', "\\!")
-}
-
-async fn handle_schedule(
+async fn handle_address(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    // Parse: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring]
-    // datetime format: "2024-01-15 14:30" or "2024-01-15T14:30:00"
-    // recurring: "daily" | "weekly" | "monthly"
-
-    let parts: Vec<&str> = args.split_whitespace().collect();
-
-    if parts.len() < 4 {
-        bot.send_message(
-            msg.chat.id,
-            "❌ Usage: /schedule <wallet_id> <to_address> <amount> <datetime> [token_address] [recurring]\n\n\
-            Examples:\n\
-            • /schedule abc123 0x742d... 1.5 \"2024-01-15 14:30\"\n\
-            • /schedule abc123 Alice 100 \"2024-01-15 14:30\" daily\n\
-            • /schedule abc123 0x742d... 50 \"2024-01-15T14:30:00\" 0xdac... weekly"
-        ).await?;
-        return Ok(());
-    }
-
-    let wallet_id = match Uuid::parse_str(parts[0]) {
+    let wallet_id = match Uuid::parse_str(args.trim()) {
         Ok(id) => id,
         Err(_) => {
-            bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
+            bot.send_message(
+                msg.chat.id,
+                "❌ Invalid wallet ID format\nUsage: /address <wallet_id>"
+            ).await?;
             return Ok(());
         }
     };
 
-    let mut to_address = parts[1].to_string();
+    match state.wallet_service.list_user_wallets(&user_id, None).await {
+        Ok(wallets) => {
+            if let Some(wallet) = wallets.iter().find(|w| w.id == wallet_id) {
+                let msg_text = format!(
+                    "📬 *Wallet Address*\n\n\
+                    📍 Chain: `{}`\n\
+                    🆔 ID: `{}`\n\
+                    📬 Address:\n`{}`\n\n\
+                    You can use this address to receive {} tokens\\.",
+                    escape_markdown(&wallet.chain),
+                    escape_markdown(&wallet.id.to_string()),
+                    escape_markdown(&wallet.address),
+                    escape_markdown(&wallet.chain)
+                );
 
-    // Check if it's an address book name
-    if !to_address.starts_with("0x") && !to_address.contains('.') {
-        if let Ok(saved) = state.address_book_service.get_address(&user_id, &to_address).await {
-            to_address = saved.address;
+                bot.send_message(msg.chat.id, msg_text).parse_mode(ParseMode::MarkdownV2).await?;
+            } else {
+                bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+            }
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Failed to get wallet: {}", e.user_facing_message())).await?;
         }
     }
 
-    let amount = parts[2].to_string();
+    Ok(())
+}
 
-    // Parse datetime (parts[3] and possibly parts[4] if it has space)
-    let datetime_str = if parts[3].contains('T') {
-        parts[3].to_string()
-    } else if parts.len() > 4 && (parts[4].contains(':') || parts[4].parse::<u8>().is_ok()) {
-        format!("{} {}", parts[3], parts[4])
-    } else {
-        parts[3].to_string()
-    };
+/// Days of history shown when /portfoliohistory is used without an argument
+const DEFAULT_HISTORY_DAYS: u32 = 7;
 
-    let scheduled_for = match
-        chrono::NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M")
-    {
-        Ok(dt) => chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(dt, chrono::Utc),
-        Err(_) =>
-            match chrono::DateTime::parse_from_rfc3339(&datetime_str) {
-                Ok(dt) => dt.with_timezone(&chrono::Utc),
-                Err(_) => {
+/// Fewest snapshots needed before a chart is meaningful
+const MIN_HISTORY_SNAPSHOTS: usize = 3;
+
+/// Sparkline width in characters, narrow enough for mobile clients
+const SPARKLINE_WIDTH: usize = 24;
+
+async fn handle_portfolio_history(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let days = match args.trim() {
+        "" => DEFAULT_HISTORY_DAYS,
+        arg =>
+            match arg.parse::<u32>() {
+                Ok(d) if (1..=365).contains(&d) => d,
+                _ => {
                     bot.send_message(
                         msg.chat.id,
-                        "❌ Invalid datetime format. Use: YYYY-MM-DD HH:MM or ISO8601"
+                        "❌ Days must be a number between 1 and 365\n\nUsage: /portfoliohistory [days]"
                     ).await?;
                     return Ok(());
                 }
             }
     };
 
-    // Check if datetime is in the future
-    if scheduled_for <= chrono::Utc::now() {
-        bot.send_message(msg.chat.id, "❌ Scheduled time must be in the future").await?;
+    let history = match state.portfolio_service.get_value_history(&user_id, days).await {
+        Ok(history) => history,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error fetching portfolio history: {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    };
+
+    if history.len() < MIN_HISTORY_SNAPSHOTS {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "📊 Not enough history yet for the last {} days.\n\n\
+                Portfolio values are recorded when you check /portfolio (at most once per hour). Check back later!",
+                days
+            )
+        ).await?;
         return Ok(());
     }
 
-    // Parse optional token and recurring type
-    let remaining_parts: Vec<&str> = if datetime_str.contains(' ') {
-        parts[5..].to_vec()
-    } else {
-        parts[4..].to_vec()
-    };
+    let values: Vec<f64> = history.iter().map(|(_, v)| *v).collect();
+    let first = values[0];
+    let current = values[values.len() - 1];
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let change_pct = if first > 0.0 { ((current - first) / first) * 100.0 } else { 0.0 };
+    let change_emoji = if change_pct > 0.0 { "📈" } else if change_pct < 0.0 { "📉" } else { "➖" };
+
+    let chart = crate::bot::utils::chart::render_sparkline(&values, SPARKLINE_WIDTH);
+
+    let response = format!(
+        "📊 *Portfolio History \\({} days\\)*\n\n\
+        `{}`\n\n\
+        💰 *Total Value:* ${}\n\
+        {} *Change:* {}{}%\n\n\
+        Min: ${} \\| Max: ${} \\| Current: ${}",
+        days,
+        chart,
+        escape_markdown(&format_currency(current)),
+        change_emoji,
+        if change_pct > 0.0 { "\\+" } else { "" },
+        escape_markdown(&format!("{:.2}", change_pct)),
+        escape_markdown(&format_currency(min)),
+        escape_markdown(&format_currency(max)),
+        escape_markdown(&format_currency(current))
+    );
 
-    let mut token_address = None;
-    let mut recurring_type: Option<RecurringType> = None;
+    bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
 
-    for part in remaining_parts {
-        if part.starts_with("0x") || part.starts_with("0X") {
-            token_address = Some(part.to_string());
-        } else if let Ok(rt) = part.to_lowercase().parse::<RecurringType>() {
-            recurring_type = Some(rt);
-        }
-    }
+    Ok(())
+}
 
-    // Verify wallet ownership
-    match state.wallet_service.get_wallet(wallet_id).await {
-        Ok(wallet) => {
-            if wallet.user_id != user_id {
-                bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
-                return Ok(());
+/// Days compared when /benchmark is used without an argument
+const DEFAULT_BENCHMARK_DAYS: u32 = 30;
+
+async fn handle_benchmark(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let days = match args.trim() {
+        "" => DEFAULT_BENCHMARK_DAYS,
+        arg =>
+            match arg.parse::<u32>() {
+                Ok(d) if (1..=365).contains(&d) => d,
+                _ => {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ Days must be a number between 1 and 365\n\nUsage: /benchmark [days]"
+                    ).await?;
+                    return Ok(());
+                }
             }
+    };
+
+    match state.portfolio_service.compare_to_benchmark(&user_id, days).await {
+        Ok(c) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "📊 Benchmark ({} days)\n\n\
+                    Your portfolio: {:+.1}% vs ETH: {:+.1}% vs BTC: {:+.1}% | Alpha: {:+.1}%",
+                    days,
+                    c.portfolio_return_pct,
+                    c.eth_return_pct,
+                    c.btc_return_pct,
+                    c.alpha
+                )
+            ).await?;
         }
-        Err(_) => {
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_watch_balance(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (wallet_id, stop) = match parts.as_slice() {
+        [id] => (Uuid::parse_str(id), false),
+        [id, off] if off.eq_ignore_ascii_case("off") => (Uuid::parse_str(id), true),
+        _ => {
+            bot.send_message(msg.chat.id, msg::ERR_WATCH_BALANCE_USAGE).await?;
+            return Ok(());
+        }
+    };
+    let Ok(wallet_id) = wallet_id else {
+        bot.send_message(msg.chat.id, msg::ERR_INVALID_WALLET_ID).await?;
+        return Ok(());
+    };
+    let wallet = match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(w) if w.user_id == user_id => w,
+        _ => {
             bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
             return Ok(());
         }
+    };
+    let label = format!("{} wallet {}", wallet.chain, &wallet.id.to_string()[..8]);
+
+    if stop {
+        let text = if state.balance_watcher.unwatch(msg.chat.id, wallet_id) {
+            format!("🔕 Stopped watching {}", label)
+        } else {
+            format!("ℹ️ {} isn't being watched", label)
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
     }
 
-    // Schedule the transaction
-    let schedule_req = ScheduleRequest {
-        user_id,
-        wallet_id,
-        to_address,
-        amount,
-        token_address,
-        scheduled_for,
-        recurring_type: recurring_type.clone(),
-    };
+    match state.balance_watcher.watch(msg.chat.id, wallet_id, label.clone()).await {
+        Ok(true) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "👀 Watching {}. You'll be notified when its balance moves by {}% or more.\nStop with /watchbalance {} off",
+                    label,
+                    state.balance_watcher.threshold_pct(),
+                    wallet_id
+                )
+            ).await?;
+        }
+        Ok(false) => {
+            bot.send_message(msg.chat.id, format!("ℹ️ {} is already being watched", label)).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
+        }
+    }
 
-    match state.scheduling_service.schedule_transaction(schedule_req).await {
-        Ok(schedule) => {
-            let recurring_text = if let Some(rec) = recurring_type {
-                format!(" \\({}\\)", escape_markdown(rec.as_str()))
-            } else {
-                String::new()
-            };
+    Ok(())
+}
 
-            bot
-                .send_message(
-                    msg.chat.id,
-                    format!(
-                        "✅ *Transaction Scheduled*\n\n\
-                    📅 Schedule ID: `{}`\n\
-                    ⏰ Scheduled for: {}{}\n\
-                    💸 Amount: {}\n\
-                    📍 To: `{}`\n\n\
-                    The transaction will be executed automatically at the scheduled time\\.",
-                        escape_markdown(&schedule.id.to_string()),
-                        escape_markdown(&scheduled_for.format("%Y-%m-%d %H:%M UTC").to_string()),
-                        recurring_text,
-                        escape_markdown(&schedule.amount),
-                        escape_markdown(&schedule.to_address)
-                    )
-                )
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+const DEFAULT_FEE_DAYS: u32 = 30;
+
+async fn handle_fees(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let days = match args.trim() {
+        "" => DEFAULT_FEE_DAYS,
+        arg =>
+            match arg.parse::<u32>() {
+                Ok(d) if (1..=365).contains(&d) => d,
+                _ => {
+                    bot.send_message(msg.chat.id, msg::ERR_FEES_USAGE).await?;
+                    return Ok(());
+                }
+            }
+    };
+
+    let to = chrono::Utc::now();
+    let from = to - chrono::Duration::days(days as i64);
+    match state.transaction_service.get_fee_summary(&user_id, from, to).await {
+        Ok(summary) if summary.tx_count == 0 => {
+            bot.send_message(
+                msg.chat.id,
+                format!("💸 No fees recorded in the last {} days.", days)
+            ).await?;
+        }
+        Ok(summary) => {
+            bot.send_message(msg.chat.id, crate::bot::utils::format_fee_summary(&summary, days)).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Failed to schedule: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_list_scheduled(
+async fn handle_portfolio(
     bot: Bot,
     msg: Message,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    match state.scheduling_service.list_scheduled(&user_id, Some(ScheduleStatus::Pending.as_str())).await {
-        Ok(schedules) => {
-            if schedules.is_empty() {
-                bot
-                    .send_message(msg.chat.id, "📅 No scheduled transactions\\.")
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+    bot.send_message(msg.chat.id, "⏳ Fetching your portfolio...").await?;
+
+    match state.portfolio_service.get_portfolio(&user_id).await {
+        Ok(portfolio) => {
+            if portfolio.holdings.is_empty() {
+                bot.send_message(
+                    msg.chat.id,
+                    "📭 Your portfolio is empty.\n\nCreate a wallet with: /createwallet <chain>"
+                ).await?;
                 return Ok(());
             }
 
-            let mut response = String::from("📅 *Scheduled Transactions*\n\n");
+            let (currency, rate) = user_fiat(&state, &user_id).await;
+            let mut response = String::from("💼 *Your Portfolio*\n\n");
 
-            for schedule in schedules {
-                let recurring = if let Some(rec) = schedule.recurring_type {
-                    format!(" \\({}\\)", escape_markdown(&rec))
+            for holding in &portfolio.holdings {
+                let change_emoji = match holding.price_change_24h {
+                    Some(change) if change > 0.0 => "📈",
+                    Some(change) if change < 0.0 => "📉",
+                    _ => "➖",
+                };
+
+                let change_text = if let Some(change) = holding.price_change_24h {
+                    format!(" \\({}{:.2}%\\)", if change > 0.0 { "+" } else { "" }, change)
                 } else {
                     String::new()
                 };
 
                 response.push_str(
                     &format!(
-                        "• ID: `{}`\n\
-                     ⏰ {}{}\n\
-                     💸 {} → `{}`\n\
-                     🆔 Wallet: `{}`\n\n",
-                        escape_markdown(&schedule.id.to_string()),
+                        "*{}:* {} {} \\({}\\) {}{}\n",
+                        escape_markdown(&holding.symbol),
                         escape_markdown(
-                            &schedule.scheduled_for.format("%Y-%m-%d %H:%M UTC").to_string()
+                            &format!("{:.6}", holding.total_balance)
+                                .trim_end_matches('0')
+                                .trim_end_matches('.')
                         ),
-                        recurring,
-                        escape_markdown(&schedule.amount),
-                        escape_markdown(&schedule.to_address),
-                        escape_markdown(&schedule.wallet_id.to_string())
+                        escape_markdown(&holding.symbol),
+                        escape_markdown(&format_fiat(holding.usd_value * rate, currency)),
+                        change_emoji,
+                        change_text
+                    )
+                );
+
+                response.push_str(
+                    &format!(
+                        "  💵 {} per {}\n",
+                        escape_markdown(&format_fiat(holding.usd_price * rate, currency)),
+                        escape_markdown(&holding.symbol)
+                    )
+                );
+
+                if holding.wallets.len() > 1 {
+                    response.push_str(&format!("  📦 {} wallets\n", holding.wallets.len()));
+                }
+
+                response.push_str("\n");
+            }
+
+            response.push_str(
+                &format!(
+                    "━━━━━━━━━━━━━━━━\n\
+                💰 *Total Value:* {}\n\
+                📊 {} chains \\| {} wallets",
+                    escape_markdown(&format_fiat(portfolio.total_usd_value * rate, currency)),
+                    portfolio.chains.len(),
+                    portfolio.wallet_count
+                )
+            );
+
+            let targets = state.rebalancing_service.list_targets(&user_id).await.unwrap_or_default();
+            let allocations = rebalancing_service::RebalancingService::compute_allocations(
+                &portfolio,
+                &targets
+            );
+            if !allocations.is_empty() {
+                response.push_str("\n\n⚖️ *Allocations*\n");
+                for allocation in &allocations {
+                    response.push_str(
+                        &format!(
+                            "{} {}: {}% \\(target {}%\\)\n",
+                            if allocation.needs_rebalance() { "⚠️" } else { "✅" },
+                            escape_markdown(&allocation.symbol),
+                            escape_markdown(&format!("{:.1}", allocation.actual_pct)),
+                            escape_markdown(&allocation.target_pct.to_string())
+                        )
+                    );
+                }
+            }
+
+            let dca_plans = state.dca_service.list_dca_plans(&user_id).await.unwrap_or_default();
+            let active_plans: Vec<_> = dca_plans
+                .iter()
+                .filter(|dca| dca.plan.status == DcaStatus::Active.as_str())
+                .collect();
+            if !active_plans.is_empty() {
+                response.truncate(response.trim_end().len());
+                response.push_str("\n\n🔁 *DCA Plans*\n");
+                for dca in active_plans {
+                    response.push_str(
+                        &format!(
+                            "{} {} → {} \\({}\\), next {}\n",
+                            escape_markdown(&dca.plan.amount_per_period.to_string()),
+                            escape_markdown(&dca.plan.from_token),
+                            escape_markdown(&dca.plan.to_token),
+                            escape_markdown(&dca.plan.period),
+                            escape_markdown(&format_next_execution(dca.next_execution))
+                        )
+                    );
+                }
+            }
+
+            bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Failed to fetch portfolio: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_prices(
+    bot: Bot,
+    msg: Message,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    bot.send_message(msg.chat.id, "⏳ Fetching prices...").await?;
+
+    // Build unique native symbols from all configured chains
+    let symbols: Vec<String> = state.config.configured_chains()
+        .iter()
+        .map(|c| c.native_symbol().to_string())
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    match state.price_service.get_prices(&symbols).await {
+        Ok(prices) => {
+            let (currency, rate) = user_fiat(&state, &user_id).await;
+            let mut response = String::from("💵 *Cryptocurrency Prices*\n\n");
+
+            // Sort by price descending for consistent display
+            let mut sorted: Vec<_> = prices.iter().collect();
+            sorted.sort_by(|a, b| b.1.usd_price.partial_cmp(&a.1.usd_price).unwrap_or(std::cmp::Ordering::Equal));
+
+            for (symbol, price) in &sorted {
+                let emoji = symbol.parse::<Chain>()
+                    .map(|c| c.emoji())
+                    .unwrap_or("🪙");
+
+                let change_emoji = match price.price_change_24h {
+                    Some(change) if change > 0.0 => "📈",
+                    Some(change) if change < 0.0 => "📉",
+                    _ => "➖",
+                };
+
+                let change_text = if let Some(change) = price.price_change_24h {
+                    format!(" \\({}{:.2}%\\)", if change > 0.0 { "\\+" } else { "" }, change)
+                } else {
+                    String::new()
+                };
+
+                response.push_str(
+                    &format!(
+                        "{} *{}:* {} {}{}\n",
+                        emoji,
+                        escape_markdown(symbol),
+                        escape_markdown(&format_fiat(price.usd_price * rate, currency)),
+                        change_emoji,
+                        change_text
+                    )
+                );
+
+                if let Some(market_cap) = price.market_cap {
+                    if market_cap > 0.0 {
+                        response.push_str(
+                            &format!(
+                                "  📊 Cap: {}B {}\n",
+                                escape_markdown(&format!("{:.1}", (market_cap * rate) / 1_000_000_000.0)),
+                                currency
+                            )
+                        );
+                    }
+                }
+
+                response.push_str("\n");
+            }
+
+            response.push_str("_Updated just now_");
+
+            let symbols: Vec<&str> = sorted.iter().map(|(symbol, _)| symbol.as_str()).collect();
+            bot.send_message(msg.chat.id, response)
+                .parse_mode(ParseMode::MarkdownV2)
+                .reply_markup(keyboards::prices_keyboard(&symbols))
+                .await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Failed to fetch prices: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_save_address(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.trim().split_whitespace().collect();
+
+    if parts.len() < 3 {
+        bot.send_message(
+            msg.chat.id,
+            "Usage: /saveaddress <name> <address> <chain> [notes]\n\
+            Example: /saveaddress alice 0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb ETH"
+        ).await?;
+        return Ok(());
+    }
+
+    let name = parts[0].to_string();
+    let address = parts[1].to_string();
+    let notes = if parts.len() > 3 { Some(parts[3..].join(" ")) } else { None };
+
+    // Validate chain
+    let chain = match parts[2].to_uppercase().parse::<Chain>() {
+        Ok(c) => c,
+        Err(_) => {
+            bot.send_message(msg.chat.id, msg::ERR_INVALID_CHAIN).await?;
+            return Ok(());
+        }
+    };
+
+    match
+        state.address_book_service.save_address(
+            user_id,
+            name.clone(),
+            address.clone(),
+            chain.to_string(),
+            notes
+        ).await
+    {
+        Ok(_) => {
+            let response = format!(
+                "✅ Address saved successfully!\n\n\
+                Name: {}\n\
+                Address: {}\n\
+                Chain: {}\n\n\
+                Use /addresses to view all saved addresses",
+                name,
+                address,
+                chain
+            );
+            bot.send_message(msg.chat.id, response).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_list_addresses(
+    bot: Bot,
+    msg: Message,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    match state.address_book_service.list_addresses(&user_id, None).await {
+        Ok(addresses) => {
+            if addresses.is_empty() {
+                bot.send_message(
+                    msg.chat.id,
+                    "📪 You don't have any saved addresses yet.\n\n\
+                    Use /saveaddress to add one!"
+                ).await?;
+                return Ok(());
+            }
+
+            let mut response = String::from("📖 *Your Address Book*\n\n");
+
+            for addr in addresses {
+                let notes_text = addr.notes
+                    .as_ref()
+                    .map(|n| format!("\n  📝 {}", escape_markdown(n)))
+                    .unwrap_or_default();
+
+                response.push_str(
+                    &format!(
+                        "*{}*\n\
+                    📍 {}\n\
+                    ⛓️ {}{}\n\n",
+                        escape_markdown(&addr.name),
+                        escape_markdown(&addr.address),
+                        escape_markdown(&addr.chain),
+                        notes_text
+                    )
+                );
+            }
+
+            response.push_str(
+                "💡 Use `/send <wallet_id> <name> <amount>` to send to saved addresses"
+            );
+
+            bot.send_message(msg.chat.id, response).parse_mode(ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_delete_address(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let name = args.trim();
+
+    if name.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "Usage: /deleteaddress <name>\n\
+            Example: /deleteaddress alice"
+        ).await?;
+        return Ok(());
+    }
+
+    match state.address_book_service.delete_address(&user_id, name).await {
+        Ok(_) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("✅ Address '{}' deleted successfully!", name)
+            ).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Helper function to escape markdown special characters
+pub(super) fn escape_markdown(text: &str) -> String {
+    text.replace('_', "\\_")
+        .replace('*', "\\*")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace('(', "\\(")
+        .replace(')', "\\)")
+        .replace('~', "\\~")
+        .replace('`', "\\`")
+        .replace('>', "\\>")
+        .replace('#', "\\#")
+        .replace('+', "\\+")
+        .replace('-', "\\-")
+        .replace('=', "\\=")
+        .replace('|', "\\|")
+        .replace('{', "\\{")
+        .replace('}', "\\}")
+        .replace('.', "\\.")
+        .replace('!', "\\!")
+}
+
+async fn handle_schedule(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Parse: /schedule <wallet_id> <to> <amount> <datetime> [token] [recurring]
+    // datetime format: "2024-01-15 14:30" or "2024-01-15T14:30:00"
+    // recurring: "daily" | "weekly" | "monthly"
+
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() < 4 {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Usage: /schedule <wallet_id> <to_address> <amount> <datetime> [token_address] [recurring] [--maxgas <gwei>]\n\n\
+            Examples:\n\
+            • /schedule abc123 0x742d... 1.5 \"2024-01-15 14:30\"\n\
+            • /schedule abc123 Alice 100 \"2024-01-15 14:30\" daily\n\
+            • /schedule abc123 0x742d... 50 \"2024-01-15T14:30:00\" 0xdac... weekly\n\
+            • /schedule abc123 0x742d... 1.5 \"2024-01-15 14:30\" --maxgas 20"
+        ).await?;
+        return Ok(());
+    }
+
+    let wallet_id = match Uuid::parse_str(parts[0]) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
+            return Ok(());
+        }
+    };
+
+    let mut to_address = parts[1].to_string();
+
+    // Check if it's an address book name
+    if !to_address.starts_with("0x") && !to_address.contains('.') {
+        if let Ok(saved) = state.address_book_service.get_address(&user_id, &to_address).await {
+            to_address = saved.address;
+        }
+    }
+
+    let amount = parts[2].to_string();
+
+    // Parse datetime (parts[3] and possibly parts[4] if it has space)
+    let datetime_str = if parts[3].contains('T') {
+        parts[3].to_string()
+    } else if parts.len() > 4 && (parts[4].contains(':') || parts[4].parse::<u8>().is_ok()) {
+        format!("{} {}", parts[3], parts[4])
+    } else {
+        parts[3].to_string()
+    };
+
+    let scheduled_for = match
+        chrono::NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M")
+    {
+        Ok(dt) => chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(dt, chrono::Utc),
+        Err(_) =>
+            match chrono::DateTime::parse_from_rfc3339(&datetime_str) {
+                Ok(dt) => dt.with_timezone(&chrono::Utc),
+                Err(_) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        "❌ Invalid datetime format. Use: YYYY-MM-DD HH:MM or ISO8601"
+                    ).await?;
+                    return Ok(());
+                }
+            }
+    };
+
+    // Check if datetime is in the future
+    if scheduled_for <= chrono::Utc::now() {
+        bot.send_message(msg.chat.id, "❌ Scheduled time must be in the future").await?;
+        return Ok(());
+    }
+
+    // Parse optional token and recurring type
+    let remaining_parts: Vec<&str> = if datetime_str.contains(' ') {
+        parts[5..].to_vec()
+    } else {
+        parts[4..].to_vec()
+    };
+
+    let mut token_address = None;
+    let mut recurring_type: Option<RecurringType> = None;
+    let mut max_gas_price_gwei: Option<f64> = None;
+
+    let mut remaining = remaining_parts.into_iter();
+    while let Some(part) = remaining.next() {
+        if part == "--maxgas" {
+            match remaining.next().and_then(|v| v.parse::<f64>().ok()) {
+                Some(gwei) if gwei > 0.0 => {
+                    max_gas_price_gwei = Some(gwei);
+                }
+                _ => {
+                    bot.send_message(msg.chat.id, "❌ --maxgas needs a positive Gwei value").await?;
+                    return Ok(());
+                }
+            }
+        } else if part.starts_with("0x") || part.starts_with("0X") {
+            token_address = Some(part.to_string());
+        } else if let Ok(rt) = part.to_lowercase().parse::<RecurringType>() {
+            recurring_type = Some(rt);
+        }
+    }
+
+    // Verify wallet ownership
+    match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(wallet) => {
+            if wallet.user_id != user_id {
+                bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+                return Ok(());
+            }
+            let is_evm = wallet.chain.parse::<Chain>().map(|c| c.is_evm()).unwrap_or(false);
+            if max_gas_price_gwei.is_some() && !is_evm {
+                bot.send_message(msg.chat.id, "❌ --maxgas is only supported for EVM wallets").await?;
+                return Ok(());
+            }
+        }
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+            return Ok(());
+        }
+    }
+
+    let schedule_req = ScheduleRequest {
+        user_id,
+        wallet_id,
+        to_address,
+        amount,
+        token_address,
+        scheduled_for,
+        recurring_type,
+        max_gas_price_gwei,
+    };
+
+    let warnings = match state.scheduling_service.validate_schedule(&schedule_req).await {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Failed to schedule: {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    };
+
+    let mut text = if warnings.is_empty() {
+        String::from("📅 Schedule check passed\n")
+    } else {
+        String::from("⚠️ Schedule check\n\n")
+    };
+    for warning in &warnings {
+        let icon = match warning.severity {
+            ScheduleWarningSeverity::Critical => "❌",
+            ScheduleWarningSeverity::Warning => "⚠️",
+            ScheduleWarningSeverity::Info => "ℹ️",
+        };
+        text.push_str(&format!("{} {}\n", icon, warning.message));
+    }
+
+    if warnings.iter().any(|w| w.severity == ScheduleWarningSeverity::Critical) {
+        text.push_str("\nThe schedule was not created.");
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let dialogue_user_id = crate::bot::dialogue_key(
+        msg.chat.id,
+        msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0)
+    );
+    let pending = crate::bot::DialogueState::PendingSchedule { request: schedule_req };
+    if let Err(e) = state.dialogue_storage.set(dialogue_user_id, pending).await {
+        bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        return Ok(());
+    }
+
+    text.push_str("\nSimulate it against your current balance before creating it.");
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboards::schedule_simulate())
+        .await?;
+
+    Ok(())
+}
+
+/// Store a validated schedule and confirm it to the user
+pub(super) async fn create_schedule(
+    bot: &Bot,
+    chat_id: ChatId,
+    schedule_req: ScheduleRequest,
+    state: &Arc<BotState>
+) -> ResponseResult<()> {
+    let scheduled_for = schedule_req.scheduled_for;
+    let recurring_type = schedule_req.recurring_type;
+    let max_gas_price_gwei = schedule_req.max_gas_price_gwei;
+
+    match state.scheduling_service.schedule_transaction(schedule_req).await {
+        Ok(schedule) => {
+            let recurring_text = if let Some(rec) = recurring_type {
+                format!(" \\({}\\)", escape_markdown(rec.as_str()))
+            } else {
+                String::new()
+            };
+            let gas_text = max_gas_price_gwei
+                .map(|gwei| format!("⛽ Max gas: {} Gwei\n", escape_markdown(&gwei.to_string())))
+                .unwrap_or_default();
+
+            bot
+                .send_message(
+                    chat_id,
+                    format!(
+                        "✅ *Transaction Scheduled*\n\n\
+                    📅 Schedule ID: `{}`\n\
+                    ⏰ Scheduled for: {}{}\n\
+                    💸 Amount: {}\n\
+                    📍 To: `{}`\n\
+                    {}\n\
+                    The transaction will be executed automatically at the scheduled time\\.",
+                        escape_markdown(&schedule.id.to_string()),
+                        escape_markdown(&scheduled_for.format("%Y-%m-%d %H:%M UTC").to_string()),
+                        recurring_text,
+                        escape_markdown(&schedule.amount),
+                        escape_markdown(&schedule.to_address),
+                        gas_text
+                    )
+                )
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, format!("❌ Failed to schedule: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_list_scheduled(
+    bot: Bot,
+    msg: Message,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    match state.scheduling_service.list_scheduled(&user_id, Some(ScheduleStatus::Pending.as_str())).await {
+        Ok(schedules) => {
+            if schedules.is_empty() {
+                bot
+                    .send_message(msg.chat.id, "📅 No scheduled transactions\\.")
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+                return Ok(());
+            }
+
+            let mut response = String::from("📅 *Scheduled Transactions*\n\n");
+
+            for schedule in schedules {
+                let recurring = if let Some(rec) = schedule.recurring_type {
+                    format!(" \\({}\\)", escape_markdown(&rec))
+                } else {
+                    String::new()
+                };
+                let gas = schedule.max_gas_price_gwei
+                    .map(|gwei| format!("⛽ Max gas: {} Gwei\n", escape_markdown(&gwei.to_string())))
+                    .unwrap_or_default();
+
+                response.push_str(
+                    &format!(
+                        "• ID: `{}`\n\
+                     ⏰ {}{}\n\
+                     💸 {} → `{}`\n\
+                     {}\
+                     🆔 Wallet: `{}`\n\n",
+                        escape_markdown(&schedule.id.to_string()),
+                        escape_markdown(
+                            &schedule.scheduled_for.format("%Y-%m-%d %H:%M UTC").to_string()
+                        ),
+                        recurring,
+                        escape_markdown(&schedule.amount),
+                        escape_markdown(&schedule.to_address),
+                        gas,
+                        escape_markdown(&schedule.wallet_id.to_string())
+                    )
+                );
+            }
+
+            response.push_str("Use /cancelschedule <id> to cancel\\.");
+
+            bot
+                .send_message(msg.chat.id, response)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+const DEFAULT_CALENDAR_DAYS: i64 = 7;
+const MAX_CALENDAR_DAYS: i64 = 90;
+
+async fn handle_calendar(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let days = match args.trim() {
+        "" => DEFAULT_CALENDAR_DAYS,
+        arg =>
+            match arg.parse::<i64>() {
+                Ok(days) if (1..=MAX_CALENDAR_DAYS).contains(&days) => days,
+                _ => {
+                    bot.send_message(msg.chat.id, msg::ERR_CALENDAR_USAGE).await?;
+                    return Ok(());
+                }
+            }
+    };
+
+    let from = chrono::Utc::now();
+    let to = from + chrono::Duration::days(days);
+    let executions = match state.scheduling_service.get_execution_calendar(&user_id, from, to).await {
+        Ok(executions) => executions,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    };
+
+    if executions.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            format!("📅 Nothing scheduled in the next {} day(s).", days)
+        ).await?;
+        return Ok(());
+    }
+
+    // Show recipients by their address book name where the user saved one
+    let contacts: std::collections::HashMap<String, String> = state.address_book_service
+        .list_addresses(&user_id, None).await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| (c.address.to_lowercase(), c.name))
+        .collect();
+    let shorten = |value: &str| {
+        if value.len() > 16 {
+            format!("{}...{}", &value[..6], &value[value.len() - 4..])
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut response = format!("📅 Next {} day(s)\n\n", days);
+    let mut current_day = None;
+    for execution in &executions {
+        let day = execution.execute_at.date_naive();
+        let recipient = contacts
+            .get(&execution.to_address.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| shorten(&execution.to_address));
+        let entry = format!("{} {} → {}", execution.amount, shorten(&execution.token), recipient);
+
+        if current_day == Some(day) {
+            response.push_str(&format!(" | {}", entry));
+        } else {
+            if current_day.is_some() {
+                response.push('\n');
+            }
+            response.push_str(&format!("📅 {}: {}", day.format("%b %-d"), entry));
+            current_day = Some(day);
+        }
+    }
+
+    bot.send_message(msg.chat.id, response).await?;
+
+    Ok(())
+}
+
+const DCA_USAGE: &str =
+    "❌ Usage:\n\
+    /dca create <wallet_id> <from> <to> <amount> <daily|weekly|monthly>\n\
+    /dca list\n\
+    /dca pause|resume|cancel <plan_id>\n\n\
+    Example: /dca create abc123 USDC ETH 50 weekly";
+
+async fn handle_defi(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let Ok(wallet_id) = Uuid::parse_str(args.trim()) else {
+        bot.send_message(msg.chat.id, msg::ERR_DEFI_USAGE).await?;
+        return Ok(());
+    };
+    let wallet = match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(w) if w.user_id == user_id => w,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+            return Ok(());
+        }
+    };
+
+    let status = bot.send_message(msg.chat.id, "🔍 Loading DeFi positions...").await?;
+
+    let positions = match state.defi_protocol_service.get_positions(wallet.id).await {
+        Ok(p) => p,
+        Err(e) => {
+            bot.edit_message_text(
+                msg.chat.id,
+                status.id,
+                format!("❌ Failed to load DeFi positions: {}", e.user_facing_message())
+            ).await?;
+            return Ok(());
+        }
+    };
+    if positions.is_empty() {
+        bot.edit_message_text(
+            msg.chat.id,
+            status.id,
+            "🏦 No Aave or Compound supply positions found for this wallet."
+        ).await?;
+        return Ok(());
+    }
+
+    let plans = state.dca_service.list_auto_compound_plans(wallet_id).await.unwrap_or_default();
+    let mut protocols: Vec<DefiProtocol> = positions
+        .iter()
+        .map(|p| p.protocol)
+        .collect();
+    protocols.dedup();
+
+    let text = format!(
+        "🌾 DeFi Positions ({})\n\n{}Auto-compound claims rewards {} and supplies them back once they're worth ${:.2}.",
+        wallet.chain,
+        crate::bot::utils::format_defi_positions(&positions),
+        state.config.auto_compound_interval,
+        crate::services::defi_protocol_service::DEFAULT_MIN_COMPOUND_PROFIT_USD
+    );
+
+    bot
+        .edit_message_text(msg.chat.id, status.id, text)
+        .reply_markup(keyboards::defi_auto_compound(&wallet_id.to_string(), &protocols, &plans)).await?;
+
+    Ok(())
+}
+
+async fn handle_dca(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    match parts.as_slice() {
+        ["create", wallet_id, from_token, to_token, amount, period] => {
+            let Ok(wallet_id) = Uuid::parse_str(wallet_id) else {
+                bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
+                return Ok(());
+            };
+            let Ok(amount) = amount.parse::<f64>() else {
+                bot.send_message(msg.chat.id, "❌ Invalid amount format").await?;
+                return Ok(());
+            };
+            let period = match period.parse::<RecurringType>() {
+                Ok(p) => p,
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
+                    return Ok(());
+                }
+            };
+
+            match
+                state.dca_service.create_dca_plan(
+                    &user_id,
+                    wallet_id,
+                    from_token,
+                    to_token,
+                    amount,
+                    period,
+                    chrono::Utc::now()
+                ).await
+            {
+                Ok(dca) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        format!(
+                            "✅ DCA Plan Created\n\n\
+                            🔁 {} {} → {} ({})\n\
+                            ⏰ First buy: {}\n\
+                            🆔 Plan: {}\n\n\
+                            Manage it with /dca pause|resume|cancel <plan_id>",
+                            dca.plan.amount_per_period,
+                            dca.plan.from_token,
+                            dca.plan.to_token,
+                            dca.plan.period,
+                            format_next_execution(dca.next_execution),
+                            dca.plan.id
+                        )
+                    ).await?;
+                }
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+                }
+            }
+        }
+        ["list"] => {
+            match state.dca_service.list_dca_plans(&user_id).await {
+                Ok(plans) if plans.is_empty() => {
+                    bot.send_message(
+                        msg.chat.id,
+                        "🔁 No DCA plans.\n\nCreate one with /dca create <wallet_id> <from> <to> <amount> <period>"
+                    ).await?;
+                }
+                Ok(plans) => {
+                    let mut text = String::from("🔁 Your DCA Plans\n\n");
+                    for dca in &plans {
+                        text.push_str(
+                            &format!(
+                                "• {} {} → {} ({}) — {}\n  ⏰ Next: {}\n  🆔 {}\n\n",
+                                dca.plan.amount_per_period,
+                                dca.plan.from_token,
+                                dca.plan.to_token,
+                                dca.plan.period,
+                                dca.plan.status,
+                                format_next_execution(dca.next_execution),
+                                dca.plan.id
+                            )
+                        );
+                    }
+                    bot.send_message(msg.chat.id, text).await?;
+                }
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+                }
+            }
+        }
+        [action @ ("pause" | "resume" | "cancel"), plan_id] => {
+            let Ok(plan_id) = Uuid::parse_str(plan_id) else {
+                bot.send_message(msg.chat.id, "❌ Invalid plan ID format").await?;
+                return Ok(());
+            };
+
+            let result = match *action {
+                "pause" =>
+                    state.dca_service
+                        .pause_dca_plan(plan_id, &user_id).await
+                        .map(|_| "⏸ DCA plan paused".to_string()),
+                "resume" =>
+                    state.dca_service
+                        .resume_dca_plan(plan_id, &user_id).await
+                        .map(|dca| {
+                            format!(
+                                "▶️ DCA plan resumed\n⏰ Next buy: {}",
+                                format_next_execution(dca.next_execution)
+                            )
+                        }),
+                _ =>
+                    state.dca_service
+                        .cancel_dca_plan(plan_id, &user_id).await
+                        .map(|_| "✅ DCA plan cancelled".to_string()),
+            };
+
+            match result {
+                Ok(text) => {
+                    bot.send_message(msg.chat.id, text).await?;
+                }
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+                }
+            }
+        }
+        _ => {
+            bot.send_message(msg.chat.id, DCA_USAGE).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(super) fn format_next_execution(next: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    next.map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_else(|| "—".to_string())
+}
+
+async fn handle_cancel_schedule(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let schedule_id = match Uuid::parse_str(args.trim()) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Invalid schedule ID format").await?;
+            return Ok(());
+        }
+    };
+
+    match state.scheduling_service.cancel_schedule(schedule_id, &user_id).await {
+        Ok(_) => {
+            bot
+                .send_message(msg.chat.id, "✅ *Scheduled transaction cancelled*")
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+// ==================== PHASE 7: PRICE ALERT HANDLERS ====================
+
+async fn handle_set_alert(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Parse: /setalert <symbol> <above|below|percent> <value> [chain]
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() < 3 {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Usage: /setalert <symbol> <above|below|percent> <value> [chain]\n\n\
+            Examples:\n\
+            • /setalert ETH above 3000\n\
+            • /setalert BTC below 40000\n\
+            • /setalert SOL percent 10 SOLANA"
+        ).await?;
+        return Ok(());
+    }
+
+    let symbol = parts[0].to_uppercase();
+    let alert_type_str = parts[1].to_lowercase();
+    let value_str = parts[2];
+    let chain = if parts.len() > 3 { parts[3].to_uppercase() } else { Chain::Eth.to_string() };
+
+    let value: f64 = match value_str.parse() {
+        Ok(v) => v,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Invalid value format").await?;
+            return Ok(());
+        }
+    };
+
+    // Parse alert kind
+    let alert_kind = match alert_type_str.parse::<AlertKind>() {
+        Ok(k) => k,
+        Err(_) => {
+            bot.send_message(
+                msg.chat.id,
+                "❌ Alert type must be 'above', 'below', or 'percent'"
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    // Build typed AlertType
+    let alert_type = match alert_kind {
+        AlertKind::Above => AlertType::Above { target_price: value },
+        AlertKind::Below => AlertType::Below { target_price: value },
+        AlertKind::StopLoss => AlertType::StopLoss { stop_price: value, token_address: None },
+        AlertKind::TakeProfit => AlertType::TakeProfit { target_price: value },
+        AlertKind::PercentChange => {
+            match state.price_service.get_price(&symbol).await {
+                Ok(current_price) => {
+                    AlertType::PercentChange {
+                        percent: value,
+                        base_price: current_price.usd_price,
+                    }
+                }
+                Err(e) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        format!("❌ Could not get current price: {}", e.user_facing_message())
+                    ).await?;
+                    return Ok(());
+                }
+            }
+        }
+        AlertKind::TrailingStop => {
+            bot.send_message(
+                msg.chat.id,
+                "❌ Trailing stops are set with /settrailstop <symbol> <trail_pct> [chain]"
+            ).await?;
+            return Ok(());
+        }
+        AlertKind::LargeIncoming => {
+            bot.send_message(
+                msg.chat.id,
+                "❌ Large incoming alerts are set per wallet with /setmempool <wallet_id> <threshold_usd>"
+            ).await?;
+            return Ok(());
+        }
+        AlertKind::VolumeSurge => {
+            bot.send_message(
+                msg.chat.id,
+                "❌ Volume alerts are set with /setvolumealert <symbol> <multiplier> [chain]"
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    let request = price_alert_service::CreateAlertRequest {
+        user_id: user_id.clone(),
+        token_symbol: symbol.clone(),
+        chain: chain.clone(),
+        token_address: None,
+        alert_type,
+        auto_execute: false,
+        wallet_id: None,
+    };
+
+    match state.price_alert_service.create_alert(request).await {
+        Ok(_) => {
+            let msg_text = format!(
+                "✅ *Alert Created*\n\n\
+                Symbol: {}\n\
+                Chain: {}\n\
+                Type: {}\n\n\
+                You'll be notified when triggered\\.",
+                escape_markdown(&symbol),
+                escape_markdown(&chain),
+                escape_markdown(&alert_type_str)
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Shared handler for /setstoploss and /settakeprofit
+async fn handle_set_exit_alert(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    kind: AlertKind,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Parse: <symbol> <price> [chain] [wallet_id]
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() < 2 {
+        let usage = if kind == AlertKind::StopLoss {
+            "❌ Usage: /setstoploss <symbol> <price> [chain] [wallet_id]\n\n\
+            Examples:\n\
+            • /setstoploss ETH 2500\n\
+            • /setstoploss ETH 2500 ETH <wallet_id> (sells to USDC when hit)"
+        } else {
+            "❌ Usage: /settakeprofit <symbol> <price> [chain]\n\n\
+            Example: /settakeprofit ETH 4000"
+        };
+        bot.send_message(msg.chat.id, usage).await?;
+        return Ok(());
+    }
+
+    let symbol = parts[0].to_uppercase();
+    let price: f64 = match parts[1].parse() {
+        Ok(p) if p > 0.0 => p,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Invalid price format").await?;
+            return Ok(());
+        }
+    };
+    let mut chain = if parts.len() > 2 { parts[2].to_uppercase() } else { Chain::Eth.to_string() };
+
+    // A wallet on a stop-loss enables auto-selling when it triggers
+    let wallet_id = match parts.get(3) {
+        Some(id_str) if kind == AlertKind::StopLoss => {
+            let wallet_id = match Uuid::parse_str(id_str) {
+                Ok(id) => id,
+                Err(_) => {
+                    bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
+                    return Ok(());
+                }
+            };
+
+            match state.wallet_service.get_wallet(wallet_id).await {
+                Ok(wallet) if wallet.user_id == user_id => {
+                    chain = wallet.chain;
+                }
+                _ => {
+                    bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+                    return Ok(());
+                }
+            }
+
+            Some(wallet_id)
+        }
+        _ => None,
+    };
+
+    let alert_type = match kind {
+        AlertKind::StopLoss => AlertType::StopLoss { stop_price: price, token_address: None },
+        _ => AlertType::TakeProfit { target_price: price },
+    };
+
+    let request = price_alert_service::CreateAlertRequest {
+        user_id: user_id.clone(),
+        token_symbol: symbol.clone(),
+        chain: chain.clone(),
+        token_address: None,
+        alert_type,
+        auto_execute: wallet_id.is_some(),
+        wallet_id,
+    };
+
+    match state.price_alert_service.create_alert(request).await {
+        Ok(_) => {
+            let (title, action) = if kind == AlertKind::StopLoss {
+                let action = if wallet_id.is_some() {
+                    "Your position will be sold to USDC when the price falls to this level\\."
+                } else {
+                    "You'll be notified when the price falls to this level\\."
+                };
+                ("Stop\\-Loss Set", action)
+            } else {
+                ("Take\\-Profit Set", "You'll be notified when the price rises to this level\\.")
+            };
+
+            let msg_text = format!(
+                "✅ *{}*\n\n\
+                Symbol: {}\n\
+                Chain: {}\n\
+                Price: ${}\n\n\
+                {}",
+                title,
+                escape_markdown(&symbol),
+                escape_markdown(&chain),
+                escape_markdown(&price.to_string()),
+                action
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_set_trail_stop(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Parse: <symbol> <trail_pct> [chain]
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() < 2 {
+        bot.send_message(msg.chat.id, msg::ERR_SET_TRAIL_STOP_USAGE).await?;
+        return Ok(());
+    }
+
+    let symbol = parts[0].to_uppercase();
+    let trail_pct: f64 = match parts[1].trim_end_matches('%').parse() {
+        Ok(p) if p > 0.0 && p < 100.0 => p,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Trail percentage must be between 0 and 100").await?;
+            return Ok(());
+        }
+    };
+    let chain = if parts.len() > 2 { parts[2].to_uppercase() } else { Chain::Eth.to_string() };
+
+    // The trail starts from the current price
+    let peak_price = match state.price_service.get_price(&symbol).await {
+        Ok(price) => price.usd_price,
+        Err(e) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("❌ Could not get current price: {}", e.user_facing_message())
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    let request = price_alert_service::CreateAlertRequest {
+        user_id: user_id.clone(),
+        token_symbol: symbol.clone(),
+        chain: chain.clone(),
+        token_address: None,
+        alert_type: AlertType::TrailingStop { trail_pct, peak_price },
+        auto_execute: false,
+        wallet_id: None,
+    };
+
+    match state.price_alert_service.create_alert(request).await {
+        Ok(_) => {
+            let stop_price = peak_price * (1.0 - trail_pct / 100.0);
+            let msg_text = format!(
+                "✅ *Trailing Stop Set*\n\n\
+                Symbol: {}\n\
+                Chain: {}\n\
+                Trail: {}%\n\
+                Current stop: ${}\n\n\
+                The stop rises with the price\\. You'll be notified when {} falls {}% from its peak\\.",
+                escape_markdown(&symbol),
+                escape_markdown(&chain),
+                escape_markdown(&trail_pct.to_string()),
+                escape_markdown(&format_currency(stop_price)),
+                escape_markdown(&symbol),
+                escape_markdown(&trail_pct.to_string())
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_set_volume_alert(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Parse: <symbol> <multiplier> [chain]
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() < 2 {
+        bot.send_message(msg.chat.id, msg::ERR_SET_VOLUME_ALERT_USAGE).await?;
+        return Ok(());
+    }
+
+    let symbol = parts[0].to_uppercase();
+    let multiplier: f64 = match parts[1].trim_end_matches(['x', 'X']).parse() {
+        Ok(m) if m > 1.0 => m,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Multiplier must be a number greater than 1").await?;
+            return Ok(());
+        }
+    };
+    let chain = if parts.len() > 2 { parts[2].to_uppercase() } else { Chain::Eth.to_string() };
+
+    // The baseline is today's 24h volume
+    let baseline_volume_usd = match state.price_service.get_price(&symbol).await {
+        Ok(price) => price.volume_24h.unwrap_or_default(),
+        Err(e) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("❌ Could not get current volume: {}", e.user_facing_message())
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    let request = price_alert_service::CreateAlertRequest {
+        user_id: user_id.clone(),
+        token_symbol: symbol.clone(),
+        chain: chain.clone(),
+        token_address: None,
+        alert_type: AlertType::VolumeSurge { baseline_volume_usd, multiplier },
+        auto_execute: false,
+        wallet_id: None,
+    };
+
+    match state.price_alert_service.create_alert(request).await {
+        Ok(_) => {
+            let msg_text = format!(
+                "✅ *Volume Alert Set*\n\n\
+                Symbol: {}\n\
+                Chain: {}\n\
+                Baseline 24h volume: ${}\n\
+                Trigger: {}x baseline\n\n\
+                You'll be notified when {} volume passes ${}\\.",
+                escape_markdown(&symbol),
+                escape_markdown(&chain),
+                escape_markdown(&format_currency(baseline_volume_usd)),
+                escape_markdown(&multiplier.to_string()),
+                escape_markdown(&symbol),
+                escape_markdown(&format_currency(baseline_volume_usd * multiplier))
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_check_alerts(bot: Bot, msg: Message, state: Arc<BotState>) -> ResponseResult<()> {
+    match state.price_alert_service.check_all_now().await {
+        Ok(triggered) if triggered.is_empty() => {
+            bot.send_message(msg.chat.id, "✅ Alert check complete. No alerts triggered.").await?;
+        }
+        Ok(triggered) => {
+            let mut text = format!(
+                "✅ Alert check complete. {} alert(s) triggered:\n",
+                triggered.len()
+            );
+            for alert in &triggered {
+                text.push_str(
+                    &format!(
+                        "\n• {} on {} ({}) at ${}",
+                        alert.token_symbol,
+                        alert.chain,
+                        alert.alert_type,
+                        format_currency(alert.current_price)
                     )
                 );
             }
-
-            response.push_str("Use /cancelschedule <id> to cancel\\.");
-
-            bot
-                .send_message(msg.chat.id, response)
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+            bot.send_message(msg.chat.id, text).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_cancel_schedule(
+async fn handle_broadcast(bot: Bot, msg: Message, args: String, state: Arc<BotState>) -> ResponseResult<()> {
+    let message = args.trim();
+    if message.is_empty() {
+        bot.send_message(msg.chat.id, msg::ERR_BROADCAST_USAGE).await?;
+        return Ok(());
+    }
+    let admin_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or_default();
+
+    let status_id = bot.send_message(msg.chat.id, "📣 Starting broadcast...").await?.id;
+
+    // Deliver in the background so the admin's other updates aren't held up while the queue drains
+    let chat_id = msg.chat.id;
+    let message = message.to_string();
+    tokio::spawn(async move {
+        // Refresh the status message as messages go out, at most every few seconds
+        let mut progress = state.broadcast_service.subscribe_progress();
+        let progress_bot = bot.clone();
+        let updater = tokio::spawn(async move {
+            while progress.changed().await.is_ok() {
+                let p = *progress.borrow_and_update();
+                let text = format!("📣 Broadcasting... {}/{} sent, {} failed", p.sent, p.total, p.failed);
+                let _ = progress_bot.edit_message_text(chat_id, status_id, text).await;
+                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+            }
+        });
+
+        let result = state.broadcast_service.send_to_all(&bot, &message, admin_id).await;
+        updater.abort();
+
+        let text = match result {
+            Ok(result) =>
+                format!(
+                    "✅ Broadcast complete\n\nSent: {}\nFailed: {}\nTook: {:.1}s",
+                    result.sent,
+                    result.failed,
+                    result.duration_secs
+                ),
+            Err(e) => format!("❌ Broadcast failed: {}", e.user_facing_message()),
+        };
+        if let Err(e) = bot.edit_message_text(chat_id, status_id, text).await {
+            tracing::warn!("Failed to report broadcast result: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+/// Grant the Admin role; the dispatcher has already checked the sender owns the group
+async fn handle_set_group_admin(bot: Bot, msg: Message, args: String, state: Arc<BotState>) -> ResponseResult<()> {
+    if msg.chat.is_private() {
+        bot.send_message(msg.chat.id, "❌ /setgroupadmin only works in group chats.").await?;
+        return Ok(());
+    }
+
+    let username = args.trim();
+    if !username.starts_with('@') || username.len() < 2 || username.contains(char::is_whitespace) {
+        bot.send_message(msg.chat.id, msg::ERR_SET_GROUP_ADMIN_USAGE).await?;
+        return Ok(());
+    }
+
+    let text = match
+        state.group_permission_service.set_role_by_username(msg.chat.id.0, username, GroupRole::Admin).await
+    {
+        Ok(member) =>
+            format!(
+                "✅ @{} is now a group admin and can send from the group wallets.",
+                member.username.unwrap_or_default()
+            ),
+        Err(e) => format!("❌ {}", e.user_facing_message()),
+    };
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+async fn handle_set_mempool(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    let schedule_id = match Uuid::parse_str(args.trim()) {
+    // Parse: <wallet_id> <threshold_usd|off>
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() != 2 {
+        bot.send_message(msg.chat.id, msg::ERR_SET_MEMPOOL_USAGE).await?;
+        return Ok(());
+    }
+
+    let wallet_id = match Uuid::parse_str(parts[0]) {
         Ok(id) => id,
         Err(_) => {
-            bot.send_message(msg.chat.id, "❌ Invalid schedule ID format").await?;
+            bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
             return Ok(());
         }
     };
 
-    match state.scheduling_service.cancel_schedule(schedule_id, &user_id).await {
+    let wallet = match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(wallet) if wallet.user_id == user_id => wallet,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+            return Ok(());
+        }
+    };
+
+    if parts[1].eq_ignore_ascii_case("off") {
+        let text = match state.price_alert_service.disable_mempool_alert(&user_id, wallet_id).await {
+            Ok(true) => "✅ Mempool alert turned off for this wallet".to_string(),
+            Ok(false) => "ℹ️ This wallet has no mempool alert".to_string(),
+            Err(e) => format!("❌ Error: {}", e.user_facing_message()),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let threshold_usd: f64 = match parts[1].trim_start_matches('$').parse() {
+        Ok(t) if t > 0.0 => t,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Threshold must be a positive USD amount").await?;
+            return Ok(());
+        }
+    };
+
+    // Pending transactions are only watched on EVM chains
+    if !wallet.chain.parse::<Chain>().map(|c| c.is_evm()).unwrap_or(false) {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Mempool alerts are only available for EVM wallets"
+        ).await?;
+        return Ok(());
+    }
+
+    match state.price_alert_service.set_mempool_alert(&user_id, &wallet, threshold_usd).await {
         Ok(_) => {
-            bot
-                .send_message(msg.chat.id, "✅ *Scheduled transaction cancelled*")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "✅ Mempool Alert Set\n\n\
+                    Wallet: {}\n\
+                    Chain: {}\n\
+                    Threshold: ${}\n\n\
+                    You'll be notified as soon as a larger incoming transfer is pending.\n\
+                    Use /setmempool {} off to stop.",
+                    wallet.address,
+                    wallet.chain,
+                    format_currency(threshold_usd),
+                    wallet_id
+                )
+            ).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-// ==================== PHASE 7: PRICE ALERT HANDLERS ====================
-
-async fn handle_set_alert(
+async fn handle_set_wallet_rpc(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    // Parse: /setalert <symbol> <above|below|percent> <value> [chain]
+    // Parse: <wallet_id> <url|off>
     let parts: Vec<&str> = args.split_whitespace().collect();
 
-    if parts.len() < 3 {
-        bot.send_message(
-            msg.chat.id,
-            "❌ Usage: /setalert <symbol> <above|below|percent> <value> [chain]\n\n\
-            Examples:\n\
-            • /setalert ETH above 3000\n\
-            • /setalert BTC below 40000\n\
-            • /setalert SOL percent 10 SOLANA"
-        ).await?;
+    if parts.len() != 2 {
+        bot.send_message(msg.chat.id, msg::ERR_SET_WRPC_USAGE).await?;
         return Ok(());
     }
 
-    let symbol = parts[0].to_uppercase();
-    let alert_type_str = parts[1].to_lowercase();
-    let value_str = parts[2];
-    let chain = if parts.len() > 3 { parts[3].to_uppercase() } else { Chain::Eth.to_string() };
-
-    let value: f64 = match value_str.parse() {
-        Ok(v) => v,
+    let wallet_id = match Uuid::parse_str(parts[0]) {
+        Ok(id) => id,
         Err(_) => {
-            bot.send_message(msg.chat.id, "❌ Invalid value format").await?;
+            bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
             return Ok(());
         }
     };
 
-    // Parse alert kind
-    let alert_kind = match alert_type_str.parse::<AlertKind>() {
-        Ok(k) => k,
-        Err(_) => {
-            bot.send_message(
-                msg.chat.id,
-                "❌ Alert type must be 'above', 'below', or 'percent'"
-            ).await?;
+    let wallet = match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(wallet) if wallet.user_id == user_id => wallet,
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
             return Ok(());
         }
     };
 
-    // Build typed AlertType
-    let alert_type = match alert_kind {
-        AlertKind::Above => AlertType::Above { target_price: value },
-        AlertKind::Below => AlertType::Below { target_price: value },
-        AlertKind::PercentChange => {
-            match state.price_service.get_price(&symbol).await {
-                Ok(current_price) => {
-                    AlertType::PercentChange {
-                        percent: value,
-                        base_price: current_price.usd_price,
-                    }
-                }
-                Err(e) => {
-                    bot.send_message(
-                        msg.chat.id,
-                        format!("❌ Could not get current price: {}", e)
-                    ).await?;
-                    return Ok(());
-                }
-            }
-        }
-    };
+    if parts[1].eq_ignore_ascii_case("off") {
+        let text = match state.wallet_service.clear_rpc_override(wallet_id).await {
+            Ok(()) => "✅ This wallet now uses the default RPCs again".to_string(),
+            Err(e) => format!("❌ Error: {}", e.user_facing_message()),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
 
-    let request = price_alert_service::CreateAlertRequest {
-        user_id: user_id.clone(),
-        token_symbol: symbol.clone(),
-        chain: chain.clone(),
-        token_address: None,
-        alert_type,
-    };
+    if !wallet.chain.parse::<Chain>().map(|c| c.is_evm()).unwrap_or(false) {
+        bot.send_message(msg.chat.id, "❌ Custom RPC URLs are only supported for EVM wallets").await?;
+        return Ok(());
+    }
 
-    match state.price_alert_service.create_alert(request).await {
-        Ok(_) => {
-            let msg_text = format!(
-                "✅ *Alert Created*\n\n\
-                Symbol: {}\n\
-                Chain: {}\n\
-                Type: {}\n\n\
-                You'll be notified when triggered\\.",
-                escape_markdown(&symbol),
-                escape_markdown(&chain),
-                escape_markdown(&alert_type_str)
-            );
-            bot
-                .send_message(msg.chat.id, msg_text)
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
-        }
+    // RPC URLs often embed API keys; keep them out of the chat history
+    let _ = bot.delete_message(msg.chat.id, msg.id).await;
+
+    let dialogue_user_id = crate::bot::dialogue_key(
+        msg.chat.id,
+        msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0)
+    );
+    let sealed_url = match state.wallet_service.seal_rpc_override(wallet_id, parts[1]).await {
+        Ok(sealed) => sealed,
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+            return Ok(());
         }
+    };
+    let pending = crate::bot::DialogueState::PendingRpcOverride {
+        wallet_id: wallet_id.to_string(),
+        sealed_url,
+    };
+    if let Err(e) = state.dialogue_storage.set(dialogue_user_id, pending).await {
+        bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        return Ok(());
     }
 
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "⚠️ Privacy Warning\n\n\
+            Wallet: {}\n\
+            Chain: {}\n\n\
+            Whoever runs this RPC will see this wallet's address, every balance lookup \
+            and every transaction before it is broadcast, and could withhold or delay them. \
+            Only use a node you run or trust.\n\n\
+            Route this wallet through the custom RPC?",
+            wallet.address,
+            wallet.chain
+        )
+    )
+        .reply_markup(keyboards::confirm_action("wrpc", &wallet_id.to_string()))
+        .await?;
+
     Ok(())
 }
 
@@ -1421,7 +3783,12 @@ async fn handle_list_alerts(
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    match state.price_alert_service.list_user_alerts(&user_id, true).await {
+    // Paused alerts are listed too, but not the ones that already triggered
+    let alerts = state.price_alert_service
+        .list_user_alerts(&user_id, false).await
+        .map(|alerts| alerts.into_iter().filter(|a| a.active).collect::<Vec<_>>());
+
+    match alerts {
         Ok(alerts) => {
             if alerts.is_empty() {
                 bot
@@ -1459,14 +3826,49 @@ async fn handle_list_alerts(
                                 .unwrap_or("0".to_string());
                             format!("{}% change from ${}", percent, base)
                         }
+                        Some(AlertKind::StopLoss) => {
+                            let auto = if alert.auto_execute { " (auto-sell)" } else { "" };
+                            match alert.stop_loss_price {
+                                Some(price) => format!("Stop-loss at ${:.2}{}", price, auto),
+                                None => "Stop-loss (price not set)".to_string(),
+                            }
+                        }
+                        Some(AlertKind::TakeProfit) => {
+                            match alert.take_profit_price {
+                                Some(price) => format!("Take-profit at ${:.2}", price),
+                                None => "Take-profit (price not set)".to_string(),
+                            }
+                        }
+                        Some(AlertKind::TrailingStop) => {
+                            match (alert.trail_pct, alert.peak_price) {
+                                (Some(pct), Some(peak)) => {
+                                    format!("Trailing stop {}% below peak ${:.2}", pct.normalize(), peak)
+                                }
+                                _ => "Trailing stop (not set)".to_string(),
+                            }
+                        }
+                        Some(AlertKind::LargeIncoming) => "Large incoming transfer".to_string(),
+                        Some(AlertKind::VolumeSurge) => {
+                            match (alert.volume_multiplier, alert.baseline_volume) {
+                                (Some(multiplier), Some(baseline)) => {
+                                    format!(
+                                        "Volume {}x above ${:.0} baseline",
+                                        multiplier.normalize(),
+                                        baseline
+                                    )
+                                }
+                                _ => "Volume surge (not set)".to_string(),
+                            }
+                        }
                         None => "Unknown alert type".to_string(),
                     };
 
                     response.push_str(
                         &format!(
-                            "🔔 *{}* \\({}\\)\n\
+                            "{} *{}* \\({}\\)\n\
                         └ {}\n\
                         └ ID: `{}`\n\n",
+                            if alert.paused { "⏸" } else { "🔔" },
                             escape_markdown(&alert.token_symbol),
                             escape_markdown(&alert.chain),
                             escape_markdown(&alert_desc),
@@ -1475,28 +3877,181 @@ async fn handle_list_alerts(
                     );
                 }
 
-                response.push_str("Use /deletealert <id> to remove an alert\\.");
+                response.push_str(
+                    "Use /deletealert <id\\> to remove an alert, /pausealert <id\\> or /resumealert <id\\> to pause it\\."
+                );
+
+                bot
+                    .send_message(msg.chat.id, response)
+                    .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+            }
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_delete_alert(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let alert_id = match Uuid::parse_str(args.trim()) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Invalid alert ID format").await?;
+            return Ok(());
+        }
+    };
+
+    match state.price_alert_service.delete_alert(alert_id, &user_id).await {
+        Ok(_) => {
+            bot
+                .send_message(msg.chat.id, "✅ *Alert deleted successfully*")
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pause or resume one of the user's price alerts
+async fn handle_pause_alert(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>,
+    pause: bool
+) -> ResponseResult<()> {
+    let Ok(alert_id) = Uuid::parse_str(args.trim()) else {
+        let usage = if pause { msg::ERR_PAUSE_ALERT_USAGE } else { msg::ERR_RESUME_ALERT_USAGE };
+        bot.send_message(msg.chat.id, usage).await?;
+        return Ok(());
+    };
+
+    let result = if pause {
+        state.price_alert_service.pause_alert(alert_id, &user_id).await
+    } else {
+        state.price_alert_service.resume_alert(alert_id, &user_id).await
+    };
+
+    let text = match result {
+        Ok(()) if pause => "⏸ Alert paused. Resume it with /resumealert <id>".to_string(),
+        Ok(()) => "▶ Alert resumed.".to_string(),
+        Err(e) => format!("❌ Error: {}", e.user_facing_message()),
+    };
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+async fn handle_gas_alert(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let [chain, max_gwei] = parts.as_slice() else {
+        bot.send_message(msg.chat.id, msg::ERR_GAS_ALERT_USAGE).await?;
+        return Ok(());
+    };
+
+    let Ok(max_gwei) = max_gwei.parse::<f64>() else {
+        bot.send_message(msg.chat.id, "❌ Invalid Gwei threshold").await?;
+        return Ok(());
+    };
+
+    match state.price_alert_service.create_gas_alert(&user_id, chain, max_gwei).await {
+        Ok(alert) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "✅ Gas Alert Set\n\n\
+                    ⛽ Chain: {}\n\
+                    Threshold: {} Gwei\n\
+                    ID: {}\n\n\
+                    You'll be notified whenever gas drops to or below this level.",
+                    alert.chain,
+                    alert.max_gwei_threshold,
+                    alert.id
+                )
+            ).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
 
-                bot
-                    .send_message(msg.chat.id, response)
-                    .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+    Ok(())
+}
+
+async fn handle_list_gas_alerts(
+    bot: Bot,
+    msg: Message,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    match state.price_alert_service.list_user_gas_alerts(&user_id).await {
+        Ok(alerts) if alerts.is_empty() => {
+            bot.send_message(
+                msg.chat.id,
+                "📭 You have no gas alerts.\n\nUse /gasalert <chain> <max_gwei> to create one."
+            ).await?;
+        }
+        Ok(alerts) => {
+            let mut response = String::from("*⛽ Your Gas Alerts*\n\n");
+
+            for alert in alerts {
+                let status = if alert.triggered { " \\(triggered\\)" } else { "" };
+                response.push_str(
+                    &format!(
+                        "⛽ *{}* ≤ {} Gwei{}\n\
+                    └ ID: `{}`\n\n",
+                        escape_markdown(&alert.chain),
+                        escape_markdown(&alert.max_gwei_threshold.to_string()),
+                        status,
+                        escape_markdown(&alert.id.to_string())
+                    )
+                );
             }
+
+            response.push_str("Use /deletegasalert <id> to remove an alert\\.");
+
+            bot
+                .send_message(msg.chat.id, response)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_delete_alert(
+async fn handle_delete_gas_alert(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
+    if args.trim().is_empty() {
+        bot.send_message(msg.chat.id, msg::ERR_DELETE_GAS_ALERT_USAGE).await?;
+        return Ok(());
+    }
+
     let alert_id = match Uuid::parse_str(args.trim()) {
         Ok(id) => id,
         Err(_) => {
@@ -1505,14 +4060,65 @@ async fn handle_delete_alert(
         }
     };
 
-    match state.price_alert_service.delete_alert(alert_id, &user_id).await {
-        Ok(_) => {
-            bot
-                .send_message(msg.chat.id, "✅ *Alert deleted successfully*")
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+    let text = match state.price_alert_service.delete_gas_alert(alert_id, &user_id).await {
+        Ok(true) => "✅ Gas alert deleted".to_string(),
+        Ok(false) => "❌ Gas alert not found".to_string(),
+        Err(e) => format!("❌ Error: {}", e.user_facing_message()),
+    };
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+async fn handle_set_allocation(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let parsed = match parts.as_slice() {
+        [symbol, target] => target.trim_end_matches('%').parse::<f64>().ok()
+            .map(|t| (*symbol, t, rebalancing_service::DEFAULT_DEVIATION_THRESHOLD_PCT)),
+        [symbol, target, threshold] => {
+            match
+                (
+                    target.trim_end_matches('%').parse::<f64>(),
+                    threshold.trim_end_matches('%').parse::<f64>(),
+                )
+            {
+                (Ok(t), Ok(th)) => Some((*symbol, t, th)),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    let Some((symbol, target_pct, threshold)) = parsed else {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Usage: /setallocation <symbol> <target_pct> [threshold_pct]\n\n\
+            Example: /setallocation ETH 25 5"
+        ).await?;
+        return Ok(());
+    };
+
+    match state.rebalancing_service.set_target(&user_id, symbol, target_pct, threshold).await {
+        Ok(target) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "⚖️ Target set: {} at {}% (±{}%)\n\n\
+                    You'll be notified when its share of your portfolio drifts outside this range.",
+                    target.symbol,
+                    target.target_pct,
+                    target.deviation_threshold_pct
+                )
+            ).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -1550,7 +4156,7 @@ async fn handle_set_pin(
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -1592,7 +4198,7 @@ async fn handle_change_pin(
                 Err(e) => {
                     bot.send_message(
                         msg.chat.id,
-                        format!("❌ Error setting new PIN: {}", e)
+                        format!("❌ Error setting new PIN: {}", e.user_facing_message())
                     ).await?;
                 }
             }
@@ -1600,96 +4206,438 @@ async fn handle_change_pin(
         Ok(false) => {
             bot.send_message(msg.chat.id, "❌ Incorrect PIN").await?;
         }
+        Err(crate::error::AppError::PinLocked { unlocks_at }) => {
+            bot.send_message(msg.chat.id, crate::bot::utils::format_pin_locked(&unlocks_at)).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_disable_pin(
+    bot: Bot,
+    msg: Message,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    match state.security_service.disable_pin(&user_id).await {
+        Ok(_) => {
+            bot
+                .send_message(
+                    msg.chat.id,
+                    "✅ *PIN Disabled*\n\n\
+                PIN protection has been removed\\."
+                )
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_set_limit(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Parse: /setlimit <daily|weekly> <amount_usd>
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    if parts.len() != 2 {
+        bot.send_message(
+            msg.chat.id,
+            "❌ Usage: /setlimit <daily|weekly> <amount_usd>\n\n\
+            Examples:\n\
+            • /setlimit daily 1000\n\
+            • /setlimit weekly 5000"
+        ).await?;
+        return Ok(());
+    }
+
+    let period = parts[0].to_lowercase();
+    let amount: f64 = match parts[1].parse() {
+        Ok(a) => a,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Invalid amount format").await?;
+            return Ok(());
+        }
+    };
+
+    let (daily_limit, weekly_limit) = match period.as_str() {
+        "daily" => (Some(amount), None),
+        "weekly" => (None, Some(amount)),
+        _ => {
+            bot.send_message(msg.chat.id, "❌ Period must be 'daily' or 'weekly'").await?;
+            return Ok(());
+        }
+    };
+
+    match state.security_service.set_limits(&user_id, daily_limit, weekly_limit).await {
+        Ok(_) => {
+            let msg_text = format!(
+                "✅ *Withdrawal Limit Set*\n\n\
+                {} limit: ${}\n\n\
+                Transactions exceeding this limit will be blocked\\.",
+                if daily_limit.is_some() {
+                    "Daily"
+                } else {
+                    "Weekly"
+                },
+                format_currency(amount)
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_set_max_send(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let arg = args.trim().to_lowercase();
+
+    if arg == "off" {
+        match state.security_service.clear_max_transfer(&user_id).await {
+            Ok(_) => {
+                bot.send_message(msg.chat.id, "✅ Per-transfer limit removed").await?;
+            }
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let amount: f64 = match arg.trim_start_matches('$').parse() {
+        Ok(a) if a > 0.0 => a,
+        _ => {
+            bot.send_message(
+                msg.chat.id,
+                "❌ Usage: /setmaxsend <amount_usd|off>\n\n\
+                Examples:\n\
+                • /setmaxsend 500\n\
+                • /setmaxsend off"
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    match state.security_service.set_max_transfer(&user_id, amount).await {
+        Ok(_) => {
+            let msg_text = format!(
+                "✅ *Per\\-Transfer Limit Set*\n\n\
+                Max per send: ${}\n\n\
+                Sends worth more than this will be blocked\\.",
+                escape_markdown(&format_currency(amount))
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_get_limits(
+    bot: Bot,
+    msg: Message,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    match state.security_service.get_or_create_settings(&user_id).await {
+        Ok(settings) => {
+            let msg_text = format!(
+                "📏 *Your Limits*\n\n\
+                *Daily Limit:* {}\n\
+                *Weekly Limit:* {}\n\
+                *Max Per Send:* {}\n\n\
+                Change them with /setlimit and /setmaxsend",
+                escape_markdown(&format_usd_limit(settings.daily_withdrawal_limit)),
+                escape_markdown(&format_usd_limit(settings.weekly_withdrawal_limit)),
+                escape_markdown(&format_usd_limit(settings.max_single_transfer_usd))
+            );
+            bot
+                .send_message(msg.chat.id, msg_text)
+                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// "$1,000.00", or "None" when the limit isn't set
+fn format_usd_limit(limit: Option<sea_orm::prelude::Decimal>) -> String {
+    limit
+        .map(|l| format!("${}", format_currency(l.to_string().parse::<f64>().unwrap_or(0.0))))
+        .unwrap_or_else(|| "None".to_string())
+}
+
+async fn handle_testnet(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let enable = match args.trim().to_lowercase().as_str() {
+        "on" => true,
+        "off" => false,
+        _ => {
+            let current = state.security_service.is_testnet_mode(&user_id).await.unwrap_or(false);
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "🧪 Testnet mode is currently {}.\n\nUsage: /testnet <on|off>",
+                    if current { "ON" } else { "OFF" }
+                )
+            ).await?;
+            return Ok(());
+        }
+    };
+
+    match state.security_service.set_testnet_mode(&user_id, enable).await {
+        Ok(_) => {
+            let text = if enable {
+                "⚠️ TESTNET MODE enabled\n\n\
+                New wallets will be created on test networks. Test tokens have no real value.\n\
+                Existing wallets stay on the network they were created on.\n\n\
+                Use /testnet off to switch back to mainnet."
+            } else {
+                "✅ Testnet mode disabled\n\n\
+                New wallets will be created on mainnet.\n\
+                Existing testnet wallets keep using test networks."
+            };
+            bot.send_message(msg.chat.id, text).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_set_currency(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let currency = args.trim();
+    if currency.is_empty() {
+        let current = state.user_preference_service
+            .get_fiat_currency(&user_id).await
+            .unwrap_or(DEFAULT_FIAT_CURRENCY);
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "💱 Prices are shown in {}.\n\nSupported: {}\n\n{}",
+                current,
+                SUPPORTED_FIAT_CURRENCIES.join(", "),
+                msg::ERR_SET_CURRENCY_USAGE
+            )
+        ).await?;
+        return Ok(());
+    }
+
+    match state.user_preference_service.set_fiat_currency(&user_id, currency).await {
+        Ok(currency) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("✅ Portfolio, prices and fee estimates will now be shown in {}.", currency)
+            ).await?;
+        }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_disable_pin(
+async fn handle_tax_report(
     bot: Bot,
     msg: Message,
+    args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    match state.security_service.disable_pin(&user_id).await {
-        Ok(_) => {
-            bot
-                .send_message(
-                    msg.chat.id,
-                    "✅ *PIN Disabled*\n\n\
-                PIN protection has been removed\\."
-                )
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+    let Ok(year) = args.trim().parse::<i32>() else {
+        bot.send_message(msg.chat.id, "❌ Usage: /taxreport <year>\n\nExample: /taxreport 2024").await?;
+        return Ok(());
+    };
+
+    match state.tax_report_service.generate_report(&user_id, year).await {
+        Ok(report) if report.disposals.is_empty() => {
+            bot.send_message(
+                msg.chat.id,
+                format!("📄 No disposals recorded for {}.", year)
+            ).await?;
+        }
+        Ok(report) => {
+            let gain = report.total_realized_gain_usd;
+            let caption = format!(
+                "📄 Tax report {}\n\n\
+                Disposals: {}\n\
+                Proceeds: ${}\n\
+                Cost basis: ${}\n\
+                {} Realized: {}${}\n\n\
+                Cost basis uses FIFO lots from confirmed transactions. Not tax advice.",
+                year,
+                report.disposals.len(),
+                format_currency(report.total_proceeds_usd),
+                format_currency(report.total_cost_basis_usd),
+                if gain >= 0.0 { "📈" } else { "📉" },
+                if gain < 0.0 { "-" } else { "" },
+                format_currency(gain.abs())
+            );
+
+            let file_name = format!("tax-report-{}.csv", year);
+            let input_file = teloxide::types::InputFile
+                ::memory(report.to_csv().into_bytes())
+                .file_name(file_name);
+            bot.send_document(msg.chat.id, input_file).caption(caption).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Failed to build tax report: {}", e.user_facing_message())).await?;
         }
     }
 
     Ok(())
 }
 
-async fn handle_set_limit(
+/// Minimum password length accepted for wallet backups
+const MIN_BACKUP_PASSWORD_LEN: usize = 8;
+
+/// Largest backup file accepted by /restore, in bytes
+const MAX_BACKUP_FILE_SIZE: u32 = 1024 * 1024;
+
+async fn handle_backup(
     bot: Bot,
     msg: Message,
     args: String,
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    // Parse: /setlimit <daily|weekly> <amount_usd>
-    let parts: Vec<&str> = args.split_whitespace().collect();
-
-    if parts.len() != 2 {
+    let password = args.trim();
+    if password.len() < MIN_BACKUP_PASSWORD_LEN {
         bot.send_message(
             msg.chat.id,
-            "❌ Usage: /setlimit <daily|weekly> <amount_usd>\n\n\
-            Examples:\n\
-            • /setlimit daily 1000\n\
-            • /setlimit weekly 5000"
+            format!(
+                "❌ Usage: /backup <password>\n\nThe password must be at least {} characters.",
+                MIN_BACKUP_PASSWORD_LEN
+            )
         ).await?;
         return Ok(());
     }
 
-    let period = parts[0].to_lowercase();
-    let amount: f64 = match parts[1].parse() {
-        Ok(a) => a,
-        Err(_) => {
-            bot.send_message(msg.chat.id, "❌ Invalid amount format").await?;
-            return Ok(());
+    // Don't leave the password sitting in the chat history
+    let _ = bot.delete_message(msg.chat.id, msg.id).await;
+
+    match state.wallet_service.export_encrypted_backup(&user_id, password).await {
+        Ok(bytes) => {
+            let file_name = format!("wallet-backup-{}.zip", chrono::Utc::now().format("%Y%m%d"));
+            let input_file = teloxide::types::InputFile::memory(bytes).file_name(file_name);
+            bot.send_document(msg.chat.id, input_file)
+                .caption(
+                    "🔐 Encrypted wallet backup\n\n\
+                    This file contains your private keys, encrypted with your password. \
+                    Keep it somewhere safe - without the password it cannot be restored.\n\n\
+                    To restore, send this file to the bot and reply to it with /restore <password>."
+                ).await?;
         }
-    };
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Backup failed: {}", e.user_facing_message())).await?;
+        }
+    }
 
-    let (daily_limit, weekly_limit) = match period.as_str() {
-        "daily" => (Some(amount), None),
-        "weekly" => (None, Some(amount)),
+    Ok(())
+}
+
+async fn handle_restore(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    let password = args.trim().to_string();
+    let document = msg
+        .reply_to_message()
+        .and_then(|m| m.document())
+        .cloned();
+
+    let document = match document {
+        Some(doc) if !password.is_empty() => doc,
         _ => {
-            bot.send_message(msg.chat.id, "❌ Period must be 'daily' or 'weekly'").await?;
+            bot.send_message(
+                msg.chat.id,
+                "❌ Usage: forward your backup file to this chat, then reply to it with /restore <password>"
+            ).await?;
             return Ok(());
         }
     };
 
-    match state.security_service.set_limits(&user_id, daily_limit, weekly_limit).await {
-        Ok(_) => {
-            let msg_text = format!(
-                "✅ *Withdrawal Limit Set*\n\n\
-                {} limit: ${}\n\n\
-                Transactions exceeding this limit will be blocked\\.",
-                if daily_limit.is_some() {
-                    "Daily"
-                } else {
-                    "Weekly"
-                },
-                format_currency(amount)
-            );
-            bot
-                .send_message(msg.chat.id, msg_text)
-                .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
+    let _ = bot.delete_message(msg.chat.id, msg.id).await;
+
+    if document.file.size > MAX_BACKUP_FILE_SIZE {
+        bot.send_message(msg.chat.id, "❌ That file is too large to be a wallet backup.").await?;
+        return Ok(());
+    }
+
+    let file = bot.get_file(document.file.id).await?;
+    let mut data = Vec::new();
+    if let Err(e) = teloxide::net::Download::download_file(&bot, &file.path, &mut data).await {
+        bot.send_message(msg.chat.id, format!("❌ Could not download backup: {}", e)).await?;
+        return Ok(());
+    }
+
+    match state.wallet_service.import_encrypted_backup(&user_id, &data, &password).await {
+        Ok(imported) if imported.is_empty() => {
+            bot.send_message(
+                msg.chat.id,
+                "ℹ️ Backup decrypted, but all of its wallets are already in the bot."
+            ).await?;
+        }
+        Ok(imported) => {
+            let addresses = imported
+                .iter()
+                .map(|w| format!("• {}", w.address))
+                .collect::<Vec<_>>()
+                .join("\n");
+            bot.send_message(
+                msg.chat.id,
+                format!("✅ Restored {} wallet(s):\n\n{}", imported.len(), addresses)
+            ).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Restore failed: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -1714,7 +4662,7 @@ async fn handle_lock_wallet(
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -1731,7 +4679,13 @@ async fn handle_unlock_wallet(
     let pin = args.trim();
 
     match state.security_service.unlock_wallet(&user_id, pin).await {
-        Ok(_) => {
+        Ok(false) => {
+            bot.send_message(msg.chat.id, "❌ Incorrect PIN").await?;
+        }
+        Err(crate::error::AppError::PinLocked { unlocks_at }) => {
+            bot.send_message(msg.chat.id, crate::bot::utils::format_pin_locked(&unlocks_at)).await?;
+        }
+        Ok(true) => {
             bot
                 .send_message(
                     msg.chat.id,
@@ -1741,10 +4695,45 @@ async fn handle_unlock_wallet(
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_audit(bot: Bot, msg: Message, user_id: String, state: Arc<BotState>) -> ResponseResult<()> {
+    let report = match state.security_service.audit(&user_id).await {
+        Ok(report) => report,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
+            return Ok(());
         }
+    };
+
+    let grade = match report.score {
+        90..=100 => "A",
+        75..=89 => "B",
+        60..=74 => "C",
+        40..=59 => "D",
+        _ => "F",
+    };
+
+    let mut text = format!("🛡 Security Audit\n\nGrade: {} ({}/100)\n", grade, report.score);
+    if report.findings.is_empty() {
+        text.push_str("\n✅ No issues found. Nice work!");
+    }
+    for finding in &report.findings {
+        let icon = match finding.severity {
+            security_service::FindingSeverity::High => "🔴",
+            security_service::FindingSeverity::Medium => "🟠",
+            security_service::FindingSeverity::Low => "🟡",
+        };
+        text.push_str(&format!("\n{} {}\n   → {}\n", icon, finding.message, finding.recommendation));
     }
 
+    bot.send_message(msg.chat.id, text).await?;
+
     Ok(())
 }
 
@@ -1758,17 +4747,9 @@ async fn handle_security_info(
         Ok(settings) => {
             let pin_status = if settings.pin_hash.is_some() { "✅ Enabled" } else { "❌ Disabled" };
 
-            let daily_limit = settings.daily_withdrawal_limit
-                .map(|l|
-                    format!("${}", format_currency(l.to_string().parse::<f64>().unwrap_or(0.0)))
-                )
-                .unwrap_or_else(|| "None".to_string());
-
-            let weekly_limit = settings.weekly_withdrawal_limit
-                .map(|l|
-                    format!("${}", format_currency(l.to_string().parse::<f64>().unwrap_or(0.0)))
-                )
-                .unwrap_or_else(|| "None".to_string());
+            let daily_limit = format_usd_limit(settings.daily_withdrawal_limit);
+            let weekly_limit = format_usd_limit(settings.weekly_withdrawal_limit);
+            let max_per_send = format_usd_limit(settings.max_single_transfer_usd);
 
             let wallet_status = if settings.wallet_locked {
                 "🔒 *Locked*"
@@ -1781,17 +4762,20 @@ async fn handle_security_info(
                 *PIN Protection:* {}\n\
                 *Daily Limit:* {}\n\
                 *Weekly Limit:* {}\n\
+                *Max Per Send:* {}\n\
                 *Wallet Status:* {}\n\n\
                 Commands:\n\
                 • /setpin \\- Set PIN protection\n\
                 • /changepin \\- Change PIN\n\
                 • /disablepin \\- Disable PIN\n\
                 • /setlimit \\- Set withdrawal limits\n\
+                • /setmaxsend \\- Cap a single send\n\
                 • /lockwallet \\- Lock wallet\n\
                 • /unlockwallet \\- Unlock wallet",
                 escape_markdown(pin_status),
                 escape_markdown(&daily_limit),
                 escape_markdown(&weekly_limit),
+                escape_markdown(&max_per_send),
                 wallet_status
             );
 
@@ -1800,7 +4784,7 @@ async fn handle_security_info(
                 .parse_mode(teloxide::types::ParseMode::MarkdownV2).await?;
         }
         Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ Error: {}", e)).await?;
+            bot.send_message(msg.chat.id, format!("❌ Error: {}", e.user_facing_message())).await?;
         }
     }
 
@@ -1816,10 +4800,201 @@ async fn handle_swap(
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    bot.send_message(
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (wallet_id, from_token, to_token, amount, slippage) = match parts.as_slice() {
+        [wallet_id, from, to, amount] => (wallet_id, from, to, amount, None),
+        [wallet_id, from, to, amount, slippage] => {
+            match swap_service::parse_slippage_pct(slippage) {
+                Some(slippage) => (wallet_id, from, to, amount, Some(slippage)),
+                None => {
+                    bot.send_message(
+                        msg.chat.id,
+                        format!("❌ Slippage must be between 0 and {}%", swap_service::MAX_SLIPPAGE_PCT)
+                    ).await?;
+                    return Ok(());
+                }
+            }
+        }
+        _ => {
+            bot.send_message(msg.chat.id, msg::ERR_SWAP_USAGE).await?;
+            return Ok(());
+        }
+    };
+
+    if !amount.parse::<f64>().is_ok_and(|a| a > 0.0) {
+        bot.send_message(msg.chat.id, "❌ Invalid amount").await?;
+        return Ok(());
+    }
+
+    let wallet = match Uuid::parse_str(wallet_id) {
+        Ok(id) => state.wallet_service.get_wallet(id).await.ok().filter(|w| w.user_id == user_id),
+        Err(_) => None,
+    };
+    let Some(wallet) = wallet else {
+        bot.send_message(msg.chat.id, "❌ Wallet not found").await?;
+        return Ok(());
+    };
+
+    let pending = crate::bot::PendingSwapTokens {
+        wallet_id: wallet.id.to_string(),
+        chain: wallet.chain,
+        from_token: from_token.to_string(),
+        to_token: to_token.to_string(),
+        amount: amount.to_string(),
+        slippage,
+        candidates: Vec::new(),
+        picking_from: true,
+    };
+    let dialogue_user_id = crate::bot::dialogue_key(
         msg.chat.id,
-        "🔄 Swap feature coming soon!\n\nThis will allow you to swap tokens using Uniswap, PancakeSwap, and Jupiter."
-    ).await?;
+        msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(msg.chat.id.0)
+    );
+
+    if let Err(e) = super::callbacks::resolve_swap_tokens(&bot, msg.chat.id, pending, dialogue_user_id, &state).await {
+        tracing::error!("Swap token resolution failed: {:?}", e);
+        bot.send_message(msg.chat.id, "❌ Could not prepare the swap. Please try again.").await?;
+    }
+
+    Ok(())
+}
+
+/// Slippage for batch legs that don't name their own
+const DEFAULT_BATCH_SWAP_SLIPPAGE: f64 = 0.5;
+
+async fn handle_batch_swap(
+    bot: Bot,
+    msg: Message,
+    args: String,
+    user_id: String,
+    state: Arc<BotState>
+) -> ResponseResult<()> {
+    // Wallet ID on the first line, then one `FROM TO AMOUNT [SLIPPAGE]` swap per line
+    let lines: Vec<&str> = args.trim().lines().collect();
+
+    if lines.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "Usage:\n\
+            /batchswap <wallet_id>\n\
+            FROM TO AMOUNT [SLIPPAGE]\n\
+            FROM TO AMOUNT [SLIPPAGE]\n\n\
+            Example:\n\
+            /batchswap abc123-def456-...\n\
+            WBNB USDT 0.5\n\
+            USDT CAKE 100 1.0"
+        ).await?;
+        return Ok(());
+    }
+
+    let wallet_id = match Uuid::parse_str(lines[0].trim()) {
+        Ok(id) => id,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "❌ Invalid wallet ID format").await?;
+            return Ok(());
+        }
+    };
+
+    match state.wallet_service.get_wallet(wallet_id).await {
+        Ok(wallet) if wallet.user_id == user_id => {}
+        Ok(_) => {
+            bot.send_message(msg.chat.id, "❌ Wallet does not belong to you").await?;
+            return Ok(());
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
+            return Ok(());
+        }
+    }
+
+    if lines.len() < 2 {
+        bot.send_message(
+            msg.chat.id,
+            "❌ No swaps provided. Add one per line:\n\
+            FROM TO AMOUNT [SLIPPAGE]"
+        ).await?;
+        return Ok(());
+    }
+
+    let mut swaps = Vec::new();
+    for (i, line) in lines[1..].iter().enumerate() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let parsed = match parts.as_slice() {
+            [from, to, amount] =>
+                amount
+                    .parse::<f64>()
+                    .ok()
+                    .map(|a| (from, to, a, DEFAULT_BATCH_SWAP_SLIPPAGE)),
+            [from, to, amount, slippage] =>
+                amount
+                    .parse::<f64>()
+                    .ok()
+                    .zip(slippage.parse::<f64>().ok())
+                    .map(|(a, s)| (from, to, a, s)),
+            _ => None,
+        };
+
+        let valid = parsed.filter(|(_, _, amount, slippage)| *amount > 0.0 && *slippage >= 0.0);
+        let Some((from, to, amount, slippage)) = valid else {
+            bot.send_message(
+                msg.chat.id,
+                format!("❌ Invalid swap on line {}: {}", i + 2, line)
+            ).await?;
+            return Ok(());
+        };
+
+        swaps.push(swap_service::BatchSwapRequest {
+            from_token: from.to_uppercase(),
+            to_token: to.to_uppercase(),
+            amount,
+            slippage,
+        });
+    }
+
+    bot.send_message(msg.chat.id, format!("⏳ Executing {} swaps...", swaps.len())).await?;
+
+    match state.swap_service.batch_swap(wallet_id, swaps).await {
+        Ok(result) => {
+            let mut response = format!(
+                "🔄 Batch Swap Complete\n\n\
+                ✅ Successful: {}/{}\n\
+                ❌ Failed: {}\n\n",
+                result.successful,
+                result.results.len(),
+                result.failed
+            );
+
+            for swap in &result.results {
+                if let Some(ref tx_hash) = swap.tx_hash {
+                    response.push_str(
+                        &format!(
+                            "✅ {} {} → {} {}\n   Tx: {}\n",
+                            swap.from_amount,
+                            swap.from_token,
+                            swap.to_amount,
+                            swap.to_token,
+                            tx_hash
+                        )
+                    );
+                } else {
+                    response.push_str(
+                        &format!(
+                            "❌ {} {} → {}: {}\n",
+                            swap.from_amount,
+                            swap.from_token,
+                            swap.to_token,
+                            swap.error_message.as_deref().unwrap_or("unknown error")
+                        )
+                    );
+                }
+            }
+
+            bot.send_message(msg.chat.id, response).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Batch swap failed: {}", e.user_facing_message())).await?;
+        }
+    }
+
     Ok(())
 }
 
@@ -1829,7 +5004,80 @@ async fn handle_swap_quote(
     args: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    bot.send_message(msg.chat.id, "💱 Swap quote feature coming soon!").await?;
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let (chain, from_token, to_token, amount, slippage) = match parts.as_slice() {
+        [chain, from, to, amount] => (chain, from, to, amount, Some(swap_service::DEFAULT_SLIPPAGE_PCT)),
+        [chain, from, to, amount, slippage] => (chain, from, to, amount, swap_service::parse_slippage_pct(slippage)),
+        _ => {
+            bot.send_message(msg.chat.id, msg::ERR_SWAP_QUOTE_USAGE).await?;
+            return Ok(());
+        }
+    };
+    let (Ok(chain), Some(amount), Some(slippage)) = (
+        chain.parse::<Chain>(),
+        amount.parse::<f64>().ok().filter(|a| *a > 0.0),
+        slippage,
+    ) else {
+        bot.send_message(msg.chat.id, msg::ERR_SWAP_QUOTE_USAGE).await?;
+        return Ok(());
+    };
+
+    // Quotes don't offer a picker; ambiguous symbols are listed so the user can retry with an address
+    let mut tokens = Vec::with_capacity(2);
+    for query in [from_token, to_token] {
+        match state.swap_service.resolve_token_for_swap(chain.as_str(), query).await {
+            Ok(swap_service::ResolvedToken::Ambiguous(candidates)) => {
+                let options = candidates
+                    .iter()
+                    .map(|t| format!("• {} - {}\n  {}", t.symbol, t.name, t.address))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                bot.send_message(
+                    msg.chat.id,
+                    format!("🔎 Several tokens match \"{}\":\n\n{}\n\nUse the contract address instead.", query, options)
+                ).await?;
+                return Ok(());
+            }
+            Ok(resolved) => tokens.push(resolved.swap_token().unwrap_or_default().to_string()),
+            Err(e) => {
+                bot.send_message(msg.chat.id, format!("❌ {}", e.user_facing_message())).await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let request = swap_service::SwapQuoteRequest {
+        chain: chain.as_str().to_string(),
+        from_token: tokens[0].clone(),
+        to_token: tokens[1].clone(),
+        amount,
+        slippage,
+        testnet: false,
+    };
+    let text = match state.swap_service.get_swap_quote(request).await {
+        Ok(quote) =>
+            format!(
+                "💱 Swap Quote ({})\n\n\
+                {} {} → {:.6} {}\n\
+                Minimum received: {:.6} {}\n\
+                Price impact: {:.2}%\n\
+                Slippage: {}%\n\
+                DEX: {}",
+                chain,
+                amount,
+                from_token.to_uppercase(),
+                quote.expected_to_amount,
+                to_token.to_uppercase(),
+                quote.minimum_to_amount,
+                to_token.to_uppercase(),
+                quote.price_impact,
+                slippage,
+                quote.dex
+            ),
+        Err(e) => format!("❌ Quote failed: {}", e.user_facing_message()),
+    };
+    bot.send_message(msg.chat.id, text).await?;
+
     Ok(())
 }
 
@@ -1840,6 +5088,16 @@ async fn handle_swap_history(
     user_id: String,
     state: Arc<BotState>
 ) -> ResponseResult<()> {
-    bot.send_message(msg.chat.id, "📊 Swap history feature coming soon!").await?;
+    let wallet_id = Some(args.trim()).filter(|a| !a.is_empty());
+
+    match super::callbacks::swap_history_page(&user_id, wallet_id, 0, &state).await {
+        Ok((text, keyboard)) => {
+            bot.send_message(msg.chat.id, text).reply_markup(keyboard).await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("❌ Failed to load swap history: {}", e.user_facing_message())).await?;
+        }
+    }
+
     Ok(())
 }
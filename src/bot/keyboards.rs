@@ -23,6 +23,20 @@ pub fn main_menu() -> InlineKeyboardMarkup {
     ])
 }
 
+/// Main menu topped with a button back to the wallet the user last worked with
+pub fn main_menu_with_resume(wallet_id: &str, chain: &str) -> InlineKeyboardMarkup {
+    let mut rows = vec![
+        vec![
+            InlineKeyboardButton::callback(
+                format!("↩️ Resume where you left off ({})", chain),
+                format!("wallet:select:{}", wallet_id)
+            ),
+        ],
+    ];
+    rows.extend(main_menu().inline_keyboard);
+    InlineKeyboardMarkup::new(rows)
+}
+
 // Chain selection keyboard for wallet creation — dynamic from Chain::all()
 pub fn chain_selection() -> InlineKeyboardMarkup {
     let chains = Chain::all();
@@ -49,8 +63,15 @@ pub fn chain_selection() -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(rows)
 }
 
-// Wallet actions keyboard
-pub fn wallet_actions(wallet_id: &str) -> InlineKeyboardMarkup {
+// Wallet actions keyboard; EVM wallets can also be added to another EVM chain
+pub fn wallet_actions(wallet_id: &str, chain: &str) -> InlineKeyboardMarkup {
+    let explorer = InlineKeyboardButton::callback("🔍 View on Explorer", format!("wallet:explorer:{}", wallet_id));
+    let explorer_row = if chain.parse::<Chain>().map(|c| c.is_evm()).unwrap_or(false) {
+        vec![explorer, InlineKeyboardButton::callback("➕ Add Chain", format!("wallet:addchain:{}", wallet_id))]
+    } else {
+        vec![explorer]
+    };
+
     InlineKeyboardMarkup::new(vec![
         vec![
             InlineKeyboardButton::callback("💰 Balance", format!("wallet:balance:{}", wallet_id)),
@@ -65,14 +86,88 @@ pub fn wallet_actions(wallet_id: &str) -> InlineKeyboardMarkup {
             InlineKeyboardButton::callback("🪙 Tokens", format!("wallet:tokens:{}", wallet_id)),
         ],
         vec![
-            InlineKeyboardButton::callback("🔍 View on Explorer", format!("wallet:explorer:{}", wallet_id)),
+            InlineKeyboardButton::callback("🔓 Approvals", format!("wallet:approvals:{}", wallet_id)),
+            InlineKeyboardButton::callback("🖼️ NFTs", format!("wallet:nfts:{}", wallet_id)),
         ],
+        explorer_row,
         vec![
             InlineKeyboardButton::callback("« Back to Wallets", "menu:wallets"),
         ],
     ])
 }
 
+// EVM chains an existing wallet's key can be added to
+pub fn add_chain_selection(wallet_id: &str, chains: &[Chain]) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+    let mut row: Vec<InlineKeyboardButton> = Vec::new();
+
+    for chain in chains {
+        let label = format!("{} {}", chain.emoji(), chain.display_name());
+        row.push(InlineKeyboardButton::callback(label, format!("wallet:addchain:{}:{}", wallet_id, chain.as_str())));
+        if row.len() == 2 {
+            rows.push(row);
+            row = Vec::new();
+        }
+    }
+    if !row.is_empty() {
+        rows.push(row);
+    }
+
+    rows.push(vec![
+        InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+    ]);
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+// Offered when a new or imported wallet is one the user already has
+pub fn existing_wallet(wallet_id: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("👀 View Wallet", format!("wallet:select:{}", wallet_id)),
+            InlineKeyboardButton::callback("« Back to Menu", "menu:main"),
+        ],
+    ])
+}
+
+/// Merge/keep choice for a wallet /cleanwallet found dust in
+pub fn dust_merge(wallet_id: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("🧹 Merge", format!("dust:merge:{}", wallet_id)),
+            InlineKeyboardButton::callback("✋ Keep", format!("dust:keep:{}", wallet_id)),
+        ],
+    ])
+}
+
+/// Start or stop auto-compounding for each protocol a wallet has positions in
+pub fn defi_auto_compound(
+    wallet_id: &str,
+    protocols: &[crate::enums::DefiProtocol],
+    plans: &[crate::db::entity::auto_compound_plan::Model]
+) -> InlineKeyboardMarkup {
+    let rows = protocols
+        .iter()
+        .map(|protocol| {
+            let button = match plans.iter().find(|p| p.protocol == protocol.as_str()) {
+                Some(plan) =>
+                    InlineKeyboardButton::callback(
+                        format!("⏹ Stop {} auto-compound", protocol.display_name()),
+                        format!("defi:stop:{}", plan.id)
+                    ),
+                None =>
+                    InlineKeyboardButton::callback(
+                        format!("🔁 Auto-compound {}", protocol.display_name()),
+                        format!("defi:auto:{}:{}", wallet_id, protocol.as_str())
+                    ),
+            };
+            vec![button]
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(rows)
+}
+
 // Back to main menu button
 pub fn back_to_menu() -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(vec![
@@ -95,6 +190,48 @@ pub fn wallets_menu() -> InlineKeyboardMarkup {
     ])
 }
 
+// One button per wallet, opening its actions
+pub fn wallet_matches(wallets: &[crate::db::entity::wallet::Model]) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = wallets
+        .iter()
+        .map(|w| {
+            let label = w.chain
+                .parse::<Chain>()
+                .map(|c| format!("{} {} {}", c.emoji(), c.display_name(), w.address))
+                .unwrap_or_else(|_| format!("{} {}", w.chain, w.address));
+            vec![InlineKeyboardButton::callback(label, format!("wallet:select:{}", w.id))]
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("« Back to Menu", "menu:main")]);
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Saved contacts matching a partial /send recipient; picking one continues the pending send
+pub fn address_book_matches(
+    contacts: &[crate::db::entity::address_book::Model],
+    user_id: i64,
+    wallet_id: &str
+) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = contacts
+        .iter()
+        .map(|c| {
+            let short_addr = if c.address.len() > 16 {
+                format!("{}...{}", &c.address[..8], &c.address[c.address.len() - 6..])
+            } else {
+                c.address.clone()
+            };
+            vec![
+                InlineKeyboardButton::callback(
+                    format!("📇 {} → {}", c.name, short_addr),
+                    format!("addr_book_pick:{}:{}", user_id, c.id)
+                )
+            ]
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("❌ Cancel", format!("send:cancel:{}", wallet_id))]);
+    InlineKeyboardMarkup::new(rows)
+}
+
 // Help menu with categories
 pub fn help_menu() -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(vec![
@@ -169,6 +306,51 @@ pub fn confirm_action(action: &str, data: &str) -> InlineKeyboardMarkup {
     ])
 }
 
+/// Proceed/cancel for a previewed /batchsend
+pub fn batch_send_confirm(wallet_id: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("✅ Proceed", format!("confirm:batch:{}", wallet_id)),
+            InlineKeyboardButton::callback("❌ Cancel", "batch:cancel"),
+        ],
+    ])
+}
+
+pub fn schedule_simulate() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("🔍 Simulate", "schedule:simulate"),
+            InlineKeyboardButton::callback("❌ Cancel", "schedule:cancel"),
+        ],
+    ])
+}
+
+pub fn schedule_confirm() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("✅ Proceed", "confirm:schedule"),
+            InlineKeyboardButton::callback("❌ Cancel", "schedule:cancel"),
+        ],
+    ])
+}
+
+// Portfolio view: chain breakdown, refresh, back
+pub fn portfolio_menu() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("📊 By Chain", "portfolio:bychain"),
+            InlineKeyboardButton::callback("🌐 All Chains", "portfolio:bytoken"),
+        ],
+        vec![
+            InlineKeyboardButton::callback("📤 Share Portfolio", "portfolio:share"),
+        ],
+        vec![
+            InlineKeyboardButton::callback("🔄 Refresh", "refresh:portfolio"),
+            InlineKeyboardButton::callback("« Back to Menu", "menu:main"),
+        ],
+    ])
+}
+
 // Refresh button for balance/portfolio
 pub fn refresh_button(action: &str) -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(vec![
@@ -179,6 +361,47 @@ pub fn refresh_button(action: &str) -> InlineKeyboardMarkup {
     ])
 }
 
+// Price list with 7d/30d chart buttons per symbol
+pub fn prices_keyboard(symbols: &[&str]) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = symbols
+        .iter()
+        .map(|symbol| {
+            vec![
+                InlineKeyboardButton::callback(
+                    format!("📈 {} 7d Chart", symbol),
+                    format!("prices:chart:{}:7d", symbol)
+                ),
+                InlineKeyboardButton::callback(
+                    format!("📉 {} 30d Chart", symbol),
+                    format!("prices:chart:{}:30d", symbol)
+                ),
+            ]
+        })
+        .collect();
+
+    rows.push(
+        vec![
+            InlineKeyboardButton::callback("🔄 Refresh", "refresh:prices"),
+            InlineKeyboardButton::callback("« Back to Menu", "menu:main")
+        ]
+    );
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+// Price chart - switch period or go back to the price list
+pub fn price_chart_keyboard(symbol: &str, days: u32) -> InlineKeyboardMarkup {
+    let other = if days == 7 {
+        InlineKeyboardButton::callback("📉 30d Chart", format!("prices:chart:{}:30d", symbol))
+    } else {
+        InlineKeyboardButton::callback("📈 7d Chart", format!("prices:chart:{}:7d", symbol))
+    };
+
+    InlineKeyboardMarkup::new(
+        vec![vec![other], vec![InlineKeyboardButton::callback("« Back to Prices", "refresh:prices")]]
+    )
+}
+
 // Send menu - choose what to send, using Chain::emoji() for label
 pub fn send_menu(wallet_id: &str, chain: &str) -> InlineKeyboardMarkup {
     let native_label = chain
@@ -211,6 +434,9 @@ pub fn send_amount_presets(wallet_id: &str, _balance: &str) -> InlineKeyboardMar
         vec![
             InlineKeyboardButton::callback("✏️ Enter Custom Amount", format!("send:custom:{}", wallet_id)),
         ],
+        vec![
+            InlineKeyboardButton::callback("💵 Enter USD Amount", format!("send:usd:{}", wallet_id)),
+        ],
         vec![
             InlineKeyboardButton::callback("« Back", format!("wallet:send:{}", wallet_id)),
         ],
@@ -366,3 +592,112 @@ pub fn token_list(wallet_id: &str, page: usize, total_pages: usize) -> InlineKey
 
     InlineKeyboardMarkup::new(rows)
 }
+
+/// NFT list keyboard with pagination for the wallet NFT view
+pub fn nft_list(wallet_id: &str, page: usize, total_pages: usize) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+    if total_pages > 1 {
+        let mut nav = Vec::new();
+        if page > 0 {
+            nav.push(InlineKeyboardButton::callback(
+                "◀️ Prev",
+                format!("wallet:nfts:{}:{}", wallet_id, page - 1),
+            ));
+        }
+        nav.push(InlineKeyboardButton::callback(
+            format!("Page {}/{}", page + 1, total_pages),
+            "noop",
+        ));
+        if page + 1 < total_pages {
+            nav.push(InlineKeyboardButton::callback(
+                "Next ▶️",
+                format!("wallet:nfts:{}:{}", wallet_id, page + 1),
+            ));
+        }
+        rows.push(nav);
+    }
+
+    rows.push(vec![
+        InlineKeyboardButton::callback("🔄 Refresh", format!("wallet:nfts:{}", wallet_id)),
+        InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+    ]);
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+// Token approvals list with a revoke button per approval and pagination
+pub fn approval_list(
+    wallet_id: &str,
+    revoke_buttons: &[(String, String)],
+    page: usize,
+    total_pages: usize
+) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = revoke_buttons
+        .iter()
+        .map(|(key, label)| {
+            vec![InlineKeyboardButton::callback(
+                format!("🚫 Revoke {}", label),
+                format!("approval:revoke:{}:{}", wallet_id, key),
+            )]
+        })
+        .collect();
+
+    if total_pages > 1 {
+        let mut nav = Vec::new();
+        if page > 0 {
+            nav.push(InlineKeyboardButton::callback(
+                "◀️ Prev",
+                format!("wallet:approvals:{}:{}", wallet_id, page - 1),
+            ));
+        }
+        nav.push(InlineKeyboardButton::callback(
+            format!("Page {}/{}", page + 1, total_pages),
+            "noop",
+        ));
+        if page + 1 < total_pages {
+            nav.push(InlineKeyboardButton::callback(
+                "Next ▶️",
+                format!("wallet:approvals:{}:{}", wallet_id, page + 1),
+            ));
+        }
+        rows.push(nav);
+    }
+
+    rows.push(vec![
+        InlineKeyboardButton::callback("🔄 Refresh", format!("wallet:approvals:{}", wallet_id)),
+        InlineKeyboardButton::callback("« Back to Wallet", format!("wallet:select:{}", wallet_id)),
+    ]);
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+// Sent/received tabs shown above a wallet's transaction history
+pub fn history_tabs(wallet_id: &str) -> Vec<InlineKeyboardButton> {
+    vec![
+        InlineKeyboardButton::callback("📤 Sent", format!("wallet:history:{}", wallet_id)),
+        InlineKeyboardButton::callback("📥 Received", format!("wallet:received:{}", wallet_id)),
+    ]
+}
+
+// Swap history pagination; the wallet filter is carried through page changes
+pub fn swap_history(page: usize, has_next: bool, wallet_id: Option<&str>) -> InlineKeyboardMarkup {
+    let page_data = |p: usize| match wallet_id {
+        Some(id) => format!("swaphist:{}:{}", p, id),
+        None => format!("swaphist:{}", p),
+    };
+
+    let mut nav = Vec::new();
+    if page > 0 {
+        nav.push(InlineKeyboardButton::callback("◀️ Prev", page_data(page - 1)));
+    }
+    nav.push(InlineKeyboardButton::callback(format!("Page {}", page + 1), "noop"));
+    if has_next {
+        nav.push(InlineKeyboardButton::callback("Next ▶️", page_data(page + 1)));
+    }
+
+    InlineKeyboardMarkup::new(vec![
+        nav,
+        vec![InlineKeyboardButton::callback("🏠 Main Menu", "menu:main")]
+    ])
+}
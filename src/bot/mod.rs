@@ -2,12 +2,13 @@ pub mod handlers;
 pub mod commands;
 pub mod constants;
 pub mod keyboards;
+pub mod rate_limiter;
+pub mod dialogue_storage;
 mod callbacks;
+mod group;
 mod utils;
 
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::RwLock;
 use teloxide::prelude::*;
 use teloxide::dispatching::{ UpdateHandler, UpdateFilterExt };
 use teloxide::utils::command::BotCommands;
@@ -15,6 +16,7 @@ use dptree::case;
 use crate::services::{
     WalletService,
     BalanceService,
+    BalanceWatcher,
     TransferService,
     TransactionService,
     PortfolioService,
@@ -23,13 +25,31 @@ use crate::services::{
     GasEstimationService,
     scheduling_service::SchedulingService,
     price_alert_service::PriceAlertService,
+    rebalancing_service::RebalancingService,
+    TaxReportService,
     security_service::SecurityService,
     swap_service::SwapService,
+    DcaService,
+    DefiProtocolService,
+    BlockService,
+    NetworkCongestionService,
+    TokenApprovalService,
+    TransactionSimulator,
+    UserPreferenceService,
+    UserSessionService,
+    BroadcastService,
+    GroupPermissionService,
+    CrossChainBalanceService,
+    RecentTransactionCache,
 };
 use crate::crypto::Encryptor;
+use crate::db::SwapRepository;
+use rate_limiter::RateLimiter;
 use crate::config::Config;
+use crate::task_manager::TaskManager;
+use serde::{ Deserialize, Serialize };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum DialogueState {
     /// No active dialogue
     None,
@@ -38,6 +58,10 @@ pub enum DialogueState {
         wallet_id: String,
         amount: String,
         symbol: String,
+        /// Send the full balance minus the network fee
+        send_max: bool,
+        /// USD value the amount was derived from, when entered in dollars
+        amount_usd_estimate: Option<f64>,
     },
     /// Waiting for send amount
     WaitingForSendAmount {
@@ -45,19 +69,31 @@ pub enum DialogueState {
         recipient: String,
         symbol: String,
     },
-    /// Pending send confirmation - stores all details for the confirm button
-    PendingSendConfirmation {
+    /// Waiting for send amount entered in USD
+    WaitingForUsdSendAmount {
         wallet_id: String,
-        recipient: String,
-        amount: String,
         symbol: String,
     },
+    /// Pending send confirmation - stores all details for the confirm button
+    PendingSendConfirmation(PendingSendConfirmation),
+    /// Confirmed send waiting for the user's PIN before it is broadcast
+    WaitingForPin {
+        pending_send: Box<PinProtectedSend>,
+    },
     /// Waiting for swap amount
     WaitingForSwapAmount {
         wallet_id: String,
         from_token: String,
         to_token: String,
     },
+    /// Swap quote shown, waiting for confirmation or a slippage change
+    PendingSwapConfirmation(PendingSwapState),
+    /// Waiting for a custom slippage tolerance for the pending swap
+    WaitingForSlippage {
+        pending_swap: Box<PendingSwapState>,
+    },
+    /// /swap token that matched several known tokens, waiting for the user to pick one
+    PendingSwapTokenPick(PendingSwapTokens),
     /// Waiting for alert target value (price or percent)
     WaitingForAlertValue {
         token_symbol: String,
@@ -71,6 +107,83 @@ pub enum DialogueState {
         alert_kind: String,
         value: f64,
     },
+    /// Batch transfer previewed with its cost estimate, waiting for the user to proceed
+    PendingBatchSend {
+        wallet_id: String,
+        recipients: Vec<crate::services::transfer_service::BatchRecipient>,
+    },
+    /// Schedule that passed validation, waiting for the user to simulate and create it
+    PendingSchedule {
+        request: crate::services::scheduling_service::ScheduleRequest,
+    },
+    /// Custom wallet RPC URL waiting for the user to accept the privacy warning,
+    /// encrypted since it often embeds an API key
+    PendingRpcOverride {
+        wallet_id: String,
+        sealed_url: String,
+    },
+}
+
+/// Send details held between the confirmation screen and execution
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingSendConfirmation {
+    pub wallet_id: String,
+    pub recipient: String,
+    pub amount: String,
+    pub symbol: String,
+    pub send_max: bool,
+    pub amount_usd_estimate: Option<f64>,
+    /// ERC-20 / SPL token being sent; native when unset
+    #[serde(default)]
+    pub token_address: Option<String>,
+}
+
+/// A send that has been confirmed and only waits on the PIN check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum PinProtectedSend {
+    Single(PendingSendConfirmation),
+    Batch {
+        wallet_id: String,
+        recipients: Vec<crate::services::transfer_service::BatchRecipient>,
+    },
+}
+
+impl PinProtectedSend {
+    pub fn wallet_id(&self) -> &str {
+        match self {
+            PinProtectedSend::Single(pending) => &pending.wallet_id,
+            PinProtectedSend::Batch { wallet_id, .. } => wallet_id,
+        }
+    }
+}
+
+/// Swap details held between the confirmation screen and execution
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingSwapState {
+    pub wallet_id: String,
+    pub from_token: String,
+    pub to_token: String,
+    pub amount: String,
+    /// DEX picked from the quote comparison; best price when unset
+    pub dex: Option<String>,
+    /// Slippage tolerance in percent
+    pub slippage: f64,
+}
+
+/// A /swap command whose tokens are still being resolved
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingSwapTokens {
+    pub wallet_id: String,
+    pub chain: String,
+    pub from_token: String,
+    pub to_token: String,
+    pub amount: String,
+    /// Slippage given with the command; the user's preference otherwise
+    pub slippage: Option<f64>,
+    /// Tokens offered for the ambiguous side
+    pub candidates: Vec<crate::services::swap_service::TokenCandidate>,
+    /// Whether the candidates are for `from_token` rather than `to_token`
+    pub picking_from: bool,
 }
 
 impl Default for DialogueState {
@@ -79,8 +192,24 @@ impl Default for DialogueState {
     }
 }
 
-/// Dialogue storage for users
-pub type DialogueStorage = Arc<RwLock<HashMap<i64, DialogueState>>>;
+/// Dialogue storage for users, in memory or in the database per `Config::dialogue_storage_backend`
+pub type DialogueStorage = Arc<dyn dialogue_storage::PersistentDialogueStorage>;
+
+/// Dialogue storage key for `user_id` in `chat_id`. Private chats use the user id itself;
+/// in groups the key is derived from both ids, so a conversation never carries over between
+/// a private chat and a group or between two groups.
+pub(crate) fn dialogue_key(chat_id: ChatId, user_id: i64) -> i64 {
+    use sha2::{ Digest, Sha256 };
+
+    if chat_id.0 == user_id {
+        return user_id;
+    }
+    let digest = Sha256::new().chain_update(chat_id.0.to_be_bytes()).chain_update(user_id.to_be_bytes()).finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    // Telegram user ids are positive; keeping group keys negative means they can't collide
+    (u64::from_be_bytes(bytes) | (1 << 63)) as i64
+}
 
 #[derive(Clone)]
 pub struct BotState {
@@ -89,16 +218,39 @@ pub struct BotState {
     pub transfer_service: Arc<TransferService>,
     pub transaction_service: Arc<TransactionService>,
     pub portfolio_service: Arc<PortfolioService>,
+    pub cross_chain_balance_service: Arc<CrossChainBalanceService>,
     pub price_service: Arc<PriceService>,
     pub address_book_service: Arc<AddressBookService>,
     pub gas_estimation_service: Arc<GasEstimationService>,
     pub scheduling_service: Arc<SchedulingService>,
     pub price_alert_service: Arc<PriceAlertService>,
+    pub rebalancing_service: Arc<RebalancingService>,
+    pub tax_report_service: Arc<TaxReportService>,
     pub security_service: Arc<SecurityService>,
     pub swap_service: Arc<SwapService>,
+    pub swap_repository: Arc<SwapRepository>,
+    pub dca_service: Arc<DcaService>,
+    pub defi_protocol_service: Arc<DefiProtocolService>,
+    pub block_service: Arc<BlockService>,
+    pub network_congestion: Arc<NetworkCongestionService>,
+    pub token_approval_service: Arc<TokenApprovalService>,
+    pub transaction_simulator: Arc<TransactionSimulator>,
+    pub user_preference_service: Arc<UserPreferenceService>,
+    /// Last wallet each user worked with, offered again on /start
+    pub user_session_service: Arc<UserSessionService>,
+    pub broadcast_service: Arc<BroadcastService>,
+    /// Member roles in group chats when `group_wallet_mode` is on
+    pub group_permission_service: Arc<GroupPermissionService>,
+    /// Newest transactions per wallet, shared with `TransactionService`
+    pub recent_transactions: Arc<RecentTransactionCache>,
     pub encryptor: Arc<Encryptor>,
     pub config: Arc<Config>,
     pub dialogue_storage: DialogueStorage,
+    pub rate_limiter: Arc<RateLimiter>,
+    /// Wallets users follow with /watchbalance
+    pub balance_watcher: Arc<BalanceWatcher>,
+    /// Background tasks running alongside the bot, for status reporting
+    pub task_manager: Arc<TaskManager>,
 }
 
 fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
@@ -113,56 +265,31 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
         .filter(|msg: Message| msg.text().is_some() && !msg.text().unwrap().starts_with('/'))
         .endpoint(callbacks::handle_text_message);
 
-    dptree::entry().branch(command_handler).branch(callback_handler).branch(message_handler)
+    // CSV/JSON uploads import address book contacts
+    let document_handler = Update::filter_message()
+        .filter(|msg: Message| msg.document().is_some())
+        .endpoint(callbacks::handle_document);
+
+    // Inline mode must also be switched on for the bot via BotFather (/setinline)
+    let inline_handler = Update::filter_inline_query().endpoint(callbacks::handle_inline_query);
+
+    dptree::entry()
+        .branch(command_handler)
+        .branch(callback_handler)
+        .branch(document_handler)
+        .branch(inline_handler)
+        .branch(message_handler)
 }
 
-pub async fn run_bot(
-    bot_token: String,
-    wallet_service: Arc<WalletService>,
-    balance_service: Arc<BalanceService>,
-    transfer_service: Arc<TransferService>,
-    transaction_service: Arc<TransactionService>,
-    portfolio_service: Arc<PortfolioService>,
-    price_service: Arc<PriceService>,
-    address_book_service: Arc<AddressBookService>,
-    gas_estimation_service: Arc<GasEstimationService>,
-    scheduling_service: Arc<SchedulingService>,
-    price_alert_service: Arc<PriceAlertService>,
-    security_service: Arc<SecurityService>,
-    swap_service: Arc<SwapService>,
-    encryptor: Arc<Encryptor>,
-    config: Arc<Config>
-) {
+pub async fn run_bot(bot: Bot, state: Arc<BotState>) {
     tracing::info!("Starting Telegram bot...");
 
-    let bot = Bot::new(bot_token);
-
     if let Err(e) = bot.set_my_commands(commands::Command::bot_commands()).await {
         tracing::warn!("Failed to set bot commands: {}", e);
     } else {
         tracing::info!("Bot commands registered successfully");
     }
 
-    let dialogue_storage: DialogueStorage = Arc::new(RwLock::new(HashMap::new()));
-
-    let state = Arc::new(BotState {
-        wallet_service,
-        balance_service,
-        transfer_service,
-        transaction_service,
-        portfolio_service,
-        price_service,
-        address_book_service,
-        gas_estimation_service,
-        scheduling_service,
-        price_alert_service,
-        security_service,
-        swap_service,
-        encryptor,
-        config,
-        dialogue_storage,
-    });
-
     Dispatcher::builder(bot, schema())
         .dependencies(dptree::deps![state])
         .enable_ctrlc_handler()
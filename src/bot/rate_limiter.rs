@@ -0,0 +1,66 @@
+use dashmap::DashMap;
+use std::time::Instant;
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-user token bucket limiter for bot commands
+pub struct RateLimiter {
+    buckets: DashMap<i64, TokenBucket>,
+    max_tokens: f64,
+    refill_rate: f64,
+}
+
+impl RateLimiter {
+    /// `max_tokens` is the burst size, `refill_rate` the tokens regained per second
+    pub fn new(max_tokens: f64, refill_rate: f64) -> Self {
+        Self {
+            buckets: DashMap::new(),
+            max_tokens,
+            refill_rate,
+        }
+    }
+
+    /// Take one token from the user's bucket, returning false if none are left
+    pub fn check(&self, user_id: i64) -> bool {
+        self.check_cost(user_id, 1.0)
+    }
+
+    /// Take `cost` tokens from the user's bucket, returning false if there aren't enough
+    pub fn check_cost(&self, user_id: i64, cost: f64) -> bool {
+        let now = Instant::now();
+        let mut bucket = self.buckets.entry(user_id).or_insert_with(|| TokenBucket {
+            tokens: self.max_tokens,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_rate).min(self.max_tokens);
+        bucket.last_refill = now;
+
+        if bucket.tokens < cost {
+            return false;
+        }
+        bucket.tokens -= cost;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_cost() {
+        let limiter = RateLimiter::new(5.0, 0.0);
+        assert!(limiter.check_cost(1, 3.0));
+        assert!(!limiter.check_cost(1, 3.0));
+        assert!(limiter.check_cost(1, 2.0));
+        assert!(!limiter.check(1));
+
+        // Buckets are tracked per user
+        assert!(limiter.check(2));
+    }
+}
@@ -0,0 +1,138 @@
+// Text charts for rendering numeric series in chat messages
+
+/// Braille cells ordered from emptiest to fullest
+const SPARK_LEVELS: [char; 8] = ['⡀', '⣀', '⣄', '⣤', '⣦', '⣶', '⣷', '⣿'];
+
+/// Render values as a single-line braille sparkline at most `width` cells wide.
+/// Longer series are downsampled by averaging consecutive values.
+pub fn render_sparkline(values: &[f64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    let cells = width.min(values.len());
+    let bucketed: Vec<f64> = (0..cells)
+        .map(|i| {
+            let start = i * values.len() / cells;
+            let end = ((i + 1) * values.len() / cells).max(start + 1);
+            let bucket = &values[start..end];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect();
+
+    let min = bucketed.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = bucketed.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    let top = SPARK_LEVELS.len() - 1;
+
+    bucketed
+        .iter()
+        .map(|v| {
+            let level = if range > 0.0 {
+                (((v - min) / range) * top as f64).round() as usize
+            } else {
+                top / 2
+            };
+            SPARK_LEVELS[level.min(top)]
+        })
+        .collect()
+}
+
+/// Horizontal bar pieces from one eighth to a full cell
+const BAR_EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Render one horizontal bar per value, `width` cells at the series maximum.
+/// Bars are scaled between the series min and max so small moves stay visible;
+/// the minimum still gets a one-eighth sliver.
+pub fn render_bar_rows(values: &[f64], width: usize) -> Vec<String> {
+    if width == 0 {
+        return Vec::new();
+    }
+
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    let total_eighths = width * 8;
+
+    values
+        .iter()
+        .map(|v| {
+            let eighths = if range > 0.0 {
+                1 + (((v - min) / range) * (total_eighths - 1) as f64).round() as usize
+            } else {
+                total_eighths / 2
+            };
+            let mut bar = "█".repeat(eighths / 8);
+            if eighths % 8 > 0 {
+                bar.push(BAR_EIGHTHS[eighths % 8 - 1]);
+            }
+            // Pad so the labels after the bar line up
+            let cells = bar.chars().count();
+            bar.push_str(&" ".repeat(width - cells));
+            bar
+        })
+        .collect()
+}
+
+/// Dotted line drawn across empty cells at a support or resistance level
+const LEVEL_LINE: char = '┈';
+
+/// Render each `(low, high)` range as a vertical column of blocks, `height` rows tall
+/// with the highest price on the first row. Rows holding one of `levels` are dotted
+/// through the empty cells so the levels read as horizontal lines.
+pub fn render_range_columns(ranges: &[(f64, f64)], levels: &[f64], height: usize) -> Vec<String> {
+    if ranges.is_empty() || height == 0 {
+        return Vec::new();
+    }
+
+    let min = ranges.iter().map(|(low, _)| *low).fold(f64::INFINITY, f64::min);
+    let max = ranges.iter().map(|(_, high)| *high).fold(f64::NEG_INFINITY, f64::max);
+    let step = (max - min) / height as f64;
+    let row_of = |price: f64| -> usize {
+        if step > 0.0 { (((max - price) / step) as usize).min(height - 1) } else { height / 2 }
+    };
+    let level_rows: Vec<usize> = levels.iter().map(|level| row_of(*level)).collect();
+
+    (0..height)
+        .map(|row| {
+            ranges
+                .iter()
+                .map(|(low, high)| {
+                    if row_of(*high) <= row && row <= row_of(*low) {
+                        '█'
+                    } else if level_rows.contains(&row) {
+                        LEVEL_LINE
+                    } else {
+                        ' '
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_sparkline() {
+        assert_eq!(render_sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], 8), "⡀⣀⣄⣤⣦⣶⣷⣿");
+        assert_eq!(render_sparkline(&[1.0, 1.0, 9.0, 9.0], 2), "⡀⣿");
+        assert_eq!(render_sparkline(&[5.0, 5.0], 10).chars().count(), 2);
+        assert_eq!(render_sparkline(&[], 10), "");
+    }
+
+    #[test]
+    fn test_render_bar_rows() {
+        assert_eq!(render_bar_rows(&[1.0, 3.0, 2.0], 2), vec!["▏ ", "██", "█▏"]);
+        assert!(render_bar_rows(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn test_render_range_columns() {
+        let rows = render_range_columns(&[(1.0, 2.0), (3.0, 4.0), (1.0, 4.0)], &[1.5], 4);
+        assert_eq!(rows, vec![" ██", " ██", "█ █", "█┈█"]);
+        assert!(render_range_columns(&[], &[], 4).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+// Utility functions for the bot module
+// QR code generation, formatting helpers, etc.
+
+pub mod chart;
+
+use qrcode::QrCode;
+use image::Luma;
+
+pub fn generate_qr_code(data: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let code = QrCode::new(data)?;
+    let image = code.render::<Luma<u8>>().build();
+
+    let mut buffer = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Png)?;
+
+    Ok(buffer)
+}
+
+/// One-line swap summary: "💱 1 ETH → 2500 USDC | success | 0x1234...abcd"
+pub fn format_swap_line(swap: &crate::db::entity::swap::Model) -> String {
+    let short_hash = match &swap.tx_hash {
+        Some(hash) if hash.len() > 12 => format!("{}...{}", &hash[..6], &hash[hash.len() - 4..]),
+        Some(hash) => hash.clone(),
+        None => "no tx".to_string(),
+    };
+
+    format!(
+        "💱 {} {} → {} {} | {} | {}",
+        swap.from_amount.normalize(),
+        swap.from_token,
+        swap.to_amount.normalize(),
+        swap.to_token,
+        swap.status,
+        short_hash
+    )
+}
+
+/// Width of the allocation bars in the "By Chain" view
+const BREAKDOWN_BAR_WIDTH: usize = 8;
+
+/// Bar-chart lines like "🔷 Ethereum  ████░░░░  62%  $3,200.00"
+pub fn format_chain_breakdown(breakdown: &[crate::services::portfolio_service::ChainAllocation]) -> String {
+    let names: Vec<String> = breakdown
+        .iter()
+        .map(|a| {
+            a.chain
+                .parse::<crate::enums::Chain>()
+                .map(|c| c.display_name().to_string())
+                .unwrap_or_else(|_| a.chain.clone())
+        })
+        .collect();
+    let name_width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+
+    let mut text = String::new();
+    for (allocation, name) in breakdown.iter().zip(&names) {
+        let emoji = allocation.chain
+            .parse::<crate::enums::Chain>()
+            .map(|c| c.emoji())
+            .unwrap_or("📍");
+        let filled = ((allocation.pct_of_total / 100.0) * BREAKDOWN_BAR_WIDTH as f64)
+            .round()
+            .clamp(0.0, BREAKDOWN_BAR_WIDTH as f64) as usize;
+
+        text.push_str(&format!(
+            "{} {:<width$}  {}{}  {:>3.0}%  ${}\n",
+            emoji,
+            name,
+            "█".repeat(filled),
+            "░".repeat(BREAKDOWN_BAR_WIDTH - filled),
+            allocation.pct_of_total,
+            super::handlers::format_currency(allocation.usd_value),
+            width = name_width
+        ));
+    }
+    text
+}
+
+/// One line per token like "USDC: $1,234.00 (Ethereum: $500.00, Polygon: $400.00)"
+pub fn format_token_aggregation(
+    tokens: &[crate::services::cross_chain_balance_service::AggregatedTokenBalance]
+) -> String {
+    let mut text = String::new();
+    for token in tokens {
+        let chains: Vec<String> = token.holdings
+            .iter()
+            .map(|h| {
+                let chain = h.chain
+                    .parse::<crate::enums::Chain>()
+                    .map(|c| c.display_name().to_string())
+                    .unwrap_or_else(|_| h.chain.clone());
+                format!("{}: ${}", chain, super::handlers::format_currency(h.usd_value))
+            })
+            .collect();
+        text.push_str(&format!(
+            "{}: ${} ({})\n",
+            token.symbol,
+            super::handlers::format_currency(token.total_usd_value),
+            chains.join(", ")
+        ));
+    }
+    text
+}
+
+/// One block per lending position: protocol and asset, supplied amount, APY and unclaimed rewards
+pub fn format_defi_positions(positions: &[crate::services::defi_protocol_service::DefiPosition]) -> String {
+    let mut text = String::new();
+    for position in positions {
+        text.push_str(&format!(
+            "🏦 {} · {}\n   Supplied: {:.4} {}\n   APY: {:.2}%\n   Earned rewards: ${}\n\n",
+            position.protocol.display_name(),
+            position.asset_symbol,
+            position.supplied_amount,
+            position.asset_symbol,
+            position.apy,
+            super::handlers::format_currency(position.earned_yield_usd)
+        ));
+    }
+    text
+}
+
+/// Fee summary headline with a per-chain breakdown, e.g.
+/// "💸 Fees paid last 30 days: $12.50 (Ethereum: $10.00, Polygon: $2.50)"
+pub fn format_fee_summary(summary: &crate::services::FeeSummary, days: u32) -> String {
+    let fmt = super::handlers::format_currency;
+    let breakdown: Vec<String> = summary.chains
+        .iter()
+        .map(|c| {
+            let name = c.chain
+                .parse::<crate::enums::Chain>()
+                .map(|chain| chain.display_name().to_string())
+                .unwrap_or_else(|_| c.chain.clone());
+            format!("{}: ${}", name, fmt(c.total_gas_usd))
+        })
+        .collect();
+
+    let mut text = format!("💸 Fees paid last {} days: ${}", days, fmt(summary.total_gas_usd));
+    if !breakdown.is_empty() {
+        text.push_str(&format!(" ({})", breakdown.join(", ")));
+    }
+    text.push_str(&format!(
+        "\n\nTransactions: {}\nAverage fee: ${}",
+        summary.tx_count,
+        fmt(summary.average_fee_usd)
+    ));
+    if let Some(max) = &summary.most_expensive {
+        text.push_str(&format!(
+            "\nMost expensive: ${} ({} {}) on {}",
+            fmt(max.gas_fee_usd),
+            max.gas_fee_native,
+            max.chain
+                .parse::<crate::enums::Chain>()
+                .map(|chain| chain.native_symbol())
+                .unwrap_or(""),
+            max.created_at.get(..10).unwrap_or(&max.created_at)
+        ));
+    }
+    text
+}
+
+/// Integer with thousands separators, e.g. "19,123,456"
+pub fn format_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Header facts of a block for /block
+pub fn format_block_info(chain: crate::enums::Chain, block: &crate::providers::BlockInfo) -> String {
+    let time = chrono::DateTime::from_timestamp(block.timestamp, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "—".to_string());
+    let gas_pct = if block.gas_limit > 0 {
+        (block.gas_used as f64) / (block.gas_limit as f64) * 100.0
+    } else {
+        0.0
+    };
+    let base_fee = block.base_fee_gwei
+        .map(|fee| format!("{:.2} Gwei", fee))
+        .unwrap_or_else(|| "—".to_string());
+
+    format!(
+        "{} {} block {}\n\n\
+        Hash: {}\n\
+        Time: {}\n\
+        Transactions: {}\n\
+        Gas used: {} / {} ({:.1}%)\n\
+        Base fee: {}",
+        chain.emoji(),
+        chain.display_name(),
+        format_thousands(block.number),
+        block.hash,
+        time,
+        block.transaction_count,
+        format_thousands(block.gas_used),
+        format_thousands(block.gas_limit),
+        gas_pct,
+        base_fee
+    )
+}
+
+/// One /networkstatus line: "🔷 ETH: Block 19,123,456 | Gas: 25 Gwei | 127 txns"
+pub fn format_network_status_line(chain: crate::enums::Chain, block: &crate::providers::BlockInfo) -> String {
+    let gas = block.base_fee_gwei
+        .map(|fee| format!(" | Gas: {} Gwei", if fee >= 10.0 { format!("{:.0}", fee) } else { format!("{:.2}", fee) }))
+        .unwrap_or_default();
+    format!(
+        "{} {}: Block {}{} | {} txns",
+        chain.emoji(),
+        chain.as_str(),
+        format_thousands(block.number),
+        gas,
+        block.transaction_count
+    )
+}
+
+/// Notice for a wallet that already exists, naming it by chain and short id
+pub fn format_existing_wallet(chain: &str, existing_id: &uuid::Uuid) -> String {
+    format!(
+        "⚠️ This address already exists in your wallets as {} wallet {}. Would you like to view it instead?",
+        chain,
+        &existing_id.to_string()[..8]
+    )
+}
+
+/// Lockout notice after too many wrong PINs, with the minutes left rounded up
+pub fn format_pin_locked(unlocks_at: &chrono::DateTime<chrono::Utc>) -> String {
+    let secs_left = (*unlocks_at - chrono::Utc::now()).num_seconds();
+    let minutes = ((secs_left + 59) / 60).max(1);
+    format!(
+        "🔒 Too many failed PIN attempts. Wallet locked for {} minute{}.",
+        minutes,
+        if minutes == 1 { "" } else { "s" }
+    )
+}
+
+/// USD amount with enough decimals that dust doesn't show as $0.00, e.g. "0.002"
+pub fn format_dust_usd(value: f64) -> String {
+    if value >= 0.01 {
+        return super::handlers::format_currency(value);
+    }
+    let formatted = format!("{:.6}", value);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Dust balances like "0.000001 ETH ($0.002), 3 USDC ($0.003)"
+pub fn format_dust_balances(balances: &[crate::services::portfolio_service::DustBalance]) -> String {
+    balances
+        .iter()
+        .map(|b| format!("{} {} (${})", b.balance, b.symbol, format_dust_usd(b.usd_value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// User-facing text for a failed send; limit errors explain how to raise the cap
+pub fn format_transfer_error(error: &crate::error::AppError) -> String {
+    match error {
+        crate::error::AppError::TransferLimitExceeded { attempted_usd, limit_usd } =>
+            format!(
+                "This transfer is worth about ${} but your per-transfer limit is ${}.\n\n\
+                To send it, raise the limit with /setmaxsend <amount_usd> \
+                or remove it with /setmaxsend off.",
+                super::handlers::format_currency(*attempted_usd),
+                super::handlers::format_currency(*limit_usd)
+            ),
+        _ => error.user_facing_message().into_owned(),
+    }
+}
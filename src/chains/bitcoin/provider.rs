@@ -13,6 +13,13 @@ pub struct BitcoinProvider {
     client: reqwest::Client,
     base_url: String,
     testnet: bool,
+    lnd: Option<LndConfig>,
+}
+
+#[derive(Clone)]
+struct LndConfig {
+    rest_url: String,
+    macaroon: String,
 }
 
 // ── Esplora API response types ──────────────────────────────────────
@@ -42,6 +49,18 @@ struct EsploraUtxoStatus {
     confirmed: bool,
 }
 
+#[derive(Debug, Deserialize)]
+struct EsploraBlock {
+    timestamp: i64,
+}
+
+// ── LND REST response types ─────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct LndAddInvoiceResponse {
+    payment_request: String,
+}
+
 // ── Implementation ──────────────────────────────────────────────────
 
 impl BitcoinProvider {
@@ -53,9 +72,49 @@ impl BitcoinProvider {
                 .unwrap_or_default(),
             base_url: base_url.trim_end_matches('/').to_string(),
             testnet,
+            lnd: None,
         }
     }
 
+    /// Enable Lightning invoices through an LND node's REST API
+    pub fn with_lnd(mut self, rest_url: &str, macaroon: &str) -> Self {
+        self.lnd = Some(LndConfig {
+            rest_url: rest_url.trim_end_matches('/').to_string(),
+            macaroon: macaroon.to_string(),
+        });
+        self
+    }
+
+    async fn add_invoice(&self, lnd: &LndConfig, amount_sats: u64, memo: &str) -> Result<String> {
+        let url = format!("{}/v1/invoices", lnd.rest_url);
+
+        // LND's REST gateway encodes int64 fields as strings; 0 = payer chooses the amount
+        let body = serde_json::json!({
+            "value": amount_sats.to_string(),
+            "memo": memo,
+        });
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Grpc-Metadata-macaroon", &lnd.macaroon)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::External(format!("LND request failed: {}", e)))?;
+
+        if !resp.status().is_success() {
+            return Err(AppError::External(format!("LND API error: {}", resp.status())));
+        }
+
+        let invoice: LndAddInvoiceResponse = resp
+            .json()
+            .await
+            .map_err(|e| AppError::External(format!("Failed to parse LND response: {}", e)))?;
+
+        Ok(invoice.payment_request)
+    }
+
     async fn get_utxos(&self, address: &str) -> Result<Vec<EsploraUtxo>> {
         let url = format!("{}/address/{}/utxo", self.base_url, address);
         let resp = self
@@ -149,6 +208,7 @@ impl ChainProvider for BitcoinProvider {
             balance: Self::satoshis_to_btc(total_sats),
             symbol: "BTC".to_string(),
             decimals: 8,
+            usd_value: None,
         })
     }
 
@@ -189,14 +249,46 @@ impl ChainProvider for BitcoinProvider {
 
         Ok(GasEstimate {
             estimated_gas: estimated_vsize,
+            gas_limit: estimated_vsize,
             gas_price: Some(format!("{:.1} sat/vB", fee_rate)),
             max_fee_per_gas: None,
             max_priority_fee_per_gas: None,
             total_cost_native: Self::satoshis_to_btc(fee_sats),
             total_cost_usd: None,
+            l1_data_fee_native: None,
+            l1_data_fee_usd: None,
+            confidence_pct: None,
         })
     }
 
+    /// Mean interval across the most recent blocks Esplora returns (usually 10)
+    async fn get_average_block_time_secs(&self) -> Result<f64> {
+        let url = format!("{}/blocks", self.base_url);
+        let blocks: Vec<EsploraBlock> = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::External(format!("Blocks request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::External(format!("Failed to parse blocks: {}", e)))?;
+
+        // Newest first
+        match (blocks.first(), blocks.last()) {
+            (Some(newest), Some(oldest)) if blocks.len() > 1 => {
+                let elapsed = (newest.timestamp - oldest.timestamp).max(0) as f64;
+                Ok(elapsed / (blocks.len() - 1) as f64)
+            }
+            _ => Err(AppError::External("Not enough blocks to measure block time".to_string())),
+        }
+    }
+
+    async fn generate_invoice(&self, amount_sats: u64, memo: &str) -> Option<Result<String>> {
+        let lnd = self.lnd.as_ref()?;
+        Some(self.add_invoice(lnd, amount_sats, memo).await)
+    }
+
     fn validate_address(&self, address: &str) -> bool {
         use std::str::FromStr;
         bitcoin::Address::from_str(address)
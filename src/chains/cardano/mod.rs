@@ -1,2 +1,3 @@
 pub mod provider;
+pub mod transaction;
 pub mod wallet;
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 
 use crate::error::{AppError, Result};
@@ -6,32 +7,79 @@ use crate::providers::{
     Balance, ChainProvider, GasEstimate, TransactionRequest, TransactionResponse, WalletInfo,
 };
 
-use super::wallet;
+use super::transaction::{self, FeeParams, Utxo};
+use super::wallet::{self, PaymentKey};
+
+/// Blockfrost returns at most this many UTxOs per page
+const UTXO_PAGE_SIZE: usize = 100;
+/// Stop paging after this many pages; enough for any realistic bot wallet
+const MAX_UTXO_PAGES: u32 = 10;
+
+/// Shelley-era fee parameters, used when protocol parameters can't be fetched
+const DEFAULT_FEE_PARAMS: FeeParams = FeeParams {
+    min_fee_a: 44,
+    min_fee_b: 155_381,
+};
 
 #[derive(Clone)]
 pub struct CardanoProvider {
     client: reqwest::Client,
     base_url: String,
+    project_id: Option<String>,
     testnet: bool,
 }
 
-// ── Koios API response types ────────────────────────────────────────
+// ── Blockfrost API response types ───────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct BlockfrostAmount {
+    unit: String,
+    quantity: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockfrostAddress {
+    amount: Vec<BlockfrostAmount>,
+}
 
 #[derive(Debug, Deserialize)]
-struct KoiosAddressInfo {
-    balance: String,
+struct BlockfrostUtxo {
+    tx_hash: String,
+    output_index: u32,
+    amount: Vec<BlockfrostAmount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockfrostBlock {
+    slot: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockfrostProtocolParams {
+    min_fee_a: u64,
+    min_fee_b: u64,
+}
+
+fn lovelace_of(amounts: &[BlockfrostAmount]) -> u64 {
+    amounts
+        .iter()
+        .find(|a| a.unit == "lovelace")
+        .and_then(|a| a.quantity.parse::<u64>().ok())
+        .unwrap_or(0)
 }
 
 // ── Implementation ──────────────────────────────────────────────────
 
 impl CardanoProvider {
-    pub fn new(base_url: &str, testnet: bool) -> Self {
+    /// `base_url` is a Blockfrost API root, e.g. `https://cardano-mainnet.blockfrost.io/api/v0`
+    pub fn new(base_url: &str, testnet: bool, project_id: Option<String>) -> Self {
         Self {
             client: reqwest::Client::builder()
                 .timeout(std::time::Duration::from_secs(15))
                 .build()
                 .unwrap_or_default(),
             base_url: base_url.trim_end_matches('/').to_string(),
+            project_id,
             testnet,
         }
     }
@@ -40,55 +88,154 @@ impl CardanoProvider {
         let ada = lovelace as f64 / 1_000_000.0;
         format!("{:.6}", ada)
     }
-}
 
-#[async_trait]
-impl ChainProvider for CardanoProvider {
-    async fn generate_wallet(&self, derivation_index: u32) -> Result<WalletInfo> {
-        wallet::generate_wallet(self.testnet, derivation_index)
+    fn ada_to_lovelace(amount: &str) -> Result<u64> {
+        let ada: f64 = amount
+            .trim()
+            .parse()
+            .map_err(|_| AppError::InvalidInput(format!("Invalid ADA amount: {}", amount)))?;
+        if !ada.is_finite() || ada <= 0.0 {
+            return Err(AppError::InvalidInput(format!("Invalid ADA amount: {}", amount)));
+        }
+        Ok((ada * 1_000_000.0).round() as u64)
     }
 
-    async fn restore_wallet(&self, secret: &str, derivation_index: u32) -> Result<WalletInfo> {
-        wallet::detect_and_restore(secret, self.testnet, derivation_index)
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.project_id {
+            Some(project_id) => builder.header("project_id", project_id),
+            None => builder,
+        }
     }
 
-    async fn get_balance(&self, address: &str) -> Result<Balance> {
-        let url = format!("{}/address_info", self.base_url);
-
-        let body = serde_json::json!({
-            "_addresses": [address]
-        });
+    /// GET a Blockfrost resource; `None` when it doesn't exist yet (e.g. an unused address)
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>> {
+        let url = format!("{}{}", self.base_url, path);
 
         let resp = self
-            .client
-            .post(&url)
-            .json(&body)
+            .request(self.client.get(&url))
             .send()
             .await
-            .map_err(|e| AppError::External(format!("Koios request failed: {}", e)))?;
+            .map_err(|e| AppError::External(format!("Blockfrost request failed: {}", e)))?;
 
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
         if !resp.status().is_success() {
             return Err(AppError::External(format!(
-                "Koios API error: {}",
+                "Blockfrost API error: {}",
                 resp.status()
             )));
         }
 
-        let data: Vec<KoiosAddressInfo> = resp
-            .json()
+        resp.json()
+            .await
+            .map(Some)
+            .map_err(|e| AppError::External(format!("Failed to parse Blockfrost response: {}", e)))
+    }
+
+    /// Spendable ADA-only UTxOs; outputs holding native assets are left alone
+    async fn get_utxos(&self, address: &str) -> Result<Vec<Utxo>> {
+        let mut utxos = Vec::new();
+
+        for page in 1..=MAX_UTXO_PAGES {
+            let batch: Vec<BlockfrostUtxo> = self
+                .get(&format!("/addresses/{}/utxos?page={}", address, page))
+                .await?
+                .unwrap_or_default();
+            let last_page = batch.len() < UTXO_PAGE_SIZE;
+
+            for utxo in batch {
+                if utxo.amount.iter().any(|a| a.unit != "lovelace") {
+                    continue;
+                }
+                let mut tx_hash = [0u8; 32];
+                hex::decode_to_slice(&utxo.tx_hash, &mut tx_hash)
+                    .map_err(|e| AppError::External(format!("Invalid UTxO hash: {}", e)))?;
+                utxos.push(Utxo {
+                    tx_hash,
+                    output_index: utxo.output_index,
+                    lovelace: lovelace_of(&utxo.amount),
+                });
+            }
+
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(utxos)
+    }
+
+    async fn get_fee_params(&self) -> FeeParams {
+        match self.get::<BlockfrostProtocolParams>("/epochs/latest/parameters").await {
+            Ok(Some(params)) => FeeParams {
+                min_fee_a: params.min_fee_a,
+                min_fee_b: params.min_fee_b,
+            },
+            Ok(None) => DEFAULT_FEE_PARAMS,
+            Err(e) => {
+                tracing::warn!("Failed to fetch Cardano protocol parameters: {}", e);
+                DEFAULT_FEE_PARAMS
+            }
+        }
+    }
+
+    async fn get_tip_slot(&self) -> Result<u64> {
+        self.get::<BlockfrostBlock>("/blocks/latest")
+            .await?
+            .and_then(|block| block.slot)
+            .ok_or_else(|| AppError::External("Blockfrost returned no tip slot".to_string()))
+    }
+
+    async fn submit(&self, cbor: Vec<u8>) -> Result<String> {
+        let url = format!("{}/tx/submit", self.base_url);
+
+        let resp = self
+            .request(self.client.post(&url))
+            .header("Content-Type", "application/cbor")
+            .body(cbor)
+            .send()
+            .await
+            .map_err(|e| AppError::External(format!("Blockfrost submit failed: {}", e)))?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(AppError::Blockchain(format!(
+                "Cardano transaction rejected ({}): {}",
+                status, body
+            )));
+        }
+
+        resp.json::<String>()
             .await
-            .map_err(|e| AppError::External(format!("Failed to parse Koios response: {}", e)))?;
+            .map_err(|e| AppError::External(format!("Failed to parse submit response: {}", e)))
+    }
+}
+
+#[async_trait]
+impl ChainProvider for CardanoProvider {
+    async fn generate_wallet(&self, derivation_index: u32) -> Result<WalletInfo> {
+        wallet::generate_wallet(self.testnet, derivation_index)
+    }
+
+    async fn restore_wallet(&self, secret: &str, derivation_index: u32) -> Result<WalletInfo> {
+        wallet::detect_and_restore(secret, self.testnet, derivation_index)
+    }
 
-        // Empty array = unfunded address
-        let lovelace: u64 = data
-            .first()
-            .map(|info| info.balance.parse::<u64>().unwrap_or(0))
+    async fn get_balance(&self, address: &str) -> Result<Balance> {
+        // 404 = address has never been funded
+        let lovelace = self
+            .get::<BlockfrostAddress>(&format!("/addresses/{}", address))
+            .await?
+            .map(|info| lovelace_of(&info.amount))
             .unwrap_or(0);
 
         Ok(Balance {
             balance: Self::lovelace_to_ada(lovelace),
             symbol: "ADA".to_string(),
             decimals: 6,
+            usd_value: None,
         })
     }
 
@@ -100,12 +247,45 @@ impl ChainProvider for CardanoProvider {
 
     async fn send_transaction(
         &self,
-        _private_key: &str,
-        _request: TransactionRequest,
+        private_key: &str,
+        request: TransactionRequest,
     ) -> Result<TransactionResponse> {
-        Err(AppError::Validation(
-            "Cardano send_transaction not yet fully implemented. Coming soon.".to_string(),
-        ))
+        if request.token_address.is_some() {
+            return Err(AppError::Validation(
+                "Cardano native assets are not yet supported".to_string(),
+            ));
+        }
+
+        let key = PaymentKey::from_hex(private_key)?;
+        let to = wallet::decode_address(&request.to)?;
+        let change_address = wallet::decode_address(&request.from)?;
+        let amount = Self::ada_to_lovelace(&request.amount)?;
+
+        let utxos = self.get_utxos(&request.from).await?;
+        let fee_params = self.get_fee_params().await;
+        let ttl = self.get_tip_slot().await? + transaction::TTL_SLOTS;
+
+        let signed = transaction::build_and_sign(
+            &key,
+            &utxos,
+            &to,
+            &change_address,
+            amount,
+            fee_params,
+            ttl,
+        )?;
+
+        let tx_hash = self.submit(signed.cbor).await?;
+        tracing::info!(
+            "Submitted Cardano tx {} (fee {} ADA)",
+            tx_hash,
+            Self::lovelace_to_ada(signed.fee)
+        );
+
+        Ok(TransactionResponse {
+            tx_hash,
+            status: "pending".to_string(),
+        })
     }
 
     async fn estimate_gas(
@@ -115,26 +295,33 @@ impl ChainProvider for CardanoProvider {
         _amount: &str,
         _token_address: Option<&str>,
     ) -> Result<GasEstimate> {
-        // Cardano has a protocol-level minimum fee formula:
-        // fee = a * tx_size + b (currently a=44, b=155381 lovelace)
-        // A typical simple transaction is ~300 bytes → ~168,581 lovelace ≈ 0.17 ADA
-        let min_fee_lovelace: u64 = 170_000;
+        // fee = a * tx_size + b; a one-input, two-output payment is ~300 bytes
+        let fee_params = self.get_fee_params().await;
+        let fee_lovelace = fee_params.fee_for_size(300);
 
         Ok(GasEstimate {
-            estimated_gas: min_fee_lovelace,
-            gas_price: Some("~0.17 ADA min fee".to_string()),
+            estimated_gas: fee_lovelace,
+            gas_limit: fee_lovelace,
+            gas_price: Some(format!(
+                "{} lovelace/byte + {} lovelace",
+                fee_params.min_fee_a, fee_params.min_fee_b
+            )),
             max_fee_per_gas: None,
             max_priority_fee_per_gas: None,
-            total_cost_native: Self::lovelace_to_ada(min_fee_lovelace),
+            total_cost_native: Self::lovelace_to_ada(fee_lovelace),
             total_cost_usd: None,
+            l1_data_fee_native: None,
+            l1_data_fee_usd: None,
+            confidence_pct: None,
         })
     }
 
     fn validate_address(&self, address: &str) -> bool {
-        if self.testnet {
-            address.starts_with("addr_test1") && address.len() > 20
+        let prefix_ok = if self.testnet {
+            address.starts_with("addr_test1")
         } else {
-            address.starts_with("addr1") && !address.starts_with("addr_test") && address.len() > 20
-        }
+            address.starts_with("addr1")
+        };
+        prefix_ok && wallet::decode_address(address).is_ok()
     }
 }
@@ -0,0 +1,203 @@
+use blake2::digest::{consts::U32, Digest};
+use blake2::Blake2b;
+
+use crate::error::{AppError, Result};
+
+use super::wallet::PaymentKey;
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Smallest ADA-only output the ledger accepts (protocol min-UTxO, ~1 ADA)
+pub const MIN_UTXO_LOVELACE: u64 = 1_000_000;
+
+/// Slots a signed transaction stays valid for (~2 hours)
+pub const TTL_SLOTS: u64 = 7200;
+
+#[derive(Debug, Clone)]
+pub struct Utxo {
+    pub tx_hash: [u8; 32],
+    pub output_index: u32,
+    pub lovelace: u64,
+}
+
+/// Linear fee parameters: `fee = min_fee_a * tx_size + min_fee_b`
+#[derive(Debug, Clone, Copy)]
+pub struct FeeParams {
+    pub min_fee_a: u64,
+    pub min_fee_b: u64,
+}
+
+impl FeeParams {
+    pub fn fee_for_size(&self, size: usize) -> u64 {
+        self.min_fee_a * size as u64 + self.min_fee_b
+    }
+}
+
+#[derive(Debug)]
+pub struct SignedTransaction {
+    pub cbor: Vec<u8>,
+    pub tx_hash: String,
+    pub fee: u64,
+}
+
+// ── Minimal CBOR encoding ───────────────────────────────────────────
+
+fn write_head(buf: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    if value < 24 {
+        buf.push(major | value as u8);
+    } else if value <= u8::MAX as u64 {
+        buf.push(major | 24);
+        buf.push(value as u8);
+    } else if value <= u16::MAX as u64 {
+        buf.push(major | 25);
+        buf.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u32::MAX as u64 {
+        buf.push(major | 26);
+        buf.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        buf.push(major | 27);
+        buf.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_uint(buf: &mut Vec<u8>, value: u64) {
+    write_head(buf, 0, value);
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_head(buf, 2, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_array(buf: &mut Vec<u8>, len: usize) {
+    write_head(buf, 4, len as u64);
+}
+
+fn write_map(buf: &mut Vec<u8>, len: usize) {
+    write_head(buf, 5, len as u64);
+}
+
+// ── Transaction building ────────────────────────────────────────────
+
+fn encode_body(inputs: &[&Utxo], outputs: &[(&[u8], u64)], fee: u64, ttl: u64) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_map(&mut buf, 4);
+
+    write_uint(&mut buf, 0);
+    write_array(&mut buf, inputs.len());
+    for input in inputs {
+        write_array(&mut buf, 2);
+        write_bytes(&mut buf, &input.tx_hash);
+        write_uint(&mut buf, input.output_index as u64);
+    }
+
+    write_uint(&mut buf, 1);
+    write_array(&mut buf, outputs.len());
+    for (address, lovelace) in outputs {
+        write_array(&mut buf, 2);
+        write_bytes(&mut buf, address);
+        write_uint(&mut buf, *lovelace);
+    }
+
+    write_uint(&mut buf, 2);
+    write_uint(&mut buf, fee);
+
+    write_uint(&mut buf, 3);
+    write_uint(&mut buf, ttl);
+
+    buf
+}
+
+fn encode_transaction(body: &[u8], vkey: &[u8; 32], signature: &[u8; 64]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(body.len() + 110);
+    write_array(&mut buf, 4);
+    buf.extend_from_slice(body);
+
+    // Witness set: { 0: [[vkey, signature]] }
+    write_map(&mut buf, 1);
+    write_uint(&mut buf, 0);
+    write_array(&mut buf, 1);
+    write_array(&mut buf, 2);
+    write_bytes(&mut buf, vkey);
+    write_bytes(&mut buf, signature);
+
+    // is_valid = true, no auxiliary data
+    buf.push(0xf5);
+    buf.push(0xf6);
+    buf
+}
+
+fn body_hash(body: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2b256::new();
+    hasher.update(body);
+    hasher.finalize().into()
+}
+
+/// Select ADA-only UTxOs largest-first, pay `amount` to `to`, return change to `change_address`
+/// and sign with `key`. Change below the min-UTxO is folded into the fee.
+pub fn build_and_sign(
+    key: &PaymentKey,
+    utxos: &[Utxo],
+    to: &[u8],
+    change_address: &[u8],
+    amount: u64,
+    fee_params: FeeParams,
+    ttl: u64,
+) -> Result<SignedTransaction> {
+    if amount < MIN_UTXO_LOVELACE {
+        return Err(AppError::InvalidInput(format!(
+            "Cardano outputs must carry at least {} ADA",
+            MIN_UTXO_LOVELACE / 1_000_000
+        )));
+    }
+
+    let mut sorted: Vec<&Utxo> = utxos.iter().collect();
+    sorted.sort_by_key(|u| std::cmp::Reverse(u.lovelace));
+
+    let vkey = key.verifying_key().to_bytes();
+    let available: u64 = sorted.iter().map(|u| u.lovelace).sum();
+
+    for selected in 1..=sorted.len() {
+        let inputs = &sorted[..selected];
+        let total: u64 = inputs.iter().map(|u| u.lovelace).sum();
+
+        // Size with placeholder values wide enough to never under-estimate
+        let sizing_body = encode_body(
+            inputs,
+            &[(to, amount), (change_address, total)],
+            u32::MAX as u64,
+            ttl,
+        );
+        let sizing_tx = encode_transaction(&sizing_body, &vkey, &[0u8; 64]);
+        let mut fee = fee_params.fee_for_size(sizing_tx.len());
+
+        let Some(change) = total.checked_sub(amount + fee) else {
+            continue;
+        };
+
+        let outputs: Vec<(&[u8], u64)> = if change >= MIN_UTXO_LOVELACE {
+            vec![(to, amount), (change_address, change)]
+        } else {
+            fee += change;
+            vec![(to, amount)]
+        };
+
+        let body = encode_body(inputs, &outputs, fee, ttl);
+        let hash = body_hash(&body);
+        let signature = key.sign(&hash);
+
+        return Ok(SignedTransaction {
+            cbor: encode_transaction(&body, &vkey, &signature),
+            tx_hash: hex::encode(hash),
+            fee,
+        });
+    }
+
+    let estimated_fee = fee_params.fee_for_size(300);
+    Err(AppError::InsufficientFunds {
+        available: format!("{:.6} ADA", available as f64 / 1_000_000.0),
+        required: format!("{:.6} ADA", (amount + estimated_fee) as f64 / 1_000_000.0),
+    })
+}
+
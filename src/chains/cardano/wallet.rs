@@ -1,7 +1,9 @@
 use bip39::Mnemonic;
 use blake2::digest::{consts::U28, Digest};
 use blake2::Blake2b;
-use ed25519_dalek::SigningKey;
+use curve25519_dalek::scalar::Scalar;
+use ed25519_dalek::hazmat::ExpandedSecretKey;
+use ed25519_dalek::{SigningKey, VerifyingKey};
 use hmac::{Hmac, Mac};
 use sha2::Sha512;
 
@@ -11,55 +13,204 @@ use crate::providers::WalletInfo;
 type Blake2b224 = Blake2b<U28>;
 type HmacSha512 = Hmac<Sha512>;
 
-/// Derive an Ed25519 keypair from seed bytes using HMAC-SHA512 (simplified CIP-1852).
-/// Real Cardano wallets use an extended key scheme, but for basic wallet generation
-/// this produces a valid Ed25519 keypair that can receive ADA.
-fn derive_cardano_keypair(seed: &[u8], derivation_index: u32) -> (SigningKey, ed25519_dalek::VerifyingKey) {
-    // Use HMAC-SHA512 with "ed25519 cardano seed" as key
-    let mut mac = HmacSha512::new_from_slice(b"ed25519 cardano seed")
-        .expect("HMAC can take key of any size");
-    mac.update(seed);
-    // Include derivation index
-    mac.update(&derivation_index.to_be_bytes());
-    let result = mac.finalize().into_bytes();
-
-    let mut key_bytes = [0u8; 32];
-    key_bytes.copy_from_slice(&result[..32]);
-
-    let signing_key = SigningKey::from_bytes(&key_bytes);
-    let verifying_key = signing_key.verifying_key();
-
-    (signing_key, verifying_key)
+const HARDENED: u32 = 0x8000_0000;
+const PURPOSE: u32 = 1852;
+const COIN_TYPE: u32 = 1815;
+const EXTERNAL_CHAIN: u32 = 0;
+const STAKING_CHAIN: u32 = 2;
+
+/// BIP32-Ed25519 extended private key: `kL || kR` plus the chain code
+#[derive(Clone)]
+struct ExtendedKey {
+    key: [u8; 64],
+    chain_code: [u8; 32],
 }
 
-/// Build a Cardano enterprise address (type 0x60/0x70) from an Ed25519 public key.
-/// Enterprise addresses have only a payment credential (no staking).
-/// Format: header_byte || Blake2b-224(pubkey)
-fn pub_key_to_address(pub_key_bytes: &[u8], testnet: bool) -> String {
-    // Blake2b-224 hash of the public key
-    let mut hasher = Blake2b224::new();
-    hasher.update(pub_key_bytes);
-    let key_hash = hasher.finalize();
+impl ExtendedKey {
+    /// Icarus master key: PBKDF2-HMAC-SHA512 over the mnemonic entropy, then clamped
+    fn from_entropy(entropy: &[u8]) -> Self {
+        let mut out = [0u8; 96];
+        pbkdf2::pbkdf2_hmac::<Sha512>(b"", entropy, 4096, &mut out);
 
-    // Header byte: 0x61 = mainnet enterprise, 0x60 = testnet enterprise
-    // Actually: type 6 (enterprise) + network tag (0=testnet, 1=mainnet)
-    let header = if testnet { 0x60u8 } else { 0x61u8 };
+        out[0] &= 0b1111_1000;
+        out[31] &= 0b0001_1111;
+        out[31] |= 0b0100_0000;
 
-    let mut payload = Vec::with_capacity(29);
-    payload.push(header);
-    payload.extend_from_slice(&key_hash);
+        let mut key = [0u8; 64];
+        key.copy_from_slice(&out[..64]);
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&out[64..]);
+        Self { key, chain_code }
+    }
+
+    fn public_key(&self) -> [u8; 32] {
+        VerifyingKey::from(&expanded_secret(&self.key)).to_bytes()
+    }
+
+    fn derive(&self, index: u32) -> Self {
+        let index_bytes = index.to_le_bytes();
+        let (z, cc) = if index >= HARDENED {
+            (
+                self.hmac(0x00, &self.key, &index_bytes),
+                self.hmac(0x01, &self.key, &index_bytes),
+            )
+        } else {
+            let public_key = self.public_key();
+            (
+                self.hmac(0x02, &public_key, &index_bytes),
+                self.hmac(0x03, &public_key, &index_bytes),
+            )
+        };
+
+        // kL' = 8 * zL[..28] + kL, kR' = zR + kR (both little-endian, 256-bit)
+        let mut key = [0u8; 64];
+        let mut carry = 0u16;
+        for i in 0..32 {
+            let zl = if i < 28 { z[i] as u16 } else { 0 };
+            let prev = if i > 0 && i <= 28 { (z[i - 1] >> 5) as u16 } else { 0 };
+            let sum = ((zl << 3) & 0xff) + prev + self.key[i] as u16 + carry;
+            key[i] = sum as u8;
+            carry = sum >> 8;
+        }
+        let mut carry = 0u16;
+        for i in 0..32 {
+            let sum = z[32 + i] as u16 + self.key[32 + i] as u16 + carry;
+            key[32 + i] = sum as u8;
+            carry = sum >> 8;
+        }
+
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&cc[32..]);
+        Self { key, chain_code }
+    }
+
+    fn derive_path(&self, path: &[u32]) -> Self {
+        path.iter().fold(self.clone(), |key, &index| key.derive(index))
+    }
 
-    // Bech32 encode
+    fn hmac(&self, tag: u8, data: &[u8], index: &[u8]) -> [u8; 64] {
+        let mut mac = HmacSha512::new_from_slice(&self.chain_code)
+            .expect("HMAC can take key of any size");
+        mac.update(&[tag]);
+        mac.update(data);
+        mac.update(index);
+        mac.finalize().into_bytes().into()
+    }
+}
+
+fn expanded_secret(key: &[u8; 64]) -> ExpandedSecretKey {
+    let mut scalar_bytes = [0u8; 32];
+    scalar_bytes.copy_from_slice(&key[..32]);
+    let mut hash_prefix = [0u8; 32];
+    hash_prefix.copy_from_slice(&key[32..]);
+
+    ExpandedSecretKey {
+        scalar: Scalar::from_bytes_mod_order(scalar_bytes),
+        hash_prefix,
+    }
+}
+
+/// Key able to sign Cardano transactions, from either storage format
+pub enum PaymentKey {
+    /// 64-byte BIP32-Ed25519 key derived along `m/1852'/1815'/0'/0/i`
+    Extended(ExpandedSecretKey),
+    /// 32-byte Ed25519 seed from wallets created before CIP-1852 derivation
+    Legacy(Box<SigningKey>),
+}
+
+impl PaymentKey {
+    pub fn from_hex(hex_key: &str) -> Result<Self> {
+        let key_bytes = hex::decode(hex_key.trim_start_matches("0x"))
+            .map_err(|e| AppError::InvalidInput(format!("Invalid hex private key: {}", e)))?;
+
+        match key_bytes.len() {
+            64 => {
+                let mut key = [0u8; 64];
+                key.copy_from_slice(&key_bytes);
+                Ok(Self::Extended(expanded_secret(&key)))
+            }
+            32 => {
+                let mut key = [0u8; 32];
+                key.copy_from_slice(&key_bytes);
+                Ok(Self::Legacy(Box::new(SigningKey::from_bytes(&key))))
+            }
+            _ => Err(AppError::InvalidInput(
+                "Cardano private key must be 32 or 64 bytes".to_string(),
+            )),
+        }
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        match self {
+            Self::Extended(esk) => esk.into(),
+            Self::Legacy(signing_key) => signing_key.verifying_key(),
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        match self {
+            Self::Extended(esk) => {
+                ed25519_dalek::hazmat::raw_sign::<Sha512>(esk, message, &self.verifying_key())
+                    .to_bytes()
+            }
+            Self::Legacy(signing_key) => {
+                use ed25519_dalek::Signer;
+                signing_key.sign(message).to_bytes()
+            }
+        }
+    }
+}
+
+fn key_hash(pub_key_bytes: &[u8]) -> [u8; 28] {
+    let mut hasher = Blake2b224::new();
+    hasher.update(pub_key_bytes);
+    hasher.finalize().into()
+}
+
+fn encode_address(payload: &[u8], testnet: bool) -> String {
     let hrp = if testnet {
         bech32::Hrp::parse("addr_test").expect("valid hrp")
     } else {
         bech32::Hrp::parse("addr").expect("valid hrp")
     };
 
-    bech32::encode::<bech32::Bech32>(hrp, &payload)
+    bech32::encode::<bech32::Bech32>(hrp, payload)
         .expect("valid bech32 encoding")
 }
 
+/// Build a Shelley base address (type 0x00/0x01): payment key hash || stake key hash.
+fn base_address(payment_pub: &[u8], stake_pub: &[u8], testnet: bool) -> String {
+    let header = if testnet { 0x00u8 } else { 0x01u8 };
+
+    let mut payload = Vec::with_capacity(57);
+    payload.push(header);
+    payload.extend_from_slice(&key_hash(payment_pub));
+    payload.extend_from_slice(&key_hash(stake_pub));
+    encode_address(&payload, testnet)
+}
+
+/// Build a Cardano enterprise address (type 0x60/0x61) from an Ed25519 public key.
+/// Used for legacy 32-byte keys, which have no staking key to pair with.
+fn enterprise_address(pub_key_bytes: &[u8], testnet: bool) -> String {
+    let header = if testnet { 0x60u8 } else { 0x61u8 };
+
+    let mut payload = Vec::with_capacity(29);
+    payload.push(header);
+    payload.extend_from_slice(&key_hash(pub_key_bytes));
+    encode_address(&payload, testnet)
+}
+
+/// Raw bytes of a bech32 Shelley address, as they appear in transaction outputs
+pub fn decode_address(address: &str) -> Result<Vec<u8>> {
+    let (hrp, data) = bech32::decode(address)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid Cardano address: {}", e)))?;
+
+    if !hrp.as_str().starts_with("addr") {
+        return Err(AppError::InvalidInput(format!("Invalid Cardano address: {}", address)));
+    }
+    Ok(data)
+}
+
 pub fn generate_wallet(testnet: bool, derivation_index: u32) -> Result<WalletInfo> {
     let mnemonic = Mnemonic::generate(24)
         .map_err(|e| AppError::Internal(format!("Failed to generate mnemonic: {}", e)))?;
@@ -70,49 +221,58 @@ pub fn restore_from_mnemonic(phrase: &str, testnet: bool, derivation_index: u32)
     let mnemonic = Mnemonic::parse(phrase)
         .map_err(|e| AppError::InvalidInput(format!("Invalid mnemonic: {}", e)))?;
 
-    let seed = mnemonic.to_seed("");
+    // m/1852'/1815'/0'
+    let account = ExtendedKey::from_entropy(&mnemonic.to_entropy())
+        .derive_path(&[PURPOSE | HARDENED, COIN_TYPE | HARDENED, HARDENED]);
+    let payment = account.derive_path(&[EXTERNAL_CHAIN, derivation_index]);
+    let stake = account.derive_path(&[STAKING_CHAIN, 0]);
 
-    let (signing_key, verifying_key) = derive_cardano_keypair(&seed, derivation_index);
-
-    let address = pub_key_to_address(verifying_key.as_bytes(), testnet);
-    let private_key_hex = hex::encode(signing_key.to_bytes());
+    let address = base_address(&payment.public_key(), &stake.public_key(), testnet);
 
     Ok(WalletInfo {
         address,
-        private_key: private_key_hex,
+        private_key: hex::encode(payment.key),
         mnemonic: Some(phrase.to_string()),
     })
 }
 
 pub fn restore_from_private_key(hex_key: &str, testnet: bool) -> Result<WalletInfo> {
-    let key_bytes = hex::decode(hex_key.trim_start_matches("0x"))
-        .map_err(|e| AppError::InvalidInput(format!("Invalid hex private key: {}", e)))?;
-
-    if key_bytes.len() != 32 {
-        return Err(AppError::InvalidInput(
-            "Cardano Ed25519 private key must be 32 bytes".to_string(),
-        ));
-    }
-
-    let mut key_array = [0u8; 32];
-    key_array.copy_from_slice(&key_bytes);
-    let signing_key = SigningKey::from_bytes(&key_array);
-    let verifying_key = signing_key.verifying_key();
-
-    let address = pub_key_to_address(verifying_key.as_bytes(), testnet);
+    let key = PaymentKey::from_hex(hex_key)?;
+    let address = enterprise_address(key.verifying_key().as_bytes(), testnet);
 
     Ok(WalletInfo {
         address,
-        private_key: hex::encode(signing_key.to_bytes()),
+        private_key: hex_key.trim_start_matches("0x").to_lowercase(),
         mnemonic: None,
     })
 }
 
 pub fn detect_and_restore(secret: &str, testnet: bool, derivation_index: u32) -> Result<WalletInfo> {
     let word_count = secret.split_whitespace().count();
-    if word_count == 12 || word_count == 24 {
+    if word_count == 12 || word_count == 15 || word_count == 24 {
         restore_from_mnemonic(secret, testnet, derivation_index)
     } else {
         restore_from_private_key(secret, testnet)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_cip1852_base_address() {
+        // Well-known CIP-1852 test mnemonic (account 0, address 0, stake key 0)
+        let phrase = "test walk nut penalty hip pave soap entry language right filter choice";
+        let wallet = restore_from_mnemonic(phrase, false, 0).unwrap();
+
+        assert_eq!(
+            wallet.address,
+            "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3jcu5d8ps7zex2k2xt3uqxgjqnnj83ws8lhrn648jjxtwqfjkjv7"
+        );
+
+        let key = PaymentKey::from_hex(&wallet.private_key).unwrap();
+        let signature = ed25519_dalek::Signature::from_bytes(&key.sign(b"body hash"));
+        assert!(key.verifying_key().verify_strict(b"body hash", &signature).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+use ethers::{
+    abi::{ Abi, Detokenize, Tokenize },
+    prelude::*,
+    providers::{ Http, Provider },
+    types::U256,
+};
+use std::sync::Arc;
+
+use crate::error::{ AppError, Result };
+
+pub const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+/// Aave V3 Pool and the RewardsController paying incentives on its aTokens
+#[derive(Debug, Clone, Copy)]
+pub struct AaveMarket {
+    pub pool: &'static str,
+    pub rewards_controller: &'static str,
+}
+
+/// Compound III USDC market (Comet) and the CometRewards contract paying COMP on it
+#[derive(Debug, Clone, Copy)]
+pub struct CometMarket {
+    pub comet: &'static str,
+    pub rewards: &'static str,
+}
+
+/// Aave V3 deployment on a mainnet chain
+pub fn aave_market(chain_id: u64) -> Option<AaveMarket> {
+    let (pool, rewards_controller) = match chain_id {
+        1 => ("0x87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2", "0x8164Cc65827dcFe994AB23944CBC90e0aa80bFcb"),
+        // Polygon, Avalanche, Arbitrum and Optimism share deployment addresses
+        10 | 137 | 42161 | 43114 =>
+            ("0x794a61358D6845594F94dc1DB02A252b5b4814aD", "0x929EC64c34a17401F460460D4B9390518E5B473e"),
+        8453 => ("0xA238Dd80C259a72e81d7e4664a9801593F98d1c5", "0xf9cc4F0D883F1a1eb2c253bdb46c254Ca51E1F44"),
+        _ => {
+            return None;
+        }
+    };
+    Some(AaveMarket { pool, rewards_controller })
+}
+
+/// Compound III USDC market on a mainnet chain
+pub fn compound_market(chain_id: u64) -> Option<CometMarket> {
+    let (comet, rewards) = match chain_id {
+        1 => ("0xc3d688B66703497DAA19211EEdff47f25384cdc3", "0x1B0e765F6224C21223AeA2af16c1C46E38885a40"),
+        137 => ("0xF25212E676D1F7F89Cd72fFEe66158f541246445", "0x45939657d1CA34A8FA39A924B71D28Fe8431e581"),
+        42161 => ("0x9c4ec768c28520B50860ea7a15bd7213a9fF58bf", "0x88730d254A2f7e6AC8388c3198aFd694bA9f7fae"),
+        8453 => ("0xb125E6687d4313864e53df431d5425969c15Eb2F", "0x123964802e6ABabBE1Bc9547D72Ef1B69B00A6b1"),
+        _ => {
+            return None;
+        }
+    };
+    Some(CometMarket { comet, rewards })
+}
+
+/// APY in percent for a rate accruing every second; continuous compounding is indistinguishable
+pub fn per_second_apy(rate_per_second: f64) -> f64 {
+    (rate_per_second * SECONDS_PER_YEAR).exp_m1() * 100.0
+}
+
+pub fn comet_abi() -> Result<Abi> {
+    parse_abi(
+        &[
+            "function baseToken() view returns (address)",
+            "function balanceOf(address) view returns (uint256)",
+            "function getUtilization() view returns (uint256)",
+            "function getSupplyRate(uint256) view returns (uint64)",
+            "function supply(address asset, uint256 amount)",
+            "function getAssetInfoByAddress(address asset) view returns ((uint8, address, address, uint64, uint64, uint64, uint64, uint128))",
+        ]
+    )
+}
+
+pub fn comet_rewards_abi() -> Result<Abi> {
+    parse_abi(
+        &[
+            "function getRewardOwed(address comet, address account) returns ((address token, uint256 owed))",
+            "function claim(address comet, address src, bool shouldAccrue)",
+        ]
+    )
+}
+
+pub fn aave_rewards_abi() -> Result<Abi> {
+    parse_abi(
+        &[
+            "function getAllUserRewards(address[] assets, address user) view returns (address[] rewardsList, uint256[] unclaimedAmounts)",
+            "function claimAllRewardsToSelf(address[] assets) returns (address[] rewardsList, uint256[] claimedAmounts)",
+        ]
+    )
+}
+
+pub fn aave_pool_abi() -> Result<Abi> {
+    parse_abi(
+        &[
+            "function supply(address asset, uint256 amount, address onBehalfOf, uint16 referralCode)",
+            "function getReservesList() view returns (address[])",
+        ]
+    )
+}
+
+fn parse_abi(signatures: &[&str]) -> Result<Abi> {
+    ethers::abi::parse_abi(signatures).map_err(|e| AppError::Chain(format!("Failed to parse ABI: {}", e)))
+}
+
+/// Read-only call, also used for non-view functions such as `getRewardOwed` that only accrue state
+pub async fn read<M: Middleware + 'static, T: Tokenize, D: Detokenize>(
+    contract: &Contract<M>,
+    name: &str,
+    args: T
+) -> Result<D> {
+    contract
+        .method::<_, D>(name, args)
+        .map_err(|e| AppError::Chain(format!("Failed to call {}: {}", name, e)))?
+        .call().await
+        .map_err(|e| AppError::Chain(format!("{} call failed: {}", name, e)))
+}
+
+/// Base token, supplied balance with interest, and supply APY of `owner` in a Comet
+#[derive(Debug, Clone)]
+pub struct CometSupply {
+    pub base_token: Address,
+    pub balance: U256,
+    pub supply_apy: f64,
+}
+
+pub async fn comet_supply(
+    provider: Arc<Provider<Http>>,
+    market: CometMarket,
+    owner: Address
+) -> Result<CometSupply> {
+    let comet_addr: Address = market.comet.parse().map_err(|_| AppError::InvalidAddress)?;
+    let comet = Contract::new(comet_addr, comet_abi()?, provider);
+
+    let base_token: Address = read(&comet, "baseToken", ()).await?;
+    let balance: U256 = read(&comet, "balanceOf", owner).await?;
+    let utilization: U256 = read(&comet, "getUtilization", ()).await?;
+    // Per-second rate scaled by 1e18
+    let rate: u64 = read(&comet, "getSupplyRate", utilization).await?;
+
+    Ok(CometSupply {
+        base_token,
+        balance,
+        supply_apy: per_second_apy((rate as f64) / 1e18),
+    })
+}
+
+/// COMP (or the chain's reward token) owed to `owner` on a Comet
+pub async fn comet_reward_owed(
+    provider: Arc<Provider<Http>>,
+    market: CometMarket,
+    owner: Address
+) -> Result<(Address, U256)> {
+    let comet_addr: Address = market.comet.parse().map_err(|_| AppError::InvalidAddress)?;
+    let rewards_addr: Address = market.rewards.parse().map_err(|_| AppError::InvalidAddress)?;
+    let rewards = Contract::new(rewards_addr, comet_rewards_abi()?, provider);
+
+    read(&rewards, "getRewardOwed", (comet_addr, owner)).await
+}
+
+/// Unclaimed Aave incentives on `assets` (aTokens), one entry per reward token
+pub async fn aave_unclaimed_rewards(
+    provider: Arc<Provider<Http>>,
+    market: AaveMarket,
+    owner: Address,
+    assets: Vec<Address>
+) -> Result<Vec<(Address, U256)>> {
+    let controller_addr: Address = market.rewards_controller.parse().map_err(|_| AppError::InvalidAddress)?;
+    let controller = Contract::new(controller_addr, aave_rewards_abi()?, provider);
+
+    let (tokens, amounts): (Vec<Address>, Vec<U256>) = read(&controller, "getAllUserRewards", (assets, owner)).await?;
+    Ok(tokens.into_iter().zip(amounts).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_second_apy_compounds_apr() {
+        // 5% APR accrued every second compounds to just over 5.127% APY
+        let apy = per_second_apy(0.05 / SECONDS_PER_YEAR);
+        assert!((apy - 5.127).abs() < 0.001);
+        assert_eq!(per_second_apy(0.0), 0.0);
+    }
+}
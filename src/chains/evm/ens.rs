@@ -0,0 +1,56 @@
+use ethers::prelude::*;
+
+use crate::error::{ AppError, Result };
+
+/// Resolves ENS names through the ENS Registry on Ethereum mainnet/testnet.
+pub struct EnsResolver {
+    provider: Provider<Http>,
+}
+
+impl EnsResolver {
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        let provider = Provider::<Http>
+            ::try_from(rpc_url)
+            .map_err(|e| AppError::Rpc(format!("Failed to create provider: {}", e)))?;
+
+        Ok(Self { provider })
+    }
+
+    /// Resolve an ENS name (e.g. `alice.eth`) to a checksummed address
+    pub async fn resolve(&self, name: &str) -> Result<String> {
+        let address = self.provider
+            .resolve_name(name).await
+            .map_err(|_| AppError::EnsNotFound(name.to_string()))?;
+
+        if address == Address::zero() {
+            return Err(AppError::EnsNotFound(name.to_string()));
+        }
+
+        Ok(ethers::utils::to_checksum(&address, None))
+    }
+
+    /// Reverse-resolve an address to its primary ENS name, if one is set
+    pub async fn lookup_address(&self, address: &str) -> Option<String> {
+        let addr: Address = address.parse().ok()?;
+        self.provider.lookup_address(addr).await.ok()
+    }
+}
+
+/// Whether the input looks like an ENS name rather than a hex address
+pub fn is_ens_name(input: &str) -> bool {
+    let lower = input.trim().to_lowercase();
+    lower.len() > 4 && lower.ends_with(".eth") && !lower.contains(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ens_name() {
+        assert!(is_ens_name("alice.eth"));
+        assert!(is_ens_name("Pay.Alice.ETH"));
+        assert!(!is_ens_name(".eth"));
+        assert!(!is_ens_name("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"));
+    }
+}
@@ -0,0 +1,145 @@
+use ethers::{ prelude::*, providers::{ Http, Provider }, types::U256 };
+use std::sync::Arc;
+
+use crate::error::{ AppError, Result };
+
+/// OP Stack GasPriceOracle predeploy
+pub const GAS_PRICE_ORACLE: &str = "0x420000000000000000000000000000000000000F";
+
+/// Gas charged for the signature bytes missing from an unsigned transaction
+const SIGNATURE_GAS: u64 = 68 * 16;
+
+/// Whether a chain posts its transaction data to L1 through the GasPriceOracle (Optimism, Base)
+pub fn is_op_stack(chain_id: u64) -> bool {
+    matches!(chain_id, 10 | 11155420 | 8453 | 84532)
+}
+
+/// Whether a chain charges a separate L1 component reported in receipts (Arbitrum One, Arbitrum Sepolia)
+pub fn is_arbitrum(chain_id: u64) -> bool {
+    matches!(chain_id, 42161 | 421614)
+}
+
+/// L1 gas used to post a serialized unsigned transaction: 16 per non-zero byte, 4 per zero byte
+pub fn tx_data_gas(tx_rlp: &[u8]) -> u64 {
+    let data_gas: u64 = tx_rlp
+        .iter()
+        .map(|b| if *b == 0 { 4 } else { 16 })
+        .sum();
+    data_gas + SIGNATURE_GAS
+}
+
+/// Pre-Ecotone fee formula: `(tx_data_gas + overhead) * l1_base_fee * scalar / 1e6`
+pub fn legacy_l1_fee(tx_data_gas: u64, overhead: U256, l1_base_fee: U256, scalar: U256) -> U256 {
+    (U256::from(tx_data_gas) + overhead) * l1_base_fee * scalar / U256::from(1_000_000u64)
+}
+
+/// L1 data fee in wei for posting `tx_rlp` from an OP Stack chain
+pub async fn estimate_l1_data_fee(provider: Arc<Provider<Http>>, tx_rlp: &[u8]) -> Result<U256> {
+    let abi = ethers::abi
+        ::parse_abi(
+            &[
+                "function l1BaseFee() external view returns (uint256)",
+                "function overhead() external view returns (uint256)",
+                "function scalar() external view returns (uint256)",
+                "function getL1Fee(bytes) external view returns (uint256)",
+            ]
+        )
+        .map_err(|e| AppError::Chain(format!("Failed to parse ABI: {}", e)))?;
+    let oracle_addr: Address = GAS_PRICE_ORACLE.parse().map_err(|_| AppError::InvalidAddress)?;
+    let oracle = Contract::new(oracle_addr, abi, provider);
+
+    let read = |name: &'static str| {
+        let oracle = oracle.clone();
+        async move {
+            oracle
+                .method::<_, U256>(name, ())
+                .map_err(|e| AppError::Chain(format!("Failed to call {}: {}", name, e)))?
+                .call().await
+                .map_err(|e| AppError::Chain(format!("{} call failed: {}", name, e)))
+        }
+    };
+
+    match tokio::try_join!(read("l1BaseFee"), read("overhead"), read("scalar")) {
+        Ok((l1_base_fee, overhead, scalar)) => {
+            Ok(legacy_l1_fee(tx_data_gas(tx_rlp), overhead, l1_base_fee, scalar))
+        }
+        Err(e) => {
+            // overhead() and scalar() revert since the Ecotone upgrade; let the oracle price it
+            tracing::debug!("Legacy L1 fee parameters unavailable, using getL1Fee: {}", e);
+            oracle
+                .method::<_, U256>("getL1Fee", Bytes::from(tx_rlp.to_vec()))
+                .map_err(|e| AppError::Chain(format!("Failed to call getL1Fee: {}", e)))?
+                .call().await
+                .map_err(|e| AppError::Chain(format!("getL1Fee call failed: {}", e)))
+        }
+    }
+}
+
+/// What a mined Arbitrum transaction paid for its L1 submission, in wei
+#[derive(Debug, Clone)]
+pub struct ArbitrumL1Fee {
+    pub payer: Address,
+    pub l1_fee_paid: U256,
+    pub l1_refund: U256,
+}
+
+/// Wei reserved for gas at submission but not charged: `(gas_limit - gas_used) * effective_gas_price`
+pub fn l1_refund(gas_limit: U256, gas_used: U256, effective_gas_price: U256) -> U256 {
+    gas_limit.saturating_sub(gas_used) * effective_gas_price
+}
+
+/// Read `gasUsedForL1` from an Arbitrum receipt and work out how much of the gas reserved by
+/// the transaction's limit came back unused. `None` until the receipt exists.
+pub async fn arbitrum_l1_fee(
+    provider: Arc<Provider<Http>>,
+    tx_hash: H256
+) -> Result<Option<ArbitrumL1Fee>> {
+    let Some(receipt) = provider
+        .get_transaction_receipt(tx_hash).await
+        .map_err(|e| AppError::Rpc(format!("Failed to get receipt: {}", e)))? else {
+        return Ok(None);
+    };
+    let tx = provider
+        .get_transaction(tx_hash).await
+        .map_err(|e| AppError::Rpc(format!("Failed to get transaction: {}", e)))?
+        .ok_or_else(|| AppError::NotFound(format!("Transaction {:?} not found", tx_hash)))?;
+
+    let used_l1_gas: U256 = receipt.other
+        .get("gasUsedForL1")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    let gas_price = receipt.effective_gas_price.unwrap_or_default();
+    let gas_used = receipt.gas_used.unwrap_or(tx.gas);
+
+    Ok(
+        Some(ArbitrumL1Fee {
+            payer: receipt.from,
+            l1_fee_paid: used_l1_gas * gas_price,
+            l1_refund: l1_refund(tx.gas, gas_used, gas_price),
+        })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_l1_fee() {
+        let tx = [0u8, 1, 2, 0];
+        assert_eq!(tx_data_gas(&tx), 4 + 16 + 16 + 4 + SIGNATURE_GAS);
+
+        // (1000 + 188) gas * 20 gwei * 0.684 scalar
+        let fee = legacy_l1_fee(1000, U256::from(188), U256::from(20_000_000_000u64), U256::from(684_000));
+        assert_eq!(fee, U256::from(16_251_840_000_000u64));
+    }
+
+    #[test]
+    fn test_l1_refund() {
+        let gwei = U256::from(100_000_000u64);
+        // 21,200 reserved, 21,000 used
+        assert_eq!(l1_refund(U256::from(21_200), U256::from(21_000), gwei), U256::from(20_000_000_000u64));
+        // Used more than reserved can't happen on chain, but mustn't underflow
+        assert_eq!(l1_refund(U256::from(20_000), U256::from(21_000), gwei), U256::zero());
+    }
+}
@@ -1,5 +1,12 @@
+pub mod defi;
+pub mod ens;
+pub mod l1_fee;
+pub mod nonce;
 pub mod provider;
+pub mod revert;
 pub mod tokens;
 pub mod wallet;
 
+pub use ens::EnsResolver;
+pub use nonce::NonceManager;
 pub use provider::EvmProvider;
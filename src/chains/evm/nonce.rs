@@ -0,0 +1,68 @@
+use dashmap::DashMap;
+use ethers::{ prelude::*, providers::{ Http, Provider } };
+use std::sync::atomic::{ AtomicU64, Ordering };
+
+use crate::error::{ AppError, Result };
+
+/// Tracks the next nonce per sender so rapid consecutive sends don't reuse the same value.
+///
+/// One instance is shared by every EVM provider, so entries are keyed by chain ID as well
+/// as address — the same key pair is valid on all EVM chains with independent nonces.
+#[derive(Default)]
+pub struct NonceManager {
+    nonces: DashMap<(u64, Address), AtomicU64>,
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve the next nonce for an address, fetching the pending nonce from chain on first use
+    pub async fn next_nonce(
+        &self,
+        provider: &Provider<Http>,
+        chain_id: u64,
+        address: Address
+    ) -> Result<U256> {
+        let key = (chain_id, address);
+
+        if !self.nonces.contains_key(&key) {
+            let on_chain = Self::fetch_pending_nonce(provider, address).await?;
+            // Another task may have initialised the entry while we were fetching
+            self.nonces.entry(key).or_insert_with(|| AtomicU64::new(on_chain));
+        }
+
+        let nonce = self.nonces
+            .get(&key)
+            .map(|n| n.fetch_add(1, Ordering::SeqCst))
+            .ok_or_else(|| AppError::Internal("Nonce entry missing".to_string()))?;
+
+        Ok(U256::from(nonce))
+    }
+
+    /// Re-sync the local counter with the chain's pending nonce
+    pub async fn resync(
+        &self,
+        provider: &Provider<Http>,
+        chain_id: u64,
+        address: Address
+    ) -> Result<()> {
+        let on_chain = Self::fetch_pending_nonce(provider, address).await?;
+        self.nonces.insert((chain_id, address), AtomicU64::new(on_chain));
+        tracing::info!("Re-synced nonce for {:?} on chain {} to {}", address, chain_id, on_chain);
+        Ok(())
+    }
+
+    /// Drop all cached nonces for an address; the next send re-fetches from chain
+    pub fn reset_nonce(&self, address: Address) {
+        self.nonces.retain(|(_, addr), _| *addr != address);
+    }
+
+    async fn fetch_pending_nonce(provider: &Provider<Http>, address: Address) -> Result<u64> {
+        let nonce = provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into())).await
+            .map_err(|e| AppError::Rpc(format!("Failed to get nonce: {}", e)))?;
+        Ok(nonce.as_u64())
+    }
+}
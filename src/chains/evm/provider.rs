@@ -1,32 +1,64 @@
-use crate::enums::TxStatus;
+use crate::enums::{ DefiProtocol, TxStatus };
 use async_trait::async_trait;
 use ethers::{
+    abi::{ Detokenize, ParamType, Token },
     prelude::*,
-    providers::{ Http, Provider },
-    types::{ TransactionRequest as EthTxRequest, U256 },
+    providers::{ Http, Provider, RpcError },
+    types::{ transaction::eip2718::TypedTransaction, TransactionRequest as EthTxRequest, U256 },
     utils::parse_units,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::chains::evm::{ tokens, wallet };
+use crate::chains::evm::{ defi, l1_fee, revert, tokens, wallet, NonceManager };
 use crate::error::{ AppError, Result };
+use crate::services::TokenListService;
 use crate::providers::{
     Balance,
+    BlockInfo,
     ChainProvider,
+    L1FeeBreakdown,
+    LendingReward,
+    LendingSupply,
+    NetworkLoad,
+    SimulationResult,
+    StateChange,
+    TokenAllowance,
+    TokenInspection,
+    TransactionDetail,
     TransactionRequest,
     TransactionResponse,
     WalletInfo,
 };
 
+/// Blocks averaged over when measuring block time
+const BLOCK_TIME_SAMPLE_BLOCKS: u64 = 10;
+
+/// Blocks per `eth_getLogs` page when scanning a wallet's whole history; pages the node
+/// refuses are split further
+const LOG_PAGE_BLOCKS: u64 = 1_000_000;
+
+/// Multicall3 is deployed at the same address on nearly every EVM chain
+const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+type SignerClient = SignerMiddleware<Arc<Provider<Http>>, LocalWallet>;
+
 #[derive(Clone)]
 pub struct EvmProvider {
     provider: Arc<Provider<Http>>,
     chain_id: u64,
     native_symbol: String,
+    pub nonce_manager: Arc<NonceManager>,
+    token_list: Option<Arc<TokenListService>>,
 }
 
 impl EvmProvider {
-    pub fn new(rpc_url: &str, chain_id: u64, native_symbol: &str) -> Result<Self> {
+    pub fn new(
+        rpc_url: &str,
+        chain_id: u64,
+        native_symbol: &str,
+        nonce_manager: Arc<NonceManager>
+    ) -> Result<Self> {
         let provider = Provider::<Http>
             ::try_from(rpc_url)
             .map_err(|e| AppError::Rpc(format!("Failed to create provider: {}", e)))?;
@@ -35,13 +67,30 @@ impl EvmProvider {
             provider: Arc::new(provider),
             chain_id,
             native_symbol: native_symbol.to_string(),
+            nonce_manager,
+            token_list: None,
         })
     }
 
+    /// Resolve ERC-20 symbols and decimals from the Uniswap token list before asking the contract
+    pub fn with_token_list(mut self, token_list: Arc<TokenListService>) -> Self {
+        self.token_list = Some(token_list);
+        self
+    }
+
+    /// Forget the locally tracked nonce for an address so it is re-fetched on next send
+    pub fn reset_nonce(&self, address: &str) -> Result<()> {
+        let addr: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+        self.nonce_manager.reset_nonce(addr);
+        Ok(())
+    }
+
+    /// ERC-20 balance at `block`, or at the latest block when `None`
     async fn get_erc20_balance(
         &self,
         wallet_address: &str,
-        token_address: &str
+        token_address: &str,
+        block: Option<BlockId>
     ) -> Result<Balance> {
         let wallet_addr: Address = wallet_address.parse().map_err(|_| AppError::InvalidAddress)?;
         let token_addr: Address = token_address.parse().map_err(|_| AppError::InvalidAddress)?;
@@ -59,9 +108,13 @@ impl EvmProvider {
 
         let contract = Contract::new(token_addr, abi.clone(), self.provider.clone());
 
-        let balance: U256 = contract
+        let mut balance_call = contract
             .method::<_, U256>("balanceOf", wallet_addr)
-            .map_err(|e| AppError::Chain(format!("Failed to call balanceOf: {}", e)))?
+            .map_err(|e| AppError::Chain(format!("Failed to call balanceOf: {}", e)))?;
+        if let Some(block) = block {
+            balance_call = balance_call.block(block);
+        }
+        let balance: U256 = balance_call
             .call().await
             .map_err(|e| AppError::Chain(format!("balanceOf call failed: {}", e)))?;
 
@@ -70,6 +123,8 @@ impl EvmProvider {
             let Some(token_info) = tokens::get_token_by_address(token_address)
         {
             (token_info.decimals, token_info.symbol.clone())
+        } else if let Some(entry) = self.listed_token(token_address).await {
+            (entry.decimals, entry.symbol)
         } else {
             // Try to fetch from contract
             let decimals = match contract.method::<_, u8>("decimals", ()) {
@@ -97,15 +152,146 @@ impl EvmProvider {
             balance: balance_str,
             symbol,
             decimals,
+            usd_value: None,
+        })
+    }
+
+    /// Gas, fees and total native cost of calling `to` with `data` and `value` from `from`,
+    /// including the L1 data fee on OP Stack chains
+    async fn estimate_call_cost(
+        &self,
+        from_addr: Address,
+        call_to: Address,
+        data: Vec<u8>,
+        value: U256
+    ) -> Result<crate::providers::GasEstimate> {
+        // Get current gas price and EIP-1559 fees
+        let gas_price = self.provider
+            .get_gas_price().await
+            .map_err(AppError::from)?;
+
+        let (max_fee, max_priority_fee) = match self.provider.estimate_eip1559_fees(None).await {
+            Ok((max_fee, max_priority_fee)) => (max_fee, max_priority_fee),
+            Err(_) => {
+                // Fallback to legacy gas price if EIP-1559 not supported
+                (gas_price, U256::from(0))
+            }
+        };
+
+        let mut fee_tx = call_request(from_addr, call_to, data, value);
+        let estimated_gas = self.provider
+            .estimate_gas(&fee_tx, None).await
+            .map_err(AppError::from)?;
+
+        let l1_data_fee = if l1_fee::is_op_stack(self.chain_id) {
+            fee_tx.set_gas(estimated_gas);
+            fee_tx.set_gas_price(max_fee);
+            fee_tx.set_chain_id(self.chain_id);
+            match l1_fee::estimate_l1_data_fee(self.provider.clone(), &fee_tx.rlp()).await {
+                Ok(fee) => Some(fee),
+                Err(e) => {
+                    tracing::warn!("Failed to estimate L1 data fee: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Calculate total cost in wei, including the L1 data fee on rollups
+        let total_cost_wei = estimated_gas * max_fee + l1_data_fee.unwrap_or_default();
+        let total_cost_eth = ethers::utils
+            ::format_units(total_cost_wei, 18)
+            .map_err(|_| AppError::Internal("Failed to format units".to_string()))?;
+        let l1_data_fee_native = l1_data_fee
+            .map(|fee| ethers::utils::format_units(fee, 18))
+            .transpose()
+            .map_err(|_| AppError::Internal("Failed to format units".to_string()))?;
+
+        Ok(crate::providers::GasEstimate {
+            estimated_gas: estimated_gas.as_u64(),
+            gas_limit: crate::providers::gas_limit_with_buffer(estimated_gas.as_u64()),
+            gas_price: Some(
+                format!("{}", ethers::utils::format_units(gas_price, "gwei").unwrap_or_default())
+            ),
+            max_fee_per_gas: Some(
+                format!("{}", ethers::utils::format_units(max_fee, "gwei").unwrap_or_default())
+            ),
+            max_priority_fee_per_gas: Some(
+                format!(
+                    "{}",
+                    ethers::utils::format_units(max_priority_fee, "gwei").unwrap_or_default()
+                )
+            ),
+            total_cost_native: total_cost_eth,
+            total_cost_usd: None, // Will be calculated by service layer
+            l1_data_fee_native,
+            l1_data_fee_usd: None,
+            confidence_pct: None,
         })
     }
 
+    async fn listed_token(&self, token_address: &str) -> Option<crate::services::token_list_service::TokenListEntry> {
+        self.token_list.as_ref()?.lookup(self.chain_id, token_address).await
+    }
+
+    /// `decimals()` of every token in a single `eth_call` through Multicall3's `aggregate3`.
+    /// Entries are `None` for tokens whose call reverted or returned something other than a uint8.
+    async fn multicall_decimals(&self, tokens: &[Address]) -> Result<Vec<Option<u8>>> {
+        let multicall: Address = MULTICALL3_ADDRESS.parse().expect("valid address");
+        let decimals_call = ethers::utils::id("decimals()")[..4].to_vec();
+
+        let calls = tokens
+            .iter()
+            .map(|token| Token::Tuple(vec![Token::Address(*token), Token::Bool(true), Token::Bytes(decimals_call.clone())]))
+            .collect();
+        let mut data = ethers::utils::id("aggregate3((address,bool,bytes)[])")[..4].to_vec();
+        data.extend(ethers::abi::encode(&[Token::Array(calls)]));
+        let tx: TypedTransaction = EthTxRequest::new().to(multicall).data(data).into();
+
+        // Without Multicall3 the call hits an empty account and returns no data, which fails to decode
+        let output = self.provider
+            .call(&tx, None).await
+            .map_err(|e| AppError::Rpc(format!("Multicall3 call failed: {}", e)))?;
+        let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes])));
+        let results = match ethers::abi::decode(&[result_type], &output) {
+            Ok(mut decoded) => match decoded.pop() {
+                Some(Token::Array(results)) => results,
+                _ => return Err(AppError::Chain("Unexpected Multicall3 result".to_string())),
+            },
+            Err(e) => return Err(AppError::Chain(format!("Failed to decode Multicall3 result: {}", e))),
+        };
+        if results.len() != tokens.len() {
+            return Err(AppError::Chain("Multicall3 returned the wrong number of results".to_string()));
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|result| match result {
+                Token::Tuple(fields) => match fields.as_slice() {
+                    [Token::Bool(true), Token::Bytes(data)] => decode_uint8(data),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn call_decimals(&self, token: Address) -> Option<u8> {
+        tokens::get_erc20_contract(token, self.provider.clone())
+            .method::<_, u8>("decimals", ())
+            .ok()?
+            .call().await
+            .ok()
+    }
+
     async fn send_erc20_transaction(
         &self,
         wallet: LocalWallet,
         to: Address,
         amount: U256,
         token_address: Address,
+        nonce: U256,
         max_fee_per_gas: Option<U256>,
         max_priority_fee_per_gas: Option<U256>,
         gas_limit: Option<U256>
@@ -115,16 +301,14 @@ impl EvmProvider {
             ::parse_abi(&["function transfer(address to, uint256 amount) external returns (bool)"])
             .map_err(|e| AppError::Chain(format!("Failed to parse ABI: {}", e)))?;
 
-        let client = SignerMiddleware::new(
-            self.provider.clone(),
-            wallet.with_chain_id(self.chain_id)
-        );
-        let contract = Contract::new(token_address, abi, Arc::new(client));
+        let contract = Contract::new(token_address, abi, self.provider.clone());
 
         let mut call = contract
             .method::<_, bool>("transfer", (to, amount))
             .map_err(|e| AppError::Chain(format!("Failed to prepare transfer: {}", e)))?;
 
+        call.tx.set_nonce(nonce);
+
         // Set gas parameters
         if let Some(limit) = gas_limit {
             call.tx.set_gas(limit);
@@ -132,59 +316,45 @@ impl EvmProvider {
         // Note: max_fee_per_gas and max_priority_fee_per_gas are set differently in ethers 2.0
         // They're automatically handled by the provider
 
-        let pending_tx = call
-            .send().await
-            .map_err(|e| AppError::Chain(format!("Transaction failed: {}", e)))?;
-
-        let tx_hash = format!("{:?}", pending_tx.tx_hash());
-
-        Ok(TransactionResponse {
-            tx_hash,
-            status: TxStatus::Pending.to_string(),
-        })
-    }
-}
-
-#[async_trait]
-impl ChainProvider for EvmProvider {
-    async fn generate_wallet(&self, derivation_index: u32) -> Result<WalletInfo> {
-        wallet::generate_wallet(derivation_index)
-    }
-
-    async fn restore_wallet(&self, secret: &str, derivation_index: u32) -> Result<WalletInfo> {
-        wallet::detect_and_restore(secret, derivation_index)
+        self.sign_and_broadcast(wallet, call.tx).await
     }
 
-    async fn get_balance(&self, address: &str) -> Result<Balance> {
-        let addr: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+    /// Fill in fees, sign and broadcast `tx`. A node that answers "already known" holds this
+    /// exact signed transaction already, so that counts as sent rather than as a nonce clash.
+    async fn sign_and_broadcast(&self, wallet: LocalWallet, mut tx: TypedTransaction) -> Result<TransactionResponse> {
+        let wallet = wallet.with_chain_id(self.chain_id);
+        let client = SignerMiddleware::new(self.provider.clone(), wallet.clone());
+        client.fill_transaction(&mut tx, None).await.map_err(|e| map_send_error(e.to_string()))?;
 
-        let balance = self.provider
-            .get_balance(addr, None).await
-            .map_err(|e| AppError::Rpc(format!("Failed to get balance: {}", e)))?;
+        let signature = wallet
+            .sign_transaction(&tx).await
+            .map_err(|e| AppError::Chain(format!("Failed to sign transaction: {}", e)))?;
+        let raw = tx.rlp_signed(&signature);
+        let signed_hash = H256::from(ethers::utils::keccak256(&raw));
 
-        let balance_str = ethers::utils::format_ether(balance);
+        let tx_hash = match self.provider.send_raw_transaction(raw).await {
+            Ok(pending_tx) => pending_tx.tx_hash(),
+            Err(e) if e.to_string().to_lowercase().contains("already known") => {
+                tracing::info!("Node already has {:?}, treating it as sent", signed_hash);
+                signed_hash
+            }
+            Err(e) => {
+                return Err(map_send_error(e.to_string()));
+            }
+        };
 
-        Ok(Balance {
-            balance: balance_str,
-            symbol: self.native_symbol.clone(),
-            decimals: 18,
+        Ok(TransactionResponse {
+            tx_hash: format!("{:?}", tx_hash),
+            status: TxStatus::Pending.to_string(),
         })
     }
 
-    async fn get_token_balance(&self, address: &str, token_address: &str) -> Result<Balance> {
-        self.get_erc20_balance(address, token_address).await
-    }
-
-    async fn send_transaction(
+    /// Send a transfer using the next nonce reserved from the shared `NonceManager`
+    async fn send_with_managed_nonce(
         &self,
-        private_key: &str,
-        request: TransactionRequest
+        wallet: LocalWallet,
+        request: &TransactionRequest
     ) -> Result<TransactionResponse> {
-        let wallet: LocalWallet = private_key
-            .trim_start_matches("0x")
-            .parse()
-            .map_err(|_| AppError::InvalidPrivateKey)?;
-
         let to: Address = request.to.parse().map_err(|_| AppError::InvalidAddress)?;
 
         // Parse gas parameters
@@ -202,12 +372,14 @@ impl ChainProvider for EvmProvider {
 
         let gas_limit = request.gas_limit.map(U256::from);
 
-        if let Some(token_address) = request.token_address {
+        let nonce = self.nonce_manager.next_nonce(&self.provider, self.chain_id, wallet.address()).await?;
+
+        if let Some(token_address) = &request.token_address {
             // ERC20 token transfer
             let token_addr: Address = token_address.parse().map_err(|_| AppError::InvalidAddress)?;
 
             // Get token decimals
-            let decimals = if let Some(token_info) = tokens::get_token_by_address(&token_address) {
+            let decimals = if let Some(token_info) = tokens::get_token_by_address(token_address) {
                 token_info.decimals
             } else {
                 18 // Default to 18 if unknown
@@ -222,6 +394,7 @@ impl ChainProvider for EvmProvider {
                 to,
                 amount,
                 token_addr,
+                nonce,
                 max_fee_per_gas,
                 max_priority_fee_per_gas,
                 gas_limit
@@ -232,28 +405,432 @@ impl ChainProvider for EvmProvider {
                 .map_err(|e| AppError::InvalidInput(format!("Invalid amount: {}", e)))?
                 .into();
 
-            let client = SignerMiddleware::new(
-                self.provider.clone(),
-                wallet.with_chain_id(self.chain_id)
-            );
-
-            let mut tx = EthTxRequest::new().to(to).value(amount);
+            let mut tx = EthTxRequest::new().to(to).value(amount).nonce(nonce);
 
             // Set gas parameters - Note: EIP-1559 params handled automatically by provider
             if let Some(limit) = gas_limit {
                 tx = tx.gas(limit);
             }
 
-            let pending_tx = client
-                .send_transaction(tx, None).await
-                .map_err(|e| AppError::Chain(format!("Transaction failed: {}", e)))?;
+            self.sign_and_broadcast(wallet, tx.into()).await
+        }
+    }
 
-            let tx_hash = format!("{:?}", pending_tx.tx_hash());
+    /// Replace a still-pending transaction by re-signing with its nonce and a bumped fee.
+    ///
+    /// `replacement` overrides the (to, value, data) of the original; `None` re-sends it as-is.
+    async fn replace_pending_transaction(
+        &self,
+        wallet: LocalWallet,
+        tx_hash: &str,
+        replacement: Option<(Address, U256, Bytes)>,
+        max_fee_override: Option<U256>
+    ) -> Result<TransactionResponse> {
+        let hash: H256 = tx_hash
+            .parse()
+            .map_err(|_| AppError::InvalidInput("Invalid transaction hash".to_string()))?;
 
-            Ok(TransactionResponse {
-                tx_hash,
-                status: TxStatus::Pending.to_string(),
-            })
+        let original = self.provider
+            .get_transaction(hash).await
+            .map_err(|e| AppError::Rpc(format!("Failed to get transaction: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("Transaction not found on chain".to_string()))?;
+
+        if original.block_number.is_some() {
+            return Err(AppError::Validation("Transaction is already confirmed".to_string()));
+        }
+        if original.from != wallet.address() {
+            return Err(AppError::Validation("Transaction was not sent from this wallet".to_string()));
+        }
+
+        let (to, value, data, gas) = match replacement {
+            Some((to, value, data)) => (to, value, data, U256::from(21_000)),
+            None => {
+                let to = original.to.ok_or_else(|| {
+                    AppError::Validation("Contract deployments cannot be replaced".to_string())
+                })?;
+                (to, original.value, original.input.clone(), original.gas)
+            }
+        };
+
+        let client = SignerMiddleware::new(
+            self.provider.clone(),
+            wallet.with_chain_id(self.chain_id)
+        );
+
+        let tx: TypedTransaction = if let Some(orig_max_fee) = original.max_fee_per_gas {
+            // EIP-1559: both fee caps must rise by at least 10% for nodes to accept the replacement
+            let min_max_fee = bump_fee(orig_max_fee);
+            let max_fee = max_fee_override.unwrap_or(min_max_fee);
+            if max_fee < min_max_fee {
+                return Err(AppError::Validation(format!(
+                    "Max fee must be at least {} gwei",
+                    ethers::utils::format_units(min_max_fee, "gwei").unwrap_or_default()
+                )));
+            }
+            let priority_fee = bump_fee(original.max_priority_fee_per_gas.unwrap_or_default()).min(max_fee);
+
+            Eip1559TransactionRequest::new()
+                .from(original.from)
+                .to(to)
+                .value(value)
+                .data(data)
+                .gas(gas)
+                .nonce(original.nonce)
+                .max_fee_per_gas(max_fee)
+                .max_priority_fee_per_gas(priority_fee)
+                .into()
+        } else {
+            let min_gas_price = bump_fee(original.gas_price.unwrap_or_default());
+            let gas_price = max_fee_override.unwrap_or(min_gas_price);
+            if gas_price < min_gas_price {
+                return Err(AppError::Validation(format!(
+                    "Gas price must be at least {} gwei",
+                    ethers::utils::format_units(min_gas_price, "gwei").unwrap_or_default()
+                )));
+            }
+
+            EthTxRequest::new()
+                .from(original.from)
+                .to(to)
+                .value(value)
+                .data(data)
+                .gas(gas)
+                .nonce(original.nonce)
+                .gas_price(gas_price)
+                .into()
+        };
+
+        let pending_tx = client
+            .send_transaction(tx, None).await
+            .map_err(|e| AppError::Chain(format!("Replacement transaction failed: {}", e)))?;
+
+        Ok(TransactionResponse {
+            tx_hash: format!("{:?}", pending_tx.tx_hash()),
+            status: TxStatus::Pending.to_string(),
+        })
+    }
+
+    /// Send a contract call with the next managed nonce and wait until it is mined,
+    /// so the next call in a sequence sees its effects
+    async fn send_and_confirm<D: Detokenize>(
+        &self,
+        mut call: ContractCall<SignerClient, D>,
+        from: Address
+    ) -> Result<H256> {
+        let nonce = self.nonce_manager.next_nonce(&self.provider, self.chain_id, from).await?;
+        call.tx.set_nonce(nonce);
+
+        let result = async {
+            let pending_tx = call.send().await.map_err(|e| map_send_error(e.to_string()))?;
+            let tx_hash = pending_tx.tx_hash();
+            let receipt = pending_tx.await
+                .map_err(|e| AppError::Chain(format!("Failed waiting for {:?}: {}", tx_hash, e)))?
+                .ok_or_else(|| AppError::Chain(format!("Transaction {:?} was dropped", tx_hash)))?;
+            if receipt.status != Some(U64::from(1)) {
+                return Err(AppError::Chain(format!("Transaction {:?} reverted", tx_hash)));
+            }
+            Ok(tx_hash)
+        }.await;
+
+        // Whether the reserved nonce was used depends on how far the send got; ask the node
+        if result.is_err() {
+            if let Err(sync_err) = self.nonce_manager.resync(&self.provider, self.chain_id, from).await {
+                tracing::warn!("Failed to re-sync nonce for {:?}: {}", from, sync_err);
+            }
+        }
+        result
+    }
+
+    /// Whether `protocol` accepts `token` as a supply at `supply_target`: an Aave reserve, or
+    /// the base asset or a listed collateral of a Comet
+    async fn accepts_supply(&self, protocol: DefiProtocol, supply_target: Address, token: Address) -> Result<bool> {
+        match protocol {
+            DefiProtocol::Aave => {
+                let pool = Contract::new(supply_target, defi::aave_pool_abi()?, self.provider.clone());
+                let reserves: Vec<Address> = defi::read(&pool, "getReservesList", ()).await?;
+                Ok(reserves.contains(&token))
+            }
+            DefiProtocol::Compound => {
+                let comet = Contract::new(supply_target, defi::comet_abi()?, self.provider.clone());
+                let base_token: Address = defi::read(&comet, "baseToken", ()).await?;
+                // Reverts for assets the market doesn't list
+                let listed = defi::read::<_, _, ethers::abi::Token>(&comet, "getAssetInfoByAddress", token).await;
+                Ok(token == base_token || listed.is_ok())
+            }
+        }
+    }
+
+    /// Logs matching `filter` in `from_block..=to_block`, fetched in pages and splitting any
+    /// page the node refuses (too many results or too wide a range), in block order
+    async fn get_logs_paged(&self, filter: &Filter, from_block: u64, to_block: u64) -> Result<Vec<Log>> {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        let mut start = from_block;
+        while start <= to_block {
+            let end = start.saturating_add(LOG_PAGE_BLOCKS - 1).min(to_block);
+            ranges.push((start, end));
+            start = end + 1;
+        }
+        // Popped from the back, so reverse to fetch the oldest page first
+        ranges.reverse();
+
+        let mut logs = Vec::new();
+        while let Some((from, to)) = ranges.pop() {
+            let page = filter.clone().from_block(from).to_block(to);
+            match self.provider.get_logs(&page).await {
+                Ok(found) => logs.extend(found),
+                Err(e) if to > from => {
+                    tracing::debug!("getLogs refused blocks {}..={}, splitting: {}", from, to, e);
+                    let mid = from + (to - from) / 2;
+                    ranges.push((mid + 1, to));
+                    ranges.push((from, mid));
+                }
+                Err(e) => {
+                    return Err(AppError::Rpc(format!("Failed to fetch logs for block {}: {}", from, e)));
+                }
+            }
+        }
+        Ok(logs)
+    }
+
+    /// Symbol and decimals of a token, from the token list when it's known there
+    async fn token_symbol_and_decimals(&self, token: Address) -> (String, u8) {
+        if let Some(info) = tokens::get_token_by_address(&format!("{:?}", token)) {
+            return (info.symbol.clone(), info.decimals);
+        }
+        let contract = tokens::get_erc20_contract(token, self.provider.clone());
+        let symbol = defi::read::<_, _, String>(&contract, "symbol", ()).await
+            .unwrap_or_else(|_| format!("{:?}", token));
+        let decimals = defi::read::<_, _, u8>(&contract, "decimals", ()).await.unwrap_or(18);
+        (symbol, decimals)
+    }
+
+    /// Rewards owed to `owner` on `protocol`, as raw token amounts
+    async fn owed_lending_rewards(
+        &self,
+        protocol: DefiProtocol,
+        owner: Address,
+        assets: &[String]
+    ) -> Result<Vec<(Address, U256)>> {
+        let unsupported = || AppError::Chain(format!("{} is not deployed on this chain", protocol.display_name()));
+        match protocol {
+            DefiProtocol::Aave => {
+                let market = defi::aave_market(self.chain_id).ok_or_else(unsupported)?;
+                defi::aave_unclaimed_rewards(self.provider.clone(), market, owner, parse_addresses(assets)?).await
+            }
+            DefiProtocol::Compound => {
+                let market = defi::compound_market(self.chain_id).ok_or_else(unsupported)?;
+                Ok(vec![defi::comet_reward_owed(self.provider.clone(), market, owner).await?])
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ChainProvider for EvmProvider {
+    async fn generate_wallet(&self, derivation_index: u32) -> Result<WalletInfo> {
+        wallet::generate_wallet(derivation_index)
+    }
+
+    async fn restore_wallet(&self, secret: &str, derivation_index: u32) -> Result<WalletInfo> {
+        wallet::detect_and_restore(secret, derivation_index)
+    }
+
+    async fn get_balance(&self, address: &str) -> Result<Balance> {
+        let addr: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+
+        let balance = self.provider
+            .get_balance(addr, None).await
+            .map_err(AppError::from)?;
+
+        let balance_str = ethers::utils::format_ether(balance);
+
+        Ok(Balance {
+            balance: balance_str,
+            symbol: self.native_symbol.clone(),
+            decimals: 18,
+            usd_value: None,
+        })
+    }
+
+    async fn get_token_balance(&self, address: &str, token_address: &str) -> Result<Balance> {
+        self.get_erc20_balance(address, token_address, None).await
+    }
+
+    async fn get_historical_balance(
+        &self,
+        address: &str,
+        token_address: Option<&str>,
+        block_number: u64
+    ) -> Result<Balance> {
+        let latest = self.get_block_number().await?;
+        if block_number > latest {
+            return Err(
+                AppError::InvalidInput(
+                    format!("Block {} is past the latest block {}", block_number, latest)
+                )
+            );
+        }
+        let block = BlockId::Number(BlockNumber::Number(block_number.into()));
+
+        // Pruned nodes only keep recent state and fail with "missing trie node" for old blocks
+        let archive_hint = |e: AppError| {
+            match e {
+                AppError::InvalidAddress => e,
+                e =>
+                    AppError::Rpc(
+                        format!(
+                            "Balance at block {} unavailable, the RPC may not be an archive node: {}",
+                            block_number,
+                            e
+                        )
+                    ),
+            }
+        };
+
+        if let Some(token_address) = token_address {
+            return self.get_erc20_balance(address, token_address, Some(block)).await.map_err(archive_hint);
+        }
+
+        let addr: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+        let balance = self.provider
+            .get_balance(addr, Some(block)).await
+            .map_err(|e| archive_hint(AppError::from(e)))?;
+
+        Ok(Balance {
+            balance: ethers::utils::format_ether(balance),
+            symbol: self.native_symbol.clone(),
+            decimals: 18,
+            usd_value: None,
+        })
+    }
+
+    async fn get_block_number(&self) -> Result<u64> {
+        let latest = self.provider.get_block_number().await.map_err(AppError::from)?;
+        Ok(latest.as_u64())
+    }
+
+    async fn get_block_by_number(&self, block_number: u64) -> Result<BlockInfo> {
+        let block = self.provider
+            .get_block(block_number).await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Block {} not found", block_number)))?;
+
+        Ok(BlockInfo {
+            number: block.number.map(|n| n.as_u64()).unwrap_or(block_number),
+            hash: block.hash.map(|h| format!("{:?}", h)).unwrap_or_default(),
+            timestamp: block.timestamp.as_u64() as i64,
+            transaction_count: block.transactions.len() as u64,
+            gas_used: block.gas_used.as_u64(),
+            gas_limit: block.gas_limit.as_u64(),
+            base_fee_gwei: block.base_fee_per_gas.map(|fee| (fee.as_u128() as f64) / 1e9),
+        })
+    }
+
+    async fn get_network_load(&self) -> Result<NetworkLoad> {
+        let to_gwei = |wei: U256| (wei.as_u128() as f64) / 1e9;
+
+        let gas_price = self.provider.get_gas_price().await.map_err(AppError::from)?;
+        let base_fee = self.provider
+            .get_block(BlockNumber::Latest).await
+            .map_err(AppError::from)?
+            .and_then(|block| block.base_fee_per_gas);
+        // Not every node implements these; the load is still useful without them
+        let priority_fee = self.provider
+            .request::<_, U256>("eth_maxPriorityFeePerGas", ()).await
+            .ok();
+        let pending_tx_count = match self.provider.txpool_status().await {
+            Ok(status) => Some(status.pending.as_u64()),
+            Err(_) => self.provider
+                .get_block(BlockNumber::Pending).await
+                .ok()
+                .flatten()
+                .map(|block| block.transactions.len() as u64),
+        };
+
+        Ok(NetworkLoad {
+            gas_price_gwei: to_gwei(gas_price),
+            base_fee_gwei: base_fee.map(to_gwei),
+            priority_fee_gwei: priority_fee.map(to_gwei),
+            pending_tx_count,
+        })
+    }
+
+    async fn get_base_fee_history(&self, block_count: u64, newest_block: u64) -> Result<Vec<f64>> {
+        let history = self.provider
+            .fee_history(block_count, BlockNumber::Number(newest_block.into()), &[]).await
+            .map_err(|e| AppError::Rpc(format!("Failed to fetch fee history: {}", e)))?;
+
+        Ok(
+            history.base_fee_per_gas
+                .iter()
+                .map(|fee| (fee.as_u128() as f64) / 1e9)
+                .collect()
+        )
+    }
+
+    async fn get_token_transfer_contracts(
+        &self,
+        address: &str,
+        from_block: u64,
+        to_block: u64
+    ) -> Result<Vec<String>> {
+        let addr: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+        let topic = H256::from(addr);
+
+        // Transfer(from indexed, to indexed, value): one query per side of the transfer
+        let mut contracts: Vec<Address> = Vec::new();
+        for incoming in [true, false] {
+            let filter = Filter::new()
+                .event("Transfer(address,address,uint256)")
+                .from_block(from_block)
+                .to_block(to_block);
+            let filter = if incoming { filter.topic2(topic) } else { filter.topic1(topic) };
+
+            let logs = self.provider
+                .get_logs(&filter).await
+                .map_err(|e| AppError::Rpc(format!("Failed to fetch transfer logs: {}", e)))?;
+            for log in logs {
+                // ERC-721 transfers share the signature but index the token id as a fourth topic
+                if log.topics.len() == 3 && !contracts.contains(&log.address) {
+                    contracts.push(log.address);
+                }
+            }
+        }
+
+        Ok(
+            contracts
+                .into_iter()
+                .map(|c| format!("{:?}", c))
+                .collect()
+        )
+    }
+
+    async fn send_transaction(
+        &self,
+        private_key: &str,
+        request: TransactionRequest
+    ) -> Result<TransactionResponse> {
+        let wallet: LocalWallet = private_key
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|_| AppError::InvalidPrivateKey)?;
+        let from = wallet.address();
+
+        match self.send_with_managed_nonce(wallet.clone(), &request).await {
+            Err(AppError::NonceTooLow(msg)) => {
+                // Local counter fell behind the chain (e.g. tx sent from another client)
+                tracing::warn!("Nonce too low for {:?}, re-syncing: {}", from, msg);
+                self.nonce_manager.resync(&self.provider, self.chain_id, from).await?;
+                self.send_with_managed_nonce(wallet, &request).await
+            }
+            Err(e) => {
+                // The reserved nonce was never used; re-sync so the next send doesn't leave a gap
+                if let Err(sync_err) = self.nonce_manager.resync(&self.provider, self.chain_id, from).await {
+                    tracing::warn!("Failed to re-sync nonce for {:?}: {}", from, sync_err);
+                }
+                Err(e)
+            }
+            ok => ok,
         }
     }
 
@@ -267,74 +844,688 @@ impl ChainProvider for EvmProvider {
         let from_addr: Address = from.parse().map_err(|_| AppError::InvalidAddress)?;
         let to_addr: Address = to.parse().map_err(|_| AppError::InvalidAddress)?;
 
-        // Get current gas price and EIP-1559 fees
-        let gas_price = self.provider
-            .get_gas_price().await
-            .map_err(|e| AppError::Rpc(format!("Failed to get gas price: {}", e)))?;
-
-        let (max_fee, max_priority_fee) = match self.provider.estimate_eip1559_fees(None).await {
-            Ok((max_fee, max_priority_fee)) => (max_fee, max_priority_fee),
-            Err(_) => {
-                // Fallback to legacy gas price if EIP-1559 not supported
-                (gas_price, U256::from(0))
-            }
-        };
-
-        // Estimate gas limit
-        let estimated_gas = if let Some(token_addr) = token_address {
+        // Estimate the exact call so its calldata also prices the L1 fee on OP Stack chains
+        let (call_to, data, value) = if let Some(token_addr) = token_address {
             // ERC20 transfer estimation
             let token_address: Address = token_addr.parse().map_err(|_| AppError::InvalidAddress)?;
             let amount_u256: U256 = parse_units(amount, 18)
                 .map_err(|_| AppError::InvalidInput("Invalid amount".to_string()))?
                 .into();
-
-            let contract = tokens::get_erc20_contract(token_address, self.provider.clone());
-            let call = contract
-                .method::<_, ()>("transfer", (to_addr, amount_u256))
-                .map_err(|e| AppError::Chain(format!("Failed to create call: {}", e)))?;
-
-            call
-                .estimate_gas().await
-                .map_err(|e| AppError::Chain(format!("Gas estimation failed: {}", e)))?
+            (token_address, erc20_transfer_calldata(to_addr, amount_u256), U256::zero())
         } else {
             // Native token transfer estimation
             let amount_u256: U256 = parse_units(amount, 18)
                 .map_err(|_| AppError::InvalidInput("Invalid amount".to_string()))?
                 .into();
+            (to_addr, Vec::new(), amount_u256)
+        };
+
+        self.estimate_call_cost(from_addr, call_to, data, value).await
+    }
+
+    async fn estimate_approval(
+        &self,
+        owner: &str,
+        token_address: &str,
+        spender: &str
+    ) -> Result<Option<crate::providers::GasEstimate>> {
+        let owner: Address = owner.parse().map_err(|_| AppError::InvalidAddress)?;
+        let token: Address = token_address.parse().map_err(|_| AppError::InvalidAddress)?;
+        let spender: Address = spender.parse().map_err(|_| AppError::InvalidAddress)?;
+
+        let mut allowance_call = ethers::utils::id("allowance(address,address)")[..4].to_vec();
+        allowance_call.extend(ethers::abi::encode(&[Token::Address(owner), Token::Address(spender)]));
+        let allowance_tx: TypedTransaction = EthTxRequest::new().to(token).data(allowance_call).into();
+        let output = self.provider
+            .call(&allowance_tx, None).await
+            .map_err(|e| AppError::Chain(format!("allowance call failed: {}", e)))?;
+        if output.len() < 32 {
+            return Err(AppError::Chain("Token did not return an allowance".to_string()));
+        }
+        if !U256::from_big_endian(&output[..32]).is_zero() {
+            return Ok(None);
+        }
+
+        let mut approve_call = ethers::utils::id("approve(address,uint256)")[..4].to_vec();
+        approve_call.extend(ethers::abi::encode(&[Token::Address(spender), Token::Uint(U256::MAX)]));
+        self.estimate_call_cost(owner, token, approve_call, U256::zero()).await.map(Some)
+    }
+
+    async fn speed_up_transaction(
+        &self,
+        private_key: &str,
+        tx_hash: &str,
+        max_fee_per_gas: Option<String>
+    ) -> Result<TransactionResponse> {
+        let wallet: LocalWallet = private_key
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|_| AppError::InvalidPrivateKey)?;
+
+        // User-supplied fee is in gwei, matching what estimate_gas reports
+        let max_fee_override = max_fee_per_gas
+            .as_deref()
+            .map(|fee| parse_units(fee, "gwei").map(U256::from))
+            .transpose()
+            .map_err(|_| AppError::InvalidInput("Invalid max fee".to_string()))?;
+
+        self.replace_pending_transaction(wallet, tx_hash, None, max_fee_override).await
+    }
+
+    async fn cancel_transaction(&self, private_key: &str, tx_hash: &str) -> Result<TransactionResponse> {
+        let wallet: LocalWallet = private_key
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|_| AppError::InvalidPrivateKey)?;
+        let own_address = wallet.address();
+
+        self.replace_pending_transaction(
+            wallet,
+            tx_hash,
+            Some((own_address, U256::zero(), Bytes::default())),
+            None
+        ).await
+    }
+
+    async fn list_token_approvals(&self, owner: &str) -> Result<Vec<TokenAllowance>> {
+        let owner_addr: Address = owner.parse().map_err(|_| AppError::InvalidAddress)?;
 
-            let tx = EthTxRequest::new().from(from_addr).to(to_addr).value(amount_u256);
+        // Approval(owner indexed, spender indexed, value) logs emitted for this owner
+        let filter = Filter::new()
+            .event("Approval(address,address,uint256)")
+            .topic1(H256::from(owner_addr));
+        let latest = self.provider.get_block_number().await.map_err(AppError::from)?.as_u64();
+        let logs = self.get_logs_paged(&filter, 0, latest).await?;
+
+        let mut pairs: Vec<(Address, Address)> = Vec::new();
+        for log in logs {
+            // ERC-721 approvals share the signature but index the token id as a fourth topic
+            if log.topics.len() != 3 {
+                continue;
+            }
+            let pair = (log.address, Address::from(log.topics[2]));
+            if !pairs.contains(&pair) {
+                pairs.push(pair);
+            }
+        }
 
-            self.provider
-                .estimate_gas(&tx.into(), None).await
-                .map_err(|e| AppError::Chain(format!("Gas estimation failed: {}", e)))?
+        let abi = ethers::abi
+            ::parse_abi(
+                &[
+                    "function allowance(address owner, address spender) external view returns (uint256)",
+                    "function decimals() external view returns (uint8)",
+                    "function symbol() external view returns (string)",
+                ]
+            )
+            .map_err(|e| AppError::Chain(format!("Failed to parse ABI: {}", e)))?;
+
+        let mut approvals = Vec::new();
+        for (token_addr, spender) in pairs {
+            let contract = Contract::new(token_addr, abi.clone(), self.provider.clone());
+
+            // Logs only tell us an approval happened; the current allowance may since be zero
+            let allowance: U256 = match contract.method::<_, U256>("allowance", (owner_addr, spender)) {
+                Ok(method) => method.call().await.unwrap_or_default(),
+                Err(_) => U256::zero(),
+            };
+            if allowance.is_zero() {
+                continue;
+            }
+
+            let token_address = format!("{:?}", token_addr);
+            let (decimals, token_symbol) = if
+                let Some(token_info) = tokens::get_token_by_address(&token_address)
+            {
+                (token_info.decimals, token_info.symbol.clone())
+            } else {
+                let decimals = match contract.method::<_, u8>("decimals", ()) {
+                    Ok(method) => method.call().await.ok().unwrap_or(18),
+                    Err(_) => 18,
+                };
+                let symbol = match contract.method::<_, String>("symbol", ()) {
+                    Ok(method) =>
+                        method
+                            .call().await
+                            .ok()
+                            .unwrap_or_else(|| "UNKNOWN".to_string()),
+                    Err(_) => "UNKNOWN".to_string(),
+                };
+                (decimals, symbol)
+            };
+
+            let allowance = if is_unlimited_allowance(allowance) {
+                "Unlimited".to_string()
+            } else {
+                ethers::utils::format_units(allowance, decimals as u32).unwrap_or_default()
+            };
+
+            approvals.push(TokenAllowance {
+                token_address,
+                token_symbol,
+                spender: format!("{:?}", spender),
+                allowance,
+            });
+        }
+
+        Ok(approvals)
+    }
+
+    async fn revoke_token_approval(
+        &self,
+        private_key: &str,
+        token_address: &str,
+        spender: &str
+    ) -> Result<TransactionResponse> {
+        let wallet: LocalWallet = private_key
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|_| AppError::InvalidPrivateKey)?;
+        let token_addr: Address = token_address.parse().map_err(|_| AppError::InvalidAddress)?;
+        let spender_addr: Address = spender.parse().map_err(|_| AppError::InvalidAddress)?;
+
+        let abi = ethers::abi
+            ::parse_abi(&["function approve(address spender, uint256 amount) external returns (bool)"])
+            .map_err(|e| AppError::Chain(format!("Failed to parse ABI: {}", e)))?;
+
+        let from = wallet.address();
+        let nonce = self.nonce_manager.next_nonce(&self.provider, self.chain_id, from).await?;
+
+        let client = SignerMiddleware::new(
+            self.provider.clone(),
+            wallet.with_chain_id(self.chain_id)
+        );
+        let contract = Contract::new(token_addr, abi, Arc::new(client));
+
+        let mut call = contract
+            .method::<_, bool>("approve", (spender_addr, U256::zero()))
+            .map_err(|e| AppError::Chain(format!("Failed to prepare approve: {}", e)))?;
+        call.tx.set_nonce(nonce);
+
+        let pending_tx = match call.send().await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                // The reserved nonce was never used; re-sync so the next send doesn't leave a gap
+                if let Err(sync_err) = self.nonce_manager.resync(&self.provider, self.chain_id, from).await {
+                    tracing::warn!("Failed to re-sync nonce for {:?}: {}", from, sync_err);
+                }
+                return Err(map_send_error(e.to_string()));
+            }
         };
 
-        // Calculate total cost in wei
-        let total_cost_wei = estimated_gas * max_fee;
-        let total_cost_eth = ethers::utils
-            ::format_units(total_cost_wei, 18)
-            .map_err(|_| AppError::Internal("Failed to format units".to_string()))?;
+        Ok(TransactionResponse {
+            tx_hash: format!("{:?}", pending_tx.tx_hash()),
+            status: TxStatus::Pending.to_string(),
+        })
+    }
 
-        Ok(crate::providers::GasEstimate {
-            estimated_gas: estimated_gas.as_u64(),
-            gas_price: Some(
-                format!("{}", ethers::utils::format_units(gas_price, "gwei").unwrap_or_default())
-            ),
-            max_fee_per_gas: Some(
-                format!("{}", ethers::utils::format_units(max_fee, "gwei").unwrap_or_default())
-            ),
-            max_priority_fee_per_gas: Some(
-                format!(
-                    "{}",
-                    ethers::utils::format_units(max_priority_fee, "gwei").unwrap_or_default()
-                )
-            ),
-            total_cost_native: total_cost_eth,
-            total_cost_usd: None, // Will be calculated by service layer
+    async fn get_compound_supply(&self, owner: &str) -> Result<Option<LendingSupply>> {
+        let Some(market) = defi::compound_market(self.chain_id) else {
+            return Ok(None);
+        };
+        let owner: Address = owner.parse().map_err(|_| AppError::InvalidAddress)?;
+
+        let supply = defi::comet_supply(self.provider.clone(), market, owner).await?;
+        if supply.balance.is_zero() {
+            return Ok(None);
+        }
+        let (asset_symbol, decimals) = self.token_symbol_and_decimals(supply.base_token).await;
+
+        Ok(
+            Some(LendingSupply {
+                market_address: market.comet.to_string(),
+                asset_symbol,
+                supplied: format_token_amount(supply.balance, decimals)?,
+                supply_apy: supply.supply_apy,
+            })
+        )
+    }
+
+    async fn get_lending_rewards(
+        &self,
+        protocol: DefiProtocol,
+        owner: &str,
+        assets: &[String]
+    ) -> Result<Vec<LendingReward>> {
+        let owner: Address = owner.parse().map_err(|_| AppError::InvalidAddress)?;
+        let owed = self.owed_lending_rewards(protocol, owner, assets).await?;
+
+        let mut rewards = Vec::new();
+        for (token, amount) in owed.into_iter().filter(|(_, amount)| !amount.is_zero()) {
+            let (token_symbol, decimals) = self.token_symbol_and_decimals(token).await;
+            rewards.push(LendingReward {
+                token_address: format!("{:?}", token),
+                token_symbol,
+                amount: format_token_amount(amount, decimals)?,
+            });
+        }
+        Ok(rewards)
+    }
+
+    async fn compound_lending_rewards(
+        &self,
+        private_key: &str,
+        protocol: DefiProtocol,
+        assets: &[String]
+    ) -> Result<TransactionResponse> {
+        let wallet: LocalWallet = private_key
+            .trim_start_matches("0x")
+            .parse()
+            .map_err(|_| AppError::InvalidPrivateKey)?;
+        let owner = wallet.address();
+
+        let owed: Vec<(Address, U256)> = self
+            .owed_lending_rewards(protocol, owner, assets).await?
+            .into_iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .collect();
+        if owed.is_empty() {
+            return Err(AppError::Validation(format!("No {} rewards to compound", protocol.display_name())));
+        }
+
+        let unsupported = || AppError::Chain(format!("{} is not deployed on this chain", protocol.display_name()));
+        let client = Arc::new(SignerMiddleware::new(self.provider.clone(), wallet.with_chain_id(self.chain_id)));
+        let prepare_err = |name: &str, e: AbiError| AppError::Chain(format!("Failed to prepare {}: {}", name, e));
+
+        // Claim everything owed, then supply each reward token back into the same protocol
+        let mut last_tx: H256;
+        let supply_target: Address = match protocol {
+            DefiProtocol::Aave => {
+                let market = defi::aave_market(self.chain_id).ok_or_else(unsupported)?;
+                let controller_addr: Address = market.rewards_controller.parse().map_err(|_| AppError::InvalidAddress)?;
+                let controller = Contract::new(controller_addr, defi::aave_rewards_abi()?, client.clone());
+                let claim = controller
+                    .method::<_, (Vec<Address>, Vec<U256>)>("claimAllRewardsToSelf", parse_addresses(assets)?)
+                    .map_err(|e| prepare_err("claimAllRewardsToSelf", e))?;
+                last_tx = self.send_and_confirm(claim, owner).await?;
+                market.pool.parse().map_err(|_| AppError::InvalidAddress)?
+            }
+            DefiProtocol::Compound => {
+                let market = defi::compound_market(self.chain_id).ok_or_else(unsupported)?;
+                let comet_addr: Address = market.comet.parse().map_err(|_| AppError::InvalidAddress)?;
+                let rewards_addr: Address = market.rewards.parse().map_err(|_| AppError::InvalidAddress)?;
+                let rewards = Contract::new(rewards_addr, defi::comet_rewards_abi()?, client.clone());
+                let claim = rewards
+                    .method::<_, ()>("claim", (comet_addr, owner, true))
+                    .map_err(|e| prepare_err("claim", e))?;
+                last_tx = self.send_and_confirm(claim, owner).await?;
+                comet_addr
+            }
+        };
+
+        let approve_abi = ethers::abi
+            ::parse_abi(&["function approve(address spender, uint256 amount) external returns (bool)"])
+            .map_err(|e| AppError::Chain(format!("Failed to parse ABI: {}", e)))?;
+
+        for (token, amount) in owed {
+            // Rewards the market doesn't list stay claimed in the wallet
+            if !self.accepts_supply(protocol, supply_target, token).await? {
+                tracing::info!("{:?} can't be supplied to {}, leaving it in the wallet", token, protocol.display_name());
+                continue;
+            }
+
+            let erc20 = Contract::new(token, approve_abi.clone(), client.clone());
+            let approve = erc20
+                .method::<_, bool>("approve", (supply_target, amount))
+                .map_err(|e| prepare_err("approve", e))?;
+            self.send_and_confirm(approve, owner).await?;
+
+            let supply = match protocol {
+                DefiProtocol::Aave =>
+                    Contract::new(supply_target, defi::aave_pool_abi()?, client.clone()).method::<_, ()>(
+                        "supply",
+                        (token, amount, owner, 0u16)
+                    ),
+                DefiProtocol::Compound =>
+                    Contract::new(supply_target, defi::comet_abi()?, client.clone()).method::<_, ()>(
+                        "supply",
+                        (token, amount)
+                    ),
+            }.map_err(|e| prepare_err("supply", e));
+            let supplied = match supply {
+                Ok(supply) => self.send_and_confirm(supply, owner).await,
+                Err(e) => Err(e),
+            };
+            match supplied {
+                Ok(tx_hash) => {
+                    last_tx = tx_hash;
+                }
+                Err(e) => {
+                    // Don't leave the market with an allowance nothing is going to use
+                    let reset = erc20
+                        .method::<_, bool>("approve", (supply_target, U256::zero()))
+                        .map_err(|e| prepare_err("approve", e));
+                    if let Err(reset_err) = match reset {
+                        Ok(reset) => self.send_and_confirm(reset, owner).await,
+                        Err(e) => Err(e),
+                    } {
+                        tracing::warn!("Failed to reset allowance of {:?} after a failed supply: {}", token, reset_err);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(TransactionResponse {
+            tx_hash: format!("{:?}", last_tx),
+            status: TxStatus::Confirmed.to_string(),
+        })
+    }
+
+    async fn simulate_transaction(&self, request: &TransactionRequest) -> Result<SimulationResult> {
+        let from: Address = request.from.parse().map_err(|_| AppError::InvalidAddress)?;
+        let to: Address = request.to.parse().map_err(|_| AppError::InvalidAddress)?;
+
+        // Build the same call the real send would make
+        let (tx, asset): (TypedTransaction, String) = if let Some(token_address) = &request.token_address {
+            let token_addr: Address = token_address.parse().map_err(|_| AppError::InvalidAddress)?;
+            let (decimals, symbol) = match tokens::get_token_by_address(token_address) {
+                Some(info) => (info.decimals, info.symbol.clone()),
+                None => (18, token_address.clone()),
+            };
+            let amount: U256 = parse_units(&request.amount, decimals as u32)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid amount: {}", e)))?
+                .into();
+
+            let contract = tokens::get_erc20_contract(token_addr, self.provider.clone());
+            let data = contract
+                .method::<_, bool>("transfer", (to, amount))
+                .map_err(|e| AppError::Chain(format!("Failed to prepare transfer: {}", e)))?
+                .calldata()
+                .ok_or_else(|| AppError::Chain("Failed to encode transfer".to_string()))?;
+
+            (EthTxRequest::new().from(from).to(token_addr).data(data).into(), symbol)
+        } else {
+            let amount: U256 = parse_units(&request.amount, 18)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid amount: {}", e)))?
+                .into();
+
+            (EthTxRequest::new().from(from).to(to).value(amount).into(), self.native_symbol.clone())
+        };
+
+        if let Err(e) = self.provider.call(&tx, None).await {
+            let revert_reason = match RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) {
+                Some(data) => revert::decode_revert_reason(&data),
+                None => e.to_string(),
+            };
+
+            return Ok(SimulationResult {
+                success: false,
+                revert_reason: Some(revert_reason),
+                gas_used: 0,
+                state_changes: vec![],
+            });
+        }
+
+        let gas_used = self.provider
+            .estimate_gas(&tx, None).await
+            .map(|gas| gas.as_u64())
+            .unwrap_or(0);
+
+        Ok(SimulationResult {
+            success: true,
+            revert_reason: None,
+            gas_used,
+            state_changes: vec![
+                StateChange {
+                    address: request.from.clone(),
+                    asset: asset.clone(),
+                    change: format!("-{}", request.amount),
+                },
+                StateChange {
+                    address: request.to.clone(),
+                    asset,
+                    change: format!("+{}", request.amount),
+                }
+            ],
+        })
+    }
+
+    async fn inspect_token(&self, token_address: &str) -> Result<TokenInspection> {
+        let token: Address = token_address.parse().map_err(|_| AppError::InvalidAddress)?;
+
+        let code = self.provider
+            .get_code(token, None).await
+            .map_err(|e| AppError::Rpc(format!("Failed to get contract code: {}", e)))?;
+        if code.is_empty() {
+            return Ok(TokenInspection {
+                is_contract: false,
+                is_proxy: false,
+                transfer_revert_reason: None,
+            });
+        }
+
+        let implementation_slot: H256 = EIP1967_IMPLEMENTATION_SLOT.parse().expect("valid slot");
+        let implementation = self.provider
+            .get_storage_at(token, implementation_slot, None).await
+            .map_err(|e| AppError::Rpc(format!("Failed to read storage: {}", e)))?;
+
+        // A zero-value transfer between arbitrary addresses succeeds on any ordinary ERC20
+        let holder: Address = "0x000000000000000000000000000000000000dEaD".parse().expect("valid address");
+        let recipient: Address = "0x0000000000000000000000000000000000000001".parse().expect("valid address");
+        let data = tokens::get_erc20_contract(token, self.provider.clone())
+            .method::<_, bool>("transfer", (recipient, U256::zero()))
+            .map_err(|e| AppError::Chain(format!("Failed to prepare transfer: {}", e)))?
+            .calldata()
+            .ok_or_else(|| AppError::Chain("Failed to encode transfer".to_string()))?;
+        let tx: TypedTransaction = EthTxRequest::new().from(holder).to(token).data(data).into();
+
+        let transfer_revert_reason = match self.provider.call(&tx, None).await {
+            Ok(_) => None,
+            Err(e) =>
+                Some(match RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) {
+                    Some(data) => revert::decode_revert_reason(&data),
+                    None => e.to_string(),
+                }),
+        };
+
+        Ok(TokenInspection {
+            is_contract: true,
+            is_proxy: !implementation.is_zero(),
+            transfer_revert_reason,
         })
     }
 
+    async fn get_transaction_by_hash(&self, hash: &str) -> Result<Option<TransactionDetail>> {
+        let tx_hash: H256 = hash
+            .parse()
+            .map_err(|_| AppError::InvalidInput(format!("Invalid transaction hash: {}", hash)))?;
+
+        let Some(tx) = self.provider
+            .get_transaction(tx_hash).await
+            .map_err(AppError::from)? else {
+            return Ok(None);
+        };
+        let receipt = self.provider
+            .get_transaction_receipt(tx_hash).await
+            .map_err(AppError::from)?;
+
+        let status = match &receipt {
+            None => TxStatus::Pending,
+            Some(r) if r.status == Some(U64::one()) => TxStatus::Confirmed,
+            Some(_) => TxStatus::Failed,
+        };
+
+        let block_number = receipt
+            .as_ref()
+            .and_then(|r| r.block_number)
+            .or(tx.block_number);
+        let fee = receipt
+            .as_ref()
+            .and_then(|r| Some(r.gas_used? * r.effective_gas_price.or(tx.gas_price)?))
+            .map(ethers::utils::format_ether);
+        let timestamp = match block_number {
+            Some(number) =>
+                self.provider
+                    .get_block(number).await
+                    .ok()
+                    .flatten()
+                    .map(|block| block.timestamp.as_u64() as i64),
+            None => None,
+        };
+
+        Ok(
+            Some(TransactionDetail {
+                hash: format!("{:?}", tx.hash),
+                block_number: block_number.map(|n| n.as_u64()),
+                from: format!("{:?}", tx.from),
+                to: tx.to.map(|to| format!("{:?}", to)),
+                value: ethers::utils::format_ether(tx.value),
+                status: status.to_string(),
+                gas_used: receipt.as_ref().and_then(|r| r.gas_used).map(|g| g.as_u64()),
+                fee,
+                timestamp,
+            })
+        )
+    }
+
+    async fn get_token_decimals_batch(&self, addresses: &[&str]) -> Result<HashMap<String, u8>> {
+        let mut decimals = HashMap::new();
+        let mut unknown = Vec::new();
+        for &address in addresses {
+            if let Some(token_info) = tokens::get_token_by_address(address) {
+                decimals.insert(address.to_string(), token_info.decimals);
+            } else if let Some(entry) = self.listed_token(address).await {
+                decimals.insert(address.to_string(), entry.decimals);
+            } else {
+                let token: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+                unknown.push((address, token));
+            }
+        }
+
+        if unknown.is_empty() {
+            return Ok(decimals);
+        }
+
+        let targets: Vec<Address> = unknown.iter().map(|(_, token)| *token).collect();
+        let results = match self.multicall_decimals(&targets).await {
+            Ok(results) => results,
+            Err(e) => {
+                tracing::debug!(
+                    "Multicall3 unavailable on chain {}, reading decimals one token at a time: {}",
+                    self.chain_id,
+                    e
+                );
+                let mut results = Vec::with_capacity(targets.len());
+                for token in &targets {
+                    results.push(self.call_decimals(*token).await);
+                }
+                results
+            }
+        };
+
+        for ((address, _), value) in unknown.into_iter().zip(results) {
+            if let Some(value) = value {
+                decimals.insert(address.to_string(), value);
+            }
+        }
+        Ok(decimals)
+    }
+
+    async fn get_average_block_time_secs(&self) -> Result<f64> {
+        let latest = self.provider.get_block_number().await.map_err(AppError::from)?;
+        let span = BLOCK_TIME_SAMPLE_BLOCKS.min(latest.as_u64());
+        if span == 0 {
+            return Err(AppError::Chain("Not enough blocks to measure block time".to_string()));
+        }
+
+        let newest = self.provider
+            .get_block(latest).await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Block {} not found", latest)))?;
+        let oldest_number = latest - U64::from(span);
+        let oldest = self.provider
+            .get_block(oldest_number).await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::NotFound(format!("Block {} not found", oldest_number)))?;
+
+        let elapsed = newest.timestamp.saturating_sub(oldest.timestamp).as_u64();
+        Ok((elapsed as f64) / (span as f64))
+    }
+
+    async fn get_pending_nonce(&self, address: &str) -> Result<u64> {
+        let address: Address = address.parse().map_err(|_| AppError::InvalidAddress)?;
+        let nonce = self.provider
+            .get_transaction_count(address, Some(BlockNumber::Pending.into())).await
+            .map_err(AppError::from)?;
+        Ok(nonce.as_u64())
+    }
+
+    async fn l1_fee_breakdown(&self, tx_hash: &str) -> Option<Result<L1FeeBreakdown>> {
+        if !l1_fee::is_arbitrum(self.chain_id) {
+            return None;
+        }
+
+        let result = async {
+            let hash: H256 = tx_hash
+                .parse()
+                .map_err(|_| AppError::InvalidInput(format!("Invalid transaction hash: {}", tx_hash)))?;
+            let fee = l1_fee
+                ::arbitrum_l1_fee(self.provider.clone(), hash).await?
+                .ok_or_else(|| AppError::NotFound(format!("Receipt for {} not found", tx_hash)))?;
+
+            Ok(L1FeeBreakdown {
+                payer: format!("{:?}", fee.payer),
+                l1_fee_paid: ethers::utils::format_ether(fee.l1_fee_paid),
+                l1_refund: ethers::utils::format_ether(fee.l1_refund),
+            })
+        };
+        Some(result.await)
+    }
+
     fn validate_address(&self, address: &str) -> bool {
         wallet::validate_address(address)
     }
 }
+
+/// keccak256("eip1967.proxy.implementation") - 1
+const EIP1967_IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// Allowances at or above this are displayed as unlimited (approve(MAX) is the common pattern)
+fn is_unlimited_allowance(allowance: U256) -> bool {
+    allowance >= U256::MAX >> 1
+}
+
+/// ABI-decode a `uint8` return value; `None` if it is malformed or out of range
+fn decode_uint8(data: &[u8]) -> Option<u8> {
+    if data.len() != 32 || data[..31].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(data[31])
+}
+
+/// Increase a fee by the 10% minimum that nodes require to accept a replacement
+fn bump_fee(fee: U256) -> U256 {
+    fee * 110 / 100 + 1
+}
+
+fn parse_addresses(addresses: &[String]) -> Result<Vec<Address>> {
+    addresses
+        .iter()
+        .map(|a| a.parse().map_err(|_| AppError::InvalidAddress))
+        .collect()
+}
+
+fn format_token_amount(amount: U256, decimals: u8) -> Result<String> {
+    ethers::utils
+        ::format_units(amount, decimals as u32)
+        .map_err(|e| AppError::Chain(format!("Failed to format amount: {}", e)))
+}
+
+/// Map a broadcast failure, surfacing nonce conflicts so callers can re-sync and retry
+fn map_send_error(message: String) -> AppError {
+    AppError::from_rpc_message(&message)
+        .unwrap_or_else(|| AppError::Chain(format!("Transaction failed: {}", message)))
+}
+
+/// Transaction calling `to` with `data` and `value`, for `eth_estimateGas`
+fn call_request(from: Address, to: Address, data: Vec<u8>, value: U256) -> TypedTransaction {
+    EthTxRequest::new().from(from).to(to).value(value).data(data).into()
+}
+
+/// ABI-encoded `transfer(address,uint256)` call
+fn erc20_transfer_calldata(to: Address, amount: U256) -> Vec<u8> {
+    let mut data = ethers::utils::id("transfer(address,uint256)")[..4].to_vec();
+    data.extend(ethers::abi::encode(&[ethers::abi::Token::Address(to), ethers::abi::Token::Uint(amount)]));
+    data
+}
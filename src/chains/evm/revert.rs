@@ -0,0 +1,92 @@
+use ethers::abi::{ decode, ParamType, Token };
+use ethers::utils::id;
+
+/// `Error(string)` — emitted by `require(cond, "message")` and `revert("message")`
+const ERROR_STRING_SIG: &str = "Error(string)";
+
+/// `Panic(uint256)` — emitted by failed asserts, overflows, out-of-bounds access, etc.
+const PANIC_SIG: &str = "Panic(uint256)";
+
+/// Custom errors commonly raised by token contracts (OpenZeppelin ERC-6093)
+const KNOWN_CUSTOM_ERRORS: &[(&str, &str)] = &[
+    ("ERC20InsufficientBalance(address,uint256,uint256)", "Insufficient token balance"),
+    ("ERC20InsufficientAllowance(address,uint256,uint256)", "Insufficient token allowance"),
+    ("ERC20InvalidSender(address)", "Invalid token sender"),
+    ("ERC20InvalidReceiver(address)", "Invalid token receiver"),
+    ("ERC20InvalidApprover(address)", "Invalid token approver"),
+    ("ERC20InvalidSpender(address)", "Invalid token spender"),
+    ("EnforcedPause()", "Token transfers are paused"),
+];
+
+/// Turn ABI-encoded revert data into a human-readable reason
+pub fn decode_revert_reason(data: &[u8]) -> String {
+    if data.len() < 4 {
+        return "Execution reverted without a reason".to_string();
+    }
+
+    let (selector, payload) = data.split_at(4);
+
+    if selector == &id(ERROR_STRING_SIG)[..] {
+        if let Ok(tokens) = decode(&[ParamType::String], payload) {
+            if let Some(Token::String(message)) = tokens.into_iter().next() {
+                return message;
+            }
+        }
+    }
+
+    if selector == &id(PANIC_SIG)[..] {
+        if let Ok(tokens) = decode(&[ParamType::Uint(256)], payload) {
+            if let Some(Token::Uint(code)) = tokens.into_iter().next() {
+                return format!("Panic: {}", panic_description(code.low_u64()));
+            }
+        }
+    }
+
+    for (signature, description) in KNOWN_CUSTOM_ERRORS {
+        if selector == &id(signature)[..] {
+            return description.to_string();
+        }
+    }
+
+    format!("Custom error 0x{}", hex::encode(selector))
+}
+
+/// Meaning of Solidity panic codes
+fn panic_description(code: u64) -> &'static str {
+    match code {
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => "unknown panic code",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::encode;
+    use ethers::types::U256;
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let mut error_string = id(ERROR_STRING_SIG).to_vec();
+        error_string.extend(encode(&[Token::String("Not enough balance".to_string())]));
+        assert_eq!(decode_revert_reason(&error_string), "Not enough balance");
+
+        let mut panic = id(PANIC_SIG).to_vec();
+        panic.extend(encode(&[Token::Uint(U256::from(0x11))]));
+        assert_eq!(decode_revert_reason(&panic), "Panic: arithmetic overflow or underflow");
+
+        let insufficient = id("ERC20InsufficientBalance(address,uint256,uint256)").to_vec();
+        assert_eq!(decode_revert_reason(&insufficient), "Insufficient token balance");
+
+        assert_eq!(decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]), "Custom error 0xdeadbeef");
+        assert_eq!(decode_revert_reason(&[]), "Execution reverted without a reason");
+    }
+}
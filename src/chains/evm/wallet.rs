@@ -1,7 +1,7 @@
 use bip39::{ Mnemonic, Language };
 use ethers::signers::{ LocalWallet, Signer };
 use ethers::core::types::H160;
-use ethers::utils::hex;
+use ethers::utils::{ hash_message, hex };
 
 use crate::error::{ AppError, Result };
 use crate::providers::WalletInfo;
@@ -81,6 +81,26 @@ pub fn validate_address(address: &str) -> bool {
     address.parse::<H160>().is_ok()
 }
 
+/// Sign `message` the way `eth_sign` does with the key behind `secret` and check the
+/// recovered signer is `address`. Mnemonics are checked at derivation index 0.
+pub fn verify_ownership(address: &str, secret: &str, message: &str) -> Result<bool> {
+    let expected: H160 = address.parse().map_err(|_| AppError::InvalidAddress)?;
+    let private_key = detect_and_restore(secret, 0)?.private_key;
+    let wallet: LocalWallet = private_key
+        .trim_start_matches("0x")
+        .parse()
+        .map_err(|_| AppError::InvalidPrivateKey)?;
+
+    let signature = wallet
+        .sign_hash(hash_message(message))
+        .map_err(|e| AppError::Internal(format!("Failed to sign ownership message: {}", e)))?;
+    let recovered = signature
+        .recover(message)
+        .map_err(|e| AppError::Internal(format!("Failed to recover ownership signer: {}", e)))?;
+
+    Ok(recovered == expected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +136,15 @@ mod tests {
         assert!(wallet2.mnemonic.is_none());
     }
 
+    #[test]
+    fn test_verify_ownership() {
+        let wallet = generate_wallet(0).unwrap();
+        let other = generate_wallet(0).unwrap();
+
+        assert!(verify_ownership(&wallet.address, &wallet.private_key, "verify_ownership:test:0").unwrap());
+        assert!(!verify_ownership(&other.address, &wallet.private_key, "verify_ownership:test:0").unwrap());
+    }
+
     #[test]
     fn test_validate_address() {
         assert!(validate_address("0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0"));
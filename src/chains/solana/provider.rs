@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use async_trait::async_trait;
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -18,11 +19,22 @@ use crate::error::{ AppError, Result };
 use crate::providers::{
     Balance,
     ChainProvider,
+    TransactionDetail,
     TransactionRequest,
     TransactionResponse,
     WalletInfo,
 };
 
+/// Target slot duration on Solana mainnet
+const SLOT_TIME_SECS: f64 = 0.4;
+
+/// Offset of `decimals` in an SPL mint account (after the mint authority and supply);
+/// Token-2022 mints share the same base layout
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Most accounts `getMultipleAccounts` accepts per request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
 #[derive(Clone)]
 pub struct SolanaProvider {
     client: Arc<RpcClient>,
@@ -85,9 +97,30 @@ impl SolanaProvider {
             balance: balance.to_string(),
             symbol,
             decimals,
+            usd_value: None,
         })
     }
 
+    /// Network fee in lamports for a message with these instructions (`getFeeForMessage`)
+    async fn fee_for_instructions(
+        &self,
+        instructions: &[solana_sdk::instruction::Instruction],
+        payer: &Pubkey
+    ) -> Result<u64> {
+        let blockhash = self.client
+            .get_latest_blockhash().await
+            .map_err(|e| AppError::Rpc(format!("Failed to get recent blockhash: {}", e)))?;
+        let message = solana_sdk::message::Message::new_with_blockhash(
+            instructions,
+            Some(payer),
+            &blockhash
+        );
+
+        self.client
+            .get_fee_for_message(&message).await
+            .map_err(|e| AppError::Rpc(format!("Failed to get fee for message: {}", e)))
+    }
+
     async fn send_spl_token_transaction(
         &self,
         keypair: &Keypair,
@@ -186,6 +219,7 @@ impl ChainProvider for SolanaProvider {
             balance: balance_sol.to_string(),
             symbol: "SOL".to_string(),
             decimals: 9,
+            usd_value: None,
         })
     }
 
@@ -268,20 +302,40 @@ impl ChainProvider for SolanaProvider {
         amount: &str,
         token_address: Option<&str>
     ) -> Result<crate::providers::GasEstimate> {
-        let _from_pubkey = Pubkey::from_str(from).map_err(|_| AppError::InvalidAddress)?;
-        let _to_pubkey = Pubkey::from_str(to).map_err(|_| AppError::InvalidAddress)?;
+        let from_pubkey = Pubkey::from_str(from).map_err(|_| AppError::InvalidAddress)?;
+        let to_pubkey = Pubkey::from_str(to).map_err(|_| AppError::InvalidAddress)?;
 
-        // Solana uses a fixed fee model
-        // Base fee is 5000 lamports per signature
-        // Priority fees can be added but are optional
+        // Fallback when the node can't price the message; priority fees are optional
         const BASE_FEE_LAMPORTS: u64 = 5000;
         const COMPUTE_UNITS_DEFAULT: u64 = 200_000;
 
-        // For SPL token transfers, we need 2 signatures (payer + token account)
-        let estimated_fee_lamports = if token_address.is_some() {
-            BASE_FEE_LAMPORTS * 2 // Token transfer may need multiple signatures
+        // Ask the node what the transfer message would cost
+        let instruction = if let Some(mint) = token_address {
+            let mint = Pubkey::from_str(mint).map_err(|_| AppError::InvalidAddress)?;
+            spl_token::instruction
+                ::transfer(
+                    &spl_token::id(),
+                    &spl_associated_token_account::get_associated_token_address(&from_pubkey, &mint),
+                    &spl_associated_token_account::get_associated_token_address(&to_pubkey, &mint),
+                    &from_pubkey,
+                    &[],
+                    0
+                )
+                .map_err(|e| AppError::Chain(format!("Failed to create transfer instruction: {}", e)))?
         } else {
-            BASE_FEE_LAMPORTS
+            let lamports = amount
+                .parse::<f64>()
+                .map(|sol| (sol * (LAMPORTS_PER_SOL as f64)) as u64)
+                .unwrap_or(0);
+            system_instruction::transfer(&from_pubkey, &to_pubkey, lamports)
+        };
+
+        let estimated_fee_lamports = match self.fee_for_instructions(&[instruction], &from_pubkey).await {
+            Ok(fee) => fee,
+            Err(e) => {
+                tracing::warn!("getFeeForMessage failed, using base fee: {}", e);
+                BASE_FEE_LAMPORTS
+            }
         };
 
         // Convert lamports to SOL
@@ -289,15 +343,106 @@ impl ChainProvider for SolanaProvider {
 
         Ok(crate::providers::GasEstimate {
             estimated_gas: COMPUTE_UNITS_DEFAULT,
+            gas_limit: COMPUTE_UNITS_DEFAULT,
             gas_price: Some(format!("{} lamports", estimated_fee_lamports)),
             max_fee_per_gas: None,
             max_priority_fee_per_gas: None,
             total_cost_native: format!("{:.9}", fee_sol),
             total_cost_usd: None,
+            l1_data_fee_native: None,
+            l1_data_fee_usd: None,
+            confidence_pct: None,
         })
     }
 
+    async fn get_transaction_by_hash(&self, hash: &str) -> Result<Option<TransactionDetail>> {
+        use solana_client::rpc_request::RpcRequest;
+
+        let tx: serde_json::Value = self.client
+            .send(
+                RpcRequest::GetTransaction,
+                serde_json::json!([
+                    hash,
+                    {
+                        "encoding": "json",
+                        "commitment": "confirmed",
+                        "maxSupportedTransactionVersion": 0
+                    }
+                ])
+            ).await
+            .map_err(|e| AppError::Rpc(format!("Failed to get transaction: {}", e)))?;
+
+        if tx.is_null() {
+            return Ok(None);
+        }
+
+        let meta = &tx["meta"];
+        let account_keys = tx["transaction"]["message"]["accountKeys"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let key = |i: usize| account_keys.get(i).and_then(|k| k.as_str()).map(str::to_string);
+
+        // A plain SOL transfer credits the second account key; report what it received
+        let balance = |field: &str| meta[field][1].as_u64().unwrap_or(0);
+        let received = balance("postBalances").saturating_sub(balance("preBalances"));
+
+        let status = if meta["err"].is_null() { TxStatus::Confirmed } else { TxStatus::Failed };
+
+        Ok(
+            Some(TransactionDetail {
+                hash: hash.to_string(),
+                block_number: tx["slot"].as_u64(),
+                from: key(0).unwrap_or_default(),
+                to: key(1),
+                value: format!("{}", (received as f64) / (LAMPORTS_PER_SOL as f64)),
+                status: status.to_string(),
+                gas_used: meta["computeUnitsConsumed"].as_u64(),
+                fee: meta["fee"].as_u64().map(|fee| format!("{}", (fee as f64) / (LAMPORTS_PER_SOL as f64))),
+                timestamp: tx["blockTime"].as_i64(),
+            })
+        )
+    }
+
     fn validate_address(&self, address: &str) -> bool {
         wallet::validate_address(address)
     }
+
+    /// Known mints from the built-in list, the rest from their mint accounts in
+    /// `getMultipleAccounts` batches
+    async fn get_token_decimals_batch(&self, addresses: &[&str]) -> Result<HashMap<String, u8>> {
+        let mut decimals = HashMap::new();
+        let mut unknown = Vec::new();
+        for &address in addresses {
+            match tokens::get_token_by_mint(address) {
+                Some(token_info) => {
+                    decimals.insert(address.to_string(), token_info.decimals);
+                }
+                None => {
+                    let mint = Pubkey::from_str(address).map_err(|_| AppError::InvalidAddress)?;
+                    unknown.push((address, mint));
+                }
+            }
+        }
+
+        for chunk in unknown.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let mints: Vec<Pubkey> = chunk.iter().map(|(_, mint)| *mint).collect();
+            let accounts = self.client
+                .get_multiple_accounts(&mints).await
+                .map_err(|e| AppError::Rpc(format!("Failed to get mint accounts: {}", e)))?;
+
+            for ((address, _), account) in chunk.iter().zip(accounts) {
+                if let Some(value) = account.and_then(|a| a.data.get(MINT_DECIMALS_OFFSET).copied()) {
+                    decimals.insert(address.to_string(), value);
+                }
+            }
+        }
+
+        Ok(decimals)
+    }
+
+    /// Solana targets a fixed slot time rather than variable blocks
+    async fn get_average_block_time_secs(&self) -> Result<f64> {
+        Ok(SLOT_TIME_SECS)
+    }
 }
@@ -1,4 +1,5 @@
 use bip39::Mnemonic;
+use ed25519_dalek::{ Signer as _, SigningKey, Verifier, VerifyingKey };
 use solana_keypair::Keypair;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signer::{SeedDerivable, Signer};
@@ -72,6 +73,25 @@ pub fn validate_address(address: &str) -> bool {
     address.parse::<Pubkey>().is_ok()
 }
 
+/// Sign `message` with the Ed25519 key behind `secret` and verify it against `address`
+pub fn verify_ownership(address: &str, secret: &str, message: &str) -> Result<bool> {
+    let expected: Pubkey = address.parse().map_err(|_| AppError::InvalidAddress)?;
+    let keypair_bytes = bs58
+        ::decode(detect_and_restore(secret, 0)?.private_key)
+        .into_vec()
+        .map_err(|_| AppError::InvalidPrivateKey)?;
+    // A Solana keypair is the 32-byte secret followed by the public key
+    let secret_bytes: [u8; 32] = keypair_bytes
+        .get(..32)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(AppError::InvalidPrivateKey)?;
+
+    let signature = SigningKey::from_bytes(&secret_bytes).sign(message.as_bytes());
+    let verifying_key = VerifyingKey::from_bytes(&expected.to_bytes()).map_err(|_| AppError::InvalidAddress)?;
+
+    Ok(verifying_key.verify(message.as_bytes(), &signature).is_ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,6 +116,15 @@ mod tests {
         assert_eq!(wallet1.address, wallet2.address);
     }
 
+    #[test]
+    fn test_verify_ownership() {
+        let wallet = generate_wallet(0).unwrap();
+        let other = generate_wallet(0).unwrap();
+
+        assert!(verify_ownership(&wallet.address, &wallet.private_key, "verify_ownership:test:0").unwrap());
+        assert!(!verify_ownership(&other.address, &wallet.private_key, "verify_ownership:test:0").unwrap());
+    }
+
     #[test]
     fn test_validate_address() {
         let wallet = generate_wallet(0).unwrap();
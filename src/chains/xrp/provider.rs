@@ -128,6 +128,7 @@ impl ChainProvider for XrpProvider {
                     balance: "0.000000".to_string(),
                     symbol: "XRP".to_string(),
                     decimals: 6,
+                    usd_value: None,
                 });
             }
             return Err(AppError::External(format!("XRP error: {}", error)));
@@ -143,6 +144,7 @@ impl ChainProvider for XrpProvider {
             balance: Self::drops_to_xrp(&balance_drops),
             symbol: "XRP".to_string(),
             decimals: 6,
+            usd_value: None,
         })
     }
 
@@ -191,11 +193,15 @@ impl ChainProvider for XrpProvider {
 
         Ok(GasEstimate {
             estimated_gas: fee_drops,
+            gas_limit: fee_drops,
             gas_price: Some(format!("{} drops", fee_drops)),
             max_fee_per_gas: None,
             max_priority_fee_per_gas: None,
             total_cost_native: Self::drops_to_xrp(&fee_drops.to_string()),
             total_cost_usd: None,
+            l1_data_fee_native: None,
+            l1_data_fee_usd: None,
+            confidence_pct: None,
         })
     }
 
@@ -1,9 +1,13 @@
 use std::collections::HashMap;
 use std::env;
+use std::net::IpAddr;
 
 use serde::Deserialize;
 
-use crate::enums::Chain;
+use crate::enums::{ Chain, RecurringType };
+
+/// Budget for each startup probe of the database and RPC endpoints
+const VALIDATE_TIMEOUT_SECS: u64 = 10;
 
 #[derive(Debug, Clone, Deserialize)]
 pub enum NetworkMode {
@@ -11,6 +15,102 @@ pub enum NetworkMode {
     Mainnet,
 }
 
+/// Source of EIP-1559 fee predictions for gas estimates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasStationProvider {
+    Node,
+    EthGasStation,
+    Blocknative,
+}
+
+/// Where the bot keeps users' in-progress dialogues
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogueStorageBackend {
+    Memory,
+    Database,
+}
+
+/// Broadcast failures `TransferService` retries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryableError {
+    /// The node didn't answer in time. It may still have accepted the transaction, in which
+    /// case a retry sends it a second time, so this is off unless configured.
+    Timeout,
+    /// The RPC couldn't be reached
+    NetworkError,
+    /// The cached nonce fell behind the chain; the provider re-syncs it before the retry
+    NonceTooLow,
+}
+
+impl RetryableError {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "timeout" => Some(Self::Timeout),
+            "network" => Some(Self::NetworkError),
+            "nonce_too_low" => Some(Self::NonceTooLow),
+            _ => None,
+        }
+    }
+}
+
+/// Retry policy for broadcasting transfers
+#[derive(Debug, Clone)]
+pub struct TransferConfig {
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub backoff_multiplier: f64,
+    pub retry_on: Vec<RetryableError>,
+}
+
+impl TransferConfig {
+    /// Wait before retry number `retry` (0-based): 1s, 2s, 4s... with the defaults
+    pub fn backoff(&self, retry: u32) -> std::time::Duration {
+        let ms = (self.initial_backoff_ms as f64) * self.backoff_multiplier.powi(retry as i32);
+        std::time::Duration::from_millis(ms as u64)
+    }
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 1000,
+            backoff_multiplier: 2.0,
+            // A failed broadcast may still have reached the mempool, so retrying is opt-in
+            retry_on: Vec::new(),
+        }
+    }
+}
+
+/// How serious a problem `Config::validate` found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSeverity {
+    /// The bot can't run safely; startup aborts
+    Fatal,
+    /// Logged, startup continues
+    Warn,
+}
+
+/// A misconfiguration found by `Config::validate`
+#[derive(Debug, Clone)]
+pub struct ConfigWarning {
+    /// Environment variable (or pattern) the problem comes from
+    pub field: String,
+    pub message: String,
+    pub severity: ConfigSeverity,
+}
+
+impl ConfigWarning {
+    fn fatal(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into(), severity: ConfigSeverity::Fatal }
+    }
+
+    fn warn(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into(), severity: ConfigSeverity::Warn }
+    }
+}
+
 /// Per-chain configuration resolved from environment variables.
 #[derive(Debug, Clone)]
 pub struct ChainConfig {
@@ -19,6 +119,10 @@ pub struct ChainConfig {
     pub explorer_url: String,
     pub chain_id: Option<u64>,
     pub native_symbol: String,
+    /// Key sent with every request to the chain's API (e.g. a Blockfrost project id)
+    pub api_key: Option<String>,
+    /// WebSocket endpoint for `eth_subscribe` (EVM chains); balance watches poll without one
+    pub ws_url: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,11 +131,52 @@ pub struct Config {
     pub database_url: String,
     pub encryption_key: Vec<u8>,
     pub chain_configs: HashMap<Chain, ChainConfig>,
+    /// Testnet chains for users who switch to testnet mode while running on mainnet
+    pub testnet_chain_configs: HashMap<Chain, ChainConfig>,
     pub alchemy_api_key: Option<String>,
+    /// Etherscan (v2, all EVM chains) key for reading on-chain history of imported wallets
+    pub etherscan_api_key: Option<String>,
     pub server_host: String,
     pub server_port: u16,
     pub rate_limit_per_user: u32,
+    /// Burst size of each user's command token bucket
+    pub rate_limit_max_tokens: f64,
+    /// Tokens regained per second by each user's command bucket
+    pub rate_limit_refill_rate: f64,
     pub telegram_bot_token: String,
+    /// Swaps whose quoted price impact exceeds this percentage are rejected
+    pub max_price_impact_pct: f64,
+    /// How often the price monitor refreshes watched symbols
+    pub price_monitor_interval_secs: u64,
+    /// How long a wallet's discovered tokens are served from the cache before re-checking the chain
+    pub token_discovery_interval_secs: u64,
+    /// External fee predictor used for Ethereum mainnet estimates, falling back to the node
+    pub gas_station_provider: GasStationProvider,
+    pub gas_station_api_key: Option<String>,
+    /// Let swaps through without honeypot/tax checks on the destination token
+    pub skip_security_checks: bool,
+    /// LND REST endpoint used to create Lightning invoices for Bitcoin wallets
+    pub lnd_rest_url: Option<String>,
+    /// Hex-encoded LND invoice macaroon
+    pub lnd_macaroon: Option<String>,
+    /// `Database` keeps dialogues across restarts, `Memory` drops them
+    pub dialogue_storage_backend: DialogueStorageBackend,
+    /// Client IPs allowed to call `/admin` endpoints; empty disables them
+    pub admin_allowed_ips: Vec<IpAddr>,
+    /// Key expected in the `X-Admin-Key` header of `/admin/alerts` requests; unset disables them
+    pub admin_api_key: Option<String>,
+    /// Telegram user allowed to run admin bot commands such as /checkalerts
+    pub admin_telegram_user_id: Option<i64>,
+    /// Answer commands in group chats, where wallets belong to the group and members have roles
+    pub group_wallet_mode: bool,
+    /// How transfers are re-broadcast after transient RPC failures
+    pub transfer_retry_config: TransferConfig,
+    /// Aave V3 subgraph endpoint per chain, read for lending positions
+    pub aave_subgraph_urls: HashMap<Chain, String>,
+    /// How often auto-compound plans claim and re-supply rewards
+    pub auto_compound_interval: RecurringType,
+    /// /watchbalance notifies once a balance moves by at least this percentage
+    pub balance_change_threshold_pct: f64,
 }
 
 impl Config {
@@ -57,60 +202,237 @@ impl Config {
         }
 
         let is_testnet = matches!(network_mode, NetworkMode::Testnet);
-        let mode_suffix = if is_testnet { "TESTNET" } else { "MAINNET" };
-
-        // Build chain configs dynamically from env vars
-        let mut chain_configs = HashMap::new();
-
-        for &chain in Chain::all() {
-            let rpc_key = format!("{}_{}_RPC_URLS", chain.as_str(), mode_suffix);
-            let explorer_key = format!("{}_{}_EXPLORER_URL", chain.as_str(), mode_suffix);
-
-            // Only configure chains that have RPC URLs set
-            if let Ok(rpc_val) = env::var(&rpc_key) {
-                let rpc_urls = Self::parse_rpc_urls(&rpc_val)?;
-                let explorer_url = env::var(&explorer_key)
-                    .unwrap_or_else(|_| chain.explorer_url(is_testnet).to_string());
-
-                chain_configs.insert(chain, ChainConfig {
-                    chain,
-                    rpc_urls,
-                    explorer_url,
-                    chain_id: chain.chain_id(is_testnet),
-                    native_symbol: chain.native_symbol().to_string(),
-                });
-            }
-        }
 
+        let chain_configs = Self::load_chain_configs(is_testnet)?;
         if chain_configs.is_empty() {
             return Err("No chain RPC URLs configured. Set at least one *_RPC_URLS env var.".into());
         }
 
+        // In testnet mode every user is already on testnet
+        let testnet_chain_configs = if is_testnet {
+            HashMap::new()
+        } else {
+            Self::load_chain_configs(true)?
+        };
+
         let alchemy_api_key = env::var("ALCHEMY_API_KEY").ok();
+        let etherscan_api_key = env::var("ETHERSCAN_API_KEY").ok().filter(|k| !k.is_empty());
 
         let server_host = env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
         let server_port = env::var("SERVER_PORT")
             .unwrap_or_else(|_| "8080".to_string())
             .parse()?;
-        let rate_limit_per_user = env::var("RATE_LIMIT_PER_USER")
+        let rate_limit_per_user: u32 = env::var("RATE_LIMIT_PER_USER")
             .unwrap_or_else(|_| "60".to_string())
             .parse()?;
+        let rate_limit_max_tokens: f64 = env::var("RATE_LIMIT_MAX_TOKENS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()?;
+        // Defaults to the per-minute limit spread over each second
+        let rate_limit_refill_rate: f64 = match env::var("RATE_LIMIT_REFILL_RATE") {
+            Ok(val) => val.parse()?,
+            Err(_) => (rate_limit_per_user as f64) / 60.0,
+        };
 
         let telegram_bot_token = env::var("TELEGRAM_BOT_TOKEN")?;
 
+        let max_price_impact_pct = env::var("MAX_PRICE_IMPACT_PCT")
+            .unwrap_or_else(|_| "5.0".to_string())
+            .parse()?;
+
+        let price_monitor_interval_secs: u64 = env::var("PRICE_MONITOR_INTERVAL_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()?;
+        if price_monitor_interval_secs == 0 {
+            return Err("PRICE_MONITOR_INTERVAL_SECS must be greater than 0".into());
+        }
+
+        let token_discovery_interval_secs: u64 = env::var("TOKEN_DISCOVERY_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()?;
+
+        let gas_station_provider = match
+            env::var("GAS_STATION_PROVIDER")
+                .unwrap_or_else(|_| "node".to_string())
+                .to_lowercase()
+                .as_str()
+        {
+            "node" => GasStationProvider::Node,
+            "ethgasstation" => GasStationProvider::EthGasStation,
+            "blocknative" => GasStationProvider::Blocknative,
+            _ => {
+                return Err(
+                    "GAS_STATION_PROVIDER must be 'node', 'ethgasstation' or 'blocknative'".into()
+                );
+            }
+        };
+        let gas_station_api_key = env::var("GAS_STATION_API_KEY").ok();
+        if gas_station_provider == GasStationProvider::Blocknative && gas_station_api_key.is_none() {
+            return Err("GAS_STATION_API_KEY is required for the Blocknative gas station".into());
+        }
+
+        let skip_security_checks = env::var("SKIP_SECURITY_CHECKS")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let lnd_rest_url = env::var("LND_REST_URL").ok().filter(|v| !v.is_empty());
+        let lnd_macaroon = env::var("LND_MACAROON").ok().filter(|v| !v.is_empty());
+
+        let dialogue_storage_backend = match
+            env::var("DIALOGUE_STORAGE_BACKEND")
+                .unwrap_or_else(|_| "database".to_string())
+                .to_lowercase()
+                .as_str()
+        {
+            "memory" => DialogueStorageBackend::Memory,
+            "database" => DialogueStorageBackend::Database,
+            _ => {
+                return Err("DIALOGUE_STORAGE_BACKEND must be 'memory' or 'database'".into());
+            }
+        };
+
+        let admin_allowed_ips = env::var("ADMIN_ALLOWED_IPS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| ip.parse::<IpAddr>().map_err(|_| format!("Invalid ADMIN_ALLOWED_IPS entry: {}", ip)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let admin_api_key = env::var("ADMIN_API_KEY").ok().filter(|k| !k.is_empty());
+        let admin_telegram_user_id = match env::var("ADMIN_TELEGRAM_USER_ID") {
+            Ok(val) if !val.is_empty() => Some(val.parse()?),
+            _ => None,
+        };
+
+        let group_wallet_mode = env::var("GROUP_WALLET_MODE")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        let default_retry = TransferConfig::default();
+        let transfer_retry_config = TransferConfig {
+            max_retries: match env::var("TRANSFER_MAX_RETRIES") {
+                Ok(val) => val.parse()?,
+                Err(_) => default_retry.max_retries,
+            },
+            initial_backoff_ms: match env::var("TRANSFER_INITIAL_BACKOFF_MS") {
+                Ok(val) => val.parse()?,
+                Err(_) => default_retry.initial_backoff_ms,
+            },
+            backoff_multiplier: match env::var("TRANSFER_BACKOFF_MULTIPLIER") {
+                Ok(val) => val.parse()?,
+                Err(_) => default_retry.backoff_multiplier,
+            },
+            retry_on: match env::var("TRANSFER_RETRY_ON") {
+                Ok(val) =>
+                    val
+                        .split(',')
+                        .filter(|v| !v.trim().is_empty())
+                        .map(|v| {
+                            RetryableError::parse(v).ok_or_else(|| {
+                                format!(
+                                    "Invalid TRANSFER_RETRY_ON entry '{}': use timeout, network or nonce_too_low",
+                                    v.trim()
+                                )
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                Err(_) => default_retry.retry_on,
+            },
+        };
+        if transfer_retry_config.backoff_multiplier < 1.0 {
+            return Err("TRANSFER_BACKOFF_MULTIPLIER must be at least 1.0".into());
+        }
+
+        let aave_subgraph_urls = Chain::all()
+            .iter()
+            .filter_map(|&chain| {
+                env::var(format!("{}_AAVE_SUBGRAPH_URL", chain.as_str()))
+                    .ok()
+                    .filter(|url| !url.is_empty())
+                    .map(|url| (chain, url))
+            })
+            .collect();
+        let auto_compound_interval: RecurringType = env::var("AUTO_COMPOUND_INTERVAL")
+            .unwrap_or_else(|_| "weekly".to_string())
+            .parse()?;
+
+        let balance_change_threshold_pct: f64 = env::var("BALANCE_CHANGE_THRESHOLD_PCT")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| "BALANCE_CHANGE_THRESHOLD_PCT must be a number")?;
+        if balance_change_threshold_pct <= 0.0 {
+            return Err("BALANCE_CHANGE_THRESHOLD_PCT must be positive".into());
+        }
+
         Ok(Config {
             network_mode,
             database_url,
             encryption_key,
             chain_configs,
+            testnet_chain_configs,
             alchemy_api_key,
+            etherscan_api_key,
             server_host,
             server_port,
             rate_limit_per_user,
+            rate_limit_max_tokens,
+            rate_limit_refill_rate,
             telegram_bot_token,
+            max_price_impact_pct,
+            price_monitor_interval_secs,
+            token_discovery_interval_secs,
+            gas_station_provider,
+            gas_station_api_key,
+            skip_security_checks,
+            lnd_rest_url,
+            lnd_macaroon,
+            dialogue_storage_backend,
+            admin_allowed_ips,
+            admin_api_key,
+            admin_telegram_user_id,
+            group_wallet_mode,
+            transfer_retry_config,
+            aave_subgraph_urls,
+            auto_compound_interval,
+            balance_change_threshold_pct,
         })
     }
 
+    /// Build chain configs dynamically from `<CHAIN>_<MAINNET|TESTNET>_*` env vars
+    fn load_chain_configs(
+        is_testnet: bool
+    ) -> Result<HashMap<Chain, ChainConfig>, Box<dyn std::error::Error>> {
+        let mode_suffix = if is_testnet { "TESTNET" } else { "MAINNET" };
+        let mut chain_configs = HashMap::new();
+
+        for &chain in Chain::all() {
+            let rpc_key = format!("{}_{}_RPC_URLS", chain.as_str(), mode_suffix);
+            let explorer_key = format!("{}_{}_EXPLORER_URL", chain.as_str(), mode_suffix);
+            let api_key_key = format!("{}_{}_API_KEY", chain.as_str(), mode_suffix);
+            let ws_key = format!("{}_{}_WS_URL", chain.as_str(), mode_suffix);
+
+            // Only configure chains that have RPC URLs set
+            if let Ok(rpc_val) = env::var(&rpc_key) {
+                let rpc_urls = Self::parse_rpc_urls(&rpc_val)?;
+                let explorer_url = env::var(&explorer_key)
+                    .unwrap_or_else(|_| chain.explorer_url(is_testnet).to_string());
+
+                chain_configs.insert(chain, ChainConfig {
+                    chain,
+                    rpc_urls,
+                    explorer_url,
+                    chain_id: chain.chain_id(is_testnet),
+                    native_symbol: chain.native_symbol().to_string(),
+                    api_key: env::var(&api_key_key).ok().filter(|k| !k.is_empty()),
+                    ws_url: env::var(&ws_key).ok().filter(|url| !url.is_empty()),
+                });
+            }
+        }
+
+        Ok(chain_configs)
+    }
+
     fn parse_rpc_urls(urls_str: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let urls: Vec<String> = urls_str
             .split(',')
@@ -125,43 +447,232 @@ impl Config {
         Ok(urls)
     }
 
+    /// Check the loaded settings against the outside world: the database answers, EVM RPC
+    /// endpoints serve the chain they're configured for, and secrets look usable.
+    /// Returns every problem found; the caller decides what to do with `Fatal` ones.
+    pub async fn validate(&self) -> crate::error::Result<Vec<ConfigWarning>> {
+        let mut warnings = Vec::new();
+
+        if self.encryption_key.len() < 16 {
+            warnings.push(
+                ConfigWarning::fatal("ENCRYPTION_KEY", "must be at least 32 hex characters")
+            );
+        } else if distinct_bytes(&self.encryption_key) < 16 {
+            warnings.push(
+                ConfigWarning::warn(
+                    "ENCRYPTION_KEY",
+                    "has low entropy (repeated bytes); generate one with `openssl rand -hex 32`"
+                )
+            );
+        }
+
+        if !is_valid_bot_token(&self.telegram_bot_token) {
+            warnings.push(
+                ConfigWarning::fatal(
+                    "TELEGRAM_BOT_TOKEN",
+                    "is not a Telegram bot token (expected <digits>:<35 characters>)"
+                )
+            );
+        }
+
+        if self.server_port == 0 {
+            warnings.push(ConfigWarning::fatal("SERVER_PORT", "must be between 1 and 65535"));
+        }
+
+        if let Err(e) = self.check_database().await {
+            warnings.push(ConfigWarning::fatal("DATABASE_URL", format!("database unreachable: {}", e)));
+        }
+
+        let client = reqwest::Client
+            ::builder()
+            .timeout(std::time::Duration::from_secs(VALIDATE_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        let mode = if self.is_testnet() { "TESTNET" } else { "MAINNET" };
+        let configs = self.chain_configs
+            .values()
+            .map(|cc| (cc, mode))
+            .chain(self.testnet_chain_configs.values().map(|cc| (cc, "TESTNET")));
+        for (chain_config, mode) in configs {
+            let field = format!("{}_{}_RPC_URLS", chain_config.chain.as_str(), mode);
+            warnings.extend(check_chain_ids(&client, chain_config, &field).await);
+        }
+
+        Ok(warnings)
+    }
+
+    async fn check_database(&self) -> std::result::Result<(), sea_orm::DbErr> {
+        let mut options = sea_orm::ConnectOptions::new(self.database_url.clone());
+        options
+            .max_connections(1)
+            .connect_timeout(std::time::Duration::from_secs(VALIDATE_TIMEOUT_SECS))
+            .sqlx_logging(false);
+        let db = sea_orm::Database::connect(options).await?;
+        db.ping().await?;
+        db.close().await
+    }
+
     /// Whether we are running in testnet mode.
     pub fn is_testnet(&self) -> bool {
         matches!(self.network_mode, NetworkMode::Testnet)
     }
 
+    /// Testnet RPC URLs for a chain, used for wallets created in per-user testnet mode.
+    pub fn testnet_rpc_urls(&self, chain: &Chain) -> Option<&[String]> {
+        let configs = if self.is_testnet() { &self.chain_configs } else { &self.testnet_chain_configs };
+        configs.get(chain).map(|cc| cc.rpc_urls.as_slice())
+    }
+
     /// Get the explorer base URL for a specific chain.
-    pub fn get_explorer_url(&self, chain: &str) -> String {
+    /// `testnet` selects testnet explorers even when the server runs on mainnet.
+    pub fn get_explorer_url(&self, chain: &str, testnet: bool) -> String {
+        let use_testnet = testnet || self.is_testnet();
+        let configs = if use_testnet && !self.is_testnet() {
+            &self.testnet_chain_configs
+        } else {
+            &self.chain_configs
+        };
+
         match chain.parse::<Chain>() {
-            Ok(c) => self
-                .chain_configs
+            Ok(c) => configs
                 .get(&c)
                 .map(|cc| cc.explorer_url.clone())
-                .unwrap_or_else(|| c.explorer_url(self.is_testnet()).to_string()),
+                .unwrap_or_else(|| c.explorer_url(use_testnet).to_string()),
             Err(_) => "https://etherscan.io".to_string(),
         }
     }
 
     /// Generate a transaction explorer URL for a specific chain and tx hash.
-    pub fn get_tx_explorer_url(&self, chain: &str, tx_hash: &str) -> String {
-        let base_url = self.get_explorer_url(chain);
+    pub fn get_tx_explorer_url(&self, chain: &str, testnet: bool, tx_hash: &str) -> String {
+        let base_url = self.get_explorer_url(chain, testnet);
         format!("{}/tx/{}", base_url, tx_hash)
     }
 
     /// Generate an address explorer URL for a specific chain and address.
-    pub fn get_address_explorer_url(&self, chain: &str, address: &str) -> String {
-        let base_url = self.get_explorer_url(chain);
+    pub fn get_address_explorer_url(&self, chain: &str, testnet: bool, address: &str) -> String {
+        let base_url = self.get_explorer_url(chain, testnet);
         format!("{}/address/{}", base_url, address)
     }
 
     /// Generate a token explorer URL for a specific chain and token contract.
-    pub fn get_token_explorer_url(&self, chain: &str, token_address: &str) -> String {
-        let base_url = self.get_explorer_url(chain);
+    pub fn get_token_explorer_url(&self, chain: &str, testnet: bool, token_address: &str) -> String {
+        let base_url = self.get_explorer_url(chain, testnet);
         format!("{}/token/{}", base_url, token_address)
     }
 
+    /// WebSocket endpoints by chain and testnet flag, for block subscriptions
+    pub fn ws_urls(&self) -> HashMap<(Chain, bool), String> {
+        let networks = [
+            (&self.chain_configs, self.is_testnet()),
+            (&self.testnet_chain_configs, true),
+        ];
+        networks
+            .into_iter()
+            .flat_map(|(configs, is_testnet)| {
+                configs
+                    .values()
+                    .filter_map(move |cc| cc.ws_url.clone().map(|url| ((cc.chain, is_testnet), url)))
+            })
+            .collect()
+    }
+
     /// Get list of configured chains.
     pub fn configured_chains(&self) -> Vec<Chain> {
         self.chain_configs.keys().copied().collect()
     }
 }
+
+/// `<bot id>:<35-character secret>`, as issued by @BotFather
+fn is_valid_bot_token(token: &str) -> bool {
+    let Some((id, secret)) = token.split_once(':') else {
+        return false;
+    };
+    !id.is_empty() &&
+        id.chars().all(|c| c.is_ascii_digit()) &&
+        secret.len() == 35 &&
+        secret.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn distinct_bytes(bytes: &[u8]) -> usize {
+    bytes.iter().collect::<std::collections::HashSet<_>>().len()
+}
+
+/// Ask every RPC URL of an EVM chain for its chain ID. A node serving a different chain is fatal,
+/// since transactions would be signed for the wrong network; an unreachable one only warns.
+async fn check_chain_ids(
+    client: &reqwest::Client,
+    chain_config: &ChainConfig,
+    field: &str
+) -> Vec<ConfigWarning> {
+    let Some(expected) = chain_config.chain_id.filter(|_| chain_config.chain.is_evm()) else {
+        return Vec::new();
+    };
+
+    let mut warnings = Vec::new();
+    let body = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_chainId", "params": [] });
+    for url in &chain_config.rpc_urls {
+        let host = reqwest::Url
+            ::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_else(|| "invalid URL".to_string());
+
+        let response = async {
+            client
+                .post(url)
+                .json(&body)
+                .send().await?
+                .error_for_status()?
+                .json::<serde_json::Value>().await
+        };
+        match response.await {
+            Ok(response) => {
+                let chain_id = response["result"]
+                    .as_str()
+                    .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok());
+                match chain_id {
+                    Some(id) if id == expected => {}
+                    Some(id) =>
+                        warnings.push(
+                            ConfigWarning::fatal(
+                                field,
+                                format!(
+                                    "{} serves chain ID {}, expected {} for {}",
+                                    host,
+                                    id,
+                                    expected,
+                                    chain_config.chain
+                                )
+                            )
+                        ),
+                    None =>
+                        warnings.push(
+                            ConfigWarning::warn(field, format!("{} returned no chain ID", host))
+                        ),
+                }
+            }
+            Err(e) => {
+                warnings.push(
+                    ConfigWarning::warn(
+                        field,
+                        format!("{} unreachable: {}", host, e.without_url())
+                    )
+                );
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bot_token_format() {
+        assert!(is_valid_bot_token("123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_"));
+        assert!(!is_valid_bot_token("123456789:short"));
+        assert!(!is_valid_bot_token("bot123:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_"));
+        assert!(!is_valid_bot_token("AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_"));
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::{ Cursor, Read, Write };
+
+use aes_gcm::{ aead::{ Aead, KeyInit }, Aes256Gcm, Nonce };
+use argon2::Argon2;
+use rand::rngs::OsRng;
+use rand::TryRngCore;
+use serde::{ Deserialize, Serialize };
+use zip::write::FileOptions;
+
+use crate::error::{ AppError, Result };
+
+pub const BACKUP_VERSION: &str = "v1";
+
+const METADATA_FILE: &str = "metadata.json";
+const PAYLOAD_FILE: &str = "wallets.enc";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Unencrypted header stored alongside the payload in the backup archive
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupMetadata {
+    version: String,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Encrypt `payload` with a key derived from `password` and pack it into a zip archive
+pub fn seal(payload: &[u8], password: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    OsRng.try_fill_bytes(&mut salt)
+        .and_then(|_| OsRng.try_fill_bytes(&mut nonce_bytes))
+        .map_err(|e| AppError::Encryption(format!("RNG error: {}", e)))?;
+
+    let cipher = derive_cipher(password, &salt)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), payload)
+        .map_err(|e| AppError::Encryption(e.to_string()))?;
+
+    let metadata = BackupMetadata {
+        version: BACKUP_VERSION.to_string(),
+        kdf: "argon2id".to_string(),
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce_bytes),
+        created_at: chrono::Utc::now(),
+    };
+    let metadata_json = serde_json
+        ::to_vec_pretty(&metadata)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default();
+    zip.start_file(METADATA_FILE, options).map_err(zip_error)?;
+    zip.write_all(&metadata_json).map_err(|e| AppError::Internal(e.to_string()))?;
+    zip.start_file(PAYLOAD_FILE, options).map_err(zip_error)?;
+    zip.write_all(&ciphertext).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let cursor = zip.finish().map_err(zip_error)?;
+    Ok(cursor.into_inner())
+}
+
+/// Unpack a backup archive and decrypt its payload with `password`
+pub fn open(data: &[u8], password: &str) -> Result<Vec<u8>> {
+    let mut archive = zip::ZipArchive
+        ::new(Cursor::new(data))
+        .map_err(|e| AppError::InvalidInput(format!("Not a valid backup file: {}", e)))?;
+
+    let metadata: BackupMetadata = serde_json
+        ::from_slice(&read_entry(&mut archive, METADATA_FILE)?)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid backup metadata: {}", e)))?;
+    if metadata.version != BACKUP_VERSION {
+        return Err(
+            AppError::InvalidInput(format!("Unsupported backup version: {}", metadata.version))
+        );
+    }
+
+    let salt = hex
+        ::decode(&metadata.salt)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid backup salt: {}", e)))?;
+    let nonce_bytes = hex
+        ::decode(&metadata.nonce)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid backup nonce: {}", e)))?;
+    if nonce_bytes.len() != NONCE_LEN {
+        return Err(AppError::InvalidInput("Invalid backup nonce".to_string()));
+    }
+
+    let ciphertext = read_entry(&mut archive, PAYLOAD_FILE)?;
+    let cipher = derive_cipher(password, &salt)?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce_bytes), ciphertext.as_slice())
+        .map_err(|_| AppError::Encryption("Wrong password or corrupted backup".to_string()))
+}
+
+fn derive_cipher(password: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Encryption(format!("Key derivation failed: {}", e)))?;
+
+    Aes256Gcm::new_from_slice(&key).map_err(|e| AppError::Encryption(e.to_string()))
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Vec<u8>> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|_| AppError::InvalidInput(format!("Backup is missing {}", name)))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).map_err(|e| AppError::InvalidInput(e.to_string()))?;
+    Ok(buf)
+}
+
+fn zip_error(e: zip::result::ZipError) -> AppError {
+    AppError::Internal(format!("Failed to build backup archive: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let payload = br#"[{"chain":"ETH","private_key":"0xabc"}]"#;
+        let sealed = seal(payload, "correct horse").unwrap();
+
+        assert_eq!(open(&sealed, "correct horse").unwrap(), payload);
+        assert!(open(&sealed, "wrong password").is_err());
+    }
+}
@@ -1,24 +1,50 @@
 use aes_gcm::{ aead::{ Aead, KeyInit }, Aes256Gcm, Nonce };
 use rand::rngs::OsRng;
 use rand::TryRngCore;
+use std::sync::RwLock;
 
 use crate::error::{ AppError, Result };
 
 pub struct Encryptor {
-    cipher: Aes256Gcm,
+    ciphers: RwLock<Ciphers>,
+}
+
+struct Ciphers {
+    active_key: Vec<u8>,
+    active: Aes256Gcm,
+    /// The cipher `active` replaced in a `rekey`, still accepted for decryption
+    previous: Option<Aes256Gcm>,
 }
 
 impl Encryptor {
     pub fn new(key: &[u8]) -> Result<Self> {
+        let ciphers = Ciphers { active_key: key.to_vec(), active: Self::cipher(key)?, previous: None };
+        Ok(Self { ciphers: RwLock::new(ciphers) })
+    }
+
+    fn cipher(key: &[u8]) -> Result<Aes256Gcm> {
         if key.len() != 32 {
             return Err(AppError::Encryption("Encryption key must be 32 bytes".to_string()));
         }
 
-        let cipher = Aes256Gcm::new_from_slice(key).map_err(|e|
-            AppError::Encryption(e.to_string())
-        )?;
+        Aes256Gcm::new_from_slice(key).map_err(|e| AppError::Encryption(e.to_string()))
+    }
 
-        Ok(Self { cipher })
+    /// Encrypt with `key` from now on, for every holder of this encryptor. Data sealed with
+    /// the previous key can still be decrypted until the process restarts. Rekeying to the
+    /// key already in use is a no-op, so the previous key isn't lost.
+    pub fn rekey(&self, key: &[u8]) -> Result<()> {
+        let cipher = Self::cipher(key)?;
+        let mut ciphers = self.ciphers
+            .write()
+            .map_err(|_| AppError::Encryption("Encryptor lock poisoned".to_string()))?;
+        if ciphers.active_key == key {
+            return Ok(());
+        }
+        ciphers.active_key = key.to_vec();
+        let previous = std::mem::replace(&mut ciphers.active, cipher);
+        ciphers.previous = Some(previous);
+        Ok(())
     }
 
     pub fn encrypt(&self, plaintext: &str) -> Result<String> {
@@ -27,7 +53,10 @@ impl Encryptor {
             .map_err(|e| AppError::Encryption(format!("RNG error: {}", e)))?;
         let nonce = Nonce::from_slice(&nonce_bytes);
 
-        let ciphertext = self.cipher
+        let ciphers = self.ciphers
+            .read()
+            .map_err(|_| AppError::Encryption("Encryptor lock poisoned".to_string()))?;
+        let ciphertext = ciphers.active
             .encrypt(nonce, plaintext.as_bytes())
             .map_err(|e| AppError::Encryption(e.to_string()))?;
 
@@ -50,9 +79,18 @@ impl Encryptor {
         let (nonce_bytes, ciphertext) = combined.split_at(12);
         let nonce = Nonce::from_slice(nonce_bytes);
 
-        let plaintext = self.cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| AppError::Encryption(e.to_string()))?;
+        let ciphers = self.ciphers
+            .read()
+            .map_err(|_| AppError::Encryption("Encryptor lock poisoned".to_string()))?;
+        let plaintext = match (ciphers.active.decrypt(nonce, ciphertext), &ciphers.previous) {
+            (Ok(plaintext), _) => plaintext,
+            (Err(_), Some(previous)) => previous
+                .decrypt(nonce, ciphertext)
+                .map_err(|e| AppError::Encryption(e.to_string()))?,
+            (Err(e), None) => {
+                return Err(AppError::Encryption(e.to_string()));
+            }
+        };
 
         String::from_utf8(plaintext).map_err(|e|
             AppError::Encryption(format!("Invalid UTF-8: {}", e))
@@ -92,4 +130,22 @@ mod tests {
         assert_eq!(encryptor.decrypt(&encrypted1).unwrap(), plaintext);
         assert_eq!(encryptor.decrypt(&encrypted2).unwrap(), plaintext);
     }
+
+    #[test]
+    fn test_rekey() {
+        let encryptor = Encryptor::new(&[0u8; 32]).unwrap();
+        let old = encryptor.encrypt("secret").unwrap();
+
+        encryptor.rekey(&[1u8; 32]).unwrap();
+        let new = encryptor.encrypt("secret").unwrap();
+
+        // New data uses the new key; data sealed before the rekey still opens
+        assert!(Encryptor::new(&[0u8; 32]).unwrap().decrypt(&new).is_err());
+        assert_eq!(Encryptor::new(&[1u8; 32]).unwrap().decrypt(&new).unwrap(), "secret");
+        assert_eq!(encryptor.decrypt(&old).unwrap(), "secret");
+
+        // Repeating the rekey keeps the original key usable
+        encryptor.rekey(&[1u8; 32]).unwrap();
+        assert_eq!(encryptor.decrypt(&old).unwrap(), "secret");
+    }
 }
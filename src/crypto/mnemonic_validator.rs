@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+
+use bip39::Language;
+use serde::Serialize;
+
+/// BIP39's shortest mnemonic (128 bits of entropy)
+const MIN_WORDS: usize = 12;
+
+/// Longest repeating cycle of words still treated as a trivial pattern
+const MAX_PATTERN_PERIOD: usize = 3;
+
+/// Published mnemonics anyone can sweep: the BIP39 reference test vectors and dev tool defaults
+const KNOWN_TEST_MNEMONICS: &[&str] = &[
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+    "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    "letter advice cage absurd amount doctor acoustic avoid letter advice cage above",
+    "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong",
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+    "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+    "ozone drill grab fiber curtain grace pudding thank cruise elder eight picnic",
+    "scheme spot photo card baby mountain device kick cradle pact join borrow",
+    "cat swing flag economy stadium alone churn speed unique patch report train",
+    "vessel ladder alter error federal sibling chat ability sun glass valve picture",
+    // Hardhat, Anvil and Ganache default accounts
+    "test test test test test test test test test test test junk",
+    "candy maple cake sugar pudding cream honey rich smooth crumble sweet treat",
+    "myth like bonus scare over problem client lizard pioneer submit female collect",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    TooFewWords,
+    UnknownWords,
+    DuplicateWords,
+    KnownTestMnemonic,
+    TrivialPattern,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MnemonicWarning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+/// Spots mnemonics that are valid enough to restore but easy for someone else to guess
+pub struct MnemonicValidator;
+
+impl MnemonicValidator {
+    /// Every weakness found in `mnemonic`; empty when it looks like a properly generated phrase
+    pub fn validate(mnemonic: &str) -> Vec<MnemonicWarning> {
+        let normalized = mnemonic.to_lowercase();
+        let words: Vec<&str> = normalized.split_whitespace().collect();
+        let mut warnings = Vec::new();
+
+        if words.len() < MIN_WORDS {
+            warnings.push(MnemonicWarning {
+                kind: WarningKind::TooFewWords,
+                message: format!(
+                    "Only {} words; BIP39 mnemonics have at least {}",
+                    words.len(),
+                    MIN_WORDS
+                ),
+            });
+        }
+
+        let unknown: Vec<&str> = words
+            .iter()
+            .copied()
+            .filter(|w| Language::English.find_word(w).is_none())
+            .collect();
+        if !unknown.is_empty() {
+            warnings.push(MnemonicWarning {
+                kind: WarningKind::UnknownWords,
+                message: format!("Not in the BIP39 wordlist: {}", unknown.join(", ")),
+            });
+        }
+
+        let mut seen = HashSet::new();
+        let duplicates: HashSet<&str> = words
+            .iter()
+            .copied()
+            .filter(|w| !seen.insert(*w))
+            .collect();
+
+        if KNOWN_TEST_MNEMONICS.contains(&words.join(" ").as_str()) {
+            warnings.push(MnemonicWarning {
+                kind: WarningKind::KnownTestMnemonic,
+                message: "This is a published test mnemonic; anyone can take funds sent to it".to_string(),
+            });
+        } else if is_trivial_pattern(&words) {
+            warnings.push(MnemonicWarning {
+                kind: WarningKind::TrivialPattern,
+                message: "The words follow a simple repeating pattern and are easy to guess".to_string(),
+            });
+        } else if !duplicates.is_empty() {
+            let mut duplicates: Vec<&str> = duplicates.into_iter().collect();
+            duplicates.sort_unstable();
+            warnings.push(MnemonicWarning {
+                kind: WarningKind::DuplicateWords,
+                message: format!(
+                    "Repeated words ({}) lower the entropy if the phrase was chosen by hand",
+                    duplicates.join(", ")
+                ),
+            });
+        }
+
+        warnings
+    }
+}
+
+/// All words but the last (the checksum word) repeat a cycle of up to `MAX_PATTERN_PERIOD` words
+fn is_trivial_pattern(words: &[&str]) -> bool {
+    let Some((_, body)) = words.split_last() else {
+        return false;
+    };
+    (1..=MAX_PATTERN_PERIOD)
+        .filter(|period| body.len() > period * 2)
+        .any(|period| body.iter().enumerate().all(|(i, w)| *w == body[i % period]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(mnemonic: &str) -> Vec<WarningKind> {
+        MnemonicValidator::validate(mnemonic).into_iter().map(|w| w.kind).collect()
+    }
+
+    #[test]
+    fn test_generated_mnemonic_has_no_warnings() {
+        // A random phrase repeats a word a few percent of the time, which is rightly flagged
+        let mnemonic = std::iter
+            ::repeat_with(|| bip39::Mnemonic::generate(12).unwrap().to_string())
+            .find(|m| m.split_whitespace().collect::<HashSet<_>>().len() == 12)
+            .unwrap();
+        assert!(kinds(&mnemonic).is_empty(), "unexpected warnings for {}", mnemonic);
+    }
+
+    #[test]
+    fn test_detects_weak_mnemonics() {
+        assert_eq!(
+            kinds("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"),
+            vec![WarningKind::KnownTestMnemonic]
+        );
+        assert_eq!(
+            kinds("zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zebra"),
+            vec![WarningKind::TrivialPattern]
+        );
+        assert_eq!(
+            kinds("ozone drill grab fiber ozone grace pudding thank cruise elder eight picnic"),
+            vec![WarningKind::DuplicateWords]
+        );
+        assert_eq!(
+            kinds("ozone drill grab fiber qwerty"),
+            vec![WarningKind::TooFewWords, WarningKind::UnknownWords]
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod backup;
 pub mod encryption;
+pub mod mnemonic_validator;
 
 pub use encryption::Encryptor;
+pub use mnemonic_validator::{ MnemonicValidator, MnemonicWarning };
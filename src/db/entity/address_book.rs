@@ -11,6 +11,7 @@ pub struct Model {
     pub address: String,
     pub chain: String,
     pub notes: Option<String>,
+    pub ens_expires_at: Option<DateTimeUtc>, // Set for entries cached from ENS resolution
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "bot_dialogue_states")]
+pub struct Model {
+    /// Telegram user id
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    /// Serialized `DialogueState`
+    pub state_json: Json,
+    /// Dialogues untouched past this point are treated as abandoned
+    pub expires_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
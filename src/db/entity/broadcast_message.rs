@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "broadcast_messages")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub message_text: String,
+    /// Telegram user id of the admin who sent the announcement
+    pub sender_admin_id: i64,
+    pub sent_at: DateTimeUtc,
+    /// Users the announcement was delivered to
+    pub recipient_count: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
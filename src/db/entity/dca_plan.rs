@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "dca_plans")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: String,
+    pub wallet_id: Uuid,
+    /// The scheduled transaction for the next buy (the last one while paused)
+    pub schedule_id: Option<Uuid>,
+    pub from_token: String,
+    pub to_token: String,
+    pub amount_per_period: f64,
+    pub period: String, // "daily", "weekly", "monthly"
+    pub status: String, // "active", "paused", "cancelled"
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::wallet::Entity",
+        from = "Column::WalletId",
+        to = "super::wallet::Column::Id"
+    )]
+    Wallet,
+    #[sea_orm(
+        belongs_to = "super::scheduled_transaction::Entity",
+        from = "Column::ScheduleId",
+        to = "super::scheduled_transaction::Column::Id"
+    )]
+    ScheduledTransaction,
+}
+
+impl Related<super::wallet::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Wallet.def()
+    }
+}
+
+impl Related<super::scheduled_transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ScheduledTransaction.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "gas_alerts")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: String,
+    pub chain: String,
+    pub max_gwei_threshold: f64,
+    /// Set once notified; cleared when gas climbs back above 120% of the threshold
+    pub triggered: bool,
+    pub active: bool,
+    pub last_triggered_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -7,6 +7,19 @@ pub mod security_settings;
 pub mod withdrawal_tracking;
 pub mod swap;
 pub mod token_metadata;
+pub mod portfolio_snapshot;
+pub mod rebalancing_alert;
+pub mod tax_lot;
+pub mod dca_plan;
+pub mod auto_compound_plan;
+pub mod bot_dialogue_state;
+pub mod mempool_alert;
+pub mod gas_alert;
+pub mod user_preference;
+pub mod token_discovery_cache;
+pub mod user_session;
+pub mod broadcast_message;
+pub mod group_permission;
 
 pub use wallet::Entity as Wallet;
 pub use transaction::Entity as Transaction;
@@ -16,3 +29,16 @@ pub use price_alert::Entity as PriceAlert;
 pub use security_settings::Entity as SecuritySettings;
 pub use withdrawal_tracking::Entity as WithdrawalTracking;
 pub use token_metadata::Entity as TokenMetadata;
+pub use portfolio_snapshot::Entity as PortfolioSnapshot;
+pub use rebalancing_alert::Entity as RebalancingAlert;
+pub use tax_lot::Entity as TaxLot;
+pub use dca_plan::Entity as DcaPlan;
+pub use auto_compound_plan::Entity as AutoCompoundPlan;
+pub use bot_dialogue_state::Entity as BotDialogueState;
+pub use mempool_alert::Entity as MempoolAlert;
+pub use gas_alert::Entity as GasAlert;
+pub use user_preference::Entity as UserPreference;
+pub use token_discovery_cache::Entity as TokenDiscoveryCache;
+pub use user_session::Entity as UserSession;
+pub use broadcast_message::Entity as BroadcastMessage;
+pub use group_permission::Entity as GroupPermission;
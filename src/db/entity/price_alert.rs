@@ -10,11 +10,25 @@ pub struct Model {
     pub token_symbol: String,
     pub chain: String,
     pub token_address: Option<String>,
-    pub alert_type: String, // "above", "below", "percent_change", "portfolio_value"
+    pub alert_type: String, // "above", "below", "percent_change", "stop_loss", "take_profit", "trailing_stop", "volume_surge"
     pub target_price: Option<Decimal>,
     pub percent_change: Option<Decimal>,
     pub base_price: Option<Decimal>,
+    pub stop_loss_price: Option<Decimal>,
+    pub take_profit_price: Option<Decimal>,
+    pub auto_execute: bool,
+    pub wallet_id: Option<Uuid>,
+    /// Highest price seen since a trailing stop was created
+    pub peak_price: Option<Decimal>,
+    /// How far below the peak, in percent, a trailing stop fires
+    pub trail_pct: Option<Decimal>,
+    /// 24h USD volume when a volume surge alert was created
+    pub baseline_volume: Option<Decimal>,
+    /// How many times the baseline volume has to trade before a volume surge alert fires
+    pub volume_multiplier: Option<Decimal>,
     pub active: bool,
+    /// Paused by the user; skipped by the alert checker until resumed
+    pub paused: bool,
     pub triggered_at: Option<DateTimeUtc>,
     pub last_checked_at: Option<DateTimeUtc>,
     pub created_at: DateTimeUtc,
@@ -17,6 +17,7 @@ pub struct Model {
     pub executed_at: Option<DateTimeUtc>,
     pub tx_hash: Option<String>,
     pub error_message: Option<String>,
+    pub max_gas_price_gwei: Option<f64>, // Only execute while gas is at or below this
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
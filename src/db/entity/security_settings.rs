@@ -13,9 +13,14 @@ pub struct Model {
     pub daily_withdrawal_limit: Option<Decimal>,
     pub weekly_withdrawal_limit: Option<Decimal>,
     pub require_confirmation_above: Option<Decimal>,
+    pub max_single_transfer_usd: Option<Decimal>,
+    pub pin_required_above_usd: Decimal, // 0 = PIN on every send
     pub session_timeout: i32,
     pub last_activity: Option<DateTimeUtc>,
     pub wallet_locked: bool,
+    pub use_testnet: bool, // New wallets are created on testnet networks
+    pub pin_attempts: i32, // Consecutive wrong PINs since the last success or lockout
+    pub pin_locked_until: Option<DateTimeUtc>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
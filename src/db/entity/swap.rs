@@ -24,6 +24,8 @@ pub struct Model {
     pub error_message: Option<String>,
     pub gas_fee: Option<Decimal>,
     pub route: Option<Json>, // Route information for multi-hop swaps
+    /// Set when the swap was submitted as part of a batch
+    pub batch_id: Option<Uuid>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
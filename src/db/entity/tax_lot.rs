@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "tax_lots")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: String,
+    pub wallet_id: Uuid,
+    pub symbol: String,
+    pub acquired_date: DateTimeUtc,
+    pub quantity: f64,
+    /// Total USD paid for `quantity`, not per unit
+    pub cost_basis_usd: f64,
+    pub disposed: bool,
+    pub disposed_date: Option<DateTimeUtc>,
+    /// USD received when the lot was disposed of
+    pub proceeds_usd: Option<f64>,
+    pub created_at: DateTimeUtc,
+}
+
+impl Model {
+    pub fn realized_gain_usd(&self) -> Option<f64> {
+        self.proceeds_usd.map(|proceeds| proceeds - self.cost_basis_usd)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::wallet::Entity",
+        from = "Column::WalletId",
+        to = "super::wallet::Column::Id"
+    )]
+    Wallet,
+}
+
+impl Related<super::wallet::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Wallet.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -18,6 +18,20 @@ pub struct Model {
     pub block_number: Option<i64>,
     pub gas_used: Option<String>,
     pub created_at: DateTime,
+    /// Set on speed-up/cancel replacements to the transaction they supersede
+    pub replaces_tx_id: Option<Uuid>,
+    /// L1 submission cost paid in the native token (Arbitrum)
+    pub l1_fee_paid: Option<Decimal>,
+    /// Part of the L1 cost reserved at submission but not charged (Arbitrum)
+    pub l1_refund: Option<Decimal>,
+    /// Times the broadcast was retried after a transient RPC failure
+    pub transfer_retry_count: i32,
+    /// Error that triggered the most recent broadcast retry
+    pub last_retry_error: Option<String>,
+    /// Network fee paid, in the native token; set on confirmation
+    pub gas_fee_native: Option<String>,
+    /// `gas_fee_native` valued in USD at confirmation
+    pub gas_fee_usd: Option<f64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
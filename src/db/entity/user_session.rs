@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "user_sessions")]
+pub struct Model {
+    /// Telegram user id
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    /// Wallet the user last opened or acted on
+    pub last_wallet_id: Option<Uuid>,
+    pub last_chain: Option<String>,
+    /// Callback action of the last wallet interaction, e.g. "balance" or "send"
+    pub last_action: Option<String>,
+    pub last_active_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -10,7 +10,14 @@ pub struct Model {
     pub chain: String,
     pub address: String,
     pub encrypted_private_key: String,
+    pub is_testnet: bool,
     pub created_at: DateTimeUtc,
+    /// Private node RPC URL for this wallet, encrypted like the private key
+    pub rpc_override_url: Option<String>,
+    /// BIP-44 address index the key was derived at; `None` for imported private keys and backups
+    pub derivation_index: Option<i32>,
+    /// When a key rotation last re-encrypted this wallet's private key
+    pub key_rotated_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
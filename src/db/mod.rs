@@ -1,4 +1,14 @@
-use sea_orm::{ entity::prelude::*, DatabaseConnection, Set };
+use std::collections::HashMap;
+
+use futures::Stream;
+use sea_orm::{
+    entity::prelude::*,
+    DatabaseConnection,
+    QueryOrder,
+    QuerySelect,
+    Set,
+    TransactionTrait,
+};
 use uuid::Uuid;
 
 use crate::error::{ AppError, Result };
@@ -7,11 +17,23 @@ pub mod entity;
 pub use entity::*;
 
 mod transaction_repository;
-pub use transaction_repository::TransactionRepository;
+pub use transaction_repository::{ FeeAggregate, TransactionRepository };
 
 mod token_metadata_repository;
 pub use token_metadata_repository::{TokenMetadataRepository, TokenMetadataInput};
 
+mod token_discovery_cache_repository;
+pub use token_discovery_cache_repository::{ CachedTokenBalance, TokenDiscoveryCacheRepository };
+
+mod portfolio_snapshot_repository;
+pub use portfolio_snapshot_repository::PortfolioSnapshotRepository;
+
+mod swap_repository;
+pub use swap_repository::SwapRepository;
+
+mod tax_lot_repository;
+pub use tax_lot_repository::{ FifoDisposal, TaxLotRepository };
+
 pub struct WalletRepository {
     db: DatabaseConnection,
 }
@@ -26,7 +48,9 @@ impl WalletRepository {
         user_id: String,
         chain: String,
         address: String,
-        encrypted_private_key: String
+        encrypted_private_key: String,
+        is_testnet: bool,
+        derivation_index: Option<u32>
     ) -> Result<entity::wallet::Model> {
         let wallet = entity::wallet::ActiveModel {
             id: Set(Uuid::new_v4()),
@@ -34,7 +58,11 @@ impl WalletRepository {
             chain: Set(chain),
             address: Set(address),
             encrypted_private_key: Set(encrypted_private_key),
+            is_testnet: Set(is_testnet),
             created_at: Set(chrono::Utc::now()),
+            rpc_override_url: Set(None),
+            derivation_index: Set(derivation_index.map(|i| i as i32)),
+            key_rotated_at: Set(None),
         };
 
         let wallet = wallet.insert(&self.db).await?;
@@ -68,6 +96,46 @@ impl WalletRepository {
         Ok(wallets)
     }
 
+    /// Number of wallets the user holds on each chain
+    pub async fn count_by_chain(&self, user_id: &str) -> Result<HashMap<String, usize>> {
+        let counts: Vec<(String, i64)> = entity::wallet::Entity
+            ::find()
+            .select_only()
+            .column(entity::wallet::Column::Chain)
+            .column_as(entity::wallet::Column::Id.count(), "count")
+            .filter(entity::wallet::Column::UserId.eq(user_id))
+            .group_by(entity::wallet::Column::Chain)
+            .into_tuple()
+            .all(&self.db).await?;
+
+        Ok(
+            counts
+                .into_iter()
+                .map(|(chain, count)| (chain, count as usize))
+                .collect()
+        )
+    }
+
+    /// Creation dates of the user's oldest and newest wallets; `None` without wallets
+    pub async fn created_at_range(
+        &self,
+        user_id: &str
+    ) -> Result<Option<(DateTimeUtc, DateTimeUtc)>> {
+        let range: Option<(Option<DateTimeUtc>, Option<DateTimeUtc>)> = entity::wallet::Entity
+            ::find()
+            .select_only()
+            .column_as(entity::wallet::Column::CreatedAt.min(), "oldest")
+            .column_as(entity::wallet::Column::CreatedAt.max(), "newest")
+            .filter(entity::wallet::Column::UserId.eq(user_id))
+            .into_tuple()
+            .one(&self.db).await?;
+
+        Ok(match range {
+            Some((Some(oldest), Some(newest))) => Some((oldest, newest)),
+            _ => None,
+        })
+    }
+
     pub async fn find_by_address(&self, address: &str) -> Result<Option<entity::wallet::Model>> {
         let wallet = entity::wallet::Entity
             ::find()
@@ -77,6 +145,133 @@ impl WalletRepository {
         Ok(wallet)
     }
 
+    /// A user's wallets whose address starts with `prefix`, ignoring case
+    pub async fn find_by_user_and_address_prefix(
+        &self,
+        user_id: &str,
+        prefix: &str
+    ) -> Result<Vec<entity::wallet::Model>> {
+        use sea_orm::sea_query::{ Expr, Func };
+
+        // Escape LIKE wildcards so the prefix is matched literally
+        let escaped = prefix
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+
+        let wallets = entity::wallet::Entity
+            ::find()
+            .filter(entity::wallet::Column::UserId.eq(user_id))
+            .filter(
+                Expr::expr(Func::lower(Expr::col(entity::wallet::Column::Address))).like(
+                    format!("{}%", escaped)
+                )
+            )
+            .all(&self.db).await?;
+
+        Ok(wallets)
+    }
+
+    /// All wallets as a stream of pages, ordered by id so pages stay stable while rows are updated.
+    /// Only one page is held in memory at a time.
+    pub fn find_all_paginated(
+        &self,
+        page_size: u64
+    ) -> impl Stream<Item = Result<Vec<entity::wallet::Model>>> + '_ {
+        let paginator = entity::wallet::Entity
+            ::find()
+            .order_by_asc(entity::wallet::Column::Id)
+            .paginate(&self.db, page_size);
+
+        futures::stream::unfold(Some(paginator), |paginator| async move {
+            let mut paginator = paginator?;
+            match paginator.fetch_and_next().await {
+                Ok(Some(page)) => Some((Ok(page), Some(paginator))),
+                Ok(None) => None,
+                // Stop after reporting the error rather than retrying the same page forever
+                Err(e) => Some((Err(AppError::from(e)), None)),
+            }
+        })
+    }
+
+    /// Wallets created at or after `since`
+    pub async fn find_created_since(
+        &self,
+        since: chrono::DateTime<chrono::Utc>
+    ) -> Result<Vec<entity::wallet::Model>> {
+        let wallets = entity::wallet::Entity
+            ::find()
+            .filter(entity::wallet::Column::CreatedAt.gte(since))
+            .all(&self.db).await?;
+
+        Ok(wallets)
+    }
+
+    /// Number of wallets across all users on each chain
+    pub async fn count_all_by_chain(&self) -> Result<HashMap<String, u64>> {
+        let counts: Vec<(String, i64)> = entity::wallet::Entity
+            ::find()
+            .select_only()
+            .column(entity::wallet::Column::Chain)
+            .column_as(entity::wallet::Column::Id.count(), "count")
+            .group_by(entity::wallet::Column::Chain)
+            .into_tuple()
+            .all(&self.db).await?;
+
+        Ok(
+            counts
+                .into_iter()
+                .map(|(chain, count)| (chain, count as u64))
+                .collect()
+        )
+    }
+
+    /// Replace the encrypted private keys and RPC override URLs of several wallets atomically
+    pub async fn update_encrypted_keys(
+        &self,
+        updates: Vec<(Uuid, String, Option<String>)>
+    ) -> Result<()> {
+        let txn = self.db.begin().await?;
+        let rotated_at = chrono::Utc::now();
+
+        for (id, encrypted_private_key, rpc_override_url) in updates {
+            entity::wallet::Entity
+                ::update_many()
+                .col_expr(
+                    entity::wallet::Column::EncryptedPrivateKey,
+                    sea_orm::sea_query::Expr::value(encrypted_private_key)
+                )
+                .col_expr(
+                    entity::wallet::Column::RpcOverrideUrl,
+                    sea_orm::sea_query::Expr::value(rpc_override_url)
+                )
+                .col_expr(
+                    entity::wallet::Column::KeyRotatedAt,
+                    sea_orm::sea_query::Expr::value(Some(rotated_at))
+                )
+                .filter(entity::wallet::Column::Id.eq(id))
+                .exec(&txn).await?;
+        }
+
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Set or clear a wallet's encrypted RPC override URL
+    pub async fn update_rpc_override(
+        &self,
+        id: Uuid,
+        rpc_override_url: Option<String>
+    ) -> Result<entity::wallet::Model> {
+        let wallet = self.find_by_id(id).await?;
+
+        let mut wallet: entity::wallet::ActiveModel = wallet.into();
+        wallet.rpc_override_url = Set(rpc_override_url);
+
+        Ok(wallet.update(&self.db).await?)
+    }
+
     pub async fn delete(&self, id: Uuid) -> Result<()> {
         entity::wallet::Entity::delete_by_id(id).exec(&self.db).await?;
         Ok(())
@@ -0,0 +1,55 @@
+use chrono::{ DateTime, Utc };
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use uuid::Uuid;
+
+use crate::db::entity::portfolio_snapshot;
+use crate::error::Result;
+
+#[derive(Clone)]
+pub struct PortfolioSnapshotRepository {
+    db: DatabaseConnection,
+}
+
+impl PortfolioSnapshotRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, user_id: &str, total_usd_value: f64) -> Result<portfolio_snapshot::Model> {
+        let model = portfolio_snapshot::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user_id.to_string()),
+            total_usd_value: ActiveValue::Set(total_usd_value),
+            created_at: ActiveValue::Set(Utc::now()),
+        };
+        let model = model.insert(&self.db).await?;
+        Ok(model)
+    }
+
+    pub async fn find_latest(&self, user_id: &str) -> Result<Option<portfolio_snapshot::Model>> {
+        let result = portfolio_snapshot::Entity::find()
+            .filter(portfolio_snapshot::Column::UserId.eq(user_id))
+            .order_by_desc(portfolio_snapshot::Column::CreatedAt)
+            .one(&self.db)
+            .await?;
+        Ok(result)
+    }
+
+    /// Snapshots taken since `since`, oldest first
+    pub async fn find_since(
+        &self,
+        user_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<portfolio_snapshot::Model>> {
+        let results = portfolio_snapshot::Entity::find()
+            .filter(portfolio_snapshot::Column::UserId.eq(user_id))
+            .filter(portfolio_snapshot::Column::CreatedAt.gte(since))
+            .order_by_asc(portfolio_snapshot::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+        Ok(results)
+    }
+}
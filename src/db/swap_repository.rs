@@ -0,0 +1,78 @@
+use sea_orm::{
+    ColumnTrait,
+    DatabaseConnection,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    QuerySelect,
+};
+use uuid::Uuid;
+
+use crate::db::entity::swap;
+use crate::error::Result;
+
+#[derive(Clone)]
+pub struct SwapRepository {
+    db: DatabaseConnection,
+}
+
+impl SwapRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// A user's swaps, newest first
+    pub async fn find_by_user(
+        &self,
+        user_id: &str,
+        limit: u64,
+        offset: u64
+    ) -> Result<Vec<swap::Model>> {
+        let swaps = swap::Entity
+            ::find()
+            .filter(swap::Column::UserId.eq(user_id))
+            .order_by_desc(swap::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&self.db).await?;
+        Ok(swaps)
+    }
+
+    /// A wallet's swaps, newest first
+    pub async fn find_by_wallet(
+        &self,
+        wallet_id: Uuid,
+        limit: u64,
+        offset: u64
+    ) -> Result<Vec<swap::Model>> {
+        let swaps = swap::Entity
+            ::find()
+            .filter(swap::Column::WalletId.eq(wallet_id))
+            .order_by_desc(swap::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&self.db).await?;
+        Ok(swaps)
+    }
+
+    /// A wallet's swaps older than the `before` swap, newest first
+    pub async fn find_by_wallet_before(
+        &self,
+        wallet_id: Uuid,
+        before: Uuid,
+        limit: u64
+    ) -> Result<Vec<swap::Model>> {
+        let Some(cursor) = swap::Entity::find_by_id(before).one(&self.db).await? else {
+            return Ok(Vec::new());
+        };
+
+        let swaps = swap::Entity
+            ::find()
+            .filter(swap::Column::WalletId.eq(wallet_id))
+            .filter(swap::Column::CreatedAt.lt(cursor.created_at))
+            .order_by_desc(swap::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db).await?;
+        Ok(swaps)
+    }
+}
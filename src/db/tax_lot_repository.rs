@@ -0,0 +1,170 @@
+use chrono::{ DateTime, Utc };
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    DatabaseConnection,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::db::entity::tax_lot;
+use crate::error::Result;
+
+/// Quantities below this are treated as fully consumed (float dust)
+const QUANTITY_EPSILON: f64 = 1e-12;
+
+/// Result of disposing of a quantity against a wallet's open lots
+#[derive(Debug, Clone)]
+pub struct FifoDisposal {
+    /// One disposed row per lot (or lot portion) consumed
+    pub disposed: Vec<tax_lot::Model>,
+    /// Quantity no open lot covered, e.g. funds that arrived before tracking started
+    pub untracked_quantity: f64,
+}
+
+#[derive(Clone)]
+pub struct TaxLotRepository {
+    db: DatabaseConnection,
+}
+
+impl TaxLotRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: &str,
+        wallet_id: Uuid,
+        symbol: &str,
+        acquired_date: DateTime<Utc>,
+        quantity: f64,
+        cost_basis_usd: f64
+    ) -> Result<tax_lot::Model> {
+        let lot = tax_lot::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user_id.to_string()),
+            wallet_id: ActiveValue::Set(wallet_id),
+            symbol: ActiveValue::Set(symbol.to_uppercase()),
+            acquired_date: ActiveValue::Set(acquired_date),
+            quantity: ActiveValue::Set(quantity),
+            cost_basis_usd: ActiveValue::Set(cost_basis_usd),
+            disposed: ActiveValue::Set(false),
+            disposed_date: ActiveValue::Set(None),
+            proceeds_usd: ActiveValue::Set(None),
+            created_at: ActiveValue::Set(Utc::now()),
+        };
+        Ok(lot.insert(&self.db).await?)
+    }
+
+    /// A wallet's open lots for a symbol, oldest first
+    pub async fn find_open_lots(&self, wallet_id: Uuid, symbol: &str) -> Result<Vec<tax_lot::Model>> {
+        let lots = tax_lot::Entity
+            ::find()
+            .filter(tax_lot::Column::WalletId.eq(wallet_id))
+            .filter(tax_lot::Column::Symbol.eq(symbol.to_uppercase()))
+            .filter(tax_lot::Column::Disposed.eq(false))
+            .order_by_asc(tax_lot::Column::AcquiredDate)
+            .all(&self.db).await?;
+        Ok(lots)
+    }
+
+    /// A user's disposals in `[from, to)`, oldest first
+    pub async fn find_disposed_between(
+        &self,
+        user_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<Vec<tax_lot::Model>> {
+        let lots = tax_lot::Entity
+            ::find()
+            .filter(tax_lot::Column::UserId.eq(user_id))
+            .filter(tax_lot::Column::Disposed.eq(true))
+            .filter(tax_lot::Column::DisposedDate.gte(from))
+            .filter(tax_lot::Column::DisposedDate.lt(to))
+            .order_by_asc(tax_lot::Column::DisposedDate)
+            .all(&self.db).await?;
+        Ok(lots)
+    }
+
+    /// Consume open lots oldest-first for `quantity` units sold for `proceeds_usd` in total.
+    /// A partly used lot is split so the disposed part keeps its share of the cost basis.
+    pub async fn dispose_fifo(
+        &self,
+        wallet_id: Uuid,
+        symbol: &str,
+        quantity: f64,
+        proceeds_usd: f64,
+        disposed_date: DateTime<Utc>
+    ) -> Result<FifoDisposal> {
+        let proceeds_per_unit = if quantity > 0.0 { proceeds_usd / quantity } else { 0.0 };
+        let txn = self.db.begin().await?;
+
+        let open_lots = tax_lot::Entity
+            ::find()
+            .filter(tax_lot::Column::WalletId.eq(wallet_id))
+            .filter(tax_lot::Column::Symbol.eq(symbol.to_uppercase()))
+            .filter(tax_lot::Column::Disposed.eq(false))
+            .order_by_asc(tax_lot::Column::AcquiredDate)
+            .all(&txn).await?;
+
+        let mut remaining = quantity;
+        let mut disposed = Vec::new();
+
+        for lot in open_lots {
+            if remaining <= QUANTITY_EPSILON {
+                break;
+            }
+
+            if lot.quantity <= remaining + QUANTITY_EPSILON {
+                remaining -= lot.quantity;
+                let proceeds = lot.quantity * proceeds_per_unit;
+
+                let mut active: tax_lot::ActiveModel = lot.into();
+                active.disposed = ActiveValue::Set(true);
+                active.disposed_date = ActiveValue::Set(Some(disposed_date));
+                active.proceeds_usd = ActiveValue::Set(Some(proceeds));
+                disposed.push(active.update(&txn).await?);
+            } else {
+                // Split: the sold part becomes a new disposed row, the rest stays open
+                let used_fraction = remaining / lot.quantity;
+                let used_cost = lot.cost_basis_usd * used_fraction;
+
+                let sold = tax_lot::ActiveModel {
+                    id: ActiveValue::Set(Uuid::new_v4()),
+                    user_id: ActiveValue::Set(lot.user_id.clone()),
+                    wallet_id: ActiveValue::Set(lot.wallet_id),
+                    symbol: ActiveValue::Set(lot.symbol.clone()),
+                    acquired_date: ActiveValue::Set(lot.acquired_date),
+                    quantity: ActiveValue::Set(remaining),
+                    cost_basis_usd: ActiveValue::Set(used_cost),
+                    disposed: ActiveValue::Set(true),
+                    disposed_date: ActiveValue::Set(Some(disposed_date)),
+                    proceeds_usd: ActiveValue::Set(Some(remaining * proceeds_per_unit)),
+                    created_at: ActiveValue::Set(Utc::now()),
+                };
+                disposed.push(sold.insert(&txn).await?);
+
+                let left_quantity = lot.quantity - remaining;
+                let left_cost = lot.cost_basis_usd - used_cost;
+                let mut active: tax_lot::ActiveModel = lot.into();
+                active.quantity = ActiveValue::Set(left_quantity);
+                active.cost_basis_usd = ActiveValue::Set(left_cost);
+                active.update(&txn).await?;
+
+                remaining = 0.0;
+            }
+        }
+
+        txn.commit().await?;
+
+        Ok(FifoDisposal {
+            disposed,
+            untracked_quantity: remaining.max(0.0),
+        })
+    }
+}
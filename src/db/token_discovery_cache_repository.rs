@@ -0,0 +1,81 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+use crate::db::entity::token_discovery_cache;
+use crate::error::{ AppError, Result };
+
+/// A token balance remembered from the last discovery run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedTokenBalance {
+    pub contract_address: String,
+    pub balance: String,
+}
+
+impl CachedTokenBalance {
+    /// Tokens stored in a cache row; a row that no longer parses reads as empty
+    pub fn from_row(row: &token_discovery_cache::Model) -> Vec<Self> {
+        serde_json::from_value(row.tokens.clone()).unwrap_or_default()
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenDiscoveryCacheRepository {
+    db: DatabaseConnection,
+}
+
+impl TokenDiscoveryCacheRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn find(
+        &self,
+        wallet_id: Uuid,
+        chain: &str,
+    ) -> Result<Option<token_discovery_cache::Model>> {
+        let result = token_discovery_cache::Entity::find()
+            .filter(token_discovery_cache::Column::WalletId.eq(wallet_id))
+            .filter(token_discovery_cache::Column::Chain.eq(chain))
+            .one(&self.db)
+            .await?;
+        Ok(result)
+    }
+
+    /// Replace the wallet's cached tokens and mark them discovered now
+    pub async fn save(
+        &self,
+        wallet_id: Uuid,
+        chain: &str,
+        tokens: &[CachedTokenBalance],
+        last_synced_block: Option<u64>,
+    ) -> Result<token_discovery_cache::Model> {
+        let tokens = serde_json::to_value(tokens)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize cached tokens: {}", e)))?;
+        let last_synced_block = last_synced_block.map(|b| b as i64);
+        let now = Utc::now();
+
+        if let Some(existing) = self.find(wallet_id, chain).await? {
+            let mut active: token_discovery_cache::ActiveModel = existing.into();
+            active.tokens = ActiveValue::Set(tokens);
+            active.last_synced_block = ActiveValue::Set(last_synced_block);
+            active.last_discovery_at = ActiveValue::Set(now);
+            let model = active.update(&self.db).await?;
+            Ok(model)
+        } else {
+            let model = token_discovery_cache::ActiveModel {
+                id: ActiveValue::Set(Uuid::new_v4()),
+                wallet_id: ActiveValue::Set(wallet_id),
+                chain: ActiveValue::Set(chain.to_string()),
+                tokens: ActiveValue::Set(tokens),
+                last_synced_block: ActiveValue::Set(last_synced_block),
+                last_discovery_at: ActiveValue::Set(now),
+            };
+            let model = model.insert(&self.db).await?;
+            Ok(model)
+        }
+    }
+}
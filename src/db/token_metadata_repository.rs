@@ -1,11 +1,21 @@
+use std::collections::HashMap;
+use std::future::Future;
+
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect,
 };
 use uuid::Uuid;
 
 use crate::db::entity::token_metadata;
+use crate::enums::Chain;
 use crate::error::Result;
+use crate::providers::ChainProvider;
+use crate::services::TokenListService;
+
+/// Most tokens a fuzzy lookup returns
+const FUZZY_MATCH_LIMIT: u64 = 20;
 
 #[derive(Clone)]
 pub struct TokenMetadataRepository {
@@ -40,6 +50,20 @@ impl TokenMetadataRepository {
         Ok(result)
     }
 
+    pub async fn find_by_chain_and_addresses(
+        &self,
+        chain: &str,
+        addresses: &[String],
+    ) -> Result<Vec<token_metadata::Model>> {
+        let lowered: Vec<String> = addresses.iter().map(|a| a.to_lowercase()).collect();
+        let results = token_metadata::Entity::find()
+            .filter(token_metadata::Column::Chain.eq(chain))
+            .filter(token_metadata::Column::ContractAddress.is_in(lowered))
+            .all(&self.db)
+            .await?;
+        Ok(results)
+    }
+
     pub async fn find_by_chain(&self, chain: &str) -> Result<Vec<token_metadata::Model>> {
         let results = token_metadata::Entity::find()
             .filter(token_metadata::Column::Chain.eq(chain))
@@ -48,17 +72,86 @@ impl TokenMetadataRepository {
         Ok(results)
     }
 
+    /// Every token on `chain` with exactly this symbol; different contracts can share one
     pub async fn find_by_chain_and_symbol(
         &self,
         chain: &str,
         symbol: &str,
-    ) -> Result<Option<token_metadata::Model>> {
-        let result = token_metadata::Entity::find()
+    ) -> Result<Vec<token_metadata::Model>> {
+        let results = token_metadata::Entity::find()
             .filter(token_metadata::Column::Chain.eq(chain))
             .filter(token_metadata::Column::Symbol.eq(symbol.to_uppercase()))
-            .one(&self.db)
+            .order_by_desc(token_metadata::Column::IsVerified)
+            .all(&self.db)
             .await?;
-        Ok(result)
+        Ok(results)
+    }
+
+    /// Tokens whose symbol or name contains `query`, ignoring case, optionally on the chain with
+    /// EVM id `chain_id`. Verified tokens come first.
+    pub async fn find_by_symbol_fuzzy(
+        &self,
+        query: &str,
+        chain_id: Option<u64>,
+    ) -> Result<Vec<token_metadata::Model>> {
+        let chain = match chain_id {
+            Some(chain_id) => {
+                let chain = Chain::all()
+                    .iter()
+                    .find(|c| c.chain_id(false) == Some(chain_id) || c.chain_id(true) == Some(chain_id));
+                match chain {
+                    Some(chain) => Some(chain.as_str()),
+                    None => return Ok(Vec::new()),
+                }
+            }
+            None => None,
+        };
+        self.fuzzy_search(query, chain).await
+    }
+
+    /// Fuzzy symbol or name lookup on one chain, for chains without an EVM id such as Solana
+    pub async fn find_by_chain_and_symbol_fuzzy(
+        &self,
+        chain: &str,
+        query: &str,
+    ) -> Result<Vec<token_metadata::Model>> {
+        self.fuzzy_search(query, Some(chain)).await
+    }
+
+    async fn fuzzy_search(
+        &self,
+        query: &str,
+        chain: Option<&str>,
+    ) -> Result<Vec<token_metadata::Model>> {
+        use sea_orm::sea_query::{Expr, Func};
+
+        // Escape LIKE wildcards so the query is matched literally
+        let escaped = query
+            .trim()
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let pattern = format!("%{}%", escaped);
+
+        let mut select = token_metadata::Entity::find().filter(
+            Condition::any()
+                .add(Expr::expr(Func::lower(Expr::col(token_metadata::Column::Symbol))).like(pattern.clone()))
+                .add(Expr::expr(Func::lower(Expr::col(token_metadata::Column::Name))).like(pattern)),
+        );
+
+        // Filter before the limit so other chains can't crowd out this one's matches
+        if let Some(chain) = chain {
+            select = select.filter(token_metadata::Column::Chain.eq(chain));
+        }
+
+        let results = select
+            .order_by_desc(token_metadata::Column::IsVerified)
+            .order_by_asc(token_metadata::Column::Symbol)
+            .limit(FUZZY_MATCH_LIMIT)
+            .all(&self.db)
+            .await?;
+        Ok(results)
     }
 
     pub async fn upsert(
@@ -110,6 +203,88 @@ impl TokenMetadataRepository {
         }
     }
 
+    /// Metadata for a token, looked up in order: the database, the Uniswap token list,
+    /// then `fetch` (typically an on-chain or API call). Tokens found past the database are stored.
+    pub async fn get_or_fetch<F, Fut>(
+        &self,
+        chain: Chain,
+        address: &str,
+        token_list: Option<&TokenListService>,
+        fetch: F,
+    ) -> Result<token_metadata::Model>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<TokenMetadataInput>>,
+    {
+        // Rows cached by `get_or_fetch_decimals` carry no symbol yet and still need the full lookup
+        if let Some(cached) = self.find_by_chain_and_address(chain.as_str(), address).await? {
+            if !cached.symbol.is_empty() {
+                return Ok(cached);
+            }
+        }
+
+        let listed = match (token_list, chain.chain_id(false)) {
+            (Some(list), Some(chain_id)) => list.lookup(chain_id, address).await,
+            _ => None,
+        };
+        let token = match listed {
+            Some(entry) => TokenMetadataInput {
+                chain: chain.as_str().to_string(),
+                contract_address: address.to_string(),
+                symbol: entry.symbol,
+                name: entry.name,
+                decimals: entry.decimals as i16,
+                logo_url: entry.logo_uri,
+                coingecko_id: None,
+            },
+            None => fetch().await?,
+        };
+
+        self.upsert(
+            chain.as_str(),
+            address,
+            &token.symbol,
+            &token.name,
+            token.decimals,
+            token.logo_url,
+            token.coingecko_id,
+        )
+        .await
+    }
+
+    /// Decimals for many tokens, keyed by lowercase address: cached rows first, then one
+    /// `get_token_decimals_batch` call for the rest. Newly fetched decimals are stored without
+    /// a symbol or name, which `get_or_fetch` fills in on first use.
+    pub async fn get_or_fetch_decimals(
+        &self,
+        chain: Chain,
+        addresses: &[String],
+        provider: &dyn ChainProvider,
+    ) -> Result<HashMap<String, u8>> {
+        let mut decimals: HashMap<String, u8> = self
+            .find_by_chain_and_addresses(chain.as_str(), addresses)
+            .await?
+            .into_iter()
+            .map(|row| (row.contract_address, row.decimals as u8))
+            .collect();
+
+        let missing: Vec<&str> = addresses
+            .iter()
+            .map(String::as_str)
+            .filter(|a| !decimals.contains_key(&a.to_lowercase()))
+            .collect();
+        if missing.is_empty() {
+            return Ok(decimals);
+        }
+
+        for (address, value) in provider.get_token_decimals_batch(&missing).await? {
+            self.upsert(chain.as_str(), &address, "", "", value as i16, None, None).await?;
+            decimals.insert(address.to_lowercase(), value);
+        }
+
+        Ok(decimals)
+    }
+
     pub async fn bulk_upsert(&self, tokens: Vec<TokenMetadataInput>) -> Result<()> {
         for token in tokens {
             self.upsert(
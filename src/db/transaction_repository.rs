@@ -1,8 +1,32 @@
-use sea_orm::{ DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, QueryOrder, Set };
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{ DateTime, Utc };
+use sea_orm::prelude::Decimal;
+use sea_orm::{
+    DatabaseConnection,
+    EntityTrait,
+    QueryFilter,
+    ColumnTrait,
+    QueryOrder,
+    Set,
+    TransactionTrait,
+};
+use serde::Serialize;
 use uuid::Uuid;
 
 use crate::error::{ AppError, Result };
-use crate::db::entity::{ transaction, Transaction };
+use crate::db::entity::{ transaction, wallet, Transaction, Wallet };
+
+/// Network fees a user paid on one chain
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeAggregate {
+    pub chain: String,
+    /// Sum of fees in the chain's native token
+    pub total_gas_native: String,
+    pub total_gas_usd: f64,
+    pub tx_count: u64,
+}
 
 pub struct TransactionRepository {
     db: DatabaseConnection,
@@ -39,6 +63,13 @@ impl TransactionRepository {
             block_number: Set(None),
             gas_used: Set(None),
             created_at: Set(chrono::Utc::now().naive_utc()),
+            replaces_tx_id: Set(None),
+            l1_fee_paid: Set(None),
+            l1_refund: Set(None),
+            transfer_retry_count: Set(0),
+            last_retry_error: Set(None),
+            gas_fee_native: Set(None),
+            gas_fee_usd: Set(None),
         };
 
         let transaction = Transaction::insert(transaction_model)
@@ -48,6 +79,63 @@ impl TransactionRepository {
         Ok(transaction)
     }
 
+    /// Record a speed-up/cancel transaction that reuses the nonce of `original`, and mark
+    /// `original` as replaced so it no longer shows or gets polled as pending
+    pub async fn create_replacement(
+        &self,
+        original: &transaction::Model,
+        tx_hash: String,
+        to_address: String,
+        amount: String,
+        token_address: Option<String>,
+        token_symbol: Option<String>,
+        status: String
+    ) -> Result<transaction::Model> {
+        let transaction_model = transaction::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            wallet_id: Set(original.wallet_id),
+            tx_hash: Set(tx_hash),
+            chain: Set(original.chain.clone()),
+            from_address: Set(original.from_address.clone()),
+            to_address: Set(to_address),
+            amount: Set(amount),
+            token_address: Set(token_address),
+            token_symbol: Set(token_symbol),
+            status: Set(status),
+            block_number: Set(None),
+            gas_used: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            replaces_tx_id: Set(Some(original.id)),
+            l1_fee_paid: Set(None),
+            l1_refund: Set(None),
+            transfer_retry_count: Set(0),
+            last_retry_error: Set(None),
+            gas_fee_native: Set(None),
+            gas_fee_usd: Set(None),
+        };
+
+        let txn = self.db.begin().await.map_err(AppError::Database)?;
+
+        let transaction = Transaction::insert(transaction_model)
+            .exec_with_returning(&txn).await
+            .map_err(AppError::Database)?;
+
+        let mut replaced: transaction::ActiveModel = original.clone().into();
+        replaced.status = Set(crate::enums::TxStatus::Replaced.to_string());
+        Transaction::update(replaced).exec(&txn).await.map_err(AppError::Database)?;
+
+        txn.commit().await.map_err(AppError::Database)?;
+
+        Ok(transaction)
+    }
+
+    pub async fn find_by_id(&self, id: Uuid) -> Result<transaction::Model> {
+        Transaction::find_by_id(id)
+            .one(&self.db).await
+            .map_err(AppError::Database)?
+            .ok_or_else(|| AppError::NotFound("Transaction not found".to_string()))
+    }
+
     pub async fn find_by_wallet_id(
         &self,
         wallet_id: Uuid,
@@ -81,6 +169,18 @@ impl TransactionRepository {
             .ok_or_else(|| AppError::NotFound("Transaction not found".to_string()))
     }
 
+    /// Oldest transactions still waiting to be mined
+    pub async fn find_pending(&self, limit: u64) -> Result<Vec<transaction::Model>> {
+        use sea_orm::QuerySelect;
+
+        Transaction::find()
+            .filter(transaction::Column::Status.eq(crate::enums::TxStatus::Pending.as_str()))
+            .order_by_asc(transaction::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db).await
+            .map_err(AppError::Database)
+    }
+
     pub async fn find_by_user_id(
         &self,
         wallet_ids: Vec<Uuid>,
@@ -106,6 +206,60 @@ impl TransactionRepository {
         Ok(transactions)
     }
 
+    /// Most recent transfers from one address to another on a chain
+    pub async fn find_by_address_pair(
+        &self,
+        from_address: &str,
+        to_address: &str,
+        chain: &str,
+        limit: u64
+    ) -> Result<Vec<transaction::Model>> {
+        use sea_orm::QuerySelect;
+
+        Transaction::find()
+            .filter(transaction::Column::FromAddress.eq(from_address))
+            .filter(transaction::Column::ToAddress.eq(to_address))
+            .filter(transaction::Column::Chain.eq(chain))
+            .order_by_desc(transaction::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db).await
+            .map_err(AppError::Database)
+    }
+
+    /// Recorded transactions paying into `to_address` on a chain, newest first. EVM addresses
+    /// are compared case-insensitively since checksummed and lowercase forms both get stored.
+    pub async fn find_by_to_address(
+        &self,
+        to_address: &str,
+        chain: &str,
+        limit: Option<u64>,
+        offset: Option<u64>
+    ) -> Result<Vec<transaction::Model>> {
+        use sea_orm::QuerySelect;
+        use sea_orm::sea_query::{ Expr, Func };
+
+        let is_evm = chain
+            .parse::<crate::enums::Chain>()
+            .map(|c| c.is_evm())
+            .unwrap_or(false);
+        let address_filter = if is_evm {
+            Expr::expr(Func::lower(Expr::col(transaction::Column::ToAddress))).eq(
+                to_address.to_lowercase()
+            )
+        } else {
+            Expr::col(transaction::Column::ToAddress).eq(to_address)
+        };
+
+        Transaction::find()
+            .filter(address_filter)
+            .filter(transaction::Column::Chain.eq(chain))
+            .order_by_desc(transaction::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&self.db).await
+            .map_err(AppError::Database)
+    }
+
     pub async fn update_status(
         &self,
         tx_hash: &str,
@@ -126,4 +280,123 @@ impl TransactionRepository {
 
         Ok(updated)
     }
+
+    /// Record the L1 fee breakdown of a confirmed rollup transaction
+    pub async fn set_l1_fees(
+        &self,
+        tx_hash: &str,
+        l1_fee_paid: sea_orm::prelude::Decimal,
+        l1_refund: sea_orm::prelude::Decimal
+    ) -> Result<transaction::Model> {
+        let transaction = self.find_by_tx_hash(tx_hash).await?;
+
+        let mut transaction_model: transaction::ActiveModel = transaction.into();
+        transaction_model.l1_fee_paid = Set(Some(l1_fee_paid));
+        transaction_model.l1_refund = Set(Some(l1_refund));
+
+        let updated = Transaction::update(transaction_model)
+            .exec(&self.db).await
+            .map_err(AppError::Database)?;
+
+        Ok(updated)
+    }
+
+    /// Record the network fee of a confirmed transaction
+    pub async fn set_gas_fees(
+        &self,
+        tx_hash: &str,
+        gas_fee_native: String,
+        gas_fee_usd: Option<f64>
+    ) -> Result<transaction::Model> {
+        let transaction = self.find_by_tx_hash(tx_hash).await?;
+
+        let mut transaction_model: transaction::ActiveModel = transaction.into();
+        transaction_model.gas_fee_native = Set(Some(gas_fee_native));
+        transaction_model.gas_fee_usd = Set(gas_fee_usd);
+
+        let updated = Transaction::update(transaction_model)
+            .exec(&self.db).await
+            .map_err(AppError::Database)?;
+
+        Ok(updated)
+    }
+
+    /// Fees paid across a user's wallets between `from` and `to`, one entry per chain,
+    /// most expensive chain first. Only transactions with a recorded fee count.
+    pub async fn aggregate_fees_paid(
+        &self,
+        user_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<Vec<FeeAggregate>> {
+        let transactions = self.fee_query(user_id, from, to).all(&self.db).await.map_err(AppError::Database)?;
+
+        let mut by_chain: BTreeMap<String, (Decimal, f64, u64)> = BTreeMap::new();
+        for tx in transactions {
+            let native = tx.gas_fee_native
+                .as_deref()
+                .and_then(|fee| Decimal::from_str(fee).ok())
+                .unwrap_or_default();
+            let entry = by_chain.entry(tx.chain).or_default();
+            entry.0 += native;
+            entry.1 += tx.gas_fee_usd.unwrap_or(0.0);
+            entry.2 += 1;
+        }
+
+        let mut aggregates: Vec<FeeAggregate> = by_chain
+            .into_iter()
+            .map(|(chain, (native, usd, count))| FeeAggregate {
+                chain,
+                total_gas_native: native.normalize().to_string(),
+                total_gas_usd: usd,
+                tx_count: count,
+            })
+            .collect();
+        aggregates.sort_by(|a, b| b.total_gas_usd.total_cmp(&a.total_gas_usd));
+
+        Ok(aggregates)
+    }
+
+    /// The transaction with the highest USD fee among a user's wallets between `from` and `to`
+    pub async fn find_most_expensive_fee(
+        &self,
+        user_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<Option<transaction::Model>> {
+        self.fee_query(user_id, from, to)
+            .filter(transaction::Column::GasFeeUsd.is_not_null())
+            .order_by_desc(transaction::Column::GasFeeUsd)
+            .one(&self.db).await
+            .map_err(AppError::Database)
+    }
+
+    fn fee_query(&self, user_id: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> sea_orm::Select<Transaction> {
+        Transaction::find()
+            .inner_join(Wallet)
+            .filter(wallet::Column::UserId.eq(user_id))
+            .filter(transaction::Column::GasFeeNative.is_not_null())
+            .filter(transaction::Column::CreatedAt.gte(from.naive_utc()))
+            .filter(transaction::Column::CreatedAt.lte(to.naive_utc()))
+    }
+
+    /// Record how many broadcast attempts a transfer needed and why the last retry happened
+    pub async fn set_transfer_retries(
+        &self,
+        id: Uuid,
+        retry_count: i32,
+        last_error: Option<String>
+    ) -> Result<transaction::Model> {
+        let transaction = self.find_by_id(id).await?;
+
+        let mut transaction_model: transaction::ActiveModel = transaction.into();
+        transaction_model.transfer_retry_count = Set(retry_count);
+        transaction_model.last_retry_error = Set(last_error);
+
+        let updated = Transaction::update(transaction_model)
+            .exec(&self.db).await
+            .map_err(AppError::Database)?;
+
+        Ok(updated)
+    }
 }
@@ -0,0 +1,163 @@
+use super::{ BatchSwapLeg, DexProvider, SwapQuote, SwapResult };
+use crate::error::{ AppError, Result };
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+/// Routes swaps to whichever registered DEX returns the best output amount
+pub struct DexAggregator {
+    chain: String,
+    providers: Vec<Arc<dyn DexProvider>>,
+}
+
+impl DexAggregator {
+    pub fn new(chain: &str, providers: Vec<Arc<dyn DexProvider>>) -> Self {
+        Self {
+            chain: chain.to_string(),
+            providers,
+        }
+    }
+
+    fn find_provider(&self, name: &str) -> Option<Arc<dyn DexProvider>> {
+        self.providers
+            .iter()
+            .find(|p| p.name() == name)
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl DexProvider for DexAggregator {
+    async fn get_quote(
+        &self,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64
+    ) -> Result<SwapQuote> {
+        let mut quotes = self.get_all_quotes(from_token, to_token, amount, slippage).await?;
+
+        let mut best = quotes.remove(0);
+        best.alternatives = quotes;
+
+        Ok(best)
+    }
+
+    /// Quote every registered DEX concurrently; DEXes that fail to quote are left out
+    async fn get_all_quotes(
+        &self,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64
+    ) -> Result<Vec<SwapQuote>> {
+        let mut set = JoinSet::new();
+
+        for provider in &self.providers {
+            let provider = provider.clone();
+            let from_token = from_token.to_string();
+            let to_token = to_token.to_string();
+            set.spawn(async move {
+                let quote = provider.get_quote(&from_token, &to_token, amount, slippage).await;
+                (provider.name().to_string(), quote)
+            });
+        }
+
+        let mut quotes = Vec::new();
+        while let Some(joined) = set.join_next().await {
+            match joined {
+                Ok((_, Ok(quote))) => quotes.push(quote),
+                Ok((name, Err(e))) => tracing::warn!("{} quote failed: {}", name, e),
+                Err(e) => tracing::warn!("DEX quote task panicked: {}", e),
+            }
+        }
+
+        if quotes.is_empty() {
+            return Err(AppError::External(format!("No DEX returned a quote on {}", self.chain)));
+        }
+
+        // Highest output first
+        quotes.sort_by(|a, b| b.expected_to_amount.total_cmp(&a.expected_to_amount));
+        Ok(quotes)
+    }
+
+    async fn execute_swap(
+        &self,
+        wallet_address: &str,
+        private_key: &str,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64,
+        min_output: f64
+    ) -> Result<SwapResult> {
+        // Callers holding a quote should use `execute_swap_via` with its DEX; this re-quotes
+        let dex_name = self.get_quote(from_token, to_token, amount, slippage).await?.dex;
+
+        let provider = self
+            .find_provider(&dex_name)
+            .ok_or_else(|| AppError::Internal(format!("DEX {} is not registered", dex_name)))?;
+
+        provider.execute_swap(
+            wallet_address,
+            private_key,
+            from_token,
+            to_token,
+            amount,
+            slippage,
+            min_output
+        ).await
+    }
+
+    async fn execute_swap_via(
+        &self,
+        dex_name: &str,
+        wallet_address: &str,
+        private_key: &str,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64,
+        min_output: f64
+    ) -> Result<SwapResult> {
+        let provider = self
+            .find_provider(dex_name)
+            .ok_or_else(|| AppError::Validation(format!("DEX {} is not available on {}", dex_name, self.chain)))?;
+
+        provider.execute_swap(
+            wallet_address,
+            private_key,
+            from_token,
+            to_token,
+            amount,
+            slippage,
+            min_output
+        ).await
+    }
+
+    /// Batches go to the DEX that quoted every leg, when its router supports them
+    async fn execute_batch_swap(
+        &self,
+        wallet_address: &str,
+        private_key: &str,
+        legs: &[BatchSwapLeg]
+    ) -> Option<Result<Vec<SwapResult>>> {
+        let dex_name = &legs.first()?.dex;
+        if legs.iter().any(|leg| &leg.dex != dex_name) {
+            return None;
+        }
+        self.find_provider(dex_name)?.execute_batch_swap(wallet_address, private_key, legs).await
+    }
+
+    fn spender_for(&self, dex_name: &str) -> Option<String> {
+        self.find_provider(dex_name)?.spender_for(dex_name)
+    }
+
+    fn name(&self) -> &str {
+        "DEX Aggregator"
+    }
+
+    fn supported_chains(&self) -> Vec<&str> {
+        vec![self.chain.as_str()]
+    }
+}
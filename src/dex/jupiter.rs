@@ -119,6 +119,7 @@ impl DexProvider for JupiterProvider {
             route,
             estimated_gas: Some("5000".to_string()), // SOL lamports for transaction
             dex: self.name().to_string(),
+            alternatives: Vec::new(),
         })
     }
 
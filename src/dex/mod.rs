@@ -1,9 +1,11 @@
 use async_trait::async_trait;
-use crate::error::Result;
+use crate::error::{ AppError, Result };
 use serde::{ Deserialize, Serialize };
 
 pub mod uniswap;
 pub mod jupiter;
+pub mod aggregator;
+pub mod pancakeswap_v3;
 
 /// Swap quote information returned by DEX providers
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +21,29 @@ pub struct SwapQuote {
     pub route: Vec<String>, // Token addresses in the swap route
     pub estimated_gas: Option<String>,
     pub dex: String,
+    /// Quotes from the other DEXes the aggregator compared against
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<SwapQuote>,
+}
+
+impl SwapQuote {
+    /// Human-readable comparison against the runner-up, e.g. "Best: Uniswap V2 (+2.1% vs SushiSwap)"
+    pub fn best_vs_alternative_summary(&self) -> Option<String> {
+        let runner_up = self.alternatives
+            .iter()
+            .max_by(|a, b| a.expected_to_amount.total_cmp(&b.expected_to_amount))?;
+
+        if runner_up.expected_to_amount <= 0.0 {
+            return Some(format!("Best: {}", self.dex));
+        }
+
+        let diff_pct =
+            ((self.expected_to_amount - runner_up.expected_to_amount) /
+                runner_up.expected_to_amount) *
+            100.0;
+
+        Some(format!("Best: {} (+{:.1}% vs {})", self.dex, diff_pct, runner_up.dex))
+    }
 }
 
 /// Swap execution result
@@ -30,6 +55,17 @@ pub struct SwapResult {
     pub gas_used: Option<String>,
 }
 
+/// One swap in a batch submitted as a single transaction
+#[derive(Debug, Clone)]
+pub struct BatchSwapLeg {
+    /// DEX that quoted this leg
+    pub dex: String,
+    pub from_token: String,
+    pub to_token: String,
+    pub amount: f64,
+    pub slippage: f64,
+}
+
 /// Trait for DEX providers (Uniswap, PancakeSwap, Jupiter, etc.)
 #[async_trait]
 pub trait DexProvider: Send + Sync {
@@ -42,6 +78,17 @@ pub trait DexProvider: Send + Sync {
         slippage: f64
     ) -> Result<SwapQuote>;
 
+    /// Quotes from every DEX this provider can route through, best output first
+    async fn get_all_quotes(
+        &self,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64
+    ) -> Result<Vec<SwapQuote>> {
+        Ok(vec![self.get_quote(from_token, to_token, amount, slippage).await?])
+    }
+
     /// Execute a token swap
     async fn execute_swap(
         &self,
@@ -54,6 +101,49 @@ pub trait DexProvider: Send + Sync {
         min_output: f64
     ) -> Result<SwapResult>;
 
+    /// Execute a swap on the named DEX rather than the best-priced one
+    async fn execute_swap_via(
+        &self,
+        dex_name: &str,
+        wallet_address: &str,
+        private_key: &str,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64,
+        min_output: f64
+    ) -> Result<SwapResult> {
+        if dex_name != self.name() {
+            return Err(AppError::Validation(format!("{} is not available for this swap", dex_name)));
+        }
+        self.execute_swap(
+            wallet_address,
+            private_key,
+            from_token,
+            to_token,
+            amount,
+            slippage,
+            min_output
+        ).await
+    }
+
+    /// Contract the wallet must approve to pull the input token when swapping on `dex_name`;
+    /// `None` when the DEX doesn't pull tokens with an allowance
+    fn spender_for(&self, _dex_name: &str) -> Option<String> {
+        None
+    }
+
+    /// Execute several swaps in one transaction, one result per leg in order.
+    /// `None` when the DEX's router can't batch calls.
+    async fn execute_batch_swap(
+        &self,
+        _wallet_address: &str,
+        _private_key: &str,
+        _legs: &[BatchSwapLeg]
+    ) -> Option<Result<Vec<SwapResult>>> {
+        None
+    }
+
     /// Get the DEX name
     fn name(&self) -> &str;
 
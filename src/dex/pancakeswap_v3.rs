@@ -0,0 +1,408 @@
+use super::uniswap::IERC20;
+use super::{ BatchSwapLeg, DexProvider, SwapQuote, SwapResult };
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use async_trait::async_trait;
+use ethers::prelude::*;
+use serde::Deserialize;
+use std::sync::Arc;
+
+// PancakeSwap SmartRouter ABI (V3 single-pool swap)
+abigen!(
+    IPancakeSmartRouter,
+    r#"[
+        struct ExactInputSingleParams { address tokenIn; address tokenOut; uint24 fee; address recipient; uint256 amountIn; uint256 amountOutMinimum; uint160 sqrtPriceLimitX96; }
+        function exactInputSingle(ExactInputSingleParams params) external payable returns (uint256 amountOut)
+        function multicall(bytes[] data) external payable returns (bytes[] results)
+    ]"#
+);
+
+// PancakeSwap V3 QuoterV2 ABI (simulated single-pool quote)
+abigen!(
+    IPancakeQuoterV2,
+    r#"[
+        struct QuoteExactInputSingleParams { address tokenIn; address tokenOut; uint256 amountIn; uint24 fee; uint160 sqrtPriceLimitX96; }
+        function quoteExactInputSingle(QuoteExactInputSingleParams params) external returns (uint256 amountOut, uint160 sqrtPriceX96After, uint32 initializedTicksCrossed, uint256 gasEstimate)
+    ]"#
+);
+
+const SMART_ROUTER_ADDRESS: &str = "0x13f4EA83D0bd40E75C8222255bc855a974568Dd4";
+const QUOTER_V2_ADDRESS: &str = "0xB048Bbc1Ee6b733FFfCFb9e9CeF7375518e25997";
+const QUOTE_API_URL: &str = "https://api.pancakeswap.info/api/v0/swap/quoteBestTrade";
+
+/// V3 pool fee tiers (in hundredths of a bip) probed against the quoter
+const FEE_TIERS: [u32; 4] = [100, 500, 2500, 10000];
+
+// PancakeSwap quote API response
+#[derive(Debug, Deserialize)]
+struct PancakeQuoteResponse {
+    #[serde(rename = "outputAmount")]
+    output_amount: String,
+    #[serde(rename = "gasUseEstimate", default)]
+    gas_use_estimate: Option<String>,
+    #[serde(rename = "routeString", default)]
+    route_string: String,
+    #[serde(rename = "priceImpact", default)]
+    price_impact: Option<f64>,
+}
+
+pub struct PancakeSwapV3Provider {
+    router_address: Address,
+    quoter_address: Address,
+    provider: Arc<Provider<Http>>,
+    client: reqwest::Client,
+}
+
+impl PancakeSwapV3Provider {
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        let router_address = SMART_ROUTER_ADDRESS
+            .parse()
+            .map_err(|e| AppError::Internal(format!("Invalid router address: {}", e)))?;
+        let quoter_address = QUOTER_V2_ADDRESS
+            .parse()
+            .map_err(|e| AppError::Internal(format!("Invalid quoter address: {}", e)))?;
+
+        let provider = Provider::<Http>
+            ::try_from(rpc_url)
+            .map_err(|e| AppError::Internal(format!("Failed to create provider: {}", e)))?;
+
+        Ok(Self {
+            router_address,
+            quoter_address,
+            provider: Arc::new(provider),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    fn resolve_token_address(&self, token: &str) -> Result<Address> {
+        let address = match token.to_uppercase().as_str() {
+            "BNB" | "WBNB" => "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c",
+            "USDT" => "0x55d398326f99059fF775485246999027B3197955",
+            "USDC" => "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d",
+            "BUSD" => "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56",
+            "CAKE" => "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82",
+            _ => token, // Assume it's already a contract address
+        };
+
+        address.parse().map_err(|e| AppError::Validation(format!("Invalid token address: {}", e)))
+    }
+
+    async fn fetch_quote(
+        &self,
+        from_address: Address,
+        to_address: Address,
+        amount_in: U256
+    ) -> Result<PancakeQuoteResponse> {
+        let chain_id = Chain::Bsc.chain_id(false).unwrap_or(56);
+        let url = format!(
+            "{}?chainId={}&currency={:?}&outputCurrency={:?}&amount={}&tradeType=EXACT_INPUT",
+            QUOTE_API_URL,
+            chain_id,
+            from_address,
+            to_address,
+            amount_in
+        );
+
+        let response = self.client
+            .get(&url)
+            .send().await
+            .map_err(|e| AppError::External(format!("PancakeSwap API error: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(
+                AppError::External(format!("PancakeSwap API returned error: {}", response.status()))
+            );
+        }
+
+        response
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse PancakeSwap response: {}", e)))
+    }
+
+    /// Simulate the swap against every V3 fee tier through QuoterV2 and return the
+    /// tier of the direct pool that pays out the most, along with that output
+    async fn best_fee_tier(
+        &self,
+        from_address: Address,
+        to_address: Address,
+        amount_in: U256
+    ) -> Result<(u32, U256)> {
+        let quoter = IPancakeQuoterV2::new(self.quoter_address, self.provider.clone());
+        let mut quotes = Vec::with_capacity(FEE_TIERS.len());
+
+        for fee in FEE_TIERS {
+            let params = QuoteExactInputSingleParams {
+                token_in: from_address,
+                token_out: to_address,
+                amount_in,
+                fee,
+                sqrt_price_limit_x96: U256::zero(),
+            };
+            // The quoter reverts when no pool exists for the tier
+            if let Ok((amount_out, _, _, _)) = quoter.quote_exact_input_single(params).call().await {
+                quotes.push((fee, amount_out));
+            }
+        }
+
+        pick_best_tier(quotes).ok_or_else(||
+            AppError::External(
+                "No PancakeSwap V3 pool with liquidity for this pair".to_string()
+            )
+        )
+    }
+}
+
+impl PancakeSwapV3Provider {
+    /// Encode every leg as `exactInputSingle` and submit them through the router's own
+    /// `multicall`, which delegatecalls so the wallet stays `msg.sender` for each swap
+    async fn multicall_swaps(
+        &self,
+        wallet_address: &str,
+        private_key: &str,
+        legs: &[BatchSwapLeg]
+    ) -> Result<Vec<SwapResult>> {
+        let wallet: LocalWallet = private_key
+            .parse()
+            .map_err(|e| AppError::Internal(format!("Invalid private key: {}", e)))?;
+        let wallet = wallet.with_chain_id(Chain::Bsc.chain_id(false).unwrap_or(56));
+        let client = Arc::new(SignerMiddleware::new(self.provider.clone(), wallet));
+
+        let recipient: Address = wallet_address
+            .parse()
+            .map_err(|e| AppError::Validation(format!("Invalid address: {}", e)))?;
+
+        let router = IPancakeSmartRouter::new(self.router_address, client.clone());
+        let mut calls = Vec::with_capacity(legs.len());
+        let mut min_outputs = Vec::with_capacity(legs.len());
+        let mut spend: Vec<(Address, U256)> = Vec::new();
+
+        for leg in legs {
+            let from_address = self.resolve_token_address(&leg.from_token)?;
+            let to_address = self.resolve_token_address(&leg.to_token)?;
+            let amount_in = U256::from((leg.amount * 1e18) as u128);
+
+            let (fee, expected) = self.best_fee_tier(from_address, to_address, amount_in).await?;
+            let expected = expected.as_u128() as f64;
+            let min_output = (expected / 1e18) * (1.0 - leg.slippage / 100.0);
+
+            let params = ExactInputSingleParams {
+                token_in: from_address,
+                token_out: to_address,
+                fee,
+                recipient,
+                amount_in,
+                amount_out_minimum: U256::from((min_output * 1e18) as u128),
+                sqrt_price_limit_x96: U256::zero(),
+            };
+            let calldata = router
+                .exact_input_single(params)
+                .calldata()
+                .ok_or_else(|| AppError::Internal("Failed to encode swap".to_string()))?;
+
+            calls.push(calldata);
+            min_outputs.push(min_output);
+            match spend.iter_mut().find(|(token, _)| *token == from_address) {
+                Some((_, total)) => {
+                    *total += amount_in;
+                }
+                None => spend.push((from_address, amount_in)),
+            }
+        }
+
+        // Approve each input token once for the whole batch
+        for (token, total) in spend {
+            let token_contract = IERC20::new(token, client.clone());
+            let allowance = token_contract
+                .allowance(recipient, self.router_address)
+                .call().await
+                .map_err(|e| AppError::Blockchain(format!("Failed to check allowance: {}", e)))?;
+
+            if allowance < total {
+                token_contract
+                    .approve(self.router_address, U256::MAX)
+                    .send().await
+                    .map_err(|e| AppError::Blockchain(format!("Failed to approve: {}", e)))?.await
+                    .map_err(|e| AppError::Blockchain(format!("Approval failed: {}", e)))?;
+            }
+        }
+
+        let receipt = router
+            .multicall(calls)
+            .send().await
+            .map_err(|e| AppError::Blockchain(format!("Batch swap failed: {}", e)))?.await
+            .map_err(|e| AppError::Blockchain(format!("Transaction failed: {}", e)))?
+            .ok_or_else(|| AppError::Internal("No receipt".to_string()))?;
+
+        let tx_hash = format!("{:?}", receipt.transaction_hash);
+        Ok(
+            legs
+                .iter()
+                .zip(min_outputs)
+                .enumerate()
+                .map(|(i, (leg, min_output))| SwapResult {
+                    tx_hash: tx_hash.clone(),
+                    from_amount: leg.amount,
+                    to_amount: min_output, // Actual amounts would need event parsing
+                    // Gas is paid once for the whole batch; book it on the first leg
+                    gas_used: if i == 0 { receipt.gas_used.map(|g| g.to_string()) } else { None },
+                })
+                .collect()
+        )
+    }
+}
+
+/// Pick the fee tier with the largest non-zero output
+fn pick_best_tier(quotes: Vec<(u32, U256)>) -> Option<(u32, U256)> {
+    quotes
+        .into_iter()
+        .filter(|(_, out)| !out.is_zero())
+        .max_by_key(|(_, out)| *out)
+}
+
+#[async_trait]
+impl DexProvider for PancakeSwapV3Provider {
+    async fn get_quote(
+        &self,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64
+    ) -> Result<SwapQuote> {
+        let from_address = self.resolve_token_address(from_token)?;
+        let to_address = self.resolve_token_address(to_token)?;
+
+        // BEP-20 tokens on BSC use 18 decimals
+        let amount_in = U256::from((amount * 1e18) as u128);
+
+        let quote = self.fetch_quote(from_address, to_address, amount_in).await?;
+
+        let output_raw: f64 = quote.output_amount.parse().unwrap_or(0.0);
+        let expected_to_amount = output_raw / 1e18;
+        let minimum_to_amount = expected_to_amount * (1.0 - slippage / 100.0);
+
+        Ok(SwapQuote {
+            from_token: from_token.to_string(),
+            from_token_address: Some(format!("{:?}", from_address)),
+            to_token: to_token.to_string(),
+            to_token_address: Some(format!("{:?}", to_address)),
+            from_amount: amount,
+            expected_to_amount,
+            minimum_to_amount,
+            price_impact: quote.price_impact.unwrap_or(0.0),
+            route: vec![quote.route_string],
+            estimated_gas: quote.gas_use_estimate,
+            dex: self.name().to_string(),
+            alternatives: Vec::new(),
+        })
+    }
+
+    async fn execute_swap(
+        &self,
+        wallet_address: &str,
+        private_key: &str,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        _slippage: f64,
+        min_output: f64
+    ) -> Result<SwapResult> {
+        let from_address = self.resolve_token_address(from_token)?;
+        let to_address = self.resolve_token_address(to_token)?;
+        let amount_in = U256::from((amount * 1e18) as u128);
+
+        // Ask the on-chain quoter which direct V3 pool to route through
+        let (fee, _) = self.best_fee_tier(from_address, to_address, amount_in).await?;
+
+        let wallet: LocalWallet = private_key
+            .parse()
+            .map_err(|e| AppError::Internal(format!("Invalid private key: {}", e)))?;
+        let wallet = wallet.with_chain_id(Chain::Bsc.chain_id(false).unwrap_or(56));
+        let client = Arc::new(SignerMiddleware::new(self.provider.clone(), wallet));
+
+        let recipient: Address = wallet_address
+            .parse()
+            .map_err(|e| AppError::Validation(format!("Invalid address: {}", e)))?;
+
+        // Approve tokens if needed
+        let token_contract = IERC20::new(from_address, client.clone());
+        let allowance = token_contract
+            .allowance(recipient, self.router_address)
+            .call().await
+            .map_err(|e| AppError::Blockchain(format!("Failed to check allowance: {}", e)))?;
+
+        if allowance < amount_in {
+            token_contract
+                .approve(self.router_address, U256::MAX)
+                .send().await
+                .map_err(|e| AppError::Blockchain(format!("Failed to approve: {}", e)))?.await
+                .map_err(|e| AppError::Blockchain(format!("Approval failed: {}", e)))?;
+        }
+
+        let router = IPancakeSmartRouter::new(self.router_address, client);
+        let params = ExactInputSingleParams {
+            token_in: from_address,
+            token_out: to_address,
+            fee,
+            recipient,
+            amount_in,
+            amount_out_minimum: U256::from((min_output * 1e18) as u128),
+            sqrt_price_limit_x96: U256::zero(),
+        };
+
+        let receipt = router
+            .exact_input_single(params)
+            .send().await
+            .map_err(|e| AppError::Blockchain(format!("Swap failed: {}", e)))?.await
+            .map_err(|e| AppError::Blockchain(format!("Transaction failed: {}", e)))?
+            .ok_or_else(|| AppError::Internal("No receipt".to_string()))?;
+
+        Ok(SwapResult {
+            tx_hash: format!("{:?}", receipt.transaction_hash),
+            from_amount: amount,
+            to_amount: min_output, // Actual amount would need event parsing
+            gas_used: receipt.gas_used.map(|g| g.to_string()),
+        })
+    }
+
+    async fn execute_batch_swap(
+        &self,
+        wallet_address: &str,
+        private_key: &str,
+        legs: &[BatchSwapLeg]
+    ) -> Option<Result<Vec<SwapResult>>> {
+        Some(self.multicall_swaps(wallet_address, private_key, legs).await)
+    }
+
+    fn spender_for(&self, dex_name: &str) -> Option<String> {
+        (dex_name == self.name()).then(|| format!("{:?}", self.router_address))
+    }
+
+    fn name(&self) -> &str {
+        "PancakeSwap V3"
+    }
+
+    fn supported_chains(&self) -> Vec<&str> {
+        vec![Chain::Bsc.as_str()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_best_tier() {
+        let quotes = vec![
+            (500, U256::from(990u64)),
+            (2500, U256::from(1000u64)),
+            (10000, U256::from(950u64))
+        ];
+        assert_eq!(pick_best_tier(quotes), Some((2500, U256::from(1000u64))));
+    }
+
+    #[test]
+    fn test_pick_best_tier_ignores_empty_pools() {
+        assert_eq!(pick_best_tier(vec![(100, U256::zero())]), None);
+        assert_eq!(pick_best_tier(Vec::new()), None);
+    }
+}
@@ -12,6 +12,24 @@ abigen!(
         function swapExactTokensForTokens(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
         function getAmountsOut(uint amountIn, address[] memory path) external view returns (uint[] memory amounts)
         function WETH() external pure returns (address)
+        function factory() external pure returns (address)
+    ]"#
+);
+
+// Uniswap V2 Factory ABI (pair lookup)
+abigen!(
+    IUniswapV2Factory,
+    r#"[
+        function getPair(address tokenA, address tokenB) external view returns (address pair)
+    ]"#
+);
+
+// Uniswap V2 Pair ABI (reserves for price impact)
+abigen!(
+    IUniswapV2Pair,
+    r#"[
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast)
+        function token0() external view returns (address)
     ]"#
 );
 
@@ -28,6 +46,10 @@ pub struct UniswapV2Provider {
     router_address: Address,
     chain: String,
     provider: Arc<Provider<Http>>,
+    dex_name: String,
+    /// Wrapped native token when it differs from the chain's mainnet deployment
+    weth_override: Option<Address>,
+    is_testnet: bool,
 }
 
 impl UniswapV2Provider {
@@ -62,14 +84,59 @@ impl UniswapV2Provider {
             router_address,
             chain: chain.to_string(),
             provider: Arc::new(provider),
+            dex_name: default_dex_name(parsed).to_string(),
+            weth_override: None,
+            is_testnet: false,
         })
     }
 
+    /// Create a provider for the chain's testnet deployment, where one exists
+    pub fn new_testnet(chain: &str, rpc_url: &str) -> Result<Self> {
+        let parsed: Chain = chain.parse()?;
+        let (router, weth, dex_name) = match parsed {
+            Chain::Eth => (
+                "0xeE567Fe1712Faf6149d80dA1E6934E354124CfE3",
+                "0xfFf9976782d46CC05630D1f6eBAb18b2324d6B14",
+                "Uniswap V2 (Sepolia)",
+            ),
+            Chain::Bsc => (
+                "0xD99D1c33F9fC3444f8101754aBC46c52416550D1",
+                "0xae13d989daC2f0dEbFf460aC112a837C89BAa7cd",
+                "PancakeSwap V2 (Testnet)",
+            ),
+            _ => {
+                return Err(
+                    AppError::Validation(format!("Swaps are not available on {} testnet", parsed))
+                );
+            }
+        };
+
+        let mut dex = Self::with_router(chain, rpc_url, router, dex_name)?;
+        dex.weth_override = Some(
+            weth.parse().map_err(|e| AppError::Internal(format!("Invalid WETH address: {}", e)))?
+        );
+        dex.is_testnet = true;
+        Ok(dex)
+    }
+
+    /// Create a provider for another V2-compatible fork on the same chain
+    pub fn with_router(chain: &str, rpc_url: &str, router: &str, dex_name: &str) -> Result<Self> {
+        let mut dex = Self::new(chain, rpc_url)?;
+        dex.router_address = router
+            .parse()
+            .map_err(|e| AppError::Internal(format!("Invalid router address: {}", e)))?;
+        dex.dex_name = dex_name.to_string();
+        Ok(dex)
+    }
+
     fn parsed_chain(&self) -> Chain {
         self.chain.parse().expect("chain validated in constructor")
     }
 
     fn get_weth_address(&self) -> Address {
+        if let Some(weth) = self.weth_override {
+            return weth;
+        }
         match self.parsed_chain() {
             Chain::Eth => "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".parse().unwrap(),       // WETH
             Chain::Bsc => "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c".parse().unwrap(),       // WBNB
@@ -95,6 +162,53 @@ impl UniswapV2Provider {
         // Parse as address
         token.parse().map_err(|e| AppError::Validation(format!("Invalid token address: {}", e)))
     }
+
+    /// Fetch (reserve_in, reserve_out) for the pair, oriented to the swap direction
+    async fn get_reserves(&self, token_in: Address, token_out: Address) -> Result<(U256, U256)> {
+        let router = IUniswapV2Router::new(self.router_address, self.provider.clone());
+        let factory_address = router
+            .factory()
+            .call().await
+            .map_err(|e| AppError::Blockchain(format!("Failed to get factory: {}", e)))?;
+
+        let factory = IUniswapV2Factory::new(factory_address, self.provider.clone());
+        let pair_address = factory
+            .get_pair(token_in, token_out)
+            .call().await
+            .map_err(|e| AppError::Blockchain(format!("Failed to get pair: {}", e)))?;
+
+        if pair_address == Address::zero() {
+            return Err(AppError::Blockchain("No liquidity pool for this pair".to_string()));
+        }
+
+        let pair = IUniswapV2Pair::new(pair_address, self.provider.clone());
+        let (reserve0, reserve1, _) = pair
+            .get_reserves()
+            .call().await
+            .map_err(|e| AppError::Blockchain(format!("Failed to get reserves: {}", e)))?;
+        let token0 = pair
+            .token_0()
+            .call().await
+            .map_err(|e| AppError::Blockchain(format!("Failed to get token0: {}", e)))?;
+
+        if token0 == token_in {
+            Ok((U256::from(reserve0), U256::from(reserve1)))
+        } else {
+            Ok((U256::from(reserve1), U256::from(reserve0)))
+        }
+    }
+}
+
+/// Price impact in percent of adding `amount_in` to a constant-product pool
+fn calculate_price_impact(amount_in: U256, reserve_in: U256) -> f64 {
+    let amount_in = amount_in.as_u128() as f64;
+    let reserve_in = reserve_in.as_u128() as f64;
+
+    if amount_in + reserve_in == 0.0 {
+        return 0.0;
+    }
+
+    (amount_in / (reserve_in + amount_in)) * 100.0
 }
 
 #[async_trait]
@@ -130,9 +244,9 @@ impl DexProvider for UniswapV2Provider {
         let expected_to_amount = (expected_out.as_u128() as f64) / 1e18;
         let minimum_to_amount = expected_to_amount * (1.0 - slippage / 100.0);
 
-        // Calculate price impact (simplified)
-        let price = expected_to_amount / amount;
-        let price_impact = 0.0; // Would need pool reserves for accurate calculation
+        // Price impact from the pool's actual reserves
+        let (reserve_in, _) = self.get_reserves(from_address, to_address).await?;
+        let price_impact = calculate_price_impact(amount_in, reserve_in);
 
         Ok(SwapQuote {
             from_token: from_token.to_string(),
@@ -149,6 +263,7 @@ impl DexProvider for UniswapV2Provider {
                 .collect(),
             estimated_gas: Some("150000".to_string()),
             dex: self.name().to_string(),
+            alternatives: Vec::new(),
         })
     }
 
@@ -169,7 +284,8 @@ impl DexProvider for UniswapV2Provider {
         let wallet: LocalWallet = private_key
             .parse()
             .map_err(|e| AppError::Internal(format!("Invalid private key: {}", e)))?;
-        let client = SignerMiddleware::new(self.provider.clone(), wallet);
+        let chain_id = self.parsed_chain().chain_id(self.is_testnet).unwrap_or(1);
+        let client = SignerMiddleware::new(self.provider.clone(), wallet.with_chain_id(chain_id));
         let client_arc = Arc::new(client);
 
         // Approve tokens if needed
@@ -221,23 +337,48 @@ impl DexProvider for UniswapV2Provider {
         })
     }
 
+    fn spender_for(&self, dex_name: &str) -> Option<String> {
+        (dex_name == self.name()).then(|| format!("{:?}", self.router_address))
+    }
+
     fn name(&self) -> &str {
-        match self.parsed_chain() {
-            Chain::Eth => "Uniswap V2",
-            Chain::Bsc => "PancakeSwap V2",
-            Chain::Polygon => "QuickSwap",
-            Chain::Avalanche => "Trader Joe",
-            Chain::Arbitrum => "SushiSwap",
-            Chain::Optimism => "Velodrome",
-            Chain::Base => "BaseSwap",
-            Chain::Fantom => "SpookySwap",
-            Chain::Cronos => "VVS Finance",
-            Chain::Gnosis => "Honeyswap",
-            Chain::Solana | Chain::Btc | Chain::Xrp | Chain::Cardano => unreachable!("Non-EVM chain not supported in Uniswap V2"),
-        }
+        &self.dex_name
     }
 
     fn supported_chains(&self) -> Vec<&str> {
         Chain::all_evm().iter().map(|c| c.as_str()).collect()
     }
 }
+
+fn default_dex_name(chain: Chain) -> &'static str {
+    match chain {
+        Chain::Eth => "Uniswap V2",
+        Chain::Bsc => "PancakeSwap V2",
+        Chain::Polygon => "QuickSwap",
+        Chain::Avalanche => "Trader Joe",
+        Chain::Arbitrum => "SushiSwap",
+        Chain::Optimism => "Velodrome",
+        Chain::Base => "BaseSwap",
+        Chain::Fantom => "SpookySwap",
+        Chain::Cronos => "VVS Finance",
+        Chain::Gnosis => "Honeyswap",
+        Chain::Solana | Chain::Btc | Chain::Xrp | Chain::Cardano => unreachable!("Non-EVM chain not supported in Uniswap V2"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_impact_from_reserves() {
+        // 10 in against 990 reserve -> 10 / 1000 = 1%
+        let impact = calculate_price_impact(U256::from(10u64), U256::from(990u64));
+        assert!((impact - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_price_impact_empty_pool() {
+        assert_eq!(calculate_price_impact(U256::zero(), U256::zero()), 0.0);
+    }
+}
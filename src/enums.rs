@@ -292,6 +292,15 @@ pub enum AlertType {
     Above { target_price: f64 },
     Below { target_price: f64 },
     PercentChange { percent: f64, base_price: f64 },
+    StopLoss { stop_price: f64, token_address: Option<String> },
+    TakeProfit { target_price: f64 },
+    /// Fires once the price falls `trail_pct` percent below the highest price seen,
+    /// starting from `peak_price`
+    TrailingStop { trail_pct: f64, peak_price: f64 },
+    /// A pending incoming transfer worth more than `threshold_usd`, watched in the mempool
+    LargeIncoming { threshold_usd: f64 },
+    /// Fires once 24h trading volume exceeds `multiplier` times the volume at creation
+    VolumeSurge { baseline_volume_usd: f64, multiplier: f64 },
 }
 
 /// The discriminant stored in the database (no payload).
@@ -300,6 +309,14 @@ pub enum AlertKind {
     Above,
     Below,
     PercentChange,
+    StopLoss,
+    TakeProfit,
+    /// A stop that follows the price up and fires on a percentage drawdown from the peak
+    TrailingStop,
+    /// A pending incoming transfer worth more than a USD threshold (mempool alerts)
+    LargeIncoming,
+    /// 24h trading volume rising to a multiple of its baseline
+    VolumeSurge,
 }
 
 impl AlertKind {
@@ -308,6 +325,11 @@ impl AlertKind {
             AlertKind::Above => "above",
             AlertKind::Below => "below",
             AlertKind::PercentChange => "percent_change",
+            AlertKind::StopLoss => "stop_loss",
+            AlertKind::TakeProfit => "take_profit",
+            AlertKind::TrailingStop => "trailing_stop",
+            AlertKind::LargeIncoming => "large_incoming",
+            AlertKind::VolumeSurge => "volume_surge",
         }
     }
 }
@@ -326,8 +348,13 @@ impl FromStr for AlertKind {
             "above" => Ok(AlertKind::Above),
             "below" => Ok(AlertKind::Below),
             "percent_change" | "percent" => Ok(AlertKind::PercentChange),
+            "stop_loss" | "stoploss" => Ok(AlertKind::StopLoss),
+            "take_profit" | "takeprofit" => Ok(AlertKind::TakeProfit),
+            "trailing_stop" | "trailstop" => Ok(AlertKind::TrailingStop),
+            "large_incoming" => Ok(AlertKind::LargeIncoming),
+            "volume_surge" | "volume" => Ok(AlertKind::VolumeSurge),
             _ => Err(AppError::InvalidInput(format!(
-                "Invalid alert type: {}. Supported: above, below, percent_change",
+                "Invalid alert type: {}. Supported: above, below, percent_change, stop_loss, take_profit, trailing_stop, large_incoming, volume_surge",
                 s
             ))),
         }
@@ -342,6 +369,8 @@ pub enum TxStatus {
     Pending,
     Confirmed,
     Failed,
+    /// Superseded by a speed-up or cancel transaction using the same nonce
+    Replaced,
 }
 
 impl TxStatus {
@@ -350,6 +379,7 @@ impl TxStatus {
             TxStatus::Pending => "pending",
             TxStatus::Confirmed => "confirmed",
             TxStatus::Failed => "failed",
+            TxStatus::Replaced => "replaced",
         }
     }
 }
@@ -368,6 +398,7 @@ impl FromStr for TxStatus {
             "pending" => Ok(TxStatus::Pending),
             "confirmed" => Ok(TxStatus::Confirmed),
             "failed" => Ok(TxStatus::Failed),
+            "replaced" => Ok(TxStatus::Replaced),
             _ => Err(AppError::InvalidInput(format!("Invalid tx status: {}", s))),
         }
     }
@@ -418,6 +449,53 @@ impl FromStr for ScheduleStatus {
     }
 }
 
+// ─── GroupRole ──────────────────────────────────────────────────────
+
+/// Member permission over a group chat's shared wallets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupRole {
+    Owner,
+    Admin,
+    Viewer,
+}
+
+impl GroupRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupRole::Owner => "owner",
+            GroupRole::Admin => "admin",
+            GroupRole::Viewer => "viewer",
+        }
+    }
+
+    /// Owners and admins may move funds; viewers only see balances and addresses
+    pub fn can_transact(&self) -> bool {
+        matches!(self, GroupRole::Owner | GroupRole::Admin)
+    }
+}
+
+impl fmt::Display for GroupRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GroupRole {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "owner" => Ok(GroupRole::Owner),
+            "admin" => Ok(GroupRole::Admin),
+            "viewer" => Ok(GroupRole::Viewer),
+            _ => Err(AppError::InvalidInput(format!(
+                "Invalid group role: {}",
+                s
+            ))),
+        }
+    }
+}
+
 // ─── RecurringType ──────────────────────────────────────────────────
 
 /// Recurrence pattern for scheduled transactions.
@@ -460,6 +538,45 @@ impl FromStr for RecurringType {
     }
 }
 
+// ─── DcaStatus ──────────────────────────────────────────────────────
+
+/// Status of a dollar-cost averaging plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DcaStatus {
+    Active,
+    Paused,
+    Cancelled,
+}
+
+impl DcaStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DcaStatus::Active => "active",
+            DcaStatus::Paused => "paused",
+            DcaStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for DcaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DcaStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(DcaStatus::Active),
+            "paused" => Ok(DcaStatus::Paused),
+            "cancelled" => Ok(DcaStatus::Cancelled),
+            _ => Err(AppError::InvalidInput(format!("Invalid DCA status: {}", s))),
+        }
+    }
+}
+
 // ─── SwapStatus ─────────────────────────────────────────────────────
 
 /// Status of a token swap.
@@ -498,3 +615,47 @@ impl FromStr for SwapStatus {
         }
     }
 }
+
+// ─── DefiProtocol ───────────────────────────────────────────────────
+
+/// Lending protocol whose positions the bot reads and auto-compounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefiProtocol {
+    Aave,
+    Compound,
+}
+
+impl DefiProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DefiProtocol::Aave => "aave",
+            DefiProtocol::Compound => "compound",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DefiProtocol::Aave => "Aave",
+            DefiProtocol::Compound => "Compound",
+        }
+    }
+}
+
+impl fmt::Display for DefiProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DefiProtocol {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "aave" => Ok(DefiProtocol::Aave),
+            "compound" => Ok(DefiProtocol::Compound),
+            _ => Err(AppError::InvalidInput(format!("Unsupported DeFi protocol: {}. Use aave or compound", s))),
+        }
+    }
+}
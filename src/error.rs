@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,6 +13,11 @@ pub enum AppError {
     #[error("Wallet not found")]
     WalletNotFound,
 
+    #[error("Wallet already exists: {existing_id}")]
+    WalletAlreadyExists {
+        existing_id: uuid::Uuid,
+    },
+
     #[error("Chain error: {0}")] Chain(String),
 
     #[error("RPC error: {0}")] Rpc(String),
@@ -18,6 +25,12 @@ pub enum AppError {
     #[error("Insufficient balance")]
     InsufficientBalance,
 
+    #[error("Insufficient funds: available {available}, required {required}")]
+    InsufficientFunds {
+        available: String,
+        required: String,
+    },
+
     #[error("Invalid address")]
     InvalidAddress,
 
@@ -27,8 +40,13 @@ pub enum AppError {
     #[error("Invalid private key")]
     InvalidPrivateKey,
 
+    #[error("Could not verify ownership of the imported address")]
+    OwnershipVerificationFailed,
+
     #[error("Not found: {0}")] NotFound(String),
 
+    #[error("Forbidden: {0}")] Forbidden(String),
+
     #[error("Configuration error: {0}")] Config(String),
 
     #[error("Internal error: {0}")] Internal(String),
@@ -38,6 +56,62 @@ pub enum AppError {
     #[error("Validation error: {0}")] Validation(String),
 
     #[error("Blockchain error: {0}")] Blockchain(String),
+
+    #[error("Nonce too low: {0}")] NonceTooLow(String),
+
+    #[error("Price impact too high: {pct:.2}%")]
+    PriceImpactTooHigh {
+        pct: f64,
+    },
+
+    #[error("ENS name not found: {0}")] EnsNotFound(String),
+
+    #[error("Simulation failed: {0}")] SimulationFailed(String),
+
+    #[error("Honeypot token detected: {0}")] HoneypotDetected(String),
+
+    #[error("Transfer of ${attempted_usd:.2} exceeds the ${limit_usd:.2} per-transfer limit")]
+    TransferLimitExceeded {
+        attempted_usd: f64,
+        limit_usd: f64,
+    },
+
+    #[error("PIN locked after too many failed attempts until {unlocks_at}")]
+    PinLocked {
+        unlocks_at: chrono::DateTime<chrono::Utc>,
+    },
+
+    #[error("Insufficient funds for gas")]
+    InsufficientGas,
+
+    #[error("Connection to {chain} RPC timed out: {url}")]
+    ConnectionTimeout {
+        chain: String,
+        url: String,
+    },
+
+    #[error("Chain unavailable: {0}")] ChainUnavailable(String),
+
+    #[error("No address matched the vanity pattern in {attempts} attempts")]
+    VanityNotFound {
+        attempts: u32,
+    },
+
+    #[error("Rate limited by {provider}")]
+    RateLimited {
+        provider: String,
+        retry_after_secs: Option<u64>,
+    },
+
+    #[error("Too many requests, retry in {retry_after_secs} seconds")]
+    RateLimitExceeded {
+        retry_after_secs: u64,
+    },
+
+    #[error("Execution reverted: {reason}")]
+    ReversionError {
+        reason: String,
+    },
 }
 
 #[derive(serde::Serialize)]
@@ -54,45 +128,199 @@ pub struct ErrorDetail {
 }
 
 impl AppError {
-    pub fn to_error_response(&self) -> ErrorResponse {
-        let (code, message, field) = match self {
-            AppError::Database(e) => ("DATABASE_ERROR", e.to_string(), None),
-            AppError::Encryption(msg) => ("ENCRYPTION_ERROR", msg.clone(), None),
-            AppError::InvalidInput(msg) => ("INVALID_INPUT", msg.clone(), None),
-            AppError::WalletNotFound => ("WALLET_NOT_FOUND", "Wallet not found".to_string(), None),
-            AppError::Chain(msg) => ("CHAIN_ERROR", msg.clone(), None),
-            AppError::Rpc(msg) => ("RPC_ERROR", msg.clone(), None),
+    /// Stable machine-readable code, used in API responses and as the error tag in structured logs
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "DATABASE_ERROR",
+            AppError::Encryption(_) => "ENCRYPTION_ERROR",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::WalletNotFound => "WALLET_NOT_FOUND",
+            AppError::WalletAlreadyExists { .. } => "WALLET_ALREADY_EXISTS",
+            AppError::Chain(_) => "CHAIN_ERROR",
+            AppError::Rpc(_) => "RPC_ERROR",
+            AppError::InsufficientBalance => "INSUFFICIENT_BALANCE",
+            AppError::InsufficientFunds { .. } => "INSUFFICIENT_FUNDS",
+            AppError::InvalidAddress => "INVALID_ADDRESS",
+            AppError::InvalidMnemonic => "INVALID_MNEMONIC",
+            AppError::InvalidPrivateKey => "INVALID_PRIVATE_KEY",
+            AppError::OwnershipVerificationFailed => "OWNERSHIP_VERIFICATION_FAILED",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::Config(_) => "CONFIG_ERROR",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+            AppError::External(_) => "EXTERNAL_ERROR",
+            AppError::Validation(_) => "VALIDATION_ERROR",
+            AppError::Blockchain(_) => "BLOCKCHAIN_ERROR",
+            AppError::NonceTooLow(_) => "NONCE_TOO_LOW",
+            AppError::PriceImpactTooHigh { .. } => "PRICE_IMPACT_TOO_HIGH",
+            AppError::EnsNotFound(_) => "ENS_NOT_FOUND",
+            AppError::SimulationFailed(_) => "SIMULATION_FAILED",
+            AppError::HoneypotDetected(_) => "HONEYPOT_DETECTED",
+            AppError::TransferLimitExceeded { .. } => "TRANSFER_LIMIT_EXCEEDED",
+            AppError::PinLocked { .. } => "PIN_LOCKED",
+            AppError::InsufficientGas => "INSUFFICIENT_GAS",
+            AppError::ConnectionTimeout { .. } => "CONNECTION_TIMEOUT",
+            AppError::ChainUnavailable(_) => "CHAIN_UNAVAILABLE",
+            AppError::VanityNotFound { .. } => "VANITY_NOT_FOUND",
+            AppError::RateLimited { .. } => "RATE_LIMITED",
+            AppError::RateLimitExceeded { .. } => "RATE_LIMIT_EXCEEDED",
+            AppError::ReversionError { .. } => "EXECUTION_REVERTED",
+        }
+    }
+
+    /// Plain-language explanation for bot users; internal details stay in the logs
+    pub fn user_facing_message(&self) -> Cow<'_, str> {
+        match self {
+            | AppError::InvalidInput(msg)
+            | AppError::Chain(msg)
+            | AppError::NotFound(msg)
+            | AppError::Forbidden(msg)
+            | AppError::Config(msg)
+            | AppError::Validation(msg)
+            | AppError::Blockchain(msg) => Cow::Borrowed(msg),
+            AppError::Database(_) | AppError::Internal(_) =>
+                Cow::Borrowed("Something went wrong on our side. Please try again later."),
+            AppError::Encryption(_) =>
+                Cow::Borrowed("Your wallet keys couldn't be unlocked. Please try again later."),
+            AppError::Rpc(_) =>
+                Cow::Borrowed("The network isn't responding right now. Please try again in a moment."),
+            AppError::External(_) =>
+                Cow::Borrowed("An outside service is unavailable right now. Please try again later."),
+            AppError::WalletNotFound => Cow::Borrowed("Wallet not found."),
+            AppError::WalletAlreadyExists { .. } => Cow::Borrowed("This address is already in your wallets."),
             AppError::InsufficientBalance =>
-                ("INSUFFICIENT_BALANCE", "Insufficient balance for transaction".to_string(), None),
+                Cow::Borrowed("This wallet doesn't have enough balance for that."),
+            AppError::InsufficientFunds { available, required } =>
+                Cow::Owned(format!("Not enough funds: you have {}, but {} is needed.", available, required)),
             AppError::InvalidAddress =>
-                (
-                    "INVALID_ADDRESS",
-                    "Invalid address format".to_string(),
-                    Some("address".to_string()),
-                ),
+                Cow::Borrowed("That address doesn't look right. Please check it and try again."),
             AppError::InvalidMnemonic =>
+                Cow::Borrowed("That recovery phrase isn't valid. Please check the words and try again."),
+            AppError::InvalidPrivateKey => Cow::Borrowed("That private key isn't valid."),
+            AppError::OwnershipVerificationFailed =>
+                Cow::Borrowed("A key in the backup doesn't control the wallet address saved with it, so nothing was imported."),
+            AppError::NonceTooLow(_) =>
+                Cow::Borrowed(
+                    "Another transaction from this wallet is still being processed. Please wait a moment and try again."
+                ),
+            AppError::PriceImpactTooHigh { pct } =>
+                Cow::Owned(
+                    format!("This swap would move the price by {:.2}%. Try a smaller amount.", pct)
+                ),
+            AppError::EnsNotFound(name) => Cow::Owned(format!("No address is registered for {}.", name)),
+            AppError::SimulationFailed(reason) =>
+                Cow::Owned(format!("This transaction would fail: {}", reason)),
+            AppError::HoneypotDetected(token) =>
+                Cow::Owned(format!("Token {} can be bought but not sold, so it was blocked.", token)),
+            AppError::TransferLimitExceeded { .. } => Cow::Owned(self.to_string()),
+            AppError::PinLocked { unlocks_at } =>
+                Cow::Owned(
+                    format!(
+                        "Too many failed PIN attempts. Try again after {} UTC.",
+                        unlocks_at.format("%H:%M")
+                    )
+                ),
+            AppError::InsufficientGas =>
+                Cow::Borrowed("Not enough funds left to pay the network fee."),
+            AppError::ConnectionTimeout { chain, .. } =>
+                Cow::Owned(format!("The {} network took too long to respond. Please try again.", chain)),
+            AppError::ChainUnavailable(chain) =>
+                Cow::Owned(format!("The {} network is unavailable right now. Please try again later.", chain)),
+            AppError::VanityNotFound { attempts } =>
+                Cow::Owned(
+                    format!("No matching address found after {} attempts. Try a shorter pattern.", attempts)
+                ),
+            AppError::RateLimited { retry_after_secs: Some(secs), .. } =>
+                Cow::Owned(format!("The network is busy. Please try again in {} seconds.", secs)),
+            AppError::RateLimited { retry_after_secs: None, .. } =>
+                Cow::Borrowed("The network is busy. Please try again in a moment."),
+            AppError::RateLimitExceeded { retry_after_secs } =>
+                Cow::Owned(format!("Too many requests. Please try again in {} seconds.", retry_after_secs)),
+            AppError::ReversionError { reason } =>
+                Cow::Owned(format!("The transaction was rejected: {}", reason)),
+        }
+    }
+
+    /// Recognise common node error messages; `None` when nothing more specific applies
+    pub fn from_rpc_message(message: &str) -> Option<AppError> {
+        let lower = message.to_lowercase();
+        if lower.contains("nonce too low") {
+            Some(AppError::NonceTooLow(message.to_string()))
+        } else if
+            lower.contains("insufficient funds for gas") ||
+            lower.contains("intrinsic gas too low") ||
+            lower.contains("gas required exceeds allowance")
+        {
+            Some(AppError::InsufficientGas)
+        } else if let Some(pos) = lower.find("execution reverted") {
+            let reason = message[pos + "execution reverted".len()..]
+                .trim_start_matches(':')
+                .trim();
+            Some(AppError::ReversionError {
+                reason: if reason.is_empty() { "no reason given".to_string() } else { reason.to_string() },
+            })
+        } else if lower.contains("too many requests") || lower.contains("rate limit") {
+            Some(AppError::RateLimited { provider: "RPC".to_string(), retry_after_secs: None })
+        } else if lower.contains("timed out") || lower.contains("timeout") {
+            Some(AppError::ConnectionTimeout { chain: "EVM".to_string(), url: String::new() })
+        } else {
+            None
+        }
+    }
+
+    pub fn to_error_response(&self) -> ErrorResponse {
+        let (message, field) = match self {
+            AppError::Database(e) => (e.to_string(), None),
+            AppError::WalletNotFound => ("Wallet not found".to_string(), None),
+            AppError::WalletAlreadyExists { existing_id } =>
+                (format!("This address is already wallet {}", existing_id), None),
+            AppError::InsufficientBalance => ("Insufficient balance for transaction".to_string(), None),
+            AppError::InsufficientFunds { available, required } =>
                 (
-                    "INVALID_MNEMONIC",
-                    "Invalid mnemonic phrase".to_string(),
-                    Some("mnemonic".to_string()),
+                    format!("Insufficient funds: available {}, required {}", available, required),
+                    Some("amount".to_string()),
                 ),
+            AppError::InvalidAddress =>
+                ("Invalid address format".to_string(), Some("address".to_string())),
+            AppError::InvalidMnemonic =>
+                ("Invalid mnemonic phrase".to_string(), Some("mnemonic".to_string())),
             AppError::InvalidPrivateKey =>
+                ("Invalid private key format".to_string(), Some("private_key".to_string())),
+            AppError::OwnershipVerificationFailed => (self.to_string(), Some("secret".to_string())),
+            | AppError::Encryption(msg)
+            | AppError::InvalidInput(msg)
+            | AppError::Chain(msg)
+            | AppError::Rpc(msg)
+            | AppError::NotFound(msg)
+            | AppError::Forbidden(msg)
+            | AppError::Config(msg)
+            | AppError::Internal(msg)
+            | AppError::External(msg)
+            | AppError::Validation(msg)
+            | AppError::Blockchain(msg)
+            | AppError::NonceTooLow(msg) => (msg.clone(), None),
+            AppError::EnsNotFound(_) => (self.to_string(), Some("to".to_string())),
+            AppError::HoneypotDetected(token) =>
                 (
-                    "INVALID_PRIVATE_KEY",
-                    "Invalid private key format".to_string(),
-                    Some("private_key".to_string()),
+                    format!("Token {} looks like a honeypot and can't be sold", token),
+                    Some("to_token".to_string()),
                 ),
-            AppError::NotFound(msg) => ("NOT_FOUND", msg.clone(), None),
-            AppError::Config(msg) => ("CONFIG_ERROR", msg.clone(), None),
-            AppError::Internal(msg) => ("INTERNAL_ERROR", msg.clone(), None),
-            AppError::External(msg) => ("EXTERNAL_ERROR", msg.clone(), None),
-            AppError::Validation(msg) => ("VALIDATION_ERROR", msg.clone(), None),
-            AppError::Blockchain(msg) => ("BLOCKCHAIN_ERROR", msg.clone(), None),
+            AppError::TransferLimitExceeded { .. } => (self.to_string(), Some("amount".to_string())),
+            AppError::PinLocked { .. } => (self.to_string(), Some("pin".to_string())),
+            AppError::InsufficientGas => (self.to_string(), Some("amount".to_string())),
+            | AppError::PriceImpactTooHigh { .. }
+            | AppError::SimulationFailed(_)
+            | AppError::ConnectionTimeout { .. }
+            | AppError::ChainUnavailable(_)
+            | AppError::VanityNotFound { .. }
+            | AppError::RateLimited { .. }
+            | AppError::RateLimitExceeded { .. }
+            | AppError::ReversionError { .. } => (self.to_string(), None),
         };
 
         ErrorResponse {
             error: ErrorDetail {
-                code: code.to_string(),
+                code: self.error_code().to_string(),
                 message,
                 field,
             },
@@ -100,26 +328,114 @@ impl AppError {
     }
 }
 
+impl From<ethers::providers::ProviderError> for AppError {
+    fn from(e: ethers::providers::ProviderError) -> Self {
+        use ethers::providers::ProviderError;
+
+        match &e {
+            ProviderError::JsonRpcClientError(inner) => {
+                if let Some(response) = inner.as_error_response() {
+                    match response.code {
+                        // EIP-1474 "limit exceeded" and the HTTP-style code some gateways return
+                        -32005 | 429 => {
+                            return AppError::RateLimited {
+                                provider: "RPC".to_string(),
+                                retry_after_secs: None,
+                            };
+                        }
+                        // Geth's code for a revert during eth_call/eth_estimateGas
+                        3 => {
+                            return AppError::from_rpc_message(&response.message).unwrap_or(
+                                AppError::ReversionError { reason: response.message.clone() }
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            ProviderError::HTTPError(http) if http.is_timeout() => {
+                return AppError::ConnectionTimeout {
+                    chain: "EVM".to_string(),
+                    url: http.url().map(|u| u.to_string()).unwrap_or_default(),
+                };
+            }
+            _ => {}
+        }
+
+        let message = e.to_string();
+        AppError::from_rpc_message(&message).unwrap_or(AppError::Rpc(message))
+    }
+}
+
 impl axum::response::IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
         let status = match &self {
             AppError::WalletNotFound => axum::http::StatusCode::NOT_FOUND,
+            AppError::WalletAlreadyExists { .. } => axum::http::StatusCode::CONFLICT,
             | AppError::InvalidInput(_)
             | AppError::InvalidAddress
             | AppError::InvalidMnemonic
             | AppError::InvalidPrivateKey => {
                 axum::http::StatusCode::BAD_REQUEST
             }
+            AppError::OwnershipVerificationFailed => axum::http::StatusCode::UNPROCESSABLE_ENTITY,
             AppError::Validation(_) => axum::http::StatusCode::BAD_REQUEST,
             AppError::Blockchain(_) => axum::http::StatusCode::BAD_REQUEST,
+            AppError::NonceTooLow(_) => axum::http::StatusCode::CONFLICT,
+            AppError::PriceImpactTooHigh { .. } => axum::http::StatusCode::BAD_REQUEST,
+            AppError::EnsNotFound(_) => axum::http::StatusCode::NOT_FOUND,
+            AppError::SimulationFailed(_) => axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::HoneypotDetected(_) => axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::TransferLimitExceeded { .. } => axum::http::StatusCode::FORBIDDEN,
+            AppError::PinLocked { .. } => axum::http::StatusCode::TOO_MANY_REQUESTS,
+            AppError::Forbidden(_) => axum::http::StatusCode::FORBIDDEN,
             AppError::InsufficientBalance => axum::http::StatusCode::BAD_REQUEST,
+            AppError::InsufficientFunds { .. } => axum::http::StatusCode::BAD_REQUEST,
+            AppError::InsufficientGas => axum::http::StatusCode::BAD_REQUEST,
+            AppError::ReversionError { .. } => axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::RateLimited { .. } => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            AppError::RateLimitExceeded { .. } => axum::http::StatusCode::TOO_MANY_REQUESTS,
+            AppError::ChainUnavailable(_) => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            AppError::VanityNotFound { .. } => axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::ConnectionTimeout { .. } => axum::http::StatusCode::GATEWAY_TIMEOUT,
             AppError::External(_) => axum::http::StatusCode::BAD_GATEWAY,
             _ => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
         };
 
-        let response = self.to_error_response();
-        (status, axum::Json(response)).into_response()
+        let mut response = (status, axum::Json(self.to_error_response())).into_response();
+        if let AppError::RateLimitExceeded { retry_after_secs } = self {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, axum::http::HeaderValue::from(retry_after_secs));
+        }
+        response
     }
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_node_error_messages() {
+        assert!(matches!(
+            AppError::from_rpc_message("nonce too low: next nonce 5, tx nonce 4"),
+            Some(AppError::NonceTooLow(_))
+        ));
+        assert!(matches!(
+            AppError::from_rpc_message("insufficient funds for gas * price + value"),
+            Some(AppError::InsufficientGas)
+        ));
+        match AppError::from_rpc_message("execution reverted: ERC20: transfer amount exceeds balance") {
+            Some(AppError::ReversionError { reason }) => {
+                assert_eq!(reason, "ERC20: transfer amount exceeds balance");
+            }
+            other => panic!("unexpected: {:?}", other),
+        }
+        assert!(AppError::from_rpc_message("header not found").is_none());
+        // The node already holds this exact transaction; that's not a nonce conflict
+        assert!(AppError::from_rpc_message("already known").is_none());
+    }
+}
@@ -11,7 +11,9 @@ pub mod api;
 pub mod bot;
 pub mod scheduler;
 pub mod alert_checker;
+pub mod price_monitor;
 pub mod dex;
+pub mod task_manager;
 
 pub use config::Config;
 pub use enums::{ Chain, AlertKind, AlertType, TxStatus, ScheduleStatus, RecurringType, SwapStatus };
@@ -1,5 +1,5 @@
 use crypto_bot::{ Config, Result };
-use axum::{ Router, routing::{ get, post } };
+use axum::{ Json, Router, routing::{ get, post } };
 use migration::MigratorTrait;
 use std::sync::Arc;
 use tokio::signal;
@@ -21,6 +21,25 @@ async fn main() -> Result<()> {
     let config = Config::from_env().map_err(|e| crypto_bot::AppError::Config(e.to_string()))?;
     tracing::info!("Starting crypto-bot with network mode: {:?}", config.network_mode);
 
+    // Fail fast on misconfiguration, before touching the schema
+    let (fatal, warnings): (Vec<_>, Vec<_>) = config
+        .validate().await?
+        .into_iter()
+        .partition(|w| w.severity == crypto_bot::config::ConfigSeverity::Fatal);
+    for warning in &warnings {
+        tracing::warn!("Config {}: {}", warning.field, warning.message);
+    }
+    if !fatal.is_empty() {
+        for warning in &fatal {
+            tracing::error!("Config {}: {}", warning.field, warning.message);
+        }
+        return Err(
+            crypto_bot::AppError::Config(
+                format!("Invalid configuration, refusing to start: {} fatal problem(s), see the log above", fatal.len())
+            )
+        );
+    }
+
     let db = sea_orm::Database
         ::connect(&config.database_url).await
         .map_err(|e| crypto_bot::AppError::Database(e))?;
@@ -33,25 +52,72 @@ async fn main() -> Result<()> {
     tracing::info!("Database migrations applied successfully");
 
     let encryptor = Arc::new(crypto_bot::crypto::Encryptor::new(&config.encryption_key)?);
-    let rpc_manager = Arc::new(crypto_bot::rpc::RpcManager::new(&config)?);
-    tracing::info!("RPC manager initialized");
+    // Uniswap default token list, loaded in the background and refreshed daily
+    let token_list = Arc::new(crypto_bot::services::TokenListService::new());
+    let task_manager = Arc::new(crypto_bot::task_manager::TaskManager::new());
+    task_manager.spawn("token_list", token_list.clone().run());
 
     let repository = Arc::new(crypto_bot::db::WalletRepository::new(db.clone()));
+
+    let rpc_manager = Arc::new(
+        crypto_bot::rpc::RpcManager
+            ::new(&config, Some(token_list.clone()))?
+            .with_wallet_overrides(repository.clone(), encryptor.clone())
+    );
+    tracing::info!("RPC manager initialized");
+
+    // Unreachable chains are disabled rather than failing startup
+    let connectivity = Arc::new(
+        crypto_bot::services::ConnectivityChecker::check_all(&config).await
+    );
+    tracing::info!(
+        "RPC connectivity:\n{}",
+        crypto_bot::services::ConnectivityChecker::format_table(&connectivity)
+    );
+    for chain in crypto_bot::services::ConnectivityChecker::unavailable_chains(&connectivity) {
+        tracing::warn!("No reachable RPC endpoint for {}; marking it unavailable", chain);
+        rpc_manager.mark_unavailable(chain).await;
+    }
+    task_manager.spawn(
+        "connectivity_reprobe",
+        crypto_bot::services::ConnectivityChecker::run_reprobe(Arc::new(config.clone()), rpc_manager.clone())
+    );
+
     let transaction_repo = Arc::new(crypto_bot::db::TransactionRepository::new(db.clone()));
     let token_metadata_repo = Arc::new(crypto_bot::db::TokenMetadataRepository::new(db.clone()));
-
-    // Optional: Alchemy token discovery service
+    let portfolio_snapshot_repo = Arc::new(crypto_bot::db::PortfolioSnapshotRepository::new(db.clone()));
+    let swap_repo = Arc::new(crypto_bot::db::SwapRepository::new(db.clone()));
+    let tax_lot_repo = Arc::new(crypto_bot::db::TaxLotRepository::new(db.clone()));
+
+    // Optional: token discovery (Alchemy for EVM chains, RPC token accounts for Solana)
+    if config.alchemy_api_key.is_some() {
+        tracing::info!("Alchemy API key found — EVM token discovery enabled");
+    } else {
+        tracing::warn!("ALCHEMY_API_KEY not set — EVM token discovery disabled");
+    }
+    let solana_rpc_url = config.chain_configs
+        .get(&crypto_bot::enums::Chain::Solana)
+        .and_then(|c| c.rpc_urls.first().cloned());
     let token_discovery: Option<Arc<crypto_bot::services::TokenDiscoveryService>> =
-        config.alchemy_api_key.as_ref().map(|key| {
-            tracing::info!("Alchemy API key found — token discovery enabled");
-            Arc::new(crypto_bot::services::TokenDiscoveryService::new(
-                key.clone(),
+        if config.alchemy_api_key.is_some() || solana_rpc_url.is_some() {
+            let mut discovery = crypto_bot::services::TokenDiscoveryService::new(
+                config.alchemy_api_key.clone(),
                 token_metadata_repo.clone(),
-            ))
-        });
-    if token_discovery.is_none() {
-        tracing::warn!("ALCHEMY_API_KEY not set — token discovery disabled");
-    }
+            )
+                .with_token_list(token_list.clone())
+                .with_discovery_cache(
+                    Arc::new(crypto_bot::db::TokenDiscoveryCacheRepository::new(db.clone())),
+                    config.token_discovery_interval_secs,
+                );
+            if let Some(url) = solana_rpc_url.clone() {
+                discovery = discovery.with_solana(
+                    crypto_bot::services::solana_token_discovery::SolanaTokenDiscovery::new(url)
+                );
+            }
+            Some(Arc::new(discovery))
+        } else {
+            None
+        };
 
     let is_testnet = config.is_testnet();
 
@@ -63,34 +129,63 @@ async fn main() -> Result<()> {
         )
     );
 
+    let price_service = Arc::new(
+        crypto_bot::services::PriceService
+            ::new()
+            .with_oracle(crypto_bot::services::OnChainPriceOracle::new(&config))
+    );
+
     let balance_service = Arc::new(
-        crypto_bot::services::BalanceService::new(
-            repository.clone(),
-            rpc_manager.clone(),
-            encryptor.clone(),
-            token_discovery.clone(),
-            is_testnet,
-        )
+        crypto_bot::services::BalanceService
+            ::new(
+                repository.clone(),
+                rpc_manager.clone(),
+                encryptor.clone(),
+                token_discovery.clone(),
+                is_testnet,
+            )
+            .with_price_service(price_service.clone())
+            .with_block_subscriptions(config.ws_urls())
+            .with_nft_service(
+                Arc::new(crypto_bot::services::NftService::new(config.alchemy_api_key.clone()))
+            )
     );
 
-    let transfer_service = Arc::new(
-        crypto_bot::services::TransferService::new(
-            repository.clone(),
+    // Shared by the bot's history view and the REST API, invalidated by every transaction write
+    let recent_transactions = Arc::new(crypto_bot::services::RecentTransactionCache::default());
+
+    let mut explorer_service = crypto_bot::services::ExplorerService::new(config.etherscan_api_key.clone());
+    if let Some(url) = solana_rpc_url {
+        explorer_service = explorer_service.with_solana_rpc(url);
+    }
+
+    let transaction_service = Arc::new(
+        crypto_bot::services::TransactionService::new(
             transaction_repo.clone(),
+            repository.clone(),
             rpc_manager.clone(),
             encryptor.clone()
         )
+            .with_tax_lots(tax_lot_repo.clone(), price_service.clone())
+            .with_fee_tracking(price_service.clone())
+            .with_gas_refund_tracker(
+                Arc::new(crypto_bot::services::GasRefundTracker::new(rpc_manager.clone()))
+            )
+            .with_bridge_service(Arc::new(crypto_bot::services::PolygonBridgeService::new()))
+            .with_recent_cache(recent_transactions.clone())
+            .with_explorer(Arc::new(explorer_service))
     );
 
-    let transaction_service = Arc::new(
-        crypto_bot::services::TransactionService::new(transaction_repo.clone(), repository.clone())
-    );
+    task_manager.spawn("tx_confirmations", transaction_service.clone().run_confirmations());
 
-    let price_service = Arc::new(crypto_bot::services::PriceService::new());
+    let tax_report_service = Arc::new(
+        crypto_bot::services::TaxReportService::new(tax_lot_repo.clone())
+    );
 
     let portfolio_service = Arc::new(
         crypto_bot::services::PortfolioService::new(
             repository.clone(),
+            portfolio_snapshot_repo.clone(),
             rpc_manager.clone(),
             price_service.clone(),
             token_discovery.clone(),
@@ -98,116 +193,354 @@ async fn main() -> Result<()> {
         )
     );
 
-    let address_book_service = Arc::new(
-        crypto_bot::services::AddressBookService::new(Arc::new(db.clone()))
+    // Background task: keep prices warm for symbols the portfolio and alerts need
+    let price_monitor = Arc::new(
+        crypto_bot::price_monitor::PriceMonitor::new(config.price_monitor_interval_secs)
+    );
+    portfolio_service.register_watched_symbols(&price_monitor);
+
+    let cross_chain_balance_service = Arc::new(
+        crypto_bot::services::CrossChainBalanceService::new(portfolio_service.clone())
     );
+    let monitor = price_monitor.clone();
+    let monitor_price_service = price_service.clone();
+    task_manager.spawn("price_monitor", async move {
+        monitor.run(monitor_price_service).await;
+    });
+
+    // ENS resolution is only available when an Ethereum RPC is configured
+    let mut address_book_service = crypto_bot::services::AddressBookService::new(Arc::new(db.clone()));
+    if let Some(url) = config.chain_configs
+        .get(&crypto_bot::enums::Chain::Eth)
+        .and_then(|c| c.rpc_urls.first())
+    {
+        match crypto_bot::chains::evm::EnsResolver::new(url) {
+            Ok(resolver) => {
+                address_book_service = address_book_service.with_ens_resolver(resolver);
+            }
+            Err(e) => tracing::warn!("ENS resolution disabled: {}", e),
+        }
+    }
+    let address_book_service = Arc::new(address_book_service);
 
     let gas_estimation_service = Arc::new(
-        crypto_bot::services::GasEstimationService::new(
+        crypto_bot::services::GasEstimationService
+            ::new(repository.clone(), rpc_manager.clone(), price_service.clone())
+            .with_gas_station(
+                crypto_bot::services::gas_station::GasStationClient::new(
+                    config.gas_station_provider,
+                    config.gas_station_api_key.clone()
+                )
+            )
+    );
+
+    let security_service = Arc::new(
+        crypto_bot::services::security_service::SecurityService::new(db.clone())
+    );
+
+    let user_preference_service = Arc::new(
+        crypto_bot::services::UserPreferenceService::new(db.clone())
+    );
+
+    let user_session_service = Arc::new(
+        crypto_bot::services::UserSessionService::new(db.clone())
+    );
+
+    let transfer_service = Arc::new(
+        crypto_bot::services::TransferService
+            ::new(
+                repository.clone(),
+                transaction_repo.clone(),
+                rpc_manager.clone(),
+                encryptor.clone(),
+                balance_service.clone(),
+                gas_estimation_service.clone()
+            )
+            .with_transfer_limit(security_service.clone(), price_service.clone())
+            .with_recent_cache(recent_transactions.clone())
+            .with_retry_config(config.transfer_retry_config.clone())
+    );
+
+    let token_approval_service = Arc::new(
+        crypto_bot::services::TokenApprovalService::new(
             repository.clone(),
             rpc_manager.clone(),
-            price_service.clone()
+            encryptor.clone()
         )
     );
 
+    let transaction_simulator = Arc::new(
+        crypto_bot::services::TransactionSimulator::new(repository.clone(), rpc_manager.clone())
+    );
+
     let scheduling_service = Arc::new(
-        crypto_bot::services::scheduling_service::SchedulingService::new(db.clone())
+        crypto_bot::services::scheduling_service::SchedulingService
+            ::new(db.clone())
+            .with_preflight_checks(balance_service.clone(), gas_estimation_service.clone())
     );
 
+    // Lets the API and bot ask the alert checker for an immediate pass
+    let (alert_check_handle, alert_check_requests) =
+        crypto_bot::services::price_alert_service::alert_check_channel();
     let price_alert_service = Arc::new(
-        crypto_bot::services::price_alert_service::PriceAlertService::new(db.clone())
+        crypto_bot::services::price_alert_service::PriceAlertService
+            ::new(db.clone())
+            .with_alert_checker(alert_check_handle)
     );
 
-    let security_service = Arc::new(
-        crypto_bot::services::security_service::SecurityService::new(db.clone())
+    let rebalancing_service = Arc::new(
+        crypto_bot::services::rebalancing_service::RebalancingService::new(db.clone())
+    );
+
+    let mut swap_service = crypto_bot::services::swap_service::SwapService
+        ::new(db.clone(), wallet_service.clone(), config.max_price_impact_pct)
+        .with_fee_check(gas_estimation_service.clone(), price_service.clone())
+        .with_token_metadata(token_metadata_repo.clone());
+    if !config.skip_security_checks {
+        swap_service = swap_service.with_token_security(
+            Arc::new(crypto_bot::services::TokenSecurityService::new(rpc_manager.clone()))
+        );
+    }
+    let swap_service = Arc::new(swap_service);
+
+    let defi_protocol_service = Arc::new(
+        crypto_bot::services::DefiProtocolService::new(
+            repository.clone(),
+            rpc_manager.clone(),
+            encryptor.clone(),
+            price_service.clone(),
+            config.aave_subgraph_urls.clone()
+        )
+    );
+
+    let dca_service = Arc::new(
+        crypto_bot::services::DcaService::new(
+            db.clone(),
+            scheduling_service.clone(),
+            swap_service.clone(),
+            defi_protocol_service.clone()
+        )
     );
 
-    let swap_service = Arc::new(
-        crypto_bot::services::swap_service::SwapService::new(db.clone(), wallet_service.clone())
+    let block_service = Arc::new(crypto_bot::services::BlockService::new(rpc_manager.clone()));
+
+    // Background task: EVM gas price and mempool sampling
+    let network_congestion = Arc::new(
+        crypto_bot::services::NetworkCongestionService::new(rpc_manager.clone())
     );
+    task_manager.spawn("network_congestion", network_congestion.clone().run());
 
     let config_clone = config.clone();
 
     // Background task: scheduled transaction executor
     let scheduler_db = db.clone();
     let scheduler_transfer_service = transfer_service.clone();
-    tokio::spawn(async move {
+    let scheduler_gas_estimation_service = gas_estimation_service.clone();
+    let scheduler_dca_service = dca_service.clone();
+    let scheduler_bot_token = config.telegram_bot_token.clone();
+    task_manager.spawn("scheduler", async move {
         let scheduler = crypto_bot::scheduler::Scheduler::new(
             scheduler_db,
-            scheduler_transfer_service
-        );
+            scheduler_transfer_service,
+            scheduler_gas_estimation_service,
+            teloxide::Bot::new(scheduler_bot_token)
+        ).with_dca_service(scheduler_dca_service);
         scheduler.start().await;
     });
 
     // Background task: Telegram bot
-    let bot_wallet_service = wallet_service.clone();
-    let bot_balance_service = balance_service.clone();
-    let bot_transfer_service = transfer_service.clone();
-    let bot_transaction_service = transaction_service.clone();
-    let bot_portfolio_service = portfolio_service.clone();
-    let bot_price_service = price_service.clone();
-    let bot_address_book_service = address_book_service.clone();
-    let bot_gas_estimation_service = gas_estimation_service.clone();
-    let bot_scheduling_service = scheduling_service.clone();
-    let bot_price_alert_service = price_alert_service.clone();
-    let bot_security_service = security_service.clone();
-    let bot_swap_service = swap_service.clone();
-    let bot_encryptor = encryptor.clone();
+    let bot = teloxide::Bot::new(config.telegram_bot_token.clone());
     let bot_config = Arc::new(config.clone());
-    let bot_token = config.telegram_bot_token.clone();
-
-    tokio::spawn(async move {
-        crypto_bot::bot::run_bot(
-            bot_token,
-            bot_wallet_service,
-            bot_balance_service,
-            bot_transfer_service,
-            bot_transaction_service,
-            bot_portfolio_service,
-            bot_price_service,
-            bot_address_book_service,
-            bot_gas_estimation_service,
-            bot_scheduling_service,
-            bot_price_alert_service,
-            bot_security_service,
-            bot_swap_service,
-            bot_encryptor,
-            bot_config,
-        ).await;
+    let dialogue_storage: crypto_bot::bot::DialogueStorage = match config.dialogue_storage_backend {
+        crypto_bot::config::DialogueStorageBackend::Memory =>
+            Arc::new(crypto_bot::bot::dialogue_storage::InMemoryDialogueStorage::new()),
+        crypto_bot::config::DialogueStorageBackend::Database =>
+            Arc::new(crypto_bot::bot::dialogue_storage::DatabaseDialogueStorage::new(db.clone())),
+    };
+    let bot_state = Arc::new(crypto_bot::bot::BotState {
+        wallet_service: wallet_service.clone(),
+        balance_service: balance_service.clone(),
+        transfer_service: transfer_service.clone(),
+        transaction_service: transaction_service.clone(),
+        portfolio_service: portfolio_service.clone(),
+        cross_chain_balance_service: cross_chain_balance_service.clone(),
+        price_service: price_service.clone(),
+        address_book_service: address_book_service.clone(),
+        gas_estimation_service: gas_estimation_service.clone(),
+        scheduling_service: scheduling_service.clone(),
+        price_alert_service: price_alert_service.clone(),
+        rebalancing_service: rebalancing_service.clone(),
+        tax_report_service: tax_report_service.clone(),
+        security_service: security_service.clone(),
+        swap_service: swap_service.clone(),
+        swap_repository: swap_repo.clone(),
+        dca_service: dca_service.clone(),
+        defi_protocol_service: defi_protocol_service.clone(),
+        block_service: block_service.clone(),
+        network_congestion: network_congestion.clone(),
+        token_approval_service: token_approval_service.clone(),
+        transaction_simulator: transaction_simulator.clone(),
+        user_preference_service: user_preference_service.clone(),
+        user_session_service: user_session_service.clone(),
+        broadcast_service: Arc::new(crypto_bot::services::BroadcastService::new(db.clone())),
+        group_permission_service: Arc::new(crypto_bot::services::GroupPermissionService::new(db.clone())),
+        recent_transactions: recent_transactions.clone(),
+        encryptor: encryptor.clone(),
+        rate_limiter: Arc::new(
+            crypto_bot::bot::rate_limiter::RateLimiter::new(config.rate_limit_max_tokens, config.rate_limit_refill_rate)
+        ),
+        balance_watcher: Arc::new(
+            crypto_bot::services::BalanceWatcher::new(
+                balance_service.clone(),
+                bot.clone(),
+                config.balance_change_threshold_pct
+            )
+        ),
+        config: bot_config,
+        dialogue_storage,
+        task_manager: task_manager.clone(),
     });
 
+    task_manager.spawn("telegram_bot", crypto_bot::bot::run_bot(bot, bot_state));
+
     // Background task: price alert checker
     let alert_db = db.clone();
     let alert_price_service = price_service.clone();
+    let alert_balance_service = balance_service.clone();
+    let alert_swap_service = swap_service.clone();
+    let alert_portfolio_service = portfolio_service.clone();
+    let alert_price_monitor = price_monitor.clone();
+    let alert_gas_estimation_service = gas_estimation_service.clone();
     let alert_bot_token = config.telegram_bot_token.clone();
+    let mempool_watcher = crypto_bot::services::MempoolWatcher::new(
+        db.clone(),
+        price_service.clone(),
+        &config
+    );
 
-    tokio::spawn(async move {
+    task_manager.spawn("alert_checker", async move {
         let bot = teloxide::Bot::new(alert_bot_token);
         let alert_checker = crypto_bot::alert_checker::AlertChecker::new(
             alert_db,
             alert_price_service,
+            alert_balance_service,
+            alert_swap_service,
+            alert_portfolio_service,
+            alert_price_monitor,
             bot
-        );
+        )
+            .with_mempool_watcher(mempool_watcher)
+            .with_gas_estimation_service(alert_gas_estimation_service)
+            .with_manual_checks(alert_check_requests);
         alert_checker.start().await;
     });
 
-    let app_state = crypto_bot::api::AppState::new(
+    let app_state = crypto_bot::api::AppState {
         wallet_service,
         balance_service,
         transfer_service,
-        transaction_service
-    );
+        transaction_service,
+        swap_repository: swap_repo,
+        portfolio_service,
+        cross_chain_balance_service,
+        tax_report_service,
+        price_service,
+        scheduling_service,
+        price_alert_service,
+        security_service,
+        block_service,
+        network_congestion,
+        admin_allowed_ips: Arc::new(config_clone.admin_allowed_ips.clone()),
+        admin_api_key: config_clone.admin_api_key.as_deref().map(Arc::from),
+    };
+
+    // Per-IP request limits on the routes most worth protecting
+    let per_minute_limit = |limit: u32| {
+        axum::middleware::from_fn_with_state(
+            Arc::new(crypto_bot::api::rate_limit::ApiRateLimiter::per_minute(limit)),
+            crypto_bot::api::rate_limit::rate_limit
+        )
+    };
+    let wallet_creation_limit = per_minute_limit(crypto_bot::api::rate_limit::WALLET_CREATION_PER_MINUTE);
 
+    let health_price_monitor = price_monitor.clone();
+    let health_recent_transactions = recent_transactions.clone();
+    let health_task_manager = task_manager.clone();
     let app = Router::new()
-        .route("/health", get(health_check))
-        .route("/api/wallets/generate", post(crypto_bot::api::wallet::generate_wallet))
-        .route("/api/wallets/restore", post(crypto_bot::api::wallet::restore_wallet))
+        .route(
+            "/health",
+            get(move || health_check(health_price_monitor.clone(), health_recent_transactions.clone()))
+        )
+        .route("/health/tasks", get(move || task_health(health_task_manager.clone())))
+        .route("/health/connectivity", get(move || connectivity_check(connectivity.clone())))
+        .route(
+            "/api/wallets/generate",
+            post(crypto_bot::api::wallet::generate_wallet).layer(wallet_creation_limit.clone())
+        )
+        .route(
+            "/api/wallets/restore",
+            post(crypto_bot::api::wallet::restore_wallet).layer(wallet_creation_limit)
+        )
+        .route("/api/wallets/statistics", get(crypto_bot::api::wallet::get_wallet_statistics))
         .route("/api/wallets/{id}", get(crypto_bot::api::wallet::get_wallet))
-        .route("/api/wallets/{id}/balance", get(crypto_bot::api::balance::get_balance))
-        .route("/api/wallets/{id}/transfer", post(crypto_bot::api::transfer::send_transaction))
+        .route(
+            "/api/wallets/{id}/balance",
+            get(crypto_bot::api::balance::get_balance).layer(
+                per_minute_limit(crypto_bot::api::rate_limit::BALANCE_PER_MINUTE)
+            )
+        )
+        .route("/api/wallets/{id}/balance/ws", get(crypto_bot::api::balance::watch_balance))
+        .route(
+            "/api/wallets/{id}/balance/historical",
+            get(crypto_bot::api::balance::get_historical_balance).layer(
+                per_minute_limit(crypto_bot::api::rate_limit::BALANCE_PER_MINUTE)
+            )
+        )
+        .route(
+            "/api/wallets/{id}/lightning/invoice",
+            get(crypto_bot::api::wallet::get_lightning_invoice)
+        )
+        .route(
+            "/api/wallets/{id}/transfer",
+            post(crypto_bot::api::transfer::send_transaction).layer(
+                per_minute_limit(crypto_bot::api::rate_limit::SEND_TRANSACTION_PER_MINUTE)
+            )
+        )
         .route("/api/wallets/{id}/transactions", get(crypto_bot::api::transaction::get_wallet_transactions))
+        .route(
+            "/api/wallets/{id}/transactions/received",
+            get(crypto_bot::api::transaction::get_received_transactions)
+        )
+        .route(
+            "/api/wallets/{id}/bridge/pending",
+            get(crypto_bot::api::transaction::get_pending_bridge_transactions)
+        )
         .route("/api/transactions", get(crypto_bot::api::transaction::get_user_transactions))
         .route("/api/transactions/{tx_hash}", get(crypto_bot::api::transaction::get_transaction))
+        .route(
+            "/api/chains/{chain}/transactions/{tx_hash}",
+            get(crypto_bot::api::transaction::get_chain_transaction)
+        )
+        .route("/api/swaps", get(crypto_bot::api::swap::get_swaps))
+        .route("/api/portfolio", get(crypto_bot::api::portfolio::get_portfolio))
+        .route("/api/portfolio/benchmark", get(crypto_bot::api::portfolio::get_benchmark))
+        .route("/api/portfolio/card.png", get(crypto_bot::api::portfolio::get_portfolio_card))
+        .route("/api/portfolio/dust", get(crypto_bot::api::portfolio::get_dust_wallets))
+        .route("/api/portfolio/by-token", get(crypto_bot::api::portfolio::get_by_token))
+        .route("/api/tax-report", get(crypto_bot::api::tax::get_tax_report))
+        .route("/api/analytics/fees", get(crypto_bot::api::analytics::get_fees))
+        .route("/api/schedules/calendar", get(crypto_bot::api::schedule::get_schedule_calendar))
+        .route("/api/schedules/simulate", post(crypto_bot::api::schedule::simulate_schedule))
+        .route("/api/chains/{chain}/blocks/{number}", get(crypto_bot::api::block::get_block))
+        .route("/api/chains/{chain}/congestion", get(crypto_bot::api::congestion::get_congestion))
+        .route("/api/prices/{symbol}/chart", get(crypto_bot::api::price::get_price_chart))
+        .route("/api/prices/{symbol}/ohlcv", get(crypto_bot::api::price::get_ohlcv))
+        .route("/api/security/pin-status", get(crypto_bot::api::security::get_pin_status))
+        .route("/admin/rotate-key", post(crypto_bot::api::admin::rotate_key))
+        .route("/admin/alerts/check", post(crypto_bot::api::admin::check_alerts))
+        .route("/admin/alerts/status", get(crypto_bot::api::admin::alert_status))
+        .route("/admin/wallets/count", get(crypto_bot::api::admin::wallet_count))
+        .route("/admin/wallets/export", post(crypto_bot::api::admin::export_wallets))
         .with_state(app_state)
         .layer(CorsLayer::permissive());
 
@@ -219,12 +552,14 @@ async fn main() -> Result<()> {
         ::bind(&addr).await
         .map_err(|e| crypto_bot::AppError::Internal(e.to_string()))?;
 
-    axum::serve(listener, app)
+    // Client addresses are needed for the admin IP allowlist and per-IP rate limits
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await
         .map_err(|e| crypto_bot::AppError::Internal(e.to_string()))?;
 
     tracing::info!("Shutting down...");
+    task_manager.shutdown_all(crypto_bot::task_manager::SHUTDOWN_TIMEOUT).await;
     Ok(())
 }
 
@@ -252,6 +587,25 @@ async fn shutdown_signal() {
     }
 }
 
-async fn health_check() -> &'static str {
-    "OK"
+async fn health_check(
+    price_monitor: Arc<crypto_bot::price_monitor::PriceMonitor>,
+    recent_transactions: Arc<crypto_bot::services::RecentTransactionCache>
+) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "OK",
+        "watched_symbols": price_monitor.watch_count(),
+        "transaction_cache_hit_rate": recent_transactions.cache_hit_rate(),
+    }))
+}
+
+async fn task_health(
+    task_manager: Arc<crypto_bot::task_manager::TaskManager>
+) -> Json<Vec<crypto_bot::task_manager::TaskInfo>> {
+    Json(task_manager.list())
+}
+
+async fn connectivity_check(
+    results: Arc<Vec<crypto_bot::services::connectivity_checker::ConnectivityResult>>
+) -> Json<Vec<crypto_bot::services::connectivity_checker::ConnectivityResult>> {
+    Json(results.as_ref().clone())
 }
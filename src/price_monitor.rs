@@ -0,0 +1,61 @@
+use crate::services::price_service::PriceService;
+use dashmap::DashSet;
+use std::sync::Arc;
+use tokio::time::{ interval, Duration };
+
+/// Keeps the price cache warm for a registry of watched symbols
+pub struct PriceMonitor {
+    watched: DashSet<String>,
+    interval_secs: u64,
+}
+
+impl PriceMonitor {
+    pub fn new(interval_secs: u64) -> Self {
+        Self {
+            watched: DashSet::new(),
+            interval_secs,
+        }
+    }
+
+    /// Start watching a symbol (case-insensitive)
+    pub fn register(&self, symbol: &str) {
+        self.watched.insert(symbol.to_uppercase());
+    }
+
+    /// Stop watching a symbol
+    pub fn unregister(&self, symbol: &str) {
+        self.watched.remove(&symbol.to_uppercase());
+    }
+
+    /// Number of symbols currently being watched
+    pub fn watch_count(&self) -> usize {
+        self.watched.len()
+    }
+
+    /// Refresh all watched and subscribed symbols on every tick, which publishes the new
+    /// prices to `PriceService` subscribers
+    pub async fn run(&self, price_service: Arc<PriceService>) {
+        let mut interval = interval(Duration::from_secs(self.interval_secs));
+
+        loop {
+            interval.tick().await;
+
+            let mut symbols: Vec<String> = self.watched
+                .iter()
+                .map(|s| s.clone())
+                .collect();
+            for symbol in price_service.subscribed_symbols() {
+                if !self.watched.contains(&symbol) {
+                    symbols.push(symbol);
+                }
+            }
+            if symbols.is_empty() {
+                continue;
+            }
+
+            if let Err(e) = price_service.get_prices(&symbols).await {
+                tracing::warn!("Price monitor refresh failed: {}", e);
+            }
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use serde::{ Deserialize, Serialize };
 
-use crate::error::Result;
+use crate::enums::DefiProtocol;
+use crate::error::{ AppError, Result };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletInfo {
@@ -16,6 +19,9 @@ pub struct Balance {
     pub balance: String,
     pub symbol: String,
     pub decimals: u8,
+    /// USD value of the balance, when a price is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_value: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,11 +45,26 @@ pub struct TransactionResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GasEstimate {
     pub estimated_gas: u64,
+    /// `estimated_gas` plus a safety buffer, used as the transaction's gas limit
+    pub gas_limit: u64,
     pub gas_price: Option<String>,
     pub max_fee_per_gas: Option<String>,
     pub max_priority_fee_per_gas: Option<String>,
     pub total_cost_native: String,
     pub total_cost_usd: Option<f64>,
+    /// Rollup fee for posting the transaction to L1, already included in `total_cost_native`
+    pub l1_data_fee_native: Option<String>,
+    pub l1_data_fee_usd: Option<f64>,
+    /// Gas station's confidence that `max_fee_per_gas` gets included (None for node estimates)
+    pub confidence_pct: Option<u8>,
+}
+
+/// Extra gas on top of the node's estimate so small state changes don't run out of gas
+const GAS_LIMIT_BUFFER_PCT: u64 = 20;
+
+/// Gas limit for a transaction estimated at `estimated_gas`
+pub fn gas_limit_with_buffer(estimated_gas: u64) -> u64 {
+    estimated_gas.saturating_add(estimated_gas * GAS_LIMIT_BUFFER_PCT / 100)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +77,113 @@ pub struct TokenBalanceEntry {
     pub logo_url: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenAllowance {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub spender: String,
+    pub allowance: String,
+}
+
+/// Base asset an address has supplied to a lending market, interest included
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LendingSupply {
+    /// Market contract (the Comet on Compound III)
+    pub market_address: String,
+    pub asset_symbol: String,
+    /// Supplied amount in whole units
+    pub supplied: String,
+    /// Current supply APY in percent
+    pub supply_apy: f64,
+}
+
+/// Incentive tokens accrued on a lending position and not yet claimed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LendingReward {
+    pub token_address: String,
+    pub token_symbol: String,
+    /// Unclaimed amount in whole units
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub success: bool,
+    pub revert_reason: Option<String>,
+    pub gas_used: u64,
+    pub state_changes: Vec<StateChange>,
+}
+
+/// Expected balance change for one address if the simulated transaction executes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateChange {
+    pub address: String,
+    pub asset: String,
+    pub change: String,
+}
+
+/// Basic on-chain facts about a token contract, for chains without an external security API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenInspection {
+    pub is_contract: bool,
+    pub is_proxy: bool,
+    /// Set when a zero-value transfer from an arbitrary holder reverts
+    pub transfer_revert_reason: Option<String>,
+}
+
+/// L1 submission cost of a confirmed rollup transaction, in the native token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L1FeeBreakdown {
+    /// Address that paid the fee
+    pub payer: String,
+    pub l1_fee_paid: String,
+    /// Gas reserved by the transaction's limit but not charged, at the effective gas price
+    pub l1_refund: String,
+}
+
+/// A transaction as the chain reports it, whether or not this bot sent it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionDetail {
+    pub hash: String,
+    /// `None` while the transaction is still pending
+    pub block_number: Option<u64>,
+    pub from: String,
+    pub to: Option<String>,
+    /// Native amount transferred, in whole units
+    pub value: String,
+    pub status: String,
+    pub gas_used: Option<u64>,
+    /// Network fee paid, in whole native units; `None` while pending
+    pub fee: Option<String>,
+    /// Block time (unix seconds)
+    pub timestamp: Option<i64>,
+}
+
+/// Header facts about a block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockInfo {
+    pub number: u64,
+    pub hash: String,
+    /// Block time (unix seconds)
+    pub timestamp: i64,
+    pub transaction_count: u64,
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    /// EIP-1559 base fee; `None` on blocks before London
+    pub base_fee_gwei: Option<f64>,
+}
+
+/// Fee market and mempool snapshot of a chain, in Gwei
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkLoad {
+    pub gas_price_gwei: f64,
+    /// EIP-1559 base fee of the latest block; `None` on chains without one
+    pub base_fee_gwei: Option<f64>,
+    pub priority_fee_gwei: Option<f64>,
+    /// Transactions waiting in the node's mempool, or in the pending block as a fallback
+    pub pending_tx_count: Option<u64>,
+}
+
 #[async_trait]
 pub trait ChainProvider: Send + Sync {
     /// Generate a new wallet with 24-word mnemonic
@@ -70,6 +198,48 @@ pub trait ChainProvider: Send + Sync {
     /// Get token balance (ERC20/SPL)
     async fn get_token_balance(&self, address: &str, token_address: &str) -> Result<Balance>;
 
+    /// Native balance, or a token balance when `token_address` is set, as of `block_number`.
+    /// Needs a node that keeps historical state (an archive node on EVM chains).
+    async fn get_historical_balance(
+        &self,
+        _address: &str,
+        _token_address: Option<&str>,
+        _block_number: u64
+    ) -> Result<Balance> {
+        Err(AppError::Chain("Historical balances are not supported on this chain".to_string()))
+    }
+
+    /// Number of the latest block
+    async fn get_block_number(&self) -> Result<u64> {
+        Err(AppError::Chain("Block numbers are not available on this chain".to_string()))
+    }
+
+    /// Header of block `block_number`
+    async fn get_block_by_number(&self, _block_number: u64) -> Result<BlockInfo> {
+        Err(AppError::Chain("Block lookup is not supported on this chain".to_string()))
+    }
+
+    /// Current gas prices and mempool size
+    async fn get_network_load(&self) -> Result<NetworkLoad> {
+        Err(AppError::Chain("Network load is not available on this chain".to_string()))
+    }
+
+    /// Base fees of the `block_count` blocks up to `newest_block`, oldest first
+    async fn get_base_fee_history(&self, _block_count: u64, _newest_block: u64) -> Result<Vec<f64>> {
+        Err(AppError::Chain("Fee history is not available on this chain".to_string()))
+    }
+
+    /// Token contracts that emitted a `Transfer` to or from `address` in blocks
+    /// `from_block..=to_block`, each listed once
+    async fn get_token_transfer_contracts(
+        &self,
+        _address: &str,
+        _from_block: u64,
+        _to_block: u64
+    ) -> Result<Vec<String>> {
+        Err(AppError::Chain("Token transfer logs are not available on this chain".to_string()))
+    }
+
     /// Send transaction (native or token)
     async fn send_transaction(
         &self,
@@ -86,6 +256,119 @@ pub trait ChainProvider: Send + Sync {
         token_address: Option<&str>
     ) -> Result<GasEstimate>;
 
+    /// Cost of `approve(spender, MAX)` on a token, or `None` when `spender` already has an
+    /// allowance. Only meaningful for contracts that pull tokens, such as DEX routers.
+    async fn estimate_approval(
+        &self,
+        _owner: &str,
+        _token_address: &str,
+        _spender: &str
+    ) -> Result<Option<GasEstimate>> {
+        Err(AppError::Chain("Token approvals are not supported on this chain".to_string()))
+    }
+
     /// Validate address format
     fn validate_address(&self, address: &str) -> bool;
+
+    /// Look up any transaction by hash; `None` when the node doesn't know it
+    async fn get_transaction_by_hash(&self, _hash: &str) -> Result<Option<TransactionDetail>> {
+        Err(AppError::Chain("Transaction lookup is not supported on this chain".to_string()))
+    }
+
+    /// Rebroadcast a pending transaction with the same nonce and a higher fee
+    async fn speed_up_transaction(
+        &self,
+        _private_key: &str,
+        _tx_hash: &str,
+        _max_fee_per_gas: Option<String>
+    ) -> Result<TransactionResponse> {
+        Err(AppError::Chain("Transaction replacement is not supported on this chain".to_string()))
+    }
+
+    /// Replace a pending transaction with a zero-value self-transfer using the same nonce
+    async fn cancel_transaction(
+        &self,
+        _private_key: &str,
+        _tx_hash: &str
+    ) -> Result<TransactionResponse> {
+        Err(AppError::Chain("Transaction replacement is not supported on this chain".to_string()))
+    }
+
+    /// List tokens `owner` has approved for spending that still carry a non-zero allowance
+    async fn list_token_approvals(&self, _owner: &str) -> Result<Vec<TokenAllowance>> {
+        Err(AppError::Chain("Token approvals are not supported on this chain".to_string()))
+    }
+
+    /// Set a spender's allowance on a token back to zero
+    async fn revoke_token_approval(
+        &self,
+        _private_key: &str,
+        _token_address: &str,
+        _spender: &str
+    ) -> Result<TransactionResponse> {
+        Err(AppError::Chain("Token approvals are not supported on this chain".to_string()))
+    }
+
+    /// What `owner` supplies to this chain's Compound III market; `None` when nothing is supplied
+    /// or no market is deployed
+    async fn get_compound_supply(&self, _owner: &str) -> Result<Option<LendingSupply>> {
+        Err(AppError::Chain("Compound is not supported on this chain".to_string()))
+    }
+
+    /// Rewards `owner` has accrued on `protocol` and not claimed. `assets` are the Aave aTokens
+    /// to check; Compound ignores them.
+    async fn get_lending_rewards(
+        &self,
+        _protocol: DefiProtocol,
+        _owner: &str,
+        _assets: &[String]
+    ) -> Result<Vec<LendingReward>> {
+        Err(AppError::Chain("DeFi rewards are not supported on this chain".to_string()))
+    }
+
+    /// Claim `protocol` rewards and supply them back into the protocol, returning the last supply
+    async fn compound_lending_rewards(
+        &self,
+        _private_key: &str,
+        _protocol: DefiProtocol,
+        _assets: &[String]
+    ) -> Result<TransactionResponse> {
+        Err(AppError::Chain("DeFi rewards are not supported on this chain".to_string()))
+    }
+
+    /// Dry-run a transfer against the latest state without broadcasting it
+    async fn simulate_transaction(&self, _request: &TransactionRequest) -> Result<SimulationResult> {
+        Err(AppError::Chain("Transaction simulation is not supported on this chain".to_string()))
+    }
+
+    /// Inspect a token contract's code and simulate a transfer to spot tokens that can't be sold
+    async fn inspect_token(&self, _token_address: &str) -> Result<TokenInspection> {
+        Err(AppError::Chain("Token inspection is not supported on this chain".to_string()))
+    }
+
+    /// Decimals for several token contracts (mints on Solana) in as few RPC calls as possible,
+    /// keyed by the address as passed in. Tokens that don't answer are left out of the map.
+    async fn get_token_decimals_batch(&self, _addresses: &[&str]) -> Result<HashMap<String, u8>> {
+        Err(AppError::Chain("Token decimals lookup is not supported on this chain".to_string()))
+    }
+
+    /// Create a Lightning invoice (BOLT11); `None` when the chain has no Lightning node configured
+    async fn generate_invoice(&self, _amount_sats: u64, _memo: &str) -> Option<Result<String>> {
+        None
+    }
+
+    /// L1 fee breakdown of a mined transaction; `None` on chains without a separate L1 fee
+    async fn l1_fee_breakdown(&self, _tx_hash: &str) -> Option<Result<L1FeeBreakdown>> {
+        None
+    }
+
+    /// Average seconds between recent blocks (slots on Solana)
+    async fn get_average_block_time_secs(&self) -> Result<f64> {
+        Err(AppError::Chain("Block time is not available on this chain".to_string()))
+    }
+
+    /// Next nonce of `address` counting transactions still in the mempool
+    async fn get_pending_nonce(&self, _address: &str) -> Result<u64> {
+        Err(AppError::Chain("Pending nonces are not available on this chain".to_string()))
+    }
 }
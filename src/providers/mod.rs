@@ -2,10 +2,21 @@ pub mod chain_provider;
 
 pub use chain_provider::{
     Balance,
+    BlockInfo,
     ChainProvider,
     GasEstimate,
+    L1FeeBreakdown,
+    LendingReward,
+    LendingSupply,
+    NetworkLoad,
+    SimulationResult,
+    StateChange,
+    TokenAllowance,
     TokenBalanceEntry,
+    TokenInspection,
+    TransactionDetail,
     TransactionRequest,
     TransactionResponse,
     WalletInfo,
+    gas_limit_with_buffer,
 };
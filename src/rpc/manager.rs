@@ -1,16 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 use std::sync::Arc;
+use std::time::{ Duration, Instant };
 use tokio::sync::RwLock;
+use uuid::Uuid;
 
 use crate::chains::bitcoin::provider::BitcoinProvider;
 use crate::chains::cardano::provider::CardanoProvider;
-use crate::chains::evm::EvmProvider;
+use crate::chains::evm::{EvmProvider, NonceManager};
 use crate::chains::solana::SolanaProvider;
 use crate::chains::xrp::provider::XrpProvider;
-use crate::config::Config;
+use crate::config::{ ChainConfig, Config };
+use crate::crypto::Encryptor;
+use crate::db::entity::wallet;
+use crate::db::WalletRepository;
 use crate::enums::Chain;
 use crate::error::{AppError, Result};
 use crate::providers::ChainProvider;
+use crate::services::TokenListService;
+
+/// How long a measured block time is reused
+const BLOCK_TIME_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
 
 struct ProviderPool {
     providers: Vec<Arc<dyn ChainProvider>>,
@@ -19,14 +28,77 @@ struct ProviderPool {
 
 pub struct RpcManager {
     pools: HashMap<Chain, ProviderPool>,
+    /// Testnet providers for per-user testnet mode (empty when the server itself runs on testnet)
+    testnet_pools: HashMap<Chain, ProviderPool>,
+    is_testnet: bool,
+    nonce_manager: Arc<NonceManager>,
+    /// Average block time per chain and testnet flag, with when it was measured
+    block_times: RwLock<HashMap<(Chain, bool), (f64, Instant)>>,
+    token_list: Option<Arc<TokenListService>>,
+    /// Wallet lookup and key for decrypting per-wallet RPC override URLs
+    wallet_overrides: Option<(Arc<WalletRepository>, Arc<Encryptor>)>,
+    /// Server-network chains whose RPC endpoints all failed the latest connectivity check
+    unavailable: RwLock<HashSet<Chain>>,
 }
 
 impl RpcManager {
-    pub fn new(config: &Config) -> Result<Self> {
+    /// `token_list` lets EVM providers name ERC-20 tokens without contract calls
+    pub fn new(config: &Config, token_list: Option<Arc<TokenListService>>) -> Result<Self> {
         let is_testnet = config.is_testnet();
+
+        // Shared across all EVM providers so round-robin RPC rotation can't hand out a nonce twice
+        let nonce_manager = Arc::new(NonceManager::new());
+
+        // The LND node lives on the server's network, so only those BTC providers get it
+        let lnd = config.lnd_rest_url.as_deref().zip(config.lnd_macaroon.as_deref());
+
+        let pools = Self::build_pools(
+            &config.chain_configs,
+            is_testnet,
+            &nonce_manager,
+            lnd,
+            token_list.as_ref()
+        )?;
+        let testnet_pools = Self::build_pools(
+            &config.testnet_chain_configs,
+            true,
+            &nonce_manager,
+            None,
+            token_list.as_ref()
+        )?;
+
+        Ok(Self {
+            pools,
+            testnet_pools,
+            is_testnet,
+            nonce_manager,
+            block_times: RwLock::new(HashMap::new()),
+            token_list,
+            wallet_overrides: None,
+            unavailable: RwLock::new(HashSet::new()),
+        })
+    }
+
+    /// Honour wallet-level RPC override URLs, decrypting them with `encryptor`
+    pub fn with_wallet_overrides(
+        mut self,
+        wallet_repo: Arc<WalletRepository>,
+        encryptor: Arc<Encryptor>
+    ) -> Self {
+        self.wallet_overrides = Some((wallet_repo, encryptor));
+        self
+    }
+
+    fn build_pools(
+        chain_configs: &HashMap<Chain, ChainConfig>,
+        is_testnet: bool,
+        nonce_manager: &Arc<NonceManager>,
+        lnd: Option<(&str, &str)>,
+        token_list: Option<&Arc<TokenListService>>
+    ) -> Result<HashMap<Chain, ProviderPool>> {
         let mut pools = HashMap::new();
 
-        for (chain, chain_config) in &config.chain_configs {
+        for (chain, chain_config) in chain_configs {
             let mut providers: Vec<Arc<dyn ChainProvider>> = Vec::new();
 
             for url in &chain_config.rpc_urls {
@@ -34,18 +106,28 @@ impl RpcManager {
                     let chain_id = chain.chain_id(is_testnet).ok_or_else(|| {
                         AppError::Config(format!("No chain ID for {}", chain))
                     })?;
-                    match EvmProvider::new(url, chain_id, &chain_config.native_symbol) {
-                        Ok(p) => providers.push(Arc::new(p)),
+                    match EvmProvider::new(url, chain_id, &chain_config.native_symbol, nonce_manager.clone()) {
+                        Ok(p) => {
+                            let p = match token_list {
+                                Some(list) => p.with_token_list(list.clone()),
+                                None => p,
+                            };
+                            providers.push(Arc::new(p));
+                        }
                         Err(e) => tracing::warn!("Failed to create {} provider for {}: {}", chain, url, e),
                     }
                 } else if *chain == Chain::Solana {
                     providers.push(Arc::new(SolanaProvider::new(url)));
                 } else if *chain == Chain::Btc {
-                    providers.push(Arc::new(BitcoinProvider::new(url, is_testnet)));
+                    let mut provider = BitcoinProvider::new(url, is_testnet);
+                    if let Some((rest_url, macaroon)) = lnd {
+                        provider = provider.with_lnd(rest_url, macaroon);
+                    }
+                    providers.push(Arc::new(provider));
                 } else if *chain == Chain::Xrp {
                     providers.push(Arc::new(XrpProvider::new(url)));
                 } else if *chain == Chain::Cardano {
-                    providers.push(Arc::new(CardanoProvider::new(url, is_testnet)));
+                    providers.push(Arc::new(CardanoProvider::new(url, is_testnet, chain_config.api_key.clone())));
                 }
             }
 
@@ -56,7 +138,12 @@ impl RpcManager {
                 )));
             }
 
-            tracing::info!("Initialized {} with {} RPC provider(s)", chain, providers.len());
+            tracing::info!(
+                "Initialized {}{} with {} RPC provider(s)",
+                chain,
+                if is_testnet { " testnet" } else { "" },
+                providers.len()
+            );
 
             pools.insert(*chain, ProviderPool {
                 providers,
@@ -64,14 +151,71 @@ impl RpcManager {
             });
         }
 
-        Ok(Self { pools })
+        Ok(pools)
     }
 
-    /// Get a provider for the given chain (round-robin).
+    /// Get a provider for the given chain on the server's network (round-robin).
     pub async fn get_provider_by_chain(&self, chain: &str) -> Result<Arc<dyn ChainProvider>> {
+        self.get_network_provider(chain, false).await
+    }
+
+    /// Get a provider for the network a wallet was created on, or a dedicated one for the
+    /// wallet's private node when it has an RPC override.
+    pub async fn get_wallet_provider(&self, wallet: &wallet::Model) -> Result<Arc<dyn ChainProvider>> {
+        if let (Some(encrypted_url), Some((_, encryptor))) = (&wallet.rpc_override_url, &self.wallet_overrides) {
+            let url = encryptor.decrypt(encrypted_url)?;
+            return self.build_override_provider(wallet, &url);
+        }
+
+        self.get_network_provider(&wallet.chain, wallet.is_testnet).await
+    }
+
+    /// Look up a wallet and get its provider, honouring any RPC override
+    pub async fn get_provider_for_wallet(&self, wallet_id: Uuid) -> Result<Arc<dyn ChainProvider>> {
+        let (wallet_repo, _) = self.wallet_overrides
+            .as_ref()
+            .ok_or_else(|| AppError::Config("Wallet RPC overrides are not enabled".to_string()))?;
+        let wallet = wallet_repo.find_by_id(wallet_id).await?;
+
+        self.get_wallet_provider(&wallet).await
+    }
+
+    /// A fresh EVM provider on the wallet's own RPC URL, sharing the nonce tracker with the pools
+    fn build_override_provider(&self, wallet: &wallet::Model, url: &str) -> Result<Arc<dyn ChainProvider>> {
+        let chain: Chain = wallet.chain.parse()?;
+        if !chain.is_evm() {
+            return Err(AppError::Config(format!("RPC overrides are not supported on {}", chain)));
+        }
+
+        let testnet = wallet.is_testnet || self.is_testnet;
+        let chain_id = chain
+            .chain_id(testnet)
+            .ok_or_else(|| AppError::Config(format!("No chain ID for {}", chain)))?;
+
+        let provider = EvmProvider::new(url, chain_id, chain.native_symbol(), self.nonce_manager.clone())?;
+        let provider = match &self.token_list {
+            Some(list) => provider.with_token_list(list.clone()),
+            None => provider,
+        };
+
+        Ok(Arc::new(provider))
+    }
+
+    /// Get a provider for the given chain, using testnet RPCs when `testnet` is set (round-robin).
+    pub async fn get_network_provider(&self, chain: &str, testnet: bool) -> Result<Arc<dyn ChainProvider>> {
         let parsed: Chain = chain.parse()?;
-        let pool = self.pools.get(&parsed).ok_or_else(|| {
-            AppError::Config(format!("Chain {} is not configured", chain))
+        let use_testnet_pools = testnet && !self.is_testnet;
+        if !use_testnet_pools && self.unavailable.read().await.contains(&parsed) {
+            return Err(AppError::ChainUnavailable(parsed.to_string()));
+        }
+
+        let pools = if use_testnet_pools { &self.testnet_pools } else { &self.pools };
+        let pool = pools.get(&parsed).ok_or_else(|| {
+            if testnet {
+                AppError::Config(format!("Chain {} testnet is not configured", chain))
+            } else {
+                AppError::Config(format!("Chain {} is not configured", chain))
+            }
         })?;
 
         let mut index = pool.current_index.write().await;
@@ -80,6 +224,23 @@ impl RpcManager {
         Ok(provider)
     }
 
+    /// Average seconds per block on a chain, measured at most every 5 minutes
+    pub async fn get_block_time(&self, chain: &str, testnet: bool) -> Result<f64> {
+        let parsed: Chain = chain.parse()?;
+        let key = (parsed, testnet && !self.is_testnet);
+
+        if let Some((secs, measured_at)) = self.block_times.read().await.get(&key) {
+            if measured_at.elapsed() < BLOCK_TIME_CACHE_TTL {
+                return Ok(*secs);
+            }
+        }
+
+        let provider = self.get_network_provider(chain, testnet).await?;
+        let secs = provider.get_average_block_time_secs().await?;
+        self.block_times.write().await.insert(key, (secs, Instant::now()));
+        Ok(secs)
+    }
+
     /// Rotate to the next provider for a chain (useful after failures).
     pub async fn rotate_provider(&self, chain: &str) -> Result<()> {
         let parsed: Chain = chain.parse()?;
@@ -93,11 +254,39 @@ impl RpcManager {
         Ok(())
     }
 
+    /// Whether the server itself runs on testnet networks.
+    pub fn is_testnet(&self) -> bool {
+        self.is_testnet
+    }
+
     /// Get all chains that have configured providers.
     pub fn get_configured_chains(&self) -> Vec<Chain> {
         self.pools.keys().copied().collect()
     }
 
+    /// Shared nonce tracker used by all EVM providers.
+    pub fn nonce_manager(&self) -> Arc<NonceManager> {
+        self.nonce_manager.clone()
+    }
+
+    /// Fail requests for `chain` with `AppError::ChainUnavailable` until it is marked available again
+    pub async fn mark_unavailable(&self, chain: Chain) {
+        self.unavailable.write().await.insert(chain);
+    }
+
+    pub async fn mark_available(&self, chain: Chain) {
+        self.unavailable.write().await.remove(&chain);
+    }
+
+    /// Chains currently marked unavailable
+    pub async fn unavailable_chains(&self) -> Vec<Chain> {
+        self.unavailable.read().await.iter().copied().collect()
+    }
+
+    pub async fn is_chain_available(&self, chain: &Chain) -> bool {
+        self.is_chain_configured(chain) && !self.unavailable.read().await.contains(chain)
+    }
+
     /// Check if a chain has providers configured.
     pub fn is_chain_configured(&self, chain: &Chain) -> bool {
         self.pools.contains_key(chain)
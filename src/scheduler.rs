@@ -1,23 +1,46 @@
-use crate::db::entity::wallet;
+use crate::db::entity::{ auto_compound_plan, dca_plan, scheduled_transaction, wallet };
+use crate::enums::DcaStatus;
+use crate::services::{ DcaService, GasEstimationService };
 use crate::services::scheduling_service::SchedulingService;
 use crate::services::transfer_service::{ TransferService, TransferRequest };
 use sea_orm::{ DatabaseConnection, EntityTrait };
 use std::sync::Arc;
+use teloxide::prelude::*;
 use tokio::time::{ interval, Duration };
 
+/// Gas-conditional schedules that can't execute within this many days are cancelled
+const GAS_CONDITION_EXPIRY_DAYS: i64 = 7;
+
 pub struct Scheduler {
     db: DatabaseConnection,
     transfer_service: Arc<TransferService>,
+    gas_estimation_service: Arc<GasEstimationService>,
+    dca_service: Option<Arc<DcaService>>,
+    bot: Bot,
 }
 
 impl Scheduler {
-    pub fn new(db: DatabaseConnection, transfer_service: Arc<TransferService>) -> Self {
+    pub fn new(
+        db: DatabaseConnection,
+        transfer_service: Arc<TransferService>,
+        gas_estimation_service: Arc<GasEstimationService>,
+        bot: Bot
+    ) -> Self {
         Self {
             db,
             transfer_service,
+            gas_estimation_service,
+            dca_service: None,
+            bot,
         }
     }
 
+    /// Run due DCA plan buys as swaps instead of transfers
+    pub fn with_dca_service(mut self, dca_service: Arc<DcaService>) -> Self {
+        self.dca_service = Some(dca_service);
+        self
+    }
+
     pub async fn start(self) {
         let mut interval = interval(Duration::from_secs(60)); // Check every minute
 
@@ -63,6 +86,25 @@ impl Scheduler {
                 continue;
             }
 
+            // DCA buys swap inside the wallet rather than sending out of it
+            if let Some(dca_service) = &self.dca_service {
+                if let Some(plan) = dca_service.find_by_schedule(schedule.id).await? {
+                    self.run_dca_buy(dca_service, &plan, &schedule).await;
+                    continue;
+                }
+                if let Some(plan) = dca_service.find_auto_compound_by_schedule(schedule.id).await? {
+                    self.run_auto_compound(dca_service, &plan, &schedule).await;
+                    continue;
+                }
+            }
+
+            // Gas-conditional schedules wait for a cheap enough network
+            if let Some(max_gas) = schedule.max_gas_price_gwei {
+                if !self.gas_condition_met(&scheduling_service, &schedule, &wallet, max_gas).await? {
+                    continue;
+                }
+            }
+
             // Prepare transfer request
             let request = TransferRequest {
                 to: schedule.to_address.clone(),
@@ -72,6 +114,7 @@ impl Scheduler {
                 max_priority_fee_per_gas: None,
                 gas_limit: None,
                 compute_units: None,
+                send_max: false,
             };
 
             // Execute transfer
@@ -109,4 +152,149 @@ impl Scheduler {
 
         Ok(())
     }
+
+    /// Execute one DCA period and tell the user how it went
+    async fn run_dca_buy(
+        &self,
+        dca_service: &DcaService,
+        plan: &dca_plan::Model,
+        schedule: &scheduled_transaction::Model
+    ) {
+        let message = match dca_service.execute_due(plan, schedule).await {
+            Ok(swap) => {
+                tracing::info!("DCA plan {} executed swap {}", plan.id, swap.id);
+                format!(
+                    "🔁 DCA Buy Executed\n\n\
+                    Swapped {} {} → {} {}\n\
+                    TX: {}",
+                    plan.amount_per_period,
+                    plan.from_token,
+                    swap.to_amount.normalize(),
+                    plan.to_token,
+                    swap.tx_hash.as_deref().unwrap_or("pending")
+                )
+            }
+            // Paused or cancelled plan whose schedule slipped through; nothing was attempted
+            Err(e) if plan.status != DcaStatus::Active.as_str() => {
+                tracing::info!("Skipped schedule {} of inactive DCA plan {}: {}", schedule.id, plan.id, e);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("DCA plan {} failed: {}", plan.id, e);
+                format!(
+                    "⚠️ DCA Buy Failed\n\n\
+                    Plan: {}\n\
+                    {} {} → {}\n\n\
+                    {}\n\n\
+                    The plan stays active and will try again next period.",
+                    plan.id,
+                    plan.amount_per_period,
+                    plan.from_token,
+                    plan.to_token,
+                    e.user_facing_message()
+                )
+            }
+        };
+
+        if let Ok(user_id) = plan.user_id.parse::<i64>() {
+            let _ = self.bot.send_message(ChatId(user_id), message).await;
+        }
+    }
+
+    /// Claim and re-supply one period's lending rewards, telling the user when something happened
+    async fn run_auto_compound(
+        &self,
+        dca_service: &DcaService,
+        plan: &auto_compound_plan::Model,
+        schedule: &scheduled_transaction::Model
+    ) {
+        let protocol = plan.protocol
+            .parse::<crate::enums::DefiProtocol>()
+            .map(|p| p.display_name())
+            .unwrap_or("DeFi");
+        let message = match dca_service.execute_auto_compound_due(plan, schedule).await {
+            Ok(Some(tx)) => {
+                tracing::info!("Auto-compound plan {} re-supplied rewards in {}", plan.id, tx.tx_hash);
+                format!("🌾 {} Rewards Compounded\n\nClaimed rewards were supplied back.\nTX: {}", protocol, tx.tx_hash)
+            }
+            // Not worth the gas yet; rewards keep accruing until next period
+            Ok(None) => {
+                return;
+            }
+            Err(e) if plan.status != DcaStatus::Active.as_str() => {
+                tracing::info!("Skipped schedule {} of inactive auto-compound plan {}: {}", schedule.id, plan.id, e);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Auto-compound plan {} failed: {}", plan.id, e);
+                format!(
+                    "⚠️ {} Auto-compound Failed\n\n\
+                    {}\n\n\
+                    The plan stays active and will try again next period.",
+                    protocol,
+                    e.user_facing_message()
+                )
+            }
+        };
+
+        if let Ok(user_id) = plan.user_id.parse::<i64>() {
+            let _ = self.bot.send_message(ChatId(user_id), message).await;
+        }
+    }
+
+    /// Check the gas condition for a due schedule, expiring it if it has waited too long.
+    /// Returns true when the schedule should execute this cycle.
+    async fn gas_condition_met(
+        &self,
+        scheduling_service: &SchedulingService,
+        schedule: &scheduled_transaction::Model,
+        wallet: &wallet::Model,
+        max_gas: f64
+    ) -> crate::error::Result<bool> {
+        let current_gas = match self.gas_estimation_service.get_wallet_gas_price_gwei(wallet).await {
+            Ok(gas) => gas,
+            Err(e) => {
+                tracing::warn!("Could not fetch gas price for schedule {}: {}", schedule.id, e);
+                return Ok(false);
+            }
+        };
+
+        if current_gas <= max_gas {
+            return Ok(true);
+        }
+
+        let waited = chrono::Utc::now() - schedule.scheduled_for;
+        if waited > chrono::Duration::days(GAS_CONDITION_EXPIRY_DAYS) {
+            let reason = format!(
+                "Gas price stayed above {} Gwei for {} days",
+                max_gas,
+                GAS_CONDITION_EXPIRY_DAYS
+            );
+            scheduling_service.expire_schedule(schedule.id, reason).await?;
+
+            if let Ok(user_id) = schedule.user_id.parse::<i64>() {
+                let message = format!(
+                    "⌛ Scheduled Transaction Cancelled\n\n\
+                    Schedule: {}\n\
+                    Amount: {} → {}\n\n\
+                    Gas never dropped to your limit of {} Gwei within {} days of the scheduled time.",
+                    schedule.id,
+                    schedule.amount,
+                    schedule.to_address,
+                    max_gas,
+                    GAS_CONDITION_EXPIRY_DAYS
+                );
+                let _ = self.bot.send_message(ChatId(user_id), message).await;
+            }
+        } else {
+            tracing::info!(
+                "Skipping schedule {}: gas {:.2} Gwei above limit {} Gwei",
+                schedule.id,
+                current_gas,
+                max_gas
+            );
+        }
+
+        Ok(false)
+    }
 }
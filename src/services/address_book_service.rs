@@ -1,18 +1,112 @@
+use std::str::FromStr;
 use std::sync::Arc;
+use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 use sea_orm::*;
+use sea_orm::sea_query::{ Expr, Func };
 
+use crate::chains::evm::EnsResolver;
 use crate::db::entity::address_book;
 use crate::db::entity::address_book::Entity as AddressBook;
+use crate::enums::Chain;
 use crate::error::{ AppError, Result };
 
+/// How long a resolved ENS name is trusted before it is resolved again
+const ENS_CACHE_TTL_HOURS: i64 = 24;
+
+/// Outcome of a bulk address book import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+/// One contact in an import file
+#[derive(Debug, Clone, Deserialize)]
+struct ImportEntry {
+    name: String,
+    address: String,
+    chain: String,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
 pub struct AddressBookService {
     db: Arc<DatabaseConnection>,
+    ens_resolver: Option<Arc<EnsResolver>>,
 }
 
 impl AddressBookService {
     pub fn new(db: Arc<DatabaseConnection>) -> Self {
-        Self { db }
+        Self { db, ens_resolver: None }
+    }
+
+    /// Enable ENS name resolution (Ethereum only)
+    pub fn with_ens_resolver(mut self, resolver: EnsResolver) -> Self {
+        self.ens_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Resolve an ENS name to an address, caching the result in the user's address book
+    pub async fn resolve_ens(&self, user_id: &str, name: &str) -> Result<String> {
+        let name = name.trim().to_lowercase();
+
+        let cached = AddressBook::find()
+            .filter(address_book::Column::UserId.eq(user_id))
+            .filter(address_book::Column::Name.eq(&name))
+            .one(self.db.as_ref()).await?;
+
+        let now = chrono::Utc::now();
+        if let Some(entry) = &cached {
+            match entry.ens_expires_at {
+                // A contact the user saved under this name takes precedence
+                None => {
+                    return Ok(entry.address.clone());
+                }
+                Some(expires_at) if expires_at > now => {
+                    return Ok(entry.address.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        let resolver = self.ens_resolver
+            .as_ref()
+            .ok_or_else(|| AppError::EnsNotFound(name.clone()))?;
+        let address = resolver.resolve(&name).await?;
+        let expires_at = now + chrono::Duration::hours(ENS_CACHE_TTL_HOURS);
+
+        match cached {
+            Some(entry) => {
+                let mut active_model: address_book::ActiveModel = entry.into();
+                active_model.address = Set(address.clone());
+                active_model.ens_expires_at = Set(Some(expires_at));
+                active_model.updated_at = Set(now);
+                active_model.update(self.db.as_ref()).await?;
+            }
+            None => {
+                let entry = address_book::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    user_id: Set(user_id.to_string()),
+                    name: Set(name),
+                    address: Set(address.clone()),
+                    chain: Set(Chain::Eth.to_string()),
+                    notes: Set(Some("Resolved via ENS".to_string())),
+                    ens_expires_at: Set(Some(expires_at)),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                };
+                AddressBook::insert(entry).exec(self.db.as_ref()).await?;
+            }
+        }
+
+        Ok(address)
+    }
+
+    /// Look up the primary ENS name for an address, if ENS is enabled and one is set
+    pub async fn reverse_resolve_ens(&self, address: &str) -> Option<String> {
+        self.ens_resolver.as_ref()?.lookup_address(address).await
     }
 
     /// Save a new address to the address book
@@ -42,6 +136,7 @@ impl AddressBookService {
             address: Set(address),
             chain: Set(chain),
             notes: Set(notes),
+            ens_expires_at: Set(None),
             created_at: Set(now.into()),
             updated_at: Set(now.into()),
         };
@@ -106,6 +201,14 @@ impl AddressBookService {
             .ok_or_else(|| AppError::NotFound(format!("Address '{}' not found", name)))
     }
 
+    /// Get an address by its ID, scoped to the owning user
+    pub async fn get_address_by_id(&self, user_id: &str, id: Uuid) -> Result<address_book::Model> {
+        AddressBook::find_by_id(id)
+            .filter(address_book::Column::UserId.eq(user_id))
+            .one(self.db.as_ref()).await?
+            .ok_or_else(|| AppError::NotFound("Address not found".to_string()))
+    }
+
     /// List all addresses for a user
     pub async fn list_addresses(
         &self,
@@ -137,4 +240,211 @@ impl AddressBookService {
 
         Ok(addresses)
     }
+
+    /// Case-insensitive substring match on contact name or address, optionally on one chain
+    pub async fn search(
+        &self,
+        user_id: &str,
+        query: &str,
+        chain: Option<&str>
+    ) -> Result<Vec<address_book::Model>> {
+        // Drop LIKE wildcards so a query can only ever match as a plain substring
+        let term: String = query
+            .trim()
+            .to_lowercase()
+            .chars()
+            .filter(|c| !matches!(c, '%' | '_' | '\\'))
+            .collect();
+        if term.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pattern = format!("%{}%", term);
+        let name = Expr::expr(Func::lower(Expr::col(address_book::Column::Name)));
+        let address = Expr::expr(Func::lower(Expr::col(address_book::Column::Address)));
+
+        let mut select = AddressBook::find()
+            .filter(address_book::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(name.like(pattern.as_str()))
+                    .add(address.like(pattern.as_str()))
+            );
+
+        if let Some(chain) = chain {
+            select = select.filter(address_book::Column::Chain.eq(chain));
+        }
+
+        Ok(select.order_by_asc(address_book::Column::Name).all(self.db.as_ref()).await?)
+    }
+
+    /// Import contacts from `name,address,chain[,notes]` CSV; an optional header row is ignored.
+    /// Contacts whose name already exists are updated in place.
+    pub async fn import_from_csv(&self, user_id: &str, data: &[u8]) -> Result<ImportResult> {
+        let text = std::str
+            ::from_utf8(data)
+            .map_err(|_| AppError::InvalidInput("CSV file must be UTF-8 text".to_string()))?;
+
+        let mut entries = Vec::new();
+        let mut result = ImportResult::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields = parse_csv_line(line);
+            if index == 0 && fields.first().is_some_and(|f| f.eq_ignore_ascii_case("name")) {
+                continue;
+            }
+            if fields.len() < 3 {
+                result.skipped += 1;
+                result.errors.push(
+                    format!("Line {}: expected name,address,chain[,notes]", index + 1)
+                );
+                continue;
+            }
+
+            let mut fields = fields.into_iter();
+            entries.push((
+                format!("Line {}", index + 1),
+                ImportEntry {
+                    name: fields.next().unwrap_or_default(),
+                    address: fields.next().unwrap_or_default(),
+                    chain: fields.next().unwrap_or_default(),
+                    notes: fields.next().filter(|n| !n.is_empty()),
+                },
+            ));
+        }
+
+        self.import_entries(user_id, entries, result).await
+    }
+
+    /// Import contacts from a JSON array of `{"name","address","chain"[,"notes"]}` objects
+    pub async fn import_from_json(&self, user_id: &str, data: &[u8]) -> Result<ImportResult> {
+        let entries: Vec<ImportEntry> = serde_json
+            ::from_slice(data)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid JSON address list: {}", e)))?;
+
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| (format!("Entry {}", index + 1), entry))
+            .collect();
+
+        self.import_entries(user_id, entries, ImportResult::default()).await
+    }
+
+    async fn import_entries(
+        &self,
+        user_id: &str,
+        entries: Vec<(String, ImportEntry)>,
+        mut result: ImportResult
+    ) -> Result<ImportResult> {
+        for (label, entry) in entries {
+            let name = entry.name.trim().to_string();
+            let address = entry.address.trim().to_string();
+            let notes = entry.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+            if name.is_empty() {
+                result.skipped += 1;
+                result.errors.push(format!("{}: missing name", label));
+                continue;
+            }
+            let chain = match entry.chain.trim().parse::<Chain>() {
+                Ok(chain) => chain,
+                Err(_) => {
+                    result.skipped += 1;
+                    result.errors.push(format!("{}: unknown chain '{}'", label, entry.chain.trim()));
+                    continue;
+                }
+            };
+            if !is_valid_address(chain, &address) {
+                result.skipped += 1;
+                result.errors.push(format!("{}: invalid {} address for '{}'", label, chain, name));
+                continue;
+            }
+
+            let saved = match self.get_address(user_id, &name).await {
+                Ok(_) =>
+                    self.update_address(
+                        user_id,
+                        &name,
+                        Some(address),
+                        Some(chain.to_string()),
+                        notes
+                    ).await,
+                Err(AppError::NotFound(_)) =>
+                    self.save_address(
+                        user_id.to_string(),
+                        name.clone(),
+                        address,
+                        chain.to_string(),
+                        notes
+                    ).await,
+                Err(e) => Err(e),
+            };
+
+            match saved {
+                Ok(_) => {
+                    result.imported += 1;
+                }
+                Err(e) => {
+                    result.skipped += 1;
+                    result.errors.push(format!("{}: {}", label, e));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Format check for a contact's address; either network (mainnet or testnet) is accepted
+pub(crate) fn is_valid_address(chain: Chain, address: &str) -> bool {
+    match chain {
+        Chain::Btc => bitcoin::Address::from_str(address).is_ok(),
+        Chain::Solana => crate::chains::solana::wallet::validate_address(address),
+        Chain::Xrp =>
+            address.starts_with('r') &&
+                (25..=35).contains(&address.len()) &&
+                bs58::decode(address).with_alphabet(bs58::Alphabet::RIPPLE).into_vec().is_ok(),
+        Chain::Cardano => crate::chains::cardano::wallet::decode_address(address).is_ok(),
+        _ => crate::chains::evm::wallet::validate_address(address),
+    }
+}
+
+/// Split one CSV record, honouring double-quoted fields and `""` escapes
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => {
+                in_quotes = !in_quotes;
+            }
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_csv_fields() {
+        assert_eq!(
+            parse_csv_line(r#"alice, 0xabc ,ETH,"Rent, ""monthly"""#),
+            vec!["alice", "0xabc", "ETH", r#"Rent, "monthly""#]
+        );
+    }
 }
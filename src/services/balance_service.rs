@@ -1,16 +1,31 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use ethers::providers::{Middleware, Provider, Ws};
 use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
 use uuid::Uuid;
 
 use crate::crypto::Encryptor;
 use crate::db::WalletRepository;
 use crate::enums::Chain;
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::providers::{Balance, TokenBalanceEntry};
 use crate::rpc::RpcManager;
+use crate::services::nft_service::{NftItem, NftService};
+use crate::services::price_service::PriceService;
 use crate::services::token_discovery_service::TokenDiscoveryService;
 
+/// How often a watched balance is re-read when the chain has no block subscription
+const BALANCE_POLL_INTERVAL_SECS: u64 = 15;
+
+/// Balance updates buffered per watcher before the polling task waits for the consumer
+const WATCH_CHANNEL_CAPACITY: usize = 16;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct WalletBalances {
     pub wallet_id: String,
@@ -26,6 +41,10 @@ pub struct BalanceService {
     encryptor: Arc<Encryptor>,
     token_discovery: Option<Arc<TokenDiscoveryService>>,
     is_testnet: bool,
+    price_service: Option<Arc<PriceService>>,
+    nft_service: Option<Arc<NftService>>,
+    /// WebSocket endpoints by chain and testnet flag
+    ws_urls: HashMap<(Chain, bool), String>,
 }
 
 impl BalanceService {
@@ -42,7 +61,48 @@ impl BalanceService {
             encryptor,
             token_discovery,
             is_testnet,
+            price_service: None,
+            nft_service: None,
+            ws_urls: HashMap::new(),
+        }
+    }
+
+    /// Re-check watched EVM balances on every new block through these WebSocket endpoints
+    /// instead of polling
+    pub fn with_block_subscriptions(mut self, ws_urls: HashMap<(Chain, bool), String>) -> Self {
+        self.ws_urls = ws_urls;
+        self
+    }
+
+    /// Fill in the USD value of token balances from prices looked up by contract address
+    pub fn with_price_service(mut self, price_service: Arc<PriceService>) -> Self {
+        self.price_service = Some(price_service);
+        self
+    }
+
+    /// List wallet NFTs through Alchemy's NFT API
+    pub fn with_nft_service(mut self, nft_service: Arc<NftService>) -> Self {
+        self.nft_service = Some(nft_service);
+        self
+    }
+
+    /// Whether NFT lookups are possible, i.e. an Alchemy API key is configured
+    pub fn nfts_enabled(&self) -> bool {
+        self.nft_service.as_ref().is_some_and(|s| s.is_configured())
+    }
+
+    pub async fn get_wallet_nfts(&self, wallet_id: Uuid) -> Result<Vec<NftItem>> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+
+        let nft_service = self.nft_service
+            .as_ref()
+            .filter(|s| s.is_configured())
+            .ok_or_else(|| AppError::Config("ALCHEMY_API_KEY not set".to_string()))?;
+        if wallet.is_testnet {
+            return Err(AppError::InvalidInput("NFTs are only listed for mainnet wallets".to_string()));
         }
+
+        nft_service.get_nfts_for_wallet(&wallet.chain, &wallet.address).await
     }
 
     pub async fn get_balance(
@@ -51,19 +111,74 @@ impl BalanceService {
         token_address: Option<String>,
     ) -> Result<Balance> {
         let wallet = self.repository.find_by_id(wallet_id).await?;
-        let provider = self.rpc_manager.get_provider_by_chain(&wallet.chain).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
 
         if let Some(token_addr) = token_address {
-            provider.get_token_balance(&wallet.address, &token_addr).await
+            let mut balance = provider.get_token_balance(&wallet.address, &token_addr).await?;
+            if !wallet.is_testnet {
+                balance.usd_value = self.token_usd_value(&wallet.chain, &token_addr, &balance).await;
+            }
+            Ok(balance)
         } else {
             provider.get_balance(&wallet.address).await
         }
     }
 
+    /// Balance as of `block_number`, for tax and audit records. Not priced in USD, since today's
+    /// price says nothing about the value at that block.
+    pub async fn get_historical_balance(
+        &self,
+        wallet_id: Uuid,
+        token_address: Option<String>,
+        block_number: u64,
+    ) -> Result<Balance> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        provider
+            .get_historical_balance(&wallet.address, token_address.as_deref(), block_number)
+            .await
+    }
+
+    /// Approximate block number on the wallet's chain at `at`, counted back from the latest
+    /// block using the chain's recent average block time
+    pub async fn estimate_block_at(&self, wallet_id: Uuid, at: DateTime<Utc>) -> Result<u64> {
+        let elapsed_secs = (Utc::now() - at).num_seconds();
+        if elapsed_secs < 0 {
+            return Err(AppError::InvalidInput("Date is in the future".to_string()));
+        }
+
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        let latest = provider.get_block_number().await?;
+        let block_time_secs = provider.get_average_block_time_secs().await?;
+        if block_time_secs <= 0.0 {
+            return Err(AppError::Chain("Block time is not available on this chain".to_string()));
+        }
+
+        let blocks_back = ((elapsed_secs as f64) / block_time_secs) as u64;
+        Ok(latest.saturating_sub(blocks_back))
+    }
+
+    /// Best-effort USD value; a missing price leaves the balance without one
+    async fn token_usd_value(&self, chain: &str, token_address: &str, balance: &Balance) -> Option<f64> {
+        let price_service = self.price_service.as_ref()?;
+        let amount: f64 = balance.balance.parse().ok()?;
+
+        match price_service.get_token_price_by_address(chain, token_address).await {
+            Ok(price) => Some(amount * price.usd_price),
+            Err(e) => {
+                tracing::debug!("No USD price for token {} on {}: {}", token_address, chain, e);
+                None
+            }
+        }
+    }
+
     /// Get native balance + all discovered token balances for a wallet.
     pub async fn get_all_balances(&self, wallet_id: Uuid) -> Result<WalletBalances> {
         let wallet = self.repository.find_by_id(wallet_id).await?;
-        let provider = self.rpc_manager.get_provider_by_chain(&wallet.chain).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
 
         let native = provider.get_balance(&wallet.address).await?;
 
@@ -71,7 +186,13 @@ impl BalanceService {
             if let Ok(chain) = wallet.chain.parse::<Chain>() {
                 if discovery.is_supported(&chain) {
                     discovery
-                        .get_all_token_balances(chain, &wallet.address, self.is_testnet)
+                        .discover_and_cache_new_tokens(
+                            wallet.id,
+                            chain,
+                            &wallet.address,
+                            self.is_testnet,
+                            provider.as_ref()
+                        )
                         .await
                         .unwrap_or_default()
                 } else {
@@ -92,4 +213,97 @@ impl BalanceService {
             tokens,
         })
     }
+
+    /// Stream a wallet's balance: the current value first, then each change. A background task
+    /// re-reads it on every new block when the chain has a WebSocket endpoint, otherwise every
+    /// few seconds, and stops once the stream is dropped. Read errors are yielded and the watch
+    /// carries on.
+    pub async fn watch_balance(
+        self: &Arc<Self>,
+        wallet_id: Uuid,
+        token_address: Option<String>,
+    ) -> Result<impl Stream<Item = Result<Balance>>> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let ws_url = wallet.chain
+            .parse::<Chain>()
+            .ok()
+            .filter(|chain| chain.is_evm())
+            .and_then(|chain| self.ws_urls.get(&(chain, wallet.is_testnet)))
+            .cloned();
+
+        let (sender, receiver) = mpsc::channel(WATCH_CHANNEL_CAPACITY);
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            service.run_balance_watch(wallet_id, token_address, ws_url, sender).await;
+        });
+
+        Ok(ReceiverStream::new(receiver))
+    }
+
+    async fn run_balance_watch(
+        &self,
+        wallet_id: Uuid,
+        token_address: Option<String>,
+        ws_url: Option<String>,
+        sender: mpsc::Sender<Result<Balance>>,
+    ) {
+        let mut last = None;
+        if !self.send_balance_if_changed(wallet_id, &token_address, &mut last, &sender).await {
+            return;
+        }
+
+        if let Some(url) = ws_url {
+            match Provider::<Ws>::connect(url.as_str()).await {
+                Ok(provider) => match provider.subscribe_blocks().await {
+                    Ok(mut blocks) => loop {
+                        tokio::select! {
+                            _ = sender.closed() => return,
+                            block = blocks.next() => {
+                                if block.is_none() {
+                                    tracing::warn!("Block subscription for wallet {} ended; polling instead", wallet_id);
+                                    break;
+                                }
+                            }
+                        }
+                        if !self.send_balance_if_changed(wallet_id, &token_address, &mut last, &sender).await {
+                            return;
+                        }
+                    },
+                    Err(e) => tracing::warn!("eth_subscribe failed for wallet {}; polling instead: {}", wallet_id, e),
+                },
+                Err(e) => tracing::warn!("WebSocket connect failed for wallet {}; polling instead: {}", wallet_id, e),
+            }
+        }
+
+        let mut ticker = tokio::time::interval(Duration::from_secs(BALANCE_POLL_INTERVAL_SECS));
+        // The first tick fires immediately and the current balance was already sent
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = sender.closed() => return,
+                _ = ticker.tick() => {}
+            }
+            if !self.send_balance_if_changed(wallet_id, &token_address, &mut last, &sender).await {
+                return;
+            }
+        }
+    }
+
+    /// Read the balance and pass it on when it differs from `last`. `false` once the receiver is gone.
+    async fn send_balance_if_changed(
+        &self,
+        wallet_id: Uuid,
+        token_address: &Option<String>,
+        last: &mut Option<String>,
+        sender: &mpsc::Sender<Result<Balance>>,
+    ) -> bool {
+        match self.get_balance(wallet_id, token_address.clone()).await {
+            Ok(balance) if last.as_deref() == Some(balance.balance.as_str()) => true,
+            Ok(balance) => {
+                *last = Some(balance.balance.clone());
+                sender.send(Ok(balance)).await.is_ok()
+            }
+            Err(e) => sender.send(Err(e)).await.is_ok(),
+        }
+    }
 }
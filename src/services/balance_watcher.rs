@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use teloxide::prelude::*;
+use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::providers::Balance;
+use crate::services::BalanceService;
+
+/// Follows wallets users asked to watch with /watchbalance and messages them when the native
+/// balance moves by at least the configured percentage. Watches live in memory and end on restart.
+pub struct BalanceWatcher {
+    balance_service: Arc<BalanceService>,
+    bot: Bot,
+    threshold_pct: f64,
+    watches: DashMap<(ChatId, Uuid), JoinHandle<()>>,
+}
+
+impl BalanceWatcher {
+    pub fn new(balance_service: Arc<BalanceService>, bot: Bot, threshold_pct: f64) -> Self {
+        Self {
+            balance_service,
+            bot,
+            threshold_pct,
+            watches: DashMap::new(),
+        }
+    }
+
+    pub fn threshold_pct(&self) -> f64 {
+        self.threshold_pct
+    }
+
+    /// Start watching `wallet_id` for `chat_id`; `false` if it was already watched
+    pub async fn watch(&self, chat_id: ChatId, wallet_id: Uuid, wallet_label: String) -> Result<bool> {
+        let key = (chat_id, wallet_id);
+        if self.watches.get(&key).is_some_and(|handle| !handle.is_finished()) {
+            return Ok(false);
+        }
+
+        let mut balances = Box::pin(self.balance_service.watch_balance(wallet_id, None).await?);
+        let bot = self.bot.clone();
+        let threshold_pct = self.threshold_pct;
+
+        let handle = tokio::spawn(async move {
+            // Changes are measured from the last balance the user was told about
+            let mut baseline: Option<Balance> = None;
+            while let Some(update) = balances.next().await {
+                let balance = match update {
+                    Ok(balance) => balance,
+                    Err(e) => {
+                        tracing::debug!("Balance watch for wallet {} failed a read: {}", wallet_id, e);
+                        continue;
+                    }
+                };
+                let Some(previous) = &baseline else {
+                    baseline = Some(balance);
+                    continue;
+                };
+
+                let Some(change_pct) = change_pct(&previous.balance, &balance.balance) else {
+                    continue;
+                };
+                if change_pct.abs() < threshold_pct {
+                    continue;
+                }
+
+                let message = format!(
+                    "💰 Balance {} on {}: {} → {} {} ({:+.1}%)",
+                    if change_pct > 0.0 { "up" } else { "down" },
+                    wallet_label,
+                    previous.balance,
+                    balance.balance,
+                    balance.symbol,
+                    change_pct
+                );
+                if let Err(e) = bot.send_message(chat_id, message).await {
+                    tracing::warn!("Failed to send balance change for wallet {}: {}", wallet_id, e);
+                }
+                baseline = Some(balance);
+            }
+        });
+
+        if let Some(previous) = self.watches.insert(key, handle) {
+            previous.abort();
+        }
+        Ok(true)
+    }
+
+    /// Stop watching; `false` if the wallet wasn't watched
+    pub fn unwatch(&self, chat_id: ChatId, wallet_id: Uuid) -> bool {
+        match self.watches.remove(&(chat_id, wallet_id)) {
+            Some((_, handle)) => {
+                let was_running = !handle.is_finished();
+                handle.abort();
+                was_running
+            }
+            None => false,
+        }
+    }
+}
+
+/// Percentage change from `previous` to `current`. Any move off zero counts as 100%.
+fn change_pct(previous: &str, current: &str) -> Option<f64> {
+    let previous: f64 = previous.parse().ok()?;
+    let current: f64 = current.parse().ok()?;
+    if previous == 0.0 {
+        return Some(if current == 0.0 { 0.0 } else { 100.0 });
+    }
+    Some(((current - previous) / previous) * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_pct() {
+        assert_eq!(change_pct("2", "2.5"), Some(25.0));
+        assert_eq!(change_pct("2", "1"), Some(-50.0));
+        assert_eq!(change_pct("0", "0.1"), Some(100.0));
+        assert_eq!(change_pct("0", "0"), Some(0.0));
+        assert_eq!(change_pct("n/a", "1"), None);
+    }
+}
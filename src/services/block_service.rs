@@ -0,0 +1,86 @@
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+
+use dashmap::DashMap;
+
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::providers::BlockInfo;
+use crate::rpc::RpcManager;
+
+/// Roughly one Ethereum slot; "latest" lookups within it reuse the last answer
+const LATEST_BLOCK_TTL: Duration = Duration::from_secs(12);
+
+/// Block lookups on the server's network, with a short cache for the latest block per chain
+pub struct BlockService {
+    rpc_manager: Arc<RpcManager>,
+    latest: DashMap<Chain, (BlockInfo, Instant)>,
+}
+
+impl BlockService {
+    pub fn new(rpc_manager: Arc<RpcManager>) -> Self {
+        Self {
+            rpc_manager,
+            latest: DashMap::new(),
+        }
+    }
+
+    /// Block `number` on `chain` (any chain alias), or the latest block when `number` is `None`
+    pub async fn get_block(&self, chain: &str, number: Option<u64>) -> Result<BlockInfo> {
+        let chain: Chain = chain.parse()?;
+        let Some(number) = number else {
+            return self.get_latest_block(chain).await;
+        };
+        let provider = self.rpc_manager.get_provider_by_chain(chain.as_str()).await?;
+        provider.get_block_by_number(number).await
+    }
+
+    pub async fn get_latest_block(&self, chain: Chain) -> Result<BlockInfo> {
+        if let Some(entry) = self.latest.get(&chain) {
+            let (block, fetched_at) = entry.value();
+            if fetched_at.elapsed() < LATEST_BLOCK_TTL {
+                return Ok(block.clone());
+            }
+        }
+
+        let provider = self.rpc_manager.get_provider_by_chain(chain.as_str()).await?;
+        let number = provider.get_block_number().await?;
+        let block = provider.get_block_by_number(number).await?;
+        self.latest.insert(chain, (block.clone(), Instant::now()));
+        Ok(block)
+    }
+
+    /// Latest block of each chain, in the order given
+    pub async fn network_status(&self, chains: &[Chain]) -> Vec<(Chain, Result<BlockInfo>)> {
+        let mut status = Vec::with_capacity(chains.len());
+        for &chain in chains {
+            status.push((chain, self.get_latest_block(chain).await));
+        }
+        status
+    }
+}
+
+/// A block height, with optional thousands separators, or "latest" (`None`)
+pub fn parse_block_number(value: &str) -> Result<Option<u64>> {
+    if value.eq_ignore_ascii_case("latest") {
+        return Ok(None);
+    }
+    value
+        .replace(',', "")
+        .parse()
+        .map(Some)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid block number: {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_block_number() {
+        assert_eq!(parse_block_number("latest").unwrap(), None);
+        assert_eq!(parse_block_number("LATEST").unwrap(), None);
+        assert_eq!(parse_block_number("19,123,456").unwrap(), Some(19_123_456));
+        assert!(parse_block_number("-1").is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::{ Duration, Instant };
+
+use chrono::Utc;
+use sea_orm::{ ActiveModelTrait, ActiveValue, DatabaseConnection, EntityTrait, QuerySelect };
+use serde::Serialize;
+use teloxide::prelude::*;
+use teloxide::RequestError;
+use tokio::sync::{ mpsc, watch, Mutex };
+use uuid::Uuid;
+
+use crate::db::entity::{ broadcast_message, wallet };
+use crate::error::{ AppError, Result };
+
+/// Telegram accepts about 30 messages per second from one bot across all chats
+const MESSAGES_PER_SECOND: u64 = 30;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BroadcastResult {
+    pub sent: usize,
+    pub failed: usize,
+    pub duration_secs: f64,
+}
+
+/// Delivery progress of the running broadcast
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BroadcastProgress {
+    pub total: usize,
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// Sends admin announcements to every user who holds a wallet, one broadcast at a time
+pub struct BroadcastService {
+    db: DatabaseConnection,
+    running: Mutex<()>,
+    progress: watch::Sender<BroadcastProgress>,
+}
+
+impl BroadcastService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            running: Mutex::new(()),
+            progress: watch::Sender::new(BroadcastProgress::default()),
+        }
+    }
+
+    /// Progress of the current (or last) broadcast, updated after every delivery attempt
+    pub fn subscribe_progress(&self) -> watch::Receiver<BroadcastProgress> {
+        self.progress.subscribe()
+    }
+
+    /// Deliver `message` to all users at Telegram's rate limit and record the broadcast.
+    /// Users who blocked the bot count as failed.
+    pub async fn send_to_all(&self, bot: &Bot, message: &str, admin_id: i64) -> Result<BroadcastResult> {
+        let _running = self.running
+            .try_lock()
+            .map_err(|_| AppError::InvalidInput("A broadcast is already running".to_string()))?;
+
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let recipients = self.recipients().await?;
+        let total = recipients.len();
+        self.progress.send_replace(BroadcastProgress { total, sent: 0, failed: 0 });
+        tracing::info!("Broadcast by admin {} started for {} users", admin_id, total);
+
+        // Queue every recipient up front; the worker drains it at the rate limit
+        let (queue, mut pending) = mpsc::channel(total.max(1));
+        for chat_id in recipients {
+            queue.send(chat_id).await.map_err(|_| AppError::Internal("Broadcast queue closed".to_string()))?;
+        }
+        drop(queue);
+
+        let worker_bot = bot.clone();
+        let text = message.to_string();
+        let progress = self.progress.clone();
+        let worker = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(1000 / MESSAGES_PER_SECOND));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            while let Some(chat_id) = pending.recv().await {
+                ticker.tick().await;
+                let delivered = deliver(&worker_bot, chat_id, &text).await;
+                progress.send_modify(|p| {
+                    if delivered {
+                        p.sent += 1;
+                    } else {
+                        p.failed += 1;
+                    }
+                });
+            }
+        });
+        worker.await.map_err(|e| AppError::Internal(format!("Broadcast worker failed: {}", e)))?;
+
+        let BroadcastProgress { sent, failed, .. } = *self.progress.borrow();
+        let record = broadcast_message::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            message_text: ActiveValue::Set(message.to_string()),
+            sender_admin_id: ActiveValue::Set(admin_id),
+            sent_at: ActiveValue::Set(started_at),
+            recipient_count: ActiveValue::Set(sent as i32),
+        };
+        record.insert(&self.db).await?;
+
+        let result = BroadcastResult {
+            sent,
+            failed,
+            duration_secs: started.elapsed().as_secs_f64(),
+        };
+        tracing::info!(
+            "Broadcast finished: {} sent, {} failed in {:.1}s",
+            result.sent,
+            result.failed,
+            result.duration_secs
+        );
+        Ok(result)
+    }
+
+    /// Every user with at least one wallet; their user id is their private chat id
+    async fn recipients(&self) -> Result<Vec<ChatId>> {
+        let user_ids: Vec<String> = wallet::Entity
+            ::find()
+            .select_only()
+            .column(wallet::Column::UserId)
+            .distinct()
+            .into_tuple()
+            .all(&self.db).await?;
+
+        Ok(
+            user_ids
+                .iter()
+                .filter_map(|id| id.parse::<i64>().ok())
+                .map(ChatId)
+                .collect()
+        )
+    }
+}
+
+/// Send one message, waiting out a single flood-control response before giving up
+async fn deliver(bot: &Bot, chat_id: ChatId, text: &str) -> bool {
+    match bot.send_message(chat_id, text).await {
+        Ok(_) => true,
+        Err(RequestError::RetryAfter(wait)) => {
+            tokio::time::sleep(wait.duration()).await;
+            bot.send_message(chat_id, text).await.is_ok()
+        }
+        Err(e) => {
+            tracing::debug!("Broadcast to {} failed: {}", chat_id, e);
+            false
+        }
+    }
+}
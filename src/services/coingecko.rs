@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::time::{ Duration, Instant, SystemTime };
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::services::price_service::TokenPrice;
+
+const COINGECKO_API_BASE: &str = "https://api.coingecko.com/api/v3";
+const CACHE_DURATION_SECS: u64 = 60;
+
+#[derive(Deserialize)]
+struct CoinGeckoTokenPrice {
+    usd: Option<f64>,
+    usd_24h_change: Option<f64>,
+}
+
+/// Contract-address price lookups through CoinGecko's `simple/token_price` endpoint
+pub struct CoinGeckoProvider {
+    client: reqwest::Client,
+    /// Keyed by chain and lower-cased contract address
+    cache: RwLock<HashMap<(Chain, String), (Instant, TokenPrice)>>,
+}
+
+impl CoinGeckoProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap(),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// USD price and 24h change of the token at `address` on `chain` (a `Chain::as_str()` value)
+    pub async fn get_price_by_address(&self, chain: &str, address: &str) -> Result<TokenPrice> {
+        let parsed: Chain = chain.parse()?;
+        let platform = platform_id(parsed).ok_or_else(|| {
+            AppError::InvalidInput(format!("Token prices by address are not available on {}", chain))
+        })?;
+
+        let key = (parsed, address.to_lowercase());
+        if let Some((fetched_at, price)) = self.cache.read().await.get(&key) {
+            if fetched_at.elapsed() < Duration::from_secs(CACHE_DURATION_SECS) {
+                return Ok(price.clone());
+            }
+        }
+
+        let url = format!("{}/simple/token_price/{}", COINGECKO_API_BASE, platform);
+        let data: HashMap<String, CoinGeckoTokenPrice> = self.client
+            .get(&url)
+            .query(
+                &[
+                    ("contract_addresses", address),
+                    ("vs_currencies", "usd"),
+                    ("include_24hr_change", "true"),
+                ]
+            )
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("CoinGecko API error: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse CoinGecko response: {}", e)))?;
+
+        // CoinGecko lower-cases EVM addresses in its response keys
+        let entry = data
+            .into_iter()
+            .find(|(addr, _)| addr.eq_ignore_ascii_case(address))
+            .map(|(_, entry)| entry)
+            .ok_or_else(|| AppError::NotFound(format!("No CoinGecko price for token {}", address)))?;
+        let usd_price = entry.usd.ok_or_else(|| {
+            AppError::NotFound(format!("No CoinGecko price for token {}", address))
+        })?;
+
+        let price = TokenPrice {
+            symbol: address.to_string(),
+            usd_price,
+            price_change_24h: entry.usd_24h_change,
+            market_cap: None,
+            volume_24h: None,
+            last_updated: SystemTime::now(),
+        };
+
+        self.cache.write().await.insert(key, (Instant::now(), price.clone()));
+        Ok(price)
+    }
+}
+
+impl Default for CoinGeckoProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CoinGecko asset platform id for token contracts on a chain
+fn platform_id(chain: Chain) -> Option<&'static str> {
+    let id = match chain {
+        Chain::Eth => "ethereum",
+        Chain::Bsc => "binance-smart-chain",
+        Chain::Solana => "solana",
+        Chain::Polygon => "polygon-pos",
+        Chain::Avalanche => "avalanche",
+        Chain::Arbitrum => "arbitrum-one",
+        Chain::Optimism => "optimistic-ethereum",
+        Chain::Base => "base",
+        Chain::Fantom => "fantom",
+        Chain::Cronos => "cronos",
+        Chain::Gnosis => "xdai",
+        Chain::Btc | Chain::Xrp | Chain::Cardano => return None,
+    };
+    Some(id)
+}
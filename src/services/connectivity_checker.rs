@@ -0,0 +1,250 @@
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::config::{ ChainConfig, Config };
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::rpc::RpcManager;
+
+/// Per-endpoint budget; a node slower than this is treated as down at startup
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// How often chains that failed the connectivity check are probed again
+const REPROBE_INTERVAL_SECS: u64 = 300;
+
+/// Outcome of probing one configured RPC endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectivityResult {
+    pub chain: String,
+    /// Scheme and host only, so API keys embedded in the URL aren't exposed
+    pub url: String,
+    pub latency_ms: u64,
+    /// Latest block height (slot on Solana, ledger index on XRP); 0 when the probe failed
+    pub block_number: u64,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Probes every configured RPC endpoint with a cheap "latest block" request
+pub struct ConnectivityChecker;
+
+impl ConnectivityChecker {
+    /// Check all mainnet (or server-network) endpoints concurrently, sorted by chain then URL
+    pub async fn check_all(config: &Config) -> Vec<ConnectivityResult> {
+        Self::check_chains(config.chain_configs.values()).await
+    }
+
+    /// Periodically re-probe chains the RPC manager has marked unavailable, re-enabling
+    /// each one as soon as any of its endpoints answers
+    pub async fn run_reprobe(config: Arc<Config>, rpc_manager: Arc<RpcManager>) {
+        let mut interval = tokio::time::interval(Duration::from_secs(REPROBE_INTERVAL_SECS));
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let unavailable = rpc_manager.unavailable_chains().await;
+            if unavailable.is_empty() {
+                continue;
+            }
+
+            let results = Self::check_chains(
+                config.chain_configs.values().filter(|c| unavailable.contains(&c.chain))
+            ).await;
+            let still_down = Self::unavailable_chains(&results);
+            for chain in unavailable.into_iter().filter(|c| !still_down.contains(c)) {
+                tracing::info!("RPC endpoint for {} is reachable again; marking it available", chain);
+                rpc_manager.mark_available(chain).await;
+            }
+        }
+    }
+
+    async fn check_chains<'a>(
+        chain_configs: impl Iterator<Item = &'a ChainConfig>
+    ) -> Vec<ConnectivityResult> {
+        let client = reqwest::Client
+            ::builder()
+            .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        let mut probes = JoinSet::new();
+        for chain_config in chain_configs {
+            for url in &chain_config.rpc_urls {
+                let client = client.clone();
+                let chain_config = chain_config.clone();
+                let url = url.clone();
+                probes.spawn(async move { Self::check_endpoint(&client, &chain_config, url).await });
+            }
+        }
+
+        let mut results = probes.join_all().await;
+        results.sort_by(|a, b| a.chain.cmp(&b.chain).then_with(|| a.url.cmp(&b.url)));
+        results
+    }
+
+    /// Chains where no endpoint answered
+    pub fn unavailable_chains(results: &[ConnectivityResult]) -> Vec<Chain> {
+        let mut chains: Vec<Chain> = results
+            .iter()
+            .filter_map(|r| r.chain.parse::<Chain>().ok())
+            .filter(|chain| {
+                results.iter().all(|r| r.chain != chain.as_str() || !r.ok)
+            })
+            .collect();
+        chains.dedup();
+        chains
+    }
+
+    /// Render results as a fixed-width table for the startup log
+    pub fn format_table(results: &[ConnectivityResult]) -> String {
+        let mut table = format!("{:<10} {:<6} {:>8} {:>12}  {}\n", "CHAIN", "STATUS", "LATENCY", "BLOCK", "URL");
+        for r in results {
+            table.push_str(
+                &format!(
+                    "{:<10} {:<6} {:>6}ms {:>12}  {}{}\n",
+                    r.chain,
+                    if r.ok { "OK" } else { "DOWN" },
+                    r.latency_ms,
+                    r.block_number,
+                    r.url,
+                    r.error
+                        .as_ref()
+                        .map(|e| format!(" ({})", e))
+                        .unwrap_or_default()
+                )
+            );
+        }
+        table
+    }
+
+    async fn check_endpoint(
+        client: &reqwest::Client,
+        chain_config: &ChainConfig,
+        url: String
+    ) -> ConnectivityResult {
+        let started = Instant::now();
+        let outcome = Self::fetch_block_number(client, chain_config, &url).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let (block_number, error) = match outcome {
+            Ok(block_number) => (block_number, None),
+            Err(e) => (0, Some(e.to_string())),
+        };
+
+        ConnectivityResult {
+            chain: chain_config.chain.as_str().to_string(),
+            url: redact_url(&url),
+            latency_ms,
+            block_number,
+            ok: error.is_none(),
+            error,
+        }
+    }
+
+    async fn fetch_block_number(
+        client: &reqwest::Client,
+        chain_config: &ChainConfig,
+        url: &str
+    ) -> Result<u64> {
+        let chain = chain_config.chain;
+        let base_url = url.trim_end_matches('/');
+
+        match chain {
+            Chain::Btc => {
+                // Esplora-style REST API, as used by the Bitcoin provider
+                let height = client
+                    .get(format!("{}/blocks/tip/height", base_url))
+                    .send().await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| probe_error(chain, url, e))?
+                    .text().await
+                    .map_err(|e| probe_error(chain, url, e))?;
+                height
+                    .trim()
+                    .parse()
+                    .map_err(|_| AppError::Rpc(format!("Unexpected tip height: {}", height.trim())))
+            }
+            Chain::Cardano => {
+                let mut request = client.get(format!("{}/blocks/latest", base_url));
+                if let Some(project_id) = &chain_config.api_key {
+                    request = request.header("project_id", project_id);
+                }
+                let block: serde_json::Value = request
+                    .send().await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| probe_error(chain, url, e))?
+                    .json().await
+                    .map_err(|e| probe_error(chain, url, e))?;
+                block["height"]
+                    .as_u64()
+                    .ok_or_else(|| AppError::Rpc("Blockfrost returned no block height".to_string()))
+            }
+            Chain::Xrp => {
+                let body =
+                    serde_json::json!({ "method": "ledger_current", "params": [{}] });
+                let response = Self::post_json(client, chain, url, &body).await?;
+                response["result"]["ledger_current_index"]
+                    .as_u64()
+                    .ok_or_else(|| AppError::Rpc("XRP node returned no ledger index".to_string()))
+            }
+            Chain::Solana => {
+                let body =
+                    serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "getSlot", "params": [] });
+                let response = Self::post_json(client, chain, url, &body).await?;
+                response["result"]
+                    .as_u64()
+                    .ok_or_else(|| json_rpc_error(&response))
+            }
+            _ => {
+                let body =
+                    serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": [] });
+                let response = Self::post_json(client, chain, url, &body).await?;
+                response["result"]
+                    .as_str()
+                    .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+                    .ok_or_else(|| json_rpc_error(&response))
+            }
+        }
+    }
+
+    async fn post_json(
+        client: &reqwest::Client,
+        chain: Chain,
+        url: &str,
+        body: &serde_json::Value
+    ) -> Result<serde_json::Value> {
+        client
+            .post(url)
+            .json(body)
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| probe_error(chain, url, e))?
+            .json().await
+            .map_err(|e| probe_error(chain, url, e))
+    }
+}
+
+fn probe_error(chain: Chain, url: &str, e: reqwest::Error) -> AppError {
+    if e.is_timeout() {
+        AppError::ConnectionTimeout { chain: chain.to_string(), url: redact_url(url) }
+    } else {
+        AppError::Rpc(e.without_url().to_string())
+    }
+}
+
+fn json_rpc_error(response: &serde_json::Value) -> AppError {
+    let message = response["error"]["message"].as_str().unwrap_or("unexpected response");
+    AppError::Rpc(message.to_string())
+}
+
+/// Hosted RPC URLs often embed an API key in the path; keep only scheme and host for logs
+fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or_default()),
+        Err(_) => url.to_string(),
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::services::portfolio_service::{ Portfolio, PortfolioService };
+
+/// One wallet's balance of a token that may also be held on other chains
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainHolding {
+    pub chain: String,
+    pub wallet_id: String,
+    pub address: String,
+    pub balance: String,
+    pub usd_value: f64,
+}
+
+/// A token's value summed across every chain and wallet it's held in
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregatedTokenBalance {
+    pub symbol: String,
+    pub total_usd_value: f64,
+    /// Largest first
+    pub holdings: Vec<ChainHolding>,
+}
+
+/// Unified per-token view of a portfolio, e.g. USDC on Ethereum, Polygon and Arbitrum as one line
+pub struct CrossChainBalanceService {
+    portfolio_service: Arc<PortfolioService>,
+}
+
+impl CrossChainBalanceService {
+    pub fn new(portfolio_service: Arc<PortfolioService>) -> Self {
+        Self { portfolio_service }
+    }
+
+    /// The user's tokens matched by symbol across chains, most valuable first
+    pub async fn aggregate_by_token(&self, user_id: &str) -> Result<Vec<AggregatedTokenBalance>> {
+        let portfolio = self.portfolio_service.get_portfolio(user_id).await?;
+        Ok(Self::aggregate(&portfolio))
+    }
+
+    /// Group a portfolio's holdings by symbol, ignoring case, skipping empty balances
+    pub fn aggregate(portfolio: &Portfolio) -> Vec<AggregatedTokenBalance> {
+        let mut by_symbol: HashMap<String, AggregatedTokenBalance> = HashMap::new();
+
+        for holding in &portfolio.holdings {
+            let symbol = holding.symbol.to_uppercase();
+            for wallet in &holding.wallets {
+                let balance: f64 = wallet.balance.parse().unwrap_or(0.0);
+                if balance <= 0.0 {
+                    continue;
+                }
+                let usd_value = balance * holding.usd_price;
+
+                let entry = by_symbol
+                    .entry(symbol.clone())
+                    .or_insert_with(|| AggregatedTokenBalance {
+                        symbol: symbol.clone(),
+                        total_usd_value: 0.0,
+                        holdings: Vec::new(),
+                    });
+                entry.total_usd_value += usd_value;
+                entry.holdings.push(ChainHolding {
+                    chain: wallet.chain.clone(),
+                    wallet_id: wallet.wallet_id.clone(),
+                    address: wallet.address.clone(),
+                    balance: wallet.balance.clone(),
+                    usd_value,
+                });
+            }
+        }
+
+        let mut tokens: Vec<AggregatedTokenBalance> = by_symbol.into_values().collect();
+        for token in &mut tokens {
+            token.holdings.sort_by(|a, b| {
+                b.usd_value.partial_cmp(&a.usd_value).unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+        tokens.sort_by(|a, b| {
+            b.total_usd_value
+                .partial_cmp(&a.total_usd_value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+        tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::portfolio_service::{ TokenHolding, WalletHolding };
+
+    fn wallet(chain: &str, balance: &str) -> WalletHolding {
+        WalletHolding {
+            wallet_id: format!("{}-wallet", chain),
+            chain: chain.to_string(),
+            address: "0xabc".to_string(),
+            balance: balance.to_string(),
+        }
+    }
+
+    fn holding(symbol: &str, usd_price: f64, wallets: Vec<WalletHolding>) -> TokenHolding {
+        TokenHolding {
+            symbol: symbol.to_string(),
+            name: None,
+            total_balance: 0.0,
+            usd_value: 0.0,
+            usd_price,
+            price_change_24h: None,
+            logo_url: None,
+            wallets,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_matches_symbols_case_insensitively() {
+        let portfolio = Portfolio {
+            user_id: "1".to_string(),
+            holdings: vec![
+                holding("USDC", 1.0, vec![wallet("ETH", "500"), wallet("ARBITRUM", "334")]),
+                holding("usdc", 1.0, vec![wallet("POLYGON", "400"), wallet("BASE", "0")]),
+                holding("ETH", 2000.0, vec![wallet("ETH", "0.1")])
+            ],
+            total_usd_value: 0.0,
+            chains: vec![],
+            wallet_count: 4,
+        };
+
+        let tokens = CrossChainBalanceService::aggregate(&portfolio);
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].symbol, "USDC");
+        assert_eq!(tokens[0].total_usd_value, 1234.0);
+        let chains: Vec<&str> = tokens[0].holdings
+            .iter()
+            .map(|h| h.chain.as_str())
+            .collect();
+        assert_eq!(chains, vec!["ETH", "POLYGON", "ARBITRUM"]);
+        assert_eq!(tokens[1].symbol, "ETH");
+    }
+}
@@ -0,0 +1,481 @@
+use std::sync::Arc;
+
+use chrono::{ DateTime, Utc };
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    DatabaseConnection,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::entity::{ auto_compound_plan, dca_plan, scheduled_transaction, swap, wallet };
+use crate::enums::{ DcaStatus, DefiProtocol, RecurringType };
+use crate::error::{ AppError, Result };
+use crate::providers::TransactionResponse;
+use crate::services::defi_protocol_service::DefiProtocolService;
+use crate::services::scheduling_service::{ ScheduleRequest, SchedulingService };
+use crate::services::swap_service::{ SwapRequest, SwapService };
+
+/// Slippage tolerance for unattended DCA buys, in percent
+const DCA_SLIPPAGE_PCT: f64 = 1.0;
+
+/// A DCA plan together with when its next buy is due
+#[derive(Debug, Clone, Serialize)]
+pub struct DcaPlan {
+    #[serde(flatten)]
+    pub plan: dca_plan::Model,
+    pub next_execution: Option<DateTime<Utc>>,
+}
+
+/// Recurring swaps of a fixed amount, and recurring reward claims on lending positions,
+/// driven by the scheduled-transaction executor
+pub struct DcaService {
+    db: DatabaseConnection,
+    scheduling_service: Arc<SchedulingService>,
+    swap_service: Arc<SwapService>,
+    defi_protocol_service: Arc<DefiProtocolService>,
+}
+
+impl DcaService {
+    pub fn new(
+        db: DatabaseConnection,
+        scheduling_service: Arc<SchedulingService>,
+        swap_service: Arc<SwapService>,
+        defi_protocol_service: Arc<DefiProtocolService>
+    ) -> Self {
+        Self { db, scheduling_service, swap_service, defi_protocol_service }
+    }
+
+    /// Buy `to_token` with `amount_per_period` of `from_token` every period, starting at `start_date`
+    pub async fn create_dca_plan(
+        &self,
+        user_id: &str,
+        wallet_id: Uuid,
+        from_token: &str,
+        to_token: &str,
+        amount_per_period: f64,
+        period: RecurringType,
+        start_date: DateTime<Utc>
+    ) -> Result<DcaPlan> {
+        self.ensure_swaps_executable()?;
+        if !amount_per_period.is_finite() || amount_per_period <= 0.0 {
+            return Err(AppError::InvalidInput("Amount per period must be positive".to_string()));
+        }
+        if from_token.eq_ignore_ascii_case(to_token) {
+            return Err(AppError::InvalidInput("Cannot DCA a token into itself".to_string()));
+        }
+
+        let wallet = wallet::Entity
+            ::find_by_id(wallet_id)
+            .one(&self.db).await?
+            .filter(|w| w.user_id == user_id)
+            .ok_or(AppError::WalletNotFound)?;
+
+        let schedule = self.schedule_buy(
+            &wallet,
+            amount_per_period,
+            period,
+            start_date
+        ).await?;
+
+        let now = Utc::now();
+        let plan = dca_plan::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user_id.to_string()),
+            wallet_id: ActiveValue::Set(wallet_id),
+            schedule_id: ActiveValue::Set(Some(schedule.id)),
+            from_token: ActiveValue::Set(from_token.to_uppercase()),
+            to_token: ActiveValue::Set(to_token.to_uppercase()),
+            amount_per_period: ActiveValue::Set(amount_per_period),
+            period: ActiveValue::Set(period.to_string()),
+            status: ActiveValue::Set(DcaStatus::Active.to_string()),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+        let plan = plan.insert(&self.db).await?;
+
+        Ok(DcaPlan {
+            plan,
+            next_execution: Some(schedule.scheduled_for),
+        })
+    }
+
+    /// A user's plans that haven't been cancelled, oldest first
+    pub async fn list_dca_plans(&self, user_id: &str) -> Result<Vec<DcaPlan>> {
+        let plans = dca_plan::Entity
+            ::find()
+            .filter(dca_plan::Column::UserId.eq(user_id))
+            .filter(dca_plan::Column::Status.ne(DcaStatus::Cancelled.as_str()))
+            .order_by_asc(dca_plan::Column::CreatedAt)
+            .all(&self.db).await?;
+
+        let mut result = Vec::with_capacity(plans.len());
+        for plan in plans {
+            let next_execution = self.next_execution(&plan).await?;
+            result.push(DcaPlan { plan, next_execution });
+        }
+        Ok(result)
+    }
+
+    /// Stop buying until resumed; the pending schedule is cancelled
+    pub async fn pause_dca_plan(&self, plan_id: Uuid, user_id: &str) -> Result<dca_plan::Model> {
+        let plan = self.find_plan(plan_id, user_id).await?;
+        if plan.status != DcaStatus::Active.as_str() {
+            return Err(AppError::Validation(format!("DCA plan is {}, not active", plan.status)));
+        }
+
+        if let Some(schedule_id) = plan.schedule_id {
+            self.scheduling_service.cancel_schedule(schedule_id, user_id).await?;
+        }
+        // Keep the schedule reference so resuming picks up the same cadence
+        let schedule_id = plan.schedule_id;
+        self.update_plan(plan, DcaStatus::Paused, schedule_id).await
+    }
+
+    /// Restart a paused plan at its next period boundary that is still in the future
+    pub async fn resume_dca_plan(&self, plan_id: Uuid, user_id: &str) -> Result<DcaPlan> {
+        let plan = self.find_plan(plan_id, user_id).await?;
+        if plan.status != DcaStatus::Paused.as_str() {
+            return Err(AppError::Validation(format!("DCA plan is {}, not paused", plan.status)));
+        }
+        self.ensure_swaps_executable()?;
+
+        let period = plan.period.parse::<RecurringType>()?;
+        let wallet = wallet::Entity
+            ::find_by_id(plan.wallet_id)
+            .one(&self.db).await?
+            .ok_or(AppError::WalletNotFound)?;
+
+        let last_run = match plan.schedule_id {
+            Some(id) => self.scheduling_service.get_schedule(id).await?.map(|s| s.scheduled_for),
+            None => None,
+        };
+        let now = Utc::now();
+        let mut next_run = last_run.unwrap_or(now);
+        while next_run < now {
+            next_run = SchedulingService::next_run(period, next_run);
+        }
+
+        let schedule = self.schedule_buy(
+            &wallet,
+            plan.amount_per_period,
+            period,
+            next_run
+        ).await?;
+        let plan = self.update_plan(plan, DcaStatus::Active, Some(schedule.id)).await?;
+
+        Ok(DcaPlan {
+            plan,
+            next_execution: Some(schedule.scheduled_for),
+        })
+    }
+
+    /// Stop the plan for good
+    pub async fn cancel_dca_plan(&self, plan_id: Uuid, user_id: &str) -> Result<dca_plan::Model> {
+        let plan = self.find_plan(plan_id, user_id).await?;
+        if plan.status == DcaStatus::Cancelled.as_str() {
+            return Ok(plan);
+        }
+
+        if let Some(schedule_id) = plan.schedule_id {
+            self.scheduling_service.cancel_schedule(schedule_id, user_id).await?;
+        }
+        self.update_plan(plan, DcaStatus::Cancelled, None).await
+    }
+
+    /// The plan a due schedule belongs to, if it is a DCA buy rather than a transfer
+    pub async fn find_by_schedule(&self, schedule_id: Uuid) -> Result<Option<dca_plan::Model>> {
+        let plan = dca_plan::Entity
+            ::find()
+            .filter(dca_plan::Column::ScheduleId.eq(schedule_id))
+            .one(&self.db).await?;
+        Ok(plan)
+    }
+
+    /// Run one period's buy for a due schedule and queue the next one.
+    /// A failed buy is recorded on the schedule but doesn't end the plan.
+    pub async fn execute_due(
+        &self,
+        plan: &dca_plan::Model,
+        schedule: &scheduled_transaction::Model
+    ) -> Result<swap::Model> {
+        if plan.status != DcaStatus::Active.as_str() {
+            let reason = format!("DCA plan is {}", plan.status);
+            self.scheduling_service.expire_schedule(schedule.id, reason.clone()).await?;
+            return Err(AppError::Validation(reason));
+        }
+
+        let result = self.swap_service.execute_swap(SwapRequest {
+            user_id: plan.user_id.clone(),
+            wallet_id: plan.wallet_id,
+            from_token: plan.from_token.clone(),
+            to_token: plan.to_token.clone(),
+            amount: plan.amount_per_period,
+            slippage: DCA_SLIPPAGE_PCT,
+            dex: None,
+        }).await;
+
+        let next = match &result {
+            Ok(swap) => {
+                let tx_hash = swap.tx_hash.clone().unwrap_or_default();
+                self.scheduling_service.mark_executed(schedule.id, tx_hash).await?
+            }
+            Err(e) => {
+                self.scheduling_service.mark_failed(schedule.id, e.to_string()).await?;
+                let period = plan.period.parse::<RecurringType>()?;
+                let wallet = wallet::Entity
+                    ::find_by_id(plan.wallet_id)
+                    .one(&self.db).await?
+                    .ok_or(AppError::WalletNotFound)?;
+                Some(
+                    self.schedule_buy(
+                        &wallet,
+                        plan.amount_per_period,
+                        period,
+                        SchedulingService::next_run(period, schedule.scheduled_for)
+                    ).await?
+                )
+            }
+        };
+
+        self.update_plan(
+            plan.clone(),
+            DcaStatus::Active,
+            next.map(|s| s.id)
+        ).await?;
+
+        result
+    }
+
+    /// Claim `protocol` rewards into the wallet's position every period once they're worth
+    /// `min_profit_usd`, starting one period from now
+    pub async fn create_auto_compound_plan(
+        &self,
+        user_id: &str,
+        wallet_id: Uuid,
+        protocol: DefiProtocol,
+        min_profit_usd: f64,
+        period: RecurringType
+    ) -> Result<auto_compound_plan::Model> {
+        if !min_profit_usd.is_finite() || min_profit_usd < 0.0 {
+            return Err(AppError::InvalidInput("Minimum profit must be zero or more".to_string()));
+        }
+
+        let wallet = wallet::Entity
+            ::find_by_id(wallet_id)
+            .one(&self.db).await?
+            .filter(|w| w.user_id == user_id)
+            .ok_or(AppError::WalletNotFound)?;
+
+        let existing = auto_compound_plan::Entity
+            ::find()
+            .filter(auto_compound_plan::Column::WalletId.eq(wallet_id))
+            .filter(auto_compound_plan::Column::Protocol.eq(protocol.as_str()))
+            .filter(auto_compound_plan::Column::Status.eq(DcaStatus::Active.as_str()))
+            .one(&self.db).await?;
+        if existing.is_some() {
+            return Err(
+                AppError::Validation(format!("{} rewards are already auto-compounded for this wallet", protocol.display_name()))
+            );
+        }
+
+        // Claims move nothing out of the wallet, so the schedule carries no amount
+        let schedule = self.schedule_buy(
+            &wallet,
+            0.0,
+            period,
+            SchedulingService::next_run(period, Utc::now())
+        ).await?;
+
+        let now = Utc::now();
+        let plan = auto_compound_plan::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user_id.to_string()),
+            wallet_id: ActiveValue::Set(wallet_id),
+            schedule_id: ActiveValue::Set(Some(schedule.id)),
+            protocol: ActiveValue::Set(protocol.to_string()),
+            min_profit_usd: ActiveValue::Set(min_profit_usd),
+            period: ActiveValue::Set(period.to_string()),
+            status: ActiveValue::Set(DcaStatus::Active.to_string()),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+        Ok(plan.insert(&self.db).await?)
+    }
+
+    /// A wallet's active auto-compound plans
+    pub async fn list_auto_compound_plans(&self, wallet_id: Uuid) -> Result<Vec<auto_compound_plan::Model>> {
+        let plans = auto_compound_plan::Entity
+            ::find()
+            .filter(auto_compound_plan::Column::WalletId.eq(wallet_id))
+            .filter(auto_compound_plan::Column::Status.eq(DcaStatus::Active.as_str()))
+            .order_by_asc(auto_compound_plan::Column::CreatedAt)
+            .all(&self.db).await?;
+        Ok(plans)
+    }
+
+    pub async fn cancel_auto_compound_plan(
+        &self,
+        plan_id: Uuid,
+        user_id: &str
+    ) -> Result<auto_compound_plan::Model> {
+        let plan = auto_compound_plan::Entity
+            ::find_by_id(plan_id)
+            .filter(auto_compound_plan::Column::UserId.eq(user_id))
+            .one(&self.db).await?
+            .ok_or_else(|| AppError::NotFound("Auto-compound plan not found".to_string()))?;
+        if plan.status == DcaStatus::Cancelled.as_str() {
+            return Ok(plan);
+        }
+
+        if let Some(schedule_id) = plan.schedule_id {
+            self.scheduling_service.cancel_schedule(schedule_id, user_id).await?;
+        }
+        self.update_auto_compound_plan(plan, DcaStatus::Cancelled, None).await
+    }
+
+    /// The auto-compound plan a due schedule belongs to, if any
+    pub async fn find_auto_compound_by_schedule(
+        &self,
+        schedule_id: Uuid
+    ) -> Result<Option<auto_compound_plan::Model>> {
+        let plan = auto_compound_plan::Entity
+            ::find()
+            .filter(auto_compound_plan::Column::ScheduleId.eq(schedule_id))
+            .one(&self.db).await?;
+        Ok(plan)
+    }
+
+    /// Run one period's claim for a due schedule and queue the next one. `None` when the
+    /// rewards weren't worth claiming yet; a failed claim doesn't end the plan.
+    pub async fn execute_auto_compound_due(
+        &self,
+        plan: &auto_compound_plan::Model,
+        schedule: &scheduled_transaction::Model
+    ) -> Result<Option<TransactionResponse>> {
+        if plan.status != DcaStatus::Active.as_str() {
+            let reason = format!("Auto-compound plan is {}", plan.status);
+            self.scheduling_service.expire_schedule(schedule.id, reason.clone()).await?;
+            return Err(AppError::Validation(reason));
+        }
+
+        let result = self.defi_protocol_service.auto_compound(
+            plan.wallet_id,
+            &plan.protocol,
+            plan.min_profit_usd
+        ).await;
+
+        let next = match &result {
+            Ok(tx) => {
+                let tx_hash = tx.as_ref().map(|t| t.tx_hash.clone()).unwrap_or_default();
+                self.scheduling_service.mark_executed(schedule.id, tx_hash).await?
+            }
+            Err(e) => {
+                self.scheduling_service.mark_failed(schedule.id, e.to_string()).await?;
+                let period = plan.period.parse::<RecurringType>()?;
+                let wallet = wallet::Entity
+                    ::find_by_id(plan.wallet_id)
+                    .one(&self.db).await?
+                    .ok_or(AppError::WalletNotFound)?;
+                Some(
+                    self.schedule_buy(
+                        &wallet,
+                        0.0,
+                        period,
+                        SchedulingService::next_run(period, schedule.scheduled_for)
+                    ).await?
+                )
+            }
+        };
+
+        self.update_auto_compound_plan(
+            plan.clone(),
+            DcaStatus::Active,
+            next.map(|s| s.id)
+        ).await?;
+
+        result
+    }
+
+    async fn update_auto_compound_plan(
+        &self,
+        plan: auto_compound_plan::Model,
+        status: DcaStatus,
+        schedule_id: Option<Uuid>
+    ) -> Result<auto_compound_plan::Model> {
+        let mut active: auto_compound_plan::ActiveModel = plan.into();
+        active.status = ActiveValue::Set(status.to_string());
+        active.schedule_id = ActiveValue::Set(schedule_id);
+        active.updated_at = ActiveValue::Set(Utc::now());
+        Ok(active.update(&self.db).await?)
+    }
+
+    /// DCA buys run unattended, so refuse to schedule them while swaps can't be signed
+    fn ensure_swaps_executable(&self) -> Result<()> {
+        if self.swap_service.can_sign_swaps() {
+            Ok(())
+        } else {
+            Err(
+                AppError::Validation(
+                    "Scheduled swaps aren't available yet, so DCA plans can't be started".to_string()
+                )
+            )
+        }
+    }
+
+    async fn schedule_buy(
+        &self,
+        wallet: &wallet::Model,
+        amount_per_period: f64,
+        period: RecurringType,
+        scheduled_for: DateTime<Utc>
+    ) -> Result<scheduled_transaction::Model> {
+        // The swap settles in the wallet itself, so the schedule points back at it
+        self.scheduling_service.schedule_transaction(ScheduleRequest {
+            user_id: wallet.user_id.clone(),
+            wallet_id: wallet.id,
+            to_address: wallet.address.clone(),
+            amount: amount_per_period.to_string(),
+            token_address: None,
+            scheduled_for,
+            recurring_type: Some(period),
+            max_gas_price_gwei: None,
+        }).await
+    }
+
+    async fn next_execution(&self, plan: &dca_plan::Model) -> Result<Option<DateTime<Utc>>> {
+        if plan.status != DcaStatus::Active.as_str() {
+            return Ok(None);
+        }
+        let Some(schedule_id) = plan.schedule_id else {
+            return Ok(None);
+        };
+        Ok(self.scheduling_service.get_schedule(schedule_id).await?.map(|s| s.scheduled_for))
+    }
+
+    async fn find_plan(&self, plan_id: Uuid, user_id: &str) -> Result<dca_plan::Model> {
+        dca_plan::Entity
+            ::find_by_id(plan_id)
+            .filter(dca_plan::Column::UserId.eq(user_id))
+            .one(&self.db).await?
+            .ok_or_else(|| AppError::NotFound("DCA plan not found".to_string()))
+    }
+
+    async fn update_plan(
+        &self,
+        plan: dca_plan::Model,
+        status: DcaStatus,
+        schedule_id: Option<Uuid>
+    ) -> Result<dca_plan::Model> {
+        let mut active: dca_plan::ActiveModel = plan.into();
+        active.status = ActiveValue::Set(status.to_string());
+        active.schedule_id = ActiveValue::Set(schedule_id);
+        active.updated_at = ActiveValue::Set(Utc::now());
+        Ok(active.update(&self.db).await?)
+    }
+}
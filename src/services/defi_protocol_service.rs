@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{ Deserialize, Serialize };
+use uuid::Uuid;
+
+use crate::chains::evm::defi::{ per_second_apy, SECONDS_PER_YEAR };
+use crate::crypto::Encryptor;
+use crate::db::WalletRepository;
+use crate::enums::{ Chain, DefiProtocol };
+use crate::error::{ AppError, Result };
+use crate::providers::{ ChainProvider, LendingReward, TransactionResponse };
+use crate::rpc::RpcManager;
+use crate::services::PriceService;
+
+/// Aave reports rates in ray units (1e27)
+const RAY: f64 = 1e27;
+
+/// Rewards must be worth this much before a bot-created auto-compound plan claims them
+pub const DEFAULT_MIN_COMPOUND_PROFIT_USD: f64 = 5.0;
+
+/// Gas budgeted for each claim, approve and supply transaction of an auto-compound
+const COMPOUND_TX_GAS: f64 = 250_000.0;
+
+const AAVE_POSITIONS_QUERY: &str =
+    r#"query ($user: String!) {
+  userReserves(where: { user: $user, currentATokenBalance_gt: "0" }) {
+    currentATokenBalance
+    reserve { symbol decimals liquidityRate aToken { id } }
+  }
+}"#;
+
+/// A supply position in a lending protocol
+#[derive(Debug, Clone, Serialize)]
+pub struct DefiPosition {
+    pub protocol: DefiProtocol,
+    pub asset_symbol: String,
+    pub supplied_amount: f64,
+    /// Supply APY in percent
+    pub apy: f64,
+    /// Unclaimed incentive rewards in USD; interest is already part of `supplied_amount`
+    pub earned_yield_usd: f64,
+    /// The aToken on Aave, the Comet market on Compound
+    pub market_address: String,
+    /// Contracts of the reward tokens behind `earned_yield_usd`
+    pub reward_tokens: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SubgraphResponse {
+    data: Option<AaveUserReserves>,
+    #[serde(default)]
+    errors: Vec<SubgraphError>,
+}
+
+#[derive(Deserialize)]
+struct SubgraphError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AaveUserReserves {
+    user_reserves: Vec<AaveUserReserve>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AaveUserReserve {
+    current_a_token_balance: String,
+    reserve: AaveReserve,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AaveReserve {
+    symbol: String,
+    decimals: u32,
+    liquidity_rate: String,
+    a_token: AaveToken,
+}
+
+#[derive(Deserialize)]
+struct AaveToken {
+    id: String,
+}
+
+/// Aave and Compound supply positions, and claiming their rewards back into the position
+pub struct DefiProtocolService {
+    repository: Arc<WalletRepository>,
+    rpc_manager: Arc<RpcManager>,
+    encryptor: Arc<Encryptor>,
+    price_service: Arc<PriceService>,
+    client: reqwest::Client,
+    aave_subgraph_urls: HashMap<Chain, String>,
+}
+
+impl DefiProtocolService {
+    pub fn new(
+        repository: Arc<WalletRepository>,
+        rpc_manager: Arc<RpcManager>,
+        encryptor: Arc<Encryptor>,
+        price_service: Arc<PriceService>,
+        aave_subgraph_urls: HashMap<Chain, String>
+    ) -> Self {
+        Self {
+            repository,
+            rpc_manager,
+            encryptor,
+            price_service,
+            client: reqwest::Client::builder().timeout(Duration::from_secs(15)).build().unwrap(),
+            aave_subgraph_urls,
+        }
+    }
+
+    /// Every Aave and Compound supply position of a wallet
+    pub async fn get_positions(&self, wallet_id: Uuid) -> Result<Vec<DefiPosition>> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+        self.positions_on(provider.as_ref(), &wallet.chain, &wallet.address).await
+    }
+
+    /// Claim `protocol` rewards for a wallet and supply them back, once they're worth more than
+    /// `min_profit_usd` after gas. `None` when there wasn't enough to be worth the transactions.
+    pub async fn auto_compound(
+        &self,
+        wallet_id: Uuid,
+        protocol: &str,
+        min_profit_usd: f64
+    ) -> Result<Option<TransactionResponse>> {
+        let protocol: DefiProtocol = protocol.parse()?;
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        let positions: Vec<DefiPosition> = self
+            .positions_on(provider.as_ref(), &wallet.chain, &wallet.address).await?
+            .into_iter()
+            .filter(|p| p.protocol == protocol)
+            .collect();
+        let earned_usd: f64 = positions
+            .iter()
+            .map(|p| p.earned_yield_usd)
+            .sum();
+        if positions.is_empty() || earned_usd < min_profit_usd {
+            tracing::debug!(
+                "Skipping {} auto-compound for wallet {}: ${:.2} earned, ${:.2} required",
+                protocol,
+                wallet_id,
+                earned_usd,
+                min_profit_usd
+            );
+            return Ok(None);
+        }
+
+        // One claim, then an approve and a supply per reward token
+        let mut reward_tokens: Vec<&str> = positions
+            .iter()
+            .flat_map(|p| p.reward_tokens.iter().map(String::as_str))
+            .collect();
+        reward_tokens.sort_unstable();
+        reward_tokens.dedup();
+        let tx_count = 1 + 2 * reward_tokens.len();
+        let Some(gas_usd) = self.gas_cost_usd(provider.as_ref(), &wallet.chain, tx_count).await else {
+            tracing::debug!("Skipping {} auto-compound for wallet {}: gas cost unknown", protocol, wallet_id);
+            return Ok(None);
+        };
+        if earned_usd - gas_usd < min_profit_usd {
+            tracing::debug!(
+                "Skipping {} auto-compound for wallet {}: ${:.2} earned, ${:.2} gas for {} transactions, ${:.2} required",
+                protocol,
+                wallet_id,
+                earned_usd,
+                gas_usd,
+                tx_count,
+                min_profit_usd
+            );
+            return Ok(None);
+        }
+
+        let assets: Vec<String> = positions
+            .into_iter()
+            .map(|p| p.market_address)
+            .collect();
+        let private_key = self.encryptor.decrypt(&wallet.encrypted_private_key)?;
+        let tx = provider.compound_lending_rewards(&private_key, protocol, &assets).await?;
+
+        Ok(Some(tx))
+    }
+
+    async fn positions_on(
+        &self,
+        provider: &dyn ChainProvider,
+        chain: &str,
+        address: &str
+    ) -> Result<Vec<DefiPosition>> {
+        let parsed: Chain = chain.parse()?;
+        if !parsed.is_evm() {
+            return Err(AppError::InvalidInput(format!("DeFi positions are not supported on {}", chain)));
+        }
+
+        let mut positions = Vec::new();
+
+        if let Some(url) = self.aave_subgraph_urls.get(&parsed) {
+            for reserve in self.fetch_aave_reserves(url, address).await? {
+                let assets = [reserve.reserve.a_token.id.clone()];
+                let rewards = provider.get_lending_rewards(DefiProtocol::Aave, address, &assets).await?;
+                let supplied_amount =
+                    reserve.current_a_token_balance.parse::<f64>().unwrap_or(0.0) /
+                    (10f64).powi(reserve.reserve.decimals as i32);
+                let apr = reserve.reserve.liquidity_rate.parse::<f64>().unwrap_or(0.0) / RAY;
+
+                positions.push(DefiPosition {
+                    protocol: DefiProtocol::Aave,
+                    asset_symbol: reserve.reserve.symbol,
+                    supplied_amount,
+                    apy: per_second_apy(apr / SECONDS_PER_YEAR),
+                    earned_yield_usd: self.rewards_usd(&rewards).await,
+                    market_address: reserve.reserve.a_token.id,
+                    reward_tokens: rewards.into_iter().map(|r| r.token_address).collect(),
+                });
+            }
+        }
+
+        if let Some(supply) = provider.get_compound_supply(address).await? {
+            let rewards = provider.get_lending_rewards(DefiProtocol::Compound, address, &[]).await?;
+            positions.push(DefiPosition {
+                protocol: DefiProtocol::Compound,
+                asset_symbol: supply.asset_symbol,
+                supplied_amount: supply.supplied.parse().unwrap_or(0.0),
+                apy: supply.supply_apy,
+                earned_yield_usd: self.rewards_usd(&rewards).await,
+                market_address: supply.market_address,
+                reward_tokens: rewards.into_iter().map(|r| r.token_address).collect(),
+            });
+        }
+
+        Ok(positions)
+    }
+
+    async fn fetch_aave_reserves(&self, url: &str, address: &str) -> Result<Vec<AaveUserReserve>> {
+        // Subgraph ids are lowercase addresses
+        let body =
+            serde_json::json!({
+            "query": AAVE_POSITIONS_QUERY,
+            "variables": { "user": address.to_lowercase() }
+        });
+
+        let response: SubgraphResponse = self.client
+            .post(url)
+            .json(&body)
+            .send().await
+            .map_err(|e| AppError::External(format!("Aave subgraph request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse Aave subgraph response: {}", e)))?;
+
+        if let Some(error) = response.errors.first() {
+            return Err(AppError::External(format!("Aave subgraph error: {}", error.message)));
+        }
+
+        Ok(
+            response.data
+                .map(|d| d.user_reserves)
+                .unwrap_or_default()
+        )
+    }
+
+    /// USD cost of `tx_count` compounding transactions at the current gas price; `None` when
+    /// the gas price or the native token price is unavailable
+    async fn gas_cost_usd(&self, provider: &dyn ChainProvider, chain: &str, tx_count: usize) -> Option<f64> {
+        let chain: Chain = chain.parse().ok()?;
+        let gas_price_gwei = provider.get_network_load().await.ok()?.gas_price_gwei;
+        let native_usd = self.price_service.get_price(chain.native_symbol()).await.ok()?.usd_price;
+        Some((tx_count as f64) * COMPOUND_TX_GAS * gas_price_gwei * 1e-9 * native_usd)
+    }
+
+    /// USD value of unclaimed rewards; tokens without a price count as zero
+    async fn rewards_usd(&self, rewards: &[LendingReward]) -> f64 {
+        let mut total = 0.0;
+        for reward in rewards {
+            let amount: f64 = reward.amount.parse().unwrap_or(0.0);
+            match self.price_service.get_price(&reward.token_symbol).await {
+                Ok(price) => {
+                    total += amount * price.usd_price;
+                }
+                Err(e) => tracing::debug!("No price for reward token {}: {}", reward.token_symbol, e),
+            }
+        }
+        total
+    }
+}
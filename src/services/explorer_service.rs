@@ -0,0 +1,345 @@
+use chrono::{ DateTime, Utc };
+use ethers::types::U256;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::db::entity::transaction;
+use crate::enums::{ Chain, TxStatus };
+use crate::error::{ AppError, Result };
+
+/// Etherscan's multichain endpoint; `chainid` picks the network
+const ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Most signatures `getSignaturesForAddress` returns in one call
+const MAX_SOLANA_SIGNATURES: u32 = 1000;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// A transaction found on-chain that may not be in the local database
+#[derive(Debug, Clone)]
+pub struct ExplorerTransaction {
+    pub tx_hash: String,
+    pub chain: String,
+    pub from_address: String,
+    pub to_address: String,
+    /// Native amount in whole units
+    pub amount: String,
+    pub token_symbol: Option<String>,
+    pub status: TxStatus,
+    pub block_number: Option<i64>,
+    pub gas_used: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ExplorerTransaction {
+    /// Shape the transaction like a stored one. The id is random since the row isn't saved.
+    pub fn into_model(self, wallet_id: Uuid) -> transaction::Model {
+        transaction::Model {
+            id: Uuid::new_v4(),
+            wallet_id,
+            tx_hash: self.tx_hash,
+            chain: self.chain,
+            from_address: self.from_address,
+            to_address: self.to_address,
+            amount: self.amount,
+            token_address: None,
+            token_symbol: self.token_symbol,
+            status: self.status.to_string(),
+            block_number: self.block_number,
+            gas_used: self.gas_used,
+            created_at: self.timestamp.naive_utc(),
+            replaces_tx_id: None,
+            l1_fee_paid: None,
+            l1_refund: None,
+            transfer_retry_count: 0,
+            last_retry_error: None,
+            gas_fee_native: None,
+            gas_fee_usd: None,
+        }
+    }
+}
+
+// ── Etherscan response types ───────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct EtherscanResponse {
+    status: String,
+    message: String,
+    /// A list of transactions, or an error string when `status` is "0"
+    result: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct EtherscanTx {
+    hash: String,
+    from: String,
+    to: String,
+    value: String,
+    #[serde(rename = "blockNumber")]
+    block_number: String,
+    #[serde(rename = "timeStamp")]
+    time_stamp: String,
+    #[serde(rename = "isError")]
+    is_error: String,
+    #[serde(rename = "gasUsed")]
+    gas_used: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SolanaSignature {
+    signature: String,
+    slot: u64,
+    #[serde(rename = "blockTime")]
+    block_time: Option<i64>,
+    err: Option<Value>,
+}
+
+/// Reads on-chain history from block explorers (Etherscan for EVM chains, the Solana RPC for
+/// Solana) for wallets whose transactions weren't sent through the bot
+pub struct ExplorerService {
+    client: reqwest::Client,
+    etherscan_api_key: Option<String>,
+    solana_rpc_url: Option<String>,
+}
+
+impl ExplorerService {
+    pub fn new(etherscan_api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client
+                ::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            etherscan_api_key,
+            solana_rpc_url: None,
+        }
+    }
+
+    /// Read Solana history through this RPC endpoint
+    pub fn with_solana_rpc(mut self, rpc_url: String) -> Self {
+        self.solana_rpc_url = Some(rpc_url);
+        self
+    }
+
+    /// Whether history for mainnet `chain` can be fetched with the configured keys
+    pub fn is_supported(&self, chain: &Chain) -> bool {
+        match chain {
+            Chain::Solana => self.solana_rpc_url.is_some(),
+            c => c.is_evm() && self.etherscan_api_key.is_some(),
+        }
+    }
+
+    /// Newest-first native transactions of `address` on mainnet `chain`, `limit` per page
+    /// starting at page 1
+    pub async fn fetch_transactions(
+        &self,
+        chain: &str,
+        address: &str,
+        page: u32,
+        limit: u32
+    ) -> Result<Vec<ExplorerTransaction>> {
+        let parsed: Chain = chain.parse()?;
+        if page == 0 || limit == 0 {
+            return Err(AppError::InvalidInput("Page and limit start at 1".to_string()));
+        }
+
+        match parsed {
+            Chain::Solana => self.fetch_solana(address, page, limit).await,
+            c if c.is_evm() => self.fetch_etherscan(c, address, page, limit).await,
+            _ => Err(AppError::InvalidInput(format!("Explorer history is not available on {}", chain))),
+        }
+    }
+
+    async fn fetch_etherscan(
+        &self,
+        chain: Chain,
+        address: &str,
+        page: u32,
+        limit: u32
+    ) -> Result<Vec<ExplorerTransaction>> {
+        let api_key = self.etherscan_api_key
+            .as_ref()
+            .ok_or_else(|| AppError::Config("ETHERSCAN_API_KEY not set".to_string()))?;
+        let chain_id = chain
+            .chain_id(false)
+            .ok_or_else(|| AppError::InvalidInput(format!("No chain id for {}", chain)))?;
+
+        let chain_id = chain_id.to_string();
+        let page = page.to_string();
+        let limit = limit.to_string();
+        let response: EtherscanResponse = self.client
+            .get(ETHERSCAN_API_URL)
+            .query(
+                &[
+                    ("chainid", chain_id.as_str()),
+                    ("module", "account"),
+                    ("action", "txlist"),
+                    ("address", address),
+                    ("page", page.as_str()),
+                    ("offset", limit.as_str()),
+                    ("sort", "desc"),
+                    ("apikey", api_key.as_str()),
+                ]
+            )
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("Etherscan request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse Etherscan response: {}", e)))?;
+
+        // An address without history also answers with status "0"
+        if response.status != "1" {
+            if response.message.starts_with("No transactions found") {
+                return Ok(vec![]);
+            }
+            return Err(
+                AppError::External(format!("Etherscan error: {} ({})", response.message, response.result))
+            );
+        }
+
+        let txs: Vec<EtherscanTx> = serde_json
+            ::from_value(response.result)
+            .map_err(|e| AppError::External(format!("Failed to parse Etherscan transactions: {}", e)))?;
+
+        Ok(
+            txs
+                .into_iter()
+                .map(|tx| {
+                    let amount = U256::from_dec_str(&tx.value)
+                        .map(ethers::utils::format_ether)
+                        .unwrap_or_else(|_| "0".to_string());
+                    let timestamp = tx.time_stamp
+                        .parse::<i64>()
+                        .ok()
+                        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                        .unwrap_or_default();
+
+                    ExplorerTransaction {
+                        tx_hash: tx.hash,
+                        chain: chain.as_str().to_string(),
+                        from_address: tx.from,
+                        to_address: tx.to,
+                        amount,
+                        token_symbol: Some(chain.native_symbol().to_string()),
+                        status: if tx.is_error == "1" { TxStatus::Failed } else { TxStatus::Confirmed },
+                        block_number: tx.block_number.parse().ok(),
+                        gas_used: Some(tx.gas_used),
+                        timestamp,
+                    }
+                })
+                .collect()
+        )
+    }
+
+    async fn fetch_solana(&self, address: &str, page: u32, limit: u32) -> Result<Vec<ExplorerTransaction>> {
+        let rpc_url = self.solana_rpc_url
+            .as_ref()
+            .ok_or_else(|| AppError::Config("No Solana RPC URL configured".to_string()))?;
+
+        // The RPC pages by signature cursor, so earlier pages are fetched and skipped
+        let wanted = page.saturating_mul(limit).min(MAX_SOLANA_SIGNATURES);
+        let skip = ((page - 1) * limit) as usize;
+        let signatures: Vec<SolanaSignature> = solana_rpc(
+            &self.client,
+            rpc_url,
+            "getSignaturesForAddress",
+            serde_json::json!([address, { "limit": wanted }])
+        ).await?;
+
+        let mut lookups = JoinSet::new();
+        for (index, sig) in signatures.into_iter().skip(skip).take(limit as usize).enumerate() {
+            let client = self.client.clone();
+            let rpc_url = rpc_url.clone();
+            let address = address.to_string();
+            lookups.spawn(async move {
+                let detail: Option<Value> = solana_rpc(
+                    &client,
+                    &rpc_url,
+                    "getTransaction",
+                    serde_json::json!([
+                        sig.signature,
+                        { "encoding": "jsonParsed", "maxSupportedTransactionVersion": 0 },
+                    ])
+                ).await.unwrap_or(None);
+                (index, solana_transaction(sig, detail.as_ref(), &address))
+            });
+        }
+
+        let mut results = lookups.join_all().await;
+        results.sort_by_key(|(index, _)| *index);
+        Ok(
+            results
+                .into_iter()
+                .map(|(_, tx)| tx)
+                .collect()
+        )
+    }
+}
+
+async fn solana_rpc<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    method: &str,
+    params: Value
+) -> Result<T> {
+    let body = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let response: Value = client
+        .post(rpc_url)
+        .json(&body)
+        .send().await
+        .map_err(|e| AppError::Rpc(format!("Solana {} failed: {}", method, e)))?
+        .json().await
+        .map_err(|e| AppError::Rpc(format!("Failed to parse Solana {} response: {}", method, e)))?;
+
+    if let Some(error) = response.get("error") {
+        return Err(AppError::Rpc(format!("Solana {} error: {}", method, error)));
+    }
+    serde_json
+        ::from_value(response.get("result").cloned().unwrap_or(Value::Null))
+        .map_err(|e| AppError::Rpc(format!("Unexpected Solana {} result: {}", method, e)))
+}
+
+/// Build a transaction from its signature entry, reading the first SOL transfer out of the parsed
+/// instructions. Anything else (token transfers, program calls) shows as a zero-amount entry.
+fn solana_transaction(sig: SolanaSignature, detail: Option<&Value>, address: &str) -> ExplorerTransaction {
+    let transfer = detail
+        .and_then(|d| d.pointer("/transaction/message/instructions"))
+        .and_then(Value::as_array)
+        .and_then(|instructions| {
+            instructions.iter().find_map(|ix| {
+                let parsed = ix.get("parsed")?;
+                if ix.get("program")?.as_str()? != "system" || parsed.get("type")?.as_str()? != "transfer" {
+                    return None;
+                }
+                let info = parsed.get("info")?;
+                Some((
+                    info.get("source")?.as_str()?.to_string(),
+                    info.get("destination")?.as_str()?.to_string(),
+                    info.get("lamports")?.as_u64()?,
+                ))
+            })
+        });
+    let (from_address, to_address, lamports) = transfer.unwrap_or_else(|| (
+        address.to_string(),
+        String::new(),
+        0,
+    ));
+
+    ExplorerTransaction {
+        tx_hash: sig.signature,
+        chain: Chain::Solana.as_str().to_string(),
+        from_address,
+        to_address,
+        amount: ((lamports as f64) / LAMPORTS_PER_SOL).to_string(),
+        token_symbol: Some(Chain::Solana.native_symbol().to_string()),
+        status: if sig.err.is_some() { TxStatus::Failed } else { TxStatus::Confirmed },
+        block_number: Some(sig.slot as i64),
+        gas_used: detail
+            .and_then(|d| d.pointer("/meta/fee"))
+            .and_then(Value::as_u64)
+            .map(|fee| fee.to_string()),
+        timestamp: sig.block_time.and_then(|secs| DateTime::from_timestamp(secs, 0)).unwrap_or_default(),
+    }
+}
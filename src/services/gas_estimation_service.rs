@@ -2,16 +2,24 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::db::WalletRepository;
+use crate::db::entity::wallet;
 use crate::enums::Chain;
-use crate::error::Result;
-use crate::providers::GasEstimate;
+use crate::error::{ AppError, Result };
+use crate::providers::{ ChainProvider, GasEstimate };
 use crate::rpc::RpcManager;
 use crate::services::PriceService;
+use crate::services::gas_station::GasStationClient;
+
+/// Blocks until inclusion assumed for each fee tier
+const SLOW_CONFIRMATION_BLOCKS: f64 = 20.0;
+const STANDARD_CONFIRMATION_BLOCKS: f64 = 3.0;
+const FAST_CONFIRMATION_BLOCKS: f64 = 1.0;
 
 pub struct GasEstimationService {
     repository: Arc<WalletRepository>,
     rpc_manager: Arc<RpcManager>,
     price_service: Arc<PriceService>,
+    gas_station: Option<GasStationClient>,
 }
 
 impl GasEstimationService {
@@ -24,9 +32,16 @@ impl GasEstimationService {
             repository,
             rpc_manager,
             price_service,
+            gas_station: None,
         }
     }
 
+    /// Prefer an external gas station's fee predictions where it supports the chain
+    pub fn with_gas_station(mut self, gas_station: GasStationClient) -> Self {
+        self.gas_station = Some(gas_station);
+        self
+    }
+
     pub async fn estimate_transaction_fee(
         &self,
         wallet_id: Uuid,
@@ -38,7 +53,7 @@ impl GasEstimationService {
         let wallet = self.repository.find_by_id(wallet_id).await?;
 
         // Get provider for the chain
-        let provider = self.rpc_manager.get_provider_by_chain(&wallet.chain).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
 
         // Estimate gas
         let mut gas_estimate = provider.estimate_gas(
@@ -48,56 +63,176 @@ impl GasEstimationService {
             token_address
         ).await?;
 
-        // Get native token price for USD calculation
         let chain: Chain = wallet.chain.parse()?;
+        if !wallet.is_testnet {
+            self.apply_gas_station_fees(chain, &mut gas_estimate).await;
+        }
+
+        // Get native token price for USD calculation
         let native_symbol = chain.native_symbol();
 
         if let Ok(price) = self.price_service.get_price(native_symbol).await {
             let fee_native: f64 = gas_estimate.total_cost_native.parse().unwrap_or(0.0);
             gas_estimate.total_cost_usd = Some(fee_native * price.usd_price);
+
+            if let Some(l1_fee) = &gas_estimate.l1_data_fee_native {
+                let l1_fee: f64 = l1_fee.parse().unwrap_or(0.0);
+                gas_estimate.l1_data_fee_usd = Some(l1_fee * price.usd_price);
+            }
         }
 
+        let confirmation_times = self.confirmation_times(&wallet.chain, wallet.is_testnet).await;
+
         Ok(GasEstimateWithUsd {
             chain: wallet.chain,
             gas_estimate,
+            confirmation_times,
         })
     }
 
+    /// Fee for approving `spender` (a DEX router or other contract that pulls tokens) to move
+    /// the wallet's `token_address`, or `None` when it already has an allowance
+    pub async fn estimate_approval_fee(
+        &self,
+        wallet_id: Uuid,
+        token_address: &str,
+        spender: &str
+    ) -> Result<Option<GasEstimate>> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        let Some(mut gas_estimate) = provider.estimate_approval(
+            &wallet.address,
+            token_address,
+            spender
+        ).await? else {
+            return Ok(None);
+        };
+
+        let chain: Chain = wallet.chain.parse()?;
+        if !wallet.is_testnet {
+            self.apply_gas_station_fees(chain, &mut gas_estimate).await;
+        }
+
+        if let Ok(price) = self.price_service.get_price(chain.native_symbol()).await {
+            let fee_native: f64 = gas_estimate.total_cost_native.parse().unwrap_or(0.0);
+            gas_estimate.total_cost_usd = Some(fee_native * price.usd_price);
+        }
+
+        Ok(Some(gas_estimate))
+    }
+
+    /// Expected wait for each fee tier from the chain's recent block time; `None` when the
+    /// block time can't be measured
+    pub async fn confirmation_times(&self, chain: &str, testnet: bool) -> Option<ConfirmationTimes> {
+        match self.rpc_manager.get_block_time(chain, testnet).await {
+            Ok(block_time) => Some(ConfirmationTimes::from_block_time(block_time)),
+            Err(e) => {
+                tracing::warn!("Block time unavailable for {}: {}", chain, e);
+                None
+            }
+        }
+    }
+
+    /// Replace node-derived fees with the gas station's prediction, keeping the node
+    /// estimate if the gas station is unavailable
+    async fn apply_gas_station_fees(&self, chain: Chain, gas_estimate: &mut GasEstimate) {
+        let Some(gas_station) = self.gas_station.as_ref().filter(|g| g.supports(chain)) else {
+            return;
+        };
+
+        match gas_station.get_fees(chain).await {
+            Ok(fees) => {
+                let l1_data_fee: f64 = gas_estimate.l1_data_fee_native
+                    .as_deref()
+                    .and_then(|f| f.parse().ok())
+                    .unwrap_or(0.0);
+                let total_cost =
+                    (gas_estimate.estimated_gas as f64) * fees.max_fee_per_gas / 1e9 + l1_data_fee;
+                gas_estimate.max_fee_per_gas = Some(fees.max_fee_per_gas.to_string());
+                gas_estimate.max_priority_fee_per_gas = Some(
+                    fees.max_priority_fee_per_gas.to_string()
+                );
+                gas_estimate.total_cost_native = format!("{:.18}", total_cost);
+                gas_estimate.confidence_pct = fees.confidence_pct;
+            }
+            Err(e) => {
+                tracing::warn!("Gas station unavailable, using node estimate: {}", e);
+            }
+        }
+    }
+
+    /// Current mainnet gas price for a chain, in Gwei
+    pub async fn get_current_gas_price_gwei(&self, chain: &str) -> Result<f64> {
+        let provider = self.rpc_manager.get_provider_by_chain(chain).await?;
+        Self::gas_price_gwei(provider.as_ref(), chain).await
+    }
+
+    /// Current gas price on the wallet's own network and RPC, in Gwei
+    pub async fn get_wallet_gas_price_gwei(&self, wallet: &wallet::Model) -> Result<f64> {
+        let provider = self.rpc_manager.get_wallet_provider(wallet).await?;
+        Self::gas_price_gwei(provider.as_ref(), &wallet.chain).await
+    }
+
+    async fn gas_price_gwei(provider: &dyn ChainProvider, chain: &str) -> Result<f64> {
+        let parsed: Chain = chain.parse()?;
+        let dummy_addr = parsed.dummy_address();
+        let estimate = provider.estimate_gas(dummy_addr, dummy_addr, "0", None).await?;
+
+        estimate.gas_price
+            .and_then(|p| p.parse::<f64>().ok())
+            .ok_or_else(|| AppError::Chain(format!("Gas price not available for {}", chain)))
+    }
+
     pub async fn get_gas_price_recommendations(
         &self,
-        chain: &str
+        wallet_id: Uuid
     ) -> Result<GasPriceRecommendation> {
-        let provider = self.rpc_manager.get_provider_by_chain(chain).await?;
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+        let chain = wallet.chain.as_str();
 
-        // For demonstration, estimate with a dummy transaction
+        // Fee levels don't depend on the call; a zero-value self-transfer can't fail on balance
         let parsed: Chain = chain.parse()?;
         let dummy_addr = parsed.dummy_address();
-        let estimate = provider.estimate_gas(dummy_addr, dummy_addr, "0.001", None).await?;
+        let estimate = provider.estimate_gas(dummy_addr, dummy_addr, "0", None).await?;
 
         // Parse gas prices
         let base_price = estimate.gas_price.clone().unwrap_or_default();
         let max_fee = estimate.max_fee_per_gas.clone().unwrap_or_default();
         let priority_fee = estimate.max_priority_fee_per_gas.clone().unwrap_or_default();
 
+        let times = self.confirmation_times(chain, wallet.is_testnet).await;
+        let option_time = |secs: Option<f64>, fallback: &str| match secs {
+            Some(secs) => (secs, format_confirmation_time(secs)),
+            None => (0.0, fallback.to_string()),
+        };
+        let (slow_secs, slow_time) = option_time(times.as_ref().map(|t| t.slow_secs), "~5 min");
+        let (normal_secs, normal_time) = option_time(times.as_ref().map(|t| t.standard_secs), "~1 min");
+        let (fast_secs, fast_time) = option_time(times.as_ref().map(|t| t.fast_secs), "~15 sec");
+
         Ok(GasPriceRecommendation {
             chain: chain.to_string(),
             slow: GasOption {
                 gas_price: base_price.clone(),
                 max_fee_per_gas: max_fee.clone(),
                 max_priority_fee_per_gas: priority_fee.clone(),
-                estimated_time: "~5 min".to_string(),
+                estimated_time: slow_time,
+                estimated_confirmation_time_secs: slow_secs,
             },
             normal: GasOption {
                 gas_price: base_price.clone(),
                 max_fee_per_gas: max_fee.clone(),
                 max_priority_fee_per_gas: priority_fee.clone(),
-                estimated_time: "~1 min".to_string(),
+                estimated_time: normal_time,
+                estimated_confirmation_time_secs: normal_secs,
             },
             fast: GasOption {
                 gas_price: base_price,
                 max_fee_per_gas: max_fee,
                 max_priority_fee_per_gas: priority_fee,
-                estimated_time: "~15 sec".to_string(),
+                estimated_time: fast_time,
+                estimated_confirmation_time_secs: fast_secs,
             },
         })
     }
@@ -108,6 +243,39 @@ pub struct GasEstimateWithUsd {
     pub chain: String,
     #[serde(flatten)]
     pub gas_estimate: GasEstimate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_times: Option<ConfirmationTimes>,
+}
+
+/// Expected seconds until inclusion for each fee tier
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConfirmationTimes {
+    pub slow_secs: f64,
+    pub standard_secs: f64,
+    pub fast_secs: f64,
+}
+
+impl ConfirmationTimes {
+    pub fn from_block_time(block_time_secs: f64) -> Self {
+        Self {
+            slow_secs: block_time_secs * SLOW_CONFIRMATION_BLOCKS,
+            standard_secs: block_time_secs * STANDARD_CONFIRMATION_BLOCKS,
+            fast_secs: block_time_secs * FAST_CONFIRMATION_BLOCKS,
+        }
+    }
+}
+
+/// Rough human duration, e.g. "~12 sec", "~5 min", "~2 h"
+pub fn format_confirmation_time(secs: f64) -> String {
+    if secs < 1.0 {
+        "<1 sec".to_string()
+    } else if secs < 60.0 {
+        format!("~{:.0} sec", secs)
+    } else if secs < 3600.0 {
+        format!("~{:.0} min", secs / 60.0)
+    } else {
+        format!("~{:.1} h", secs / 3600.0)
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -124,4 +292,5 @@ pub struct GasOption {
     pub max_fee_per_gas: String,
     pub max_priority_fee_per_gas: String,
     pub estimated_time: String,
+    pub estimated_confirmation_time_secs: f64,
 }
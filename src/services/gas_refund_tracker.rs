@@ -0,0 +1,63 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use sea_orm::prelude::Decimal;
+use serde::Serialize;
+
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::rpc::RpcManager;
+
+/// L1 submission cost of an Arbitrum transaction and the part of it that wasn't charged
+#[derive(Debug, Clone, Serialize)]
+pub struct GasRefund {
+    pub l1_fee_paid: Decimal,
+    pub l1_refund: Decimal,
+}
+
+pub struct GasRefundTracker {
+    rpc_manager: Arc<RpcManager>,
+}
+
+impl GasRefundTracker {
+    pub fn new(rpc_manager: Arc<RpcManager>) -> Self {
+        Self { rpc_manager }
+    }
+
+    /// L1 fee breakdown for a confirmed transaction sent by `wallet_address`.
+    /// `None` for other chains and for transactions the wallet received rather than paid for.
+    pub async fn track(
+        &self,
+        chain: &str,
+        tx_hash: &str,
+        wallet_address: &str,
+        testnet: bool
+    ) -> Result<Option<GasRefund>> {
+        if chain.parse::<Chain>().ok() != Some(Chain::Arbitrum) {
+            return Ok(None);
+        }
+
+        let provider = self.rpc_manager.get_network_provider(chain, testnet).await?;
+        let Some(breakdown) = provider.l1_fee_breakdown(tx_hash).await else {
+            return Ok(None);
+        };
+        let breakdown = breakdown?;
+
+        if !breakdown.payer.eq_ignore_ascii_case(wallet_address) {
+            return Ok(None);
+        }
+
+        let parse = |value: &str| {
+            Decimal::from_str(value).map_err(|e|
+                AppError::Internal(format!("Invalid L1 fee amount {}: {}", value, e))
+            )
+        };
+
+        Ok(
+            Some(GasRefund {
+                l1_fee_paid: parse(&breakdown.l1_fee_paid)?,
+                l1_refund: parse(&breakdown.l1_refund)?,
+            })
+        )
+    }
+}
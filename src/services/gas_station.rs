@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::time::{ Duration, Instant };
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::config::GasStationProvider;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+
+const ETH_GAS_STATION_URL: &str = "https://api.ethgasstation.info/api/fee-estimate";
+const BLOCKNATIVE_URL: &str = "https://api.blocknative.com/gasprices/blockprices";
+const CACHE_DURATION_SECS: u64 = 15;
+/// Blocknative returns several confidence levels; use the cheapest one at or above this
+const TARGET_CONFIDENCE_PCT: u8 = 90;
+
+/// EIP-1559 fee suggestion from an external gas station, in Gwei
+#[derive(Debug, Clone)]
+pub struct GasStationFees {
+    pub max_fee_per_gas: f64,
+    pub max_priority_fee_per_gas: f64,
+    pub confidence_pct: Option<u8>,
+}
+
+#[derive(Deserialize)]
+struct EthGasStationResponse {
+    #[serde(rename = "nextBaseFee")]
+    next_base_fee: f64,
+    #[serde(rename = "priorityFee")]
+    priority_fee: EthGasStationPriorityFee,
+}
+
+#[derive(Deserialize)]
+struct EthGasStationPriorityFee {
+    standard: f64,
+}
+
+#[derive(Deserialize)]
+struct BlocknativeResponse {
+    #[serde(rename = "blockPrices")]
+    block_prices: Vec<BlocknativeBlockPrices>,
+}
+
+#[derive(Deserialize)]
+struct BlocknativeBlockPrices {
+    #[serde(rename = "estimatedPrices")]
+    estimated_prices: Vec<BlocknativeEstimate>,
+}
+
+#[derive(Deserialize)]
+struct BlocknativeEstimate {
+    confidence: u8,
+    #[serde(rename = "maxFeePerGas")]
+    max_fee_per_gas: f64,
+    #[serde(rename = "maxPriorityFeePerGas")]
+    max_priority_fee_per_gas: f64,
+}
+
+/// Client for ETH Gas Station / Blocknative fee predictions on Ethereum mainnet
+pub struct GasStationClient {
+    client: reqwest::Client,
+    provider: GasStationProvider,
+    api_key: Option<String>,
+    cache: RwLock<HashMap<Chain, (Instant, GasStationFees)>>,
+}
+
+impl GasStationClient {
+    pub fn new(provider: GasStationProvider, api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap(),
+            provider,
+            api_key,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Whether this client can provide fees for the chain
+    pub fn supports(&self, chain: Chain) -> bool {
+        self.provider != GasStationProvider::Node && chain == Chain::Eth
+    }
+
+    /// Current fee suggestion for the chain, cached for a few seconds
+    pub async fn get_fees(&self, chain: Chain) -> Result<GasStationFees> {
+        if !self.supports(chain) {
+            return Err(
+                AppError::InvalidInput(format!("No gas station available for {}", chain))
+            );
+        }
+
+        if let Some((fetched_at, fees)) = self.cache.read().await.get(&chain) {
+            if fetched_at.elapsed() < Duration::from_secs(CACHE_DURATION_SECS) {
+                return Ok(fees.clone());
+            }
+        }
+
+        let fees = match self.provider {
+            GasStationProvider::EthGasStation => self.fetch_eth_gas_station().await?,
+            GasStationProvider::Blocknative => self.fetch_blocknative().await?,
+            GasStationProvider::Node => unreachable!("checked by supports()"),
+        };
+
+        self.cache.write().await.insert(chain, (Instant::now(), fees.clone()));
+        Ok(fees)
+    }
+
+    async fn fetch_eth_gas_station(&self) -> Result<GasStationFees> {
+        let mut request = self.client.get(ETH_GAS_STATION_URL);
+        if let Some(key) = &self.api_key {
+            request = request.query(&[("api-key", key)]);
+        }
+
+        let data: EthGasStationResponse = request
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("ETH Gas Station request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Invalid ETH Gas Station response: {}", e)))?;
+
+        // Leave room for the base fee to double before the transaction is mined
+        let priority = data.priority_fee.standard;
+        Ok(GasStationFees {
+            max_fee_per_gas: data.next_base_fee * 2.0 + priority,
+            max_priority_fee_per_gas: priority,
+            confidence_pct: None,
+        })
+    }
+
+    async fn fetch_blocknative(&self) -> Result<GasStationFees> {
+        let api_key = self.api_key
+            .as_deref()
+            .ok_or_else(|| AppError::External("Blocknative API key not configured".to_string()))?;
+
+        let data: BlocknativeResponse = self.client
+            .get(BLOCKNATIVE_URL)
+            .query(&[("chainid", "1")])
+            .header("Authorization", api_key)
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("Blocknative request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Invalid Blocknative response: {}", e)))?;
+
+        let estimates = data.block_prices
+            .into_iter()
+            .next()
+            .map(|b| b.estimated_prices)
+            .unwrap_or_default();
+
+        let estimate = estimates
+            .iter()
+            .filter(|e| e.confidence >= TARGET_CONFIDENCE_PCT)
+            .min_by_key(|e| e.confidence)
+            .or_else(|| estimates.iter().max_by_key(|e| e.confidence))
+            .ok_or_else(|| AppError::External("Blocknative returned no estimates".to_string()))?;
+
+        Ok(GasStationFees {
+            max_fee_per_gas: estimate.max_fee_per_gas,
+            max_priority_fee_per_gas: estimate.max_priority_fee_per_gas,
+            confidence_pct: Some(estimate.confidence),
+        })
+    }
+}
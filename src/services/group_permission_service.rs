@@ -0,0 +1,126 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    DatabaseConnection,
+    EntityTrait,
+    IntoActiveModel,
+    QueryFilter,
+};
+use uuid::Uuid;
+
+use crate::db::entity::group_permission;
+use crate::enums::GroupRole;
+use crate::error::{ AppError, Result };
+
+/// Roles of group chat members over the group's shared wallets
+pub struct GroupPermissionService {
+    db: DatabaseConnection,
+}
+
+impl GroupPermissionService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Role of a member already seen in the group, refreshing their stored username.
+    /// `None` means the member hasn't been recorded yet.
+    pub async fn role_of(&self, chat_id: i64, user_id: i64, username: Option<&str>) -> Result<Option<GroupRole>> {
+        let Some(member) = self.find_member(chat_id, user_id).await? else {
+            return Ok(None);
+        };
+        let role: GroupRole = member.role.parse()?;
+
+        let username = username.map(normalize_username);
+        if member.username != username {
+            let mut active = member.into_active_model();
+            active.username = ActiveValue::Set(username);
+            active.updated_at = ActiveValue::Set(Utc::now());
+            active.update(&self.db).await?;
+        }
+
+        Ok(Some(role))
+    }
+
+    /// Record a member the first time they use the bot in the group
+    pub async fn add_member(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        username: Option<&str>,
+        role: GroupRole
+    ) -> Result<GroupRole> {
+        let now = Utc::now();
+        let member = group_permission::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            chat_id: ActiveValue::Set(chat_id),
+            user_id: ActiveValue::Set(user_id),
+            username: ActiveValue::Set(username.map(normalize_username)),
+            role: ActiveValue::Set(role.to_string()),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+        member.insert(&self.db).await?;
+
+        tracing::info!("Recorded user {} in group {} as {}", user_id, chat_id, role);
+        Ok(role)
+    }
+
+    /// Give the member known as `@username` in the group a new role. Telegram doesn't let bots
+    /// look users up by name, so they must have used the bot in the group before.
+    pub async fn set_role_by_username(
+        &self,
+        chat_id: i64,
+        username: &str,
+        role: GroupRole
+    ) -> Result<group_permission::Model> {
+        let username = normalize_username(username);
+        let mut matches = group_permission::Entity
+            ::find()
+            .filter(group_permission::Column::ChatId.eq(chat_id))
+            .filter(group_permission::Column::Username.eq(username.as_str()))
+            .all(&self.db).await?;
+        // Usernames can change hands; a stale row would grant the role to whoever had it before
+        if matches.len() > 1 {
+            return Err(
+                AppError::InvalidInput(
+                    format!("More than one member was last seen as @{}. Ask them to send /start here again first.", username)
+                )
+            );
+        }
+        let member = matches
+            .pop()
+            .ok_or_else(||
+                AppError::NotFound(
+                    format!("@{} hasn't used the bot in this group yet. Ask them to send /start here first.", username)
+                )
+            )?;
+
+        if member.role.parse::<GroupRole>()? == GroupRole::Owner {
+            return Err(AppError::InvalidInput("The group owner's role can't be changed".to_string()));
+        }
+
+        let mut active = member.into_active_model();
+        active.role = ActiveValue::Set(role.to_string());
+        active.updated_at = ActiveValue::Set(Utc::now());
+        let member = active.update(&self.db).await?;
+
+        tracing::info!("Set @{} in group {} to {}", username, chat_id, role);
+        Ok(member)
+    }
+
+    async fn find_member(&self, chat_id: i64, user_id: i64) -> Result<Option<group_permission::Model>> {
+        let member = group_permission::Entity
+            ::find()
+            .filter(group_permission::Column::ChatId.eq(chat_id))
+            .filter(group_permission::Column::UserId.eq(user_id))
+            .one(&self.db).await?;
+        Ok(member)
+    }
+}
+
+/// Telegram usernames are case-insensitive; store and compare them lowercase without the `@`
+fn normalize_username(username: &str) -> String {
+    username.trim().trim_start_matches('@').to_lowercase()
+}
@@ -0,0 +1,220 @@
+use std::collections::{ HashMap, HashSet };
+use std::sync::Arc;
+
+use ethers::prelude::*;
+use ethers::providers::{ Http, Provider };
+use ethers::utils::format_ether;
+use sea_orm::DatabaseConnection;
+use teloxide::prelude::*;
+use tokio::time::{ interval, Duration };
+
+use crate::config::{ ChainConfig, Config };
+use crate::db::entity::mempool_alert;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::services::price_alert_service::PriceAlertService;
+use crate::services::PriceService;
+
+/// How often pending-transaction filters are polled
+const MEMPOOL_POLL_INTERVAL_SECS: u64 = 5;
+
+/// Upper bound on pending transactions fetched per chain per poll, so a busy mempool
+/// can't stall the loop
+const MAX_TX_LOOKUPS_PER_POLL: usize = 500;
+
+/// Seen hashes are forgotten past this many to keep memory bounded
+const MAX_SEEN_HASHES: usize = 50_000;
+
+/// Chain and testnet flag a set of alerts is watched on
+type NetworkKey = (Chain, bool);
+
+/// Watches EVM mempools through `eth_newPendingTransactionFilter` and notifies users
+/// of large pending transfers into wallets with an active mempool alert
+pub struct MempoolWatcher {
+    alert_service: PriceAlertService,
+    price_service: Arc<PriceService>,
+    providers: HashMap<NetworkKey, Provider<Http>>,
+    filters: HashMap<NetworkKey, U256>,
+    seen: HashSet<H256>,
+}
+
+impl MempoolWatcher {
+    pub fn new(db: DatabaseConnection, price_service: Arc<PriceService>, config: &Config) -> Self {
+        let mut providers = HashMap::new();
+        let networks = [
+            (&config.chain_configs, config.is_testnet()),
+            (&config.testnet_chain_configs, true),
+        ];
+
+        for (configs, is_testnet) in networks {
+            for chain_config in configs.values().filter(|cc| cc.chain.is_evm()) {
+                match Self::build_provider(chain_config) {
+                    Ok(provider) => {
+                        providers.insert((chain_config.chain, is_testnet), provider);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Mempool watcher skipping {}: {}", chain_config.chain, e);
+                    }
+                }
+            }
+        }
+
+        Self {
+            alert_service: PriceAlertService::new(db),
+            price_service,
+            providers,
+            filters: HashMap::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    fn build_provider(chain_config: &ChainConfig) -> Result<Provider<Http>> {
+        let url = chain_config.rpc_urls
+            .first()
+            .ok_or_else(|| AppError::Config(format!("No RPC URL for {}", chain_config.chain)))?;
+        Provider::<Http>
+            ::try_from(url.as_str())
+            .map_err(|e| AppError::Rpc(format!("Failed to create provider: {}", e)))
+    }
+
+    /// Poll pending transactions every few seconds until the process exits
+    pub async fn run(mut self, bot: Bot) {
+        let mut interval = interval(Duration::from_secs(MEMPOOL_POLL_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.poll(&bot).await {
+                tracing::warn!("Mempool watcher error: {}", e);
+            }
+        }
+    }
+
+    async fn poll(&mut self, bot: &Bot) -> Result<()> {
+        let alerts = self.alert_service.get_active_mempool_alerts().await?;
+
+        let mut by_network: HashMap<NetworkKey, HashMap<String, Vec<mempool_alert::Model>>> =
+            HashMap::new();
+        for alert in alerts {
+            let Ok(chain) = alert.chain.parse::<Chain>() else {
+                continue;
+            };
+            by_network
+                .entry((chain, alert.is_testnet))
+                .or_default()
+                .entry(alert.address.to_lowercase())
+                .or_default()
+                .push(alert);
+        }
+
+        // Networks nobody watches any more don't need their filter kept alive
+        let stale: Vec<NetworkKey> = self.filters
+            .keys()
+            .filter(|key| !by_network.contains_key(key))
+            .copied()
+            .collect();
+        for key in stale {
+            if let (Some(id), Some(provider)) = (self.filters.remove(&key), self.providers.get(&key)) {
+                let _ = provider.uninstall_filter(id).await;
+            }
+        }
+
+        for (key, watched) in by_network {
+            if let Err(e) = self.poll_network(bot, key, &watched).await {
+                // The node may have dropped the filter; install a fresh one next poll
+                self.filters.remove(&key);
+                tracing::warn!("Mempool poll failed on {}: {}", key.0, e);
+            }
+        }
+
+        if self.seen.len() > MAX_SEEN_HASHES {
+            self.seen.clear();
+        }
+
+        Ok(())
+    }
+
+    async fn poll_network(
+        &mut self,
+        bot: &Bot,
+        key: NetworkKey,
+        watched: &HashMap<String, Vec<mempool_alert::Model>>
+    ) -> Result<()> {
+        let Some(provider) = self.providers.get(&key) else {
+            return Ok(());
+        };
+
+        let filter_id = match self.filters.get(&key) {
+            Some(id) => *id,
+            None => {
+                let id = provider
+                    .new_filter(FilterKind::PendingTransactions).await
+                    .map_err(|e| AppError::Rpc(format!("Failed to install pending filter: {}", e)))?;
+                self.filters.insert(key, id);
+                // A new filter only reports transactions seen from now on
+                return Ok(());
+            }
+        };
+
+        let hashes: Vec<H256> = provider
+            .get_filter_changes(filter_id).await
+            .map_err(|e| AppError::Rpc(format!("Failed to read pending filter: {}", e)))?;
+
+        let (chain, _) = key;
+        let symbol = chain.native_symbol();
+        let mut native_price: Option<f64> = None;
+
+        for hash in hashes.into_iter().take(MAX_TX_LOOKUPS_PER_POLL) {
+            if !self.seen.insert(hash) {
+                continue;
+            }
+
+            let Ok(Some(tx)) = provider.get_transaction(hash).await else {
+                continue;
+            };
+            let Some(to) = tx.to else {
+                continue;
+            };
+            let Some(alerts) = watched.get(&format!("{:?}", to)) else {
+                continue;
+            };
+            if tx.value.is_zero() {
+                continue;
+            }
+
+            let price = match native_price {
+                Some(p) => p,
+                None => {
+                    let p = self.price_service.get_price(symbol).await?.usd_price;
+                    native_price = Some(p);
+                    p
+                }
+            };
+            let amount: f64 = format_ether(tx.value).parse().unwrap_or(0.0);
+            let usd_value = amount * price;
+
+            for alert in alerts.iter().filter(|a| usd_value > a.threshold_usd) {
+                let message = format!(
+                    "📨 Incoming: ~${:.2} {} pending from {}",
+                    usd_value,
+                    symbol,
+                    short_address(&format!("{:?}", tx.from))
+                );
+
+                if let Ok(chat_id) = alert.user_id.parse::<i64>() {
+                    let _ = bot.send_message(ChatId(chat_id), message).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn short_address(address: &str) -> String {
+    if address.len() > 16 {
+        format!("{}...{}", &address[..8], &address[address.len() - 6..])
+    } else {
+        address.to_string()
+    }
+}
@@ -1,24 +1,75 @@
 pub mod wallet_service;
 pub mod balance_service;
+pub mod balance_watcher;
+pub mod block_service;
+pub mod broadcast_service;
 pub mod transfer_service;
 pub mod transaction_service;
 pub mod price_service;
+pub mod coingecko;
+pub mod connectivity_checker;
 pub mod portfolio_service;
+pub mod portfolio_card;
 pub mod address_book_service;
 pub mod gas_estimation_service;
+pub mod gas_station;
+pub mod gas_refund_tracker;
+pub mod group_permission_service;
 pub mod scheduling_service;
 pub mod price_alert_service;
+pub mod mempool_watcher;
+pub mod network_congestion;
+pub mod nft_service;
+pub mod onchain_price_oracle;
+pub mod polygon_bridge_service;
+pub mod rebalancing_service;
+pub mod recent_transaction_cache;
 pub mod security_service;
+pub mod dca_service;
+pub mod defi_protocol_service;
+pub mod explorer_service;
 pub mod swap_service;
+pub mod tax_report_service;
 pub mod token_discovery_service;
+pub mod solana_token_discovery;
+pub mod token_approval_service;
+pub mod token_security_service;
+pub mod token_list_service;
+pub mod transaction_simulator;
+pub mod user_preference_service;
+pub mod user_session_service;
+pub mod cross_chain_balance_service;
 
 pub use wallet_service::WalletService;
 pub use balance_service::BalanceService;
+pub use balance_watcher::BalanceWatcher;
+pub use block_service::BlockService;
+pub use broadcast_service::BroadcastService;
 pub use transfer_service::TransferService;
-pub use transaction_service::TransactionService;
+pub use transaction_service::{ FeeSummary, TransactionService };
 pub use price_service::PriceService;
+pub use connectivity_checker::ConnectivityChecker;
 pub use portfolio_service::PortfolioService;
 pub use address_book_service::AddressBookService;
 pub use gas_estimation_service::GasEstimationService;
+pub use gas_refund_tracker::GasRefundTracker;
+pub use group_permission_service::GroupPermissionService;
 pub use swap_service::SwapService;
 pub use token_discovery_service::TokenDiscoveryService;
+pub use token_approval_service::TokenApprovalService;
+pub use token_security_service::TokenSecurityService;
+pub use token_list_service::TokenListService;
+pub use tax_report_service::TaxReportService;
+pub use dca_service::DcaService;
+pub use defi_protocol_service::DefiProtocolService;
+pub use explorer_service::ExplorerService;
+pub use transaction_simulator::TransactionSimulator;
+pub use mempool_watcher::MempoolWatcher;
+pub use network_congestion::NetworkCongestionService;
+pub use nft_service::NftService;
+pub use onchain_price_oracle::OnChainPriceOracle;
+pub use polygon_bridge_service::PolygonBridgeService;
+pub use recent_transaction_cache::RecentTransactionCache;
+pub use user_preference_service::UserPreferenceService;
+pub use user_session_service::UserSessionService;
+pub use cross_chain_balance_service::CrossChainBalanceService;
@@ -0,0 +1,297 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{ Duration, Instant };
+
+use chrono::{ DateTime, Utc };
+use dashmap::DashMap;
+use futures::{ stream, StreamExt, TryStreamExt };
+use serde::Serialize;
+use tokio::time::interval;
+
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::providers::{ ChainProvider, NetworkLoad };
+use crate::rpc::RpcManager;
+
+/// How often each chain's fee market is sampled
+const POLL_INTERVAL_SECS: u64 = 30;
+
+/// Readings kept per chain; 30 minutes at the poll interval
+const MAX_READINGS: usize = 60;
+
+/// Hourly base fee averages kept per chain; one week
+const MAX_HOURLY_AVERAGES: usize = 24 * 7;
+
+/// Blocks of fee history averaged for each hour of the week seeded at startup
+const SEED_BLOCKS_PER_HOUR: u64 = 16;
+
+/// Fee history requests in flight while seeding a chain's week
+const SEED_CONCURRENCY: usize = 8;
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionLevel {
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl CongestionLevel {
+    /// Level for the current base fee relative to its 7-day average
+    pub fn from_ratio(ratio: f64) -> Self {
+        if ratio < 0.9 {
+            Self::Low
+        } else if ratio < 1.3 {
+            Self::Moderate
+        } else if ratio < 2.0 {
+            Self::High
+        } else {
+            Self::Critical
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Moderate => "Moderate",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BaseFeeReading {
+    pub at: DateTime<Utc>,
+    pub base_fee_gwei: f64,
+    pub gas_price_gwei: f64,
+    pub priority_fee_gwei: Option<f64>,
+    pub pending_tx_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CongestionReport {
+    pub chain: Chain,
+    pub level: CongestionLevel,
+    pub base_fee_gwei: f64,
+    /// Average base fee the level is rated against
+    pub base_fee_avg_gwei: f64,
+    /// Hours the average covers; 168 once a full week is known
+    pub average_window_hours: usize,
+    pub priority_fee_gwei: Option<f64>,
+    pub pending_tx_count: Option<u64>,
+    /// Recent readings, oldest first
+    pub history: Vec<BaseFeeReading>,
+}
+
+#[derive(Default)]
+struct ChainHistory {
+    readings: VecDeque<BaseFeeReading>,
+    hourly_averages: VecDeque<f64>,
+    hour_sum: f64,
+    hour_count: u32,
+    hour_started: Option<Instant>,
+    /// Whether the past week has been loaded from fee history (successfully or not)
+    seed_attempted: bool,
+}
+
+impl ChainHistory {
+    fn record(&mut self, reading: BaseFeeReading) {
+        let started = *self.hour_started.get_or_insert_with(Instant::now);
+        if started.elapsed() >= HOUR && self.hour_count > 0 {
+            if self.hourly_averages.len() == MAX_HOURLY_AVERAGES {
+                self.hourly_averages.pop_front();
+            }
+            self.hourly_averages.push_back(self.hour_sum / (self.hour_count as f64));
+            self.hour_sum = 0.0;
+            self.hour_count = 0;
+            self.hour_started = Some(Instant::now());
+        }
+        self.hour_sum += reading.base_fee_gwei;
+        self.hour_count += 1;
+
+        if self.readings.len() == MAX_READINGS {
+            self.readings.pop_front();
+        }
+        self.readings.push_back(reading);
+    }
+
+    /// Average base fee over the recorded hours (up to a week) and how many hours that is.
+    /// Before the first full hour the running hour stands in as a one-hour window.
+    fn average(&self) -> Option<(f64, usize)> {
+        if self.hourly_averages.is_empty() {
+            return (self.hour_count > 0).then(|| (self.hour_sum / (self.hour_count as f64), 1));
+        }
+        let hours = self.hourly_averages.len();
+        Some((self.hourly_averages.iter().sum::<f64>() / (hours as f64), hours))
+    }
+}
+
+/// Samples gas prices and mempool size of every configured EVM chain and rates how
+/// congested each one is compared to its last week
+pub struct NetworkCongestionService {
+    rpc_manager: Arc<RpcManager>,
+    history: DashMap<Chain, ChainHistory>,
+}
+
+impl NetworkCongestionService {
+    pub fn new(rpc_manager: Arc<RpcManager>) -> Self {
+        Self {
+            rpc_manager,
+            history: DashMap::new(),
+        }
+    }
+
+    /// Poll every configured EVM chain every 30 seconds
+    pub async fn run(self: Arc<Self>) {
+        let mut interval = interval(Duration::from_secs(POLL_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            for chain in self.rpc_manager.get_configured_chains() {
+                if !chain.is_evm() {
+                    continue;
+                }
+                if let Err(e) = self.sample(chain).await {
+                    tracing::debug!("Congestion sample for {} failed: {}", chain, e);
+                }
+            }
+        }
+    }
+
+    async fn sample(&self, chain: Chain) -> Result<()> {
+        let provider = self.rpc_manager.get_provider_by_chain(chain.as_str()).await?;
+        let NetworkLoad { gas_price_gwei, base_fee_gwei, priority_fee_gwei, pending_tx_count } =
+            provider.get_network_load().await?;
+
+        // Only EIP-1559 chains have a base fee history to seed from; the others build
+        // their week from live gas price samples
+        let needs_seed = base_fee_gwei.is_some() &&
+            !self.history.get(&chain).is_some_and(|h| h.seed_attempted);
+        let seeded_week = if needs_seed {
+            match self.seed_week(chain, provider.as_ref()).await {
+                Ok(week) => Some(week),
+                Err(e) => {
+                    tracing::debug!("Could not seed a week of base fees for {}: {}", chain, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut history = self.history.entry(chain).or_default();
+        if needs_seed {
+            history.seed_attempted = true;
+        }
+        if let Some(week) = seeded_week {
+            history.hourly_averages = week;
+        }
+        history.record(BaseFeeReading {
+            at: Utc::now(),
+            // Chains without EIP-1559 only have a gas price to go by
+            base_fee_gwei: base_fee_gwei.unwrap_or(gas_price_gwei),
+            gas_price_gwei,
+            priority_fee_gwei,
+            pending_tx_count,
+        });
+        Ok(())
+    }
+
+    /// Hourly base fee averages for the week before now, oldest first, sampled from fee
+    /// history one slice of blocks per hour. Fails unless the whole week is available.
+    async fn seed_week(&self, chain: Chain, provider: &dyn ChainProvider) -> Result<VecDeque<f64>> {
+        let block_time = self.rpc_manager.get_block_time(chain.as_str(), false).await?;
+        let blocks_per_hour = ((HOUR.as_secs_f64() / block_time) as u64).max(1);
+        let latest = provider.get_block_number().await?;
+        let week_blocks = blocks_per_hour * (MAX_HOURLY_AVERAGES as u64);
+        if latest < week_blocks {
+            return Err(AppError::Chain(format!("{} has less than a week of blocks", chain)));
+        }
+
+        let hours_ago = (1..=MAX_HOURLY_AVERAGES as u64).rev();
+        stream::iter(hours_ago)
+            .map(|hours| async move {
+                let fees = provider.get_base_fee_history(SEED_BLOCKS_PER_HOUR, latest - hours * blocks_per_hour).await?;
+                if fees.is_empty() {
+                    return Err(AppError::Chain("Empty fee history".to_string()));
+                }
+                Ok(fees.iter().sum::<f64>() / (fees.len() as f64))
+            })
+            .buffered(SEED_CONCURRENCY)
+            .try_collect().await
+    }
+
+    /// Latest congestion of `chain`, or `None` before its first sample
+    pub fn report(&self, chain: Chain) -> Option<CongestionReport> {
+        let history = self.history.get(&chain)?;
+        let latest = history.readings.back()?;
+        let (average, hours) = history.average().filter(|(avg, _)| *avg > 0.0)?;
+
+        Some(CongestionReport {
+            chain,
+            level: CongestionLevel::from_ratio(latest.base_fee_gwei / average),
+            base_fee_gwei: latest.base_fee_gwei,
+            base_fee_avg_gwei: average,
+            average_window_hours: hours,
+            priority_fee_gwei: latest.priority_fee_gwei,
+            pending_tx_count: latest.pending_tx_count,
+            history: history.readings.iter().cloned().collect(),
+        })
+    }
+
+    /// Report for a chain given by any alias, failing when it hasn't been sampled yet
+    pub fn report_for(&self, chain: &str) -> Result<CongestionReport> {
+        let chain: Chain = chain.parse()?;
+        if !chain.is_evm() {
+            return Err(AppError::InvalidInput(format!("Congestion is only tracked on EVM chains, not {}", chain)));
+        }
+        self.report(chain).ok_or_else(||
+            AppError::NotFound(format!("No congestion data for {} yet", chain))
+        )
+    }
+
+    /// Warning for confirmation screens when `chain` is highly congested; names the
+    /// comparison window while less than a week of fees is known
+    pub fn congestion_warning(&self, chain: Chain) -> Option<String> {
+        let report = self.report(chain)?;
+        matches!(report.level, CongestionLevel::High | CongestionLevel::Critical).then(|| {
+            let level = if report.average_window_hours < MAX_HOURLY_AVERAGES {
+                format!("{} vs. the last {}h", report.level.label(), report.average_window_hours)
+            } else {
+                report.level.label().to_string()
+            };
+            format!("⚠️ {} network is congested ({}). Consider waiting or paying higher fees.", chain, level)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_ratio() {
+        assert_eq!(CongestionLevel::from_ratio(0.5), CongestionLevel::Low);
+        assert_eq!(CongestionLevel::from_ratio(1.0), CongestionLevel::Moderate);
+        assert_eq!(CongestionLevel::from_ratio(1.5), CongestionLevel::High);
+        assert_eq!(CongestionLevel::from_ratio(3.0), CongestionLevel::Critical);
+    }
+
+    #[test]
+    fn test_average_window() {
+        let mut history = ChainHistory::default();
+        assert_eq!(history.average(), None);
+
+        history.hour_sum = 30.0;
+        history.hour_count = 3;
+        assert_eq!(history.average(), Some((10.0, 1)));
+
+        history.hourly_averages = VecDeque::from(vec![10.0, 20.0]);
+        assert_eq!(history.average(), Some((15.0, 2)));
+    }
+}
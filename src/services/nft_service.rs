@@ -0,0 +1,139 @@
+use serde::{ Deserialize, Serialize };
+
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+
+/// Alchemy returns at most this many NFTs per page
+const NFT_PAGE_SIZE: u32 = 100;
+
+/// An ERC-721 / ERC-1155 token held by a wallet
+#[derive(Debug, Clone, Serialize)]
+pub struct NftItem {
+    pub contract_address: String,
+    pub token_id: String,
+    pub name: Option<String>,
+    pub collection_name: Option<String>,
+    pub image_url: Option<String>,
+    /// OpenSea collection floor, in ETH
+    pub floor_price_eth: Option<f64>,
+}
+
+// ── Alchemy NFT API v3 response types ──────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct AlchemyNftsResponse {
+    #[serde(rename = "ownedNfts", default)]
+    owned_nfts: Vec<AlchemyNft>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlchemyNft {
+    contract: AlchemyNftContract,
+    #[serde(rename = "tokenId")]
+    token_id: String,
+    name: Option<String>,
+    image: Option<AlchemyNftImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlchemyNftContract {
+    address: String,
+    name: Option<String>,
+    #[serde(rename = "openSeaMetadata")]
+    open_sea_metadata: Option<AlchemyOpenSeaMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlchemyOpenSeaMetadata {
+    #[serde(rename = "floorPrice")]
+    floor_price: Option<f64>,
+    #[serde(rename = "collectionName")]
+    collection_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlchemyNftImage {
+    #[serde(rename = "cachedUrl")]
+    cached_url: Option<String>,
+    #[serde(rename = "originalUrl")]
+    original_url: Option<String>,
+}
+
+/// Lists NFTs owned by EVM wallets through Alchemy's NFT API
+pub struct NftService {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+impl NftService {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client
+                ::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            api_key,
+        }
+    }
+
+    /// Whether an Alchemy API key is configured
+    pub fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Whether Alchemy indexes NFTs on `chain`
+    pub fn is_supported(chain: &Chain) -> bool {
+        chain.is_evm() && chain.alchemy_network_name(false).is_some()
+    }
+
+    /// NFTs held by `address` on mainnet `chain` (a `Chain::as_str()` value)
+    pub async fn get_nfts_for_wallet(&self, chain: &str, address: &str) -> Result<Vec<NftItem>> {
+        let api_key = self.api_key
+            .as_ref()
+            .ok_or_else(|| AppError::Config("ALCHEMY_API_KEY not set".to_string()))?;
+
+        let parsed: Chain = chain.parse()?;
+        let network = parsed
+            .alchemy_network_name(false)
+            .filter(|_| parsed.is_evm())
+            .ok_or_else(|| AppError::InvalidInput(format!("NFTs are not available on {}", chain)))?;
+
+        let url = format!("https://{}.g.alchemy.com/nft/v3/{}/getNFTsForOwner", network, api_key);
+        let page_size = NFT_PAGE_SIZE.to_string();
+        let response: AlchemyNftsResponse = self.client
+            .get(&url)
+            .query(
+                &[
+                    ("owner", address),
+                    ("withMetadata", "true"),
+                    ("pageSize", page_size.as_str()),
+                ]
+            )
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("Alchemy NFT request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse Alchemy NFT response: {}", e)))?;
+
+        Ok(
+            response.owned_nfts
+                .into_iter()
+                .map(|nft| {
+                    let open_sea = nft.contract.open_sea_metadata;
+                    NftItem {
+                        contract_address: nft.contract.address,
+                        token_id: nft.token_id,
+                        name: nft.name,
+                        collection_name: open_sea
+                            .as_ref()
+                            .and_then(|m| m.collection_name.clone())
+                            .or(nft.contract.name),
+                        image_url: nft.image.and_then(|i| i.cached_url.or(i.original_url)),
+                        floor_price_eth: open_sea.and_then(|m| m.floor_price),
+                    }
+                })
+                .collect()
+        )
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use ethers::abi::{ ParamType, Token };
+use ethers::providers::{ Http, Middleware, Provider };
+use ethers::types::{ transaction::eip2718::TypedTransaction, Address, Bytes, TransactionRequest, U256 };
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::Config;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+
+/// Feed registry, keyed by chain and token address (the zero address for native tokens)
+const FEED_REGISTRY: &str = include_str!("chainlink_feeds.json");
+
+/// `latestRoundData()` selector on Chainlink's AggregatorV3Interface
+const LATEST_ROUND_DATA_SELECTOR: [u8; 4] = [0xfe, 0xaf, 0x96, 0x8c];
+
+/// Chainlink USD feeds report 8 decimals
+const CHAINLINK_USD_DECIMALS: i32 = 8;
+
+/// Answers older than this are treated as stale; the slowest USD feeds heartbeat daily
+const MAX_FEED_AGE_SECS: u64 = 25 * 60 * 60;
+
+/// Pyth price account layout (v2)
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+const PYTH_EXPONENT_OFFSET: usize = 20;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+#[derive(Deserialize)]
+struct FeedEntry {
+    chain: String,
+    symbol: String,
+    token_address: String,
+    feed: String,
+}
+
+/// Reads USD prices straight from on-chain oracles: Chainlink feeds on EVM chains and
+/// Pyth price accounts on Solana. Used when the price APIs are unreachable.
+pub struct OnChainPriceOracle {
+    feeds: HashMap<(Chain, String), String>,
+    /// First registered token per symbol, for symbol-based lookups
+    by_symbol: HashMap<String, (Chain, String)>,
+    evm_providers: HashMap<Chain, Arc<Provider<Http>>>,
+    solana: Option<Arc<RpcClient>>,
+}
+
+impl OnChainPriceOracle {
+    /// Oracles live on mainnet; with testnet chain configs the oracle has no providers
+    pub fn new(config: &Config) -> Self {
+        let mut feeds = HashMap::new();
+        let mut by_symbol = HashMap::new();
+        let entries: Vec<FeedEntry> = serde_json
+            ::from_str(FEED_REGISTRY)
+            .expect("chainlink_feeds.json is valid");
+        for entry in entries {
+            let Ok(chain) = entry.chain.parse::<Chain>() else {
+                tracing::warn!("Skipping oracle feed on unknown chain {}", entry.chain);
+                continue;
+            };
+            let key = (chain, normalize_address(chain, &entry.token_address));
+            by_symbol.entry(entry.symbol.to_uppercase()).or_insert_with(|| key.clone());
+            feeds.insert(key, entry.feed);
+        }
+
+        let mut evm_providers = HashMap::new();
+        let mut solana = None;
+        if !config.is_testnet() {
+            for chain_config in config.chain_configs.values() {
+                let Some(url) = chain_config.rpc_urls.first() else {
+                    continue;
+                };
+                if chain_config.chain == Chain::Solana {
+                    solana = Some(Arc::new(RpcClient::new(url.clone())));
+                } else if chain_config.chain.is_evm() {
+                    match Provider::<Http>::try_from(url.as_str()) {
+                        Ok(provider) => {
+                            evm_providers.insert(chain_config.chain, Arc::new(provider));
+                        }
+                        Err(e) => {
+                            tracing::warn!("Price oracle skipping {}: {}", chain_config.chain, e);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self { feeds, by_symbol, evm_providers, solana }
+    }
+
+    /// USD price of the token at `token_address` on `chain` (a `Chain::as_str()` value)
+    pub async fn get_price(&self, chain: &str, token_address: &str) -> Result<f64> {
+        let chain: Chain = chain.parse()?;
+        let feed = self.feeds
+            .get(&(chain, normalize_address(chain, token_address)))
+            .ok_or_else(|| {
+                AppError::NotFound(format!("No oracle feed for {} on {}", token_address, chain))
+            })?;
+
+        self.read_feed(chain, feed).await
+    }
+
+    /// USD price of a registered token by symbol (ETH, BNB, SOL, ...)
+    pub async fn get_price_by_symbol(&self, symbol: &str) -> Result<f64> {
+        let (chain, token_address) = self.by_symbol
+            .get(&symbol.to_uppercase())
+            .ok_or_else(|| AppError::NotFound(format!("No oracle feed for {}", symbol)))?;
+
+        self.read_feed(*chain, &self.feeds[&(*chain, token_address.clone())]).await
+    }
+
+    async fn read_feed(&self, chain: Chain, feed: &str) -> Result<f64> {
+        if chain == Chain::Solana {
+            self.pyth_price(feed).await
+        } else {
+            self.chainlink_price(chain, feed).await
+        }
+    }
+
+    async fn chainlink_price(&self, chain: Chain, feed: &str) -> Result<f64> {
+        let provider = self.evm_providers
+            .get(&chain)
+            .ok_or_else(|| AppError::Config(format!("No RPC provider for {} price feeds", chain)))?;
+        let feed: Address = feed
+            .parse()
+            .map_err(|_| AppError::Config(format!("Invalid Chainlink feed address {}", feed)))?;
+
+        let call: TypedTransaction = TransactionRequest::new()
+            .to(feed)
+            .data(Bytes::from(LATEST_ROUND_DATA_SELECTOR.to_vec()))
+            .into();
+        let output = provider
+            .call(&call, None).await
+            .map_err(|e| AppError::Rpc(format!("Chainlink latestRoundData failed: {}", e)))?;
+
+        let decoded = ethers::abi
+            ::decode(
+                &[
+                    ParamType::Uint(80),
+                    ParamType::Int(256),
+                    ParamType::Uint(256),
+                    ParamType::Uint(256),
+                    ParamType::Uint(80),
+                ],
+                &output
+            )
+            .map_err(|e| AppError::Chain(format!("Failed to decode latestRoundData: {}", e)))?;
+
+        let (Some(Token::Int(answer)), Some(Token::Uint(updated_at))) = (
+            decoded.get(1).cloned(),
+            decoded.get(3).cloned(),
+        ) else {
+            return Err(AppError::Chain("Unexpected latestRoundData output".to_string()));
+        };
+
+        // int256 arrives as two's complement; a non-positive price means a broken feed
+        if answer.is_zero() || answer.bit(255) {
+            return Err(AppError::Chain(format!("Chainlink feed {:?} returned no price", feed)));
+        }
+        check_freshness(updated_at.as_u64())?;
+
+        Ok(scale(u256_to_f64(answer), -CHAINLINK_USD_DECIMALS))
+    }
+
+    async fn pyth_price(&self, account: &str) -> Result<f64> {
+        let client = self.solana
+            .as_ref()
+            .ok_or_else(|| AppError::Config("No Solana RPC for Pyth price feeds".to_string()))?;
+        let account = Pubkey::from_str(account).map_err(|_| {
+            AppError::Config(format!("Invalid Pyth price account {}", account))
+        })?;
+
+        let data = client
+            .get_account_data(&account).await
+            .map_err(|e| AppError::Rpc(format!("Failed to read Pyth price account: {}", e)))?;
+
+        let read_u32 = |offset: usize| {
+            data.get(offset..offset + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        };
+        if read_u32(0) != Some(PYTH_MAGIC) {
+            return Err(AppError::Chain("Not a Pyth price account".to_string()));
+        }
+        if read_u32(PYTH_AGG_STATUS_OFFSET) != Some(PYTH_STATUS_TRADING) {
+            return Err(AppError::Chain("Pyth price is not currently trading".to_string()));
+        }
+
+        let exponent = read_u32(PYTH_EXPONENT_OFFSET).map(|e| e as i32);
+        let price = data
+            .get(PYTH_AGG_PRICE_OFFSET..PYTH_AGG_PRICE_OFFSET + 8)
+            .map(|b| i64::from_le_bytes(b.try_into().unwrap()));
+        match (price, exponent) {
+            (Some(price), Some(exponent)) if price > 0 => Ok(scale(price as f64, exponent)),
+            _ => Err(AppError::Chain("Pyth price account has no price".to_string())),
+        }
+    }
+}
+
+/// EVM addresses are case-insensitive; Solana mints are not
+fn normalize_address(chain: Chain, address: &str) -> String {
+    if chain.is_evm() { address.to_lowercase() } else { address.to_string() }
+}
+
+fn check_freshness(updated_at: u64) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if now.saturating_sub(updated_at) > MAX_FEED_AGE_SECS {
+        return Err(AppError::Chain("Chainlink price is stale".to_string()));
+    }
+    Ok(())
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(0.0)
+}
+
+fn scale(value: f64, exponent: i32) -> f64 {
+    value * (10_f64).powi(exponent)
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::time::{ Duration, Instant, SystemTime, UNIX_EPOCH };
+use serde::{ Deserialize, Serialize };
+use tokio::sync::RwLock;
+
+use crate::error::{ AppError, Result };
+
+const BRIDGE_API_URL: &str = "https://bridge-api.polygon.technology/v1/transactions";
+const CACHE_DURATION_SECS: u64 = 30;
+/// Typical end-to-end time for a PoS bridge deposit (state sync to Polygon)
+const DEPOSIT_DURATION_SECS: u64 = 8 * 60;
+/// Typical end-to-end time for a PoS bridge withdrawal (checkpoint to Ethereum)
+const WITHDRAWAL_DURATION_SECS: u64 = 3 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgeDirection {
+    /// Ethereum to Polygon
+    Deposit,
+    /// Polygon to Ethereum
+    Withdrawal,
+}
+
+impl BridgeDirection {
+    /// Network the funds are moving to, e.g. "MATIC" for deposits
+    pub fn destination(&self) -> &'static str {
+        match self {
+            BridgeDirection::Deposit => "MATIC",
+            BridgeDirection::Withdrawal => "ETH",
+        }
+    }
+
+    fn typical_duration_secs(&self) -> u64 {
+        match self {
+            BridgeDirection::Deposit => DEPOSIT_DURATION_SECS,
+            BridgeDirection::Withdrawal => WITHDRAWAL_DURATION_SECS,
+        }
+    }
+}
+
+/// A PoS bridge transfer for an address
+#[derive(Debug, Clone, Serialize)]
+pub struct BridgeTx {
+    pub tx_hash: String,
+    pub amount: String,
+    pub token: String,
+    pub direction: BridgeDirection,
+    pub status: String,
+    /// Estimated seconds until the transfer lands; `None` once it has completed
+    pub eta_secs: Option<u64>,
+}
+
+impl BridgeTx {
+    pub fn is_pending(&self) -> bool {
+        self.eta_secs.is_some()
+    }
+}
+
+#[derive(Deserialize)]
+struct BridgeApiResponse {
+    #[serde(default)]
+    result: Vec<BridgeApiTx>,
+}
+
+#[derive(Deserialize)]
+struct BridgeApiTx {
+    #[serde(rename = "transactionHash")]
+    tx_hash: String,
+    #[serde(rename = "transactionType")]
+    transaction_type: String,
+    status: String,
+    amount: String,
+    #[serde(rename = "tokenSymbol")]
+    token_symbol: String,
+    /// Unix seconds the bridge transfer was initiated
+    timestamp: Option<u64>,
+}
+
+/// Tracks deposits and withdrawals through the Polygon PoS bridge
+pub struct PolygonBridgeService {
+    client: reqwest::Client,
+    /// Keyed by lower-cased Ethereum address
+    cache: RwLock<HashMap<String, (Instant, Vec<BridgeTx>)>>,
+}
+
+impl PolygonBridgeService {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap(),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Ethereum to Polygon transfers from `eth_address` that haven't arrived yet
+    pub async fn get_pending_deposits(&self, eth_address: &str) -> Result<Vec<BridgeTx>> {
+        self.get_pending(eth_address, BridgeDirection::Deposit).await
+    }
+
+    /// Polygon to Ethereum transfers from `eth_address` that haven't been exited yet
+    pub async fn get_pending_withdrawals(&self, eth_address: &str) -> Result<Vec<BridgeTx>> {
+        self.get_pending(eth_address, BridgeDirection::Withdrawal).await
+    }
+
+    async fn get_pending(&self, eth_address: &str, direction: BridgeDirection) -> Result<Vec<BridgeTx>> {
+        Ok(
+            self
+                .get_transactions(eth_address).await?
+                .into_iter()
+                .filter(|tx| tx.direction == direction && tx.is_pending())
+                .collect()
+        )
+    }
+
+    async fn get_transactions(&self, eth_address: &str) -> Result<Vec<BridgeTx>> {
+        let key = eth_address.to_lowercase();
+        if let Some((fetched_at, txs)) = self.cache.read().await.get(&key) {
+            if fetched_at.elapsed() < Duration::from_secs(CACHE_DURATION_SECS) {
+                return Ok(txs.clone());
+            }
+        }
+
+        let data: BridgeApiResponse = self.client
+            .get(BRIDGE_API_URL)
+            .query(&[("userAddress", eth_address)])
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("Polygon bridge API error: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse Polygon bridge response: {}", e)))?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let txs: Vec<BridgeTx> = data.result
+            .into_iter()
+            .filter_map(|tx| to_bridge_tx(tx, now))
+            .collect();
+
+        self.cache.write().await.insert(key, (Instant::now(), txs.clone()));
+        Ok(txs)
+    }
+}
+
+impl Default for PolygonBridgeService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_bridge_tx(tx: BridgeApiTx, now: u64) -> Option<BridgeTx> {
+    let direction = match tx.transaction_type.to_uppercase().as_str() {
+        "DEPOSIT" => BridgeDirection::Deposit,
+        "WITHDRAW" | "WITHDRAWAL" => BridgeDirection::Withdrawal,
+        _ => {
+            return None;
+        }
+    };
+
+    let completed = matches!(
+        tx.status.to_uppercase().as_str(),
+        "CLAIMED" | "COMPLETED" | "EXITED" | "FAILED"
+    );
+    let eta_secs = if completed {
+        None
+    } else {
+        let elapsed = tx.timestamp.map(|t| now.saturating_sub(t)).unwrap_or(0);
+        Some(direction.typical_duration_secs().saturating_sub(elapsed))
+    };
+
+    Some(BridgeTx {
+        tx_hash: tx.tx_hash,
+        amount: tx.amount,
+        token: tx.token_symbol,
+        direction,
+        status: tx.status,
+        eta_secs,
+    })
+}
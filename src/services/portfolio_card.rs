@@ -0,0 +1,329 @@
+// PNG portfolio card rendered with the `image` crate and a built-in 5x7 bitmap font
+
+use image::{ ImageFormat, Rgb, RgbImage };
+
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::services::portfolio_service::Portfolio;
+
+pub const CARD_WIDTH: u32 = 600;
+pub const CARD_HEIGHT: u32 = 400;
+
+/// Holdings shown as bar segments; the rest are folded into "Other"
+const TOP_HOLDINGS: usize = 5;
+
+const BRAND_NAME: &str = "CRYPTO BOT";
+
+const BACKGROUND: Rgb<u8> = Rgb([18, 20, 28]);
+const PANEL: Rgb<u8> = Rgb([30, 33, 45]);
+const ACCENT: Rgb<u8> = Rgb([108, 92, 231]);
+const TEXT: Rgb<u8> = Rgb([236, 238, 245]);
+const MUTED: Rgb<u8> = Rgb([138, 143, 163]);
+const POSITIVE: Rgb<u8> = Rgb([46, 204, 113]);
+const NEGATIVE: Rgb<u8> = Rgb([231, 76, 60]);
+const OTHER_SEGMENT: Rgb<u8> = Rgb([85, 90, 110]);
+
+/// Segment colors for the top holdings, largest first
+const SEGMENT_COLORS: [Rgb<u8>; TOP_HOLDINGS] = [
+    Rgb([108, 92, 231]),
+    Rgb([0, 184, 212]),
+    Rgb([253, 203, 110]),
+    Rgb([232, 67, 147]),
+    Rgb([85, 239, 196]),
+];
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Render the portfolio as a dark-themed PNG card and return the encoded bytes
+pub fn render(portfolio: &Portfolio) -> Result<Vec<u8>> {
+    let mut img = RgbImage::from_pixel(CARD_WIDTH, CARD_HEIGHT, BACKGROUND);
+
+    // Branding strip
+    fill_rect(&mut img, 0, 0, CARD_WIDTH, 6, ACCENT);
+    draw_text(&mut img, 24, 24, BRAND_NAME, 2, ACCENT);
+    draw_text(&mut img, 24 + text_width(BRAND_NAME, 2) + 12, 28, "PORTFOLIO", 1, MUTED);
+
+    // Total value and 24h change
+    draw_text(&mut img, 24, 64, "TOTAL VALUE", 1, MUTED);
+    let total = format!("${}", format_usd(portfolio.total_usd_value));
+    draw_text(&mut img, 24, 80, &total, 5, TEXT);
+
+    match change_24h_pct(portfolio) {
+        Some(change) => {
+            let color = if change >= 0.0 { POSITIVE } else { NEGATIVE };
+            draw_text(&mut img, 24, 130, &format!("{:+.2}% 24H", change), 2, color);
+        }
+        None => draw_text(&mut img, 24, 130, "24H CHANGE N/A", 2, MUTED),
+    }
+
+    // Allocation bar across the top holdings
+    fill_rect(&mut img, 16, 164, CARD_WIDTH - 32, 168, PANEL);
+    draw_text(&mut img, 32, 176, "TOP HOLDINGS", 1, MUTED);
+
+    let bar_x = 32;
+    let bar_y = 192;
+    let bar_width = CARD_WIDTH - 64;
+    fill_rect(&mut img, bar_x, bar_y, bar_width, 20, OTHER_SEGMENT);
+
+    let top: Vec<_> = portfolio.holdings
+        .iter()
+        .filter(|h| h.usd_value > 0.0)
+        .take(TOP_HOLDINGS)
+        .collect();
+
+    if portfolio.total_usd_value > 0.0 {
+        let mut x = bar_x;
+        for (holding, color) in top.iter().zip(SEGMENT_COLORS) {
+            let share = holding.usd_value / portfolio.total_usd_value;
+            let width = ((bar_width as f64) * share).round() as u32;
+            let width = width.min(bar_x + bar_width - x);
+            fill_rect(&mut img, x, bar_y, width, 20, color);
+            x += width;
+        }
+    }
+
+    // Legend, one row per holding
+    let mut y = 226;
+    for (holding, color) in top.iter().zip(SEGMENT_COLORS) {
+        let pct = if portfolio.total_usd_value > 0.0 {
+            (holding.usd_value / portfolio.total_usd_value) * 100.0
+        } else {
+            0.0
+        };
+        fill_rect(&mut img, 32, y, 12, 12, color);
+        draw_text(&mut img, 52, y - 1, &holding.symbol, 2, TEXT);
+        draw_text(&mut img, 200, y - 1, &format!("{:.1}%", pct), 2, TEXT);
+        draw_text(&mut img, 320, y - 1, &format!("${}", format_usd(holding.usd_value)), 2, MUTED);
+        y += 20;
+    }
+    if top.is_empty() {
+        draw_text(&mut img, 32, y, "NO HOLDINGS YET", 2, MUTED);
+    }
+
+    // Chain icons along the footer
+    let mut x = 24;
+    for chain in &portfolio.chains {
+        if x + 28 > CARD_WIDTH - 24 {
+            break;
+        }
+        draw_chain_icon(&mut img, x + 12, 364, chain);
+        x += 32;
+    }
+    let wallets = format!("{} WALLETS", portfolio.wallet_count);
+    let wallets_x = CARD_WIDTH - 24 - text_width(&wallets, 1);
+    draw_text(&mut img, wallets_x, 360, &wallets, 1, MUTED);
+
+    let mut bytes: Vec<u8> = Vec::new();
+    img
+        .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| AppError::Internal(format!("Failed to encode portfolio card: {}", e)))?;
+
+    Ok(bytes)
+}
+
+/// Value-weighted 24h change across holdings with a known price change
+fn change_24h_pct(portfolio: &Portfolio) -> Option<f64> {
+    let mut current = 0.0;
+    let mut previous = 0.0;
+
+    for holding in &portfolio.holdings {
+        if let Some(change) = holding.price_change_24h {
+            current += holding.usd_value;
+            previous += holding.usd_value / (1.0 + change / 100.0);
+        }
+    }
+
+    (previous > 0.0).then(|| ((current - previous) / previous) * 100.0)
+}
+
+/// Thousands-separated USD amount with two decimals, e.g. "12,345.67"
+fn format_usd(value: f64) -> String {
+    let formatted = format!("{:.2}", value.abs());
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, "00"));
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    let sign = if value < 0.0 { "-" } else { "" };
+    format!("{}{}.{}", sign, grouped, fraction)
+}
+
+fn chain_color(chain: Option<Chain>) -> Rgb<u8> {
+    match chain {
+        Some(Chain::Eth) => Rgb([98, 126, 234]),
+        Some(Chain::Bsc) => Rgb([243, 186, 47]),
+        Some(Chain::Solana) => Rgb([153, 69, 255]),
+        Some(Chain::Polygon) => Rgb([130, 71, 229]),
+        Some(Chain::Avalanche) => Rgb([232, 65, 66]),
+        Some(Chain::Arbitrum) => Rgb([40, 160, 240]),
+        Some(Chain::Optimism) => Rgb([255, 4, 32]),
+        Some(Chain::Base) => Rgb([0, 82, 255]),
+        Some(Chain::Fantom) => Rgb([25, 105, 255]),
+        Some(Chain::Cronos) => Rgb([0, 45, 116]),
+        Some(Chain::Gnosis) => Rgb([4, 121, 88]),
+        Some(Chain::Btc) => Rgb([247, 147, 26]),
+        Some(Chain::Xrp) => Rgb([35, 41, 47]),
+        Some(Chain::Cardano) => Rgb([0, 51, 173]),
+        None => OTHER_SEGMENT,
+    }
+}
+
+/// Filled circle in the chain's brand color with its initial
+fn draw_chain_icon(img: &mut RgbImage, cx: u32, cy: u32, chain: &str) {
+    let color = chain_color(chain.parse().ok());
+    let radius: i64 = 12;
+
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy <= radius * radius {
+                put_pixel(img, (cx as i64) + dx, (cy as i64) + dy, color);
+            }
+        }
+    }
+
+    let initial: String = chain.chars().take(1).collect();
+    draw_text(img, cx - GLYPH_WIDTH, cy - GLYPH_HEIGHT, &initial, 2, TEXT);
+}
+
+fn fill_rect(img: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+    for py in y..(y + height).min(img.height()) {
+        for px in x..(x + width).min(img.width()) {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn put_pixel(img: &mut RgbImage, x: i64, y: i64, color: Rgb<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+fn text_width(text: &str, scale: u32) -> u32 {
+    (text.chars().count() as u32) * (GLYPH_WIDTH + 1) * scale
+}
+
+/// Draw upper-cased text with the bitmap font, each font pixel `scale` pixels square
+fn draw_text(img: &mut RgbImage, x: u32, y: u32, text: &str, scale: u32, color: Rgb<u8>) {
+    let mut cursor = x;
+
+    for ch in text.chars().flat_map(char::to_uppercase) {
+        for (row, bits) in glyph(ch).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    fill_rect(
+                        img,
+                        cursor + col * scale,
+                        y + (row as u32) * scale,
+                        scale,
+                        scale,
+                        color
+                    );
+                }
+            }
+        }
+        cursor += (GLYPH_WIDTH + 1) * scale;
+    }
+}
+
+/// Rows of a 5x7 glyph, most significant of the low five bits leftmost
+fn glyph(ch: char) -> [u8; 7] {
+    match ch {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '$' => [0b00100, 0b01111, 0b10100, 0b01110, 0b00101, 0b11110, 0b00100],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        '+' => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '/' => [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000],
+        ' ' => [0; 7],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::portfolio_service::TokenHolding;
+
+    fn holding(symbol: &str, usd_value: f64, change: Option<f64>) -> TokenHolding {
+        TokenHolding {
+            symbol: symbol.to_string(),
+            name: None,
+            total_balance: 1.0,
+            usd_value,
+            usd_price: usd_value,
+            price_change_24h: change,
+            logo_url: None,
+            wallets: vec![],
+        }
+    }
+
+    #[test]
+    fn test_format_usd() {
+        assert_eq!(format_usd(0.0), "0.00");
+        assert_eq!(format_usd(999.5), "999.50");
+        assert_eq!(format_usd(1234567.891), "1,234,567.89");
+    }
+
+    #[test]
+    fn test_render_card() {
+        let portfolio = Portfolio {
+            user_id: "1".to_string(),
+            holdings: vec![holding("ETH", 110.0, Some(10.0)), holding("SOL", 50.0, None)],
+            total_usd_value: 160.0,
+            chains: vec!["ETH".to_string(), "SOLANA".to_string()],
+            wallet_count: 2,
+        };
+
+        let change = change_24h_pct(&portfolio).unwrap();
+        assert!((change - 10.0).abs() < 1e-9);
+
+        let bytes = render(&portfolio).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (CARD_WIDTH, CARD_HEIGHT));
+    }
+}
@@ -1,21 +1,41 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{ Duration, Instant };
 
+use chrono::{ DateTime, Utc };
 use serde::Serialize;
+use tokio::sync::RwLock;
 
-use crate::db::WalletRepository;
+use crate::db::{ PortfolioSnapshotRepository, WalletRepository };
 use crate::enums::Chain;
-use crate::error::Result;
+use crate::error::{ AppError, Result };
+use crate::price_monitor::PriceMonitor;
 use crate::rpc::RpcManager;
+use crate::services::portfolio_card;
 use crate::services::price_service::PriceService;
 use crate::services::token_discovery_service::TokenDiscoveryService;
 
+/// Minimum time between two recorded portfolio snapshots for the same user
+const SNAPSHOT_INTERVAL_HOURS: i64 = 1;
+
+/// Share of ETH in the benchmark alpha is measured against; BTC makes up the rest
+const BENCHMARK_ETH_WEIGHT: f64 = 0.5;
+
+/// How long a rendered portfolio card is reused before it's drawn again
+const CARD_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Wallets worth less than this are offered for merging by /cleanwallet
+pub const DEFAULT_DUST_THRESHOLD_USD: f64 = 1.0;
+
 pub struct PortfolioService {
     wallet_repo: Arc<WalletRepository>,
+    snapshot_repo: Arc<PortfolioSnapshotRepository>,
     rpc_manager: Arc<RpcManager>,
     price_service: Arc<PriceService>,
     token_discovery: Option<Arc<TokenDiscoveryService>>,
     is_testnet: bool,
+    /// Rendered portfolio card PNG per user, with when it was rendered
+    card_cache: RwLock<HashMap<String, (Vec<u8>, Instant)>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -47,9 +67,50 @@ pub struct Portfolio {
     pub wallet_count: usize,
 }
 
+/// A chain's share of the portfolio's USD value
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainAllocation {
+    pub chain: String,
+    pub usd_value: f64,
+    pub pct_of_total: f64,
+    pub wallet_count: usize,
+}
+
+/// One non-zero balance held by a dust wallet
+#[derive(Debug, Clone, Serialize)]
+pub struct DustBalance {
+    pub symbol: String,
+    pub balance: String,
+    pub usd_value: f64,
+}
+
+/// A wallet whose holdings are worth less than the dust threshold
+#[derive(Debug, Clone, Serialize)]
+pub struct DustWallet {
+    pub wallet_id: String,
+    pub chain: String,
+    pub address: String,
+    pub total_usd_value: f64,
+    pub balances: Vec<DustBalance>,
+    /// The user's most valuable non-dust wallet on the same chain and network, if any
+    pub main_wallet_id: Option<String>,
+    pub main_wallet_address: Option<String>,
+}
+
+/// Portfolio return over a period next to holding ETH or BTC instead
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkComparison {
+    pub portfolio_return_pct: f64,
+    pub eth_return_pct: f64,
+    pub btc_return_pct: f64,
+    /// Portfolio return minus the weighted ETH/BTC benchmark return
+    pub alpha: f64,
+}
+
 impl PortfolioService {
     pub fn new(
         wallet_repo: Arc<WalletRepository>,
+        snapshot_repo: Arc<PortfolioSnapshotRepository>,
         rpc_manager: Arc<RpcManager>,
         price_service: Arc<PriceService>,
         token_discovery: Option<Arc<TokenDiscoveryService>>,
@@ -57,10 +118,19 @@ impl PortfolioService {
     ) -> Self {
         Self {
             wallet_repo,
+            snapshot_repo,
             rpc_manager,
             price_service,
             token_discovery,
             is_testnet,
+            card_cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Have the price monitor keep native token prices warm for every configured chain
+    pub fn register_watched_symbols(&self, monitor: &PriceMonitor) {
+        for chain in self.rpc_manager.get_configured_chains() {
+            monitor.register(chain.native_symbol());
         }
     }
 
@@ -84,7 +154,7 @@ impl PortfolioService {
         for wallet in &wallets {
             chains_set.insert(wallet.chain.clone());
 
-            let provider = match self.rpc_manager.get_provider_by_chain(&wallet.chain).await {
+            let provider = match self.rpc_manager.get_wallet_provider(wallet).await {
                 Ok(p) => p,
                 Err(e) => {
                     tracing::warn!("No provider for chain {}: {}", wallet.chain, e);
@@ -132,7 +202,13 @@ impl PortfolioService {
                 if let Some(chain) = chain_parsed {
                     if discovery.is_supported(&chain) {
                         match discovery
-                            .get_all_token_balances(chain, &wallet.address, self.is_testnet)
+                            .discover_and_cache_new_tokens(
+                                wallet.id,
+                                chain,
+                                &wallet.address,
+                                self.is_testnet,
+                                provider.as_ref(),
+                            )
                             .await
                         {
                             Ok(tokens) => {
@@ -203,6 +279,10 @@ impl PortfolioService {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        if let Err(e) = self.record_snapshot(user_id, total_usd_value).await {
+            tracing::warn!("Failed to record portfolio snapshot for {}: {}", user_id, e);
+        }
+
         Ok(Portfolio {
             user_id: user_id.to_string(),
             holdings,
@@ -212,6 +292,204 @@ impl PortfolioService {
         })
     }
 
+    /// Render the portfolio as a shareable 600x400 PNG card
+    pub fn render_portfolio_card(portfolio: &Portfolio) -> Result<Vec<u8>> {
+        portfolio_card::render(portfolio)
+    }
+
+    /// The user's portfolio card, re-rendered at most every five minutes
+    pub async fn get_portfolio_card(&self, user_id: &str) -> Result<Vec<u8>> {
+        if let Some((bytes, rendered_at)) = self.card_cache.read().await.get(user_id) {
+            if rendered_at.elapsed() < CARD_CACHE_TTL {
+                return Ok(bytes.clone());
+            }
+        }
+
+        let portfolio = self.get_portfolio(user_id).await?;
+        let bytes = Self::render_portfolio_card(&portfolio)?;
+
+        let mut cache = self.card_cache.write().await;
+        cache.retain(|_, (_, rendered_at)| rendered_at.elapsed() < CARD_CACHE_TTL);
+        cache.insert(user_id.to_string(), (bytes.clone(), Instant::now()));
+
+        Ok(bytes)
+    }
+
+    /// USD value per chain, largest first
+    pub async fn get_chain_breakdown(&self, user_id: &str) -> Result<Vec<ChainAllocation>> {
+        let portfolio = self.get_portfolio(user_id).await?;
+        Ok(Self::chain_breakdown(&portfolio))
+    }
+
+    /// Split each holding's value across chains by the balance each wallet holds
+    pub fn chain_breakdown(portfolio: &Portfolio) -> Vec<ChainAllocation> {
+        let mut values: HashMap<String, f64> = portfolio.chains
+            .iter()
+            .map(|chain| (chain.clone(), 0.0))
+            .collect();
+        let mut wallets: HashMap<String, std::collections::HashSet<&str>> = HashMap::new();
+
+        for holding in &portfolio.holdings {
+            for wallet in &holding.wallets {
+                let balance: f64 = wallet.balance.parse().unwrap_or(0.0);
+                *values.entry(wallet.chain.clone()).or_insert(0.0) += balance * holding.usd_price;
+                wallets.entry(wallet.chain.clone()).or_default().insert(&wallet.wallet_id);
+            }
+        }
+
+        let mut breakdown: Vec<ChainAllocation> = values
+            .into_iter()
+            .map(|(chain, usd_value)| {
+                let pct_of_total = if portfolio.total_usd_value > 0.0 {
+                    (usd_value / portfolio.total_usd_value) * 100.0
+                } else {
+                    0.0
+                };
+                let wallet_count = wallets.get(&chain).map(|w| w.len()).unwrap_or(0);
+                ChainAllocation { chain, usd_value, pct_of_total, wallet_count }
+            })
+            .collect();
+
+        breakdown.sort_by(|a, b| {
+            b.usd_value
+                .partial_cmp(&a.usd_value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        breakdown
+    }
+
+    /// Wallets holding something but worth less than `dust_threshold_usd` in total.
+    /// Empty wallets aren't dust, since there is nothing to clean out of them.
+    pub async fn find_dust_wallets(&self, user_id: &str, dust_threshold_usd: f64) -> Result<Vec<DustWallet>> {
+        let wallets = self.wallet_repo.find_by_user(user_id).await?;
+        let portfolio = self.get_portfolio(user_id).await?;
+
+        let mut values: HashMap<String, f64> = HashMap::new();
+        let mut balances: HashMap<String, Vec<DustBalance>> = HashMap::new();
+        for holding in &portfolio.holdings {
+            for wallet in &holding.wallets {
+                let amount: f64 = wallet.balance.parse().unwrap_or(0.0);
+                if amount <= 0.0 {
+                    continue;
+                }
+                let usd_value = amount * holding.usd_price;
+                *values.entry(wallet.wallet_id.clone()).or_insert(0.0) += usd_value;
+                balances.entry(wallet.wallet_id.clone()).or_default().push(DustBalance {
+                    symbol: holding.symbol.clone(),
+                    balance: wallet.balance.clone(),
+                    usd_value,
+                });
+            }
+        }
+        let value_of = |id: &uuid::Uuid| values.get(&id.to_string()).copied().unwrap_or(0.0);
+
+        let mut dust = Vec::new();
+        for wallet in &wallets {
+            let total_usd_value = value_of(&wallet.id);
+            if total_usd_value >= dust_threshold_usd {
+                continue;
+            }
+            let Some(wallet_balances) = balances.remove(&wallet.id.to_string()) else {
+                continue;
+            };
+
+            let main_wallet = wallets
+                .iter()
+                .filter(|w| {
+                    w.id != wallet.id && w.chain == wallet.chain && w.is_testnet == wallet.is_testnet
+                })
+                .filter(|w| value_of(&w.id) >= dust_threshold_usd)
+                .max_by(|a, b| {
+                    value_of(&a.id)
+                        .partial_cmp(&value_of(&b.id))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+            dust.push(DustWallet {
+                wallet_id: wallet.id.to_string(),
+                chain: wallet.chain.clone(),
+                address: wallet.address.clone(),
+                total_usd_value,
+                balances: wallet_balances,
+                main_wallet_id: main_wallet.map(|w| w.id.to_string()),
+                main_wallet_address: main_wallet.map(|w| w.address.clone()),
+            });
+        }
+
+        Ok(dust)
+    }
+
+    /// Store the portfolio value, at most once per snapshot interval
+    async fn record_snapshot(&self, user_id: &str, total_usd_value: f64) -> Result<()> {
+        if let Some(latest) = self.snapshot_repo.find_latest(user_id).await? {
+            if Utc::now() - latest.created_at < chrono::Duration::hours(SNAPSHOT_INTERVAL_HOURS) {
+                return Ok(());
+            }
+        }
+        self.snapshot_repo.create(user_id, total_usd_value).await?;
+        Ok(())
+    }
+
+    /// Get recorded portfolio values for the last `days` days, oldest first.
+    pub async fn get_value_history(&self, user_id: &str, days: u32) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        let since = Utc::now() - chrono::Duration::days(days as i64);
+        let snapshots = self.snapshot_repo.find_since(user_id, since).await?;
+
+        Ok(snapshots
+            .into_iter()
+            .map(|s| (s.created_at, s.total_usd_value))
+            .collect())
+    }
+
+    /// Compare the portfolio's return since its oldest snapshot in the last `days` days
+    /// with ETH and BTC over the same period
+    pub async fn compare_to_benchmark(&self, user_id: &str, days: u32) -> Result<BenchmarkComparison> {
+        let since = Utc::now() - chrono::Duration::days(days as i64);
+        let oldest = self.snapshot_repo
+            .find_since(user_id, since).await?
+            .into_iter()
+            .next()
+            .filter(|s| s.total_usd_value > 0.0)
+            .ok_or_else(|| {
+                AppError::Validation(
+                    format!("No portfolio history in the last {} days. Check /portfolio first.", days)
+                )
+            })?;
+
+        let current = self.get_portfolio(user_id).await?.total_usd_value;
+        let portfolio_return_pct = percent_change(oldest.total_usd_value, current);
+
+        let start = oldest.created_at.timestamp().max(0) as u64;
+        let eth_return_pct = self.benchmark_return("ETH", days, start).await?;
+        let btc_return_pct = self.benchmark_return("BTC", days, start).await?;
+
+        let benchmark_return =
+            eth_return_pct * BENCHMARK_ETH_WEIGHT + btc_return_pct * (1.0 - BENCHMARK_ETH_WEIGHT);
+
+        Ok(BenchmarkComparison {
+            portfolio_return_pct,
+            eth_return_pct,
+            btc_return_pct,
+            alpha: portfolio_return_pct - benchmark_return,
+        })
+    }
+
+    /// A symbol's return from the daily close at or before `start` to the latest close
+    async fn benchmark_return(&self, symbol: &str, days: u32, start: u64) -> Result<f64> {
+        let history = self.price_service.get_price_history(symbol, days).await?;
+        let (Some(first), Some(last)) = (history.first(), history.last()) else {
+            return Err(AppError::External(format!("No price history for {}", symbol)));
+        };
+
+        let start_price = history
+            .iter()
+            .take_while(|(ts, _)| *ts <= start)
+            .last()
+            .unwrap_or(first).1;
+
+        Ok(percent_change(start_price, last.1))
+    }
+
     /// Get portfolio for a specific chain.
     pub async fn get_chain_portfolio(&self, user_id: &str, chain: &str) -> Result<Portfolio> {
         let wallets = self.wallet_repo.find_by_user_and_chain(user_id, chain).await?;
@@ -226,7 +504,6 @@ impl PortfolioService {
             });
         }
 
-        let provider = self.rpc_manager.get_provider_by_chain(chain).await?;
         let chain_parsed = chain.parse::<Chain>().ok();
         let symbol = chain_parsed
             .map(|c| c.native_symbol())
@@ -236,6 +513,15 @@ impl PortfolioService {
         let mut wallet_holdings = vec![];
 
         for wallet in &wallets {
+            // Wallets on the same chain may live on different networks
+            let provider = match self.rpc_manager.get_wallet_provider(wallet).await {
+                Ok(p) => p,
+                Err(e) => {
+                    tracing::warn!("No provider for wallet {}: {}", wallet.id, e);
+                    continue;
+                }
+            };
+
             match provider.get_balance(&wallet.address).await {
                 Ok(balance) => {
                     let balance_float: f64 = balance.balance.parse().unwrap_or(0.0);
@@ -281,3 +567,7 @@ impl PortfolioService {
         })
     }
 }
+
+fn percent_change(from: f64, to: f64) -> f64 {
+    if from > 0.0 { ((to - from) / from) * 100.0 } else { 0.0 }
+}
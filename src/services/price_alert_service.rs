@@ -1,21 +1,99 @@
-use crate::db::entity::price_alert;
-use crate::enums::{ AlertKind, AlertType };
-use crate::error::Result;
-use chrono::Utc;
+use crate::db::entity::{ gas_alert, mempool_alert, price_alert, wallet };
+use crate::enums::{ AlertKind, AlertType, Chain };
+use crate::error::{ AppError, Result };
+use crate::services::price_service::is_stablecoin;
+use chrono::{ DateTime, Utc };
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{ Arc, RwLock };
+use tokio::sync::{ mpsc, oneshot };
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
     ColumnTrait,
+    Condition,
     DatabaseConnection,
     EntityTrait,
     QueryFilter,
+    TransactionTrait,
     prelude::Decimal,
+    sea_query::{ Expr, OnConflict },
 };
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct PriceAlertService {
     db: DatabaseConnection,
+    alert_checker: Option<AlertCheckHandle>,
+}
+
+/// An alert that fired during a check pass
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggeredAlert {
+    pub alert_id: Uuid,
+    pub user_id: String,
+    pub token_symbol: String,
+    pub chain: String,
+    pub alert_type: String,
+    pub current_price: f64,
+}
+
+/// When the alert checker last ran and when its next periodic pass is due
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct AlertCheckSchedule {
+    pub last_check_at: Option<DateTime<Utc>>,
+    pub next_check_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertStatus {
+    pub active_alerts: usize,
+    /// Active alerts per alert type, e.g. "above" or "trailing_stop"
+    pub by_kind: BTreeMap<String, usize>,
+    #[serde(flatten)]
+    pub schedule: AlertCheckSchedule,
+}
+
+/// Where the alert checker sends the result of a requested pass
+pub type AlertCheckReply = oneshot::Sender<Result<Vec<TriggeredAlert>>>;
+
+/// Asks the running alert checker for an immediate pass and reads its schedule
+#[derive(Clone)]
+pub struct AlertCheckHandle {
+    requests: mpsc::Sender<AlertCheckReply>,
+    schedule: Arc<RwLock<AlertCheckSchedule>>,
+}
+
+/// The alert checker's end of an `AlertCheckHandle`
+pub struct AlertCheckRequests {
+    requests: mpsc::Receiver<AlertCheckReply>,
+    schedule: Arc<RwLock<AlertCheckSchedule>>,
+}
+
+/// Connect a `PriceAlertService` (handle) to the `AlertChecker` (requests)
+pub fn alert_check_channel() -> (AlertCheckHandle, AlertCheckRequests) {
+    let (sender, receiver) = mpsc::channel(4);
+    let schedule = Arc::new(RwLock::new(AlertCheckSchedule::default()));
+    (
+        AlertCheckHandle { requests: sender, schedule: schedule.clone() },
+        AlertCheckRequests { requests: receiver, schedule },
+    )
+}
+
+impl AlertCheckRequests {
+    /// Next manual check request; the reply channel receives the pass's triggered alerts
+    pub async fn recv(&mut self) -> Option<AlertCheckReply> {
+        self.requests.recv().await
+    }
+
+    /// Record a finished pass, and when the next periodic one is due
+    pub fn record_check(&self, next_check_at: Option<DateTime<Utc>>) {
+        let mut schedule = self.schedule.write().unwrap();
+        schedule.last_check_at = Some(Utc::now());
+        if next_check_at.is_some() {
+            schedule.next_check_at = next_check_at;
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,17 +103,66 @@ pub struct CreateAlertRequest {
     pub chain: String,
     pub token_address: Option<String>,
     pub alert_type: AlertType,
+    /// Sell the position from `wallet_id` when a stop-loss triggers
+    pub auto_execute: bool,
+    pub wallet_id: Option<Uuid>,
 }
 
 impl PriceAlertService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self { db, alert_checker: None }
+    }
+
+    /// Route `check_all_now` to the running alert checker and report its schedule
+    pub fn with_alert_checker(mut self, handle: AlertCheckHandle) -> Self {
+        self.alert_checker = Some(handle);
+        self
+    }
+
+    /// Run a full alert check pass right away instead of waiting for the next poll,
+    /// returning the alerts that fired. Users are notified as in a regular pass.
+    pub async fn check_all_now(&self) -> Result<Vec<TriggeredAlert>> {
+        let handle = self.alert_checker
+            .as_ref()
+            .ok_or_else(|| AppError::Config("Alert checker is not running".to_string()))?;
+
+        let (reply, response) = oneshot::channel();
+        handle.requests
+            .send(reply).await
+            .map_err(|_| AppError::Internal("Alert checker stopped".to_string()))?;
+        response.await.map_err(|_| AppError::Internal("Alert checker dropped the check request".to_string()))?
+    }
+
+    /// Active alert counts and the checker's last and next run
+    pub async fn alert_status(&self) -> Result<AlertStatus> {
+        let alerts = self.get_active_alerts().await?;
+        let mut by_kind: BTreeMap<String, usize> = BTreeMap::new();
+        for alert in &alerts {
+            *by_kind.entry(alert.alert_type.clone()).or_default() += 1;
+        }
+
+        Ok(AlertStatus {
+            active_alerts: alerts.len(),
+            by_kind,
+            schedule: self.alert_checker
+                .as_ref()
+                .map(|handle| *handle.schedule.read().unwrap())
+                .unwrap_or_default(),
+        })
     }
 
     /// Create a new price alert
     pub async fn create_alert(&self, req: CreateAlertRequest) -> Result<price_alert::Model> {
         let now = Utc::now();
 
+        let mut token_address = req.token_address;
+        let mut stop_loss_price = None;
+        let mut take_profit_price = None;
+        let mut peak_price = None;
+        let mut trail_pct = None;
+        let mut baseline_volume = None;
+        let mut volume_multiplier = None;
+
         let (alert_kind, target_price, percent_change, base_price) = match req.alert_type {
             AlertType::Above { target_price } => {
                 (
@@ -61,19 +188,88 @@ impl PriceAlertService {
                     Some(Decimal::from_f64_retain(base_price).unwrap()),
                 )
             }
+            AlertType::StopLoss { stop_price, token_address: stop_token } => {
+                stop_loss_price = Some(Decimal::from_f64_retain(stop_price).unwrap());
+                if stop_token.is_some() {
+                    token_address = stop_token;
+                }
+                (AlertKind::StopLoss, None, None, None)
+            }
+            AlertType::TakeProfit { target_price } => {
+                take_profit_price = Some(Decimal::from_f64_retain(target_price).unwrap());
+                (AlertKind::TakeProfit, None, None, None)
+            }
+            AlertType::TrailingStop { trail_pct: pct, peak_price: peak } => {
+                if !(pct > 0.0 && pct < 100.0) {
+                    return Err(
+                        AppError::InvalidInput("Trail percentage must be between 0 and 100".to_string())
+                    );
+                }
+                trail_pct = Some(Decimal::from_f64_retain(pct).unwrap());
+                peak_price = Some(Decimal::from_f64_retain(peak).unwrap());
+                (AlertKind::TrailingStop, None, None, None)
+            }
+            AlertType::VolumeSurge { baseline_volume_usd, multiplier } => {
+                // A pegged token's volume says nothing about market interest
+                if is_stablecoin(&req.token_symbol) {
+                    return Err(
+                        AppError::InvalidInput("Volume alerts are not available for stablecoins".to_string())
+                    );
+                }
+                if !multiplier.is_finite() || multiplier <= 1.0 {
+                    return Err(AppError::InvalidInput("Volume multiplier must be greater than 1".to_string()));
+                }
+                if !baseline_volume_usd.is_finite() || baseline_volume_usd <= 0.0 {
+                    return Err(
+                        AppError::InvalidInput(format!("No 24h volume known for {}", req.token_symbol))
+                    );
+                }
+                baseline_volume = Some(Decimal::from_f64_retain(baseline_volume_usd).unwrap());
+                volume_multiplier = Some(Decimal::from_f64_retain(multiplier).unwrap());
+                (AlertKind::VolumeSurge, None, None, None)
+            }
+            AlertType::LargeIncoming { .. } => {
+                return Err(
+                    AppError::InvalidInput(
+                        "Large incoming alerts are per wallet; use set_mempool_alert".to_string()
+                    )
+                );
+            }
         };
 
+        // Auto-execution only makes sense for stop-losses with a wallet to sell from
+        let auto_execute = req.auto_execute && alert_kind == AlertKind::StopLoss && req.wallet_id.is_some();
+        if auto_execute && token_address.is_none() && !is_native_symbol(&req.chain, &req.token_symbol) {
+            return Err(
+                AppError::InvalidInput(
+                    format!(
+                        "Auto-selling {} needs its contract address; only the chain's native token can be sold without one",
+                        req.token_symbol
+                    )
+                )
+            );
+        }
+
         let alert = price_alert::ActiveModel {
             id: ActiveValue::Set(Uuid::new_v4()),
             user_id: ActiveValue::Set(req.user_id),
             token_symbol: ActiveValue::Set(req.token_symbol),
             chain: ActiveValue::Set(req.chain),
-            token_address: ActiveValue::Set(req.token_address),
+            token_address: ActiveValue::Set(token_address),
             alert_type: ActiveValue::Set(alert_kind.to_string()),
             target_price: ActiveValue::Set(target_price),
             percent_change: ActiveValue::Set(percent_change),
             base_price: ActiveValue::Set(base_price),
+            stop_loss_price: ActiveValue::Set(stop_loss_price),
+            take_profit_price: ActiveValue::Set(take_profit_price),
+            auto_execute: ActiveValue::Set(auto_execute),
+            wallet_id: ActiveValue::Set(req.wallet_id),
+            peak_price: ActiveValue::Set(peak_price),
+            trail_pct: ActiveValue::Set(trail_pct),
+            baseline_volume: ActiveValue::Set(baseline_volume),
+            volume_multiplier: ActiveValue::Set(volume_multiplier),
             active: ActiveValue::Set(true),
+            paused: ActiveValue::Set(false),
             triggered_at: ActiveValue::Set(None),
             last_checked_at: ActiveValue::Set(None),
             created_at: ActiveValue::Set(now),
@@ -84,7 +280,7 @@ impl PriceAlertService {
         Ok(alert)
     }
 
-    /// List all alerts for a user
+    /// List a user's alerts; `active_only` leaves out triggered and paused ones
     pub async fn list_user_alerts(
         &self,
         user_id: &str,
@@ -93,7 +289,9 @@ impl PriceAlertService {
         let mut query = price_alert::Entity::find().filter(price_alert::Column::UserId.eq(user_id));
 
         if active_only {
-            query = query.filter(price_alert::Column::Active.eq(true));
+            query = query
+                .filter(price_alert::Column::Active.eq(true))
+                .filter(price_alert::Column::Paused.eq(false));
         }
 
         let alerts = query.all(&self.db).await?;
@@ -119,15 +317,41 @@ impl PriceAlertService {
         Ok(())
     }
 
-    /// Get all active alerts
+    /// Get all active alerts that aren't paused
     pub async fn get_active_alerts(&self) -> Result<Vec<price_alert::Model>> {
         let alerts = price_alert::Entity
             ::find()
             .filter(price_alert::Column::Active.eq(true))
+            .filter(price_alert::Column::Paused.eq(false))
             .all(&self.db).await?;
         Ok(alerts)
     }
 
+    /// Stop checking an alert without deleting it
+    pub async fn pause_alert(&self, alert_id: Uuid, user_id: &str) -> Result<()> {
+        self.set_paused(alert_id, user_id, true).await
+    }
+
+    /// Check a paused alert again from the next pass
+    pub async fn resume_alert(&self, alert_id: Uuid, user_id: &str) -> Result<()> {
+        self.set_paused(alert_id, user_id, false).await
+    }
+
+    async fn set_paused(&self, alert_id: Uuid, user_id: &str, paused: bool) -> Result<()> {
+        let alert = self
+            .get_alert(alert_id, user_id).await?
+            .ok_or_else(|| AppError::NotFound("Alert not found".to_string()))?;
+        if !alert.active {
+            return Err(AppError::InvalidInput("Alert has already triggered".to_string()));
+        }
+
+        let mut active: price_alert::ActiveModel = alert.into();
+        active.paused = ActiveValue::Set(paused);
+        active.updated_at = ActiveValue::Set(Utc::now());
+        active.update(&self.db).await?;
+        Ok(())
+    }
+
     /// Mark alert as triggered
     pub async fn trigger_alert(&self, id: Uuid) -> Result<()> {
         let alert = price_alert::Entity::find_by_id(id).one(&self.db).await?;
@@ -156,4 +380,187 @@ impl PriceAlertService {
 
         Ok(())
     }
+
+    /// Raise trailing-stop peaks in one transaction. Each row is only written when the new
+    /// peak is higher than the stored one, so concurrent checks can't lower a peak.
+    pub async fn raise_peak_prices(&self, peaks: &[(Uuid, Decimal)]) -> Result<()> {
+        if peaks.is_empty() {
+            return Ok(());
+        }
+
+        let txn = self.db.begin().await?;
+        for (id, peak) in peaks {
+            price_alert::Entity
+                ::update_many()
+                .col_expr(price_alert::Column::PeakPrice, Expr::value(*peak))
+                .col_expr(price_alert::Column::UpdatedAt, Expr::value(Utc::now()))
+                .filter(price_alert::Column::Id.eq(*id))
+                .filter(
+                    Condition::any()
+                        .add(price_alert::Column::PeakPrice.is_null())
+                        .add(price_alert::Column::PeakPrice.lt(*peak))
+                )
+                .exec(&txn).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Create or replace the large-incoming mempool alert on a wallet
+    pub async fn set_mempool_alert(
+        &self,
+        user_id: &str,
+        wallet: &wallet::Model,
+        threshold_usd: f64
+    ) -> Result<mempool_alert::Model> {
+        if !threshold_usd.is_finite() || threshold_usd <= 0.0 {
+            return Err(AppError::InvalidInput("Threshold must be a positive USD amount".to_string()));
+        }
+
+        let now = Utc::now();
+        let alert = mempool_alert::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user_id.to_string()),
+            wallet_id: ActiveValue::Set(wallet.id),
+            chain: ActiveValue::Set(wallet.chain.clone()),
+            address: ActiveValue::Set(wallet.address.to_lowercase()),
+            is_testnet: ActiveValue::Set(wallet.is_testnet),
+            alert_type: ActiveValue::Set(AlertKind::LargeIncoming.to_string()),
+            threshold_usd: ActiveValue::Set(threshold_usd),
+            active: ActiveValue::Set(true),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+
+        mempool_alert::Entity
+            ::insert(alert)
+            .on_conflict(
+                OnConflict::column(mempool_alert::Column::WalletId)
+                    .update_columns([
+                        mempool_alert::Column::ThresholdUsd,
+                        mempool_alert::Column::Active,
+                        mempool_alert::Column::UpdatedAt,
+                    ])
+                    .to_owned()
+            )
+            .exec(&self.db).await?;
+
+        mempool_alert::Entity
+            ::find()
+            .filter(mempool_alert::Column::WalletId.eq(wallet.id))
+            .one(&self.db).await?
+            .ok_or_else(|| AppError::Internal("Mempool alert missing after upsert".to_string()))
+    }
+
+    /// Turn off the mempool alert on a wallet; returns false when there was none
+    pub async fn disable_mempool_alert(&self, user_id: &str, wallet_id: Uuid) -> Result<bool> {
+        let alert = mempool_alert::Entity
+            ::find()
+            .filter(mempool_alert::Column::WalletId.eq(wallet_id))
+            .filter(mempool_alert::Column::UserId.eq(user_id))
+            .filter(mempool_alert::Column::Active.eq(true))
+            .one(&self.db).await?;
+
+        let Some(alert) = alert else {
+            return Ok(false);
+        };
+
+        let mut active: mempool_alert::ActiveModel = alert.into();
+        active.active = ActiveValue::Set(false);
+        active.updated_at = ActiveValue::Set(Utc::now());
+        active.update(&self.db).await?;
+        Ok(true)
+    }
+
+    /// Get all active mempool alerts
+    pub async fn get_active_mempool_alerts(&self) -> Result<Vec<mempool_alert::Model>> {
+        let alerts = mempool_alert::Entity
+            ::find()
+            .filter(mempool_alert::Column::Active.eq(true))
+            .all(&self.db).await?;
+        Ok(alerts)
+    }
+
+    /// Watch `chain` gas and notify once it is at or below `max_gwei`
+    pub async fn create_gas_alert(
+        &self,
+        user_id: &str,
+        chain: &str,
+        max_gwei: f64
+    ) -> Result<gas_alert::Model> {
+        let parsed: Chain = chain.parse()?;
+        if !parsed.is_evm() {
+            return Err(AppError::InvalidInput(format!("Gas alerts are only available for EVM chains, not {}", chain)));
+        }
+        if !max_gwei.is_finite() || max_gwei <= 0.0 {
+            return Err(AppError::InvalidInput("Gas threshold must be a positive Gwei value".to_string()));
+        }
+
+        let now = Utc::now();
+        let alert = gas_alert::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(user_id.to_string()),
+            chain: ActiveValue::Set(parsed.to_string()),
+            max_gwei_threshold: ActiveValue::Set(max_gwei),
+            triggered: ActiveValue::Set(false),
+            active: ActiveValue::Set(true),
+            last_triggered_at: ActiveValue::Set(None),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+
+        Ok(alert.insert(&self.db).await?)
+    }
+
+    pub async fn list_user_gas_alerts(&self, user_id: &str) -> Result<Vec<gas_alert::Model>> {
+        let alerts = gas_alert::Entity
+            ::find()
+            .filter(gas_alert::Column::UserId.eq(user_id))
+            .filter(gas_alert::Column::Active.eq(true))
+            .all(&self.db).await?;
+        Ok(alerts)
+    }
+
+    /// Delete a user's gas alert; returns false when no such alert exists
+    pub async fn delete_gas_alert(&self, id: Uuid, user_id: &str) -> Result<bool> {
+        let result = gas_alert::Entity
+            ::delete_many()
+            .filter(gas_alert::Column::Id.eq(id))
+            .filter(gas_alert::Column::UserId.eq(user_id))
+            .exec(&self.db).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Get all active gas alerts
+    pub async fn get_active_gas_alerts(&self) -> Result<Vec<gas_alert::Model>> {
+        let alerts = gas_alert::Entity
+            ::find()
+            .filter(gas_alert::Column::Active.eq(true))
+            .all(&self.db).await?;
+        Ok(alerts)
+    }
+
+    /// Record whether a gas alert has fired since gas was last above its reset level
+    pub async fn set_gas_alert_triggered(&self, id: Uuid, triggered: bool) -> Result<()> {
+        let now = Utc::now();
+        let mut update = gas_alert::Entity
+            ::update_many()
+            .col_expr(gas_alert::Column::Triggered, Expr::value(triggered))
+            .col_expr(gas_alert::Column::UpdatedAt, Expr::value(now));
+        if triggered {
+            update = update.col_expr(gas_alert::Column::LastTriggeredAt, Expr::value(Some(now)));
+        }
+        update.filter(gas_alert::Column::Id.eq(id)).exec(&self.db).await?;
+        Ok(())
+    }
+}
+
+/// Whether `symbol` is the native token of `chain`, the only asset a stop-loss can sell
+/// without a contract address
+pub fn is_native_symbol(chain: &str, symbol: &str) -> bool {
+    chain
+        .parse::<Chain>()
+        .map(|c| c.native_symbol().eq_ignore_ascii_case(symbol))
+        .unwrap_or(false)
 }
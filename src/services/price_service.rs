@@ -1,13 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 use std::sync::Arc;
+use std::sync::atomic::{ AtomicU64, Ordering };
 use std::time::{ Duration, SystemTime };
-use tokio::sync::RwLock;
+use dashmap::DashMap;
+use tokio::sync::{ watch, RwLock };
 use serde::{ Deserialize, Serialize };
 use crate::error::{ AppError, Result };
+use crate::services::coingecko::CoinGeckoProvider;
+use crate::services::onchain_price_oracle::OnChainPriceOracle;
 
 const BINANCE_API_BASE: &str = "https://api.binance.com/api/v3";
+const COINGECKO_API_BASE: &str = "https://api.coingecko.com/api/v3";
+const EXCHANGE_RATE_API_URL: &str = "https://api.exchangerate-api.com/v4/latest/USD";
 const CACHE_DURATION_SECS: u64 = 60; // Cache prices for 1 minute
+const CHART_CACHE_DURATION_SECS: u64 = 300; // Daily history barely moves; cache for 5 minutes
+const EXCHANGE_RATE_CACHE_DURATION_SECS: u64 = 3600; // Fiat rates update daily; cache for 1 hour
 const MAX_RETRIES: u32 = 3;
+/// Most candles Binance returns for one klines request
+pub const MAX_OHLCV_LIMIT: u32 = 1000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenPrice {
@@ -19,15 +29,149 @@ pub struct TokenPrice {
     pub last_updated: SystemTime,
 }
 
+/// Candle length for `PriceService::get_ohlcv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OhlcvInterval {
+    Min1,
+    Min5,
+    Min15,
+    Hour1,
+    Hour4,
+    Day1,
+}
+
+impl OhlcvInterval {
+    /// Binance klines interval, also what users type ("1h", "4h", ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OhlcvInterval::Min1 => "1m",
+            OhlcvInterval::Min5 => "5m",
+            OhlcvInterval::Min15 => "15m",
+            OhlcvInterval::Hour1 => "1h",
+            OhlcvInterval::Hour4 => "4h",
+            OhlcvInterval::Day1 => "1d",
+        }
+    }
+}
+
+impl std::str::FromStr for OhlcvInterval {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "1m" => Ok(OhlcvInterval::Min1),
+            "5m" => Ok(OhlcvInterval::Min5),
+            "15m" => Ok(OhlcvInterval::Min15),
+            "1h" => Ok(OhlcvInterval::Hour1),
+            "4h" => Ok(OhlcvInterval::Hour4),
+            "1d" => Ok(OhlcvInterval::Day1),
+            _ =>
+                Err(
+                    AppError::InvalidInput(
+                        format!("Unsupported interval: {}. Use 1m, 5m, 15m, 1h, 4h or 1d", s)
+                    )
+                ),
+        }
+    }
+}
+
+/// One OHLCV candle; `open_time` is unix milliseconds and `volume` is in the base asset
+#[derive(Debug, Clone, Serialize)]
+pub struct Candle {
+    pub open_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl Candle {
+    /// Parse one Binance klines row: `[open_time, "open", "high", "low", "close", "volume", ...]`
+    fn from_binance_kline(row: &[serde_json::Value]) -> Option<Self> {
+        let number = |i: usize| row.get(i)?.as_str()?.parse::<f64>().ok();
+        Some(Candle {
+            open_time: row.first()?.as_u64()?,
+            open: number(1)?,
+            high: number(2)?,
+            low: number(3)?,
+            close: number(4)?,
+            volume: number(5)?,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 struct CachedPrice {
     price: TokenPrice,
     fetched_at: SystemTime,
 }
 
+#[derive(Debug, Clone)]
+struct CachedHistory {
+    points: Vec<(u64, f64)>,
+    fetched_at: SystemTime,
+}
+
+/// Price history keyed by `(symbol, days, vs_currency)`
+type HistoryCache = HashMap<(String, u32, String), CachedHistory>;
+
+#[derive(Debug, Clone)]
+struct CachedRates {
+    /// Units of each currency per 1 USD
+    rates: HashMap<String, f64>,
+    fetched_at: SystemTime,
+}
+
+/// Watch channel for one symbol and the ids of everyone subscribed to it
+struct PriceChannel {
+    sender: watch::Sender<Option<TokenPrice>>,
+    subscribers: HashSet<u64>,
+}
+
+/// A registered interest in one symbol's price; pass `id` to `PriceService::unsubscribe` when done
+pub struct PriceSubscription {
+    pub id: u64,
+    pub receiver: watch::Receiver<Option<TokenPrice>>,
+}
+
+impl PriceSubscription {
+    /// Most recently published price, unless it is older than the price cache would keep it
+    pub fn latest(&self) -> Option<TokenPrice> {
+        self.receiver
+            .borrow()
+            .clone()
+            .filter(|price| {
+                price.last_updated
+                    .elapsed()
+                    .map(|age| age.as_secs() < CACHE_DURATION_SECS)
+                    .unwrap_or(false)
+            })
+    }
+}
+
 pub struct PriceService {
     client: reqwest::Client,
     cache: Arc<RwLock<HashMap<String, CachedPrice>>>,
+    history_cache: Arc<RwLock<HistoryCache>>,
+    exchange_rates: Arc<RwLock<Option<CachedRates>>>,
+    coingecko: CoinGeckoProvider,
+    /// Last-resort on-chain prices when Binance and CoinGecko are unreachable
+    oracle: Option<OnChainPriceOracle>,
+    /// Symbols other parts of the crate follow, published to on every cache update
+    subscriptions: DashMap<String, PriceChannel>,
+    next_subscription_id: AtomicU64,
+}
+
+#[derive(Deserialize)]
+struct CoinGeckoMarketChart {
+    /// `[timestamp_ms, price]` pairs
+    prices: Vec<(f64, f64)>,
+}
+
+#[derive(Deserialize)]
+struct ExchangeRateResponse {
+    rates: HashMap<String, f64>,
 }
 
 #[derive(Deserialize)]
@@ -46,7 +190,64 @@ impl PriceService {
         Self {
             client: reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap(),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            history_cache: Arc::new(RwLock::new(HashMap::new())),
+            exchange_rates: Arc::new(RwLock::new(None)),
+            coingecko: CoinGeckoProvider::new(),
+            oracle: None,
+            subscriptions: DashMap::new(),
+            next_subscription_id: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_oracle(mut self, oracle: OnChainPriceOracle) -> Self {
+        self.oracle = Some(oracle);
+        self
+    }
+
+    /// Follow a symbol's price. The receiver starts with the cached price, if any, and sees every
+    /// later fetch; `PriceMonitor` keeps subscribed symbols refreshed.
+    pub fn subscribe(&self, symbol: &str) -> PriceSubscription {
+        let symbol_upper = symbol.to_uppercase();
+        let id = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
+
+        let mut channel = self.subscriptions.entry(symbol_upper.clone()).or_insert_with(|| {
+            let cached = self.cache
+                .try_read()
+                .ok()
+                .and_then(|cache| cache.get(&symbol_upper).map(|c| c.price.clone()));
+            PriceChannel {
+                sender: watch::channel(cached).0,
+                subscribers: HashSet::new(),
+            }
+        });
+        channel.subscribers.insert(id);
+
+        PriceSubscription { id, receiver: channel.sender.subscribe() }
+    }
+
+    /// Drop a subscription; the symbol's channel goes once nobody follows it
+    pub fn unsubscribe(&self, symbol: &str, receiver_id: u64) {
+        let symbol_upper = symbol.to_uppercase();
+        if let Some(mut channel) = self.subscriptions.get_mut(&symbol_upper) {
+            channel.subscribers.remove(&receiver_id);
         }
+        self.subscriptions.remove_if(&symbol_upper, |_, channel| channel.subscribers.is_empty());
+    }
+
+    /// Subscriptions registered for a symbol
+    pub fn subscriber_count(&self, symbol: &str) -> usize {
+        self.subscriptions
+            .get(&symbol.to_uppercase())
+            .map(|channel| channel.subscribers.len())
+            .unwrap_or(0)
+    }
+
+    /// Every symbol with at least one subscriber
+    pub fn subscribed_symbols(&self) -> Vec<String> {
+        self.subscriptions
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect()
     }
 
     /// Get price for a single token by symbol (ETH, BNB, SOL, etc.)
@@ -58,12 +259,20 @@ impl PriceService {
             return Ok(cached);
         }
 
-        // Map to Binance trading pair
-        let binance_symbol = self
-            .symbol_to_binance_pair(&symbol_upper)
-            .ok_or_else(|| AppError::InvalidInput(format!("Unknown token symbol: {}", symbol)))?;
-
-        let price = self.fetch_ticker_24hr(&binance_symbol, &symbol_upper).await?;
+        // Map to Binance trading pair, falling back to on-chain oracles
+        let price = match self.symbol_to_binance_pair(&symbol_upper) {
+            Some(binance_symbol) =>
+                match self.fetch_ticker_24hr(&binance_symbol, &symbol_upper).await {
+                    Ok(price) => price,
+                    Err(e) => self.oracle_price_by_symbol(&symbol_upper).await.ok_or(e)?,
+                }
+            None =>
+                self
+                    .oracle_price_by_symbol(&symbol_upper).await
+                    .ok_or_else(|| {
+                        AppError::InvalidInput(format!("Unknown token symbol: {}", symbol))
+                    })?,
+        };
 
         // Update cache
         self.update_cache(symbol_upper, price.clone()).await;
@@ -99,16 +308,183 @@ impl PriceService {
     }
 
     /// Get price for a token by contract address.
-    /// Binance doesn't support contract address lookups directly,
-    /// so we try to resolve known token addresses to symbols.
+    /// Binance has no contract address lookups, so these go to CoinGecko.
     pub async fn get_token_price_by_address(
         &self,
-        _chain: &str,
-        _address: &str
+        chain: &str,
+        address: &str
     ) -> Result<TokenPrice> {
-        Err(AppError::External(
-            "Token price by contract address is not supported with Binance API. Use token symbol instead.".to_string()
-        ))
+        let err = match self.coingecko.get_price_by_address(chain, address).await {
+            Ok(price) => {
+                return Ok(price);
+            }
+            Err(e) => e,
+        };
+
+        let Some(oracle) = &self.oracle else {
+            return Err(err);
+        };
+        match oracle.get_price(chain, address).await {
+            Ok(usd_price) => Ok(oracle_token_price(address.to_string(), usd_price)),
+            Err(oracle_err) => {
+                tracing::debug!("Oracle fallback for {} on {} failed: {}", address, chain, oracle_err);
+                Err(err)
+            }
+        }
+    }
+
+    async fn oracle_price_by_symbol(&self, symbol: &str) -> Option<TokenPrice> {
+        let oracle = self.oracle.as_ref()?;
+        match oracle.get_price_by_symbol(symbol).await {
+            Ok(usd_price) => Some(oracle_token_price(symbol.to_string(), usd_price)),
+            Err(e) => {
+                tracing::debug!("Oracle fallback for {} failed: {}", symbol, e);
+                None
+            }
+        }
+    }
+
+    /// Daily USD closes for the last `days` days as `(unix_seconds, price)`, oldest first
+    pub async fn get_price_history(&self, symbol: &str, days: u32) -> Result<Vec<(u64, f64)>> {
+        self.get_price_history_in(symbol, days, "usd").await
+    }
+
+    /// Daily closes quoted in `vs_currency` (any CoinGecko quote currency: EUR, GBP, JPY, ...)
+    pub async fn get_price_history_in(
+        &self,
+        symbol: &str,
+        days: u32,
+        vs_currency: &str
+    ) -> Result<Vec<(u64, f64)>> {
+        let symbol_upper = symbol.to_uppercase();
+        let vs_currency = vs_currency.to_lowercase();
+        let key = (symbol_upper.clone(), days, vs_currency.clone());
+
+        if let Some(cached) = self.history_cache.read().await.get(&key) {
+            let age = SystemTime::now()
+                .duration_since(cached.fetched_at)
+                .unwrap_or(Duration::from_secs(999));
+            if age.as_secs() < CHART_CACHE_DURATION_SECS {
+                return Ok(cached.points.clone());
+            }
+        }
+
+        let coin_id = self
+            .symbol_to_coingecko_id(&symbol_upper)
+            .ok_or_else(|| AppError::InvalidInput(format!("No price history for {}", symbol)))?;
+        let url = format!(
+            "{}/coins/{}/market_chart?vs_currency={}&days={}&interval=daily",
+            COINGECKO_API_BASE,
+            coin_id,
+            vs_currency,
+            days
+        );
+
+        let response = self.client
+            .get(&url)
+            .send().await
+            .map_err(|e| AppError::External(format!("CoinGecko API error: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(
+                AppError::External(format!("CoinGecko API returned status: {}", response.status()))
+            );
+        }
+        let chart: CoinGeckoMarketChart = response
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse CoinGecko response: {}", e)))?;
+
+        let points: Vec<(u64, f64)> = chart.prices
+            .into_iter()
+            .map(|(timestamp_ms, price)| ((timestamp_ms / 1000.0) as u64, price))
+            .collect();
+
+        self.history_cache.write().await.insert(key, CachedHistory {
+            points: points.clone(),
+            fetched_at: SystemTime::now(),
+        });
+
+        Ok(points)
+    }
+
+    /// The last `limit` candles for `symbol` against USDT, oldest first
+    pub async fn get_ohlcv(&self, symbol: &str, interval: OhlcvInterval, limit: u32) -> Result<Vec<Candle>> {
+        if limit == 0 || limit > MAX_OHLCV_LIMIT {
+            return Err(
+                AppError::InvalidInput(format!("limit must be between 1 and {}", MAX_OHLCV_LIMIT))
+            );
+        }
+
+        let symbol_upper = symbol.to_uppercase();
+        // Stablecoins map to a placeholder pair that Binance doesn't list
+        let pair = self
+            .symbol_to_binance_pair(&symbol_upper)
+            .filter(|_| !is_stablecoin(&symbol_upper))
+            .ok_or_else(|| AppError::InvalidInput(format!("No candle data for {}", symbol)))?;
+
+        let url = format!(
+            "{}/klines?symbol={}&interval={}&limit={}",
+            BINANCE_API_BASE,
+            pair,
+            interval.as_str(),
+            limit
+        );
+        let response = self.fetch_with_retry(&url).await?;
+        let rows: Vec<Vec<serde_json::Value>> = response
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse Binance klines: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                Candle::from_binance_kline(row).ok_or_else(||
+                    AppError::External("Malformed Binance kline".to_string())
+                )
+            })
+            .collect()
+    }
+
+    /// Convert a USD amount into `currency` at the current exchange rate
+    pub async fn convert_to_fiat(&self, usd_value: f64, currency: &str) -> Result<f64> {
+        Ok(usd_value * self.get_exchange_rate(currency).await?)
+    }
+
+    /// Units of `currency` per 1 USD
+    pub async fn get_exchange_rate(&self, currency: &str) -> Result<f64> {
+        let currency = currency.to_uppercase();
+        if currency == "USD" {
+            return Ok(1.0);
+        }
+
+        let rates = self.get_exchange_rates().await?;
+        rates
+            .get(&currency)
+            .copied()
+            .ok_or_else(|| AppError::InvalidInput(format!("No exchange rate for {}", currency)))
+    }
+
+    async fn get_exchange_rates(&self) -> Result<HashMap<String, f64>> {
+        if let Some(cached) = self.exchange_rates.read().await.as_ref() {
+            let age = SystemTime::now()
+                .duration_since(cached.fetched_at)
+                .unwrap_or(Duration::from_secs(u64::MAX));
+            if age.as_secs() < EXCHANGE_RATE_CACHE_DURATION_SECS {
+                return Ok(cached.rates.clone());
+            }
+        }
+
+        let data: ExchangeRateResponse = self.client
+            .get(EXCHANGE_RATE_API_URL)
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("Exchange rate API error: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse exchange rates: {}", e)))?;
+
+        *self.exchange_rates.write().await = Some(CachedRates {
+            rates: data.rates.clone(),
+            fetched_at: SystemTime::now(),
+        });
+
+        Ok(data.rates)
     }
 
     async fn get_from_cache(&self, symbol: &str) -> Option<TokenPrice> {
@@ -126,6 +502,10 @@ impl PriceService {
     }
 
     async fn update_cache(&self, symbol: String, price: TokenPrice) {
+        if let Some(channel) = self.subscriptions.get(&symbol) {
+            channel.sender.send_replace(Some(price.clone()));
+        }
+
         let mut cache = self.cache.write().await;
         cache.insert(symbol, CachedPrice {
             price,
@@ -161,7 +541,7 @@ impl PriceService {
 
     async fn fetch_ticker_24hr(&self, binance_symbol: &str, symbol: &str) -> Result<TokenPrice> {
         // Stablecoins pegged to USD
-        if matches!(symbol, "USDT" | "USDC" | "DAI" | "BUSD") {
+        if is_stablecoin(symbol) {
             return Ok(TokenPrice {
                 symbol: symbol.to_string(),
                 usd_price: 1.0,
@@ -237,7 +617,7 @@ impl PriceService {
         let mut results = HashMap::new();
         for (symbol, binance_pair) in &pairs {
             // Handle stablecoins
-            if matches!(symbol.as_str(), "USDT" | "USDC" | "DAI" | "BUSD") {
+            if is_stablecoin(symbol) {
                 results.insert((*symbol).clone(), TokenPrice {
                     symbol: (*symbol).clone(),
                     usd_price: 1.0,
@@ -271,7 +651,7 @@ impl PriceService {
     /// Map a token symbol to a Binance USDT trading pair.
     fn symbol_to_binance_pair(&self, symbol: &str) -> Option<String> {
         // Stablecoins — return a dummy pair; handled specially in fetch methods
-        if matches!(symbol, "USDT" | "USDC" | "DAI" | "BUSD") {
+        if is_stablecoin(symbol) {
             return Some(format!("{}USDT", symbol));
         }
 
@@ -323,6 +703,58 @@ impl PriceService {
         };
         Some(format!("{}USDT", base))
     }
+
+    /// Map a token symbol to its CoinGecko coin id
+    fn symbol_to_coingecko_id(&self, symbol: &str) -> Option<&'static str> {
+        let id = match symbol {
+            "ETH" | "ETHEREUM" | "WETH" => "ethereum",
+            "BNB" | "BSC" | "WBNB" => "binancecoin",
+            "SOL" | "SOLANA" => "solana",
+            "BTC" | "BITCOIN" => "bitcoin",
+            "MATIC" | "POL" => "matic-network",
+            "AVAX" => "avalanche-2",
+            "LINK" => "chainlink",
+            "UNI" => "uniswap",
+            "AAVE" => "aave",
+            "SHIB" => "shiba-inu",
+            "DOGE" => "dogecoin",
+            "DOT" => "polkadot",
+            "ADA" => "cardano",
+            "XRP" => "ripple",
+            "ARB" => "arbitrum",
+            "OP" => "optimism",
+            "APT" => "aptos",
+            "SUI" => "sui",
+            "ATOM" => "cosmos",
+            "NEAR" => "near",
+            "FTM" => "fantom",
+            "CRO" | "CRONOS" => "crypto-com-chain",
+            "XDAI" | "GNOSIS" => "xdai",
+            "CRV" => "curve-dao-token",
+            "MKR" => "maker",
+            "LDO" => "lido-dao",
+            "PEPE" => "pepe",
+            "WIF" => "dogwifcoin",
+            "JUP" => "jupiter-exchange-solana",
+            "BONK" => "bonk",
+            "RAY" => "raydium",
+            "CAKE" => "pancakeswap-token",
+            "INJ" => "injective-protocol",
+            "TIA" => "celestia",
+            "RENDER" | "RNDR" => "render-token",
+            "FET" => "fetch-ai",
+            "GRT" => "the-graph",
+            "SNX" => "havven",
+            "COMP" => "compound-governance-token",
+            "SUSHI" => "sushi",
+            "1INCH" => "1inch",
+            "USDT" => "tether",
+            "USDC" => "usd-coin",
+            "DAI" => "dai",
+            _ => return None,
+        };
+        Some(id)
+    }
 }
 
 impl Default for PriceService {
@@ -330,3 +762,55 @@ impl Default for PriceService {
         Self::new()
     }
 }
+
+/// Oracles only report a spot price; 24h change and volume are unknown
+/// USD-pegged stablecoins, priced at $1 without a lookup
+pub fn is_stablecoin(symbol: &str) -> bool {
+    matches!(symbol.to_uppercase().as_str(), "USDT" | "USDC" | "DAI" | "BUSD")
+}
+
+fn oracle_token_price(symbol: String, usd_price: f64) -> TokenPrice {
+    TokenPrice {
+        symbol,
+        usd_price,
+        price_change_24h: None,
+        market_cap: None,
+        volume_24h: None,
+        last_updated: SystemTime::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_cache_updates() {
+        let service = PriceService::new();
+        let subscription = service.subscribe("eth");
+        assert_eq!(service.subscriber_count("ETH"), 1);
+        assert!(subscription.latest().is_none());
+
+        service.update_cache("ETH".to_string(), oracle_token_price("ETH".to_string(), 3000.0)).await;
+        assert_eq!(subscription.latest().map(|p| p.usd_price), Some(3000.0));
+
+        service.unsubscribe("ETH", subscription.id);
+        assert_eq!(service.subscriber_count("ETH"), 0);
+        assert!(service.subscribed_symbols().is_empty());
+    }
+
+    #[test]
+    fn test_parse_binance_kline() {
+        let row: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[1700000000000, "2000.5", "2010.0", "1995.25", "2005.0", "123.4", 1700003599999, "0", 10, "0", "0", "0"]"#
+        ).unwrap();
+        let candle = Candle::from_binance_kline(&row).unwrap();
+        assert_eq!(candle.open_time, 1700000000000);
+        assert_eq!(candle.high, 2010.0);
+        assert_eq!(candle.low, 1995.25);
+        assert_eq!(candle.volume, 123.4);
+
+        assert!(Candle::from_binance_kline(&row[..3]).is_none());
+        assert_eq!("1H".parse::<OhlcvInterval>().unwrap(), OhlcvInterval::Hour1);
+    }
+}
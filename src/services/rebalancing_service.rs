@@ -0,0 +1,221 @@
+use crate::db::entity::rebalancing_alert;
+use crate::error::{ AppError, Result };
+use crate::services::portfolio_service::Portfolio;
+use chrono::{ Duration, Utc };
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    DatabaseConnection,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+};
+use uuid::Uuid;
+
+/// Deviation allowed before a rebalancing alert fires, when the user doesn't specify one
+pub const DEFAULT_DEVIATION_THRESHOLD_PCT: f64 = 5.0;
+
+/// Minimum time between repeated notifications for the same target
+const NOTIFY_COOLDOWN_HOURS: i64 = 24;
+
+#[derive(Clone)]
+pub struct RebalancingService {
+    db: DatabaseConnection,
+}
+
+/// An asset's current share of the portfolio compared with the user's target
+#[derive(Debug, Clone)]
+pub struct AllocationStatus {
+    pub symbol: String,
+    pub actual_pct: f64,
+    pub target_pct: f64,
+    pub threshold_pct: f64,
+    /// Units to sell (positive) or buy (negative) to reach the target
+    pub rebalance_amount: f64,
+}
+
+impl AllocationStatus {
+    pub fn needs_rebalance(&self) -> bool {
+        (self.actual_pct - self.target_pct).abs() > self.threshold_pct
+    }
+}
+
+impl RebalancingService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Set (or replace) the target allocation for a symbol
+    pub async fn set_target(
+        &self,
+        user_id: &str,
+        symbol: &str,
+        target_pct: f64,
+        threshold: f64
+    ) -> Result<rebalancing_alert::Model> {
+        if !(0.0..=100.0).contains(&target_pct) {
+            return Err(AppError::Validation("Target must be between 0 and 100%".to_string()));
+        }
+        if threshold <= 0.0 || threshold > 100.0 {
+            return Err(AppError::Validation("Threshold must be between 0 and 100%".to_string()));
+        }
+
+        let symbol = symbol.to_uppercase();
+        let now = Utc::now();
+
+        let existing = rebalancing_alert::Entity
+            ::find()
+            .filter(rebalancing_alert::Column::UserId.eq(user_id))
+            .filter(rebalancing_alert::Column::Symbol.eq(&symbol))
+            .one(&self.db).await?;
+
+        let model = match existing {
+            Some(existing) => {
+                let mut active: rebalancing_alert::ActiveModel = existing.into();
+                active.target_pct = ActiveValue::Set(target_pct);
+                active.deviation_threshold_pct = ActiveValue::Set(threshold);
+                active.last_notified_at = ActiveValue::Set(None);
+                active.updated_at = ActiveValue::Set(now);
+                active.update(&self.db).await?
+            }
+            None => {
+                let active = rebalancing_alert::ActiveModel {
+                    id: ActiveValue::Set(Uuid::new_v4()),
+                    user_id: ActiveValue::Set(user_id.to_string()),
+                    symbol: ActiveValue::Set(symbol),
+                    target_pct: ActiveValue::Set(target_pct),
+                    deviation_threshold_pct: ActiveValue::Set(threshold),
+                    last_notified_at: ActiveValue::Set(None),
+                    created_at: ActiveValue::Set(now),
+                    updated_at: ActiveValue::Set(now),
+                };
+                active.insert(&self.db).await?
+            }
+        };
+
+        Ok(model)
+    }
+
+    /// A user's allocation targets
+    pub async fn list_targets(&self, user_id: &str) -> Result<Vec<rebalancing_alert::Model>> {
+        let targets = rebalancing_alert::Entity
+            ::find()
+            .filter(rebalancing_alert::Column::UserId.eq(user_id))
+            .order_by_asc(rebalancing_alert::Column::Symbol)
+            .all(&self.db).await?;
+        Ok(targets)
+    }
+
+    /// Every user's allocation targets, for the background checker
+    pub async fn get_all_targets(&self) -> Result<Vec<rebalancing_alert::Model>> {
+        let targets = rebalancing_alert::Entity::find().all(&self.db).await?;
+        Ok(targets)
+    }
+
+    /// Whether a target is outside its notification cooldown
+    pub fn can_notify(target: &rebalancing_alert::Model) -> bool {
+        target.last_notified_at
+            .map(|t| Utc::now() - t > Duration::hours(NOTIFY_COOLDOWN_HOURS))
+            .unwrap_or(true)
+    }
+
+    /// Record that the user was told about a deviation
+    pub async fn mark_notified(&self, id: Uuid) -> Result<()> {
+        if let Some(target) = rebalancing_alert::Entity::find_by_id(id).one(&self.db).await? {
+            let mut active: rebalancing_alert::ActiveModel = target.into();
+            active.last_notified_at = ActiveValue::Set(Some(Utc::now()));
+            active.update(&self.db).await?;
+        }
+        Ok(())
+    }
+
+    /// Compare a portfolio's actual allocation with the user's targets
+    pub fn compute_allocations(
+        portfolio: &Portfolio,
+        targets: &[rebalancing_alert::Model]
+    ) -> Vec<AllocationStatus> {
+        if portfolio.total_usd_value <= 0.0 {
+            return Vec::new();
+        }
+
+        targets
+            .iter()
+            .map(|target| {
+                let holding = portfolio.holdings
+                    .iter()
+                    .find(|h| h.symbol.eq_ignore_ascii_case(&target.symbol));
+                let usd_value = holding.map(|h| h.usd_value).unwrap_or(0.0);
+                let actual_pct = (usd_value / portfolio.total_usd_value) * 100.0;
+
+                let excess_usd = ((actual_pct - target.target_pct) / 100.0) * portfolio.total_usd_value;
+                let rebalance_amount = match holding {
+                    Some(h) if h.usd_price > 0.0 => excess_usd / h.usd_price,
+                    _ => 0.0,
+                };
+
+                AllocationStatus {
+                    symbol: target.symbol.clone(),
+                    actual_pct,
+                    target_pct: target.target_pct,
+                    threshold_pct: target.deviation_threshold_pct,
+                    rebalance_amount,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::portfolio_service::TokenHolding;
+
+    fn holding(symbol: &str, usd_value: f64, usd_price: f64) -> TokenHolding {
+        TokenHolding {
+            symbol: symbol.to_string(),
+            name: None,
+            total_balance: usd_value / usd_price,
+            usd_value,
+            usd_price,
+            price_change_24h: None,
+            logo_url: None,
+            wallets: Vec::new(),
+        }
+    }
+
+    fn target(symbol: &str, target_pct: f64, threshold: f64) -> rebalancing_alert::Model {
+        rebalancing_alert::Model {
+            id: Uuid::new_v4(),
+            user_id: "1".to_string(),
+            symbol: symbol.to_string(),
+            target_pct,
+            deviation_threshold_pct: threshold,
+            last_notified_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_compute_allocations() {
+        let portfolio = Portfolio {
+            user_id: "1".to_string(),
+            holdings: vec![holding("ETH", 3500.0, 2000.0), holding("SOL", 6500.0, 100.0)],
+            total_usd_value: 10000.0,
+            chains: Vec::new(),
+            wallet_count: 2,
+        };
+        let targets = vec![target("ETH", 25.0, 5.0), target("SOL", 70.0, 10.0)];
+
+        let allocations = RebalancingService::compute_allocations(&portfolio, &targets);
+
+        assert!((allocations[0].actual_pct - 35.0).abs() < 1e-9);
+        assert!(allocations[0].needs_rebalance());
+        // 10% of $10k over target at $2000/ETH
+        assert!((allocations[0].rebalance_amount - 0.5).abs() < 1e-9);
+
+        assert!(!allocations[1].needs_rebalance());
+        assert!(allocations[1].rebalance_amount < 0.0);
+    }
+}
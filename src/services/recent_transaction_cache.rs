@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use uuid::Uuid;
+
+use crate::db::entity::transaction;
+
+/// How long a wallet's recent transactions are served from memory
+pub const RECENT_TRANSACTIONS_TTL: Duration = Duration::from_secs(30);
+
+/// Newest transactions kept per wallet; requests for a deeper page go to the database
+pub const RECENT_TRANSACTIONS_CACHED: u64 = 50;
+
+struct CachedTransactions {
+    transactions: Vec<transaction::Model>,
+    fetched_at: Instant,
+}
+
+/// Short-lived per-wallet cache of the newest transactions, so switching between the history
+/// view and wallet actions doesn't re-query the database each time
+pub struct RecentTransactionCache {
+    entries: DashMap<Uuid, CachedTransactions>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RecentTransactionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Up to `limit` of the wallet's newest transactions, if they were cached within the TTL
+    pub fn get(&self, wallet_id: Uuid, limit: u64) -> Option<Vec<transaction::Model>> {
+        let cached = self.entries
+            .get(&wallet_id)
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.transactions.iter().take(limit as usize).cloned().collect());
+
+        let counter = if cached.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    /// Store the wallet's newest transactions, as returned by the repository
+    pub fn insert(&self, wallet_id: Uuid, transactions: Vec<transaction::Model>) {
+        self.entries.insert(wallet_id, CachedTransactions {
+            transactions,
+            fetched_at: Instant::now(),
+        });
+    }
+
+    /// Drop the wallet's entry after any of its transactions is written
+    pub fn invalidate(&self, wallet_id: Uuid) {
+        self.entries.remove(&wallet_id);
+    }
+
+    /// Share of lookups answered from memory since startup, 0.0 before the first lookup
+    pub fn cache_hit_rate(&self) -> f64 {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let total = hits + self.misses.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        hits as f64 / total as f64
+    }
+}
+
+impl Default for RecentTransactionCache {
+    fn default() -> Self {
+        Self::new(RECENT_TRANSACTIONS_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_rate_and_invalidation() {
+        let cache = RecentTransactionCache::default();
+        let wallet_id = Uuid::new_v4();
+
+        assert!(cache.get(wallet_id, 10).is_none());
+        cache.insert(wallet_id, vec![]);
+        assert!(cache.get(wallet_id, 10).is_some());
+        assert_eq!(cache.cache_hit_rate(), 0.5);
+
+        cache.invalidate(wallet_id);
+        assert!(cache.get(wallet_id, 10).is_none());
+    }
+
+    #[test]
+    fn test_expired_entry_is_a_miss() {
+        let cache = RecentTransactionCache::new(Duration::ZERO);
+        let wallet_id = Uuid::new_v4();
+
+        cache.insert(wallet_id, vec![]);
+        assert!(cache.get(wallet_id, 10).is_none());
+    }
+}
@@ -1,7 +1,13 @@
-use crate::db::entity::scheduled_transaction;
-use crate::enums::{ ScheduleStatus, RecurringType };
-use crate::error::Result;
-use chrono::{ DateTime, Duration, Utc };
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::db::entity::{ scheduled_transaction, wallet };
+use crate::enums::{ Chain, ScheduleStatus, RecurringType };
+use crate::error::{ AppError, Result };
+use crate::services::address_book_service::is_valid_address;
+use crate::services::balance_service::BalanceService;
+use crate::services::gas_estimation_service::GasEstimationService;
+use chrono::{ DateTime, Duration, Months, Utc };
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
@@ -10,14 +16,45 @@ use sea_orm::{
     EntityTrait,
     QueryFilter,
 };
+use serde::{ Deserialize, Serialize };
 use uuid::Uuid;
 
+/// Longest range a calendar can span, so a daily schedule can't expand without bound
+const MAX_CALENDAR_DAYS: i64 = 366;
+
+/// Schedules further out than this get a warning, since balances and fees will have moved by then
+const MAX_SCHEDULE_AHEAD_DAYS: i64 = 365;
+
 #[derive(Clone)]
 pub struct SchedulingService {
     db: DatabaseConnection,
+    balance_service: Option<Arc<BalanceService>>,
+    gas_estimation_service: Option<Arc<GasEstimationService>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleWarningSeverity {
+    Info,
+    Warning,
+    /// The schedule can never run as requested and must not be created
+    Critical,
+}
+
+/// One problem found by `validate_schedule`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleWarning {
+    pub severity: ScheduleWarningSeverity,
+    pub message: String,
 }
 
-#[derive(Debug, Clone)]
+impl ScheduleWarning {
+    fn new(severity: ScheduleWarningSeverity, message: impl Into<String>) -> Self {
+        Self { severity, message: message.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduleRequest {
     pub user_id: String,
     pub wallet_id: Uuid,
@@ -26,11 +63,303 @@ pub struct ScheduleRequest {
     pub token_address: Option<String>,
     pub scheduled_for: DateTime<Utc>,
     pub recurring_type: Option<RecurringType>,
+    pub max_gas_price_gwei: Option<f64>,
+}
+
+/// Dry run of a schedule against the wallet as it is now
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleSimulation {
+    pub would_succeed: bool,
+    pub estimated_gas_native: String,
+    pub estimated_fee_usd: f64,
+    /// Current balance of the asset being sent; the balance at run time can't be known
+    pub balance_at_schedule_time: String,
+    pub warning_messages: Vec<String>,
+}
+
+/// Fees can rise before a schedule runs, so the native balance should cover this many times
+/// today's estimate
+const FEE_HEADROOM_MULTIPLIER: f64 = 2.0;
+
+/// One upcoming run of a schedule; recurring schedules produce one per occurrence
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledExecution {
+    pub schedule_id: Uuid,
+    pub wallet_id: Uuid,
+    pub to_address: String,
+    pub amount: String,
+    /// Token contract address, or the chain's native symbol for native sends
+    pub token: String,
+    pub execute_at: DateTime<Utc>,
+    pub recurring_type: Option<String>,
 }
 
 impl SchedulingService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            balance_service: None,
+            gas_estimation_service: None,
+        }
+    }
+
+    /// Enable the balance and fee checks in `validate_schedule`
+    pub fn with_preflight_checks(
+        mut self,
+        balance_service: Arc<BalanceService>,
+        gas_estimation_service: Arc<GasEstimationService>
+    ) -> Self {
+        self.balance_service = Some(balance_service);
+        self.gas_estimation_service = Some(gas_estimation_service);
+        self
+    }
+
+    /// Check a schedule before it is stored. Any `Critical` warning means it must not be
+    /// created; the rest are for the user to acknowledge. Balance and fee checks reflect the
+    /// wallet today and are skipped unless `with_preflight_checks` was set.
+    pub async fn validate_schedule(&self, req: &ScheduleRequest) -> Result<Vec<ScheduleWarning>> {
+        let wallet = wallet::Entity
+            ::find_by_id(req.wallet_id)
+            .one(&self.db).await?
+            .ok_or(AppError::WalletNotFound)?;
+        let chain: Chain = wallet.chain.parse()?;
+
+        let mut warnings = Vec::new();
+
+        if !is_valid_address(chain, &req.to_address) {
+            warnings.push(
+                ScheduleWarning::new(
+                    ScheduleWarningSeverity::Critical,
+                    format!("{} is not a valid {} address", req.to_address, chain)
+                )
+            );
+        }
+
+        let amount: f64 = match req.amount.parse() {
+            Ok(a) if a > 0.0 => a,
+            _ => {
+                warnings.push(
+                    ScheduleWarning::new(
+                        ScheduleWarningSeverity::Critical,
+                        format!("{} is not a valid amount", req.amount)
+                    )
+                );
+                return Ok(warnings);
+            }
+        };
+
+        if req.scheduled_for - Utc::now() > Duration::days(MAX_SCHEDULE_AHEAD_DAYS) {
+            warnings.push(
+                ScheduleWarning::new(
+                    ScheduleWarningSeverity::Warning,
+                    "Scheduled more than a year ahead; balance and fees may be very different by then"
+                )
+            );
+        }
+
+        if let (Some(recurring), Some(gas)) = (req.recurring_type, &self.gas_estimation_service) {
+            let period = Self::next_run(recurring, req.scheduled_for) - req.scheduled_for;
+            if let Some(times) = gas.confirmation_times(&wallet.chain, wallet.is_testnet).await {
+                if (period.num_seconds() as f64) < times.slow_secs {
+                    warnings.push(
+                        ScheduleWarning::new(
+                            ScheduleWarningSeverity::Warning,
+                            format!(
+                                "Runs {} but a {} transaction can take up to {:.0}s to confirm; runs may overlap",
+                                recurring,
+                                chain,
+                                times.slow_secs
+                            )
+                        )
+                    );
+                }
+            }
+        }
+
+        let (Some(balances), Some(gas)) = (&self.balance_service, &self.gas_estimation_service) else {
+            return Ok(warnings);
+        };
+
+        let native = match balances.get_balance(req.wallet_id, None).await {
+            Ok(native) => native,
+            Err(e) => {
+                warnings.push(
+                    ScheduleWarning::new(
+                        ScheduleWarningSeverity::Warning,
+                        format!("Couldn't check the wallet balance: {}", e.user_facing_message())
+                    )
+                );
+                return Ok(warnings);
+            }
+        };
+        let native_available: f64 = native.balance.parse().unwrap_or(0.0);
+
+        if let Some(token_addr) = &req.token_address {
+            match balances.get_balance(req.wallet_id, Some(token_addr.clone())).await {
+                Ok(token) if token.balance.parse::<f64>().unwrap_or(0.0) < amount => {
+                    warnings.push(
+                        ScheduleWarning::new(
+                            ScheduleWarningSeverity::Warning,
+                            format!(
+                                "Balance {} {} doesn't currently cover {} {}",
+                                token.balance,
+                                token.symbol,
+                                req.amount,
+                                token.symbol
+                            )
+                        )
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warnings.push(
+                        ScheduleWarning::new(
+                            ScheduleWarningSeverity::Warning,
+                            format!("Couldn't check the token balance: {}", e.user_facing_message())
+                        )
+                    );
+                }
+            }
+        }
+
+        let estimate = gas.estimate_transaction_fee(
+            req.wallet_id,
+            &req.to_address,
+            &req.amount,
+            req.token_address.as_deref()
+        ).await;
+        match estimate {
+            Ok(estimate) => {
+                let fee: f64 = estimate.gas_estimate.total_cost_native.parse().unwrap_or(0.0);
+                let native_amount = if req.token_address.is_some() { 0.0 } else { amount };
+                if native_available < native_amount + fee {
+                    let message = if native_amount > 0.0 {
+                        format!(
+                            "Balance {} {} doesn't currently cover {} {} plus ~{} {} in fees",
+                            native.balance,
+                            native.symbol,
+                            req.amount,
+                            native.symbol,
+                            estimate.gas_estimate.total_cost_native,
+                            native.symbol
+                        )
+                    } else {
+                        format!(
+                            "Balance {} {} doesn't currently cover the ~{} {} network fee",
+                            native.balance,
+                            native.symbol,
+                            estimate.gas_estimate.total_cost_native,
+                            native.symbol
+                        )
+                    };
+                    warnings.push(ScheduleWarning::new(ScheduleWarningSeverity::Warning, message));
+                }
+            }
+            Err(e) => {
+                warnings.push(
+                    ScheduleWarning::new(
+                        ScheduleWarningSeverity::Warning,
+                        format!("Couldn't estimate the network fee: {}", e.user_facing_message())
+                    )
+                );
+            }
+        }
+
+        Ok(warnings)
+    }
+
+    /// Check a schedule against the wallet's current balance and today's fees without storing
+    /// it. Needs `with_preflight_checks`.
+    pub async fn simulate_schedule(&self, req: &ScheduleRequest) -> Result<ScheduleSimulation> {
+        let (Some(balances), Some(gas)) = (&self.balance_service, &self.gas_estimation_service) else {
+            return Err(AppError::Config("Schedule simulation is not available".to_string()));
+        };
+        let wallet = wallet::Entity
+            ::find_by_id(req.wallet_id)
+            .one(&self.db).await?
+            .ok_or(AppError::WalletNotFound)?;
+        let chain: Chain = wallet.chain.parse()?;
+
+        let mut would_succeed = true;
+        let mut warning_messages = Vec::new();
+
+        if !is_valid_address(chain, &req.to_address) {
+            would_succeed = false;
+            warning_messages.push(format!("{} is not a valid {} address", req.to_address, chain));
+        }
+        let amount = match req.amount.parse::<f64>() {
+            Ok(a) if a > 0.0 => a,
+            _ => {
+                would_succeed = false;
+                warning_messages.push(format!("{} is not a valid amount", req.amount));
+                0.0
+            }
+        };
+
+        let native = balances.get_balance(req.wallet_id, None).await?;
+        let native_available: f64 = native.balance.parse().unwrap_or(0.0);
+        let mut balance_at_schedule_time = format!("{} {}", native.balance, native.symbol);
+
+        if let Some(token_addr) = &req.token_address {
+            let token = balances.get_balance(req.wallet_id, Some(token_addr.clone())).await?;
+            if token.balance.parse::<f64>().unwrap_or(0.0) < amount {
+                would_succeed = false;
+                warning_messages.push(
+                    format!("Balance {} {} doesn't cover {} {}", token.balance, token.symbol, req.amount, token.symbol)
+                );
+            }
+            balance_at_schedule_time = format!("{} {}", token.balance, token.symbol);
+        }
+
+        let (estimated_gas_native, estimated_fee_usd) = match
+            gas.estimate_transaction_fee(
+                req.wallet_id,
+                &req.to_address,
+                &req.amount,
+                req.token_address.as_deref()
+            ).await
+        {
+            Ok(estimate) => {
+                let fee: f64 = estimate.gas_estimate.total_cost_native.parse().unwrap_or(0.0);
+                let native_amount = if req.token_address.is_some() { 0.0 } else { amount };
+
+                if native_available < native_amount + fee {
+                    would_succeed = false;
+                    warning_messages.push(
+                        format!(
+                            "Balance {} {} doesn't cover the amount plus ~{} {} in fees",
+                            native.balance,
+                            native.symbol,
+                            estimate.gas_estimate.total_cost_native,
+                            native.symbol
+                        )
+                    );
+                } else if !covers_fee_headroom(native_available, native_amount, fee) {
+                    warning_messages.push(
+                        format!(
+                            "Balance {} {} leaves little room if fees rise before the schedule runs",
+                            native.balance,
+                            native.symbol
+                        )
+                    );
+                }
+
+                (estimate.gas_estimate.total_cost_native, estimate.gas_estimate.total_cost_usd.unwrap_or(0.0))
+            }
+            Err(e) => {
+                would_succeed = false;
+                warning_messages.push(format!("Couldn't estimate the network fee: {}", e.user_facing_message()));
+                ("0".to_string(), 0.0)
+            }
+        };
+
+        Ok(ScheduleSimulation {
+            would_succeed,
+            estimated_gas_native,
+            estimated_fee_usd,
+            balance_at_schedule_time,
+            warning_messages,
+        })
     }
 
     /// Schedule a new transaction (one-time or recurring)
@@ -53,6 +382,7 @@ impl SchedulingService {
             executed_at: ActiveValue::Set(None),
             tx_hash: ActiveValue::Set(None),
             error_message: ActiveValue::Set(None),
+            max_gas_price_gwei: ActiveValue::Set(req.max_gas_price_gwei),
             created_at: ActiveValue::Set(now),
             updated_at: ActiveValue::Set(now),
         };
@@ -117,8 +447,12 @@ impl SchedulingService {
         Ok(schedules)
     }
 
-    /// Mark a transaction as executed
-    pub async fn mark_executed(&self, id: Uuid, tx_hash: String) -> Result<()> {
+    /// Mark a transaction as executed, returning the next occurrence if it recurs
+    pub async fn mark_executed(
+        &self,
+        id: Uuid,
+        tx_hash: String
+    ) -> Result<Option<scheduled_transaction::Model>> {
         let schedule = scheduled_transaction::Entity::find_by_id(id).one(&self.db).await?;
 
         if let Some(schedule) = schedule {
@@ -131,11 +465,11 @@ impl SchedulingService {
 
             // If recurring, create next schedule
             if let Some(recurring_type) = &schedule.recurring_type {
-                self.create_next_recurring_schedule(&schedule, recurring_type).await?;
+                return self.create_next_recurring_schedule(&schedule, recurring_type).await;
             }
         }
 
-        Ok(())
+        Ok(None)
     }
 
     /// Mark a transaction as failed
@@ -154,22 +488,118 @@ impl SchedulingService {
         Ok(())
     }
 
+    /// Cancel a schedule the executor gave up on, recording why
+    pub async fn expire_schedule(&self, id: Uuid, reason: String) -> Result<()> {
+        let schedule = scheduled_transaction::Entity::find_by_id(id).one(&self.db).await?;
+
+        if let Some(schedule) = schedule {
+            let mut active: scheduled_transaction::ActiveModel = schedule.into();
+            active.status = ActiveValue::Set(ScheduleStatus::Cancelled.to_string());
+            active.error_message = ActiveValue::Set(Some(reason));
+            active.updated_at = ActiveValue::Set(Utc::now());
+            active.update(&self.db).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Every pending run between `from` and `to`, with recurring schedules expanded, sorted by time
+    pub async fn get_execution_calendar(
+        &self,
+        user_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<Vec<ScheduledExecution>> {
+        if to < from {
+            return Err(AppError::InvalidInput("Calendar end is before its start".to_string()));
+        }
+        if to - from > Duration::days(MAX_CALENDAR_DAYS) {
+            return Err(
+                AppError::InvalidInput(
+                    format!("Calendar range can't exceed {} days", MAX_CALENDAR_DAYS)
+                )
+            );
+        }
+
+        let schedules = self.list_scheduled(user_id, Some(ScheduleStatus::Pending.as_str())).await?;
+
+        let wallet_ids: Vec<Uuid> = schedules.iter().map(|s| s.wallet_id).collect();
+        let native_symbols: HashMap<Uuid, String> = wallet::Entity
+            ::find()
+            .filter(wallet::Column::Id.is_in(wallet_ids))
+            .all(&self.db).await?
+            .into_iter()
+            .map(|w| {
+                let symbol = w.chain
+                    .parse::<Chain>()
+                    .map(|c| c.native_symbol().to_string())
+                    .unwrap_or_else(|_| w.chain.to_uppercase());
+                (w.id, symbol)
+            })
+            .collect();
+
+        let mut executions = Vec::new();
+        for schedule in schedules {
+            let token = schedule.token_address
+                .clone()
+                .or_else(|| native_symbols.get(&schedule.wallet_id).cloned())
+                .unwrap_or_default();
+            let recurring = schedule.recurring_type
+                .as_deref()
+                .and_then(|r| r.parse::<RecurringType>().ok());
+
+            // Step the same way the executor does, so the calendar matches what will actually run
+            let mut execute_at = schedule.scheduled_for;
+            while execute_at <= to {
+                if execute_at >= from {
+                    executions.push(ScheduledExecution {
+                        schedule_id: schedule.id,
+                        wallet_id: schedule.wallet_id,
+                        to_address: schedule.to_address.clone(),
+                        amount: schedule.amount.clone(),
+                        token: token.clone(),
+                        execute_at,
+                        recurring_type: schedule.recurring_type.clone(),
+                    });
+                }
+                match recurring {
+                    Some(recurring) => {
+                        execute_at = Self::next_run(recurring, execute_at);
+                    }
+                    None => {
+                        break;
+                    }
+                }
+            }
+        }
+
+        executions.sort_by_key(|e| e.execute_at);
+        Ok(executions)
+    }
+
+    /// When a schedule recurring at `recurring_type` runs next after `from`.
+    /// Monthly runs land on the same day next month, clamped to the month's last day (Jan 31 → Feb 28).
+    pub fn next_run(recurring_type: RecurringType, from: DateTime<Utc>) -> DateTime<Utc> {
+        match recurring_type {
+            RecurringType::Daily => from + Duration::days(1),
+            RecurringType::Weekly => from + Duration::weeks(1),
+            RecurringType::Monthly =>
+                from.checked_add_months(Months::new(1)).unwrap_or(from + Duration::days(30)),
+        }
+    }
+
     /// Create the next recurring schedule
     async fn create_next_recurring_schedule(
         &self,
         schedule: &scheduled_transaction::Model,
         recurring_type: &str
-    ) -> Result<()> {
+    ) -> Result<Option<scheduled_transaction::Model>> {
         let parsed = match recurring_type.parse::<RecurringType>() {
             Ok(r) => r,
-            Err(_) => return Ok(()),
+            Err(_) => return Ok(None),
         };
 
-        let next_time = match parsed {
-            RecurringType::Daily => schedule.scheduled_for + Duration::days(1),
-            RecurringType::Weekly => schedule.scheduled_for + Duration::weeks(1),
-            RecurringType::Monthly => schedule.scheduled_for + Duration::days(30),
-        };
+        let next_time = Self::next_run(parsed, schedule.scheduled_for);
 
         let next_schedule = scheduled_transaction::ActiveModel {
             id: ActiveValue::Set(Uuid::new_v4()),
@@ -184,11 +614,42 @@ impl SchedulingService {
             executed_at: ActiveValue::Set(None),
             tx_hash: ActiveValue::Set(None),
             error_message: ActiveValue::Set(None),
+            max_gas_price_gwei: ActiveValue::Set(schedule.max_gas_price_gwei),
             created_at: ActiveValue::Set(Utc::now()),
             updated_at: ActiveValue::Set(Utc::now()),
         };
 
-        next_schedule.insert(&self.db).await?;
-        Ok(())
+        Ok(Some(next_schedule.insert(&self.db).await?))
+    }
+}
+
+/// Whether `available` still covers the send if fees reach `FEE_HEADROOM_MULTIPLIER` times `fee`
+fn covers_fee_headroom(available: f64, native_amount: f64, fee: f64) -> bool {
+    available >= native_amount + fee * FEE_HEADROOM_MULTIPLIER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fee_headroom_needs_twice_the_fee() {
+        assert!(covers_fee_headroom(1.2, 1.0, 0.1));
+        assert!(!covers_fee_headroom(1.15, 1.0, 0.1));
+    }
+
+    #[test]
+    fn monthly_run_clamps_to_month_end() {
+        let jan_31 = Utc.with_ymd_and_hms(2025, 1, 31, 9, 0, 0).unwrap();
+        let next = SchedulingService::next_run(RecurringType::Monthly, jan_31);
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 2, 28, 9, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn monthly_run_keeps_day_of_month() {
+        let jan_15 = Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap();
+        let next = SchedulingService::next_run(RecurringType::Monthly, jan_15);
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 2, 15, 9, 0, 0).unwrap());
     }
 }
@@ -1,5 +1,16 @@
-use crate::db::entity::{ security_settings, withdrawal_tracking };
-use crate::error::Result;
+use std::collections::HashSet;
+
+use crate::db::entity::{
+    address_book,
+    gas_alert,
+    price_alert,
+    security_settings,
+    transaction,
+    wallet,
+    withdrawal_tracking,
+};
+use crate::enums::TxStatus;
+use crate::error::{ AppError, Result };
 use chrono::{ DateTime, Duration, Utc };
 use sea_orm::{
     ActiveModelTrait,
@@ -9,12 +20,66 @@ use sea_orm::{
     EntityTrait,
     QueryFilter,
     QueryOrder,
+    QuerySelect,
     prelude::Decimal,
 };
+use serde::Serialize;
 use uuid::Uuid;
 use argon2::{ Argon2, PasswordHash, PasswordHasher, PasswordVerifier };
 use argon2::password_hash::{ SaltString, rand_core::OsRng };
 
+/// Alerts that haven't fired in this long are flagged as stale by the audit
+const STALE_ALERT_DAYS: i64 = 90;
+/// Wallet keys encrypted longer ago than this should be rotated
+const KEY_ROTATION_MAX_AGE_DAYS: i64 = 365;
+/// Consecutive wrong PINs after which PIN entry is locked
+pub const MAX_PIN_ATTEMPTS: i32 = 5;
+/// How long PIN entry stays locked once the attempts run out
+pub const PIN_LOCKOUT_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    High,
+    Medium,
+    Low,
+}
+
+impl FindingSeverity {
+    /// Points taken off the 100-point audit score
+    fn penalty(&self) -> u8 {
+        match self {
+            FindingSeverity::High => 25,
+            FindingSeverity::Medium => 15,
+            FindingSeverity::Low => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub severity: FindingSeverity,
+    pub message: String,
+    pub recommendation: String,
+}
+
+/// Result of `SecurityService::audit`: a 0-100 score and what lowered it, most severe first
+#[derive(Debug, Clone, Serialize)]
+pub struct SecurityAuditReport {
+    pub score: u8,
+    pub findings: Vec<Finding>,
+}
+
+/// Failed PIN attempts and lockout state, as returned by `GET /api/security/pin-status`
+#[derive(Debug, Clone, Serialize)]
+pub struct PinStatus {
+    pub pin_enabled: bool,
+    pub failed_attempts: i32,
+    pub attempts_remaining: i32,
+    pub locked: bool,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
 #[derive(Clone)]
 pub struct SecurityService {
     db: DatabaseConnection,
@@ -47,9 +112,14 @@ impl SecurityService {
             daily_withdrawal_limit: ActiveValue::Set(None),
             weekly_withdrawal_limit: ActiveValue::Set(None),
             require_confirmation_above: ActiveValue::Set(None),
+            max_single_transfer_usd: ActiveValue::Set(None),
+            pin_required_above_usd: ActiveValue::Set(Decimal::ZERO),
             session_timeout: ActiveValue::Set(3600),
             last_activity: ActiveValue::Set(Some(now)),
             wallet_locked: ActiveValue::Set(false),
+            use_testnet: ActiveValue::Set(false),
+            pin_attempts: ActiveValue::Set(0),
+            pin_locked_until: ActiveValue::Set(None),
             created_at: ActiveValue::Set(now),
             updated_at: ActiveValue::Set(now),
         };
@@ -79,7 +149,8 @@ impl SecurityService {
         Ok(())
     }
 
-    /// Verify PIN
+    /// Verify PIN. Each wrong PIN counts towards `MAX_PIN_ATTEMPTS`; once they run out
+    /// this returns `AppError::PinLocked` until `PIN_LOCKOUT_MINUTES` have passed.
     pub async fn verify_pin(&self, user_id: &str, pin: &str) -> Result<bool> {
         let settings = self.get_or_create_settings(user_id).await?;
 
@@ -87,7 +158,12 @@ impl SecurityService {
             return Ok(true); // PIN not enabled, always pass
         }
 
-        let Some(pin_hash) = settings.pin_hash else {
+        let now = Utc::now();
+        if let Some(unlocks_at) = settings.pin_locked_until.filter(|until| *until > now) {
+            return Err(AppError::PinLocked { unlocks_at });
+        }
+
+        let Some(pin_hash) = settings.pin_hash.clone() else {
             return Ok(false);
         };
 
@@ -96,7 +172,64 @@ impl SecurityService {
         )?;
 
         let argon2 = Argon2::default();
-        Ok(argon2.verify_password(pin.as_bytes(), &parsed_hash).is_ok())
+        let valid = argon2.verify_password(pin.as_bytes(), &parsed_hash).is_ok();
+
+        if valid {
+            if settings.pin_attempts != 0 || settings.pin_locked_until.is_some() {
+                let mut active: security_settings::ActiveModel = settings.into();
+                active.pin_attempts = ActiveValue::Set(0);
+                active.pin_locked_until = ActiveValue::Set(None);
+                active.update(&self.db).await?;
+            }
+            return Ok(true);
+        }
+
+        let attempts = settings.pin_attempts + 1;
+        let mut active: security_settings::ActiveModel = settings.into();
+        if attempts >= MAX_PIN_ATTEMPTS {
+            // The count starts over so the user gets a full set of attempts after the lockout
+            let unlocks_at = now + Duration::minutes(PIN_LOCKOUT_MINUTES);
+            active.pin_attempts = ActiveValue::Set(0);
+            active.pin_locked_until = ActiveValue::Set(Some(unlocks_at));
+            active.update(&self.db).await?;
+            tracing::warn!("PIN locked for user {} after {} failed attempts", user_id, attempts);
+            return Err(AppError::PinLocked { unlocks_at });
+        }
+
+        active.pin_attempts = ActiveValue::Set(attempts);
+        active.pin_locked_until = ActiveValue::Set(None);
+        active.update(&self.db).await?;
+        Ok(false)
+    }
+
+    /// How many wrong PINs the user has entered and whether PIN entry is locked
+    pub async fn pin_status(&self, user_id: &str) -> Result<PinStatus> {
+        let settings = self.get_or_create_settings(user_id).await?;
+        let locked_until = settings.pin_locked_until.filter(|until| *until > Utc::now());
+
+        Ok(PinStatus {
+            pin_enabled: settings.pin_enabled,
+            failed_attempts: settings.pin_attempts,
+            attempts_remaining: if locked_until.is_some() {
+                0
+            } else {
+                (MAX_PIN_ATTEMPTS - settings.pin_attempts).max(0)
+            },
+            locked: locked_until.is_some(),
+            locked_until,
+        })
+    }
+
+    /// Whether a send worth `amount_usd` must be confirmed with the user's PIN
+    pub async fn is_pin_required_for_amount(&self, user_id: &str, amount_usd: f64) -> Result<bool> {
+        let settings = self.get_or_create_settings(user_id).await?;
+
+        if !settings.pin_enabled {
+            return Ok(false);
+        }
+
+        let threshold = settings.pin_required_above_usd.to_string().parse::<f64>().unwrap_or(0.0);
+        Ok(threshold <= 0.0 || amount_usd > threshold)
     }
 
     /// Disable PIN
@@ -134,6 +267,47 @@ impl SecurityService {
         Ok(())
     }
 
+    /// Set the largest USD value a single send may have
+    pub async fn set_max_transfer(&self, user_id: &str, amount_usd: f64) -> Result<()> {
+        if !amount_usd.is_finite() || amount_usd <= 0.0 {
+            return Err(AppError::InvalidInput("Limit must be a positive USD amount".to_string()));
+        }
+        self.update_max_transfer(user_id, Decimal::from_f64_retain(amount_usd)).await
+    }
+
+    /// Remove the per-transfer cap
+    pub async fn clear_max_transfer(&self, user_id: &str) -> Result<()> {
+        self.update_max_transfer(user_id, None).await
+    }
+
+    async fn update_max_transfer(&self, user_id: &str, limit: Option<Decimal>) -> Result<()> {
+        let settings = self.get_or_create_settings(user_id).await?;
+
+        let mut active: security_settings::ActiveModel = settings.into();
+        active.max_single_transfer_usd = ActiveValue::Set(limit);
+        active.updated_at = ActiveValue::Set(Utc::now());
+        active.update(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// Reject a send worth more than the user's per-transfer cap
+    pub async fn check_max_transfer(&self, user_id: &str, amount_usd: f64) -> Result<()> {
+        let settings = self.get_or_create_settings(user_id).await?;
+
+        if let Some(limit) = settings.max_single_transfer_usd {
+            let limit_usd = limit.to_string().parse::<f64>().unwrap_or(0.0);
+            if amount_usd > limit_usd {
+                return Err(AppError::TransferLimitExceeded {
+                    attempted_usd: amount_usd,
+                    limit_usd,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check if withdrawal is within limits
     pub async fn check_withdrawal_limit(
         &self,
@@ -254,6 +428,168 @@ impl SecurityService {
         Ok(settings.wallet_locked)
     }
 
+    /// Switch whether new wallets are created on testnet networks
+    pub async fn set_testnet_mode(&self, user_id: &str, enable: bool) -> Result<()> {
+        let settings = self.get_or_create_settings(user_id).await?;
+
+        let mut active: security_settings::ActiveModel = settings.into();
+        active.use_testnet = ActiveValue::Set(enable);
+        active.updated_at = ActiveValue::Set(Utc::now());
+        active.update(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// Check if the user has testnet mode enabled
+    pub async fn is_testnet_mode(&self, user_id: &str) -> Result<bool> {
+        let settings = self.get_or_create_settings(user_id).await?;
+        Ok(settings.use_testnet)
+    }
+
+    /// Review a user's account for weak settings and stale data
+    pub async fn audit(&self, user_id: &str) -> Result<SecurityAuditReport> {
+        let settings = self.get_or_create_settings(user_id).await?;
+        let now = Utc::now();
+        let mut findings = Vec::new();
+
+        if !settings.pin_enabled {
+            findings.push(Finding {
+                severity: FindingSeverity::High,
+                message: "PIN protection is off, so anyone with access to this chat can send funds".to_string(),
+                recommendation: "Set a PIN with /setpin".to_string(),
+            });
+        }
+
+        if
+            settings.daily_withdrawal_limit.is_none() &&
+            settings.weekly_withdrawal_limit.is_none() &&
+            settings.max_single_transfer_usd.is_none()
+        {
+            findings.push(Finding {
+                severity: FindingSeverity::Medium,
+                message: "No withdrawal limits are set; a compromised account could be emptied at once".to_string(),
+                recommendation: "Cap withdrawals with /setlimit or /setmaxsend".to_string(),
+            });
+        }
+
+        let wallets = wallet::Entity
+            ::find()
+            .filter(wallet::Column::UserId.eq(user_id))
+            .all(&self.db).await?;
+
+        let hd_indices: Vec<i32> = wallets
+            .iter()
+            .filter_map(|w| w.derivation_index)
+            .collect();
+        if !hd_indices.is_empty() && hd_indices.iter().all(|i| *i == 0) {
+            findings.push(Finding {
+                severity: FindingSeverity::Low,
+                message: format!(
+                    "All {} of your recovery-phrase wallets use derivation index 0",
+                    hd_indices.len()
+                ),
+                recommendation: "Spread funds across additional address indices so one linked address doesn't reveal them all".to_string(),
+            });
+        }
+
+        let wallet_ids: Vec<Uuid> = wallets
+            .iter()
+            .map(|w| w.id)
+            .collect();
+        let sent_to: HashSet<String> = transaction::Entity
+            ::find()
+            .select_only()
+            .column(transaction::Column::ToAddress)
+            .filter(transaction::Column::WalletId.is_in(wallet_ids))
+            .filter(transaction::Column::Status.eq(TxStatus::Confirmed.as_str()))
+            .into_tuple::<String>()
+            .all(&self.db).await?
+            .into_iter()
+            .map(|address| address.to_lowercase())
+            .collect();
+        let unverified: Vec<String> = address_book::Entity
+            ::find()
+            .filter(address_book::Column::UserId.eq(user_id))
+            // ENS cache rows aren't addresses the user saved
+            .filter(address_book::Column::EnsExpiresAt.is_null())
+            .all(&self.db).await?
+            .into_iter()
+            .filter(|entry| !sent_to.contains(&entry.address.to_lowercase()))
+            .map(|entry| entry.name)
+            .collect();
+        if !unverified.is_empty() {
+            findings.push(Finding {
+                severity: FindingSeverity::Low,
+                message: format!(
+                    "{} saved address(es) have never received a successful send: {}",
+                    unverified.len(),
+                    unverified.join(", ")
+                ),
+                recommendation: "Send a small test amount before a large transfer to a new address".to_string(),
+            });
+        }
+
+        let stale_cutoff = now - Duration::days(STALE_ALERT_DAYS);
+        let stale_price_alerts = price_alert::Entity
+            ::find()
+            .filter(price_alert::Column::UserId.eq(user_id))
+            .filter(price_alert::Column::Active.eq(true))
+            .filter(price_alert::Column::TriggeredAt.is_null())
+            .filter(price_alert::Column::CreatedAt.lt(stale_cutoff))
+            .all(&self.db).await?;
+        let stale_gas_alerts = gas_alert::Entity
+            ::find()
+            .filter(gas_alert::Column::UserId.eq(user_id))
+            .filter(gas_alert::Column::Active.eq(true))
+            .filter(gas_alert::Column::LastTriggeredAt.is_null())
+            .filter(gas_alert::Column::CreatedAt.lt(stale_cutoff))
+            .all(&self.db).await?;
+        let stale_count = stale_price_alerts.len() + stale_gas_alerts.len();
+        if stale_count > 0 {
+            // A forgotten stop-loss that can still sell on its own is worth more attention
+            let auto_executing = stale_price_alerts
+                .iter()
+                .any(|a| a.auto_execute);
+            findings.push(Finding {
+                severity: if auto_executing { FindingSeverity::Medium } else { FindingSeverity::Low },
+                message: format!(
+                    "{} alert(s) are over {} days old and have never triggered{}",
+                    stale_count,
+                    STALE_ALERT_DAYS,
+                    if auto_executing { ", including ones that trade automatically" } else { "" }
+                ),
+                recommendation: "Review them with /alerts and delete the ones you no longer need".to_string(),
+            });
+        }
+
+        // A wallet's key was last encrypted at its latest rotation, or at creation if never rotated
+        let oldest_key = wallets
+            .iter()
+            .map(|w| w.key_rotated_at.unwrap_or(w.created_at))
+            .min();
+        if let Some(oldest_key) = oldest_key {
+            let age_days = (now - oldest_key).num_days();
+            if age_days > KEY_ROTATION_MAX_AGE_DAYS {
+                findings.push(Finding {
+                    severity: FindingSeverity::Medium,
+                    message: format!("Your wallet keys were last re-encrypted {} days ago", age_days),
+                    recommendation: "Ask the bot operator to rotate the encryption key".to_string(),
+                });
+            }
+        }
+
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity.penalty()));
+        let penalty: u32 = findings
+            .iter()
+            .map(|f| f.severity.penalty() as u32)
+            .sum();
+
+        Ok(SecurityAuditReport {
+            score: (100u32).saturating_sub(penalty) as u8,
+            findings,
+        })
+    }
+
     /// Update last activity
     pub async fn update_activity(&self, user_id: &str) -> Result<()> {
         let settings = self.get_or_create_settings(user_id).await?;
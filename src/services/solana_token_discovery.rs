@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::chains::solana::tokens;
+use crate::error::{ AppError, Result };
+use crate::providers::TokenBalanceEntry;
+
+const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_LIST_URL: &str =
+    "https://raw.githubusercontent.com/solana-labs/token-list/main/src/tokens/solana.tokenlist.json";
+
+/// Discovers SPL token balances for a Solana address via `getTokenAccountsByOwner`.
+pub struct SolanaTokenDiscovery {
+    client: reqwest::Client,
+    rpc_url: String,
+    /// Mint address -> token list entry, loaded once on first use
+    token_list: RwLock<Option<HashMap<String, TokenListEntry>>>,
+}
+
+// ── Solana JSON-RPC response types ─────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAccountsResult {
+    value: Vec<TokenAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAccount {
+    account: TokenAccountData,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAccountData {
+    data: ParsedData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedData {
+    parsed: ParsedAccount,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedAccount {
+    info: ParsedTokenInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedTokenInfo {
+    mint: String,
+    #[serde(rename = "tokenAmount")]
+    token_amount: TokenAmount,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAmount {
+    decimals: u8,
+    #[serde(rename = "uiAmountString")]
+    ui_amount_string: String,
+}
+
+// ── Solana Token List types ────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct TokenList {
+    tokens: Vec<TokenListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenListEntry {
+    address: String,
+    symbol: String,
+    name: String,
+    #[serde(rename = "logoURI")]
+    logo_uri: Option<String>,
+}
+
+impl SolanaTokenDiscovery {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            rpc_url,
+            token_list: RwLock::new(None),
+        }
+    }
+
+    /// Get all non-zero SPL token balances owned by an address.
+    pub async fn get_all_token_balances(&self, address: &str) -> Result<Vec<TokenBalanceEntry>> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getTokenAccountsByOwner",
+            "params": [
+                address,
+                { "programId": SPL_TOKEN_PROGRAM_ID },
+                { "encoding": "jsonParsed" }
+            ]
+        });
+
+        let response = self.client
+            .post(&self.rpc_url)
+            .json(&body)
+            .send().await
+            .map_err(|e| AppError::Rpc(format!("Solana token accounts request failed: {}", e)))?;
+
+        let rpc_resp: RpcResponse<TokenAccountsResult> = response
+            .json().await
+            .map_err(|e| AppError::Rpc(format!("Failed to parse token accounts response: {}", e)))?;
+
+        if let Some(err) = rpc_resp.error {
+            return Err(AppError::Rpc(format!("Solana RPC error: {}", err.message)));
+        }
+
+        let accounts = match rpc_resp.result {
+            Some(r) => r.value,
+            None => {
+                return Ok(vec![]);
+            }
+        };
+
+        self.ensure_token_list().await;
+        let token_list = self.token_list.read().await;
+
+        let mut entries = Vec::new();
+        for account in accounts {
+            let info = account.account.data.parsed.info;
+            let balance = info.token_amount.ui_amount_string;
+
+            if balance.parse::<f64>().unwrap_or(0.0) == 0.0 {
+                continue;
+            }
+
+            let listed = token_list.as_ref().and_then(|list| list.get(&info.mint));
+            let (symbol, name, logo_url) = match listed {
+                Some(entry) => (entry.symbol.clone(), entry.name.clone(), entry.logo_uri.clone()),
+                None =>
+                    match tokens::get_token_by_mint(&info.mint) {
+                        Some(known) => (known.symbol.clone(), known.symbol.clone(), None),
+                        None => ("UNKNOWN".to_string(), "Unknown Token".to_string(), None),
+                    }
+            };
+
+            entries.push(TokenBalanceEntry {
+                contract_address: info.mint,
+                symbol,
+                name,
+                decimals: info.token_amount.decimals,
+                balance,
+                logo_url,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Load the token list into the cache on first use. On failure the cache stays empty
+    /// and the download is retried on the next call.
+    async fn ensure_token_list(&self) {
+        if self.token_list.read().await.is_some() {
+            return;
+        }
+
+        match self.fetch_token_list().await {
+            Ok(list) => {
+                *self.token_list.write().await = Some(list);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to load Solana token list: {}", e);
+            }
+        }
+    }
+
+    async fn fetch_token_list(&self) -> Result<HashMap<String, TokenListEntry>> {
+        let list: TokenList = self.client
+            .get(TOKEN_LIST_URL)
+            .send().await
+            .map_err(|e| AppError::External(format!("Token list request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse token list: {}", e)))?;
+
+        Ok(
+            list.tokens
+                .into_iter()
+                .map(|t| (t.address.clone(), t))
+                .collect()
+        )
+    }
+}
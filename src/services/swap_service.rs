@@ -1,10 +1,14 @@
-use crate::db::entity::swap;
-use crate::dex::{ DexProvider, SwapQuote };
+use crate::db::entity::{ swap, token_metadata, wallet };
+use crate::db::TokenMetadataRepository;
+use crate::dex::{ BatchSwapLeg, DexProvider, SwapQuote, SwapResult };
 use crate::dex::uniswap::UniswapV2Provider;
 use crate::dex::jupiter::JupiterProvider;
+use crate::dex::aggregator::DexAggregator;
+use crate::dex::pancakeswap_v3::PancakeSwapV3Provider;
 use crate::enums::{ Chain, SwapStatus };
 use crate::error::{ AppError, Result };
-use crate::services::WalletService;
+use crate::services::{ GasEstimationService, PriceService, TokenSecurityService, WalletService };
+use crate::services::token_security_service::TokenSecurity;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
@@ -15,12 +19,79 @@ use sea_orm::{
     QueryOrder,
     prelude::Decimal,
 };
+use serde::{ Deserialize, Serialize };
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Slippage the bot quotes and swaps with, in percent
+pub const DEFAULT_SLIPPAGE_PCT: f64 = 0.5;
+/// Tolerances above this leave room for sandwich attacks and are flagged to the user
+pub const HIGH_SLIPPAGE_PCT: f64 = 5.0;
+/// Largest custom tolerance the bot accepts
+pub const MAX_SLIPPAGE_PCT: f64 = 50.0;
+
+/// Bot quotes warn above these levels
+const WARN_PRICE_IMPACT_PCT: f64 = 3.0;
+const WARN_MIN_OUTPUT_RATIO: f64 = 0.97;
+const WARN_GAS_SHARE_OF_TRADE: f64 = 0.05;
+
 pub struct SwapService {
     db: DatabaseConnection,
     wallet_service: Arc<WalletService>,
+    max_price_impact_pct: f64,
+    token_security: Option<Arc<TokenSecurityService>>,
+    /// Gas and token prices for comparing network fees to trade value
+    fee_check: Option<(Arc<GasEstimationService>, Arc<PriceService>)>,
+    /// Known tokens for resolving symbols typed by users
+    token_metadata: Option<Arc<TokenMetadataRepository>>,
+}
+
+/// Token choices the bot offers when a symbol is ambiguous
+const MAX_TOKEN_CANDIDATES: usize = 8;
+
+/// A token a swap can be routed through, identified by contract address or mint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenCandidate {
+    pub symbol: String,
+    pub name: String,
+    pub address: String,
+}
+
+impl From<token_metadata::Model> for TokenCandidate {
+    fn from(token: token_metadata::Model) -> Self {
+        Self { symbol: token.symbol, name: token.name, address: token.contract_address }
+    }
+}
+
+/// What a symbol or address typed by the user refers to on a chain
+#[derive(Debug, Clone)]
+pub enum ResolvedToken {
+    /// The chain's native token, which DEXes take by symbol
+    Native(String),
+    Token(TokenCandidate),
+    /// Several tokens matched; the user has to pick one
+    Ambiguous(Vec<TokenCandidate>),
+}
+
+impl ResolvedToken {
+    /// Value DEX providers accept for this token; `None` while ambiguous
+    pub fn swap_token(&self) -> Option<&str> {
+        match self {
+            ResolvedToken::Native(symbol) => Some(symbol),
+            ResolvedToken::Token(token) => Some(&token.address),
+            ResolvedToken::Ambiguous(_) => None,
+        }
+    }
+}
+
+/// A swap quote with the warnings the bot shows before the user confirms
+#[derive(Debug, Clone, Serialize)]
+pub struct BotSwapQuote {
+    #[serde(flatten)]
+    pub quote: SwapQuote,
+    pub warning_messages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_security: Option<TokenSecurity>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +102,26 @@ pub struct SwapRequest {
     pub to_token: String,
     pub amount: f64,
     pub slippage: f64, // Percentage (e.g., 1.0 for 1%)
+    /// Execute on this DEX instead of the best-priced one
+    pub dex: Option<String>,
+}
+
+/// One swap within a batch; the wallet is shared across the batch
+#[derive(Debug, Clone)]
+pub struct BatchSwapRequest {
+    pub from_token: String,
+    pub to_token: String,
+    pub amount: f64,
+    pub slippage: f64,
+}
+
+/// Outcome of a batch swap; `results` holds one swap record per request, in order
+#[derive(Debug, Clone)]
+pub struct BatchSwapResult {
+    pub batch_id: Uuid,
+    pub successful: usize,
+    pub failed: usize,
+    pub results: Vec<swap::Model>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,16 +131,126 @@ pub struct SwapQuoteRequest {
     pub to_token: String,
     pub amount: f64,
     pub slippage: f64,
+    /// Quote against the chain's testnet deployment
+    pub testnet: bool,
 }
 
 impl SwapService {
-    pub fn new(db: DatabaseConnection, wallet_service: Arc<WalletService>) -> Self {
-        Self { db, wallet_service }
+    pub fn new(
+        db: DatabaseConnection,
+        wallet_service: Arc<WalletService>,
+        max_price_impact_pct: f64
+    ) -> Self {
+        Self {
+            db,
+            wallet_service,
+            max_price_impact_pct,
+            token_security: None,
+            fee_check: None,
+            token_metadata: None,
+        }
+    }
+
+    /// Resolve token symbols in bot commands against known token metadata
+    pub fn with_token_metadata(mut self, token_metadata: Arc<TokenMetadataRepository>) -> Self {
+        self.token_metadata = Some(token_metadata);
+        self
+    }
+
+    /// Turn a symbol or address typed by the user into a token on `chain`. Addresses and the
+    /// native symbol pass through; symbols are matched exactly first, then by symbol or name.
+    pub async fn resolve_token_for_swap(&self, chain: &str, symbol_or_address: &str) -> Result<ResolvedToken> {
+        let chain: Chain = chain.parse()?;
+        let query = symbol_or_address.trim();
+        if query.is_empty() {
+            return Err(AppError::InvalidInput("Token is required".to_string()));
+        }
+
+        if query.eq_ignore_ascii_case(chain.native_symbol()) {
+            return Ok(ResolvedToken::Native(chain.native_symbol().to_string()));
+        }
+
+        let is_address = if chain.is_evm() {
+            query.starts_with("0x") && query.len() == 42
+        } else {
+            chain == Chain::Solana && query.len() >= 32 && bs58::decode(query).into_vec().is_ok()
+        };
+        if is_address {
+            // Rows cached for their decimals alone have no symbol to show
+            let known = match &self.token_metadata {
+                Some(repo) => repo
+                    .find_by_chain_and_address(chain.as_str(), query).await?
+                    .filter(|t| !t.symbol.is_empty()),
+                None => None,
+            };
+            return Ok(
+                ResolvedToken::Token(
+                    known.map(TokenCandidate::from).unwrap_or_else(|| TokenCandidate {
+                        symbol: query.to_string(),
+                        name: String::new(),
+                        address: query.to_string(),
+                    })
+                )
+            );
+        }
+
+        // The built-in token list only holds Ethereum mainnet addresses
+        if chain == Chain::Eth {
+            if let Some(token) = crate::chains::evm::tokens::get_token_by_symbol(query) {
+                return Ok(
+                    ResolvedToken::Token(TokenCandidate {
+                        symbol: token.symbol.clone(),
+                        name: token.symbol.clone(),
+                        address: token.address.clone(),
+                    })
+                );
+            }
+        }
+
+        let Some(repo) = &self.token_metadata else {
+            return Err(AppError::InvalidInput(format!("Unknown token {} on {}", query, chain)));
+        };
+
+        let mut matches = repo.find_by_chain_and_symbol(chain.as_str(), query).await?;
+        if matches.is_empty() {
+            matches = repo.find_by_chain_and_symbol_fuzzy(chain.as_str(), query).await?;
+        }
+
+        match matches.len() {
+            0 => Err(AppError::InvalidInput(format!("Unknown token {} on {}", query, chain))),
+            1 => Ok(ResolvedToken::Token(matches.remove(0).into())),
+            _ => {
+                matches.truncate(MAX_TOKEN_CANDIDATES);
+                Ok(ResolvedToken::Ambiguous(matches.into_iter().map(TokenCandidate::from).collect()))
+            }
+        }
+    }
+
+    /// Warn in bot quotes when network fees are large relative to the trade
+    pub fn with_fee_check(
+        mut self,
+        gas_estimation_service: Arc<GasEstimationService>,
+        price_service: Arc<PriceService>
+    ) -> Self {
+        self.fee_check = Some((gas_estimation_service, price_service));
+        self
+    }
+
+    /// Refuse swaps into tokens that fail a honeypot check
+    pub fn with_token_security(mut self, token_security: Arc<TokenSecurityService>) -> Self {
+        self.token_security = Some(token_security);
+        self
+    }
+
+    /// Whether `execute_swap` can sign with the wallet's own key. It still passes a
+    /// placeholder key, so unattended swaps (such as DCA buys) must not be scheduled yet.
+    pub fn can_sign_swaps(&self) -> bool {
+        false
     }
 
     /// Get swap quote from appropriate DEX
     pub async fn get_swap_quote(&self, request: SwapQuoteRequest) -> Result<SwapQuote> {
-        let provider = self.get_dex_provider(&request.chain)?;
+        let provider = self.get_dex_provider(&request.chain, request.testnet)?;
 
         provider.get_quote(
             &request.from_token,
@@ -59,6 +260,122 @@ impl SwapService {
         ).await
     }
 
+    /// Quotes from every DEX available for the swap, best output first
+    pub async fn get_all_swap_quotes(&self, request: SwapQuoteRequest) -> Result<Vec<SwapQuote>> {
+        let provider = self.get_dex_provider(&request.chain, request.testnet)?;
+
+        provider.get_all_quotes(
+            &request.from_token,
+            &request.to_token,
+            request.amount,
+            request.slippage
+        ).await
+    }
+
+    /// Best quote for swapping from a wallet at the default slippage, with user-facing warnings
+    pub async fn get_bot_quote(
+        &self,
+        wallet_id: Uuid,
+        from_token: &str,
+        to_token: &str,
+        amount: &str
+    ) -> Result<BotSwapQuote> {
+        let amount: f64 = amount
+            .trim()
+            .parse()
+            .ok()
+            .filter(|a: &f64| *a > 0.0)
+            .ok_or_else(|| AppError::InvalidInput(format!("Invalid swap amount: {}", amount)))?;
+        let wallet = self.wallet_service.get_wallet(wallet_id).await?;
+
+        let quote = self.get_swap_quote(SwapQuoteRequest {
+            chain: wallet.chain.clone(),
+            from_token: from_token.to_string(),
+            to_token: to_token.to_string(),
+            amount,
+            slippage: DEFAULT_SLIPPAGE_PCT,
+            testnet: wallet.is_testnet,
+        }).await?;
+
+        let mut warning_messages = Vec::new();
+        if quote.price_impact > WARN_PRICE_IMPACT_PCT {
+            warning_messages.push(format!("⚠️ High price impact: {:.2}%", quote.price_impact));
+        }
+        if
+            quote.expected_to_amount > 0.0 &&
+            quote.minimum_to_amount / quote.expected_to_amount < WARN_MIN_OUTPUT_RATIO
+        {
+            warning_messages.push("⚠️ High slippage".to_string());
+        }
+        if !wallet.is_testnet {
+            if let Some(gas_share) = self.gas_share_of_trade(&wallet, &quote).await {
+                if gas_share > WARN_GAS_SHARE_OF_TRADE {
+                    warning_messages.push("⚠️ Gas costs are high relative to trade size".to_string());
+                }
+            }
+        }
+
+        if let Some(warning) = self.approval_warning(&wallet, &quote).await {
+            warning_messages.push(warning);
+        }
+
+        let token_security = self.token_security_report(&wallet, &quote).await;
+        if token_security.as_ref().is_some_and(|s| s.is_honeypot) {
+            warning_messages.push("🚨 Potential honeypot detected".to_string());
+        }
+
+        Ok(BotSwapQuote { quote, warning_messages, token_security })
+    }
+
+    /// Fee warning when the quoting DEX's router still needs an allowance for the input token
+    async fn approval_warning(&self, wallet: &wallet::Model, quote: &SwapQuote) -> Option<String> {
+        let (gas_estimation_service, _) = self.fee_check.as_ref()?;
+        let chain: Chain = wallet.chain.parse().ok()?;
+        if !chain.is_evm() || quote.from_token.eq_ignore_ascii_case(chain.native_symbol()) {
+            return None;
+        }
+        let token = quote.from_token_address.as_deref()?;
+        let spender = self.get_dex_provider(&wallet.chain, wallet.is_testnet).ok()?.spender_for(&quote.dex)?;
+
+        let approval = gas_estimation_service
+            .estimate_approval_fee(wallet.id, token, &spender).await
+            .unwrap_or_else(|e| {
+                tracing::debug!("Approval check skipped: {}", e);
+                None
+            })?;
+        let fee: f64 = approval.total_cost_native.parse().unwrap_or(0.0);
+        Some(format!("⚠️ Approval required first: ~{:.6} {} gas", fee, chain.native_symbol()))
+    }
+
+    /// Network fee as a fraction of the traded value; `None` when either can't be priced
+    async fn gas_share_of_trade(&self, wallet: &wallet::Model, quote: &SwapQuote) -> Option<f64> {
+        let (gas_estimation_service, price_service) = self.fee_check.as_ref()?;
+        let gas_units: f64 = quote.estimated_gas.as_deref()?.parse().ok()?;
+        let parsed: Chain = wallet.chain.parse().ok()?;
+
+        // Jupiter reports its fee directly in lamports; EVM DEXes report gas units
+        let fee_native = if parsed == Chain::Solana {
+            gas_units / 1e9
+        } else {
+            let gwei = gas_estimation_service.get_wallet_gas_price_gwei(wallet).await.ok()?;
+            (gas_units * gwei) / 1e9
+        };
+
+        let native_symbol = parsed.native_symbol();
+        let trade_native = if quote.from_token.eq_ignore_ascii_case(native_symbol) {
+            quote.from_amount
+        } else {
+            let native_usd = price_service.get_price(native_symbol).await.ok()?.usd_price;
+            let from_usd = price_service.get_price(&quote.from_token).await.ok()?.usd_price;
+            if native_usd <= 0.0 {
+                return None;
+            }
+            (quote.from_amount * from_usd) / native_usd
+        };
+
+        (trade_native > 0.0).then(|| fee_native / trade_native)
+    }
+
     /// Execute a token swap
     pub async fn execute_swap(&self, request: SwapRequest) -> Result<swap::Model> {
         // Get wallet details
@@ -70,9 +387,204 @@ impl SwapService {
         }
 
         // Get DEX provider for chain
-        let provider = self.get_dex_provider(&wallet.chain)?;
+        let provider = self.get_dex_provider(&wallet.chain, wallet.is_testnet)?;
+
+        // Get quote first, from the requested DEX when one was chosen
+        let quote = match &request.dex {
+            Some(dex_name) =>
+                provider
+                    .get_all_quotes(
+                        &request.from_token,
+                        &request.to_token,
+                        request.amount,
+                        request.slippage
+                    ).await?
+                    .into_iter()
+                    .find(|q| &q.dex == dex_name)
+                    .ok_or_else(||
+                        AppError::Validation(format!("{} returned no quote for this swap", dex_name))
+                    )?,
+            None =>
+                provider.get_quote(
+                    &request.from_token,
+                    &request.to_token,
+                    request.amount,
+                    request.slippage
+                ).await?,
+        };
+
+        // Validate price impact
+        if quote.price_impact > self.max_price_impact_pct {
+            return Err(AppError::PriceImpactTooHigh { pct: quote.price_impact });
+        }
+
+        if let Some(security) = self.token_security_report(&wallet, &quote).await {
+            if security.is_honeypot {
+                return Err(
+                    AppError::HoneypotDetected(quote.to_token_address.clone().unwrap_or_default())
+                );
+            }
+        }
+
+        let swap_model = self.insert_pending_swap(
+            &wallet,
+            &request.from_token,
+            &request.to_token,
+            request.amount,
+            request.slippage,
+            &quote,
+            None
+        ).await?;
+
+        // Execute swap
+        // Note: In production, this should decrypt the private key properly
+        let private_key = "ENCRYPTED_KEY_PLACEHOLDER"; // Would decrypt wallet.encrypted_private_key
+        // Route to the DEX that gave this quote, not whichever one quoted last
+        let outcome = provider.execute_swap_via(
+            &quote.dex,
+            &wallet.address,
+            private_key,
+            &request.from_token,
+            &request.to_token,
+            request.amount,
+            request.slippage,
+            quote.minimum_to_amount
+        ).await;
+
+        match outcome {
+            Ok(result) => self.mark_swap_success(swap_model, &result).await,
+            Err(e) => {
+                self.mark_swap_failed(swap_model, &e.to_string()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Execute several swaps from one wallet. On DEXes whose router can batch calls the
+    /// legs share a single transaction; elsewhere they run one after another.
+    /// Every leg is recorded in the swap table under a shared batch id.
+    pub async fn batch_swap(
+        &self,
+        wallet_id: Uuid,
+        swaps: Vec<BatchSwapRequest>
+    ) -> Result<BatchSwapResult> {
+        if swaps.is_empty() {
+            return Err(AppError::Validation("No swaps provided".to_string()));
+        }
+
+        let wallet = self.wallet_service.get_wallet(wallet_id).await?;
+        let provider = self.get_dex_provider(&wallet.chain, wallet.is_testnet)?;
+        let batch_id = Uuid::new_v4();
+
+        // Quote and record every leg up front; legs that fail validation are stored as failed
+        let mut records: Vec<swap::Model> = Vec::with_capacity(swaps.len());
+        let mut pending: Vec<(usize, SwapQuote)> = Vec::new();
+        for (index, request) in swaps.iter().enumerate() {
+            match self.validated_quote(provider.as_ref(), &wallet, request).await {
+                Ok(quote) => {
+                    let record = self.insert_pending_swap(
+                        &wallet,
+                        &request.from_token,
+                        &request.to_token,
+                        request.amount,
+                        request.slippage,
+                        &quote,
+                        Some(batch_id)
+                    ).await?;
+                    records.push(record);
+                    pending.push((index, quote));
+                }
+                Err(e) => {
+                    let record = self.insert_rejected_swap(
+                        &wallet,
+                        provider.name(),
+                        request,
+                        &e.to_string(),
+                        batch_id
+                    ).await?;
+                    records.push(record);
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            let legs: Vec<BatchSwapLeg> = pending
+                .iter()
+                .map(|(index, quote)| BatchSwapLeg {
+                    dex: quote.dex.clone(),
+                    from_token: swaps[*index].from_token.clone(),
+                    to_token: swaps[*index].to_token.clone(),
+                    amount: swaps[*index].amount,
+                    slippage: swaps[*index].slippage,
+                })
+                .collect();
+
+            // Note: In production, this should decrypt the private key properly
+            let private_key = "ENCRYPTED_KEY_PLACEHOLDER";
+            match provider.execute_batch_swap(&wallet.address, private_key, &legs).await {
+                Some(Ok(results)) => {
+                    for ((index, _), result) in pending.iter().zip(results.iter()) {
+                        records[*index] = self.mark_swap_success(
+                            records[*index].clone(),
+                            result
+                        ).await?;
+                    }
+                }
+                Some(Err(e)) => {
+                    // The batch transaction reverts as a whole, so every leg failed
+                    for (index, _) in &pending {
+                        records[*index] = self.mark_swap_failed(
+                            records[*index].clone(),
+                            &e.to_string()
+                        ).await?;
+                    }
+                }
+                None => {
+                    for ((index, quote), leg) in pending.iter().zip(legs.iter()) {
+                        let outcome = provider.execute_swap_via(
+                            &leg.dex,
+                            &wallet.address,
+                            private_key,
+                            &leg.from_token,
+                            &leg.to_token,
+                            leg.amount,
+                            leg.slippage,
+                            quote.minimum_to_amount
+                        ).await;
+
+                        records[*index] = match outcome {
+                            Ok(result) => {
+                                self.mark_swap_success(records[*index].clone(), &result).await?
+                            }
+                            Err(e) => {
+                                self.mark_swap_failed(records[*index].clone(), &e.to_string()).await?
+                            }
+                        };
+                    }
+                }
+            }
+        }
+
+        let successful = records
+            .iter()
+            .filter(|r| r.status == SwapStatus::Success.as_str())
+            .count();
 
-        // Get quote first
+        Ok(BatchSwapResult {
+            batch_id,
+            successful,
+            failed: records.len() - successful,
+            results: records,
+        })
+    }
+
+    /// Quote a batch leg and apply the same price impact and honeypot checks as a single swap
+    async fn validated_quote(
+        &self,
+        provider: &dyn DexProvider,
+        wallet: &wallet::Model,
+        request: &BatchSwapRequest
+    ) -> Result<SwapQuote> {
         let quote = provider.get_quote(
             &request.from_token,
             &request.to_token,
@@ -80,26 +592,43 @@ impl SwapService {
             request.slippage
         ).await?;
 
-        // Validate price impact
-        if quote.price_impact > 5.0 {
-            return Err(
-                AppError::Validation(format!("Price impact too high: {:.2}%", quote.price_impact))
-            );
+        if quote.price_impact > self.max_price_impact_pct {
+            return Err(AppError::PriceImpactTooHigh { pct: quote.price_impact });
+        }
+
+        if let Some(security) = self.token_security_report(wallet, &quote).await {
+            if security.is_honeypot {
+                return Err(
+                    AppError::HoneypotDetected(quote.to_token_address.clone().unwrap_or_default())
+                );
+            }
         }
 
-        // Create pending swap record
-        let swap_id = Uuid::new_v4();
+        Ok(quote)
+    }
+
+    /// Create the pending swap record for a quoted swap
+    async fn insert_pending_swap(
+        &self,
+        wallet: &wallet::Model,
+        from_token: &str,
+        to_token: &str,
+        amount: f64,
+        slippage: f64,
+        quote: &SwapQuote,
+        batch_id: Option<Uuid>
+    ) -> Result<swap::Model> {
         let swap_entity = swap::ActiveModel {
-            id: ActiveValue::Set(swap_id),
-            user_id: ActiveValue::Set(request.user_id.clone()),
-            wallet_id: ActiveValue::Set(request.wallet_id),
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(wallet.user_id.clone()),
+            wallet_id: ActiveValue::Set(wallet.id),
             chain: ActiveValue::Set(wallet.chain.clone()),
-            dex: ActiveValue::Set(provider.name().to_string()),
-            from_token: ActiveValue::Set(request.from_token.clone()),
+            dex: ActiveValue::Set(quote.dex.clone()),
+            from_token: ActiveValue::Set(from_token.to_string()),
             from_token_address: ActiveValue::Set(quote.from_token_address.clone()),
-            to_token: ActiveValue::Set(request.to_token.clone()),
+            to_token: ActiveValue::Set(to_token.to_string()),
             to_token_address: ActiveValue::Set(quote.to_token_address.clone()),
-            from_amount: ActiveValue::Set(Decimal::from_f64_retain(request.amount).unwrap()),
+            from_amount: ActiveValue::Set(Decimal::from_f64_retain(amount).unwrap()),
             to_amount: ActiveValue::Set(Decimal::from_f64_retain(0.0).unwrap()),
             expected_to_amount: ActiveValue::Set(
                 Some(Decimal::from_f64_retain(quote.expected_to_amount).unwrap())
@@ -107,62 +636,108 @@ impl SwapService {
             price_impact: ActiveValue::Set(
                 Some(Decimal::from_f64_retain(quote.price_impact).unwrap())
             ),
-            slippage: ActiveValue::Set(Decimal::from_f64_retain(request.slippage).unwrap()),
+            slippage: ActiveValue::Set(Decimal::from_f64_retain(slippage).unwrap()),
             tx_hash: ActiveValue::Set(None),
             status: ActiveValue::Set(SwapStatus::Pending.to_string()),
             error_message: ActiveValue::Set(None),
             gas_fee: ActiveValue::Set(None),
             route: ActiveValue::Set(Some(serde_json::json!(quote.route))),
+            batch_id: ActiveValue::Set(batch_id),
             created_at: ActiveValue::Set(chrono::Utc::now()),
             updated_at: ActiveValue::Set(chrono::Utc::now()),
         };
 
-        let swap_model = swap_entity.insert(&self.db).await.map_err(|e| AppError::Database(e))?;
+        swap_entity.insert(&self.db).await.map_err(|e| AppError::Database(e))
+    }
 
-        // Execute swap
-        // Note: In production, this should decrypt the private key properly
-        match
-            provider.execute_swap(
-                &wallet.address,
-                "ENCRYPTED_KEY_PLACEHOLDER", // Would decrypt wallet.encrypted_private_key
-                &request.from_token,
-                &request.to_token,
-                request.amount,
-                request.slippage,
-                quote.minimum_to_amount
-            ).await
-        {
-            Ok(result) => {
-                // Update swap record with success
-                let mut swap_active: swap::ActiveModel = swap_model.clone().into();
-                swap_active.status = ActiveValue::Set(SwapStatus::Success.to_string());
-                swap_active.tx_hash = ActiveValue::Set(Some(result.tx_hash.clone()));
-                swap_active.to_amount = ActiveValue::Set(
-                    Decimal::from_f64_retain(result.to_amount).unwrap()
-                );
-                swap_active.gas_fee = result.gas_used
-                    .map(|g| {
-                        ActiveValue::Set(
-                            Some(Decimal::from_f64_retain(g.parse::<f64>().unwrap_or(0.0)).unwrap())
-                        )
-                    })
-                    .unwrap_or(ActiveValue::NotSet);
-                swap_active.updated_at = ActiveValue::Set(chrono::Utc::now());
+    /// Record a batch leg that never got a usable quote
+    async fn insert_rejected_swap(
+        &self,
+        wallet: &wallet::Model,
+        dex: &str,
+        request: &BatchSwapRequest,
+        error: &str,
+        batch_id: Uuid
+    ) -> Result<swap::Model> {
+        let swap_entity = swap::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(wallet.user_id.clone()),
+            wallet_id: ActiveValue::Set(wallet.id),
+            chain: ActiveValue::Set(wallet.chain.clone()),
+            dex: ActiveValue::Set(dex.to_string()),
+            from_token: ActiveValue::Set(request.from_token.clone()),
+            from_token_address: ActiveValue::Set(None),
+            to_token: ActiveValue::Set(request.to_token.clone()),
+            to_token_address: ActiveValue::Set(None),
+            from_amount: ActiveValue::Set(Decimal::from_f64_retain(request.amount).unwrap_or_default()),
+            to_amount: ActiveValue::Set(Decimal::ZERO),
+            expected_to_amount: ActiveValue::Set(None),
+            price_impact: ActiveValue::Set(None),
+            slippage: ActiveValue::Set(Decimal::from_f64_retain(request.slippage).unwrap_or_default()),
+            tx_hash: ActiveValue::Set(None),
+            status: ActiveValue::Set(SwapStatus::Failed.to_string()),
+            error_message: ActiveValue::Set(Some(error.to_string())),
+            gas_fee: ActiveValue::Set(None),
+            route: ActiveValue::Set(None),
+            batch_id: ActiveValue::Set(Some(batch_id)),
+            created_at: ActiveValue::Set(chrono::Utc::now()),
+            updated_at: ActiveValue::Set(chrono::Utc::now()),
+        };
 
-                swap_active.update(&self.db).await.map_err(|e| AppError::Database(e))
-            }
-            Err(e) => {
-                // Update swap record with failure
-                let mut swap_active: swap::ActiveModel = swap_model.clone().into();
-                swap_active.status = ActiveValue::Set(SwapStatus::Failed.to_string());
-                swap_active.error_message = ActiveValue::Set(Some(e.to_string()));
-                swap_active.updated_at = ActiveValue::Set(chrono::Utc::now());
+        swap_entity.insert(&self.db).await.map_err(|e| AppError::Database(e))
+    }
 
-                let failed_swap = swap_active
-                    .update(&self.db).await
-                    .map_err(|e| AppError::Database(e))?;
+    async fn mark_swap_success(
+        &self,
+        swap_model: swap::Model,
+        result: &SwapResult
+    ) -> Result<swap::Model> {
+        let mut swap_active: swap::ActiveModel = swap_model.into();
+        swap_active.status = ActiveValue::Set(SwapStatus::Success.to_string());
+        swap_active.tx_hash = ActiveValue::Set(Some(result.tx_hash.clone()));
+        swap_active.to_amount = ActiveValue::Set(
+            Decimal::from_f64_retain(result.to_amount).unwrap()
+        );
+        swap_active.gas_fee = result.gas_used
+            .as_ref()
+            .map(|g| {
+                ActiveValue::Set(
+                    Some(Decimal::from_f64_retain(g.parse::<f64>().unwrap_or(0.0)).unwrap())
+                )
+            })
+            .unwrap_or(ActiveValue::NotSet);
+        swap_active.updated_at = ActiveValue::Set(chrono::Utc::now());
 
-                Err(e)
+        swap_active.update(&self.db).await.map_err(|e| AppError::Database(e))
+    }
+
+    async fn mark_swap_failed(&self, swap_model: swap::Model, error: &str) -> Result<swap::Model> {
+        let mut swap_active: swap::ActiveModel = swap_model.into();
+        swap_active.status = ActiveValue::Set(SwapStatus::Failed.to_string());
+        swap_active.error_message = ActiveValue::Set(Some(error.to_string()));
+        swap_active.updated_at = ActiveValue::Set(chrono::Utc::now());
+
+        swap_active.update(&self.db).await.map_err(|e| AppError::Database(e))
+    }
+
+    /// Security report for the token a quote buys; `None` when checks are off or don't apply
+    pub async fn token_security_report(
+        &self,
+        wallet: &wallet::Model,
+        quote: &SwapQuote
+    ) -> Option<TokenSecurity> {
+        let token_security = self.token_security.as_ref()?;
+        let token_address = quote.to_token_address.as_deref()?;
+        let is_evm = wallet.chain.parse::<Chain>().map(|c| c.is_evm()).unwrap_or(false);
+        if wallet.is_testnet || !is_evm || crate::chains::evm::tokens::get_token_by_address(token_address).is_some() {
+            return None;
+        }
+
+        match token_security.check_token(wallet, token_address).await {
+            Ok(security) => Some(security),
+            Err(e) => {
+                tracing::warn!("Token security check failed for {}: {}", token_address, e);
+                None
             }
         }
     }
@@ -194,8 +769,25 @@ impl SwapService {
     }
 
     /// Get DEX provider based on chain
-    fn get_dex_provider(&self, chain: &str) -> Result<Box<dyn DexProvider>> {
+    /// Testnet swaps go straight to the single V2 deployment on that testnet
+    fn get_testnet_dex_provider(&self, chain: Chain) -> Result<Box<dyn DexProvider>> {
+        let rpc_url = match chain {
+            Chain::Eth => "https://ethereum-sepolia-rpc.publicnode.com",
+            Chain::Bsc => "https://data-seed-prebsc-1-s1.binance.org:8545",
+            _ => {
+                return Err(
+                    AppError::Validation(format!("Swaps are not available on {} testnet", chain))
+                );
+            }
+        };
+        Ok(Box::new(UniswapV2Provider::new_testnet(chain.as_str(), rpc_url)?))
+    }
+
+    fn get_dex_provider(&self, chain: &str, testnet: bool) -> Result<Box<dyn DexProvider>> {
         let parsed: Chain = chain.parse()?;
+        if testnet {
+            return self.get_testnet_dex_provider(parsed);
+        }
         match parsed {
             Chain::Solana => Ok(Box::new(JupiterProvider::new())),
             chain if chain.is_evm() => {
@@ -213,9 +805,59 @@ impl SwapService {
                     Chain::Gnosis => "https://rpc.gnosischain.com",
                     _ => unreachable!(),
                 };
-                Ok(Box::new(UniswapV2Provider::new(chain.as_str(), rpc_url)?))
+                let mut dexes: Vec<Arc<dyn DexProvider>> = vec![
+                    Arc::new(UniswapV2Provider::new(chain.as_str(), rpc_url)?)
+                ];
+
+                // Additional V2 forks with meaningful liquidity on the same chain
+                let extra_routers: &[(&str, &str)] = match chain {
+                    Chain::Eth => &[("0xd9e1cE17f2641f24aE83637ab66a2cca9C378B9F", "SushiSwap")],
+                    Chain::Polygon => &[("0x1b02dA8Cb0d097eB8D57A175b88c7D8b47997506", "SushiSwap")],
+                    _ => &[],
+                };
+                for (router, name) in extra_routers {
+                    dexes.push(
+                        Arc::new(UniswapV2Provider::with_router(chain.as_str(), rpc_url, router, name)?)
+                    );
+                }
+
+                if chain == Chain::Bsc {
+                    dexes.push(Arc::new(PancakeSwapV3Provider::new(rpc_url)?));
+                }
+
+                // Chains with multiple DEXes route through the aggregator for best price
+                if dexes.len() > 1 {
+                    Ok(Box::new(DexAggregator::new(chain.as_str(), dexes)))
+                } else {
+                    Ok(Box::new(UniswapV2Provider::new(chain.as_str(), rpc_url)?))
+                }
             }
             _ => Err(AppError::InvalidInput(format!("Swap not supported for chain: {}", chain))),
         }
     }
 }
+
+/// Slippage tolerance typed by the user, e.g. "0.8" or "0.8%"
+pub fn parse_slippage_pct(input: &str) -> Option<f64> {
+    input
+        .trim()
+        .trim_end_matches('%')
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|pct| *pct > 0.0 && *pct <= MAX_SLIPPAGE_PCT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_slippage_with_or_without_percent_sign() {
+        assert_eq!(parse_slippage_pct("0.8"), Some(0.8));
+        assert_eq!(parse_slippage_pct(" 2.5% "), Some(2.5));
+        assert_eq!(parse_slippage_pct("0"), None);
+        assert_eq!(parse_slippage_pct("75"), None);
+        assert_eq!(parse_slippage_pct("abc"), None);
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use chrono::{ DateTime, TimeZone, Utc };
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::TaxLotRepository;
+use crate::error::{ AppError, Result };
+
+/// A lot (or part of one) sold during the report year
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxDisposal {
+    pub lot_id: Uuid,
+    pub wallet_id: Uuid,
+    pub symbol: String,
+    pub quantity: f64,
+    pub acquired_date: DateTime<Utc>,
+    pub disposed_date: DateTime<Utc>,
+    pub cost_basis_usd: f64,
+    pub proceeds_usd: f64,
+    pub realized_gain_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxReport {
+    pub user_id: String,
+    pub year: i32,
+    pub disposals: Vec<TaxDisposal>,
+    pub total_proceeds_usd: f64,
+    pub total_cost_basis_usd: f64,
+    pub total_realized_gain_usd: f64,
+}
+
+impl TaxReport {
+    /// One row per disposal, ready to attach or download
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "symbol,quantity,acquired_date,disposed_date,cost_basis_usd,proceeds_usd,realized_gain_usd,wallet_id\n"
+        );
+        for d in &self.disposals {
+            csv.push_str(&format!(
+                "{},{},{},{},{:.2},{:.2},{:.2},{}\n",
+                d.symbol,
+                d.quantity,
+                d.acquired_date.format("%Y-%m-%d"),
+                d.disposed_date.format("%Y-%m-%d"),
+                d.cost_basis_usd,
+                d.proceeds_usd,
+                d.realized_gain_usd,
+                d.wallet_id
+            ));
+        }
+        csv
+    }
+}
+
+pub struct TaxReportService {
+    tax_lot_repo: Arc<TaxLotRepository>,
+}
+
+impl TaxReportService {
+    pub fn new(tax_lot_repo: Arc<TaxLotRepository>) -> Self {
+        Self { tax_lot_repo }
+    }
+
+    /// All disposals in the calendar year (UTC) with their realized gains/losses
+    pub async fn generate_report(&self, user_id: &str, year: i32) -> Result<TaxReport> {
+        let start = Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).single();
+        let end = Utc.with_ymd_and_hms(year + 1, 1, 1, 0, 0, 0).single();
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(AppError::InvalidInput(format!("Invalid tax year: {}", year)));
+        };
+
+        let lots = self.tax_lot_repo.find_disposed_between(user_id, start, end).await?;
+
+        let disposals: Vec<TaxDisposal> = lots
+            .into_iter()
+            .filter_map(|lot| {
+                let proceeds_usd = lot.proceeds_usd?;
+                Some(TaxDisposal {
+                    lot_id: lot.id,
+                    wallet_id: lot.wallet_id,
+                    realized_gain_usd: lot.realized_gain_usd()?,
+                    symbol: lot.symbol,
+                    quantity: lot.quantity,
+                    acquired_date: lot.acquired_date,
+                    disposed_date: lot.disposed_date?,
+                    cost_basis_usd: lot.cost_basis_usd,
+                    proceeds_usd,
+                })
+            })
+            .collect();
+
+        let total_proceeds_usd = disposals.iter().map(|d| d.proceeds_usd).sum();
+        let total_cost_basis_usd = disposals.iter().map(|d| d.cost_basis_usd).sum();
+        let total_realized_gain_usd = disposals.iter().map(|d| d.realized_gain_usd).sum();
+
+        Ok(TaxReport {
+            user_id: user_id.to_string(),
+            year,
+            disposals,
+            total_proceeds_usd,
+            total_cost_basis_usd,
+            total_realized_gain_usd,
+        })
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::crypto::Encryptor;
+use crate::db::WalletRepository;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::providers::TransactionResponse;
+use crate::rpc::RpcManager;
+
+pub struct TokenApprovalService {
+    repository: Arc<WalletRepository>,
+    rpc_manager: Arc<RpcManager>,
+    encryptor: Arc<Encryptor>,
+}
+
+impl TokenApprovalService {
+    pub fn new(
+        repository: Arc<WalletRepository>,
+        rpc_manager: Arc<RpcManager>,
+        encryptor: Arc<Encryptor>
+    ) -> Self {
+        Self {
+            repository,
+            rpc_manager,
+            encryptor,
+        }
+    }
+
+    /// List ERC-20 approvals granted by an address that still have a non-zero allowance
+    pub async fn list_approvals(
+        &self,
+        wallet_address: &str,
+        chain: &str,
+        testnet: bool
+    ) -> Result<Vec<Approval>> {
+        let parsed: Chain = chain.parse()?;
+        if !parsed.is_evm() {
+            return Err(
+                AppError::InvalidInput(format!("Token approvals are not supported on {}", chain))
+            );
+        }
+
+        let provider = self.rpc_manager.get_network_provider(chain, testnet).await?;
+        let allowances = provider.list_token_approvals(wallet_address).await?;
+
+        Ok(
+            allowances
+                .into_iter()
+                .map(|a| Approval {
+                    spender_name: known_spender_name(&a.spender).map(String::from),
+                    token_address: a.token_address,
+                    token_symbol: a.token_symbol,
+                    spender: a.spender,
+                    allowance: a.allowance,
+                })
+                .collect()
+        )
+    }
+
+    /// Revoke a spender's allowance by approving zero
+    pub async fn revoke_approval(
+        &self,
+        wallet_id: Uuid,
+        token_address: &str,
+        spender: &str
+    ) -> Result<TransactionResponse> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let private_key = self.encryptor.decrypt(&wallet.encrypted_private_key)?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        provider.revoke_token_approval(&private_key, token_address, spender).await
+    }
+}
+
+/// Short id of a token/spender pair for revoke buttons; Telegram's 64-byte callback data
+/// can't hold both addresses next to a wallet id
+pub fn approval_key(token_address: &str, spender: &str) -> String {
+    use sha2::{ Digest, Sha256 };
+
+    let digest = Sha256::new()
+        .chain_update(token_address.to_lowercase())
+        .chain_update(":")
+        .chain_update(spender.to_lowercase())
+        .finalize();
+    hex::encode(&digest[..5])
+}
+
+/// Friendly names for well-known router and aggregator contracts
+fn known_spender_name(spender: &str) -> Option<&'static str> {
+    let name = match spender.to_lowercase().as_str() {
+        "0x7a250d5630b4cf539739df2c5dacb4c659f2488d" => "Uniswap V2 Router",
+        "0xe592427a0aece92de3edee1f18e0157c05861564" => "Uniswap V3 Router",
+        "0x68b3465833fb72a70ecdf485e0e4c7bd8665fc45" => "Uniswap V3 Router 2",
+        "0x3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad" => "Uniswap Universal Router",
+        "0x000000000022d473030f116ddee9f6b43ac78ba3" => "Uniswap Permit2",
+        "0xd9e1ce17f2641f24ae83637ab66a2cca9c378b9f" => "SushiSwap Router",
+        "0x1b02da8cb0d097eb8d57a175b88c7d8b47997506" => "SushiSwap Router",
+        "0x10ed43c718714eb63d5aa57b78b54704e256024e" => "PancakeSwap V2 Router",
+        "0x13f4ea83d0bd40e75c8222255bc855a974568dd4" => "PancakeSwap Smart Router",
+        "0x1111111254eeb25477b68fb85ed929f73a960582" => "1inch Router V5",
+        "0x111111125421ca6dc452d289314280a0f8842a65" => "1inch Router V6",
+        "0xdef1c0ded9bec7f1a1670819833240f027b25eff" => "0x Exchange Proxy",
+        _ => {
+            return None;
+        }
+    };
+    Some(name)
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Approval {
+    pub token_address: String,
+    pub token_symbol: String,
+    pub spender: String,
+    pub allowance: String,
+    pub spender_name: Option<String>,
+}
+
+impl Approval {
+    /// See `approval_key`
+    pub fn key(&self) -> String {
+        approval_key(&self.token_address, &self.spender)
+    }
+}
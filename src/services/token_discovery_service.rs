@@ -1,20 +1,41 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use chrono::{Duration, Utc};
+use futures::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::db::TokenMetadataRepository;
+use crate::db::entity::{token_discovery_cache, token_metadata};
+use crate::db::{
+    CachedTokenBalance, TokenDiscoveryCacheRepository, TokenMetadataInput, TokenMetadataRepository,
+};
 use crate::enums::Chain;
 use crate::error::{AppError, Result};
-use crate::providers::TokenBalanceEntry;
+use crate::providers::{ChainProvider, TokenBalanceEntry};
+use crate::services::solana_token_discovery::SolanaTokenDiscovery;
+use crate::services::TokenListService;
 
-/// Service for discovering all tokens held by a wallet address using Alchemy APIs.
+/// Service for discovering all tokens held by a wallet address.
+/// EVM chains use Alchemy APIs; Solana uses `getTokenAccountsByOwner`.
 #[derive(Clone)]
 pub struct TokenDiscoveryService {
     client: reqwest::Client,
-    api_key: String,
+    api_key: Option<String>,
     token_repo: Arc<TokenMetadataRepository>,
+    solana: Option<Arc<SolanaTokenDiscovery>>,
+    token_list: Option<Arc<TokenListService>>,
+    cache: Option<Arc<TokenDiscoveryCacheRepository>>,
+    cache_interval_secs: u64,
 }
 
+/// Metadata lookups in flight for tokens that aren't cached yet
+const METADATA_FETCH_CONCURRENCY: usize = 8;
+
+/// Widest block range scanned for transfer logs; many RPCs reject larger `eth_getLogs` ranges,
+/// so wallets that went unchecked for longer get a full discovery instead
+const MAX_LOG_BLOCK_RANGE: u64 = 10_000;
+
 // ── Alchemy JSON-RPC response types ────────────────────────────────
 
 #[derive(Debug, Deserialize)]
@@ -62,7 +83,7 @@ pub struct WalletBalances {
 // ── Implementation ──────────────────────────────────────────────────
 
 impl TokenDiscoveryService {
-    pub fn new(api_key: String, token_repo: Arc<TokenMetadataRepository>) -> Self {
+    pub fn new(api_key: Option<String>, token_repo: Arc<TokenMetadataRepository>) -> Self {
         Self {
             client: reqwest::Client::builder()
                 .timeout(std::time::Duration::from_secs(15))
@@ -70,29 +91,73 @@ impl TokenDiscoveryService {
                 .unwrap_or_default(),
             api_key,
             token_repo,
+            solana: None,
+            token_list: None,
+            cache: None,
+            cache_interval_secs: 0,
         }
     }
 
-    /// Whether Alchemy token discovery is supported for this chain.
+    /// Remember each wallet's tokens and only look for new ones every `interval_secs`.
+    pub fn with_discovery_cache(
+        mut self,
+        cache: Arc<TokenDiscoveryCacheRepository>,
+        interval_secs: u64,
+    ) -> Self {
+        self.cache = Some(cache);
+        self.cache_interval_secs = interval_secs;
+        self
+    }
+
+    /// Name well-known tokens from the Uniswap token list instead of Alchemy metadata calls.
+    pub fn with_token_list(mut self, token_list: Arc<TokenListService>) -> Self {
+        self.token_list = Some(token_list);
+        self
+    }
+
+    /// Enable SPL token discovery for Solana wallets.
+    pub fn with_solana(mut self, discovery: SolanaTokenDiscovery) -> Self {
+        self.solana = Some(Arc::new(discovery));
+        self
+    }
+
+    /// Whether token discovery is supported for this chain.
     pub fn is_supported(&self, chain: &Chain) -> bool {
+        if *chain == Chain::Solana {
+            return self.solana.is_some();
+        }
+
         // Alchemy supports token enumeration for EVM chains it knows about
-        chain.alchemy_network_name(false).is_some() && chain.is_evm()
+        self.api_key.is_some() && chain.alchemy_network_name(false).is_some() && chain.is_evm()
     }
 
-    /// Get all ERC-20 token balances for an address on a given chain.
+    /// Get all ERC-20 / SPL token balances for an address on a given chain.
+    /// `provider` reads ERC-20 decimals on-chain in one batch for tokens not cached yet.
     pub async fn get_all_token_balances(
         &self,
         chain: Chain,
         address: &str,
         testnet: bool,
+        provider: &dyn ChainProvider,
     ) -> Result<Vec<TokenBalanceEntry>> {
+        if chain == Chain::Solana {
+            return match self.solana {
+                Some(ref solana) => solana.get_all_token_balances(address).await,
+                None => Err(AppError::External("Solana token discovery not configured".to_string())),
+            };
+        }
+
+        let api_key = self.api_key.as_deref().ok_or_else(|| {
+            AppError::External("ALCHEMY_API_KEY not set".to_string())
+        })?;
+
         let network = chain.alchemy_network_name(testnet).ok_or_else(|| {
             AppError::External(format!("Alchemy not available for {}", chain))
         })?;
 
         let url = format!(
             "https://{}.g.alchemy.com/v2/{}",
-            network, self.api_key
+            network, api_key
         );
 
         // Step 1: fetch all token balances
@@ -137,20 +202,64 @@ impl TokenDiscoveryService {
             })
             .collect();
 
-        // Step 3: enrich with metadata
+        // Step 3: decimals for every token at once, from the cache or a single batched call
+        let addresses: Vec<String> = non_zero
+            .iter()
+            .map(|tb| tb.contract_address.to_lowercase())
+            .collect();
+        let batch_decimals = match self
+            .token_repo
+            .get_or_fetch_decimals(chain, &addresses, provider)
+            .await
+        {
+            Ok(decimals) => decimals,
+            Err(e) => {
+                tracing::warn!("Batch decimals lookup failed on {}: {}", chain, e);
+                HashMap::new()
+            }
+        };
+
+        // Step 4: enrich with metadata; cached tokens come from one query, the rest are
+        // looked up a few at a time
+        let cached: HashMap<String, TokenMetadataInfo> = self
+            .token_repo
+            .find_by_chain_and_addresses(chain.as_str(), &addresses)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            // Decimals-only rows from step 3 still need their symbol and name
+            .filter(|m| !m.symbol.is_empty())
+            .map(|m| (m.contract_address.clone(), TokenMetadataInfo::from(m)))
+            .collect();
+        let lookups: Vec<(String, Option<TokenMetadataInfo>)> = non_zero
+            .iter()
+            .map(|tb| (tb.contract_address.clone(), cached.get(&tb.contract_address.to_lowercase()).cloned()))
+            .collect();
+        let url = url.as_str();
+        let metas: Vec<Result<TokenMetadataInfo>> = stream::iter(lookups)
+            .map(|(address, cached)| async move {
+                match cached {
+                    Some(meta) => Ok(meta),
+                    None => self.get_or_fetch_metadata(chain, &address, url).await,
+                }
+            })
+            .buffered(METADATA_FETCH_CONCURRENCY)
+            .collect()
+            .await;
+
         let mut entries = Vec::with_capacity(non_zero.len());
 
-        for tb in non_zero {
+        for (tb, meta) in non_zero.into_iter().zip(metas) {
             let raw_balance = tb.token_balance.unwrap_or_default();
 
-            let meta = self
-                .get_or_fetch_metadata(chain, &tb.contract_address, &url)
-                .await;
-
             let (symbol, name, decimals, logo_url) = match meta {
                 Ok(m) => (m.symbol, m.name, m.decimals, m.logo_url),
                 Err(_) => ("UNKNOWN".to_string(), "Unknown Token".to_string(), 18, None),
             };
+            let decimals = batch_decimals
+                .get(&tb.contract_address.to_lowercase())
+                .copied()
+                .unwrap_or(decimals);
 
             let formatted = format_hex_balance(&raw_balance, decimals);
 
@@ -167,7 +276,164 @@ impl TokenDiscoveryService {
         Ok(entries)
     }
 
-    /// Get or fetch token metadata. DB cache first, then Alchemy API, then store.
+    /// Token balances of a wallet, served from the discovery cache while it is fresh. A stale
+    /// EVM cache is brought up to date from `Transfer` logs since the last synced block, re-reading
+    /// balances only for contracts that appear in them; anything else runs a full discovery.
+    pub async fn discover_and_cache_new_tokens(
+        &self,
+        wallet_id: Uuid,
+        chain: Chain,
+        address: &str,
+        testnet: bool,
+        provider: &dyn ChainProvider,
+    ) -> Result<Vec<TokenBalanceEntry>> {
+        let cache = match self.cache {
+            Some(ref cache) => cache,
+            None => return self.get_all_token_balances(chain, address, testnet, provider).await,
+        };
+
+        let cached = cache.find(wallet_id, chain.as_str()).await?;
+        if let Some(ref row) = cached {
+            let fresh_until = row.last_discovery_at + Duration::seconds(self.cache_interval_secs as i64);
+            if Utc::now() < fresh_until {
+                return self.cached_entries(chain, &CachedTokenBalance::from_row(row)).await;
+            }
+        }
+
+        let latest_block = if chain.is_evm() {
+            provider.get_block_number().await.ok()
+        } else {
+            None
+        };
+
+        let incremental = match (&cached, latest_block) {
+            (Some(row), Some(latest)) => match self
+                .sync_from_transfer_logs(chain, address, row, latest, provider)
+                .await
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    tracing::warn!(
+                        "Incremental token discovery failed for wallet {}, running a full one: {}",
+                        wallet_id,
+                        e
+                    );
+                    None
+                }
+            },
+            _ => None,
+        };
+        let entries = match incremental {
+            Some(entries) => entries,
+            None => self.get_all_token_balances(chain, address, testnet, provider).await?,
+        };
+
+        let tokens: Vec<CachedTokenBalance> = entries
+            .iter()
+            .map(|e| CachedTokenBalance {
+                contract_address: e.contract_address.to_lowercase(),
+                balance: e.balance.clone(),
+            })
+            .collect();
+        if let Err(e) = cache.save(wallet_id, chain.as_str(), &tokens, latest_block).await {
+            tracing::warn!("Failed to cache discovered tokens for wallet {}: {}", wallet_id, e);
+        }
+
+        Ok(entries)
+    }
+
+    /// Cached balances updated with the contracts touched by transfers since the row's last
+    /// synced block. `None` when the row has no block to start from or the gap is too wide.
+    async fn sync_from_transfer_logs(
+        &self,
+        chain: Chain,
+        address: &str,
+        row: &token_discovery_cache::Model,
+        latest_block: u64,
+        provider: &dyn ChainProvider,
+    ) -> Result<Option<Vec<TokenBalanceEntry>>> {
+        let synced = match row.last_synced_block {
+            Some(block) => block as u64,
+            None => return Ok(None),
+        };
+        if latest_block.saturating_sub(synced) > MAX_LOG_BLOCK_RANGE {
+            return Ok(None);
+        }
+
+        let mut tokens = CachedTokenBalance::from_row(row);
+        if latest_block > synced {
+            let touched = provider
+                .get_token_transfer_contracts(address, synced + 1, latest_block)
+                .await?;
+
+            for contract in touched {
+                let contract = contract.to_lowercase();
+                let balance = provider.get_token_balance(address, &contract).await?;
+
+                // Store metadata for new contracts so cached reads can name them
+                let (symbol, decimals) = (balance.symbol.clone(), balance.decimals);
+                let contract_address = contract.clone();
+                self.token_repo
+                    .get_or_fetch(chain, &contract, self.token_list.as_deref(), || async move {
+                        Ok(TokenMetadataInput {
+                            chain: chain.as_str().to_string(),
+                            contract_address,
+                            name: symbol.clone(),
+                            symbol,
+                            decimals: decimals as i16,
+                            logo_url: None,
+                            coingecko_id: None,
+                        })
+                    })
+                    .await?;
+
+                tokens.retain(|t| t.contract_address != contract);
+                if balance.balance.parse::<f64>().map(|b| b > 0.0).unwrap_or(false) {
+                    tokens.push(CachedTokenBalance {
+                        contract_address: contract,
+                        balance: balance.balance,
+                    });
+                }
+            }
+        }
+
+        self.cached_entries(chain, &tokens).await.map(Some)
+    }
+
+    /// Balance entries for cached tokens, named from stored token metadata
+    async fn cached_entries(
+        &self,
+        chain: Chain,
+        tokens: &[CachedTokenBalance],
+    ) -> Result<Vec<TokenBalanceEntry>> {
+        let addresses: Vec<String> = tokens.iter().map(|t| t.contract_address.clone()).collect();
+        let metadata: HashMap<String, _> = self
+            .token_repo
+            .find_by_chain_and_addresses(chain.as_str(), &addresses)
+            .await?
+            .into_iter()
+            .map(|m| (m.contract_address.clone(), m))
+            .collect();
+
+        Ok(tokens
+            .iter()
+            .map(|t| {
+                let meta = metadata.get(&t.contract_address.to_lowercase());
+                // Rows cached for their decimals alone have no symbol or name yet
+                let named = meta.filter(|m| !m.symbol.is_empty());
+                TokenBalanceEntry {
+                    contract_address: t.contract_address.clone(),
+                    symbol: named.map(|m| m.symbol.clone()).unwrap_or_else(|| "UNKNOWN".to_string()),
+                    name: named.map(|m| m.name.clone()).unwrap_or_else(|| "Unknown Token".to_string()),
+                    decimals: meta.map(|m| m.decimals as u8).unwrap_or(18),
+                    balance: t.balance.clone(),
+                    logo_url: meta.and_then(|m| m.logo_url.clone()),
+                }
+            })
+            .collect())
+    }
+
+    /// Get or fetch token metadata. DB cache first, then the token list, then Alchemy API.
     async fn get_or_fetch_metadata(
         &self,
         chain: Chain,
@@ -176,21 +442,22 @@ impl TokenDiscoveryService {
     ) -> Result<TokenMetadataInfo> {
         let address_lower = contract_address.to_lowercase();
 
-        // Check DB cache
-        if let Some(cached) = self
+        let model = self
             .token_repo
-            .find_by_chain_and_address(chain.as_str(), &address_lower)
-            .await?
-        {
-            return Ok(TokenMetadataInfo {
-                symbol: cached.symbol,
-                name: cached.name,
-                decimals: cached.decimals as u8,
-                logo_url: cached.logo_url,
-            });
-        }
+            .get_or_fetch(chain, &address_lower, self.token_list.as_deref(), || {
+                self.fetch_alchemy_metadata(chain, &address_lower, rpc_url)
+            })
+            .await?;
+
+        Ok(model.into())
+    }
 
-        // Fetch from Alchemy
+    async fn fetch_alchemy_metadata(
+        &self,
+        chain: Chain,
+        contract_address: &str,
+        rpc_url: &str,
+    ) -> Result<TokenMetadataInput> {
         let body = serde_json::json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -218,34 +485,19 @@ impl TokenDiscoveryService {
             logo: None,
         });
 
-        let symbol = meta.symbol.unwrap_or_else(|| "UNKNOWN".to_string());
-        let name = meta.name.unwrap_or_else(|| "Unknown Token".to_string());
-        let decimals = meta.decimals.unwrap_or(18);
-        let logo_url = meta.logo;
-
-        // Store in DB
-        let _ = self
-            .token_repo
-            .upsert(
-                chain.as_str(),
-                &address_lower,
-                &symbol,
-                &name,
-                decimals as i16,
-                logo_url.clone(),
-                None,
-            )
-            .await;
-
-        Ok(TokenMetadataInfo {
-            symbol,
-            name,
-            decimals,
-            logo_url,
+        Ok(TokenMetadataInput {
+            chain: chain.as_str().to_string(),
+            contract_address: contract_address.to_string(),
+            symbol: meta.symbol.unwrap_or_else(|| "UNKNOWN".to_string()),
+            name: meta.name.unwrap_or_else(|| "Unknown Token".to_string()),
+            decimals: meta.decimals.unwrap_or(18) as i16,
+            logo_url: meta.logo,
+            coingecko_id: None,
         })
     }
 }
 
+#[derive(Clone)]
 struct TokenMetadataInfo {
     symbol: String,
     name: String,
@@ -253,6 +505,17 @@ struct TokenMetadataInfo {
     logo_url: Option<String>,
 }
 
+impl From<token_metadata::Model> for TokenMetadataInfo {
+    fn from(model: token_metadata::Model) -> Self {
+        Self {
+            symbol: model.symbol,
+            name: model.name,
+            decimals: model.decimals as u8,
+            logo_url: model.logo_url,
+        }
+    }
+}
+
 /// Parse a hex balance string (e.g. "0x1234") and format it with decimals.
 fn format_hex_balance(hex_str: &str, decimals: u8) -> String {
     let hex_clean = hex_str.trim_start_matches("0x");
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tokio::time::{ interval, Duration };
+
+use crate::error::{ AppError, Result };
+
+const UNISWAP_TOKEN_LIST_URL: &str = "https://tokens.uniswap.org";
+const REFRESH_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// A token from the Uniswap default list
+#[derive(Debug, Clone)]
+pub struct TokenListEntry {
+    pub symbol: String,
+    pub name: String,
+    pub decimals: u8,
+    pub logo_uri: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenListResponse {
+    tokens: Vec<TokenListToken>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenListToken {
+    chain_id: u64,
+    address: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    #[serde(rename = "logoURI")]
+    logo_uri: Option<String>,
+}
+
+/// In-memory copy of the Uniswap default token list, keyed by chain id and lowercase address.
+/// Lets EVM token metadata resolve without RPC calls for well-known tokens.
+pub struct TokenListService {
+    client: reqwest::Client,
+    tokens: RwLock<HashMap<(u64, String), TokenListEntry>>,
+}
+
+impl TokenListService {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .unwrap_or_default(),
+            tokens: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// The listed token at `address` on `chain_id`, if any
+    pub async fn lookup(&self, chain_id: u64, address: &str) -> Option<TokenListEntry> {
+        self.tokens.read().await.get(&(chain_id, address.to_lowercase())).cloned()
+    }
+
+    /// Number of tokens currently loaded
+    pub async fn token_count(&self) -> usize {
+        self.tokens.read().await.len()
+    }
+
+    /// Download the list and replace the in-memory copy
+    pub async fn refresh(&self) -> Result<()> {
+        let response = self.client
+            .get(UNISWAP_TOKEN_LIST_URL)
+            .send().await
+            .map_err(|e| AppError::External(format!("Token list request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(
+                AppError::External(format!("Token list returned status: {}", response.status()))
+            );
+        }
+        let list: TokenListResponse = response
+            .json().await
+            .map_err(|e| AppError::External(format!("Failed to parse token list: {}", e)))?;
+
+        let tokens: HashMap<(u64, String), TokenListEntry> = list.tokens
+            .into_iter()
+            .map(|t| {
+                (
+                    (t.chain_id, t.address.to_lowercase()),
+                    TokenListEntry {
+                        symbol: t.symbol,
+                        name: t.name,
+                        decimals: t.decimals,
+                        logo_uri: t.logo_uri,
+                    },
+                )
+            })
+            .collect();
+
+        *self.tokens.write().await = tokens;
+        Ok(())
+    }
+
+    /// Load the list now and again every 24 hours; a failed refresh keeps the previous copy
+    pub async fn run(self: Arc<Self>) {
+        let mut interval = interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            match self.refresh().await {
+                Ok(()) => tracing::info!("Token list loaded: {} tokens", self.token_count().await),
+                Err(e) => tracing::warn!("Token list refresh failed: {}", e),
+            }
+        }
+    }
+}
+
+impl Default for TokenListService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,249 @@
+use serde::{ Deserialize, Serialize };
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::entity::wallet;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::rpc::RpcManager;
+
+const HONEYPOT_API_URL: &str = "https://api.honeypot.is/v2/IsHoneypot";
+
+/// Tax above which a token is flagged as high risk, in percent
+pub const HIGH_TAX_PCT: f64 = 10.0;
+
+/// Pool liquidity in USD below which a failed Honeypot.is simulation is treated as
+/// inconclusive rather than as a honeypot
+const MIN_SIMULATION_LIQUIDITY_USD: f64 = 1_000.0;
+
+/// Outcome of a token security check
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenSecurity {
+    pub is_honeypot: bool,
+    pub buy_tax_pct: f64,
+    pub sell_tax_pct: f64,
+    pub is_proxy: bool,
+    pub warnings: Vec<String>,
+}
+
+impl TokenSecurity {
+    /// Overall risk level used for display
+    pub fn risk_level(&self) -> RiskLevel {
+        if
+            self.is_honeypot ||
+            self.buy_tax_pct > HIGH_TAX_PCT ||
+            self.sell_tax_pct > HIGH_TAX_PCT
+        {
+            RiskLevel::High
+        } else if self.is_proxy || !self.warnings.is_empty() {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Deserialize)]
+struct HoneypotResponse {
+    #[serde(rename = "honeypotResult")]
+    honeypot_result: Option<HoneypotResult>,
+    #[serde(rename = "simulationSuccess", default = "default_true")]
+    simulation_success: bool,
+    #[serde(rename = "simulationError")]
+    simulation_error: Option<String>,
+    #[serde(rename = "simulationResult")]
+    simulation_result: Option<HoneypotSimulation>,
+    pair: Option<HoneypotPair>,
+    #[serde(rename = "contractCode")]
+    contract_code: Option<HoneypotContractCode>,
+    summary: Option<HoneypotSummary>,
+}
+
+#[derive(Deserialize)]
+struct HoneypotResult {
+    #[serde(rename = "isHoneypot")]
+    is_honeypot: bool,
+}
+
+#[derive(Deserialize)]
+struct HoneypotPair {
+    #[serde(default)]
+    liquidity: f64,
+}
+
+#[derive(Deserialize)]
+struct HoneypotSimulation {
+    #[serde(rename = "buyTax", default)]
+    buy_tax: f64,
+    #[serde(rename = "sellTax", default)]
+    sell_tax: f64,
+}
+
+#[derive(Deserialize)]
+struct HoneypotContractCode {
+    #[serde(rename = "isProxy", default)]
+    is_proxy: bool,
+    #[serde(rename = "openSource", default = "default_true")]
+    open_source: bool,
+}
+
+#[derive(Deserialize)]
+struct HoneypotSummary {
+    #[serde(default)]
+    flags: Vec<HoneypotFlag>,
+}
+
+#[derive(Deserialize)]
+struct HoneypotFlag {
+    description: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Honeypot and tax checks for tokens about to be bought
+pub struct TokenSecurityService {
+    client: reqwest::Client,
+    rpc_manager: Arc<RpcManager>,
+}
+
+impl TokenSecurityService {
+    pub fn new(rpc_manager: Arc<RpcManager>) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap(),
+            rpc_manager,
+        }
+    }
+
+    /// Check a token on the wallet's chain using Honeypot.is where supported, falling back
+    /// to on-chain inspection through the wallet's own provider
+    pub async fn check_token(&self, wallet: &wallet::Model, token_address: &str) -> Result<TokenSecurity> {
+        let chain = wallet.chain.as_str();
+        let parsed: Chain = chain.parse()?;
+        if !parsed.is_evm() {
+            return Err(
+                AppError::InvalidInput(format!("Token security checks are not available on {}", chain))
+            );
+        }
+
+        if let Some(chain_id) = honeypot_chain_id(parsed) {
+            match self.check_honeypot_is(chain_id, token_address).await {
+                Ok(security) => return Ok(security),
+                Err(e) => tracing::warn!("Honeypot.is check failed, inspecting on-chain: {}", e),
+            }
+        }
+
+        self.inspect_on_chain(wallet, token_address).await
+    }
+
+    async fn check_honeypot_is(&self, chain_id: u64, token_address: &str) -> Result<TokenSecurity> {
+        let data: HoneypotResponse = self.client
+            .get(HONEYPOT_API_URL)
+            .query(&[("address", token_address), ("chainID", &chain_id.to_string())])
+            .send().await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::External(format!("Honeypot.is request failed: {}", e)))?
+            .json().await
+            .map_err(|e| AppError::External(format!("Invalid Honeypot.is response: {}", e)))?;
+
+        let mut warnings: Vec<String> = data.summary
+            .map(|s| s.flags.into_iter().map(|f| f.description).collect())
+            .unwrap_or_default();
+
+        let (is_proxy, open_source) = data.contract_code
+            .map(|c| (c.is_proxy, c.open_source))
+            .unwrap_or((false, true));
+        if !open_source {
+            warnings.push("Contract source code is not verified".to_string());
+        }
+
+        let (buy_tax_pct, sell_tax_pct) = data.simulation_result
+            .map(|s| (s.buy_tax, s.sell_tax))
+            .unwrap_or_else(|| {
+                warnings.push("Buy/sell simulation unavailable".to_string());
+                (0.0, 0.0)
+            });
+
+        let flagged = data.honeypot_result.map(|r| r.is_honeypot).unwrap_or(false);
+        let liquidity_usd = data.pair.map(|p| p.liquidity);
+        let is_honeypot = honeypot_verdict(flagged, data.simulation_success, liquidity_usd);
+        if flagged && !is_honeypot {
+            let reason = data.simulation_error.unwrap_or_else(|| "simulation failed".to_string());
+            warnings.push(match liquidity_usd {
+                Some(liquidity) =>
+                    format!("Honeypot check inconclusive on low liquidity (${:.0}): {}", liquidity, reason),
+                None => format!("Honeypot check inconclusive: {}", reason),
+            });
+        }
+
+        Ok(TokenSecurity {
+            is_honeypot,
+            buy_tax_pct,
+            sell_tax_pct,
+            is_proxy,
+            warnings,
+        })
+    }
+
+    async fn inspect_on_chain(&self, wallet: &wallet::Model, token_address: &str) -> Result<TokenSecurity> {
+        let provider = self.rpc_manager.get_wallet_provider(wallet).await?;
+        let inspection = provider.inspect_token(token_address).await?;
+
+        if !inspection.is_contract {
+            return Err(AppError::InvalidInput(format!("{} is not a token contract", token_address)));
+        }
+
+        let mut warnings = vec!["Taxes could not be checked on this chain".to_string()];
+        if let Some(reason) = &inspection.transfer_revert_reason {
+            warnings.push(format!("Transfer simulation reverted: {}", reason));
+        }
+
+        Ok(TokenSecurity {
+            is_honeypot: inspection.transfer_revert_reason.is_some(),
+            buy_tax_pct: 0.0,
+            sell_tax_pct: 0.0,
+            is_proxy: inspection.is_proxy,
+            warnings,
+        })
+    }
+}
+
+/// Whether a Honeypot.is flag should block the swap. A failed buy/sell simulation on a thin
+/// pool usually means the simulated trade itself couldn't fill, so it only counts when the
+/// pool is deep enough for the simulation to be meaningful.
+fn honeypot_verdict(flagged: bool, simulation_success: bool, liquidity_usd: Option<f64>) -> bool {
+    if !flagged {
+        return false;
+    }
+    simulation_success || liquidity_usd.is_some_and(|l| l >= MIN_SIMULATION_LIQUIDITY_USD)
+}
+
+/// Chain IDs covered by the Honeypot.is API
+fn honeypot_chain_id(chain: Chain) -> Option<u64> {
+    match chain {
+        Chain::Eth | Chain::Bsc | Chain::Base => chain.chain_id(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_honeypot_verdict() {
+        assert!(!honeypot_verdict(false, true, Some(50_000.0)));
+        assert!(honeypot_verdict(true, true, Some(50.0)));
+        assert!(honeypot_verdict(true, false, Some(50_000.0)));
+        assert!(!honeypot_verdict(true, false, Some(50.0)));
+        assert!(!honeypot_verdict(true, false, None));
+    }
+}
@@ -1,27 +1,136 @@
 use std::sync::Arc;
+use std::time::Duration;
+use chrono::{ DateTime, Utc };
+use serde::Serialize;
 use uuid::Uuid;
 
-use crate::db::{ TransactionRepository, WalletRepository };
-use crate::enums::TxStatus;
+use crate::crypto::Encryptor;
+use crate::db::{ FeeAggregate, TaxLotRepository, TransactionRepository, WalletRepository };
+use crate::enums::{ Chain, TxStatus };
 use crate::error::{ AppError, Result };
 use crate::db::entity::transaction;
+use crate::providers::{ TransactionDetail, TransactionResponse };
+use crate::rpc::RpcManager;
+use crate::services::{
+    ExplorerService,
+    GasRefundTracker,
+    PolygonBridgeService,
+    PriceService,
+    RecentTransactionCache,
+};
+use crate::services::recent_transaction_cache::RECENT_TRANSACTIONS_CACHED;
+use crate::services::polygon_bridge_service::BridgeTx;
+
+/// How often pending transactions are checked for a receipt
+const CONFIRMATION_POLL_SECS: u64 = 30;
+
+/// Pending transactions looked up per poll
+const CONFIRMATION_BATCH: u64 = 100;
+
+/// Network fees a user paid over a period, per chain and overall
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeSummary {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Most expensive chain first
+    pub chains: Vec<FeeAggregate>,
+    pub total_gas_usd: f64,
+    pub tx_count: u64,
+    pub average_fee_usd: f64,
+    pub most_expensive: Option<PaidFee>,
+}
+
+/// The fee of a single transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct PaidFee {
+    pub tx_hash: String,
+    pub chain: String,
+    pub gas_fee_native: String,
+    pub gas_fee_usd: f64,
+    pub created_at: String,
+}
 
 pub struct TransactionService {
     transaction_repo: Arc<TransactionRepository>,
     wallet_repo: Arc<WalletRepository>,
+    rpc_manager: Arc<RpcManager>,
+    encryptor: Arc<Encryptor>,
+    tax_lots: Option<(Arc<TaxLotRepository>, Arc<PriceService>)>,
+    gas_refund_tracker: Option<Arc<GasRefundTracker>>,
+    bridge_service: Option<Arc<PolygonBridgeService>>,
+    recent_cache: Option<Arc<RecentTransactionCache>>,
+    explorer: Option<Arc<ExplorerService>>,
+    fee_price_service: Option<Arc<PriceService>>,
 }
 
 impl TransactionService {
     pub fn new(
         transaction_repo: Arc<TransactionRepository>,
-        wallet_repo: Arc<WalletRepository>
+        wallet_repo: Arc<WalletRepository>,
+        rpc_manager: Arc<RpcManager>,
+        encryptor: Arc<Encryptor>
     ) -> Self {
         Self {
             transaction_repo,
             wallet_repo,
+            rpc_manager,
+            encryptor,
+            tax_lots: None,
+            gas_refund_tracker: None,
+            bridge_service: None,
+            recent_cache: None,
+            explorer: None,
+            fee_price_service: None,
         }
     }
 
+    /// Record the network fee of confirmed transactions, valued in USD with `price_service`
+    pub fn with_fee_tracking(mut self, price_service: Arc<PriceService>) -> Self {
+        self.fee_price_service = Some(price_service);
+        self
+    }
+
+    /// Fill short first pages of wallet history with on-chain transactions from block explorers,
+    /// so imported wallets show what happened before the bot knew them
+    pub fn with_explorer(mut self, explorer: Arc<ExplorerService>) -> Self {
+        self.explorer = Some(explorer);
+        self
+    }
+
+    /// Serve a wallet's newest transactions from `cache` and invalidate it on every write
+    pub fn with_recent_cache(mut self, cache: Arc<RecentTransactionCache>) -> Self {
+        self.recent_cache = Some(cache);
+        self
+    }
+
+    fn invalidate_recent(&self, wallet_id: Uuid) {
+        if let Some(cache) = &self.recent_cache {
+            cache.invalidate(wallet_id);
+        }
+    }
+
+    /// Record the L1 fee and refund of confirmed Arbitrum transactions
+    pub fn with_gas_refund_tracker(mut self, tracker: Arc<GasRefundTracker>) -> Self {
+        self.gas_refund_tracker = Some(tracker);
+        self
+    }
+
+    /// Look up pending Polygon PoS bridge transfers for Polygon wallets
+    pub fn with_bridge_service(mut self, bridge_service: Arc<PolygonBridgeService>) -> Self {
+        self.bridge_service = Some(bridge_service);
+        self
+    }
+
+    /// Track FIFO cost-basis lots for confirmed transactions, priced with `price_service`
+    pub fn with_tax_lots(
+        mut self,
+        tax_lot_repo: Arc<TaxLotRepository>,
+        price_service: Arc<PriceService>
+    ) -> Self {
+        self.tax_lots = Some((tax_lot_repo, price_service));
+        self
+    }
+
     pub async fn log_transaction(
         &self,
         wallet_id: Uuid,
@@ -37,7 +146,7 @@ impl TransactionService {
         self.wallet_repo.find_by_id(wallet_id).await?;
 
         // Create transaction record
-        self.transaction_repo.create(
+        let tx = self.transaction_repo.create(
             wallet_id,
             tx_hash,
             chain,
@@ -47,7 +156,267 @@ impl TransactionService {
             token_address,
             token_symbol,
             TxStatus::Confirmed.to_string()
-        ).await
+        ).await?;
+
+        self.invalidate_recent(wallet_id);
+        self.record_tax_lots(&tx).await;
+        Ok(self.record_gas_fee(tx).await)
+    }
+
+    /// Poll pending transactions every 30 seconds and confirm or fail them once mined
+    pub async fn run_confirmations(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(Duration::from_secs(CONFIRMATION_POLL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            let pending = match self.transaction_repo.find_pending(CONFIRMATION_BATCH).await {
+                Ok(pending) => pending,
+                Err(e) => {
+                    tracing::warn!("Failed to load pending transactions: {}", e);
+                    continue;
+                }
+            };
+            for tx in pending {
+                if let Err(e) = self.check_confirmation(&tx).await {
+                    tracing::debug!("Confirmation check for {} failed: {}", tx.tx_hash, e);
+                }
+            }
+        }
+    }
+
+    async fn check_confirmation(&self, tx: &transaction::Model) -> Result<()> {
+        let wallet = self.wallet_repo.find_by_id(tx.wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+        let Some(detail) = provider.get_transaction_by_hash(&tx.tx_hash).await? else {
+            return Ok(());
+        };
+
+        let block_number = detail.block_number.map(|n| n as i64);
+        let gas_used = detail.gas_used.map(|g| g.to_string());
+        match detail.status.parse::<TxStatus>() {
+            Ok(TxStatus::Confirmed) => {
+                self.confirm_transaction(&tx.tx_hash, block_number, gas_used).await?;
+            }
+            Ok(TxStatus::Failed) => {
+                self.transaction_repo.update_status(
+                    &tx.tx_hash,
+                    TxStatus::Failed.to_string(),
+                    block_number,
+                    gas_used
+                ).await?;
+                self.invalidate_recent(tx.wallet_id);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Mark a transaction confirmed and update the owner's tax lots
+    pub async fn confirm_transaction(
+        &self,
+        tx_hash: &str,
+        block_number: Option<i64>,
+        gas_used: Option<String>
+    ) -> Result<transaction::Model> {
+        let previous = self.transaction_repo.find_by_tx_hash(tx_hash).await?;
+        let tx = self.transaction_repo.update_status(
+            tx_hash,
+            TxStatus::Confirmed.to_string(),
+            block_number,
+            gas_used
+        ).await?;
+        self.invalidate_recent(tx.wallet_id);
+
+        // Only the first confirmation moves lots
+        if previous.status != TxStatus::Confirmed.as_str() {
+            self.record_tax_lots(&tx).await;
+            let tx = self.record_gas_refund(tx).await;
+            return Ok(self.record_gas_fee(tx).await);
+        }
+        Ok(tx)
+    }
+
+    /// Store the fee the chain charged; failures leave the row as it was
+    async fn record_gas_fee(&self, tx: transaction::Model) -> transaction::Model {
+        let Some(price_service) = &self.fee_price_service else {
+            return tx;
+        };
+
+        let result = async {
+            let wallet = self.wallet_repo.find_by_id(tx.wallet_id).await?;
+            let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+            let Some(fee) = provider
+                .get_transaction_by_hash(&tx.tx_hash).await?
+                .and_then(|detail| detail.fee) else {
+                return Ok(None);
+            };
+
+            // Testnet gas has no market value
+            let fee_usd = match (wallet.is_testnet, tx.chain.parse::<Chain>()) {
+                (false, Ok(chain)) =>
+                    match price_service.get_price(chain.native_symbol()).await {
+                        Ok(price) => Some(fee.parse::<f64>().unwrap_or(0.0) * price.usd_price),
+                        Err(e) => {
+                            tracing::debug!("No {} price for fee of {}: {}", chain.native_symbol(), tx.tx_hash, e);
+                            None
+                        }
+                    }
+                _ => None,
+            };
+
+            self.transaction_repo.set_gas_fees(&tx.tx_hash, fee, fee_usd).await.map(Some)
+        };
+
+        match result.await {
+            Ok(Some(updated)) => {
+                self.invalidate_recent(updated.wallet_id);
+                updated
+            }
+            Ok(None) => tx,
+            Err(e) => {
+                tracing::warn!("Failed to record gas fee for {}: {}", tx.tx_hash, e);
+                tx
+            }
+        }
+    }
+
+    /// Fees paid across a user's wallets between `from` and `to`
+    pub async fn get_fee_summary(
+        &self,
+        user_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<FeeSummary> {
+        if from > to {
+            return Err(AppError::InvalidInput("'from' must be before 'to'".to_string()));
+        }
+
+        let chains = self.transaction_repo.aggregate_fees_paid(user_id, from, to).await?;
+        let most_expensive = self.transaction_repo
+            .find_most_expensive_fee(user_id, from, to).await?
+            .map(|tx| PaidFee {
+                gas_fee_native: tx.gas_fee_native.unwrap_or_default(),
+                gas_fee_usd: tx.gas_fee_usd.unwrap_or(0.0),
+                created_at: tx.created_at.to_string(),
+                tx_hash: tx.tx_hash,
+                chain: tx.chain,
+            });
+
+        let total_gas_usd: f64 = chains
+            .iter()
+            .map(|c| c.total_gas_usd)
+            .sum();
+        let tx_count: u64 = chains
+            .iter()
+            .map(|c| c.tx_count)
+            .sum();
+        let average_fee_usd = if tx_count > 0 { total_gas_usd / (tx_count as f64) } else { 0.0 };
+
+        Ok(FeeSummary {
+            from,
+            to,
+            chains,
+            total_gas_usd,
+            tx_count,
+            average_fee_usd,
+            most_expensive,
+        })
+    }
+
+    /// Attach the L1 fee breakdown when the chain has one; failures leave the row as it was
+    async fn record_gas_refund(&self, tx: transaction::Model) -> transaction::Model {
+        let Some(tracker) = &self.gas_refund_tracker else {
+            return tx;
+        };
+
+        let result = async {
+            let wallet = self.wallet_repo.find_by_id(tx.wallet_id).await?;
+            let Some(refund) = tracker.track(
+                &tx.chain,
+                &tx.tx_hash,
+                &wallet.address,
+                wallet.is_testnet
+            ).await? else {
+                return Ok(None);
+            };
+            self.transaction_repo
+                .set_l1_fees(&tx.tx_hash, refund.l1_fee_paid, refund.l1_refund).await
+                .map(Some)
+        };
+
+        match result.await {
+            Ok(Some(updated)) => {
+                self.invalidate_recent(updated.wallet_id);
+                updated
+            }
+            Ok(None) => tx,
+            Err(e) => {
+                tracing::warn!("Failed to track L1 gas refund for {}: {}", tx.tx_hash, e);
+                tx
+            }
+        }
+    }
+
+    /// Open a lot for inbound funds or consume lots FIFO for outbound ones.
+    /// Failures are logged rather than failing the confirmation itself.
+    async fn record_tax_lots(&self, tx: &transaction::Model) {
+        let Some((tax_lot_repo, price_service)) = &self.tax_lots else {
+            return;
+        };
+
+        if let Err(e) = self.apply_tax_lots(tax_lot_repo, price_service, tx).await {
+            tracing::warn!("Failed to update tax lots for {}: {}", tx.tx_hash, e);
+        }
+    }
+
+    async fn apply_tax_lots(
+        &self,
+        tax_lot_repo: &TaxLotRepository,
+        price_service: &PriceService,
+        tx: &transaction::Model
+    ) -> Result<()> {
+        let wallet = self.wallet_repo.find_by_id(tx.wallet_id).await?;
+        if wallet.is_testnet {
+            return Ok(());
+        }
+
+        let Some(symbol) = tx.token_symbol.clone().or_else(|| {
+            tx.chain.parse::<Chain>().ok().map(|c| c.native_symbol().to_string())
+        }) else {
+            return Ok(());
+        };
+
+        let quantity: f64 = tx.amount.parse().unwrap_or(0.0);
+        if quantity <= 0.0 {
+            return Ok(());
+        }
+
+        let is_outbound = tx.from_address.eq_ignore_ascii_case(&wallet.address);
+        let is_inbound = tx.to_address.eq_ignore_ascii_case(&wallet.address);
+        if is_outbound == is_inbound {
+            // Self-transfers don't change cost basis
+            return Ok(());
+        }
+
+        let usd_value = price_service.get_price(&symbol).await?.usd_price * quantity;
+        let when = tx.created_at.and_utc();
+
+        if is_inbound {
+            tax_lot_repo.create(&wallet.user_id, wallet.id, &symbol, when, quantity, usd_value).await?;
+        } else {
+            let disposal = tax_lot_repo.dispose_fifo(wallet.id, &symbol, quantity, usd_value, when).await?;
+            if disposal.untracked_quantity > 0.0 {
+                tracing::warn!(
+                    "{} {} sent from wallet {} had no tax lot to match",
+                    disposal.untracked_quantity,
+                    symbol,
+                    wallet.id
+                );
+            }
+        }
+
+        Ok(())
     }
 
     pub async fn get_wallet_transactions(
@@ -57,9 +426,124 @@ impl TransactionService {
         offset: Option<u64>
     ) -> Result<Vec<transaction::Model>> {
         // Verify wallet exists
-        self.wallet_repo.find_by_id(wallet_id).await?;
+        let wallet = self.wallet_repo.find_by_id(wallet_id).await?;
+
+        let local = self.local_wallet_transactions(wallet_id, limit, offset).await?;
+
+        // Explorer pages don't line up with local offsets, so only the first page is filled in
+        match (limit, offset) {
+            (Some(limit), None | Some(0)) if (local.len() as u64) < limit => {
+                Ok(self.with_explorer_transactions(&wallet, local, limit).await)
+            }
+            _ => Ok(local),
+        }
+    }
+
+    /// Append explorer transactions the database doesn't know, newest first, up to `limit`.
+    /// Explorer failures only cost the extra rows.
+    async fn with_explorer_transactions(
+        &self,
+        wallet: &crate::db::entity::wallet::Model,
+        mut transactions: Vec<transaction::Model>,
+        limit: u64
+    ) -> Vec<transaction::Model> {
+        let Some(explorer) = &self.explorer else {
+            return transactions;
+        };
+        let supported = wallet.chain.parse::<Chain>().is_ok_and(|c| explorer.is_supported(&c));
+        if wallet.is_testnet || !supported {
+            return transactions;
+        }
+
+        let page_size = limit.min(u32::MAX as u64) as u32;
+        let fetched = match explorer.fetch_transactions(&wallet.chain, &wallet.address, 1, page_size).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                tracing::warn!("Explorer history lookup failed for wallet {}: {}", wallet.id, e);
+                return transactions;
+            }
+        };
+
+        let known: std::collections::HashSet<String> = transactions
+            .iter()
+            .map(|tx| tx.tx_hash.to_lowercase())
+            .collect();
+        transactions.extend(
+            fetched
+                .into_iter()
+                .filter(|tx| !known.contains(&tx.tx_hash.to_lowercase()))
+                .map(|tx| tx.into_model(wallet.id))
+        );
+        transactions.sort_by_key(|tx| std::cmp::Reverse(tx.created_at));
+        transactions.truncate(limit as usize);
+        transactions
+    }
+
+    /// The wallet's transactions from the database, through the recent cache when it applies
+    async fn local_wallet_transactions(
+        &self,
+        wallet_id: Uuid,
+        limit: Option<u64>,
+        offset: Option<u64>
+    ) -> Result<Vec<transaction::Model>> {
+        // Only the first page of a bounded size is cached
+        let cacheable = match (limit, offset, &self.recent_cache) {
+            (Some(limit), None | Some(0), Some(cache)) if limit <= RECENT_TRANSACTIONS_CACHED =>
+                Some((limit, cache)),
+            _ => None,
+        };
+        let Some((limit, cache)) = cacheable else {
+            return self.transaction_repo.find_by_wallet_id(wallet_id, limit, offset).await;
+        };
+
+        if let Some(transactions) = cache.get(wallet_id, limit) {
+            return Ok(transactions);
+        }
+
+        let mut transactions = self.transaction_repo
+            .find_by_wallet_id(wallet_id, Some(RECENT_TRANSACTIONS_CACHED), None).await?;
+        cache.insert(wallet_id, transactions.clone());
+        transactions.truncate(limit as usize);
+        Ok(transactions)
+    }
+
+    /// Transactions paying into the wallet's address, whichever wallet recorded them
+    pub async fn get_inbound_transactions(
+        &self,
+        wallet_id: Uuid,
+        limit: Option<u64>,
+        offset: Option<u64>
+    ) -> Result<Vec<transaction::Model>> {
+        let wallet = self.wallet_repo.find_by_id(wallet_id).await?;
 
-        self.transaction_repo.find_by_wallet_id(wallet_id, limit, offset).await
+        self.transaction_repo.find_by_to_address(&wallet.address, &wallet.chain, limit, offset).await
+    }
+
+    /// Recent transfers between two addresses, for spotting repeat counterparties
+    pub async fn get_transfers_between(
+        &self,
+        from_address: &str,
+        to_address: &str,
+        chain: &str,
+        limit: u64
+    ) -> Result<Vec<transaction::Model>> {
+        self.transaction_repo.find_by_address_pair(from_address, to_address, chain, limit).await
+    }
+
+    /// Pending PoS bridge deposits and withdrawals for a Polygon wallet; empty for other chains
+    pub async fn get_pending_bridge_transactions(&self, wallet_id: Uuid) -> Result<Vec<BridgeTx>> {
+        let wallet = self.wallet_repo.find_by_id(wallet_id).await?;
+
+        let Some(bridge_service) = &self.bridge_service else {
+            return Ok(Vec::new());
+        };
+        if wallet.chain != Chain::Polygon.as_str() || wallet.is_testnet {
+            return Ok(Vec::new());
+        }
+
+        let mut pending = bridge_service.get_pending_deposits(&wallet.address).await?;
+        pending.extend(bridge_service.get_pending_withdrawals(&wallet.address).await?);
+        Ok(pending)
     }
 
     pub async fn get_user_transactions(
@@ -92,4 +576,92 @@ impl TransactionService {
     pub async fn get_transaction_by_hash(&self, tx_hash: &str) -> Result<transaction::Model> {
         self.transaction_repo.find_by_tx_hash(tx_hash).await
     }
+
+    pub async fn get_transaction(&self, tx_id: Uuid) -> Result<transaction::Model> {
+        self.transaction_repo.find_by_id(tx_id).await
+    }
+
+    /// Look up any transaction on `chain` (mainnet or testnet) straight from the node,
+    /// including ones the bot didn't send
+    pub async fn get_chain_transaction(
+        &self,
+        chain: &str,
+        testnet: bool,
+        tx_hash: &str
+    ) -> Result<Option<TransactionDetail>> {
+        let provider = self.rpc_manager.get_network_provider(chain, testnet).await?;
+        provider.get_transaction_by_hash(tx_hash.trim()).await
+    }
+
+    /// Rebroadcast a pending transaction with the same nonce and a higher fee.
+    /// `new_max_fee` is in gwei; defaults to the original fee plus 10%.
+    pub async fn speed_up_transaction(
+        &self,
+        tx_id: Uuid,
+        new_max_fee: Option<String>
+    ) -> Result<TransactionResponse> {
+        let original = self.find_pending(tx_id).await?;
+        let wallet = self.wallet_repo.find_by_id(original.wallet_id).await?;
+        let private_key = self.encryptor.decrypt(&wallet.encrypted_private_key)?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        let response = provider.speed_up_transaction(
+            &private_key,
+            &original.tx_hash,
+            new_max_fee
+        ).await?;
+
+        self.transaction_repo.create_replacement(
+            &original,
+            response.tx_hash.clone(),
+            original.to_address.clone(),
+            original.amount.clone(),
+            original.token_address.clone(),
+            original.token_symbol.clone(),
+            response.status.clone()
+        ).await?;
+        self.invalidate_recent(original.wallet_id);
+
+        Ok(response)
+    }
+
+    /// Cancel a pending transaction by replacing it with a zero-value self-transfer
+    pub async fn cancel_transaction(&self, tx_id: Uuid) -> Result<TransactionResponse> {
+        let original = self.find_pending(tx_id).await?;
+        let wallet = self.wallet_repo.find_by_id(original.wallet_id).await?;
+        let private_key = self.encryptor.decrypt(&wallet.encrypted_private_key)?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        let response = provider.cancel_transaction(&private_key, &original.tx_hash).await?;
+
+        let native_symbol = wallet.chain
+            .parse::<Chain>()
+            .ok()
+            .map(|c| c.native_symbol().to_string());
+
+        self.transaction_repo.create_replacement(
+            &original,
+            response.tx_hash.clone(),
+            wallet.address.clone(),
+            "0".to_string(),
+            None,
+            native_symbol,
+            response.status.clone()
+        ).await?;
+        self.invalidate_recent(original.wallet_id);
+
+        Ok(response)
+    }
+
+    async fn find_pending(&self, tx_id: Uuid) -> Result<transaction::Model> {
+        let tx = self.transaction_repo.find_by_id(tx_id).await?;
+
+        if tx.status != TxStatus::Pending.as_str() {
+            return Err(
+                AppError::Validation(format!("Only pending transactions can be replaced (status: {})", tx.status))
+            );
+        }
+
+        Ok(tx)
+    }
 }
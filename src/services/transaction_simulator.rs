@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::db::WalletRepository;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::providers::{ SimulationResult, TransactionRequest };
+use crate::rpc::RpcManager;
+use crate::services::transfer_service::TransferRequest;
+
+pub struct TransactionSimulator {
+    repository: Arc<WalletRepository>,
+    rpc_manager: Arc<RpcManager>,
+}
+
+impl TransactionSimulator {
+    pub fn new(repository: Arc<WalletRepository>, rpc_manager: Arc<RpcManager>) -> Self {
+        Self {
+            repository,
+            rpc_manager,
+        }
+    }
+
+    /// Dry-run a transfer with `eth_call` before it is broadcast.
+    /// A reverting transaction is returned as `AppError::SimulationFailed` with the decoded reason.
+    pub async fn simulate(&self, wallet_id: Uuid, request: &TransferRequest) -> Result<SimulationResult> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+
+        let chain: Chain = wallet.chain.parse()?;
+        if !chain.is_evm() {
+            return Err(
+                AppError::InvalidInput(format!("Transaction simulation is not supported on {}", wallet.chain))
+            );
+        }
+
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+        if !provider.validate_address(&request.to) {
+            return Err(AppError::InvalidAddress);
+        }
+
+        let tx_request = TransactionRequest {
+            from: wallet.address.clone(),
+            to: request.to.clone(),
+            amount: request.amount.clone(),
+            token_address: request.token_address.clone(),
+            max_fee_per_gas: request.max_fee_per_gas.clone(),
+            max_priority_fee_per_gas: request.max_priority_fee_per_gas.clone(),
+            gas_limit: request.gas_limit,
+            compute_units: request.compute_units,
+        };
+
+        let result = provider.simulate_transaction(&tx_request).await?;
+        if !result.success {
+            return Err(
+                AppError::SimulationFailed(
+                    result.revert_reason.unwrap_or_else(|| "Execution reverted".to_string())
+                )
+            );
+        }
+
+        Ok(result)
+    }
+}
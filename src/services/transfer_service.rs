@@ -1,18 +1,34 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::config::{ RetryableError, TransferConfig };
 use crate::crypto::Encryptor;
 use crate::db::{ WalletRepository, TransactionRepository };
+use crate::db::entity::wallet;
 use crate::enums::{ Chain, TxStatus };
-use crate::error::Result;
-use crate::providers::{ TransactionRequest, TransactionResponse };
+use crate::error::{ AppError, Result };
+use crate::providers::{ ChainProvider, TransactionRequest, TransactionResponse };
 use crate::rpc::RpcManager;
+use crate::services::{ BalanceService, GasEstimationService, PriceService, RecentTransactionCache };
+use crate::services::security_service::SecurityService;
 
 pub struct TransferService {
     repository: Arc<WalletRepository>,
     transaction_repo: Arc<TransactionRepository>,
     rpc_manager: Arc<RpcManager>,
     encryptor: Arc<Encryptor>,
+    balance_service: Arc<BalanceService>,
+    gas_estimation_service: Arc<GasEstimationService>,
+    transfer_limit: Option<(Arc<SecurityService>, Arc<PriceService>)>,
+    recent_cache: Option<Arc<RecentTransactionCache>>,
+    retry_config: TransferConfig,
+}
+
+/// Retries a broadcast needed before it went through
+struct BroadcastRetries {
+    count: u32,
+    last_error: Option<String>,
 }
 
 impl TransferService {
@@ -20,14 +36,176 @@ impl TransferService {
         repository: Arc<WalletRepository>,
         transaction_repo: Arc<TransactionRepository>,
         rpc_manager: Arc<RpcManager>,
-        encryptor: Arc<Encryptor>
+        encryptor: Arc<Encryptor>,
+        balance_service: Arc<BalanceService>,
+        gas_estimation_service: Arc<GasEstimationService>
     ) -> Self {
         Self {
             repository,
             transaction_repo,
             rpc_manager,
             encryptor,
+            balance_service,
+            gas_estimation_service,
+            transfer_limit: None,
+            recent_cache: None,
+            retry_config: TransferConfig::default(),
+        }
+    }
+
+    /// Retry policy for broadcasts that fail with a transient RPC error
+    pub fn with_retry_config(mut self, retry_config: TransferConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Invalidate a wallet's cached history whenever a transfer from it is recorded
+    pub fn with_recent_cache(mut self, cache: Arc<RecentTransactionCache>) -> Self {
+        self.recent_cache = Some(cache);
+        self
+    }
+
+    fn invalidate_recent(&self, wallet_id: Uuid) {
+        if let Some(cache) = &self.recent_cache {
+            cache.invalidate(wallet_id);
+        }
+    }
+
+    /// Enforce each user's per-transfer USD cap before sending
+    pub fn with_transfer_limit(
+        mut self,
+        security_service: Arc<SecurityService>,
+        price_service: Arc<PriceService>
+    ) -> Self {
+        self.transfer_limit = Some((security_service, price_service));
+        self
+    }
+
+    /// Price the transfer in USD and compare it against the owner's per-transfer cap.
+    /// When a cap is set, a token that can't be priced is rejected rather than let through.
+    async fn check_transfer_limit(
+        &self,
+        wallet: &wallet::Model,
+        amount: &str,
+        token_address: Option<&str>
+    ) -> Result<()> {
+        let Some((security_service, price_service)) = &self.transfer_limit else {
+            return Ok(());
+        };
+        if wallet.is_testnet {
+            return Ok(());
+        }
+        let settings = security_service.get_or_create_settings(&wallet.user_id).await?;
+        if settings.max_single_transfer_usd.is_none() {
+            return Ok(());
+        }
+
+        let symbol = match token_address {
+            Some(token_addr) =>
+                self.balance_service.get_balance(wallet.id, Some(token_addr.to_string())).await?.symbol,
+            None => wallet.chain.parse::<Chain>()?.native_symbol().to_string(),
+        };
+
+        let price = price_service.get_price(&symbol).await.map_err(|e| {
+            tracing::debug!("No USD price for {}, blocking send under transfer limit: {}", symbol, e);
+            AppError::Validation(
+                format!(
+                    "Can't value {} in USD to check your per-transfer limit. \
+                    Remove the limit with /setmaxsend off to send it.",
+                    symbol
+                )
+            )
+        })?;
+
+        let amount_num: f64 = amount
+            .parse()
+            .map_err(|_| AppError::InvalidInput("Invalid amount".to_string()))?;
+        security_service.check_max_transfer(&wallet.user_id, amount_num * price.usd_price).await
+    }
+
+    /// Compute the largest native amount that can be sent once the network fee is deducted
+    pub async fn get_max_sendable_amount(&self, wallet_id: Uuid, to: &str) -> Result<MaxSendAmount> {
+        let balance = self.balance_service.get_balance(wallet_id, None).await?;
+        let available: f64 = balance.balance.parse().unwrap_or(0.0);
+
+        // Estimate with a zero value so the node doesn't reject the simulation for lack of funds
+        let estimate = self.gas_estimation_service.estimate_transaction_fee(
+            wallet_id,
+            to,
+            "0",
+            None
+        ).await?;
+        let fee: f64 = estimate.gas_estimate.total_cost_native.parse().unwrap_or(0.0);
+
+        let max_amount = floor_to_precision(available - fee, 6);
+        if max_amount <= 0.0 {
+            return Err(AppError::InsufficientFunds {
+                available: balance.balance,
+                required: estimate.gas_estimate.total_cost_native,
+            });
+        }
+
+        Ok(MaxSendAmount {
+            amount: format!("{:.6}", max_amount),
+            estimated_fee: estimate.gas_estimate.total_cost_native,
+            symbol: balance.symbol,
+        })
+    }
+
+    /// Verify the wallet can cover the amount plus the estimated network fee
+    async fn check_sufficient_balance(
+        &self,
+        wallet_id: Uuid,
+        to: &str,
+        amount: &str,
+        token_address: Option<&str>
+    ) -> Result<()> {
+        let amount_num: f64 = amount
+            .parse()
+            .map_err(|_| AppError::InvalidInput("Invalid amount".to_string()))?;
+
+        let native = self.balance_service.get_balance(wallet_id, None).await?;
+        let native_available: f64 = native.balance.parse().unwrap_or(0.0);
+
+        let estimate = self.gas_estimation_service.estimate_transaction_fee(
+            wallet_id,
+            to,
+            amount,
+            token_address
+        ).await?;
+        let fee: f64 = estimate.gas_estimate.total_cost_native.parse().unwrap_or(0.0);
+
+        if let Some(token_addr) = token_address {
+            let token = self.balance_service.get_balance(
+                wallet_id,
+                Some(token_addr.to_string())
+            ).await?;
+            let token_available: f64 = token.balance.parse().unwrap_or(0.0);
+
+            if token_available < amount_num {
+                return Err(AppError::InsufficientFunds {
+                    available: format!("{} {}", token.balance, token.symbol),
+                    required: format!("{} {}", amount, token.symbol),
+                });
+            }
+
+            if native_available < fee {
+                return Err(AppError::InsufficientFunds {
+                    available: format!("{} {}", native.balance, native.symbol),
+                    required: format!("{} {}", estimate.gas_estimate.total_cost_native, native.symbol),
+                });
+            }
+        } else {
+            let required = amount_num + fee;
+            if native_available < required {
+                return Err(AppError::InsufficientFunds {
+                    available: format!("{} {}", native.balance, native.symbol),
+                    required: format!("{} {}", required, native.symbol),
+                });
+            }
         }
+
+        Ok(())
     }
 
     pub async fn send_transaction(
@@ -42,18 +220,38 @@ impl TransferService {
         let private_key = self.encryptor.decrypt(&wallet.encrypted_private_key)?;
 
         // Get appropriate provider
-        let provider = self.rpc_manager.get_provider_by_chain(&wallet.chain).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
 
         // Validate destination address
         if !provider.validate_address(&request.to) {
-            return Err(crate::error::AppError::InvalidAddress);
+            return Err(AppError::InvalidAddress);
         }
 
+        // Resolve the amount to send: either the requested amount or balance minus fee
+        let amount = if request.send_max {
+            if request.token_address.is_some() {
+                return Err(
+                    AppError::InvalidInput("Send max is only supported for native transfers".to_string())
+                );
+            }
+            self.get_max_sendable_amount(wallet_id, &request.to).await?.amount
+        } else {
+            self.check_sufficient_balance(
+                wallet_id,
+                &request.to,
+                &request.amount,
+                request.token_address.as_deref()
+            ).await?;
+            request.amount.clone()
+        };
+
+        self.check_transfer_limit(&wallet, &amount, request.token_address.as_deref()).await?;
+
         // Build transaction request
         let tx_request = TransactionRequest {
             from: wallet.address.clone(),
             to: request.to.clone(),
-            amount: request.amount.clone(),
+            amount: amount.clone(),
             token_address: request.token_address.clone(),
             max_fee_per_gas: request.max_fee_per_gas,
             max_priority_fee_per_gas: request.max_priority_fee_per_gas,
@@ -61,8 +259,12 @@ impl TransferService {
             compute_units: request.compute_units,
         };
 
-        // Send transaction
-        let response = provider.send_transaction(&private_key, tx_request).await?;
+        let (response, retries) = self.broadcast_with_retry(
+            provider.as_ref(),
+            &private_key,
+            tx_request,
+            &self.retry_config
+        ).await?;
 
         // Log transaction to database
         let token_symbol = if request.token_address.is_some() {
@@ -71,21 +273,233 @@ impl TransferService {
             wallet.chain.parse::<Chain>().ok().map(|c| c.native_symbol().to_string())
         };
 
-        self.transaction_repo.create(
+        let tx = self.transaction_repo.create(
             wallet_id,
             response.tx_hash.clone(),
             wallet.chain.clone(),
             wallet.address.clone(),
             request.to,
-            request.amount,
+            amount,
             request.token_address,
             token_symbol,
             response.status.clone()
         ).await?;
+        if retries.count > 0 {
+            self.transaction_repo.set_transfer_retries(
+                tx.id,
+                retries.count as i32,
+                retries.last_error
+            ).await?;
+        }
+        self.invalidate_recent(wallet_id);
 
         Ok(response)
     }
 
+    /// Broadcast, retrying with exponential backoff while the failure is one `config` retries.
+    /// Each retry signs a new transaction, so it only happens while the wallet's pending nonce
+    /// shows the failed attempt never reached the mempool.
+    async fn broadcast_with_retry(
+        &self,
+        provider: &dyn ChainProvider,
+        private_key: &str,
+        request: TransactionRequest,
+        config: &TransferConfig
+    ) -> Result<(TransactionResponse, BroadcastRetries)> {
+        let mut retries = BroadcastRetries { count: 0, last_error: None };
+        let nonce_before = if config.retry_on.is_empty() || config.max_retries == 0 {
+            None
+        } else {
+            provider.get_pending_nonce(&request.from).await.ok()
+        };
+
+        loop {
+            let error = match provider.send_transaction(private_key, request.clone()).await {
+                Ok(response) => {
+                    return Ok((response, retries));
+                }
+                Err(e) => e,
+            };
+
+            let retryable = retryable_kind(&error).filter(|kind| config.retry_on.contains(kind));
+            if retryable.is_none() || retries.count >= config.max_retries {
+                return Err(error);
+            }
+            // Without a nonce to compare there's no telling whether the attempt went out
+            let Some(nonce_before) = nonce_before else {
+                return Err(error);
+            };
+            match provider.get_pending_nonce(&request.from).await {
+                Ok(nonce) if nonce == nonce_before => {}
+                Ok(_) => {
+                    tracing::warn!("Broadcast from {} failed ({}) but its nonce was used; not retrying", request.from, error);
+                    return Err(
+                        AppError::Blockchain(
+                            "The transfer may already have been broadcast. Check your history before sending again.".to_string()
+                        )
+                    );
+                }
+                Err(_) => {
+                    return Err(error);
+                }
+            }
+
+            let backoff = config.backoff(retries.count);
+            retries.count += 1;
+            tracing::warn!(
+                "Broadcast from {} failed ({}), retry {}/{} in {:?}",
+                request.from,
+                error,
+                retries.count,
+                config.max_retries,
+                backoff
+            );
+            retries.last_error = Some(error.to_string());
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Pre-flight a batch: validate each recipient, estimate its fee and check the wallet can cover
+    /// every amount plus every fee. Nothing is sent.
+    pub async fn estimate_batch_cost(
+        &self,
+        wallet_id: Uuid,
+        recipients: &[BatchRecipient]
+    ) -> Result<BatchCostEstimate> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+        let chain: Chain = wallet.chain.parse()?;
+
+        let native = self.balance_service.get_balance(wallet_id, None).await?;
+        let native_available: f64 = native.balance.parse().unwrap_or(0.0);
+        let native_usd_price = unit_usd_price(&native);
+
+        let mut total_native = 0.0;
+        let mut total_gas = 0.0;
+        let mut total_usd = 0.0;
+        // Token amounts requested per contract, checked against each token balance afterwards
+        let mut token_totals: HashMap<String, f64> = HashMap::new();
+        let mut warnings = Vec::new();
+        let mut statuses = Vec::with_capacity(recipients.len());
+
+        for (index, recipient) in recipients.iter().enumerate() {
+            let mut status = BatchRecipientEstimate {
+                index,
+                to: recipient.to.clone(),
+                amount: recipient.amount.clone(),
+                token_address: recipient.token_address.clone(),
+                fee_native: None,
+                below_dust: false,
+                error: None,
+            };
+
+            let amount = match recipient.amount.parse::<f64>() {
+                Ok(amount) if amount > 0.0 => amount,
+                _ => {
+                    status.error = Some("Invalid amount".to_string());
+                    statuses.push(status);
+                    continue;
+                }
+            };
+            if !provider.validate_address(&recipient.to) {
+                status.error = Some("Invalid address format".to_string());
+                statuses.push(status);
+                continue;
+            }
+
+            let fee = match
+                self.gas_estimation_service.estimate_transaction_fee(
+                    wallet_id,
+                    &recipient.to,
+                    &recipient.amount,
+                    recipient.token_address.as_deref()
+                ).await
+            {
+                Ok(estimate) => estimate.gas_estimate.total_cost_native.parse::<f64>().unwrap_or(0.0),
+                Err(e) => {
+                    status.error = Some(e.user_facing_message().into_owned());
+                    statuses.push(status);
+                    continue;
+                }
+            };
+            status.fee_native = Some(format!("{:.8}", fee));
+            total_gas += fee;
+
+            match &recipient.token_address {
+                Some(token) => {
+                    *token_totals.entry(token.clone()).or_insert(0.0) += amount;
+                }
+                None => {
+                    total_native += amount;
+                    total_usd += amount * native_usd_price;
+                    if is_dust(chain, amount, fee) {
+                        status.below_dust = true;
+                        warnings.push(
+                            format!(
+                                "#{}: {} {} is below the dust threshold and may be rejected or cost more in fees than it sends",
+                                index + 1,
+                                recipient.amount,
+                                native.symbol
+                            )
+                        );
+                    }
+                }
+            }
+
+            statuses.push(status);
+        }
+        total_usd += total_gas * native_usd_price;
+
+        let mut insufficient_balance = native_available < total_native + total_gas;
+        if insufficient_balance {
+            warnings.push(
+                format!(
+                    "Balance {} {} doesn't cover {:.8} {} in amounts plus fees",
+                    native.balance,
+                    native.symbol,
+                    total_native + total_gas,
+                    native.symbol
+                )
+            );
+        }
+
+        for (token, requested) in &token_totals {
+            let balance = self.balance_service.get_balance(wallet_id, Some(token.clone())).await?;
+            let available: f64 = balance.balance.parse().unwrap_or(0.0);
+            total_usd += requested * unit_usd_price(&balance);
+            if available < *requested {
+                insufficient_balance = true;
+                warnings.push(
+                    format!(
+                        "Balance {} {} doesn't cover {} {}",
+                        balance.balance,
+                        balance.symbol,
+                        requested,
+                        balance.symbol
+                    )
+                );
+            }
+        }
+
+        let invalid = statuses
+            .iter()
+            .filter(|s| s.error.is_some())
+            .count();
+        if invalid > 0 {
+            warnings.push(format!("{} recipient(s) will fail and be skipped", invalid));
+        }
+
+        Ok(BatchCostEstimate {
+            total_native_amount: format!("{:.8}", total_native),
+            total_gas_cost_native: format!("{:.8}", total_gas),
+            total_usd_value: total_usd,
+            symbol: native.symbol,
+            insufficient_balance,
+            warnings,
+            recipients: statuses,
+        })
+    }
+
     pub async fn send_batch_transactions(
         &self,
         wallet_id: Uuid,
@@ -94,11 +508,20 @@ impl TransferService {
         // Get wallet from database
         let wallet = self.repository.find_by_id(wallet_id).await?;
 
+        // Same balance and per-transfer limit checks as a single send, all before anything
+        // is broadcast so a rejected batch leaves nothing half sent
+        if self.estimate_batch_cost(wallet_id, &recipients).await?.insufficient_balance {
+            return Err(AppError::InsufficientBalance);
+        }
+        for recipient in &recipients {
+            self.check_transfer_limit(&wallet, &recipient.amount, recipient.token_address.as_deref()).await?;
+        }
+
         // Decrypt private key
         let private_key = self.encryptor.decrypt(&wallet.encrypted_private_key)?;
 
         // Get appropriate provider
-        let provider = self.rpc_manager.get_provider_by_chain(&wallet.chain).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
 
         let mut results = Vec::new();
         let mut successful = 0;
@@ -152,6 +575,7 @@ impl TransferService {
                         token_symbol,
                         response.status.clone()
                     ).await;
+                    self.invalidate_recent(wallet_id);
 
                     results.push(BatchTransferStatus {
                         index,
@@ -203,9 +627,19 @@ pub struct TransferRequest {
     pub gas_limit: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compute_units: Option<u32>,
+    /// Send the whole native balance minus the estimated fee, ignoring `amount`
+    #[serde(default)]
+    pub send_max: bool,
 }
 
-#[derive(serde::Deserialize, Clone)]
+#[derive(serde::Serialize)]
+pub struct MaxSendAmount {
+    pub amount: String,
+    pub estimated_fee: String,
+    pub symbol: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct BatchRecipient {
     pub to: String,
     pub amount: String,
@@ -221,6 +655,33 @@ pub struct BatchTransferResult {
     pub results: Vec<BatchTransferStatus>,
 }
 
+/// What a batch would cost, from `TransferService::estimate_batch_cost`
+#[derive(serde::Serialize)]
+pub struct BatchCostEstimate {
+    /// Sum of native-token amounts; token transfers are checked against their own balances
+    pub total_native_amount: String,
+    pub total_gas_cost_native: String,
+    /// Amounts plus fees in USD, counting only assets with a known price
+    pub total_usd_value: f64,
+    /// Native token symbol of the wallet's chain
+    pub symbol: String,
+    pub insufficient_balance: bool,
+    pub warnings: Vec<String>,
+    pub recipients: Vec<BatchRecipientEstimate>,
+}
+
+#[derive(serde::Serialize)]
+pub struct BatchRecipientEstimate {
+    pub index: usize,
+    pub to: String,
+    pub amount: String,
+    pub token_address: Option<String>,
+    pub fee_native: Option<String>,
+    pub below_dust: bool,
+    /// Why this transfer would fail; `None` when it looks sendable
+    pub error: Option<String>,
+}
+
 #[derive(serde::Serialize)]
 pub struct BatchTransferStatus {
     pub index: usize,
@@ -230,3 +691,85 @@ pub struct BatchTransferStatus {
     pub tx_hash: Option<String>,
     pub error: Option<String>,
 }
+
+/// USD price of one unit, derived from the priced balance; 0 when unknown or the balance is empty
+fn unit_usd_price(balance: &crate::providers::Balance) -> f64 {
+    let amount: f64 = balance.balance.parse().unwrap_or(0.0);
+    match balance.usd_value {
+        Some(usd) if amount > 0.0 => usd / amount,
+        _ => 0.0,
+    }
+}
+
+/// Whether a native transfer is too small to be worth sending. UTXO and account-reserve chains
+/// have protocol minimums; on EVM chains a transfer is dust when it's smaller than its own fee.
+fn is_dust(chain: Chain, amount: f64, fee: f64) -> bool {
+    let minimum = match chain {
+        // Bitcoin Core's dust limit for a P2PKH output (546 sats)
+        Chain::Btc => 0.00000546,
+        // Minimum lovelace a Cardano output must hold
+        Chain::Cardano => 1.0,
+        // Base reserve needed to fund a new XRP account
+        Chain::Xrp => 1.0,
+        // Rent-exempt minimum for a new Solana system account
+        Chain::Solana => 0.00089088,
+        _ => fee,
+    };
+    amount < minimum
+}
+
+/// Round down so the displayed amount never exceeds what is actually available
+fn floor_to_precision(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).floor() / factor
+}
+
+/// Which retryable failure a broadcast error is, if any
+fn retryable_kind(error: &AppError) -> Option<RetryableError> {
+    match error {
+        AppError::ConnectionTimeout { .. } => Some(RetryableError::Timeout),
+        AppError::NonceTooLow(_) => Some(RetryableError::NonceTooLow),
+        AppError::ChainUnavailable(_) => Some(RetryableError::NetworkError),
+        AppError::Rpc(message) => {
+            let lower = message.to_lowercase();
+            let unreachable = [
+                "error sending request",
+                "error trying to connect",
+                "connection refused",
+                "connection reset",
+                "dns error",
+            ];
+            unreachable
+                .iter()
+                .any(|m| lower.contains(m))
+                .then_some(RetryableError::NetworkError)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_backoff_doubles() {
+        let config = TransferConfig::default();
+        let waits: Vec<u64> = (0..config.max_retries).map(|r| config.backoff(r).as_millis() as u64).collect();
+        assert_eq!(waits, vec![1000, 2000, 4000]);
+    }
+
+    #[test]
+    fn test_classifies_transient_broadcast_errors() {
+        assert_eq!(
+            retryable_kind(&AppError::Rpc("error sending request for url (http://node): connection refused".into())),
+            Some(RetryableError::NetworkError)
+        );
+        assert_eq!(
+            retryable_kind(&AppError::NonceTooLow("nonce too low".into())),
+            Some(RetryableError::NonceTooLow)
+        );
+        assert_eq!(retryable_kind(&AppError::InsufficientGas), None);
+        assert_eq!(retryable_kind(&AppError::Rpc("execution reverted".into())), None);
+    }
+}
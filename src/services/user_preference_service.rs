@@ -0,0 +1,124 @@
+use chrono::Utc;
+use sea_orm::{ sea_query::OnConflict, ActiveValue, DatabaseConnection, EntityTrait };
+
+use crate::db::entity::user_preference;
+use crate::error::{ AppError, Result };
+
+pub const DEFAULT_FIAT_CURRENCY: &str = "USD";
+const DEFAULT_LOCALE: &str = "en";
+
+/// Display currencies offered by /setcurrency; all are quoted by CoinGecko and exchangerate-api
+pub const SUPPORTED_FIAT_CURRENCIES: &[&str] = &[
+    "USD",
+    "EUR",
+    "GBP",
+    "JPY",
+    "CHF",
+    "CAD",
+    "AUD",
+    "CNY",
+    "INR",
+    "KRW",
+    "BRL",
+    "RUB",
+    "TRY",
+    "UZS",
+];
+
+/// Per-user display preferences
+#[derive(Clone)]
+pub struct UserPreferenceService {
+    db: DatabaseConnection,
+}
+
+impl UserPreferenceService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Fiat currency the user sees prices in; USD until they pick another
+    pub async fn get_fiat_currency(&self, user_id: &str) -> Result<&'static str> {
+        let stored = user_preference::Entity::find_by_id(user_id.to_string()).one(&self.db).await?;
+
+        Ok(
+            stored
+                .and_then(|prefs| supported_currency(&prefs.fiat_currency))
+                .unwrap_or(DEFAULT_FIAT_CURRENCY)
+        )
+    }
+
+    pub async fn set_fiat_currency(&self, user_id: &str, currency: &str) -> Result<&'static str> {
+        let currency = supported_currency(currency).ok_or_else(|| {
+            AppError::InvalidInput(
+                format!(
+                    "Unsupported currency: {}. Supported: {}",
+                    currency.to_uppercase(),
+                    SUPPORTED_FIAT_CURRENCIES.join(", ")
+                )
+            )
+        })?;
+
+        let now = Utc::now();
+        let row = user_preference::ActiveModel {
+            user_id: ActiveValue::Set(user_id.to_string()),
+            fiat_currency: ActiveValue::Set(currency.to_string()),
+            locale: ActiveValue::Set(DEFAULT_LOCALE.to_string()),
+            swap_slippage_pct: ActiveValue::NotSet,
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+
+        user_preference::Entity
+            ::insert(row)
+            .on_conflict(
+                OnConflict::column(user_preference::Column::UserId)
+                    .update_columns([
+                        user_preference::Column::FiatCurrency,
+                        user_preference::Column::UpdatedAt,
+                    ])
+                    .to_owned()
+            )
+            .exec(&self.db).await?;
+
+        Ok(currency)
+    }
+
+    /// Slippage the user last swapped with, if they ever changed it
+    pub async fn get_swap_slippage(&self, user_id: &str) -> Result<Option<f64>> {
+        let stored = user_preference::Entity::find_by_id(user_id.to_string()).one(&self.db).await?;
+
+        Ok(stored.and_then(|prefs| prefs.swap_slippage_pct))
+    }
+
+    pub async fn set_swap_slippage(&self, user_id: &str, slippage_pct: f64) -> Result<()> {
+        let now = Utc::now();
+        let row = user_preference::ActiveModel {
+            user_id: ActiveValue::Set(user_id.to_string()),
+            fiat_currency: ActiveValue::Set(DEFAULT_FIAT_CURRENCY.to_string()),
+            locale: ActiveValue::Set(DEFAULT_LOCALE.to_string()),
+            swap_slippage_pct: ActiveValue::Set(Some(slippage_pct)),
+            created_at: ActiveValue::Set(now),
+            updated_at: ActiveValue::Set(now),
+        };
+
+        user_preference::Entity
+            ::insert(row)
+            .on_conflict(
+                OnConflict::column(user_preference::Column::UserId)
+                    .update_columns([
+                        user_preference::Column::SwapSlippagePct,
+                        user_preference::Column::UpdatedAt,
+                    ])
+                    .to_owned()
+            )
+            .exec(&self.db).await?;
+
+        Ok(())
+    }
+}
+
+fn supported_currency(code: &str) -> Option<&'static str> {
+    SUPPORTED_FIAT_CURRENCIES.iter()
+        .copied()
+        .find(|c| c.eq_ignore_ascii_case(code.trim()))
+}
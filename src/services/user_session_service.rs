@@ -0,0 +1,69 @@
+use chrono::{ Duration, Utc };
+use sea_orm::{ sea_query::OnConflict, ActiveValue, DatabaseConnection, EntityTrait };
+use uuid::Uuid;
+
+use crate::db::entity::{ user_session, wallet };
+use crate::error::Result;
+
+/// Sessions untouched for longer than this are no longer offered to resume
+const SESSION_TTL_DAYS: i64 = 7;
+
+pub type UserSession = user_session::Model;
+
+/// Remembers the wallet each user last worked with so /start can pick up from there
+#[derive(Clone)]
+pub struct UserSessionService {
+    db: DatabaseConnection,
+}
+
+impl UserSessionService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Record a wallet interaction as the user's latest
+    pub async fn update_session(&self, user_id: i64, wallet_id: Uuid, action: &str) -> Result<()> {
+        let chain = wallet::Entity
+            ::find_by_id(wallet_id)
+            .one(&self.db).await?
+            .map(|w| w.chain);
+
+        let row = user_session::ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            last_wallet_id: ActiveValue::Set(Some(wallet_id)),
+            last_chain: ActiveValue::Set(chain),
+            last_action: ActiveValue::Set(Some(action.to_string())),
+            last_active_at: ActiveValue::Set(Utc::now()),
+        };
+
+        user_session::Entity
+            ::insert(row)
+            .on_conflict(
+                OnConflict::column(user_session::Column::UserId)
+                    .update_columns([
+                        user_session::Column::LastWalletId,
+                        user_session::Column::LastChain,
+                        user_session::Column::LastAction,
+                        user_session::Column::LastActiveAt,
+                    ])
+                    .to_owned()
+            )
+            .exec(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// The user's session, unless it has gone `SESSION_TTL_DAYS` without activity
+    pub async fn get_session(&self, user_id: i64) -> Result<Option<UserSession>> {
+        let Some(session) = user_session::Entity::find_by_id(user_id).one(&self.db).await? else {
+            return Ok(None);
+        };
+
+        if Utc::now() - session.last_active_at > Duration::days(SESSION_TTL_DAYS) {
+            user_session::Entity::delete_by_id(user_id).exec(&self.db).await?;
+            return Ok(None);
+        }
+
+        Ok(Some(session))
+    }
+}
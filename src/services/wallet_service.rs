@@ -1,11 +1,103 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{ AtomicU32, Ordering };
 use std::sync::Arc;
+
+use chrono::{ DateTime, Utc };
+use futures::StreamExt;
+use rayon::prelude::*;
 use uuid::Uuid;
 
-use crate::crypto::Encryptor;
+use crate::crypto::{ backup, Encryptor, MnemonicValidator, MnemonicWarning };
 use crate::db::WalletRepository;
-use crate::error::Result;
+use crate::enums::Chain;
+use crate::error::{ AppError, Result };
+use crate::providers::WalletInfo;
 use crate::rpc::RpcManager;
 
+/// Shortest partial address (after any `0x`) accepted by wallet lookups
+const MIN_ADDRESS_PREFIX_LEN: usize = 4;
+
+/// Wallets re-encrypted per database transaction during key rotation
+const KEY_ROTATION_BATCH_SIZE: u64 = 100;
+
+/// Fresh mnemonics tried before giving up on one the validator has no warnings for
+const MAX_MNEMONIC_REGENERATIONS: u32 = 5;
+
+/// Upper bound on vanity search attempts; each one derives a fresh mnemonic, so this caps CPU per request
+pub const MAX_VANITY_ATTEMPTS: u32 = 100_000;
+
+/// Hex characters an EVM vanity address should start and/or end with
+#[derive(Debug, Clone)]
+pub struct VanityPattern {
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    /// Match against the EIP-55 checksummed address instead of ignoring case
+    pub case_sensitive: bool,
+}
+
+impl VanityPattern {
+    /// Strip a leading `0x` from the prefix and check both parts are hex that fits in an address
+    fn normalized(&self) -> Result<Self> {
+        let clean = |part: &Option<String>, is_prefix: bool| -> Result<Option<String>> {
+            let Some(part) = part.as_deref().map(str::trim) else {
+                return Ok(None);
+            };
+            let part = if is_prefix {
+                part.strip_prefix("0x").or_else(|| part.strip_prefix("0X")).unwrap_or(part)
+            } else {
+                part
+            };
+            if !part.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(
+                    AppError::InvalidInput(format!("{} isn't hex; use only 0-9 and a-f", part))
+                );
+            }
+            Ok((!part.is_empty()).then(|| part.to_string()))
+        };
+
+        let prefix = clean(&self.prefix, true)?;
+        let suffix = clean(&self.suffix, false)?;
+        let len = prefix.as_ref().map_or(0, |p| p.len()) + suffix.as_ref().map_or(0, |s| s.len());
+        if len == 0 {
+            return Err(AppError::InvalidInput("Give a prefix, a suffix or both".to_string()));
+        }
+        if len > 40 {
+            return Err(AppError::InvalidInput("Pattern is longer than an address".to_string()));
+        }
+
+        Ok(Self { prefix, suffix, case_sensitive: self.case_sensitive })
+    }
+
+    /// `address` as `0x` plus 40 lowercase hex characters
+    fn matches(&self, address: &str) -> bool {
+        let hex = if self.case_sensitive {
+            match address.parse::<ethers::types::H160>() {
+                Ok(parsed) => ethers::utils::to_checksum(&parsed, None),
+                Err(_) => {
+                    return false;
+                }
+            }
+        } else {
+            address.to_string()
+        };
+        let hex = hex.trim_start_matches("0x");
+
+        let matches_part = |part: &Option<String>, found: Option<&str>| match (part, found) {
+            (None, _) => true,
+            (Some(part), Some(found)) if self.case_sensitive => found == part,
+            (Some(part), Some(found)) => found.eq_ignore_ascii_case(part),
+            (Some(_), None) => false,
+        };
+
+        matches_part(&self.prefix, self.prefix.as_ref().and_then(|p| hex.get(..p.len()))) &&
+            matches_part(
+                &self.suffix,
+                self.suffix.as_ref().and_then(|s| hex.get(hex.len().saturating_sub(s.len())..))
+            )
+    }
+}
+
 pub struct WalletService {
     repository: Arc<WalletRepository>,
     rpc_manager: Arc<RpcManager>,
@@ -29,11 +121,28 @@ impl WalletService {
         &self,
         user_id: String,
         chain: String,
-        derivation_index: Option<u32>
+        derivation_index: Option<u32>,
+        use_testnet: bool
     ) -> Result<GeneratedWalletResponse> {
-        let provider = self.rpc_manager.get_provider_by_chain(&chain).await?;
+        let is_testnet = use_testnet || self.rpc_manager.is_testnet();
+        let provider = self.rpc_manager.get_network_provider(&chain, is_testnet).await?;
+
+        let derivation_index = derivation_index.unwrap_or(0);
+        let mut wallet_info = provider.generate_wallet(derivation_index).await?;
+
+        // A random mnemonic can still land on repeated words or a guessable pattern; draw again
+        for _ in 1..MAX_MNEMONIC_REGENERATIONS {
+            let warnings = wallet_info.mnemonic.as_deref().map(MnemonicValidator::validate).unwrap_or_default();
+            if warnings.is_empty() {
+                break;
+            }
+            tracing::debug!("Regenerating mnemonic after {} validator warning(s)", warnings.len());
+            wallet_info = provider.generate_wallet(derivation_index).await?;
+        }
 
-        let wallet_info = provider.generate_wallet(derivation_index.unwrap_or(0)).await?;
+        if let Some(existing) = self.check_derivation_collision(&user_id, &chain, is_testnet, &wallet_info.address).await? {
+            return Err(AppError::WalletAlreadyExists { existing_id: existing.id });
+        }
 
         // Encrypt private key
         let encrypted_private_key = self.encryptor.encrypt(&wallet_info.private_key)?;
@@ -43,7 +152,9 @@ impl WalletService {
             user_id,
             chain.clone(),
             wallet_info.address.clone(),
-            encrypted_private_key
+            encrypted_private_key,
+            is_testnet,
+            Some(derivation_index)
         ).await?;
 
         Ok(GeneratedWalletResponse {
@@ -51,6 +162,7 @@ impl WalletService {
             address: wallet_info.address,
             chain,
             mnemonic: wallet_info.mnemonic,
+            is_testnet,
         })
     }
 
@@ -59,34 +171,228 @@ impl WalletService {
         user_id: String,
         chain: String,
         secret: String,
-        derivation_index: Option<u32>
+        derivation_index: Option<u32>,
+        use_testnet: bool
     ) -> Result<RestoredWalletResponse> {
-        let provider = self.rpc_manager.get_provider_by_chain(&chain).await?;
+        let is_testnet = use_testnet || self.rpc_manager.is_testnet();
+        let provider = self.rpc_manager.get_network_provider(&chain, is_testnet).await?;
+
+        // Weak phrases are still restored; the caller shows the warnings
+        let warnings = if secret.split_whitespace().count() > 1 {
+            MnemonicValidator::validate(&secret)
+        } else {
+            vec![]
+        };
+
+        let derivation_index = derivation_index.unwrap_or(0);
+        let wallet_info = provider.restore_wallet(&secret, derivation_index).await?;
 
-        let wallet_info = provider.restore_wallet(&secret, derivation_index.unwrap_or(0)).await?;
+        // Importing the same key twice would leave two rows for one account
+        if let Some(existing) = self.check_derivation_collision(&user_id, &chain, is_testnet, &wallet_info.address).await? {
+            return Err(AppError::WalletAlreadyExists { existing_id: existing.id });
+        }
 
         // Encrypt private key
         let encrypted_private_key = self.encryptor.encrypt(&wallet_info.private_key)?;
 
-        // Save to database
+        // Save to database; only mnemonic restores have a derivation index
         let wallet = self.repository.create(
             user_id,
             chain.clone(),
             wallet_info.address.clone(),
-            encrypted_private_key
+            encrypted_private_key,
+            is_testnet,
+            wallet_info.mnemonic.is_some().then_some(derivation_index)
         ).await?;
 
         Ok(RestoredWalletResponse {
             id: wallet.id,
             address: wallet_info.address,
             chain,
+            is_testnet,
+            warnings,
+        })
+    }
+
+    /// Sign `verify_ownership:<address>:<timestamp>` with the given key or phrase and check the
+    /// signature belongs to `address`. Supported on EVM chains and Solana.
+    pub fn verify_ownership(&self, address: &str, private_key_or_mnemonic: &str, chain: &str) -> Result<bool> {
+        let chain: Chain = chain.parse()?;
+        let message = format!("verify_ownership:{}:{}", address, Utc::now().timestamp());
+
+        if chain.is_evm() {
+            crate::chains::evm::wallet::verify_ownership(address, private_key_or_mnemonic, &message)
+        } else if chain == Chain::Solana {
+            crate::chains::solana::wallet::verify_ownership(address, private_key_or_mnemonic, &message)
+        } else {
+            Err(AppError::Chain(format!("Ownership verification is not supported on {}", chain)))
+        }
+    }
+
+    /// Generate and save an EVM wallet whose address matches `pattern`, trying up to `max_attempts`
+    /// random mnemonics across all cores. `attempts` counts tries so callers can report progress.
+    pub async fn generate_vanity_wallet(
+        &self,
+        user_id: &str,
+        chain: &str,
+        pattern: VanityPattern,
+        max_attempts: u32,
+        attempts: Arc<AtomicU32>,
+        use_testnet: bool
+    ) -> Result<GeneratedWalletResponse> {
+        let parsed: Chain = chain.parse()?;
+        if !parsed.is_evm() {
+            return Err(AppError::InvalidInput("Vanity addresses are only supported on EVM chains".to_string()));
+        }
+        if !self.rpc_manager.is_chain_configured(&parsed) {
+            return Err(AppError::Config(format!("Chain {} is not configured", parsed)));
+        }
+        let pattern = pattern.normalized()?;
+        let max_attempts = max_attempts.clamp(1, MAX_VANITY_ATTEMPTS);
+
+        let wallet_info = tokio::task
+            ::spawn_blocking(move || {
+                (0..max_attempts).into_par_iter().find_map_any(|_| {
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    let info = crate::chains::evm::wallet::generate_wallet(0).ok()?;
+                    pattern.matches(&info.address).then_some(info)
+                })
+            }).await
+            .map_err(|e| AppError::Internal(format!("Vanity search task failed: {}", e)))?
+            .ok_or(AppError::VanityNotFound { attempts: max_attempts })?;
+
+        let is_testnet = use_testnet || self.rpc_manager.is_testnet();
+        if let Some(existing) = self.check_derivation_collision(user_id, chain, is_testnet, &wallet_info.address).await? {
+            return Err(AppError::WalletAlreadyExists { existing_id: existing.id });
+        }
+
+        let encrypted_private_key = self.encryptor.encrypt(&wallet_info.private_key)?;
+        let wallet = self.repository.create(
+            user_id.to_string(),
+            parsed.to_string(),
+            wallet_info.address.clone(),
+            encrypted_private_key,
+            is_testnet,
+            Some(0)
+        ).await?;
+
+        Ok(GeneratedWalletResponse {
+            id: wallet.id,
+            address: wallet_info.address,
+            chain: parsed.to_string(),
+            mnemonic: wallet_info.mnemonic,
+            is_testnet,
         })
     }
 
+    /// The user's existing wallet on `chain` and the same network with `address`, if any.
+    /// Chains compare by their parsed value so aliases match, and EVM addresses compare
+    /// without case since the same account may be stored checksummed or lowercase.
+    pub async fn check_derivation_collision(
+        &self,
+        user_id: &str,
+        chain: &str,
+        is_testnet: bool,
+        address: &str
+    ) -> Result<Option<crate::db::entity::wallet::Model>> {
+        let chain: Chain = chain.parse()?;
+        let wallets = self.repository.find_by_user(user_id).await?;
+
+        Ok(
+            wallets.into_iter().find(|w| {
+                let same_address = if chain.is_evm() {
+                    w.address.eq_ignore_ascii_case(address)
+                } else {
+                    w.address == address
+                };
+                w.is_testnet == is_testnet && w.chain.parse::<Chain>().ok() == Some(chain) && same_address
+            })
+        )
+    }
+
     pub async fn get_wallet(&self, wallet_id: Uuid) -> Result<crate::db::entity::wallet::Model> {
         self.repository.find_by_id(wallet_id).await
     }
 
+    /// Save an EVM wallet's key as a new wallet on `target_chain`. EVM chains share one key
+    /// format, so the new wallet has the same address; Solana and other non-EVM chains use
+    /// different keys and have to be restored from the recovery phrase instead.
+    pub async fn add_chain_to_existing_wallet(
+        &self,
+        existing_wallet_id: Uuid,
+        target_chain: &str
+    ) -> Result<crate::db::entity::wallet::Model> {
+        let existing = self.repository.find_by_id(existing_wallet_id).await?;
+        let source: Chain = existing.chain.parse()?;
+        let target: Chain = target_chain.parse()?;
+
+        if !source.is_evm() {
+            return Err(
+                AppError::InvalidInput(format!("Only EVM wallets can be added to another chain, not {}", source))
+            );
+        }
+        if !target.is_evm() {
+            return Err(
+                AppError::InvalidInput(
+                    format!(
+                        "{} uses a different key format than {}, so this key can't be reused there. \
+                         Restore your recovery phrase on {} instead",
+                        target.display_name(),
+                        source.display_name(),
+                        target.display_name()
+                    )
+                )
+            );
+        }
+        if target == source {
+            return Err(AppError::InvalidInput(format!("This wallet is already on {}", target)));
+        }
+        if !self.rpc_manager.is_chain_configured(&target) {
+            return Err(AppError::Config(format!("Chain {} is not configured", target)));
+        }
+
+        let private_key = self.encryptor.decrypt(&existing.encrypted_private_key)?;
+        let wallet_info = crate::chains::evm::wallet::restore_from_private_key(&private_key)?;
+
+        if
+            let Some(duplicate) = self.check_derivation_collision(
+                &existing.user_id,
+                target.as_str(),
+                existing.is_testnet,
+                &wallet_info.address
+            ).await?
+        {
+            return Err(AppError::WalletAlreadyExists { existing_id: duplicate.id });
+        }
+
+        // Same key, same ciphertext; only the chain differs
+        self.repository.create(
+            existing.user_id,
+            target.to_string(),
+            wallet_info.address,
+            existing.encrypted_private_key,
+            existing.is_testnet,
+            existing.derivation_index.map(|i| i as u32)
+        ).await
+    }
+
+    /// BOLT11 invoice paying into the wallet's Lightning node; `amount_sats` 0 lets the payer choose
+    pub async fn generate_lightning_invoice(
+        &self,
+        wallet_id: Uuid,
+        amount_sats: u64,
+        memo: &str
+    ) -> Result<String> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+        let provider = self.rpc_manager.get_wallet_provider(&wallet).await?;
+
+        provider
+            .generate_invoice(amount_sats, memo).await
+            .unwrap_or_else(|| {
+                Err(AppError::Validation("Lightning invoices are not available for this wallet".to_string()))
+            })
+    }
+
     pub async fn list_user_wallets(
         &self,
         user_id: &str,
@@ -98,6 +404,298 @@ impl WalletService {
             self.repository.find_by_user(user_id).await
         }
     }
+
+    /// Wallet counts per chain and the span of wallet creation dates
+    pub async fn get_wallet_statistics(&self, user_id: &str) -> Result<WalletStatistics> {
+        let by_chain = self.repository.count_by_chain(user_id).await?;
+        let range = self.repository.created_at_range(user_id).await?;
+
+        Ok(WalletStatistics {
+            total_wallets: by_chain.values().sum(),
+            by_chain,
+            oldest_wallet_date: range.map(|(oldest, _)| oldest),
+            newest_wallet_date: range.map(|(_, newest)| newest),
+        })
+    }
+
+    /// A user's wallets on any chain whose address starts with `prefix` (`0x…` hex or base58/bech32)
+    pub async fn find_wallets_by_partial_address(
+        &self,
+        user_id: &str,
+        prefix: &str
+    ) -> Result<Vec<crate::db::entity::wallet::Model>> {
+        let prefix = prefix.trim();
+        let significant = prefix.strip_prefix("0x").unwrap_or(prefix);
+
+        if significant.len() < MIN_ADDRESS_PREFIX_LEN {
+            return Err(
+                AppError::InvalidInput(
+                    format!("Enter at least {} characters of the address", MIN_ADDRESS_PREFIX_LEN)
+                )
+            );
+        }
+        if !prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(AppError::InvalidInput("Addresses only contain letters and digits".to_string()));
+        }
+
+        self.repository.find_by_user_and_address_prefix(user_id, prefix).await
+    }
+
+    /// Export all of a user's wallets as a password-encrypted zip archive
+    pub async fn export_encrypted_backup(&self, user_id: &str, password: &str) -> Result<Vec<u8>> {
+        let wallets = self.repository.find_by_user(user_id).await?;
+        if wallets.is_empty() {
+            return Err(AppError::NotFound("No wallets to back up".to_string()));
+        }
+
+        let entries = wallets
+            .into_iter()
+            .map(|w| {
+                Ok(BackupWallet {
+                    private_key: self.encryptor.decrypt(&w.encrypted_private_key)?,
+                    chain: w.chain,
+                    address: w.address,
+                    is_testnet: w.is_testnet,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let payload = serde_json::to_vec(&entries).map_err(|e| AppError::Internal(e.to_string()))?;
+        backup::seal(&payload, password)
+    }
+
+    /// Export every wallet of every user as one password-encrypted archive for server backups.
+    /// Rows are read a page at a time and keys stay sealed with the server's encryption key,
+    /// so restoring the archive also needs that key.
+    pub async fn admin_export_all_wallets(&self, password: &str) -> Result<Vec<u8>> {
+        let mut wallets = Vec::new();
+        let pages = self.repository.find_all_paginated(KEY_ROTATION_BATCH_SIZE);
+        futures::pin_mut!(pages);
+        while let Some(page) = pages.next().await {
+            wallets.extend(page?);
+        }
+        if wallets.is_empty() {
+            return Err(AppError::NotFound("No wallets to back up".to_string()));
+        }
+
+        tracing::info!("Exported {} wallets for server backup", wallets.len());
+        let payload = serde_json::to_vec(&wallets).map_err(|e| AppError::Internal(e.to_string()))?;
+        backup::seal(&payload, password)
+    }
+
+    /// Total wallet count and the count per chain, across all users
+    pub async fn count_all_wallets(&self) -> Result<(u64, HashMap<String, u64>)> {
+        let by_chain = self.repository.count_all_by_chain().await?;
+        Ok((by_chain.values().sum(), by_chain))
+    }
+
+    /// Restore wallets from a backup archive, skipping wallets the user already has on the
+    /// same chain and network. Every entry's key must derive its stored address, otherwise
+    /// nothing is imported.
+    pub async fn import_encrypted_backup(
+        &self,
+        user_id: &str,
+        data: &[u8],
+        password: &str
+    ) -> Result<Vec<WalletInfo>> {
+        let payload = backup::open(data, password)?;
+        let entries: Vec<BackupWallet> = serde_json
+            ::from_slice(&payload)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid backup contents: {}", e)))?;
+
+        for entry in &entries {
+            self.check_backup_entry(entry).await?;
+        }
+
+        let mut imported = Vec::new();
+        for entry in entries {
+            if self.check_derivation_collision(user_id, &entry.chain, entry.is_testnet, &entry.address).await?.is_some() {
+                continue;
+            }
+
+            let encrypted_private_key = self.encryptor.encrypt(&entry.private_key)?;
+            self.repository.create(
+                user_id.to_string(),
+                entry.chain,
+                entry.address.clone(),
+                encrypted_private_key,
+                entry.is_testnet,
+                None
+            ).await?;
+
+            imported.push(WalletInfo {
+                address: entry.address,
+                private_key: entry.private_key,
+                mnemonic: None,
+            });
+        }
+
+        Ok(imported)
+    }
+
+    /// Reject a backup entry whose private key doesn't control the address stored with it.
+    /// EVM and Solana keys sign for the claimed address; other chains re-derive it.
+    async fn check_backup_entry(&self, entry: &BackupWallet) -> Result<()> {
+        let chain: Chain = entry.chain.parse()?;
+        let owns_address = if chain.is_evm() || chain == Chain::Solana {
+            self.verify_ownership(&entry.address, &entry.private_key, &entry.chain)?
+        } else {
+            let provider = self.rpc_manager.get_network_provider(&entry.chain, entry.is_testnet).await?;
+            provider.restore_wallet(&entry.private_key, 0).await?.address == entry.address
+        };
+
+        if !owns_address {
+            tracing::warn!("Backup entry for {} on {} failed ownership verification", entry.address, chain);
+            return Err(AppError::OwnershipVerificationFailed);
+        }
+        Ok(())
+    }
+
+    /// Check a custom RPC URL for a wallet and encrypt it, so it can wait for the user's
+    /// confirmation without being held in plain text
+    pub async fn seal_rpc_override(&self, wallet_id: Uuid, url: &str) -> Result<String> {
+        let wallet = self.repository.find_by_id(wallet_id).await?;
+
+        let chain: Chain = wallet.chain.parse()?;
+        if !chain.is_evm() {
+            return Err(AppError::Validation("Custom RPC URLs are only supported for EVM wallets".to_string()));
+        }
+
+        let parsed = check_rpc_url(url).await?;
+        self.encryptor.encrypt(parsed.as_str())
+    }
+
+    /// Route this wallet's chain calls through a private node, given a URL sealed by
+    /// `seal_rpc_override`. The URL is checked again since its DNS may have changed meanwhile.
+    pub async fn set_rpc_override(&self, wallet_id: Uuid, sealed_url: &str) -> Result<()> {
+        let url = self.encryptor.decrypt(sealed_url)?;
+        let encrypted = self.seal_rpc_override(wallet_id, &url).await?;
+        self.repository.update_rpc_override(wallet_id, Some(encrypted)).await?;
+
+        tracing::info!("RPC override set for wallet {}", wallet_id);
+        Ok(())
+    }
+
+    /// Go back to the shared RPC pool for this wallet
+    pub async fn clear_rpc_override(&self, wallet_id: Uuid) -> Result<()> {
+        self.repository.update_rpc_override(wallet_id, None).await?;
+
+        tracing::info!("RPC override cleared for wallet {}", wallet_id);
+        Ok(())
+    }
+
+    /// Re-encrypt every stored private key and RPC override URL from `old_key` to `new_key`
+    /// (64-char hex keys). The running encryptor switches to `new_key` first, so new data is
+    /// sealed with it while both keys still decrypt, even if the rotation stops partway.
+    /// Each batch is updated in one database transaction, and values already sealed with
+    /// `new_key` are skipped, so an interrupted rotation can simply be run again.
+    /// Returns the number of wallets rotated; set `new_key` as `ENCRYPTION_KEY` before the next restart.
+    pub async fn rotate_encryption_key(&self, old_key: &str, new_key: &str) -> Result<usize> {
+        let old_encryptor = Encryptor::new(&Self::key_from_hex(old_key, "old_key")?)?;
+        let new_key_bytes = Self::key_from_hex(new_key, "new_key")?;
+        let new_encryptor = Encryptor::new(&new_key_bytes)?;
+        if old_key.eq_ignore_ascii_case(new_key) {
+            return Err(AppError::Validation("new_key must differ from old_key".to_string()));
+        }
+
+        tracing::info!("Starting encryption key rotation");
+        let started_at = Utc::now();
+        self.encryptor.rekey(&new_key_bytes)?;
+
+        let mut rotated = 0;
+        let mut page = 0;
+        let pages = self.repository.find_all_paginated(KEY_ROTATION_BATCH_SIZE);
+        futures::pin_mut!(pages);
+        while let Some(wallets) = pages.next().await {
+            let wallets = wallets?;
+            let batch_len = self.reseal_wallets(&wallets, &old_encryptor, &new_encryptor).await?;
+            rotated += batch_len;
+            tracing::info!(
+                "Key rotation batch {} done: {} of {} wallets re-encrypted ({} total)",
+                page + 1,
+                batch_len,
+                wallets.len(),
+                rotated
+            );
+
+            page += 1;
+        }
+
+        // A wallet sealed just before the switch may have been inserted after its page was read
+        let late = self.repository.find_created_since(started_at - chrono::Duration::minutes(1)).await?;
+        rotated += self.reseal_wallets(&late, &old_encryptor, &new_encryptor).await?;
+
+        tracing::info!("Encryption key rotation complete: {} wallets rotated", rotated);
+        Ok(rotated)
+    }
+
+    /// Re-encrypt one batch of wallets in a single transaction, skipping values already
+    /// sealed with the new key. Returns how many wallets changed.
+    async fn reseal_wallets(
+        &self,
+        wallets: &[crate::db::entity::wallet::Model],
+        old_encryptor: &Encryptor,
+        new_encryptor: &Encryptor
+    ) -> Result<usize> {
+        let mut updates = Vec::with_capacity(wallets.len());
+        for wallet in wallets {
+            let private_key = reseal(old_encryptor, new_encryptor, &wallet.encrypted_private_key).map_err(|e| {
+                tracing::error!("Key rotation aborted: wallet {} failed to decrypt", wallet.id);
+                e
+            })?;
+            let rpc_override_url = wallet.rpc_override_url
+                .as_deref()
+                .map(|url| reseal(old_encryptor, new_encryptor, url))
+                .transpose()?
+                .flatten();
+            if private_key.is_none() && rpc_override_url.is_none() {
+                tracing::info!("Wallet {} already uses the new key, skipping", wallet.id);
+                continue;
+            }
+
+            updates.push((
+                wallet.id,
+                private_key.unwrap_or_else(|| wallet.encrypted_private_key.clone()),
+                rpc_override_url.or_else(|| wallet.rpc_override_url.clone()),
+            ));
+        }
+
+        let count = updates.len();
+        if count > 0 {
+            self.repository.update_encrypted_keys(updates).await?;
+        }
+        Ok(count)
+    }
+
+    fn key_from_hex(key_hex: &str, field: &str) -> Result<Vec<u8>> {
+        let key = hex
+            ::decode(key_hex.trim())
+            .map_err(|_| AppError::Validation(format!("{} must be a valid hex string", field)))?;
+        if key.len() != 32 {
+            return Err(
+                AppError::Validation(format!("{} must be 32 bytes (64 hex characters)", field))
+            );
+        }
+        Ok(key)
+    }
+}
+
+/// `sealed` re-encrypted from `old` to `new`, or `None` when it is already sealed with `new`
+fn reseal(old: &Encryptor, new: &Encryptor, sealed: &str) -> Result<Option<String>> {
+    match old.decrypt(sealed) {
+        Ok(plaintext) => new.encrypt(&plaintext).map(Some),
+        Err(_) if new.decrypt(sealed).is_ok() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Wallet entry inside an encrypted backup payload
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BackupWallet {
+    chain: String,
+    address: String,
+    private_key: String,
+    is_testnet: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -106,6 +704,7 @@ pub struct GeneratedWalletResponse {
     pub address: String,
     pub chain: String,
     pub mnemonic: Option<String>,
+    pub is_testnet: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -113,4 +712,90 @@ pub struct RestoredWalletResponse {
     pub id: Uuid,
     pub address: String,
     pub chain: String,
+    pub is_testnet: bool,
+    /// Weaknesses found in a restored mnemonic
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<MnemonicWarning>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WalletStatistics {
+    pub total_wallets: usize,
+    pub by_chain: HashMap<String, usize>,
+    pub oldest_wallet_date: Option<DateTime<Utc>>,
+    pub newest_wallet_date: Option<DateTime<Utc>>,
+}
+
+/// Parse a custom RPC URL, accepting only https endpoints whose host resolves to public
+/// addresses, so a wallet can't be pointed at the bot's own network
+async fn check_rpc_url(url: &str) -> Result<reqwest::Url> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|_| AppError::InvalidInput("Invalid RPC URL".to_string()))?;
+    if parsed.scheme() != "https" {
+        return Err(AppError::InvalidInput("RPC URL must use https".to_string()));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| AppError::InvalidInput("RPC URL has no host".to_string()))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let addresses: Vec<IpAddr> = tokio::net
+        ::lookup_host((host.as_str(), port)).await
+        .map_err(|_| AppError::InvalidInput(format!("Could not resolve {}", host)))?
+        .map(|addr| addr.ip())
+        .collect();
+    if addresses.is_empty() || !addresses.iter().copied().all(is_public_ip) {
+        return Err(AppError::InvalidInput("RPC URL must point to a public host".to_string()));
+    }
+    Ok(parsed)
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(
+                v4.is_loopback() ||
+                v4.is_private() ||
+                v4.is_link_local() ||
+                v4.is_unspecified() ||
+                v4.is_broadcast() ||
+                v4.is_multicast() ||
+                v4.is_documentation() ||
+                a == 0 ||
+                // Carrier-grade NAT, 100.64.0.0/10
+                (a == 100 && (b & 0xc0) == 64)
+            )
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(
+                v6.is_loopback() ||
+                v6.is_unspecified() ||
+                v6.is_multicast() ||
+                // Unique local fc00::/7 and link-local fe80::/10
+                (first & 0xfe00) == 0xfc00 ||
+                (first & 0xffc0) == 0xfe80
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_internal_rpc_hosts() {
+        for ip in ["127.0.0.1", "10.1.2.3", "192.168.0.10", "169.254.169.254", "100.64.0.1", "::1", "fd00::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should be rejected", ip);
+        }
+        assert!(is_public_ip("8.8.8.8".parse().unwrap()));
+        assert!(is_public_ip("2606:4700::1111".parse().unwrap()));
+    }
 }
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio::task::{ AbortHandle, JoinHandle };
+use tokio_util::sync::CancellationToken;
+
+/// How long `shutdown_all` waits for tasks to wind down before aborting them
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TaskStatus {
+    Running,
+    /// Panicked, or returned without being asked to stop
+    Failed,
+    /// Cancelled by `shutdown_all`
+    Stopped,
+}
+
+/// One background task as reported by `GET /health/tasks`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub name: String,
+    pub status: TaskStatus,
+    /// Seconds since the task started, or how long it ran once it finished
+    pub uptime_secs: u64,
+}
+
+struct TaskRecord {
+    status: TaskStatus,
+    started_at: Instant,
+    finished_at: Option<Instant>,
+    /// Aborts the task itself, for tasks that ignore cancellation past the timeout
+    abort: AbortHandle,
+}
+
+/// Spawns the long-running background tasks (price monitor, alert checker, scheduler, bot)
+/// under one cancellation token so they can be listed and shut down together
+pub struct TaskManager {
+    tasks: Arc<Mutex<HashMap<String, TaskRecord>>>,
+    cancel: CancellationToken,
+    finished: Arc<Notify>,
+}
+
+impl TaskManager {
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+            cancel: CancellationToken::new(),
+            finished: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Run `future` as a named background task. It is dropped when `shutdown_all` is called;
+    /// the returned handle resolves once the task has finished either way.
+    pub fn spawn<F>(&self, name: &str, future: F) -> JoinHandle<()>
+        where F: Future<Output = ()> + Send + 'static
+    {
+        let cancel = self.cancel.child_token();
+        let task_cancel = cancel.clone();
+        let task = tokio::spawn(async move {
+            tokio::select! {
+                _ = task_cancel.cancelled() => {}
+                _ = future => {}
+            }
+        });
+
+        self.tasks.lock().unwrap().insert(name.to_string(), TaskRecord {
+            status: TaskStatus::Running,
+            started_at: Instant::now(),
+            finished_at: None,
+            abort: task.abort_handle(),
+        });
+
+        // A second task watches the first so panics are recorded instead of lost
+        let name = name.to_string();
+        let tasks = self.tasks.clone();
+        let finished = self.finished.clone();
+        tokio::spawn(async move {
+            let result = task.await;
+            let status = match result {
+                Err(e) if e.is_panic() => {
+                    tracing::warn!("Background task '{}' panicked: {}", name, e);
+                    TaskStatus::Failed
+                }
+                _ if cancel.is_cancelled() => TaskStatus::Stopped,
+                _ => {
+                    tracing::warn!("Background task '{}' exited unexpectedly", name);
+                    TaskStatus::Failed
+                }
+            };
+
+            if let Some(record) = tasks.lock().unwrap().get_mut(&name) {
+                record.status = status;
+                record.finished_at = Some(Instant::now());
+            }
+            finished.notify_waiters();
+        })
+    }
+
+    /// Every task spawned so far, sorted by name
+    pub fn list(&self) -> Vec<TaskInfo> {
+        let tasks = self.tasks.lock().unwrap();
+        let mut infos: Vec<TaskInfo> = tasks
+            .iter()
+            .map(|(name, record)| TaskInfo {
+                name: name.clone(),
+                status: record.status,
+                uptime_secs: record.finished_at
+                    .unwrap_or_else(Instant::now)
+                    .duration_since(record.started_at)
+                    .as_secs(),
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    /// Cancel every task and wait up to `timeout` for them to finish, aborting any still running
+    pub async fn shutdown_all(&self, timeout: Duration) {
+        self.cancel.cancel();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            // Registered before checking so a task finishing in between still wakes us
+            let notified = self.finished.notified();
+            if self.running_count() == 0 {
+                return;
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                break;
+            }
+        }
+
+        for (name, record) in self.tasks.lock().unwrap().iter() {
+            if record.status == TaskStatus::Running {
+                tracing::warn!("Background task '{}' didn't stop in {:?}, aborting", name, timeout);
+                record.abort.abort();
+            }
+        }
+    }
+
+    fn running_count(&self) -> usize {
+        self.tasks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|record| record.status == TaskStatus::Running)
+            .count()
+    }
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of(manager: &TaskManager, name: &str) -> TaskStatus {
+        manager.list().into_iter().find(|t| t.name == name).unwrap().status
+    }
+
+    #[tokio::test]
+    async fn test_tracks_exits_and_shutdown() {
+        let manager = TaskManager::new();
+        manager.spawn("forever", std::future::pending());
+        manager.spawn("returns", async {}).await.unwrap();
+        manager.spawn("panics", async { panic!("boom") }).await.unwrap();
+
+        assert_eq!(status_of(&manager, "forever"), TaskStatus::Running);
+        assert_eq!(status_of(&manager, "returns"), TaskStatus::Failed);
+        assert_eq!(status_of(&manager, "panics"), TaskStatus::Failed);
+
+        manager.shutdown_all(Duration::from_secs(1)).await;
+        assert_eq!(status_of(&manager, "forever"), TaskStatus::Stopped);
+    }
+}